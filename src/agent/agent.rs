@@ -9,6 +9,7 @@ use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
+use crate::security::cancellation::{self, StopScope};
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool, ToolSpec};
 use anyhow::Result;
@@ -348,6 +349,9 @@ impl Agent {
             &config.model_routes,
             &model_name,
         )?;
+        let provider =
+            providers::cassette::wrap_with_cassette(provider, &config.provider.cassette)?;
+        let provider = providers::cascade::wrap_with_cascade(provider, config);
 
         let dispatcher_choice = config.agent.tool_dispatcher.as_str();
         let tool_dispatcher: Box<dyn ToolDispatcher> = match dispatcher_choice {
@@ -440,7 +444,19 @@ impl Agent {
         let start = Instant::now();
 
         let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
-            match tool.execute(call.arguments.clone()).await {
+            let estop_guard = cancellation::register(StopScope::tool_call(&call.name));
+            let outcome = if estop_guard.is_stopped() {
+                Err(anyhow::anyhow!(cancellation::stopped_message("Tool call")))
+            } else {
+                tokio::select! {
+                    biased;
+                    () = estop_guard.stopped() => {
+                        Err(anyhow::anyhow!(cancellation::stopped_message("Tool call")))
+                    }
+                    result = tool.execute(call.arguments.clone()) => result,
+                }
+            };
+            match outcome {
                 Ok(r) => {
                     self.observer.record_event(&ObserverEvent::ToolCall {
                         tool: call.name.clone(),
@@ -0,0 +1,259 @@
+//! Session artifact store for oversized tool outputs.
+//!
+//! The tool-call loop keeps tool results under `agent.tool_output_max_chars`.
+//! A longer result is written to
+//! `<workspace>/state/artifacts/<session>/<artifact_id>.txt` and replaced in
+//! history with a head/tail preview plus the artifact id. The `artifact_read`
+//! tool pages, greps or slices the stored output on demand. Artifacts are
+//! scoped to the transcript session (`observability::transcript`), so one
+//! conversation cannot read another's outputs.
+
+use crate::config::AgentConfig;
+use crate::observability::transcript;
+use anyhow::{bail, Context, Result};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use uuid::Uuid;
+
+/// Directory under the workspace holding per-session artifact directories.
+pub const ARTIFACTS_DIR: &str = "state/artifacts";
+
+const MAX_SESSION_DIR_LEN: usize = 96;
+
+/// Stored tool outputs, one directory per session.
+#[derive(Debug)]
+pub struct ArtifactStore {
+    root: PathBuf,
+    max_per_session: usize,
+}
+
+impl ArtifactStore {
+    pub fn new(workspace_dir: &Path, max_per_session: usize) -> Self {
+        Self {
+            root: workspace_dir.join(ARTIFACTS_DIR),
+            max_per_session: max_per_session.max(1),
+        }
+    }
+
+    /// Store `content` for `session` and return its artifact id.
+    pub fn save(&self, session: &str, content: &str) -> Result<String> {
+        let dir = self.session_dir(session);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let short = Uuid::new_v4().simple().to_string();
+        let id = format!("art-{}", &short[..12]);
+        let path = dir.join(format!("{id}.txt"));
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        self.prune(&dir);
+        Ok(id)
+    }
+
+    /// Load an artifact stored for `session`.
+    pub fn load(&self, session: &str, id: &str) -> Result<String> {
+        let valid = id.starts_with("art-")
+            && id.len() <= 32
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            bail!("Invalid artifact id `{id}`");
+        }
+        let path = self.session_dir(session).join(format!("{id}.txt"));
+        if !path.exists() {
+            bail!("Artifact `{id}` not found in this session");
+        }
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    fn session_dir(&self, session: &str) -> PathBuf {
+        let mut name: String = session
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_SESSION_DIR_LEN)
+            .collect();
+        if name.trim_matches('.').is_empty() {
+            name = "default".into();
+        }
+        self.root.join(name)
+    }
+
+    fn prune(&self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect();
+        if files.len() <= self.max_per_session {
+            return;
+        }
+        files.sort_by_key(|(modified, _)| *modified);
+        let excess = files.len() - self.max_per_session;
+        for (_, path) in files.into_iter().take(excess) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Inline size limits for tool outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputBudget {
+    pub max_chars: usize,
+    pub preview_chars: usize,
+}
+
+struct Runtime {
+    store: ArtifactStore,
+    budget: OutputBudget,
+}
+
+static RUNTIME: LazyLock<RwLock<Option<Runtime>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Initialize (or disable) the tool-output budget.
+pub fn init_from_config(config: &AgentConfig, workspace_dir: &Path) {
+    let runtime = (config.tool_output_max_chars > 0).then(|| Runtime {
+        store: ArtifactStore::new(workspace_dir, config.max_artifacts_per_session),
+        budget: OutputBudget {
+            max_chars: config.tool_output_max_chars,
+            preview_chars: config.tool_output_preview_chars,
+        },
+    });
+    let mut guard = RUNTIME.write().unwrap_or_else(|e| e.into_inner());
+    *guard = runtime;
+}
+
+/// Session id artifacts are stored under for the current task.
+pub fn current_session() -> String {
+    transcript::current_session_id()
+}
+
+/// Keep `output` of `tool_name` within the installed budget.
+pub fn apply_budget(tool_name: &str, output: String) -> String {
+    let guard = RUNTIME.read().unwrap_or_else(|e| e.into_inner());
+    let Some(runtime) = guard.as_ref() else {
+        return output;
+    };
+    spill(
+        &runtime.store,
+        runtime.budget,
+        &current_session(),
+        tool_name,
+        output,
+    )
+}
+
+/// Replace an output over `budget.max_chars` with a preview of a stored artifact.
+pub fn spill(
+    store: &ArtifactStore,
+    budget: OutputBudget,
+    session: &str,
+    tool_name: &str,
+    output: String,
+) -> String {
+    let total_chars = output.chars().count();
+    if budget.max_chars == 0 || total_chars <= budget.max_chars {
+        return output;
+    }
+
+    let preview_chars = budget.preview_chars.min(budget.max_chars);
+    let head_chars = preview_chars.div_ceil(2);
+    let tail_chars = preview_chars / 2;
+    let head: String = output.chars().take(head_chars).collect();
+    let tail: String = output.chars().skip(total_chars - tail_chars).collect();
+    let omitted = total_chars - head_chars - tail_chars;
+
+    let mut preview = match store.save(session, &output) {
+        Ok(id) => format!(
+            "[Output of `{tool_name}` is {total_chars} chars; stored as artifact `{id}`. \
+             Showing the first {head_chars} and last {tail_chars} chars. \
+             Call `artifact_read` with artifact_id \"{id}\" to page, grep or slice the full output.]\n"
+        ),
+        Err(error) => {
+            tracing::warn!("Failed to store tool output artifact: {error:#}");
+            format!(
+                "[Output of `{tool_name}` is {total_chars} chars and was truncated \
+                 (artifact storage failed). Showing the first {head_chars} and last {tail_chars} chars.]\n"
+            )
+        }
+    };
+    preview.push_str(&head);
+    let _ = write!(preview, "\n… [{omitted} chars omitted] …\n");
+    preview.push_str(&tail);
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const BUDGET: OutputBudget = OutputBudget {
+        max_chars: 100,
+        preview_chars: 20,
+    };
+
+    #[test]
+    fn short_output_is_returned_unchanged() {
+        let tmp = TempDir::new().unwrap();
+        let store = ArtifactStore::new(tmp.path(), 10);
+
+        let output = spill(&store, BUDGET, "s1", "shell", "ok".into());
+
+        assert_eq!(output, "ok");
+        assert!(!tmp.path().join(ARTIFACTS_DIR).exists());
+    }
+
+    #[test]
+    fn oversized_output_is_stored_with_head_and_tail_preview() {
+        let tmp = TempDir::new().unwrap();
+        let store = ArtifactStore::new(tmp.path(), 10);
+        let full = format!("HEAD{}TAIL", "é".repeat(200));
+
+        let preview = spill(&store, BUDGET, "telegram_42", "file_read", full.clone());
+
+        assert!(preview.contains("Output of `file_read` is 208 chars"));
+        assert!(preview.contains("\nHEAD"));
+        assert!(preview.ends_with("TAIL"));
+        assert!(preview.contains("[188 chars omitted]"));
+        let id = preview
+            .split('`')
+            .nth(3)
+            .expect("artifact id in preview")
+            .to_string();
+        assert_eq!(store.load("telegram_42", &id).unwrap(), full);
+    }
+
+    #[test]
+    fn artifacts_are_isolated_per_session() {
+        let tmp = TempDir::new().unwrap();
+        let store = ArtifactStore::new(tmp.path(), 10);
+        let id = store.save("alice", "secret output").unwrap();
+
+        assert!(store.load("bob", &id).is_err());
+        assert!(store.load("alice", "../alice/x").is_err());
+    }
+
+    #[test]
+    fn oldest_artifacts_are_pruned() {
+        let tmp = TempDir::new().unwrap();
+        let store = ArtifactStore::new(tmp.path(), 2);
+        let first = store.save("s1", "one").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        store.save("s1", "two").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        store.save("s1", "three").unwrap();
+
+        assert!(store.load("s1", &first).is_err());
+        let count = fs::read_dir(store.session_dir("s1")).unwrap().count();
+        assert_eq!(count, 2);
+    }
+}
@@ -1,8 +1,12 @@
+use crate::agent::plan;
+use crate::agent::session;
+use crate::approval::quorum::{self as approval_quorum, QuorumDecision};
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::multimodal;
-use crate::observability::{self, runtime_trace, Observer, ObserverEvent};
+use crate::observability::span::{self, SpanGuard, SpanKind};
+use crate::observability::{self, runtime_trace, transcript, Observer, ObserverEvent};
 use crate::providers::{
     self, ChatMessage, ChatRequest, Provider, ProviderCapabilityError, ToolCall,
 };
@@ -84,6 +88,11 @@ const SLASH_COMMANDS: &[(&[&str], &str, &str)] = &[
         "/clear /new",
         "Clear conversation history",
     ),
+    (
+        &["/plan"],
+        "/plan <task>",
+        "Plan the task, review the plan, then run it step by step",
+    ),
     (&["/quit", "/exit"], "/quit /exit", "Exit interactive mode"),
 ];
 
@@ -411,6 +420,60 @@ fn maybe_inject_cron_add_delivery(
     }
 }
 
+/// Route a queued delegate task's result back to the channel conversation
+/// that enqueued it unless the model chose an explicit `notify` target.
+fn maybe_inject_delegate_queue_callback(
+    tool_name: &str,
+    tool_args: &mut serde_json::Value,
+    channel_name: &str,
+    reply_target: Option<&str>,
+) {
+    if tool_name != "delegate_queue" || channel_name == "cli" {
+        return;
+    }
+    let Some(reply_target) = reply_target.map(str::trim).filter(|v| !v.is_empty()) else {
+        return;
+    };
+    let Some(args_obj) = tool_args.as_object_mut() else {
+        return;
+    };
+    if args_obj.get("action").and_then(serde_json::Value::as_str) != Some("enqueue")
+        || args_obj.get("notify").is_some_and(|value| !value.is_null())
+    {
+        return;
+    }
+    args_obj.insert(
+        "notify".to_string(),
+        serde_json::json!({ "channel": channel_name, "target": reply_target }),
+    );
+}
+
+/// Pin `reply_edit` to the conversation being answered; the model cannot
+/// point it at another chat.
+fn maybe_inject_reply_edit_conversation(
+    tool_name: &str,
+    tool_args: &mut serde_json::Value,
+    channel_name: &str,
+    reply_target: Option<&str>,
+) {
+    if tool_name != "reply_edit" {
+        return;
+    }
+    let Some(args_obj) = tool_args.as_object_mut() else {
+        return;
+    };
+    args_obj.remove("channel");
+    args_obj.remove("target");
+    let Some(reply_target) = reply_target.map(str::trim).filter(|v| !v.is_empty()) else {
+        return;
+    };
+    if channel_name == "cli" {
+        return;
+    }
+    args_obj.insert("channel".to_string(), channel_name.into());
+    args_obj.insert("target".to_string(), reply_target.into());
+}
+
 async fn await_non_cli_approval_decision(
     mgr: &ApprovalManager,
     request_id: &str,
@@ -611,6 +674,7 @@ pub(crate) async fn agent_turn(
     silent: bool,
     multimodal_config: &crate::config::MultimodalConfig,
     max_tool_iterations: usize,
+    excluded_tools: &[String],
 ) -> Result<String> {
     run_tool_call_loop(
         provider,
@@ -628,7 +692,7 @@ pub(crate) async fn agent_turn(
         None,
         None,
         None,
-        &[],
+        excluded_tools,
     )
     .await
 }
@@ -680,6 +744,117 @@ pub(crate) async fn run_tool_call_loop_with_reply_target(
         .await
 }
 
+/// Run `future` with the channel reply target in scope, for callers (plan
+/// mode) that drive `run_tool_call_loop` several times per message.
+pub(crate) async fn with_reply_target<F: std::future::Future>(
+    reply_target: Option<&str>,
+    future: F,
+) -> F::Output {
+    TOOL_LOOP_REPLY_TARGET
+        .scope(reply_target.map(str::to_string), future)
+        .await
+}
+
+/// Run one CLI turn: a reviewed plan for `/plan <task>` (or every message
+/// with `agent.plan_mode.auto`), otherwise a single tool-call loop.
+#[allow(clippy::too_many_arguments)]
+async fn run_cli_turn(
+    config: &Config,
+    input: &str,
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    approval: Option<&ApprovalManager>,
+    channel_name: &str,
+    active_session: Option<session::ActiveSession>,
+    excluded_tools: &[String],
+) -> Result<String> {
+    session::scope(
+        active_session,
+        run_cli_turn_inner(
+            config,
+            input,
+            provider,
+            history,
+            tools_registry,
+            observer,
+            provider_name,
+            model,
+            temperature,
+            approval,
+            channel_name,
+            excluded_tools,
+        ),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_cli_turn_inner(
+    config: &Config,
+    input: &str,
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    approval: Option<&ApprovalManager>,
+    channel_name: &str,
+    excluded_tools: &[String],
+) -> Result<String> {
+    let Some(task) = plan::plan_request(&config.agent.plan_mode, input) else {
+        return run_tool_call_loop(
+            provider,
+            history,
+            tools_registry,
+            observer,
+            provider_name,
+            model,
+            temperature,
+            false,
+            approval,
+            channel_name,
+            &config.multimodal,
+            config.agent.max_tool_iterations,
+            None,
+            None,
+            None,
+            excluded_tools,
+        )
+        .await;
+    };
+    let execution = plan::PlanExecution {
+        provider,
+        tools_registry,
+        observer,
+        provider_name,
+        model,
+        temperature,
+        silent: false,
+        approval,
+        channel_name,
+        multimodal_config: &config.multimodal,
+        max_tool_iterations: config.agent.max_tool_iterations,
+        cancellation_token: None,
+        hooks: None,
+        excluded_tools,
+    };
+    plan::run_plan_mode(
+        &execution,
+        &config.agent.plan_mode,
+        history,
+        task,
+        &plan::CliPlanReviewer,
+    )
+    .await
+}
+
 /// Run the tool loop with optional non-CLI approval context scoped to this task.
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_tool_call_loop_with_non_cli_approval_context(
@@ -797,6 +972,19 @@ pub(crate) async fn run_tool_call_loop(
         .collect();
     let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
     let turn_id = Uuid::new_v4().to_string();
+    let transcript_ctx = transcript::TurnContext {
+        channel: channel_name,
+        provider: provider_name,
+        model,
+        turn_id: &turn_id,
+    };
+    let mut turn_span = SpanGuard::start(SpanKind::Turn, "turn");
+    turn_span.set_attribute("gen_ai.operation.name", "invoke_agent");
+    turn_span.set_attribute("gen_ai.provider.name", provider_name);
+    turn_span.set_attribute("gen_ai.request.model", model);
+    turn_span.set_attribute("zeroclaw.channel", channel_name);
+    turn_span.set_attribute("zeroclaw.turn_id", turn_id.as_str());
+    turn_span.set_attribute("gen_ai.conversation.id", transcript::current_session_id());
     let mut seen_tool_signatures: HashSet<(String, String)> = HashSet::new();
     let mut missing_tool_call_retry_used = false;
     let mut missing_tool_call_retry_prompt: Option<String> = None;
@@ -831,6 +1019,10 @@ pub(crate) async fn run_tool_call_loop(
         {
             return Err(ToolLoopCancelled.into());
         }
+        turn_span.set_attribute("zeroclaw.iterations", iteration + 1);
+        let mut iteration_span =
+            turn_span.child(SpanKind::Iteration, format!("iteration {}", iteration + 1));
+        iteration_span.set_attribute("zeroclaw.iteration", iteration + 1);
 
         let image_marker_count = multimodal::count_image_markers(history);
         let provider_supports_vision =
@@ -899,6 +1091,11 @@ pub(crate) async fn run_tool_call_loop(
         );
 
         let llm_started_at = Instant::now();
+        let mut llm_span = iteration_span.child(SpanKind::LlmCall, format!("chat {model}"));
+        llm_span.set_attribute("gen_ai.operation.name", "chat");
+        llm_span.set_attribute("gen_ai.provider.name", provider_name);
+        llm_span.set_attribute("gen_ai.request.model", model);
+        llm_span.set_attribute("gen_ai.request.temperature", temperature);
 
         // Fire void hook before LLM call
         if let Some(hooks) = hooks {
@@ -955,6 +1152,12 @@ pub(crate) async fn run_tool_call_loop(
                     input_tokens: resp_input_tokens,
                     output_tokens: resp_output_tokens,
                 });
+                if let Some(tokens) = resp_input_tokens {
+                    llm_span.set_attribute("gen_ai.usage.input_tokens", tokens);
+                }
+                if let Some(tokens) = resp_output_tokens {
+                    llm_span.set_attribute("gen_ai.usage.output_tokens", tokens);
+                }
 
                 let response_text = resp.text_or_empty().to_string();
                 // First try native structured tool calls (OpenAI-format).
@@ -1010,6 +1213,31 @@ pub(crate) async fn run_tool_call_loop(
                         "parsed_tool_calls": calls.len(),
                     }),
                 );
+                if transcript::is_enabled() {
+                    transcript::record_llm_call(
+                        transcript_ctx,
+                        iteration + 1,
+                        temperature,
+                        &request_messages,
+                        request_tools,
+                        transcript::LlmCallRecord {
+                            response: Some(response_text.clone()),
+                            tool_calls: calls
+                                .iter()
+                                .map(|call| ToolCall {
+                                    id: call.tool_call_id.clone().unwrap_or_default(),
+                                    name: call.name.clone(),
+                                    arguments: call.arguments.to_string(),
+                                })
+                                .collect(),
+                            input_tokens: resp_input_tokens,
+                            output_tokens: resp_output_tokens,
+                            duration_ms: u64::try_from(llm_started_at.elapsed().as_millis())
+                                .unwrap_or(u64::MAX),
+                            error: None,
+                        },
+                    );
+                }
 
                 // Preserve native tool call IDs in assistant history so role=tool
                 // follow-up messages can reference the exact call id.
@@ -1045,6 +1273,8 @@ pub(crate) async fn run_tool_call_loop(
             }
             Err(e) => {
                 let safe_error = crate::providers::sanitize_api_error(&e.to_string());
+                llm_span.fail(safe_error.clone());
+                turn_span.fail(safe_error.clone());
                 observer.record_event(&ObserverEvent::LlmResponse {
                     provider: provider_name.to_string(),
                     model: model.to_string(),
@@ -1067,10 +1297,26 @@ pub(crate) async fn run_tool_call_loop(
                         "duration_ms": llm_started_at.elapsed().as_millis(),
                     }),
                 );
+                transcript::record_llm_call(
+                    transcript_ctx,
+                    iteration + 1,
+                    temperature,
+                    &request_messages,
+                    request_tools,
+                    transcript::LlmCallRecord {
+                        duration_ms: u64::try_from(llm_started_at.elapsed().as_millis())
+                            .unwrap_or(u64::MAX),
+                        error: Some(safe_error),
+                        ..transcript::LlmCallRecord::default()
+                    },
+                );
                 return Err(e);
             }
         };
 
+        llm_span.set_attribute("zeroclaw.tool_calls", tool_calls.len());
+        drop(llm_span);
+
         let display_text = if parsed_text.is_empty() {
             response_text.clone()
         } else {
@@ -1245,6 +1491,18 @@ pub(crate) async fn run_tool_call_loop(
                 channel_name,
                 channel_reply_target.as_deref(),
             );
+            maybe_inject_delegate_queue_callback(
+                &tool_name,
+                &mut tool_args,
+                channel_name,
+                channel_reply_target.as_deref(),
+            );
+            maybe_inject_reply_edit_conversation(
+                &tool_name,
+                &mut tool_args,
+                channel_name,
+                channel_reply_target.as_deref(),
+            );
 
             if excluded_tools.iter().any(|ex| ex == &tool_name) {
                 let blocked = format!("Tool '{tool_name}' is not available in this channel.");
@@ -1276,8 +1534,87 @@ pub(crate) async fn run_tool_call_loop(
                 continue;
             }
 
+            // ── Approval quorum ──────────────────────────────
+            // Quorum rules apply regardless of autonomy level, session grants
+            // or one-time bypass tokens; an approved quorum replaces the
+            // regular approval prompt for this call.
+            let mut quorum_authorized = false;
+            if let Some(store) = approval_quorum::runtime_store() {
+                let requested_by = non_cli_approval_context
+                    .as_ref()
+                    .map(|ctx| format!("{channel_name}:{}", ctx.sender))
+                    .or_else(|| {
+                        channel_reply_target
+                            .as_deref()
+                            .map(|target| format!("{channel_name}:{target}"))
+                    })
+                    .unwrap_or_else(|| channel_name.to_string());
+                let blocked = match store.authorize(&tool_name, &tool_args, &requested_by) {
+                    Ok(QuorumDecision::NotRequired) => None,
+                    Ok(QuorumDecision::Authorized(request)) => {
+                        quorum_authorized = true;
+                        runtime_trace::record_event(
+                            "approval_quorum_authorized",
+                            Some(channel_name),
+                            Some(provider_name),
+                            Some(model),
+                            Some(&turn_id),
+                            Some(true),
+                            None,
+                            serde_json::json!({
+                                "iteration": iteration + 1,
+                                "tool": tool_name.clone(),
+                                "request_id": request.id,
+                                "rule": request.rule,
+                            }),
+                        );
+                        None
+                    }
+                    Ok(QuorumDecision::Pending(request)) => Some(format!(
+                        "Tool '{tool_name}' requires {required} distinct approvals under quorum rule '{rule}'. Approval request `{id}` has {have}/{required}; approvers can run `/quorum-approve {id}`{otp} or `/quorum-deny {id}`. Retry the same call after it is approved (expires {expires}).",
+                        required = request.required,
+                        rule = request.rule,
+                        id = request.id,
+                        have = request.approvals.len(),
+                        otp = if request.require_otp { " <otp>" } else { "" },
+                        expires = request.expires_at,
+                    )),
+                    Err(err) => Some(format!(
+                        "Tool '{tool_name}' is gated by an approval quorum that could not be checked: {err}"
+                    )),
+                };
+                if let Some(blocked) = blocked {
+                    runtime_trace::record_event(
+                        "tool_call_result",
+                        Some(channel_name),
+                        Some(provider_name),
+                        Some(model),
+                        Some(&turn_id),
+                        Some(false),
+                        Some(&blocked),
+                        serde_json::json!({
+                            "iteration": iteration + 1,
+                            "tool": tool_name.clone(),
+                            "arguments": scrub_credentials(&tool_args.to_string()),
+                            "blocked_by_approval_quorum": true,
+                        }),
+                    );
+                    ordered_results[idx] = Some((
+                        tool_name.clone(),
+                        call.tool_call_id.clone(),
+                        ToolExecutionOutcome {
+                            output: blocked.clone(),
+                            success: false,
+                            error_reason: Some(blocked),
+                            duration: Duration::ZERO,
+                        },
+                    ));
+                    continue;
+                }
+            }
+
             // ── Approval hook ────────────────────────────────
-            if let Some(mgr) = approval {
+            if let Some(mgr) = approval.filter(|_| !quorum_authorized) {
                 let non_cli_session_granted =
                     channel_name != "cli" && mgr.is_non_cli_session_granted(&tool_name);
                 if bypass_non_cli_approval_for_turn || non_cli_session_granted {
@@ -1445,19 +1782,25 @@ pub(crate) async fn run_tool_call_loop(
         }
 
         let executed_outcomes = if allow_parallel_execution && executable_calls.len() > 1 {
-            execute_tools_parallel(
-                &executable_calls,
-                tools_registry,
-                observer,
-                cancellation_token.as_ref(),
+            span::scope(
+                iteration_span.context(),
+                execute_tools_parallel(
+                    &executable_calls,
+                    tools_registry,
+                    observer,
+                    cancellation_token.as_ref(),
+                ),
             )
             .await?
         } else {
-            execute_tools_sequential(
-                &executable_calls,
-                tools_registry,
-                observer,
-                cancellation_token.as_ref(),
+            span::scope(
+                iteration_span.context(),
+                execute_tools_sequential(
+                    &executable_calls,
+                    tools_registry,
+                    observer,
+                    cancellation_token.as_ref(),
+                ),
             )
             .await?
         };
@@ -1482,6 +1825,15 @@ pub(crate) async fn run_tool_call_loop(
                     "output": scrub_credentials(&outcome.output),
                 }),
             );
+            transcript::record_tool_call(
+                transcript_ctx,
+                iteration + 1,
+                &call.name,
+                &call.arguments,
+                &outcome.output,
+                outcome.success,
+                u64::try_from(outcome.duration.as_millis()).unwrap_or(u64::MAX),
+            );
 
             // ── Hook: after_tool_call (void) ─────────────────
             if let Some(hooks) = hooks {
@@ -1598,6 +1950,7 @@ pub(crate) async fn run_tool_call_loop(
                     Some("loop persisted after warning, stopping early"),
                     serde_json::json!({ "iteration": iteration + 1, "reason": &reason }),
                 );
+                turn_span.fail("loop detected");
                 anyhow::bail!(
                     "Agent stopped early due to detected loop pattern (iteration {}/{}): {}",
                     iteration + 1,
@@ -1620,6 +1973,7 @@ pub(crate) async fn run_tool_call_loop(
             "max_iterations": max_iterations,
         }),
     );
+    turn_span.fail("maximum tool iterations exceeded");
     anyhow::bail!("Agent exceeded maximum tool iterations ({max_iterations})")
 }
 
@@ -1750,10 +2104,14 @@ pub async fn run(
     temperature: f64,
     peripheral_overrides: Vec<String>,
     interactive: bool,
+    session_name: Option<String>,
 ) -> Result<String> {
     // ── Wire up agnostic subsystems ──────────────────────────────
     let base_observer = observability::create_observer(&config.observability);
     let observer: Arc<dyn Observer> = Arc::from(base_observer);
+    crate::security::dlp::set_runtime_observer(observer.clone());
+    span::set_runtime_observer(observer.clone());
+    crate::security::cancellation::spawn_watcher();
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let security = Arc::new(SecurityPolicy::from_config(
@@ -1810,39 +2168,43 @@ pub async fn run(
         tools_registry.extend(peripheral_tools);
     }
 
-    // ── Resolve provider ─────────────────────────────────────────
-    let provider_name = provider_override
-        .as_deref()
-        .or(config.default_provider.as_deref())
-        .unwrap_or("openrouter");
-
-    let model_name = model_override
+    // ── Named session (`--session`) ──────────────────────────────
+    let session_store = session::SessionStore::new(&config.workspace_dir);
+    let mut current_session = session_name
         .as_deref()
-        .or(config.default_model.as_deref())
-        .unwrap_or("anthropic/claude-sonnet-4");
-
-    let provider_runtime_options = providers::ProviderRuntimeOptions {
-        auth_profile_override: None,
-        provider_api_url: config.api_url.clone(),
-        provider_transport: config.effective_provider_transport(),
-        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
-        secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
-        reasoning_level: config.effective_provider_reasoning_level(),
-        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
-        max_tokens_override: None,
-        model_support_vision: config.model_support_vision,
-    };
+        .map(|name| session_store.open(name))
+        .transpose()?;
+    if let Some(current) = current_session.as_mut() {
+        // Explicit --provider/--model flags become the session's overrides.
+        if provider_override.is_some() || model_override.is_some() {
+            *current = session_store.update(&current.name, |stored| {
+                if provider_override.is_some() {
+                    stored.provider.clone_from(&provider_override);
+                }
+                if model_override.is_some() {
+                    stored.model.clone_from(&model_override);
+                }
+                stored.clone()
+            })?;
+        }
+    }
 
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
-        provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
-        model_name,
-        &provider_runtime_options,
-    )?;
+    // ── Resolve provider ─────────────────────────────────────────
+    let base_provider = provider_override
+        .or_else(|| config.default_provider.clone())
+        .unwrap_or_else(|| "openrouter".to_string());
+    let base_model = model_override
+        .or_else(|| config.default_model.clone())
+        .unwrap_or_else(|| "anthropic/claude-sonnet-4".to_string());
+    let mut provider_name = current_session
+        .as_ref()
+        .and_then(|current| current.provider.clone())
+        .unwrap_or_else(|| base_provider.clone());
+    let mut model_name = current_session
+        .as_ref()
+        .and_then(|current| current.model.clone())
+        .unwrap_or_else(|| base_model.clone());
+    let mut provider = create_agent_provider(&config, &provider_name, &model_name)?;
 
     observer.record_event(&ObserverEvent::AgentStart {
         provider: provider_name.to_string(),
@@ -1999,7 +2361,7 @@ pub async fn run(
     let native_tools = provider.supports_native_tools();
     let mut system_prompt = crate::channels::build_system_prompt_with_mode(
         &config.workspace_dir,
-        model_name,
+        &model_name,
         &tool_descs,
         &skills,
         Some(&config.identity),
@@ -2021,6 +2383,12 @@ pub async fn run(
         None
     };
     let channel_name = if interactive { "cli" } else { "daemon" };
+    let transcript_session = transcript::new_session_id(channel_name);
+    let mut session_span = SpanGuard::start(SpanKind::Session, format!("session {channel_name}"));
+    session_span.set_attribute("gen_ai.conversation.id", transcript_session.clone());
+    session_span.set_attribute("gen_ai.provider.name", provider_name.as_str());
+    session_span.set_attribute("gen_ai.request.model", model_name.as_str());
+    let mut excluded_tools = session_excluded_tools(current_session.as_ref(), &tools_registry);
 
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
@@ -2052,10 +2420,12 @@ pub async fn run(
             format!("{context}[{now}] {msg}")
         };
 
-        let mut history = vec![
-            ChatMessage::system(&system_prompt),
-            ChatMessage::user(&enriched),
-        ];
+        let mut history = vec![ChatMessage::system(&system_prompt)];
+        if let Some(current) = &current_session {
+            history.extend(current.provider_messages(config.agent.max_history_messages));
+        }
+        let turn_start = history.len();
+        history.push(ChatMessage::user(&enriched));
 
         let ld_cfg = LoopDetectionConfig {
             no_progress_threshold: config.agent.loop_detection_no_progress_threshold,
@@ -2070,32 +2440,40 @@ pub async fn run(
         } else {
             None
         };
-        let response = SAFETY_HEARTBEAT_CONFIG
-            .scope(
-                hb_cfg,
-                LOOP_DETECTION_CONFIG.scope(
-                    ld_cfg,
-                    run_tool_call_loop(
-                        provider.as_ref(),
-                        &mut history,
-                        &tools_registry,
-                        observer.as_ref(),
-                        provider_name,
-                        model_name,
-                        temperature,
-                        false,
-                        approval_manager.as_ref(),
-                        channel_name,
-                        &config.multimodal,
-                        config.agent.max_tool_iterations,
-                        None,
-                        None,
-                        None,
-                        &[],
+        let response = Box::pin(transcript::scope(
+            transcript_session.clone(),
+            span::scope(
+                session_span.context(),
+                SAFETY_HEARTBEAT_CONFIG.scope(
+                    hb_cfg,
+                    LOOP_DETECTION_CONFIG.scope(
+                        ld_cfg,
+                        run_cli_turn(
+                            &config,
+                            &msg,
+                            provider.as_ref(),
+                            &mut history,
+                            &tools_registry,
+                            observer.as_ref(),
+                            &provider_name,
+                            &model_name,
+                            temperature,
+                            approval_manager.as_ref(),
+                            channel_name,
+                            active_session(&session_store, current_session.as_ref()),
+                            &excluded_tools,
+                        ),
                     ),
                 ),
-            )
-            .await?;
+            ),
+        ))
+        .await;
+        persist_session_turn(
+            &session_store,
+            current_session.as_ref(),
+            &history[turn_start..],
+        );
+        let response = response?;
         final_output = response.clone();
         println!("{response}");
         observer.record_event(&ObserverEvent::TurnComplete);
@@ -2106,6 +2484,14 @@ pub async fn run(
 
         // Persistent conversation history across turns
         let mut history = vec![ChatMessage::system(&system_prompt)];
+        if let Some(current) = &current_session {
+            history.extend(current.provider_messages(config.agent.max_history_messages));
+            println!(
+                "Resumed session `{}` ({} messages).\n",
+                current.name,
+                current.history.len()
+            );
+        }
         let mut interactive_turn: usize = 0;
         // Reusable readline editor for UTF-8 input support
         let mut rl = Editor::with_config(
@@ -2132,12 +2518,137 @@ pub async fn run(
                 continue;
             }
             rl.add_history_entry(&input)?;
+            if let Some(args) = user_input
+                .strip_prefix("/session")
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+            {
+                let command = match session::parse_command(args) {
+                    Ok(command) => command,
+                    Err(usage) => {
+                        println!("{usage}\n");
+                        continue;
+                    }
+                };
+                match command {
+                    session::SessionCommand::Show => match &current_session {
+                        Some(current) => match session_store.load(&current.name) {
+                            Ok(Some(stored)) => println!("{}\n", stored.describe()),
+                            _ => println!("{}\n", current.describe()),
+                        },
+                        None => {
+                            println!("No named session is active. Use `/session switch <name>`.\n");
+                        }
+                    },
+                    session::SessionCommand::List => match session_store.list() {
+                        Ok(sessions) => println!(
+                            "{}\n",
+                            session::format_list(
+                                &sessions,
+                                current_session
+                                    .as_ref()
+                                    .map(|current| current.name.as_str())
+                            )
+                        ),
+                        Err(e) => eprintln!("\nError listing sessions: {e}\n"),
+                    },
+                    session::SessionCommand::Switch(name) => match session_store.open(&name) {
+                        Ok(next) => {
+                            let next_provider = next
+                                .provider
+                                .clone()
+                                .unwrap_or_else(|| base_provider.clone());
+                            let next_model =
+                                next.model.clone().unwrap_or_else(|| base_model.clone());
+                            if next_provider != provider_name || next_model != model_name {
+                                match create_agent_provider(&config, &next_provider, &next_model) {
+                                    Ok(next_provider_impl) => {
+                                        provider = next_provider_impl;
+                                        provider_name = next_provider;
+                                        model_name = next_model;
+                                    }
+                                    Err(e) => eprintln!(
+                                        "\n⚠️ Keeping {provider_name}/{model_name}: failed to initialize {next_provider}/{next_model}: {e}\n"
+                                    ),
+                                }
+                            }
+                            history.truncate(1);
+                            history
+                                .extend(next.provider_messages(config.agent.max_history_messages));
+                            excluded_tools = session_excluded_tools(Some(&next), &tools_registry);
+                            println!(
+                                "Switched to session `{}` ({} messages, {provider_name}/{model_name}).\n",
+                                next.name,
+                                next.history.len()
+                            );
+                            current_session = Some(next);
+                        }
+                        Err(e) => eprintln!("\nError opening session: {e}\n"),
+                    },
+                    session::SessionCommand::Fork(name) => {
+                        let source = match &current_session {
+                            Some(current) => session_store
+                                .load(&current.name)
+                                .ok()
+                                .flatten()
+                                .unwrap_or_else(|| current.clone()),
+                            None => {
+                                let mut unnamed = session::NamedSession::new(&name);
+                                unnamed.append(&history);
+                                unnamed
+                            }
+                        };
+                        match session_store.fork(&source, &name) {
+                            Ok(fork) => {
+                                println!("Forked into session `{}`.\n", fork.name);
+                                current_session = Some(fork);
+                            }
+                            Err(e) => eprintln!("\nError forking session: {e}\n"),
+                        }
+                    }
+                    session::SessionCommand::Delete(name) => {
+                        if current_session
+                            .as_ref()
+                            .is_some_and(|current| current.name == name)
+                        {
+                            println!("Cannot delete the active session; switch away first.\n");
+                        } else {
+                            match session_store.delete(&name) {
+                                Ok(true) => println!("Deleted session `{name}`.\n"),
+                                Ok(false) => println!("No session named `{name}`.\n"),
+                                Err(e) => eprintln!("\nError deleting session: {e}\n"),
+                            }
+                        }
+                    }
+                    session::SessionCommand::Tools(allowed) => {
+                        let Some(current) = current_session.as_mut() else {
+                            println!("No named session is active. Use `/session switch <name>`.\n");
+                            continue;
+                        };
+                        match session_store.update(&current.name, |stored| {
+                            stored.allowed_tools.clone_from(&allowed);
+                            stored.clone()
+                        }) {
+                            Ok(stored) => {
+                                *current = stored;
+                                excluded_tools =
+                                    session_excluded_tools(Some(current), &tools_registry);
+                                println!("{}\n", current.describe());
+                            }
+                            Err(e) => eprintln!("\nError updating session: {e}\n"),
+                        }
+                    }
+                }
+                continue;
+            }
             match user_input.as_str() {
                 "/quit" | "/exit" => break,
                 "/help" => {
                     println!("Available commands:");
                     println!("  /help        Show this help message");
                     println!("  /clear /new  Clear conversation history");
+                    println!(
+                        "  /plan <task> Plan the task, review the plan, then run it step by step"
+                    );
                     println!("  /quit /exit  Exit interactive mode\n");
                     continue;
                 }
@@ -2202,6 +2713,7 @@ pub async fn run(
                 format!("{context}[{now}] {user_input}")
             };
 
+            let turn_start = history.len();
             history.push(ChatMessage::user(&enriched));
             interactive_turn += 1;
 
@@ -2231,33 +2743,40 @@ pub async fn run(
             } else {
                 None
             };
-            let response = match SAFETY_HEARTBEAT_CONFIG
-                .scope(
-                    hb_cfg,
-                    LOOP_DETECTION_CONFIG.scope(
-                        ld_cfg,
-                        run_tool_call_loop(
-                            provider.as_ref(),
-                            &mut history,
-                            &tools_registry,
-                            observer.as_ref(),
-                            provider_name,
-                            model_name,
-                            temperature,
-                            false,
-                            approval_manager.as_ref(),
-                            channel_name,
-                            &config.multimodal,
-                            config.agent.max_tool_iterations,
-                            None,
-                            None,
-                            None,
-                            &[],
+            let response = Box::pin(transcript::scope(
+                transcript_session.clone(),
+                span::scope(
+                    session_span.context(),
+                    SAFETY_HEARTBEAT_CONFIG.scope(
+                        hb_cfg,
+                        LOOP_DETECTION_CONFIG.scope(
+                            ld_cfg,
+                            run_cli_turn(
+                                &config,
+                                &user_input,
+                                provider.as_ref(),
+                                &mut history,
+                                &tools_registry,
+                                observer.as_ref(),
+                                &provider_name,
+                                &model_name,
+                                temperature,
+                                approval_manager.as_ref(),
+                                channel_name,
+                                active_session(&session_store, current_session.as_ref()),
+                                &excluded_tools,
+                            ),
                         ),
                     ),
-                )
-                .await
-            {
+                ),
+            ))
+            .await;
+            persist_session_turn(
+                &session_store,
+                current_session.as_ref(),
+                &history[turn_start..],
+            );
+            let response = match response {
                 Ok(resp) => resp,
                 Err(e) => {
                     if is_tool_iteration_limit_error(&e) {
@@ -2298,7 +2817,7 @@ pub async fn run(
             if let Ok(compacted) = auto_compact_history(
                 &mut history,
                 provider.as_ref(),
-                model_name,
+                &model_name,
                 config.agent.max_history_messages,
             )
             .await
@@ -2315,8 +2834,8 @@ pub async fn run(
 
     let duration = start.elapsed();
     observer.record_event(&ObserverEvent::AgentEnd {
-        provider: provider_name.to_string(),
-        model: model_name.to_string(),
+        provider: provider_name,
+        model: model_name,
         duration,
         tokens_used: None,
         cost_usd: None,
@@ -2325,9 +2844,85 @@ pub async fn run(
     Ok(final_output)
 }
 
+/// Build the routed provider for an agent run, with the optional cassette and
+/// model cascade applied.
+fn create_agent_provider(
+    config: &Config,
+    provider_name: &str,
+    model_name: &str,
+) -> Result<Box<dyn Provider>> {
+    let provider_runtime_options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        provider_transport: config.effective_provider_transport(),
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning_level: config.effective_provider_reasoning_level(),
+        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
+        max_tokens_override: None,
+        model_support_vision: config.model_support_vision,
+    };
+    let provider = providers::create_routed_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model_name,
+        &provider_runtime_options,
+    )?;
+    let provider = providers::cassette::wrap_with_cassette(provider, &config.provider.cassette)?;
+    Ok(providers::cascade::wrap_with_cascade(provider, config))
+}
+
+/// Tools a named session's allowlist excludes from `tools_registry`.
+fn session_excluded_tools(
+    current: Option<&session::NamedSession>,
+    tools_registry: &[Box<dyn Tool>],
+) -> Vec<String> {
+    current.map_or_else(Vec::new, |current| {
+        current.excluded_tools(tools_registry.iter().map(|tool| tool.name()))
+    })
+}
+
+fn active_session(
+    store: &session::SessionStore,
+    current: Option<&session::NamedSession>,
+) -> Option<session::ActiveSession> {
+    current.map(|current| session::ActiveSession {
+        store: store.clone(),
+        name: current.name.clone(),
+    })
+}
+
+/// Append one turn's messages to the named session, if any.
+fn persist_session_turn(
+    store: &session::SessionStore,
+    current: Option<&session::NamedSession>,
+    turn: &[ChatMessage],
+) {
+    let Some(current) = current else {
+        return;
+    };
+    if let Err(e) = store.update(&current.name, |stored| stored.append(turn)) {
+        tracing::warn!("Failed to save session `{}`: {e:#}", current.name);
+    }
+}
+
 /// Process a single message through the full agent (with tools, peripherals, memory).
 /// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
 pub async fn process_message(config: Config, message: &str) -> Result<String> {
+    Box::pin(process_message_in_session(config, message, None)).await
+}
+
+/// [`process_message`] continuing the named session `session_name` (history,
+/// provider/model overrides, tool allowlist) and appending the turn to it.
+pub async fn process_message_in_session(
+    config: Config,
+    message: &str,
+    session_name: Option<&str>,
+) -> Result<String> {
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
@@ -2370,32 +2965,21 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     tools_registry.extend(peripheral_tools);
 
-    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
-    let model_name = config
-        .default_model
-        .clone()
+    let session_store = session::SessionStore::new(&config.workspace_dir);
+    let current_session = session_name
+        .map(|name| session_store.open(name))
+        .transpose()?;
+    let provider_name = current_session
+        .as_ref()
+        .and_then(|current| current.provider.clone())
+        .or_else(|| config.default_provider.clone())
+        .unwrap_or_else(|| "openrouter".to_string());
+    let model_name = current_session
+        .as_ref()
+        .and_then(|current| current.model.clone())
+        .or_else(|| config.default_model.clone())
         .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
-    let provider_runtime_options = providers::ProviderRuntimeOptions {
-        auth_profile_override: None,
-        provider_api_url: config.api_url.clone(),
-        provider_transport: config.effective_provider_transport(),
-        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
-        secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
-        reasoning_level: config.effective_provider_reasoning_level(),
-        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
-        max_tokens_override: None,
-        model_support_vision: config.model_support_vision,
-    };
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
-        provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
-        &model_name,
-        &provider_runtime_options,
-    )?;
+    let provider = create_agent_provider(&config, &provider_name, &model_name)?;
 
     let hardware_rag: Option<crate::rag::HardwareRag> = config
         .peripherals
@@ -2504,10 +3088,13 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         format!("{context}[{now}] {message}")
     };
 
-    let mut history = vec![
-        ChatMessage::system(&system_prompt),
-        ChatMessage::user(&enriched),
-    ];
+    let mut history = vec![ChatMessage::system(&system_prompt)];
+    if let Some(current) = &current_session {
+        history.extend(current.provider_messages(config.agent.max_history_messages));
+    }
+    let turn_start = history.len();
+    history.push(ChatMessage::user(&enriched));
+    let excluded_tools = session_excluded_tools(current_session.as_ref(), &tools_registry);
 
     let hb_cfg = if config.agent.safety_heartbeat_interval > 0 {
         Some(SafetyHeartbeatConfig {
@@ -2517,23 +3104,32 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     } else {
         None
     };
-    SAFETY_HEARTBEAT_CONFIG
-        .scope(
+    let response = session::scope(
+        active_session(&session_store, current_session.as_ref()),
+        SAFETY_HEARTBEAT_CONFIG.scope(
             hb_cfg,
             agent_turn(
                 provider.as_ref(),
                 &mut history,
                 &tools_registry,
                 observer.as_ref(),
-                provider_name,
+                &provider_name,
                 &model_name,
                 config.default_temperature,
                 true,
                 &config.multimodal,
                 config.agent.max_tool_iterations,
+                &excluded_tools,
             ),
-        )
-        .await
+        ),
+    )
+    .await;
+    persist_session_turn(
+        &session_store,
+        current_session.as_ref(),
+        &history[turn_start..],
+    );
+    response
 }
 
 #[cfg(test)]
@@ -2597,6 +3193,46 @@ mod tests {
         assert_eq!(args["delivery"]["to"], "C123");
     }
 
+    #[test]
+    fn maybe_inject_delegate_queue_callback_targets_originating_conversation() {
+        let mut args = serde_json::json!({
+            "action": "enqueue",
+            "agent": "researcher",
+            "task": "dig"
+        });
+        maybe_inject_delegate_queue_callback("delegate_queue", &mut args, "slack", Some("C42"));
+        assert_eq!(args["notify"]["channel"], "slack");
+        assert_eq!(args["notify"]["target"], "C42");
+
+        let mut explicit = serde_json::json!({
+            "action": "enqueue",
+            "notify": {"channel": "telegram", "target": "1"}
+        });
+        maybe_inject_delegate_queue_callback("delegate_queue", &mut explicit, "slack", Some("C42"));
+        assert_eq!(explicit["notify"]["channel"], "telegram");
+
+        let mut cli = serde_json::json!({"action": "enqueue"});
+        maybe_inject_delegate_queue_callback("delegate_queue", &mut cli, "cli", Some("user"));
+        assert!(cli.get("notify").is_none());
+    }
+
+    #[test]
+    fn maybe_inject_reply_edit_conversation_pins_current_chat() {
+        let mut args = serde_json::json!({
+            "action": "delete",
+            "channel": "discord",
+            "target": "elsewhere"
+        });
+        maybe_inject_reply_edit_conversation("reply_edit", &mut args, "slack", Some("C42"));
+        assert_eq!(args["channel"], "slack");
+        assert_eq!(args["target"], "C42");
+
+        let mut cli = serde_json::json!({"action": "delete", "channel": "slack", "target": "C42"});
+        maybe_inject_reply_edit_conversation("reply_edit", &mut cli, "cli", Some("user"));
+        assert!(cli.get("channel").is_none());
+        assert!(cli.get("target").is_none());
+    }
+
     #[test]
     fn maybe_inject_cron_add_delivery_skips_shell_jobs() {
         let mut args = serde_json::json!({
@@ -3214,6 +3850,85 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_interrupts_tool_call_when_estop_freezes_it() {
+        let (_lock, state_path) = crate::security::cancellation::lock_for_test().await;
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"estop_slow_tool","arguments":{"value":"A"}}
+</tool_call>"#,
+            "done",
+        ]);
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "estop_slow_tool",
+            30_000,
+            Arc::clone(&active),
+            Arc::clone(&max_active),
+        ))];
+
+        let engage = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            crate::security::cancellation::engage_for_test(
+                &state_path,
+                crate::security::EstopLevel::ToolFreeze(vec!["estop_slow_tool".into()]),
+            );
+            crate::security::cancellation::poll_once()
+        });
+
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run the slow tool"),
+        ];
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &NoopObserver,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "cli",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+            ),
+        )
+        .await
+        .expect("estop should interrupt the slow tool")
+        .expect("loop should complete after the interruption");
+
+        assert_eq!(result, "done");
+        let report = engage
+            .await
+            .unwrap()
+            .expect("engagement should be reported");
+        assert!(report
+            .stopped
+            .iter()
+            .any(|task| task.label == "estop_slow_tool"));
+        let tool_results = history
+            .iter()
+            .find(|msg| msg.role == "user" && msg.content.starts_with("[Tool results]"))
+            .expect("tool results message should be present");
+        assert!(tool_results
+            .content
+            .contains("Tool call stopped by emergency stop"));
+        assert_eq!(
+            active.load(Ordering::SeqCst),
+            1,
+            "tool future was dropped mid-call"
+        );
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_denies_supervised_tools_on_non_cli_channels() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -1,7 +1,11 @@
 use super::parsing::ParsedToolCall;
 use super::{scrub_credentials, ToolLoopCancelled};
+use crate::agent::artifacts;
 use crate::approval::ApprovalManager;
+use crate::observability::span::{self, SpanGuard, SpanKind};
 use crate::observability::{Observer, ObserverEvent};
+use crate::security::cancellation::{self, StopScope};
+use crate::security::dlp::{self, DlpSurface};
 use crate::tools::Tool;
 use anyhow::Result;
 use std::time::{Duration, Instant};
@@ -10,20 +14,53 @@ use tokio_util::sync::CancellationToken;
 fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool> {
     tools.iter().find(|t| t.name() == name).map(|t| t.as_ref())
 }
+
+/// Apply `[security.dlp]` to tool output before it is fed back to the model.
+fn guard_tool_output(call_name: &str, output: &str) -> String {
+    let outcome = dlp::apply(DlpSurface::ToolOutput, None, output);
+    if outcome.is_withheld() {
+        return format!(
+            "[Output of `{call_name}` withheld by data-loss-prevention rules: {}]",
+            outcome.detector_names().join(", ")
+        );
+    }
+    outcome.content
+}
+
+/// Keep tool output within `agent.tool_output_max_chars`, spilling the full
+/// text to the session artifact store. `artifact_read` pages are already
+/// bounded and are never spilled again.
+fn budget_tool_output(call_name: &str, output: String) -> String {
+    if call_name == "artifact_read" {
+        return output;
+    }
+    artifacts::apply_budget(call_name, output)
+}
+
 async fn execute_one_tool(
-    call_name: &str,
-    call_arguments: serde_json::Value,
+    call: &ParsedToolCall,
     tools_registry: &[Box<dyn Tool>],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<ToolExecutionOutcome> {
+    let call_name = call.name.as_str();
+    let call_arguments = call.arguments.clone();
     observer.record_event(&ObserverEvent::ToolCallStart {
         tool: call_name.to_string(),
     });
     let start = Instant::now();
+    // Nested agent loops started by the tool (delegate, subagent_spawn)
+    // parent their spans to this one.
+    let mut tool_span = SpanGuard::start(SpanKind::ToolCall, format!("execute_tool {call_name}"));
+    tool_span.set_attribute("gen_ai.operation.name", "execute_tool");
+    tool_span.set_attribute("gen_ai.tool.name", call_name);
+    if let Some(call_id) = call.tool_call_id.as_deref() {
+        tool_span.set_attribute("gen_ai.tool.call.id", call_id);
+    }
 
     let Some(tool) = find_tool(tools_registry, call_name) else {
         let reason = format!("Unknown tool: {call_name}");
+        tool_span.fail(reason.clone());
         let duration = start.elapsed();
         observer.record_event(&ObserverEvent::ToolCall {
             tool: call_name.to_string(),
@@ -38,14 +75,26 @@ async fn execute_one_tool(
         });
     };
 
-    let tool_future = tool.execute(call_arguments);
-    let tool_result = if let Some(token) = cancellation_token {
+    let estop_guard = cancellation::register(StopScope::tool_call(call_name));
+    let tool_result = if estop_guard.is_stopped() {
+        Err(anyhow::anyhow!(cancellation::stopped_message("Tool call")))
+    } else if let Some(token) = cancellation_token {
         tokio::select! {
+            biased;
             () = token.cancelled() => return Err(ToolLoopCancelled.into()),
-            result = tool_future => result,
+            () = estop_guard.stopped() => {
+                Err(anyhow::anyhow!(cancellation::stopped_message("Tool call")))
+            }
+            result = span::scope(tool_span.context(), tool.execute(call_arguments)) => result,
         }
     } else {
-        tool_future.await
+        tokio::select! {
+            biased;
+            () = estop_guard.stopped() => {
+                Err(anyhow::anyhow!(cancellation::stopped_message("Tool call")))
+            }
+            result = span::scope(tool_span.context(), tool.execute(call_arguments)) => result,
+        }
     };
 
     match tool_result {
@@ -56,9 +105,15 @@ async fn execute_one_tool(
                 duration,
                 success: r.success,
             });
+            if !r.success {
+                tool_span.fail(r.error.as_deref().unwrap_or("tool failed"));
+            }
             if r.success {
                 Ok(ToolExecutionOutcome {
-                    output: scrub_credentials(&r.output),
+                    output: budget_tool_output(
+                        call_name,
+                        guard_tool_output(call_name, &scrub_credentials(&r.output)),
+                    ),
                     success: true,
                     error_reason: None,
                     duration,
@@ -66,7 +121,13 @@ async fn execute_one_tool(
             } else {
                 let reason = r.error.unwrap_or(r.output);
                 Ok(ToolExecutionOutcome {
-                    output: format!("Error: {reason}"),
+                    output: budget_tool_output(
+                        call_name,
+                        guard_tool_output(
+                            call_name,
+                            &scrub_credentials(&format!("Error: {reason}")),
+                        ),
+                    ),
                     success: false,
                     error_reason: Some(scrub_credentials(&reason)),
                     duration,
@@ -74,6 +135,7 @@ async fn execute_one_tool(
             }
         }
         Err(e) => {
+            tool_span.fail(e.to_string());
             let duration = start.elapsed();
             observer.record_event(&ObserverEvent::ToolCall {
                 tool: call_name.to_string(),
@@ -125,15 +187,7 @@ pub(super) async fn execute_tools_parallel(
 ) -> Result<Vec<ToolExecutionOutcome>> {
     let futures: Vec<_> = tool_calls
         .iter()
-        .map(|call| {
-            execute_one_tool(
-                &call.name,
-                call.arguments.clone(),
-                tools_registry,
-                observer,
-                cancellation_token,
-            )
-        })
+        .map(|call| execute_one_tool(call, tools_registry, observer, cancellation_token))
         .collect();
 
     let results = futures_util::future::join_all(futures).await;
@@ -149,16 +203,7 @@ pub(super) async fn execute_tools_sequential(
     let mut outcomes = Vec::with_capacity(tool_calls.len());
 
     for call in tool_calls {
-        outcomes.push(
-            execute_one_tool(
-                &call.name,
-                call.arguments.clone(),
-                tools_registry,
-                observer,
-                cancellation_token,
-            )
-            .await?,
-        );
+        outcomes.push(execute_one_tool(call, tools_registry, observer, cancellation_token).await?);
     }
 
     Ok(outcomes)
@@ -1,12 +1,15 @@
 #[allow(clippy::module_inception)]
 pub mod agent;
+pub mod artifacts;
 pub mod classifier;
 pub mod dispatcher;
 pub mod loop_;
 pub mod memory_loader;
+pub mod plan;
 pub mod prompt;
 pub mod quota_aware;
 pub mod research;
+pub mod session;
 
 #[cfg(test)]
 mod tests;
@@ -14,4 +17,4 @@ mod tests;
 #[allow(unused_imports)]
 pub use agent::{Agent, AgentBuilder};
 #[allow(unused_imports)]
-pub use loop_::{process_message, run};
+pub use loop_::{process_message, process_message_in_session, run};
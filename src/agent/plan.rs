@@ -0,0 +1,915 @@
+//! Plan mode — planner/executor turns with explicit plan approval.
+//!
+//! A planning call turns the user's request into a structured plan (steps,
+//! expected tools, risk). The plan is reviewed by the user (approve, edit or
+//! reject) on the CLI or through the originating channel, then executed one
+//! step at a time through the regular tool-call loop. Progress is reported
+//! against the plan, and a failed step triggers a re-plan of the remaining
+//! work (bounded by `agent.plan_mode.max_replans`).
+
+use crate::agent::loop_::{is_tool_loop_cancelled, run_tool_call_loop};
+use crate::approval::ApprovalManager;
+use crate::channels::traits::{Channel, SendMessage};
+use crate::config::{MultimodalConfig, PlanModeConfig};
+use crate::observability::Observer;
+use crate::providers::{ChatMessage, Provider};
+use crate::tools::{Tool, ToolSpec};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// Prefix that requests a planned turn (`/plan <task>`).
+pub const PLAN_COMMAND: &str = "/plan";
+
+/// Prefix of channel plan review ids. `/approve-allow` and `/approve-deny`
+/// route ids with this prefix to the plan review instead of tool approvals.
+pub const PLAN_REVIEW_ID_PREFIX: &str = "plan-";
+
+/// Tool name shown in the approval prompt that asks for plan approval.
+const PLAN_REVIEW_TOOL: &str = "plan_review";
+
+/// Marker a step reply starts with when the step could not be completed.
+const STEP_FAILED_MARKER: &str = "STEP FAILED:";
+
+const PLANNER_SYSTEM_PROMPT: &str = r#"You are in PLANNING MODE. Break the user's task into a short sequence of concrete steps that an agent with the tools listed below can execute one at a time.
+
+Reply with JSON only, in this shape:
+{"goal": "<one sentence>", "steps": [{"description": "<what to do>", "tools": ["<tool name>"], "risk": "low|medium|high"}]}
+
+RULES:
+1. Each step must be independently verifiable and name the tools it expects to use
+2. Rate risk "high" for destructive, irreversible or externally visible actions, "medium" for local changes, "low" for read-only work
+3. Do not execute anything — only plan
+4. Use at most {max_steps} steps"#;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanRisk {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl PlanRisk {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StepStatus {
+    #[default]
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+impl StepStatus {
+    fn marker(self) -> &'static str {
+        match self {
+            Self::Pending => "[ ]",
+            Self::Running => "[>]",
+            Self::Done => "[x]",
+            Self::Failed => "[!]",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub description: String,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub risk: PlanRisk,
+    #[serde(skip)]
+    pub status: StepStatus,
+    /// Outcome summary (result or failure reason) once the step has run.
+    #[serde(skip)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Plan {
+    #[serde(default)]
+    pub goal: String,
+    pub steps: Vec<PlanStep>,
+}
+
+impl Plan {
+    pub fn max_risk(&self) -> PlanRisk {
+        self.steps
+            .iter()
+            .map(|step| step.risk)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Checklist rendering used for review prompts and progress reports.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if !self.goal.is_empty() {
+            let _ = writeln!(out, "Goal: {}", self.goal);
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{} {}. {}",
+                step.status.marker(),
+                index + 1,
+                step.description
+            );
+            if !step.tools.is_empty() {
+                let _ = write!(out, " (tools: {})", step.tools.join(", "));
+            }
+            if step.risk != PlanRisk::Low {
+                let _ = write!(out, " [risk: {}]", step.risk.as_str());
+            }
+            out.push('\n');
+        }
+        let _ = write!(out, "Overall risk: {}", self.max_risk().as_str());
+        out
+    }
+}
+
+/// The user's answer to a plan review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanDecision {
+    Approve,
+    /// Re-plan with the given change request.
+    Edit(String),
+    Reject,
+}
+
+/// Return the task to plan for `message`: the text after `/plan`, or the
+/// whole message when `auto` is enabled.
+pub fn plan_request<'a>(config: &PlanModeConfig, message: &'a str) -> Option<&'a str> {
+    let trimmed = message.trim();
+    if let Some(rest) = trimmed.strip_prefix(PLAN_COMMAND) {
+        if rest.starts_with(char::is_whitespace) && !rest.trim().is_empty() {
+            return Some(rest.trim());
+        }
+        if rest.is_empty() {
+            return None;
+        }
+    }
+    (config.auto && !trimmed.is_empty() && !trimmed.starts_with('/')).then_some(trimmed)
+}
+
+/// Parse the planner reply, tolerating Markdown fences and surrounding prose.
+pub fn parse_plan(text: &str, max_steps: usize) -> Result<Plan> {
+    let start = text
+        .find('{')
+        .context("planner reply contains no JSON object")?;
+    let end = text
+        .rfind('}')
+        .filter(|end| *end > start)
+        .context("planner reply contains no JSON object")?;
+    let mut plan: Plan =
+        serde_json::from_str(&text[start..=end]).context("planner reply is not a valid plan")?;
+    plan.steps
+        .retain(|step| !step.description.trim().is_empty());
+    if plan.steps.is_empty() {
+        anyhow::bail!("planner returned no steps");
+    }
+    if max_steps > 0 && plan.steps.len() > max_steps {
+        anyhow::bail!(
+            "planner returned {} steps (limit is {max_steps})",
+            plan.steps.len()
+        );
+    }
+    Ok(plan)
+}
+
+/// Detect a step reply that reports failure; returns the reason.
+fn step_failure(reply: &str) -> Option<String> {
+    let trimmed = reply.trim_start();
+    let head = trimmed.get(..STEP_FAILED_MARKER.len())?;
+    head.eq_ignore_ascii_case(STEP_FAILED_MARKER).then(|| {
+        let reason = trimmed[STEP_FAILED_MARKER.len()..].trim();
+        if reason.is_empty() {
+            "step reported failure".to_string()
+        } else {
+            reason.to_string()
+        }
+    })
+}
+
+fn planner_system_prompt(tool_specs: &[ToolSpec], max_steps: usize) -> String {
+    let mut prompt = PLANNER_SYSTEM_PROMPT.replace("{max_steps}", &max_steps.to_string());
+    prompt.push_str("\n\nAVAILABLE TOOLS:\n");
+    for spec in tool_specs {
+        let _ = writeln!(prompt, "- {}: {}", spec.name, spec.description);
+    }
+    prompt
+}
+
+/// What the planner is asked for: a first plan, a revision after user edits,
+/// or a re-plan of the remaining work after a failed step.
+enum PlanningRequest<'a> {
+    Initial,
+    Revise {
+        plan: &'a Plan,
+        feedback: &'a str,
+    },
+    Replan {
+        completed: &'a [PlanStep],
+        failed: &'a PlanStep,
+        reason: &'a str,
+    },
+}
+
+fn planning_prompt(task: &str, request: &PlanningRequest<'_>) -> String {
+    let mut prompt = format!("Task:\n{task}\n");
+    match request {
+        PlanningRequest::Initial => {}
+        PlanningRequest::Revise { plan, feedback } => {
+            let _ = write!(
+                prompt,
+                "\nCurrent plan:\n{}\n\nThe user asked for these changes:\n{feedback}\n\nReturn the full revised plan.",
+                plan.render()
+            );
+        }
+        PlanningRequest::Replan {
+            completed,
+            failed,
+            reason,
+        } => {
+            prompt.push_str("\nAlready completed (do not repeat):\n");
+            if completed.is_empty() {
+                prompt.push_str("- nothing yet\n");
+            }
+            for step in *completed {
+                let _ = writeln!(
+                    prompt,
+                    "- {}: {}",
+                    step.description,
+                    step.note.as_deref().unwrap_or("done")
+                );
+            }
+            let _ = write!(
+                prompt,
+                "\nThis step failed:\n- {}\nReason: {reason}\n\nReturn a plan for the remaining work only, working around the failure.",
+                failed.description
+            );
+        }
+    }
+    prompt
+}
+
+/// Issues planning calls for one planned turn.
+struct Planner<'a> {
+    provider: &'a dyn Provider,
+    model: &'a str,
+    temperature: f64,
+    system_prompt: String,
+    max_steps: usize,
+    task: &'a str,
+}
+
+impl Planner<'_> {
+    async fn plan(&self, request: PlanningRequest<'_>) -> Result<Plan> {
+        let reply = self
+            .provider
+            .chat_with_system(
+                Some(&self.system_prompt),
+                &planning_prompt(self.task, &request),
+                self.model,
+                self.temperature,
+            )
+            .await
+            .context("planning call failed")?;
+        parse_plan(&reply, self.max_steps)
+    }
+}
+
+fn step_instruction(plan: &Plan, completed: &[PlanStep], index: usize) -> String {
+    let step = &plan.steps[index];
+    let mut instruction = format!(
+        "[Plan step {}/{}] {}\n",
+        index + 1,
+        plan.steps.len(),
+        step.description
+    );
+    if !step.tools.is_empty() {
+        let _ = writeln!(instruction, "Expected tools: {}", step.tools.join(", "));
+    }
+    if !plan.goal.is_empty() {
+        let _ = writeln!(instruction, "Overall goal: {}", plan.goal);
+    }
+    let finished: Vec<&PlanStep> = completed.iter().chain(plan.steps[..index].iter()).collect();
+    if !finished.is_empty() {
+        instruction.push_str("Completed steps:\n");
+        for step in finished {
+            let _ = writeln!(instruction, "- {}", step.description);
+        }
+    }
+    let _ = write!(
+        instruction,
+        "Carry out only this step, then reply with a short summary of the result. \
+         If the step cannot be completed, reply starting with `{STEP_FAILED_MARKER}` and the reason."
+    );
+    instruction
+}
+
+/// Presents plans to the user and relays progress.
+#[async_trait]
+pub trait PlanReviewer: Send + Sync {
+    async fn review(&self, plan: &Plan) -> PlanDecision;
+    async fn progress(&self, message: &str);
+}
+
+/// Reviews plans on the terminal.
+pub struct CliPlanReviewer;
+
+#[async_trait]
+impl PlanReviewer for CliPlanReviewer {
+    async fn review(&self, plan: &Plan) -> PlanDecision {
+        eprintln!();
+        eprintln!("📋 Proposed plan:");
+        eprintln!("{}", plan.render());
+        eprint!("   [Y]es / [E]dit / [N]o: ");
+        let _ = io::stderr().flush();
+
+        let Some(line) = read_stdin_line().await else {
+            return PlanDecision::Reject;
+        };
+        match line.trim().to_ascii_lowercase().as_str() {
+            "" | "y" | "yes" => PlanDecision::Approve,
+            "e" | "edit" => {
+                eprint!("   Describe the changes: ");
+                let _ = io::stderr().flush();
+                match read_stdin_line().await {
+                    Some(feedback) if !feedback.trim().is_empty() => {
+                        PlanDecision::Edit(feedback.trim().to_string())
+                    }
+                    _ => PlanDecision::Reject,
+                }
+            }
+            _ => PlanDecision::Reject,
+        }
+    }
+
+    async fn progress(&self, message: &str) {
+        eprintln!("{message}");
+    }
+}
+
+/// Read one line from stdin without blocking the async runtime.
+async fn read_stdin_line() -> Option<String> {
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line).ok().map(|_| line)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+struct PendingReview {
+    channel: String,
+    sender: String,
+    responder: oneshot::Sender<PlanDecision>,
+}
+
+static PENDING_REVIEWS: LazyLock<Mutex<HashMap<String, PendingReview>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Answer a pending channel plan review. Only the user who requested the
+/// plan, on the same channel, may answer it.
+pub fn resolve_review(
+    request_id: &str,
+    channel: &str,
+    sender: &str,
+    decision: PlanDecision,
+) -> Result<(), String> {
+    let mut pending = PENDING_REVIEWS.lock();
+    let Some(review) = pending.get(request_id) else {
+        return Err(format!(
+            "Plan review `{request_id}` was not found or has already been answered."
+        ));
+    };
+    if review.channel != channel || review.sender != sender {
+        return Err(format!(
+            "Plan review `{request_id}` can only be answered by the user who requested the plan."
+        ));
+    }
+    let review = pending
+        .remove(request_id)
+        .expect("pending review checked above");
+    review
+        .responder
+        .send(decision)
+        .map_err(|_| format!("Plan review `{request_id}` is no longer waiting for an answer."))
+}
+
+/// Reviews plans through the channel the request came from.
+pub struct ChannelPlanReviewer {
+    pub channel: Arc<dyn Channel>,
+    pub sender: String,
+    pub reply_target: String,
+    pub thread_ts: Option<String>,
+    pub timeout: Duration,
+}
+
+#[async_trait]
+impl PlanReviewer for ChannelPlanReviewer {
+    async fn review(&self, plan: &Plan) -> PlanDecision {
+        let request_id = format!(
+            "{PLAN_REVIEW_ID_PREFIX}{}",
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let (responder, answer) = oneshot::channel();
+        PENDING_REVIEWS.lock().insert(
+            request_id.clone(),
+            PendingReview {
+                channel: self.channel.name().to_string(),
+                sender: self.sender.clone(),
+                responder,
+            },
+        );
+
+        // The plan itself goes out as a regular message; approval uses the
+        // channel's approval prompt (buttons where the channel has them).
+        let summary = format!(
+            "📋 Proposed plan (`{request_id}`):\n{}\nRequest changes: `/plan-edit {request_id} <changes>`",
+            plan.render()
+        );
+        let arguments = serde_json::json!({
+            "goal": plan.goal,
+            "steps": plan.steps.len(),
+        });
+        let sent = match self
+            .channel
+            .send(&SendMessage::new(summary, &self.reply_target).in_thread(self.thread_ts.clone()))
+            .await
+        {
+            Ok(()) => {
+                self.channel
+                    .send_approval_prompt(
+                        &self.reply_target,
+                        &request_id,
+                        PLAN_REVIEW_TOOL,
+                        &arguments,
+                        self.thread_ts.clone(),
+                    )
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        if let Err(err) = sent {
+            tracing::warn!("Failed to send plan review prompt: {err}");
+            PENDING_REVIEWS.lock().remove(&request_id);
+            return PlanDecision::Reject;
+        }
+
+        match tokio::time::timeout(self.timeout, answer).await {
+            Ok(Ok(decision)) => decision,
+            _ => {
+                PENDING_REVIEWS.lock().remove(&request_id);
+                self.progress(&format!(
+                    "Plan review `{request_id}` timed out; the plan was not executed."
+                ))
+                .await;
+                PlanDecision::Reject
+            }
+        }
+    }
+
+    async fn progress(&self, message: &str) {
+        let message =
+            SendMessage::new(message, &self.reply_target).in_thread(self.thread_ts.clone());
+        if let Err(err) = self.channel.send(&message).await {
+            tracing::debug!("Failed to send plan progress: {err}");
+        }
+    }
+}
+
+/// Tool-loop settings shared by every step of a planned turn.
+pub(crate) struct PlanExecution<'a> {
+    pub provider: &'a dyn Provider,
+    pub tools_registry: &'a [Box<dyn Tool>],
+    pub observer: &'a dyn Observer,
+    pub provider_name: &'a str,
+    pub model: &'a str,
+    pub temperature: f64,
+    pub silent: bool,
+    pub approval: Option<&'a ApprovalManager>,
+    pub channel_name: &'a str,
+    pub multimodal_config: &'a MultimodalConfig,
+    pub max_tool_iterations: usize,
+    pub cancellation_token: Option<CancellationToken>,
+    pub hooks: Option<&'a crate::hooks::HookRunner>,
+    pub excluded_tools: &'a [String],
+}
+
+fn render_progress(completed: &[PlanStep], plan: &Plan) -> String {
+    let combined = Plan {
+        goal: plan.goal.clone(),
+        steps: completed.iter().chain(plan.steps.iter()).cloned().collect(),
+    };
+    combined.render()
+}
+
+/// Plan `task`, have it reviewed, and execute it step by step. The user
+/// message is expected to be in `history` already; each step appends its
+/// instruction and tool-loop exchange.
+pub(crate) async fn run_plan_mode(
+    exec: &PlanExecution<'_>,
+    config: &PlanModeConfig,
+    history: &mut Vec<ChatMessage>,
+    task: &str,
+    reviewer: &dyn PlanReviewer,
+) -> Result<String> {
+    let tool_specs: Vec<ToolSpec> = exec
+        .tools_registry
+        .iter()
+        .filter(|tool| !exec.excluded_tools.iter().any(|ex| ex == tool.name()))
+        .map(|tool| tool.spec())
+        .collect();
+    let max_iterations = if config.step_max_tool_iterations == 0 {
+        exec.max_tool_iterations
+    } else {
+        config.step_max_tool_iterations
+    };
+    let planner = Planner {
+        provider: exec.provider,
+        model: exec.model,
+        temperature: exec.temperature,
+        system_prompt: planner_system_prompt(&tool_specs, config.max_steps),
+        max_steps: config.max_steps,
+        task,
+    };
+
+    let mut plan = planner.plan(PlanningRequest::Initial).await?;
+    let mut completed: Vec<PlanStep> = Vec::new();
+    let mut replans = 0;
+    let mut last_reply = String::new();
+
+    'plan: loop {
+        if config.require_approval {
+            loop {
+                match reviewer.review(&plan).await {
+                    PlanDecision::Approve => break,
+                    PlanDecision::Edit(feedback) => {
+                        plan = planner
+                            .plan(PlanningRequest::Revise {
+                                plan: &plan,
+                                feedback: &feedback,
+                            })
+                            .await?;
+                    }
+                    PlanDecision::Reject => {
+                        let notice = "Plan rejected; nothing further was executed.";
+                        history.push(ChatMessage::assistant(notice));
+                        return Ok(notice.to_string());
+                    }
+                }
+            }
+        } else {
+            reviewer
+                .progress(&format!("📋 Plan:\n{}", plan.render()))
+                .await;
+        }
+
+        let total = plan.steps.len();
+        for index in 0..total {
+            plan.steps[index].status = StepStatus::Running;
+            reviewer
+                .progress(&format!(
+                    "▶ Step {}/{total}: {}",
+                    index + 1,
+                    plan.steps[index].description
+                ))
+                .await;
+            history.push(ChatMessage::user(step_instruction(
+                &plan, &completed, index,
+            )));
+
+            let result = run_tool_call_loop(
+                exec.provider,
+                history,
+                exec.tools_registry,
+                exec.observer,
+                exec.provider_name,
+                exec.model,
+                exec.temperature,
+                exec.silent,
+                exec.approval,
+                exec.channel_name,
+                exec.multimodal_config,
+                max_iterations,
+                exec.cancellation_token.clone(),
+                None,
+                exec.hooks,
+                exec.excluded_tools,
+            )
+            .await;
+            let failure = match result {
+                Ok(reply) => match step_failure(&reply) {
+                    Some(reason) => Some(reason),
+                    None => {
+                        last_reply = reply;
+                        None
+                    }
+                },
+                Err(err) if is_tool_loop_cancelled(&err) => return Err(err),
+                Err(err) => Some(err.to_string()),
+            };
+
+            let Some(reason) = failure else {
+                let step = &mut plan.steps[index];
+                step.status = StepStatus::Done;
+                step.note = Some(crate::util::truncate_with_ellipsis(&last_reply, 200));
+                reviewer
+                    .progress(&format!("✅ Step {}/{total} done", index + 1))
+                    .await;
+                continue;
+            };
+
+            plan.steps[index].status = StepStatus::Failed;
+            plan.steps[index].note = Some(reason.clone());
+            reviewer
+                .progress(&format!("❌ Step {}/{total} failed: {reason}", index + 1))
+                .await;
+            if replans >= config.max_replans {
+                let summary = format!(
+                    "Plan stopped after a failed step ({replans} re-plan(s) used).\n{}",
+                    render_progress(&completed, &plan)
+                );
+                history.push(ChatMessage::assistant(&summary));
+                return Ok(summary);
+            }
+            replans += 1;
+
+            completed.extend(plan.steps[..index].iter().cloned());
+            let failed = plan.steps[index].clone();
+            plan = planner
+                .plan(PlanningRequest::Replan {
+                    completed: &completed,
+                    failed: &failed,
+                    reason: &reason,
+                })
+                .await?;
+            reviewer
+                .progress(&format!(
+                    "🔁 Re-planned the remaining work ({replans}/{})",
+                    config.max_replans
+                ))
+                .await;
+            continue 'plan;
+        }
+        break;
+    }
+
+    let total = completed.len() + plan.steps.len();
+    Ok(format!(
+        "{last_reply}\n\nPlan complete ({total}/{total} steps):\n{}",
+        render_progress(&completed, &plan)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatRequest;
+    use crate::providers::ChatResponse;
+    use std::collections::VecDeque;
+
+    struct ScriptedProvider {
+        replies: Mutex<VecDeque<String>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().map(|reply| reply.to_string()).collect()),
+            }
+        }
+
+        fn next(&self) -> String {
+            self.replies.lock().pop_front().unwrap_or_default()
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok(self.next())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            Ok(ChatResponse {
+                text: Some(self.next()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+                quota_metadata: None,
+            })
+        }
+    }
+
+    struct ScriptedReviewer {
+        decisions: Mutex<VecDeque<PlanDecision>>,
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl ScriptedReviewer {
+        fn new(decisions: Vec<PlanDecision>) -> Self {
+            Self {
+                decisions: Mutex::new(decisions.into()),
+                messages: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl PlanReviewer for ScriptedReviewer {
+        async fn review(&self, plan: &Plan) -> PlanDecision {
+            self.messages
+                .lock()
+                .push(format!("review:\n{}", plan.render()));
+            self.decisions
+                .lock()
+                .pop_front()
+                .unwrap_or(PlanDecision::Reject)
+        }
+
+        async fn progress(&self, message: &str) {
+            self.messages.lock().push(message.to_string());
+        }
+    }
+
+    fn execution<'a>(provider: &'a dyn Provider, observer: &'a dyn Observer) -> PlanExecution<'a> {
+        static MULTIMODAL: LazyLock<MultimodalConfig> = LazyLock::new(MultimodalConfig::default);
+        PlanExecution {
+            provider,
+            tools_registry: &[],
+            observer,
+            provider_name: "scripted",
+            model: "scripted-model",
+            temperature: 0.0,
+            silent: true,
+            approval: None,
+            channel_name: "cli",
+            multimodal_config: &MULTIMODAL,
+            max_tool_iterations: 4,
+            cancellation_token: None,
+            hooks: None,
+            excluded_tools: &[],
+        }
+    }
+
+    #[test]
+    fn plan_request_honours_command_prefix_and_auto_mode() {
+        let config = PlanModeConfig::default();
+        assert_eq!(plan_request(&config, "/plan ship it"), Some("ship it"));
+        assert_eq!(plan_request(&config, "/plan"), None);
+        assert_eq!(plan_request(&config, "/plan-edit plan-1 more"), None);
+        assert_eq!(plan_request(&config, "ship it"), None);
+
+        let auto = PlanModeConfig {
+            auto: true,
+            ..PlanModeConfig::default()
+        };
+        assert_eq!(plan_request(&auto, "ship it"), Some("ship it"));
+        assert_eq!(plan_request(&auto, "/help"), None);
+    }
+
+    #[test]
+    fn parse_plan_accepts_fenced_json_and_enforces_limits() {
+        let reply = "Here is the plan:\n```json\n{\"goal\": \"Deploy\", \"steps\": [\
+            {\"description\": \"Run tests\", \"tools\": [\"shell\"]},\
+            {\"description\": \"Push release\", \"tools\": [\"shell\"], \"risk\": \"high\"}]}\n```";
+        let plan = parse_plan(reply, 8).unwrap();
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.max_risk(), PlanRisk::High);
+        let rendered = plan.render();
+        assert!(rendered.contains("[ ] 2. Push release (tools: shell) [risk: high]"));
+
+        assert!(parse_plan(reply, 1).is_err());
+        assert!(parse_plan("no plan here", 8).is_err());
+        assert!(parse_plan("{\"steps\": []}", 8).is_err());
+    }
+
+    #[test]
+    fn step_failure_marker_is_detected() {
+        assert_eq!(
+            step_failure("step failed: disk full").as_deref(),
+            Some("disk full")
+        );
+        assert!(step_failure("All tests passed").is_none());
+    }
+
+    #[tokio::test]
+    async fn approved_plan_executes_steps_and_replans_after_failure() {
+        let provider = ScriptedProvider::new(&[
+            r#"{"goal": "g", "steps": [{"description": "first"}, {"description": "second"}]}"#,
+            "first done",
+            "STEP FAILED: missing file",
+            r#"{"goal": "g", "steps": [{"description": "recover"}]}"#,
+            "recovered",
+        ]);
+        let observer = crate::observability::NoopObserver;
+        let reviewer = ScriptedReviewer::new(vec![PlanDecision::Approve, PlanDecision::Approve]);
+        let mut history = vec![ChatMessage::user("/plan do it")];
+
+        let reply = run_plan_mode(
+            &execution(&provider, &observer),
+            &PlanModeConfig::default(),
+            &mut history,
+            "do it",
+            &reviewer,
+        )
+        .await
+        .unwrap();
+
+        assert!(reply.starts_with("recovered"));
+        assert!(reply.contains("Plan complete (2/2 steps)"));
+        assert!(reply.contains("[x] 1. first"));
+        assert!(reply.contains("[x] 2. recover"));
+        let messages = reviewer.messages.lock();
+        assert!(messages
+            .iter()
+            .any(|m| m == "❌ Step 2/2 failed: missing file"));
+        assert!(messages.iter().any(|m| m.starts_with("🔁 Re-planned")));
+        assert_eq!(
+            messages.iter().filter(|m| m.starts_with("review:")).count(),
+            2
+        );
+        assert!(history
+            .iter()
+            .any(|message| message.content.starts_with("[Plan step 1/1] recover")));
+    }
+
+    #[tokio::test]
+    async fn edited_plan_is_revised_and_rejection_stops_execution() {
+        let provider = ScriptedProvider::new(&[
+            r#"{"steps": [{"description": "delete everything", "risk": "high"}]}"#,
+            r#"{"steps": [{"description": "archive first"}]}"#,
+        ]);
+        let observer = crate::observability::NoopObserver;
+        let reviewer = ScriptedReviewer::new(vec![
+            PlanDecision::Edit("archive instead".into()),
+            PlanDecision::Reject,
+        ]);
+        let mut history = Vec::new();
+
+        let reply = run_plan_mode(
+            &execution(&provider, &observer),
+            &PlanModeConfig::default(),
+            &mut history,
+            "clean up",
+            &reviewer,
+        )
+        .await
+        .unwrap();
+
+        assert!(reply.starts_with("Plan rejected"));
+        let messages = reviewer.messages.lock();
+        assert!(messages[1].contains("archive first"));
+        assert_eq!(history.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn channel_reviews_only_accept_the_requesting_user() {
+        let (responder, answer) = oneshot::channel();
+        PENDING_REVIEWS.lock().insert(
+            "plan-test".into(),
+            PendingReview {
+                channel: "slack".into(),
+                sender: "alice".into(),
+                responder,
+            },
+        );
+        assert!(resolve_review("plan-test", "slack", "bob", PlanDecision::Approve).is_err());
+        assert!(resolve_review("plan-test", "slack", "alice", PlanDecision::Approve).is_ok());
+        assert_eq!(answer.await.unwrap(), PlanDecision::Approve);
+        assert!(resolve_review("plan-test", "slack", "alice", PlanDecision::Approve).is_err());
+    }
+}
@@ -0,0 +1,471 @@
+//! Named, persistent agent sessions.
+//!
+//! A named session keeps the full conversation history, the `task_plan`
+//! checklist, provider/model overrides and an optional tool allowlist in
+//! `<workspace>/state/named_sessions/<name>.json`. The CLI (`zeroclaw agent --session`),
+//! the gateway WebSocket (`/ws/chat?session=`) and channels (`/session
+//! switch`) all read and write the same files, so a conversation started in
+//! one surface can be continued in another.
+//!
+//! Turns are appended with [`SessionStore::update`], a read-modify-write of
+//! the session file; concurrent writers never drop each other's turns within
+//! one process.
+
+use crate::agent::dispatcher::{NativeToolDispatcher, ToolDispatcher};
+use crate::providers::{ChatMessage, ConversationMessage};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// Directory under the workspace holding `<name>.json` session files. Kept
+/// out of `sessions/`, which memory hygiene archives and purges.
+pub const SESSIONS_DIR: &str = "state/named_sessions";
+
+const MAX_SESSION_NAME_LEN: usize = 64;
+
+tokio::task_local! {
+    static ACTIVE_SESSION: Option<ActiveSession>;
+}
+
+/// Serializes read-modify-write cycles on session files in this process.
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// One `task_plan` checklist entry stored with the session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionTask {
+    pub id: usize,
+    pub title: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedSession {
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tools this session may use; `None` allows every tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub tasks: Vec<SessionTask>,
+    #[serde(default)]
+    pub history: Vec<ConversationMessage>,
+}
+
+impl NamedSession {
+    pub fn new(name: &str) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            name: name.to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+            forked_from: None,
+            provider: None,
+            model: None,
+            allowed_tools: None,
+            tasks: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// The last `limit` history entries as provider messages (`0` = all).
+    pub fn provider_messages(&self, limit: usize) -> Vec<ChatMessage> {
+        let start = if limit == 0 {
+            0
+        } else {
+            self.history.len().saturating_sub(limit)
+        };
+        NativeToolDispatcher.to_provider_messages(&self.history[start..])
+    }
+
+    /// The last `limit` plain user/assistant turns, with tool exchanges
+    /// dropped — the shape channel conversation caches keep.
+    pub fn chat_turns(&self, limit: usize) -> Vec<ChatMessage> {
+        let mut turns: Vec<ChatMessage> = self
+            .provider_messages(0)
+            .into_iter()
+            .filter_map(|message| match message.role.as_str() {
+                "user" if !message.content.starts_with("[Tool results]") => Some(message),
+                "assistant" => match serde_json::from_str::<serde_json::Value>(&message.content) {
+                    Ok(payload) if payload.get("tool_calls").is_some() => payload
+                        .get("content")
+                        .and_then(serde_json::Value::as_str)
+                        .filter(|text| !text.trim().is_empty())
+                        .map(ChatMessage::assistant),
+                    _ => Some(message),
+                },
+                _ => None,
+            })
+            .collect();
+        if limit > 0 && turns.len() > limit {
+            turns.drain(..turns.len() - limit);
+        }
+        turns
+    }
+
+    /// Append runtime messages; system prompts are not stored because every
+    /// surface builds its own.
+    pub fn append(&mut self, messages: &[ChatMessage]) {
+        self.history.extend(
+            messages
+                .iter()
+                .filter(|message| message.role != "system")
+                .cloned()
+                .map(ConversationMessage::Chat),
+        );
+    }
+
+    /// Tools in `available` that the allowlist excludes.
+    pub fn excluded_tools<'a>(&self, available: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let Some(allowed) = &self.allowed_tools else {
+            return Vec::new();
+        };
+        available
+            .into_iter()
+            .filter(|name| !allowed.iter().any(|allowed| allowed == name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut out = format!(
+            "Session `{}` — {} messages, {} tasks, updated {}",
+            self.name,
+            self.history.len(),
+            self.tasks.len(),
+            self.updated_at
+        );
+        if let Some(parent) = &self.forked_from {
+            let _ = write!(out, "\nForked from: {parent}");
+        }
+        if self.provider.is_some() || self.model.is_some() {
+            let _ = write!(
+                out,
+                "\nModel: {} / {}",
+                self.provider.as_deref().unwrap_or("(default provider)"),
+                self.model.as_deref().unwrap_or("(default model)")
+            );
+        }
+        match &self.allowed_tools {
+            Some(tools) => {
+                let _ = write!(out, "\nAllowed tools: {}", tools.join(", "));
+            }
+            None => out.push_str("\nAllowed tools: all"),
+        }
+        out
+    }
+}
+
+/// File-backed store of named sessions.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            dir: workspace_dir.join(SESSIONS_DIR),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+
+    pub fn load(&self, name: &str) -> Result<Option<NamedSession>> {
+        validate_name(name)?;
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read session {}", path.display()))?;
+        let session = serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse session {}", path.display()))?;
+        Ok(Some(session))
+    }
+
+    /// Load `name`, creating (and saving) an empty session if it is missing.
+    pub fn open(&self, name: &str) -> Result<NamedSession> {
+        if let Some(session) = self.load(name)? {
+            return Ok(session);
+        }
+        let session = NamedSession::new(name);
+        self.write(&session)?;
+        Ok(session)
+    }
+
+    fn write(&self, session: &NamedSession) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.path(&session.name);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(session)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Re-read `name` from disk, apply `change`, and write it back.
+    pub fn update<T>(&self, name: &str, change: impl FnOnce(&mut NamedSession) -> T) -> Result<T> {
+        let _guard = UPDATE_LOCK.lock();
+        let mut session = self.load(name)?.unwrap_or_else(|| NamedSession::new(name));
+        let result = change(&mut session);
+        session.updated_at = Utc::now().to_rfc3339();
+        self.write(&session)?;
+        Ok(result)
+    }
+
+    /// Sessions ordered by most recent update first.
+    pub fn list(&self) -> Result<Vec<NamedSession>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            if validate_name(name).is_err() {
+                continue;
+            }
+            match self.load(name) {
+                Ok(Some(session)) => sessions.push(session),
+                Ok(None) => {}
+                Err(err) => tracing::warn!("Skipping unreadable session {name}: {err:#}"),
+            }
+        }
+        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(sessions)
+    }
+
+    /// Copy `source` (history, tasks, overrides) into a new session `name`.
+    /// A `source` already called `name` is an unsaved conversation being
+    /// named for the first time.
+    pub fn fork(&self, source: &NamedSession, name: &str) -> Result<NamedSession> {
+        validate_name(name)?;
+        let _guard = UPDATE_LOCK.lock();
+        if self.path(name).exists() {
+            anyhow::bail!("Session `{name}` already exists");
+        }
+        let now = Utc::now().to_rfc3339();
+        let fork = NamedSession {
+            name: name.to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+            forked_from: (source.name != name).then(|| source.name.clone()),
+            ..source.clone()
+        };
+        self.write(&fork)?;
+        Ok(fork)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<bool> {
+        validate_name(name)?;
+        let _guard = UPDATE_LOCK.lock();
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))?;
+        Ok(true)
+    }
+}
+
+/// Session names become file names: letters, digits, `-`, `_` and `.`.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SESSION_NAME_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        anyhow::bail!(
+            "Invalid session name `{name}` (use up to {MAX_SESSION_NAME_LEN} letters, digits, `-`, `_` or `.`)"
+        );
+    }
+    Ok(())
+}
+
+/// The named session a task is running in.
+#[derive(Debug, Clone)]
+pub struct ActiveSession {
+    pub store: SessionStore,
+    pub name: String,
+}
+
+/// Run `future` with `active` as the current named session.
+pub async fn scope<F: Future>(active: Option<ActiveSession>, future: F) -> F::Output {
+    ACTIVE_SESSION.scope(active, future).await
+}
+
+/// The named session the current task is running in, if any.
+pub fn active() -> Option<ActiveSession> {
+    ACTIVE_SESSION.try_with(Clone::clone).ok().flatten()
+}
+
+/// A `/session` command, shared by the CLI and channels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionCommand {
+    Show,
+    List,
+    Switch(String),
+    Fork(String),
+    Delete(String),
+    /// Set the tool allowlist; `None` allows all tools.
+    Tools(Option<Vec<String>>),
+}
+
+pub const SESSION_USAGE: &str =
+    "Usage: /session [list | switch <name> | fork <name> | delete <name> | tools <tool,...|all>]";
+
+/// Parse the arguments after `/session`; `Err` carries the usage text.
+pub fn parse_command(args: &str) -> Result<SessionCommand, String> {
+    let mut parts = args.split_whitespace();
+    let command = match (parts.next(), parts.next()) {
+        (None | Some("show"), None) => SessionCommand::Show,
+        (Some("list" | "ls"), None) => SessionCommand::List,
+        (Some("switch" | "open"), Some(name)) => SessionCommand::Switch(name.to_string()),
+        (Some("fork"), Some(name)) => SessionCommand::Fork(name.to_string()),
+        (Some("delete" | "rm"), Some(name)) => SessionCommand::Delete(name.to_string()),
+        (Some("tools"), Some("all" | "*")) => SessionCommand::Tools(None),
+        (Some("tools"), Some(first)) => {
+            let tools = std::iter::once(first)
+                .chain(parts.by_ref())
+                .flat_map(|part| part.split(','))
+                .map(str::trim)
+                .filter(|tool| !tool.is_empty())
+                .map(str::to_string)
+                .collect();
+            return Ok(SessionCommand::Tools(Some(tools)));
+        }
+        _ => return Err(SESSION_USAGE.to_string()),
+    };
+    if parts.next().is_some() {
+        return Err(SESSION_USAGE.to_string());
+    }
+    Ok(command)
+}
+
+/// Render `sessions` for `/session list`, marking `current`.
+pub fn format_list(sessions: &[NamedSession], current: Option<&str>) -> String {
+    if sessions.is_empty() {
+        return "No named sessions yet. Start one with `/session switch <name>`.".to_string();
+    }
+    let mut out = String::from("Named sessions:");
+    for session in sessions {
+        let marker = if Some(session.name.as_str()) == current {
+            "*"
+        } else {
+            "-"
+        };
+        let _ = write!(
+            out,
+            "\n{marker} {} ({} messages, updated {})",
+            session.name,
+            session.history.len(),
+            session.updated_at
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_round_trip_append_fork_and_delete() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let session = store.open("refactor-auth").unwrap();
+        assert!(session.history.is_empty());
+
+        store
+            .update("refactor-auth", |session| {
+                session.append(&[
+                    ChatMessage::system("prompt"),
+                    ChatMessage::user("hello"),
+                    ChatMessage::assistant("hi"),
+                ]);
+                session.model = Some("gpt-4o-mini".into());
+                session.allowed_tools = Some(vec!["shell".into()]);
+            })
+            .unwrap();
+        let session = store.load("refactor-auth").unwrap().unwrap();
+        let messages = session.provider_messages(0);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "hello");
+        assert_eq!(session.provider_messages(1)[0].content, "hi");
+        assert_eq!(
+            session.excluded_tools(["shell", "file_write"]),
+            vec!["file_write".to_string()]
+        );
+
+        let fork = store.fork(&session, "auth-alt").unwrap();
+        assert_eq!(fork.forked_from.as_deref(), Some("refactor-auth"));
+        assert_eq!(fork.history.len(), 2);
+        assert!(store.fork(&session, "auth-alt").is_err());
+
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names.len(), 2);
+        assert!(store.delete("auth-alt").unwrap());
+        assert!(!store.delete("auth-alt").unwrap());
+        assert!(store.load("../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn parse_command_covers_subcommands() {
+        assert_eq!(parse_command(""), Ok(SessionCommand::Show));
+        assert_eq!(parse_command("list"), Ok(SessionCommand::List));
+        assert_eq!(
+            parse_command("switch refactor-auth"),
+            Ok(SessionCommand::Switch("refactor-auth".into()))
+        );
+        assert_eq!(
+            parse_command("tools shell, file_read"),
+            Ok(SessionCommand::Tools(Some(vec![
+                "shell".into(),
+                "file_read".into()
+            ])))
+        );
+        assert_eq!(parse_command("tools all"), Ok(SessionCommand::Tools(None)));
+        assert!(parse_command("switch").is_err());
+        assert!(parse_command("delete a b").is_err());
+    }
+
+    #[tokio::test]
+    async fn active_session_is_task_scoped() {
+        assert!(active().is_none());
+        let store = SessionStore::new(Path::new("/tmp"));
+        let name = scope(
+            Some(ActiveSession {
+                store,
+                name: "s1".into(),
+            }),
+            async { active().map(|active| active.name) },
+        )
+        .await;
+        assert_eq!(name.as_deref(), Some("s1"));
+    }
+}
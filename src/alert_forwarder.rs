@@ -2,7 +2,7 @@
 //! into Mattermost messages and forward them to a Mattermost incoming webhook URL.
 //!
 //! Route: `POST /webhooks/{source}` — source is one of `vercel`, `supabase`, `upstash`, `custom`.
-//! Config: `channels_config.mattermost.alerts_incoming_webhook_url`.
+//! The gateway routes alerts through `[notifications]` via [`forward_to_notifications`].
 //! Auth: set `channels_config.webhook.secret` to require `X-Webhook-Secret` on all alert endpoints.
 
 use anyhow::{Context, Result};
@@ -279,11 +279,71 @@ pub async fn forward_to_mattermost(url: &str, text: &str) -> Result<()> {
     }
 }
 
+/// Transform a `/webhooks/{source}` payload. `None` for unknown sources.
+pub fn transform(source: &str, body: &serde_json::Value) -> Option<String> {
+    match source {
+        "vercel" => Some(transform_vercel(body)),
+        "supabase" => Some(transform_supabase(body)),
+        "upstash" => Some(transform_upstash(body)),
+        "custom" => Some(transform_custom(body)),
+        _ => None,
+    }
+}
+
+/// Infer a notification severity from the status icon a transformer put in
+/// front of the message.
+pub fn alert_severity(text: &str) -> crate::config::NotificationSeverity {
+    use crate::config::NotificationSeverity;
+
+    let head = text.split_whitespace().next().unwrap_or_default();
+    match head {
+        ":x:" | ":red_circle:" | ":skull_and_crossbones:" => NotificationSeverity::Critical,
+        ":warning:" => NotificationSeverity::Warning,
+        _ => NotificationSeverity::Info,
+    }
+}
+
+/// Forward a transformed alert through `[notifications]` routing rules instead
+/// of a single Mattermost webhook. The severity comes from
+/// `notifications.alert_severity`, or from [`alert_severity`] when unset.
+/// Identical alerts from the same webhook source (`vercel`, `supabase`, ...)
+/// are deduplicated.
+pub async fn forward_to_notifications(
+    config: &crate::config::Config,
+    source: &str,
+    text: &str,
+) -> Result<crate::notify::NotifyReport> {
+    let severity = config
+        .notifications
+        .alert_severity
+        .unwrap_or_else(|| alert_severity(text));
+    let notification = crate::notify::Notification::new("alert_forwarder", severity, text)
+        .with_dedup_key(format!("{source}:{text}"));
+    let report = crate::notify::notify(config, notification).await?;
+    if report.delivered.is_empty() && report.queued.is_empty() && !report.failed.is_empty() {
+        anyhow::bail!("alert notification failed: {}", report.failed.join("; "));
+    }
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn alert_severity_follows_status_icon() {
+        use crate::config::NotificationSeverity;
+
+        let failed = transform_vercel(&json!({"type": "deployment.failed"}));
+        assert_eq!(alert_severity(&failed), NotificationSeverity::Critical);
+        let cancelled = transform_vercel(&json!({"type": "deployment.cancelled"}));
+        assert_eq!(alert_severity(&cancelled), NotificationSeverity::Warning);
+        let succeeded = transform_vercel(&json!({"type": "deployment.succeeded"}));
+        assert_eq!(alert_severity(&succeeded), NotificationSeverity::Info);
+        assert!(transform("unknown", &json!({})).is_none());
+    }
+
     #[test]
     fn vercel_succeeded_formats_correctly() {
         let payload = json!({
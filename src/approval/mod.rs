@@ -3,13 +3,16 @@
 //! Provides a pre-execution hook that prompts the user before tool calls,
 //! with session-scoped "Always" allowlists and audit logging.
 
+pub mod quorum;
+
 use crate::config::{AutonomyConfig, NonCliNaturalLanguageApprovalMode};
-use crate::security::AutonomyLevel;
+use crate::security::{AutonomyLevel, UserDirectory};
 use chrono::{Duration, Utc};
 use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::io::{self, BufRead, Write};
+use std::sync::Arc;
 use uuid::Uuid;
 
 // ── Types ────────────────────────────────────────────────────────
@@ -97,6 +100,8 @@ pub struct ApprovalManager {
     resolved_non_cli_requests: Mutex<HashMap<String, ApprovalResponse>>,
     /// Audit trail of approval decisions.
     audit_log: Mutex<Vec<ApprovalLogEntry>>,
+    /// Optional user directory used to resolve `user:` / `role:` approver entries.
+    user_directory: RwLock<Option<Arc<UserDirectory>>>,
 }
 
 impl ApprovalManager {
@@ -147,9 +152,20 @@ impl ApprovalManager {
             pending_non_cli_requests: Mutex::new(HashMap::new()),
             resolved_non_cli_requests: Mutex::new(HashMap::new()),
             audit_log: Mutex::new(Vec::new()),
+            user_directory: RwLock::new(None),
         }
     }
 
+    /// Attach the user directory consulted for approver and tool-access checks.
+    pub fn set_user_directory(&self, directory: Option<Arc<UserDirectory>>) {
+        *self.user_directory.write() = directory;
+    }
+
+    /// Snapshot the attached user directory, if any.
+    pub fn user_directory(&self) -> Option<Arc<UserDirectory>> {
+        self.user_directory.read().clone()
+    }
+
     /// Check whether a tool call requires interactive approval.
     ///
     /// Returns `true` if the call needs a prompt, `false` if it can proceed.
@@ -305,29 +321,16 @@ impl ApprovalManager {
     /// Check whether `sender` on `channel` may manage non-CLI approvals.
     ///
     /// If no approver entries are configured, this defaults to `true` so
-    /// existing setups continue to behave as before.
+    /// existing setups continue to behave as before. When a user directory is
+    /// attached, `user:<id>` and `role:<name>` entries match senders linked to
+    /// that user or holding that role.
     pub fn is_non_cli_approval_actor_allowed(&self, channel: &str, sender: &str) -> bool {
         let approvers = self.non_cli_approval_approvers.read();
         if approvers.is_empty() {
             return true;
         }
-
-        if approvers.contains("*") || approvers.contains(sender) {
-            return true;
-        }
-
-        let exact = format!("{channel}:{sender}");
-        if approvers.contains(&exact) {
-            return true;
-        }
-
-        let any_on_channel = format!("{channel}:*");
-        if approvers.contains(&any_on_channel) {
-            return true;
-        }
-
-        let sender_any_channel = format!("*:{sender}");
-        approvers.contains(&sender_any_channel)
+        let directory = self.user_directory();
+        approver_entries_match(&approvers, channel, sender, directory.as_deref())
     }
 
     /// Apply runtime + persisted approval grant semantics:
@@ -612,6 +615,46 @@ fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
     }
 }
 
+/// Check a sender against approver entries (`*`, `<sender>`, `<channel>:<sender>`,
+/// `<channel>:*`, `*:<sender>`, and with a directory `user:<id>` / `role:<name>`).
+pub(crate) fn approver_entries_match(
+    approvers: &HashSet<String>,
+    channel: &str,
+    sender: &str,
+    directory: Option<&UserDirectory>,
+) -> bool {
+    if approvers.contains("*") || approvers.contains(sender) {
+        return true;
+    }
+
+    let exact = format!("{channel}:{sender}");
+    if approvers.contains(&exact) {
+        return true;
+    }
+
+    let any_on_channel = format!("{channel}:*");
+    if approvers.contains(&any_on_channel) {
+        return true;
+    }
+
+    let sender_any_channel = format!("*:{sender}");
+    if approvers.contains(&sender_any_channel) {
+        return true;
+    }
+
+    let Some(directory) = directory else {
+        return false;
+    };
+    if let Some(user) = directory.resolve(channel, sender) {
+        if approvers.contains(&format!("user:{}", user.id)) {
+            return true;
+        }
+    }
+    directory
+        .role_for(channel, sender)
+        .is_some_and(|role| approvers.contains(&format!("role:{role}")))
+}
+
 /// Produce a short human-readable summary of tool arguments.
 fn summarize_args(args: &serde_json::Value) -> String {
     match args {
@@ -981,6 +1024,31 @@ mod tests {
         assert!(!mgr.is_non_cli_approval_actor_allowed("matrix", "bob"));
     }
 
+    #[test]
+    fn non_cli_approval_actor_allowlist_resolves_directory_users_and_roles() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut security = crate::config::SecurityConfig::default();
+        security.users.enabled = true;
+        security.users.state_file = dir.path().join("users.json").display().to_string();
+        let directory = UserDirectory::load(&security, dir.path()).unwrap();
+        directory.add_user("alice", "Alice", "viewer").unwrap();
+        directory.link_identity("alice", "slack", "U01").unwrap();
+        directory.add_user("bob", "Bob", "admin").unwrap();
+        directory
+            .link_identity("bob", "email", "bob@example.com")
+            .unwrap();
+
+        let mut cfg = supervised_config();
+        cfg.non_cli_approval_approvers = vec!["user:alice".to_string(), "role:admin".to_string()];
+        let mgr = ApprovalManager::from_config(&cfg);
+        assert!(!mgr.is_non_cli_approval_actor_allowed("slack", "U01"));
+
+        mgr.set_user_directory(Some(Arc::new(directory)));
+        assert!(mgr.is_non_cli_approval_actor_allowed("slack", "U01"));
+        assert!(mgr.is_non_cli_approval_actor_allowed("email", "bob@example.com"));
+        assert!(!mgr.is_non_cli_approval_actor_allowed("telegram", "U01"));
+    }
+
     #[test]
     fn non_cli_natural_language_approval_mode_honors_config_override() {
         let mut cfg = supervised_config();
@@ -0,0 +1,1013 @@
+//! Multi-approver quorum for destructive operations.
+//!
+//! Rules from `[security.approval_quorum]` require a number of distinct
+//! approvers from an approver group before a matching tool call (or
+//! `zeroclaw estop resume`) may run. Requests are persisted as JSON so the CLI
+//! and a running daemon share them, and every request, approval, denial and
+//! use is written to the audit log.
+
+use super::{approver_entries_match, summarize_args};
+use crate::config::{ApprovalQuorumRuleConfig, Config, OtpConfig};
+use crate::security::{
+    AuditEvent, AuditEventType, AuditLogger, CommandRiskLevel, OtpValidator, SecretStore,
+    SecurityPolicy, UserDirectory,
+};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
+/// Operation name used by quorum rules that gate `zeroclaw estop resume`.
+pub const ESTOP_RESUME_OPERATION: &str = "estop_resume";
+
+const REQUEST_ID_PREFIX: &str = "qrm-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuorumStatus {
+    Pending,
+    Approved,
+    Denied,
+    Consumed,
+}
+
+impl QuorumStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Denied => "denied",
+            Self::Consumed => "consumed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuorumApproval {
+    /// Distinct approver key: `user:<id>` for directory users, otherwise
+    /// `<channel>:<sender>`.
+    pub approver: String,
+    pub identity: String,
+    pub otp_verified: bool,
+    pub approved_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuorumRequest {
+    pub id: String,
+    pub rule: String,
+    pub operation: String,
+    pub summary: String,
+    fingerprint: String,
+    pub requested_by: String,
+    pub required: usize,
+    pub require_otp: bool,
+    #[serde(default)]
+    pub approvals: Vec<QuorumApproval>,
+    #[serde(default)]
+    pub denied_by: Option<String>,
+    pub status: QuorumStatus,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+impl QuorumRequest {
+    pub fn is_expired(&self) -> bool {
+        is_expired(&self.expires_at)
+    }
+
+    /// Approvals still missing before the request is approved.
+    pub fn remaining(&self) -> usize {
+        self.required.saturating_sub(self.approvals.len())
+    }
+}
+
+/// Result of checking an operation against the quorum rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuorumDecision {
+    /// No rule covers the operation.
+    NotRequired,
+    /// An approved request matched and has now been used up.
+    Authorized(QuorumRequest),
+    /// A request is open and still waiting for approvals.
+    Pending(QuorumRequest),
+}
+
+#[derive(Debug)]
+struct QuorumRule {
+    name: String,
+    operations: Vec<String>,
+    min_command_risk: Option<CommandRiskLevel>,
+    when_argument: Option<String>,
+    approvals: usize,
+    approvers: HashSet<String>,
+    require_otp: bool,
+    expiry: Duration,
+}
+
+impl QuorumRule {
+    fn from_config(config: &ApprovalQuorumRuleConfig) -> Self {
+        let expiry_secs = i64::try_from(config.expiry_secs.max(1)).unwrap_or(i64::MAX);
+        Self {
+            name: config.name.trim().to_string(),
+            operations: config
+                .operations
+                .iter()
+                .map(|op| op.trim().to_ascii_lowercase())
+                .filter(|op| !op.is_empty())
+                .collect(),
+            min_command_risk: config.min_command_risk,
+            when_argument: config
+                .when_argument
+                .as_deref()
+                .map(str::trim)
+                .filter(|arg| !arg.is_empty())
+                .map(str::to_string),
+            approvals: config.approvals.max(1),
+            approvers: config
+                .approvers
+                .iter()
+                .map(|entry| entry.trim().to_string())
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+            require_otp: config.require_otp,
+            expiry: Duration::seconds(expiry_secs),
+        }
+    }
+
+    fn matches(&self, operation: &str, args: &serde_json::Value, policy: &SecurityPolicy) -> bool {
+        let operation = operation.to_ascii_lowercase();
+        if !self
+            .operations
+            .iter()
+            .any(|op| op == "*" || *op == operation)
+        {
+            return false;
+        }
+
+        if let Some(argument) = self.when_argument.as_deref() {
+            let present = args.get(argument).is_some_and(|value| match value {
+                serde_json::Value::Null => false,
+                serde_json::Value::Bool(flag) => *flag,
+                serde_json::Value::String(text) => !text.trim().is_empty(),
+                _ => true,
+            });
+            if !present {
+                return false;
+            }
+        }
+
+        if let Some(threshold) = self.min_command_risk {
+            let Some(command) = args.get("command").and_then(serde_json::Value::as_str) else {
+                return false;
+            };
+            if policy.command_risk_level(command) < threshold {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct QuorumState {
+    #[serde(default)]
+    requests: Vec<QuorumRequest>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+#[derive(Debug)]
+struct StoreInner {
+    state: QuorumState,
+    stamp: Option<FileStamp>,
+}
+
+/// Lazily constructed OTP validator; built on first use so startup keeps
+/// printing the enrollment URI for a freshly generated secret.
+struct OtpSource {
+    config: OtpConfig,
+    config_dir: PathBuf,
+    encrypt: bool,
+    validator: OnceLock<OtpValidator>,
+}
+
+impl OtpSource {
+    fn validate(&self, code: &str) -> Result<bool> {
+        if let Some(validator) = self.validator.get() {
+            return validator.validate(code);
+        }
+        let store = SecretStore::new(&self.config_dir, self.encrypt);
+        let (validator, _) = OtpValidator::from_config(&self.config, &self.config_dir, &store)?;
+        self.validator.get_or_init(|| validator).validate(code)
+    }
+}
+
+pub struct QuorumStore {
+    state_path: PathBuf,
+    rules: Vec<QuorumRule>,
+    /// Policy from `[autonomy]`, used to classify shell command risk.
+    policy: SecurityPolicy,
+    user_directory: Option<Arc<UserDirectory>>,
+    audit: Option<AuditLogger>,
+    otp: Option<OtpSource>,
+    inner: Mutex<StoreInner>,
+}
+
+impl QuorumStore {
+    pub fn load(config: &Config) -> Result<Self> {
+        let config_dir = config
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        let quorum = &config.security.approval_quorum;
+        let state_path =
+            crate::security::estop::resolve_state_file_path(config_dir, &quorum.state_file);
+
+        let user_directory = if config.security.users.enabled {
+            Some(Arc::new(
+                UserDirectory::load(&config.security, config_dir)
+                    .context("Failed to load [security.users] directory for approval quorum")?,
+            ))
+        } else {
+            None
+        };
+
+        let audit = if config.security.audit.enabled {
+            Some(AuditLogger::new(
+                config.security.audit.clone(),
+                config_dir.to_path_buf(),
+            )?)
+        } else {
+            None
+        };
+
+        let otp = config.security.otp.enabled.then(|| OtpSource {
+            config: config.security.otp.clone(),
+            config_dir: config_dir.to_path_buf(),
+            encrypt: config.secrets.encrypt,
+            validator: OnceLock::new(),
+        });
+
+        let (state, stamp) = read_state(&state_path)?;
+        Ok(Self {
+            state_path,
+            rules: quorum.rules.iter().map(QuorumRule::from_config).collect(),
+            policy: SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir),
+            user_directory,
+            audit,
+            otp,
+            inner: Mutex::new(StoreInner { state, stamp }),
+        })
+    }
+
+    pub fn state_path(&self) -> &Path {
+        &self.state_path
+    }
+
+    /// Name of the first rule covering `operation` with these arguments.
+    pub fn rule_for(&self, operation: &str, args: &serde_json::Value) -> Option<&str> {
+        self.find_rule(operation, args)
+            .map(|rule| rule.name.as_str())
+    }
+
+    /// Check `operation` against the quorum rules.
+    ///
+    /// An approved request for the same operation and arguments is consumed
+    /// and returned as [`QuorumDecision::Authorized`]. Otherwise the open
+    /// request for this call is returned, creating one if needed.
+    pub fn authorize(
+        &self,
+        operation: &str,
+        args: &serde_json::Value,
+        requested_by: &str,
+    ) -> Result<QuorumDecision> {
+        let Some(rule) = self.find_rule(operation, args) else {
+            return Ok(QuorumDecision::NotRequired);
+        };
+        let fingerprint = fingerprint(operation, args);
+
+        let (decision, stage) = self.mutate(|state| {
+            let live = |request: &&mut QuorumRequest| {
+                request.fingerprint == fingerprint && !request.is_expired()
+            };
+            if let Some(request) = state
+                .requests
+                .iter_mut()
+                .filter(live)
+                .find(|request| request.status == QuorumStatus::Approved)
+            {
+                request.status = QuorumStatus::Consumed;
+                return Ok((QuorumDecision::Authorized(request.clone()), "consumed"));
+            }
+            if let Some(request) = state
+                .requests
+                .iter_mut()
+                .filter(live)
+                .find(|request| request.status == QuorumStatus::Pending)
+            {
+                return Ok((QuorumDecision::Pending(request.clone()), "reused"));
+            }
+
+            let now = Utc::now();
+            let request = QuorumRequest {
+                id: format!(
+                    "{REQUEST_ID_PREFIX}{}",
+                    &uuid::Uuid::new_v4().simple().to_string()[..8]
+                ),
+                rule: rule.name.clone(),
+                operation: operation.to_string(),
+                summary: summarize_args(args),
+                fingerprint: fingerprint.clone(),
+                requested_by: requested_by.to_string(),
+                required: rule.approvals,
+                require_otp: rule.require_otp,
+                approvals: Vec::new(),
+                denied_by: None,
+                status: QuorumStatus::Pending,
+                created_at: now.to_rfc3339(),
+                expires_at: (now + rule.expiry).to_rfc3339(),
+            };
+            state.requests.push(request.clone());
+            Ok((QuorumDecision::Pending(request), "requested"))
+        })?;
+
+        match &decision {
+            QuorumDecision::Authorized(request) => {
+                self.audit(stage, request, None, true);
+            }
+            QuorumDecision::Pending(request) if stage == "requested" => {
+                self.audit(stage, request, None, false);
+            }
+            _ => {}
+        }
+        Ok(decision)
+    }
+
+    /// Record an approval from `channel:sender`, verifying `otp` when the
+    /// rule requires it. Returns the updated request.
+    pub fn approve(
+        &self,
+        request_id: &str,
+        channel: &str,
+        sender: &str,
+        otp: Option<&str>,
+    ) -> Result<QuorumRequest> {
+        let request_id = request_id.trim();
+        let approver = self.approver_key(channel, sender);
+        let result = self.mutate(|state| {
+            let request = find_request_mut(state, request_id)?;
+            ensure_pending(request)?;
+            let rule = self.rule_named(&request.rule)?;
+            if !approver_entries_match(
+                &rule.approvers,
+                channel,
+                sender,
+                self.user_directory.as_deref(),
+            ) {
+                bail!(
+                    "`{channel}:{sender}` is not an approver for quorum rule '{}'",
+                    rule.name
+                );
+            }
+            if request
+                .approvals
+                .iter()
+                .any(|approval| approval.approver == approver)
+            {
+                bail!("`{approver}` has already approved request `{request_id}`");
+            }
+
+            let otp_verified = if request.require_otp {
+                let Some(code) = otp.map(str::trim).filter(|code| !code.is_empty()) else {
+                    bail!(
+                        "Quorum rule '{}' requires an OTP code with each approval",
+                        rule.name
+                    );
+                };
+                let Some(source) = self.otp.as_ref() else {
+                    bail!(
+                        "Quorum rule '{}' requires OTP but security.otp is disabled",
+                        rule.name
+                    );
+                };
+                if !source.validate(code)? {
+                    bail!("Invalid or already used OTP code");
+                }
+                true
+            } else {
+                false
+            };
+
+            request.approvals.push(QuorumApproval {
+                approver: approver.clone(),
+                identity: format!("{channel}:{sender}"),
+                otp_verified,
+                approved_at: Utc::now().to_rfc3339(),
+            });
+            if request.approvals.len() >= request.required {
+                request.status = QuorumStatus::Approved;
+            }
+            Ok(request.clone())
+        });
+
+        self.audit_outcome("approve", request_id, channel, sender, &result);
+        result
+    }
+
+    /// Deny a pending request on behalf of `channel:sender`.
+    pub fn deny(&self, request_id: &str, channel: &str, sender: &str) -> Result<QuorumRequest> {
+        let request_id = request_id.trim();
+        let approver = self.approver_key(channel, sender);
+        let result = self.mutate(|state| {
+            let request = find_request_mut(state, request_id)?;
+            ensure_pending(request)?;
+            let rule = self.rule_named(&request.rule)?;
+            if !approver_entries_match(
+                &rule.approvers,
+                channel,
+                sender,
+                self.user_directory.as_deref(),
+            ) {
+                bail!(
+                    "`{channel}:{sender}` is not an approver for quorum rule '{}'",
+                    rule.name
+                );
+            }
+            request.status = QuorumStatus::Denied;
+            request.denied_by = Some(approver.clone());
+            Ok(request.clone())
+        });
+
+        self.audit_outcome("deny", request_id, channel, sender, &result);
+        result
+    }
+
+    pub fn get(&self, request_id: &str) -> Option<QuorumRequest> {
+        let request_id = request_id.trim();
+        self.with_state(|state| {
+            state
+                .requests
+                .iter()
+                .find(|request| request.id == request_id)
+                .cloned()
+        })
+    }
+
+    /// Open requests that have not expired, oldest first.
+    pub fn pending(&self) -> Vec<QuorumRequest> {
+        self.with_state(|state| {
+            state
+                .requests
+                .iter()
+                .filter(|request| request.status == QuorumStatus::Pending && !request.is_expired())
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Whether `channel:sender` belongs to any rule's approver group.
+    pub fn is_approver(&self, channel: &str, sender: &str) -> bool {
+        self.rules.iter().any(|rule| {
+            approver_entries_match(
+                &rule.approvers,
+                channel,
+                sender,
+                self.user_directory.as_deref(),
+            )
+        })
+    }
+
+    fn find_rule(&self, operation: &str, args: &serde_json::Value) -> Option<&QuorumRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(operation, args, &self.policy))
+    }
+
+    fn rule_named(&self, name: &str) -> Result<&QuorumRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.name == name)
+            .with_context(|| format!("Quorum rule '{name}' is no longer configured"))
+    }
+
+    fn approver_key(&self, channel: &str, sender: &str) -> String {
+        self.user_directory
+            .as_ref()
+            .and_then(|directory| directory.resolve(channel, sender))
+            .map_or_else(
+                || format!("{channel}:{sender}"),
+                |user| format!("user:{}", user.id),
+            )
+    }
+
+    fn audit_outcome(
+        &self,
+        stage: &str,
+        request_id: &str,
+        channel: &str,
+        sender: &str,
+        result: &Result<QuorumRequest>,
+    ) {
+        match result {
+            Ok(request) => {
+                self.audit(stage, request, Some((channel, sender)), true);
+                if request.status == QuorumStatus::Approved {
+                    self.audit("approved", request, None, true);
+                }
+            }
+            Err(error) => {
+                tracing::warn!(
+                    request_id,
+                    channel,
+                    sender,
+                    "approval quorum {stage} rejected: {error}"
+                );
+                if let Some(request) = self.get(request_id) {
+                    self.audit(
+                        &format!("{stage}_rejected"),
+                        &request,
+                        Some((channel, sender)),
+                        false,
+                    );
+                }
+            }
+        }
+    }
+
+    fn audit(
+        &self,
+        stage: &str,
+        request: &QuorumRequest,
+        actor: Option<(&str, &str)>,
+        allowed: bool,
+    ) {
+        tracing::info!(
+            request_id = %request.id,
+            rule = %request.rule,
+            operation = %request.operation,
+            status = request.status.as_str(),
+            approvals = request.approvals.len(),
+            required = request.required,
+            "approval quorum {stage}"
+        );
+        let Some(logger) = self.audit.as_ref() else {
+            return;
+        };
+
+        let (channel, user_id, username) = match actor {
+            Some((channel, sender)) => (
+                channel.to_string(),
+                self.user_directory
+                    .as_ref()
+                    .and_then(|directory| directory.resolve(channel, sender))
+                    .map(|user| user.id),
+                Some(sender.to_string()),
+            ),
+            None => (request.requested_by.clone(), None, None),
+        };
+        let approvers: Vec<&str> = request
+            .approvals
+            .iter()
+            .map(|approval| approval.approver.as_str())
+            .collect();
+        let event = AuditEvent::new(AuditEventType::ApprovalDecision)
+            .with_actor(channel, user_id, username)
+            .with_action(
+                format!(
+                    "approval_quorum {stage} {} rule={} operation={} approvals={}/{} approvers=[{}]: {}",
+                    request.id,
+                    request.rule,
+                    request.operation,
+                    request.approvals.len(),
+                    request.required,
+                    approvers.join(","),
+                    request.summary
+                ),
+                "high".to_string(),
+                matches!(
+                    request.status,
+                    QuorumStatus::Approved | QuorumStatus::Consumed
+                ),
+                allowed,
+            );
+        if let Err(error) = logger.log(&event) {
+            tracing::warn!("Failed to write approval quorum audit event: {error}");
+        }
+    }
+
+    fn with_state<T>(&self, read: impl FnOnce(&QuorumState) -> T) -> T {
+        let mut inner = self.inner.lock();
+        self.refresh(&mut inner);
+        read(&inner.state)
+    }
+
+    fn mutate<T>(&self, update: impl FnOnce(&mut QuorumState) -> Result<T>) -> Result<T> {
+        let mut inner = self.inner.lock();
+        self.refresh(&mut inner);
+        let mut next = inner.state.clone();
+        next.requests.retain(|request| !request.is_expired());
+        let result = update(&mut next)?;
+        persist_state(&self.state_path, &next)?;
+        inner.state = next;
+        inner.stamp = file_stamp(&self.state_path);
+        Ok(result)
+    }
+
+    fn refresh(&self, inner: &mut StoreInner) {
+        let stamp = file_stamp(&self.state_path);
+        if stamp == inner.stamp {
+            return;
+        }
+        match read_state(&self.state_path) {
+            Ok((state, stamp)) => {
+                inner.state = state;
+                inner.stamp = stamp;
+            }
+            Err(error) => {
+                tracing::warn!(
+                    path = %self.state_path.display(),
+                    "Failed to reload approval quorum state; keeping previous state: {error}"
+                );
+            }
+        }
+    }
+}
+
+fn runtime() -> &'static RwLock<Option<Arc<QuorumStore>>> {
+    static RUNTIME: OnceLock<RwLock<Option<Arc<QuorumStore>>>> = OnceLock::new();
+    RUNTIME.get_or_init(|| RwLock::new(None))
+}
+
+/// Install (or clear, when disabled) the process-wide quorum store.
+pub fn install_runtime(config: &Config) -> Result<()> {
+    let store = if config.security.approval_quorum.enabled {
+        Some(Arc::new(
+            QuorumStore::load(config).context("Failed to load [security.approval_quorum]")?,
+        ))
+    } else {
+        None
+    };
+    *runtime().write() = store;
+    Ok(())
+}
+
+/// The installed quorum store, if `[security.approval_quorum]` is enabled.
+pub fn runtime_store() -> Option<Arc<QuorumStore>> {
+    runtime().read().clone()
+}
+
+/// Human-readable status line for a request.
+pub fn describe_request(request: &QuorumRequest) -> String {
+    let approvers: Vec<&str> = request
+        .approvals
+        .iter()
+        .map(|approval| approval.approver.as_str())
+        .collect();
+    format!(
+        "`{}` [{}] rule `{}`: `{}` ({}) — {}/{} approvals{}{}, requested by `{}`, expires {}",
+        request.id,
+        request.status.as_str(),
+        request.rule,
+        request.operation,
+        request.summary,
+        request.approvals.len(),
+        request.required,
+        if approvers.is_empty() {
+            String::new()
+        } else {
+            format!(" from {}", approvers.join(", "))
+        },
+        if request.require_otp {
+            ", OTP required"
+        } else {
+            ""
+        },
+        request.requested_by,
+        request.expires_at
+    )
+}
+
+fn fingerprint(operation: &str, args: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(operation.to_ascii_lowercase().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(args.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn find_request_mut<'a>(
+    state: &'a mut QuorumState,
+    request_id: &str,
+) -> Result<&'a mut QuorumRequest> {
+    state
+        .requests
+        .iter_mut()
+        .find(|request| request.id == request_id)
+        .with_context(|| format!("No quorum request `{request_id}` (it may have expired)"))
+}
+
+fn ensure_pending(request: &QuorumRequest) -> Result<()> {
+    if request.status != QuorumStatus::Pending {
+        bail!(
+            "Quorum request `{}` is already {}",
+            request.id,
+            request.status.as_str()
+        );
+    }
+    Ok(())
+}
+
+fn is_expired(expires_at: &str) -> bool {
+    DateTime::parse_from_rfc3339(expires_at)
+        .map(|at| at.with_timezone(&Utc) <= Utc::now())
+        .unwrap_or(true)
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+fn read_state(path: &Path) -> Result<(QuorumState, Option<FileStamp>)> {
+    if !path.exists() {
+        return Ok((QuorumState::default(), None));
+    }
+    let stamp = file_stamp(path);
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read approval quorum state {}", path.display()))?;
+    let state = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse approval quorum state {}", path.display()))?;
+    Ok((state, stamp))
+}
+
+fn persist_state(path: &Path, state: &QuorumState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "Failed to create approval quorum state dir {}",
+                parent.display()
+            )
+        })?;
+    }
+
+    let body =
+        serde_json::to_string_pretty(state).context("Failed to serialize approval quorum state")?;
+    let temp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    fs::write(&temp_path, body).with_context(|| {
+        format!(
+            "Failed to write temporary approval quorum state file {}",
+            temp_path.display()
+        )
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600));
+    }
+
+    fs::rename(&temp_path, path).with_context(|| {
+        format!(
+            "Failed to atomically replace approval quorum state file {}",
+            path.display()
+        )
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApprovalQuorumConfig;
+    use tempfile::{tempdir, TempDir};
+
+    fn rule(name: &str, operations: &[&str]) -> ApprovalQuorumRuleConfig {
+        ApprovalQuorumRuleConfig {
+            name: name.into(),
+            operations: operations.iter().map(|op| (*op).to_string()).collect(),
+            min_command_risk: None,
+            when_argument: None,
+            approvals: 2,
+            approvers: vec![
+                "telegram:alice".into(),
+                "telegram:bob".into(),
+                "slack:*".into(),
+            ],
+            require_otp: false,
+            expiry_secs: 900,
+        }
+    }
+
+    fn store_with(rules: Vec<ApprovalQuorumRuleConfig>) -> (TempDir, QuorumStore) {
+        let dir = tempdir().unwrap();
+        let mut config = Config::default();
+        config.config_path = dir.path().join("config.toml");
+        config.security.approval_quorum = ApprovalQuorumConfig {
+            enabled: true,
+            state_file: dir.path().join("quorum.json").display().to_string(),
+            rules,
+        };
+        let store = QuorumStore::load(&config).unwrap();
+        (dir, store)
+    }
+
+    fn pending(decision: QuorumDecision) -> QuorumRequest {
+        match decision {
+            QuorumDecision::Pending(request) => request,
+            other => panic!("expected pending request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn high_risk_shell_requires_distinct_approvers_before_running() {
+        let mut shell = rule("destructive-shell", &["shell"]);
+        shell.min_command_risk = Some(CommandRiskLevel::High);
+        let (_dir, store) = store_with(vec![shell]);
+
+        let low = serde_json::json!({"command": "ls -la"});
+        assert_eq!(
+            store.authorize("shell", &low, "telegram").unwrap(),
+            QuorumDecision::NotRequired
+        );
+
+        let args = serde_json::json!({"command": "rm -rf /srv/data"});
+        let request = pending(store.authorize("shell", &args, "telegram").unwrap());
+        assert_eq!(request.required, 2);
+
+        store
+            .approve(&request.id, "telegram", "alice", None)
+            .unwrap();
+        let err = store
+            .approve(&request.id, "telegram", "alice", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("already approved"));
+        let again = pending(store.authorize("shell", &args, "telegram").unwrap());
+        assert_eq!(again.id, request.id);
+        assert_eq!(again.remaining(), 1);
+
+        let approved = store.approve(&request.id, "slack", "U123", None).unwrap();
+        assert_eq!(approved.status, QuorumStatus::Approved);
+
+        match store.authorize("shell", &args, "telegram").unwrap() {
+            QuorumDecision::Authorized(used) => assert_eq!(used.id, request.id),
+            other => panic!("expected authorized, got {other:?}"),
+        }
+        // Approvals are single-use: the next identical call opens a new request.
+        let next = pending(store.authorize("shell", &args, "telegram").unwrap());
+        assert_ne!(next.id, request.id);
+    }
+
+    #[test]
+    fn approvals_are_bound_to_the_exact_arguments() {
+        let (_dir, store) = store_with(vec![rule("patches", &["apply_patch"])]);
+        let args = serde_json::json!({"patch": "a", "commit_message": "fix"});
+        let request = pending(store.authorize("apply_patch", &args, "cli").unwrap());
+        store
+            .approve(&request.id, "telegram", "alice", None)
+            .unwrap();
+        store.approve(&request.id, "telegram", "bob", None).unwrap();
+
+        let other = serde_json::json!({"patch": "b", "commit_message": "fix"});
+        assert!(matches!(
+            store.authorize("apply_patch", &other, "cli").unwrap(),
+            QuorumDecision::Pending(_)
+        ));
+        assert!(matches!(
+            store.authorize("apply_patch", &args, "cli").unwrap(),
+            QuorumDecision::Authorized(_)
+        ));
+    }
+
+    #[test]
+    fn when_argument_limits_rule_to_calls_using_it() {
+        let mut patches = rule("patch-commits", &["apply_patch"]);
+        patches.when_argument = Some("commit_message".into());
+        let (_dir, store) = store_with(vec![patches]);
+
+        let no_commit = serde_json::json!({"patch": "a"});
+        assert_eq!(store.rule_for("apply_patch", &no_commit), None);
+        let commit = serde_json::json!({"patch": "a", "commit_message": "ship"});
+        assert_eq!(
+            store.rule_for("apply_patch", &commit),
+            Some("patch-commits")
+        );
+    }
+
+    #[test]
+    fn non_members_cannot_approve_and_denial_closes_request() {
+        let (_dir, store) = store_with(vec![rule("estop", &[ESTOP_RESUME_OPERATION])]);
+        let args = serde_json::json!({"network": true});
+        let request = pending(
+            store
+                .authorize(ESTOP_RESUME_OPERATION, &args, "cli")
+                .unwrap(),
+        );
+
+        let err = store
+            .approve(&request.id, "telegram", "mallory", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("not an approver"));
+        assert!(!store.is_approver("telegram", "mallory"));
+        assert!(store.is_approver("slack", "anyone"));
+
+        let denied = store.deny(&request.id, "telegram", "bob").unwrap();
+        assert_eq!(denied.status, QuorumStatus::Denied);
+        assert!(store.pending().is_empty());
+        let err = store
+            .approve(&request.id, "telegram", "alice", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("already denied"));
+    }
+
+    #[test]
+    fn state_is_shared_through_the_state_file() {
+        let (dir, store) = store_with(vec![rule("estop", &[ESTOP_RESUME_OPERATION])]);
+        let args = serde_json::json!({"network": true});
+        let request = pending(
+            store
+                .authorize(ESTOP_RESUME_OPERATION, &args, "cli")
+                .unwrap(),
+        );
+
+        let mut config = Config::default();
+        config.config_path = dir.path().join("config.toml");
+        config.security.approval_quorum = ApprovalQuorumConfig {
+            enabled: true,
+            state_file: store.state_path().display().to_string(),
+            rules: vec![rule("estop", &[ESTOP_RESUME_OPERATION])],
+        };
+        let daemon = QuorumStore::load(&config).unwrap();
+        daemon
+            .approve(&request.id, "telegram", "alice", None)
+            .unwrap();
+        daemon
+            .approve(&request.id, "telegram", "bob", None)
+            .unwrap();
+
+        assert_eq!(
+            store.get(&request.id).map(|request| request.status),
+            Some(QuorumStatus::Approved)
+        );
+    }
+
+    #[test]
+    fn otp_rules_require_a_valid_code_per_approver() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::default();
+        config.config_path = dir.path().join("config.toml");
+        config.security.otp.enabled = true;
+        let mut estop = rule("estop", &[ESTOP_RESUME_OPERATION]);
+        estop.require_otp = true;
+        estop.approvals = 1;
+        config.security.approval_quorum = ApprovalQuorumConfig {
+            enabled: true,
+            state_file: dir.path().join("quorum.json").display().to_string(),
+            rules: vec![estop],
+        };
+        let store = QuorumStore::load(&config).unwrap();
+        let request = pending(
+            store
+                .authorize(ESTOP_RESUME_OPERATION, &serde_json::json!({}), "cli")
+                .unwrap(),
+        );
+
+        let err = store
+            .approve(&request.id, "telegram", "alice", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("requires an OTP code"));
+        let err = store
+            .approve(&request.id, "telegram", "alice", Some("000000"))
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid"));
+
+        let secret_store = SecretStore::new(dir.path(), config.secrets.encrypt);
+        let (validator, _) =
+            OtpValidator::from_config(&config.security.otp, dir.path(), &secret_store).unwrap();
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let code = validator.code_for_timestamp(now);
+        let approved = store
+            .approve(&request.id, "telegram", "alice", Some(&code))
+            .unwrap();
+        assert_eq!(approved.status, QuorumStatus::Approved);
+        assert!(approved.approvals[0].otp_verified);
+    }
+}
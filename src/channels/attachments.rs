@@ -0,0 +1,614 @@
+//! Typed attachment helpers shared by all channels.
+//!
+//! Inbound: channels put [`Attachment`]s on [`ChannelMessage::attachments`]
+//! (local files they already downloaded, or remote URLs with auth headers).
+//! [`materialize_inbound`] fetches remote ones through the shared download
+//! cache at `<workspace>/state/attachment_cache` and appends a reference to
+//! the message content so the agent can open the file.
+//!
+//! Outbound: [`deliver_reply`] turns `[IMAGE:..]` / `[DOCUMENT:..]` markers in
+//! an agent reply into typed attachments for channels that report
+//! [`Channel::attachment_limits`], and [`deliver`] sends the text followed by
+//! each attachment through [`Channel::send_attachment`]. Attachments a channel
+//! cannot take (unsupported kind, too large, upload failure) are reported back
+//! to the recipient as a short note instead of being dropped silently.
+
+use super::traits::{
+    Attachment, AttachmentKind, AttachmentLimits, AttachmentSource, Channel, ChannelMessage,
+    SendMessage,
+};
+use anyhow::{bail, Context, Result};
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Directory under the workspace holding downloaded inbound attachments.
+pub const CACHE_DIR: &str = "state/attachment_cache";
+
+const DOWNLOAD_TIMEOUT_SECS: u64 = 120;
+const MAX_CACHE_FILENAME_LEN: usize = 96;
+
+/// Download cache for remote attachments, keyed by URL.
+#[derive(Debug)]
+pub struct AttachmentCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl AttachmentCache {
+    pub fn new(workspace_dir: &Path, max_bytes: u64) -> Self {
+        Self {
+            dir: workspace_dir.join(CACHE_DIR),
+            max_bytes,
+        }
+    }
+
+    /// Local path for `attachment`, downloading it first when it is remote.
+    ///
+    /// Repeated fetches of the same URL reuse the cached file.
+    pub async fn fetch(&self, attachment: &Attachment) -> Result<PathBuf> {
+        let (url, headers) = match &attachment.source {
+            AttachmentSource::Local(path) => return Ok(path.clone()),
+            AttachmentSource::Remote { url, headers } => (url, headers),
+        };
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            bail!("Unsupported attachment URL scheme: {url}");
+        }
+        if let Some(size) = attachment.size {
+            if size > self.max_bytes {
+                bail!(
+                    "Attachment {} is {size} bytes; download limit is {} bytes",
+                    attachment.display_name(),
+                    self.max_bytes
+                );
+            }
+        }
+
+        let path = self.cache_path(url, attachment.filename.as_deref());
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            "channel.attachments",
+            DOWNLOAD_TIMEOUT_SECS,
+            10,
+        );
+        let mut request = client.get(url);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", attachment.display_name()))?;
+        if !response.status().is_success() {
+            bail!(
+                "Downloading {} failed with status {}",
+                attachment.display_name(),
+                response.status()
+            );
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_bytes)
+        {
+            bail!(
+                "Attachment {} exceeds the {} byte download limit",
+                attachment.display_name(),
+                self.max_bytes
+            );
+        }
+
+        let mut bytes = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if (bytes.len() + chunk.len()) as u64 > self.max_bytes {
+                bail!(
+                    "Attachment {} exceeds the {} byte download limit",
+                    attachment.display_name(),
+                    self.max_bytes
+                );
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        // Write to a temp name first so a concurrent reader never sees a
+        // partially written file under the final name.
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, &bytes)
+            .await
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(path)
+    }
+
+    fn cache_path(&self, url: &str, filename: Option<&str>) -> PathBuf {
+        let digest = hex::encode(Sha256::digest(url.as_bytes()));
+        let name = filename
+            .or_else(|| {
+                url.split(['?', '#'])
+                    .next()
+                    .and_then(|base| base.rsplit('/').next())
+            })
+            .unwrap_or_default();
+        let mut safe: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_CACHE_FILENAME_LEN)
+            .collect();
+        if safe.trim_matches('.').is_empty() {
+            safe = "attachment.bin".into();
+        }
+        self.dir.join(format!("{}-{safe}", &digest[..16]))
+    }
+}
+
+static CACHE: LazyLock<RwLock<Option<Arc<AttachmentCache>>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Install the shared download cache.
+pub fn init(workspace_dir: &Path, max_download_mb: u64) {
+    let cache = AttachmentCache::new(workspace_dir, max_download_mb.saturating_mul(1024 * 1024));
+    let mut guard = CACHE.write().unwrap_or_else(|e| e.into_inner());
+    *guard = Some(Arc::new(cache));
+}
+
+/// Shared download cache, if channels have been started.
+pub fn cache() -> Option<Arc<AttachmentCache>> {
+    CACHE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Fetch remote attachments on `msg` into the cache and reference every
+/// attachment the content does not already mention.
+pub async fn materialize_inbound(msg: &mut ChannelMessage) {
+    if msg.attachments.is_empty() {
+        return;
+    }
+    let cache = cache();
+    for attachment in &mut msg.attachments {
+        if msg.content.contains(&reference_target(attachment)) {
+            continue;
+        }
+        if let (Some(cache), AttachmentSource::Remote { .. }) = (&cache, &attachment.source) {
+            match cache.fetch(attachment).await {
+                Ok(path) => attachment.source = AttachmentSource::Local(path),
+                Err(error) => {
+                    tracing::warn!(
+                        channel = %msg.channel,
+                        "Failed to fetch attachment {}: {error:#}",
+                        attachment.display_name()
+                    );
+                }
+            }
+        }
+        let reference = inbound_reference(attachment);
+        if !msg.content.is_empty() {
+            msg.content.push('\n');
+        }
+        msg.content.push_str(&reference);
+    }
+}
+
+fn reference_target(attachment: &Attachment) -> String {
+    match &attachment.source {
+        AttachmentSource::Local(path) => path.display().to_string(),
+        AttachmentSource::Remote { url, .. } => url.clone(),
+    }
+}
+
+/// Content line for an inbound attachment: images use the `[IMAGE:..]`
+/// marker understood by the multimodal pipeline, other files follow the
+/// `[Document: name] path` form Telegram already uses.
+fn inbound_reference(attachment: &Attachment) -> String {
+    let target = reference_target(attachment);
+    if attachment.kind == AttachmentKind::Image {
+        return format!("[IMAGE:{target}]");
+    }
+    let label = match attachment.kind {
+        AttachmentKind::Audio => "Audio",
+        AttachmentKind::Voice => "Voice",
+        AttachmentKind::Video => "Video",
+        AttachmentKind::Image | AttachmentKind::Document => "Document",
+    };
+    let name = attachment
+        .filename
+        .clone()
+        .unwrap_or_else(|| attachment.display_name());
+    format!("[{label}: {name}] {target}")
+}
+
+/// Split `[KIND:target]` markers out of `text`.
+///
+/// Returns the remaining text and one attachment per valid marker; invalid
+/// markers stay in the text. Nested brackets in targets are allowed.
+pub fn parse_markers(text: &str) -> (String, Vec<Attachment>) {
+    let mut cleaned = String::with_capacity(text.len());
+    let mut attachments = Vec::new();
+    let mut cursor = 0;
+
+    while cursor < text.len() {
+        let Some(open_rel) = text[cursor..].find('[') else {
+            cleaned.push_str(&text[cursor..]);
+            break;
+        };
+        let open = cursor + open_rel;
+        cleaned.push_str(&text[cursor..open]);
+
+        let Some(close_rel) = find_matching_close(&text[open + 1..]) else {
+            cleaned.push_str(&text[open..]);
+            break;
+        };
+        let close = open + 1 + close_rel;
+        let marker = &text[open + 1..close];
+
+        let parsed = marker.split_once(':').and_then(|(kind, target)| {
+            let kind = AttachmentKind::from_marker(kind)?;
+            let target = target.trim();
+            if target.is_empty() {
+                return None;
+            }
+            let attachment = if target.starts_with("https://") || target.starts_with("http://") {
+                Attachment::remote(target, None)
+            } else {
+                Attachment::local(target)
+            };
+            Some(attachment.with_kind(kind))
+        });
+
+        match parsed {
+            Some(attachment) => attachments.push(attachment),
+            None => cleaned.push_str(&text[open..=close]),
+        }
+        cursor = close + 1;
+    }
+
+    (cleaned.trim().to_string(), attachments)
+}
+
+fn find_matching_close(s: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Check `attachment` against a channel's limits, returning the reason it
+/// cannot be sent.
+pub fn check_limits(limits: &AttachmentLimits, attachment: &Attachment) -> Result<(), String> {
+    if !limits.kinds.contains(&attachment.kind) {
+        return Err(format!(
+            "{} attachments are not supported here",
+            attachment.kind.marker().to_ascii_lowercase()
+        ));
+    }
+    let size = attachment.size.or_else(|| {
+        attachment
+            .local_path()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len())
+    });
+    if let Some(size) = size {
+        if size > limits.max_bytes {
+            return Err(format!(
+                "file is {} MB; the limit here is {} MB",
+                size.div_ceil(1024 * 1024),
+                limits.max_bytes / (1024 * 1024)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Send `message` text, then each of its attachments.
+///
+/// Channels without attachment support receive the attachments rendered as
+/// markers in the text. Only a failure to send the text is returned as an
+/// error; attachment failures are reported to the recipient.
+pub async fn deliver(channel: &dyn Channel, message: &SendMessage) -> Result<()> {
+    let mut text = message.clone();
+    text.attachments = Vec::new();
+
+    let Some(limits) = channel.attachment_limits() else {
+        for attachment in &message.attachments {
+            if !text.content.is_empty() {
+                text.content.push('\n');
+            }
+            text.content.push_str(&attachment.to_marker());
+        }
+        return channel.send(&text).await;
+    };
+
+    if !text.content.trim().is_empty() {
+        channel.send(&text).await?;
+    }
+
+    let mut failures = Vec::new();
+    for attachment in &message.attachments {
+        let result = match check_limits(&limits, attachment) {
+            Ok(()) => channel
+                .send_attachment(message, attachment)
+                .await
+                .map_err(|error| {
+                    tracing::warn!(
+                        channel = channel.name(),
+                        "Failed to send attachment {}: {error:#}",
+                        attachment.display_name()
+                    );
+                    "upload failed".to_string()
+                }),
+            Err(reason) => Err(reason),
+        };
+        if let Err(reason) = result {
+            failures.push(format!(
+                "Could not send {}: {reason}.",
+                attachment
+                    .filename
+                    .clone()
+                    .unwrap_or_else(|| attachment.display_name())
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        let mut note = SendMessage::new(failures.join("\n"), &message.recipient)
+            .in_thread(message.thread_ts.clone());
+        note.subject.clone_from(&message.subject);
+        channel.send(&note).await?;
+    }
+    Ok(())
+}
+
+/// Deliver an agent reply, lifting attachment markers out of the text for
+/// channels that can send files.
+pub async fn deliver_reply(channel: &dyn Channel, message: SendMessage) -> Result<()> {
+    if channel.attachment_limits().is_none() {
+        return deliver(channel, &message).await;
+    }
+    let (content, mut attachments) = parse_markers(&message.content);
+    if attachments.is_empty() {
+        return deliver(channel, &message).await;
+    }
+    let mut message = message;
+    message.content = content;
+    message.attachments.append(&mut attachments);
+    deliver(channel, &message).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelEvent;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        limits: Option<AttachmentLimits>,
+        texts: Mutex<Vec<String>>,
+        files: Mutex<Vec<Attachment>>,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &SendMessage) -> Result<()> {
+            self.texts.lock().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+
+        fn attachment_limits(&self) -> Option<AttachmentLimits> {
+            self.limits.clone()
+        }
+
+        async fn send_attachment(
+            &self,
+            _message: &SendMessage,
+            attachment: &Attachment,
+        ) -> Result<()> {
+            self.files.lock().push(attachment.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parse_markers_builds_typed_attachments() {
+        let (text, attachments) =
+            parse_markers("Report ready [DOCUMENT:/tmp/report.csv] [IMAGE:https://x.test/a.png]");
+
+        assert_eq!(text, "Report ready");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].kind, AttachmentKind::Document);
+        assert_eq!(
+            attachments[0].local_path(),
+            Some(Path::new("/tmp/report.csv"))
+        );
+        assert_eq!(attachments[0].filename.as_deref(), Some("report.csv"));
+        assert_eq!(attachments[1].kind, AttachmentKind::Image);
+        assert!(matches!(
+            attachments[1].source,
+            AttachmentSource::Remote { .. }
+        ));
+    }
+
+    #[test]
+    fn parse_markers_keeps_unknown_markers() {
+        let (text, attachments) = parse_markers("see [NOTE:x] and [IMAGE:]");
+        assert_eq!(text, "see [NOTE:x] and [IMAGE:]");
+        assert!(attachments.is_empty());
+    }
+
+    #[test]
+    fn check_limits_rejects_kind_and_size() {
+        let limits = AttachmentLimits {
+            max_bytes: 1024 * 1024,
+            kinds: vec![AttachmentKind::Image],
+        };
+        let doc = Attachment::remote("https://x.test/a.pdf", None);
+        assert!(check_limits(&limits, &doc)
+            .unwrap_err()
+            .contains("document attachments"));
+
+        let big = Attachment::remote("https://x.test/a.png", None).with_size(3 * 1024 * 1024);
+        assert!(check_limits(&limits, &big).unwrap_err().contains("3 MB"));
+
+        let small = Attachment::remote("https://x.test/a.png", None).with_size(10);
+        assert!(check_limits(&limits, &small).is_ok());
+    }
+
+    #[tokio::test]
+    async fn deliver_reply_sends_files_and_reports_rejections() {
+        let tmp = TempDir::new().unwrap();
+        let csv = tmp.path().join("out.csv");
+        std::fs::write(&csv, "a,b\n").unwrap();
+        let channel = RecordingChannel {
+            limits: Some(AttachmentLimits {
+                max_bytes: 1024,
+                kinds: vec![AttachmentKind::Document],
+            }),
+            ..Default::default()
+        };
+
+        let reply = format!(
+            "Here you go [DOCUMENT:{}] [VIDEO:https://x.test/v.mp4]",
+            csv.display()
+        );
+        deliver_reply(&channel, SendMessage::new(reply, "chat"))
+            .await
+            .unwrap();
+
+        let texts = channel.texts.lock().clone();
+        assert_eq!(texts[0], "Here you go");
+        assert!(texts[1].contains("Could not send https://x.test/v.mp4"));
+        let files = channel.files.lock().clone();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].local_path(), Some(csv.as_path()));
+    }
+
+    #[tokio::test]
+    async fn deliver_renders_markers_for_channels_without_file_support() {
+        let channel = RecordingChannel::default();
+        let message = SendMessage::new("done", "chat")
+            .with_attachments(vec![Attachment::local("/tmp/out.csv")]);
+
+        deliver(&channel, &message).await.unwrap();
+
+        assert_eq!(
+            channel.texts.lock().clone(),
+            vec!["done\n[DOCUMENT:/tmp/out.csv]".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn deliver_reply_leaves_markers_for_channels_without_file_support() {
+        let channel = RecordingChannel::default();
+
+        deliver_reply(&channel, SendMessage::new("see [IMAGE:/tmp/a.png]", "chat"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            channel.texts.lock().clone(),
+            vec!["see [IMAGE:/tmp/a.png]".to_string()]
+        );
+        assert!(channel.files.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn materialize_inbound_downloads_into_cache_once() {
+        use axum::{routing::get, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/files/report.pdf",
+            get(move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    "%PDF-1.4"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let tmp = TempDir::new().unwrap();
+        let cache = AttachmentCache::new(tmp.path(), 1024);
+        let attachment = Attachment::remote(
+            format!("http://{addr}/files/report.pdf"),
+            Some("report.pdf".into()),
+        )
+        .with_header("Authorization", "Bearer t");
+
+        let first = cache.fetch(&attachment).await.unwrap();
+        let second = cache.fetch(&attachment).await.unwrap();
+
+        assert_eq!(first, second);
+        assert!(first.starts_with(tmp.path().join(CACHE_DIR)));
+        assert!(first.to_string_lossy().ends_with("-report.pdf"));
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "%PDF-1.4");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let small = AttachmentCache::new(tmp.path(), 4);
+        let other = Attachment::remote(format!("http://{addr}/files/report.pdf?v=2"), None);
+        assert!(small.fetch(&other).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn materialize_inbound_appends_references() {
+        let mut msg = ChannelMessage {
+            id: "1".into(),
+            sender: "u".into(),
+            reply_target: "c".into(),
+            content: "summarize this".into(),
+            channel: "slack".into(),
+            timestamp: 0,
+            thread_ts: None,
+            attachments: vec![
+                Attachment::local("/tmp/in/report.pdf"),
+                Attachment::local("/tmp/in/pic.png"),
+            ],
+            event: ChannelEvent::Message,
+        };
+
+        materialize_inbound(&mut msg).await;
+
+        assert_eq!(
+            msg.content,
+            "summarize this\n[Document: report.pdf] /tmp/in/report.pdf\n[IMAGE:/tmp/in/pic.png]"
+        );
+    }
+}
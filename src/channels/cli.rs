@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use tokio::io::{self, AsyncBufReadExt, BufReader};
 use uuid::Uuid;
@@ -48,6 +48,8 @@ impl Channel for CliChannel {
                     .unwrap_or_default()
                     .as_secs(),
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             };
 
             if tx.send(msg).await.is_err() {
@@ -80,6 +82,8 @@ mod tests {
                 icon_emoji: None,
                 reply_broadcast: None,
                 blocks: None,
+                attachments: Vec::new(),
+                resume_from: 0,
             })
             .await;
         assert!(result.is_ok());
@@ -98,6 +102,8 @@ mod tests {
                 icon_emoji: None,
                 reply_broadcast: None,
                 blocks: None,
+                attachments: Vec::new(),
+                resume_from: 0,
             })
             .await;
         assert!(result.is_ok());
@@ -119,6 +125,8 @@ mod tests {
             channel: "cli".into(),
             timestamp: 1_234_567_890,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
         assert_eq!(msg.id, "test-id");
         assert_eq!(msg.sender, "user");
@@ -138,6 +146,8 @@ mod tests {
             channel: "ch".into(),
             timestamp: 0,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
         let cloned = msg.clone();
         assert_eq!(cloned.id, msg.id);
@@ -1,4 +1,9 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::outbound::{PartiallySent, RateLimited};
+use super::render::{render_markdown, split_markdown, Format};
+use super::traits::{
+    note_sent_message, Attachment, AttachmentLimits, AttachmentSource, Channel, ChannelEvent,
+    ChannelMessage, SendMessage,
+};
 use anyhow::Context;
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
@@ -116,6 +121,70 @@ impl DiscordChannel {
 
         Ok(resolved)
     }
+
+    /// Edits (`MESSAGE_UPDATE`) and deletions (`MESSAGE_DELETE`) of earlier
+    /// messages. Deletion payloads carry no author, so `sender` stays empty.
+    fn parse_revision_event(
+        &self,
+        event_type: &str,
+        d: &serde_json::Value,
+        bot_user_id: &str,
+    ) -> Option<ChannelMessage> {
+        let message_id = d.get("id").and_then(|i| i.as_str())?;
+        let channel_id = d.get("channel_id").and_then(|c| c.as_str())?;
+        if let (Some(gid), Some(msg_guild)) = (
+            self.guild_id.as_deref(),
+            d.get("guild_id").and_then(serde_json::Value::as_str),
+        ) {
+            if gid != msg_guild {
+                return None;
+            }
+        }
+
+        let (event, sender, content) = match event_type {
+            "MESSAGE_UPDATE" => {
+                // Embed unfurls also arrive as updates; only real edits carry
+                // an edit timestamp.
+                if d.get("edited_timestamp")
+                    .is_none_or(serde_json::Value::is_null)
+                {
+                    return None;
+                }
+                let author_id = d
+                    .get("author")
+                    .and_then(|a| a.get("id"))
+                    .and_then(|i| i.as_str())?;
+                if author_id == bot_user_id || !self.is_user_allowed(author_id) {
+                    return None;
+                }
+                let content = d.get("content").and_then(|c| c.as_str())?;
+                let content = normalize_incoming_content(content, false, bot_user_id)?;
+                (ChannelEvent::Edited, author_id.to_string(), content)
+            }
+            "MESSAGE_DELETE" => (ChannelEvent::Deleted, String::new(), String::new()),
+            _ => return None,
+        };
+
+        Some(ChannelMessage {
+            id: format!("discord_{message_id}"),
+            sender,
+            reply_target: channel_id.to_string(),
+            content,
+            channel: "discord".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            attachments: Vec::new(),
+            event,
+        })
+    }
+
+    fn message_url(channel_id: &str, message_id: &str) -> String {
+        let message_id = message_id.strip_prefix("discord_").unwrap_or(message_id);
+        format!("https://discord.com/api/v10/channels/{channel_id}/messages/{message_id}")
+    }
 }
 
 fn normalize_group_reply_allowed_sender_ids(sender_ids: Vec<String>) -> Vec<String> {
@@ -181,6 +250,36 @@ async fn process_attachments(
     parts.join("\n---\n")
 }
 
+/// Typed attachments for the files `process_attachments` does not inline as
+/// text, so the channel runtime can fetch them into the shared cache.
+fn typed_attachments(attachments: &[serde_json::Value]) -> Vec<Attachment> {
+    attachments
+        .iter()
+        .filter_map(|att| {
+            let url = att.get("url").and_then(|v| v.as_str())?;
+            let ct = att
+                .get("content_type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            if ct.starts_with("text/") {
+                return None;
+            }
+            let name = att
+                .get("filename")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let mut attachment = Attachment::remote(url, name);
+            if !ct.is_empty() {
+                attachment = attachment.with_mime(ct);
+            }
+            if let Some(size) = att.get("size").and_then(serde_json::Value::as_u64) {
+                attachment = attachment.with_size(size);
+            }
+            Some(attachment)
+        })
+        .collect()
+}
+
 fn is_image_attachment(content_type: &str, filename: &str, url: &str) -> bool {
     let normalized_content_type = content_type
         .split(';')
@@ -350,6 +449,23 @@ fn with_inline_attachment_urls(
     lines.join("\n")
 }
 
+/// Report the id of a message Discord just created so the reply can be
+/// edited or deleted later.
+async fn note_sent_discord_message(resp: reqwest::Response) {
+    let id = resp
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|body| {
+            body.get("id")
+                .and_then(|id| id.as_str())
+                .map(str::to_string)
+        });
+    if let Some(id) = id {
+        note_sent_message(id);
+    }
+}
+
 async fn send_discord_message_json(
     client: &reqwest::Client,
     bot_token: &str,
@@ -368,14 +484,19 @@ async fn send_discord_message_json(
 
     if !resp.status().is_success() {
         let status = resp.status();
+        let headers = resp.headers().clone();
         let err = resp
             .text()
             .await
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited::from_response("discord", &headers, &err).into());
+        }
         let sanitized = crate::providers::sanitize_api_error(&err);
         anyhow::bail!("Discord send message failed ({status}): {sanitized}");
     }
 
+    note_sent_discord_message(resp).await;
     Ok(())
 }
 
@@ -417,14 +538,19 @@ async fn send_discord_message_with_files(
 
     if !resp.status().is_success() {
         let status = resp.status();
+        let headers = resp.headers().clone();
         let err = resp
             .text()
             .await
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited::from_response("discord", &headers, &err).into());
+        }
         let sanitized = crate::providers::sanitize_api_error(&err);
         anyhow::bail!("Discord send message with files failed ({status}): {sanitized}");
     }
 
+    note_sent_discord_message(resp).await;
     Ok(())
 }
 
@@ -434,54 +560,14 @@ const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstu
 ///
 /// Discord rejects longer payloads with `50035 Invalid Form Body`.
 const DISCORD_MAX_MESSAGE_LENGTH: usize = 2000;
+/// Upload limit for bot attachments on servers without boosts.
+const DISCORD_MAX_UPLOAD_BYTES: u64 = 10 * 1024 * 1024;
 const DISCORD_ACK_REACTIONS: &[&str] = &["⚡️", "🦀", "🙌", "💪", "👌", "👀", "👣"];
 
 /// Split a message into chunks that respect Discord's 2000-character limit.
-/// Tries to split at word boundaries when possible.
+/// Tries to split at word boundaries when possible and keeps code fences intact.
 fn split_message_for_discord(message: &str) -> Vec<String> {
-    if message.chars().count() <= DISCORD_MAX_MESSAGE_LENGTH {
-        return vec![message.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = message;
-
-    while !remaining.is_empty() {
-        // Find the byte offset for the 2000th character boundary.
-        // If there are fewer than 2000 chars left, we can emit the tail directly.
-        let hard_split = remaining
-            .char_indices()
-            .nth(DISCORD_MAX_MESSAGE_LENGTH)
-            .map_or(remaining.len(), |(idx, _)| idx);
-
-        let chunk_end = if hard_split == remaining.len() {
-            hard_split
-        } else {
-            // Try to find a good break point (newline, then space)
-            let search_area = &remaining[..hard_split];
-
-            // Prefer splitting at newline
-            if let Some(pos) = search_area.rfind('\n') {
-                // Don't split if the newline is too close to the end
-                if search_area[..pos].chars().count() >= DISCORD_MAX_MESSAGE_LENGTH / 2 {
-                    pos + 1
-                } else {
-                    // Try space as fallback
-                    search_area.rfind(' ').map_or(hard_split, |space| space + 1)
-                }
-            } else if let Some(pos) = search_area.rfind(' ') {
-                pos + 1
-            } else {
-                // Hard split at the limit
-                hard_split
-            }
-        };
-
-        chunks.push(remaining[..chunk_end].to_string());
-        remaining = &remaining[chunk_end..];
-    }
-
-    chunks
+    split_markdown(message, DISCORD_MAX_MESSAGE_LENGTH)
 }
 
 #[allow(clippy::cast_possible_truncation)]
@@ -651,13 +737,14 @@ impl Channel for DiscordChannel {
             local_files.truncate(10);
         }
 
-        let content =
-            with_inline_attachment_urls(&cleaned_content, &remote_urls, &unresolved_markers);
+        let rendered = render_markdown(&cleaned_content, Format::Discord);
+        let content = with_inline_attachment_urls(&rendered, &remote_urls, &unresolved_markers);
         let chunks = split_message_for_discord(&content);
         let client = self.http_client();
 
-        for (i, chunk) in chunks.iter().enumerate() {
-            if i == 0 && !local_files.is_empty() {
+        // Skip chunks an earlier attempt delivered before it was rate limited.
+        for (i, chunk) in chunks.iter().enumerate().skip(message.resume_from) {
+            let sent = if i == 0 && !local_files.is_empty() {
                 send_discord_message_with_files(
                     &client,
                     &self.bot_token,
@@ -665,11 +752,11 @@ impl Channel for DiscordChannel {
                     chunk,
                     &local_files,
                 )
-                .await?;
+                .await
             } else {
-                send_discord_message_json(&client, &self.bot_token, &message.recipient, chunk)
-                    .await?;
-            }
+                send_discord_message_json(&client, &self.bot_token, &message.recipient, chunk).await
+            };
+            sent.map_err(|e| PartiallySent::wrap(e, i))?;
 
             if i < chunks.len() - 1 {
                 tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -803,9 +890,17 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
+                    // Handle MESSAGE_CREATE, plus edits/deletions of earlier messages
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
                     if event_type != "MESSAGE_CREATE" {
+                        let revision = event
+                            .get("d")
+                            .and_then(|d| self.parse_revision_event(event_type, d, &bot_user_id));
+                        if let Some(revision) = revision {
+                            if tx.send(revision).await.is_err() {
+                                break;
+                            }
+                        }
                         continue;
                     }
 
@@ -853,14 +948,12 @@ impl Channel for DiscordChannel {
                         continue;
                     };
 
-                    let attachment_text = {
-                        let atts = d
-                            .get("attachments")
-                            .and_then(|a| a.as_array())
-                            .cloned()
-                            .unwrap_or_default();
-                        process_attachments(&atts, &self.http_client()).await
-                    };
+                    let atts = d
+                        .get("attachments")
+                        .and_then(|a| a.as_array())
+                        .cloned()
+                        .unwrap_or_default();
+                    let attachment_text = process_attachments(&atts, &self.http_client()).await;
                     let final_content = if attachment_text.is_empty() {
                         clean_content
                     } else {
@@ -920,6 +1013,8 @@ impl Channel for DiscordChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: typed_attachments(&atts),
+                        event: ChannelEvent::Message,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -932,6 +1027,35 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    fn attachment_limits(&self) -> Option<AttachmentLimits> {
+        Some(AttachmentLimits::all_kinds(DISCORD_MAX_UPLOAD_BYTES))
+    }
+
+    async fn send_attachment(
+        &self,
+        message: &SendMessage,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        let client = self.http_client();
+        match &attachment.source {
+            AttachmentSource::Local(path) => {
+                let path = self.resolve_local_attachment_path(&path.to_string_lossy())?;
+                send_discord_message_with_files(
+                    &client,
+                    &self.bot_token,
+                    &message.recipient,
+                    "",
+                    &[path],
+                )
+                .await
+            }
+            // Discord unfurls media links, so remote files are posted as URLs.
+            AttachmentSource::Remote { url, .. } => {
+                send_discord_message_json(&client, &self.bot_token, &message.recipient, url).await
+            }
+        }
+    }
+
     async fn health_check(&self) -> bool {
         self.http_client()
             .get("https://discord.com/api/v10/users/@me")
@@ -975,6 +1099,60 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    fn supports_message_edits(&self) -> bool {
+        true
+    }
+
+    async fn edit_message(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let content = render_markdown(text, Format::Discord);
+        let content: String = content.chars().take(DISCORD_MAX_MESSAGE_LENGTH).collect();
+        let resp = self
+            .http_client()
+            .patch(Self::message_url(recipient, message_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&json!({ "content": content }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&err);
+            anyhow::bail!("Discord edit message failed ({status}): {sanitized}");
+        }
+
+        Ok(())
+    }
+
+    async fn delete_message(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        let resp = self
+            .http_client()
+            .delete(Self::message_url(recipient, message_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&err);
+            anyhow::bail!("Discord delete message failed ({status}): {sanitized}");
+        }
+
+        Ok(())
+    }
+
     async fn add_reaction(
         &self,
         channel_id: &str,
@@ -1037,6 +1215,16 @@ impl Channel for DiscordChannel {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn created_message_ids_are_reported_for_reply_tracking() {
+        let resp = reqwest::Response::from(axum::http::Response::new(
+            r#"{"id":"1234","content":"hi"}"#.to_string(),
+        ));
+        let ((), ids) =
+            crate::channels::traits::record_sent_messages(note_sent_discord_message(resp)).await;
+        assert_eq!(ids, vec!["1234"]);
+    }
+
     #[test]
     fn discord_channel_name() {
         let ch = DiscordChannel::new("fake".into(), None, vec![], false, false);
@@ -1147,6 +1335,66 @@ mod tests {
         assert!(!contains_bot_mention("hi <@99999>", "12345"));
     }
 
+    #[test]
+    fn parse_revision_event_reads_edits_and_deletions() {
+        let ch = DiscordChannel::new("fake".into(), None, vec!["111".into()], false, false);
+        let update = json!({
+            "id": "900",
+            "channel_id": "42",
+            "content": "  corrected  ",
+            "edited_timestamp": "2026-01-01T00:00:00Z",
+            "author": {"id": "111"}
+        });
+        let msg = ch
+            .parse_revision_event("MESSAGE_UPDATE", &update, "bot")
+            .unwrap();
+        assert_eq!(msg.event, ChannelEvent::Edited);
+        assert_eq!(msg.id, "discord_900");
+        assert_eq!(msg.content, "corrected");
+        assert_eq!(msg.reply_target, "42");
+
+        let delete = json!({"id": "900", "channel_id": "42"});
+        let msg = ch
+            .parse_revision_event("MESSAGE_DELETE", &delete, "bot")
+            .unwrap();
+        assert_eq!(msg.event, ChannelEvent::Deleted);
+        assert_eq!(msg.id, "discord_900");
+    }
+
+    #[test]
+    fn parse_revision_event_skips_unfurls_and_unauthorized_authors() {
+        let ch = DiscordChannel::new("fake".into(), None, vec!["111".into()], false, false);
+        let unfurl = json!({
+            "id": "900",
+            "channel_id": "42",
+            "content": "https://example.com",
+            "edited_timestamp": null,
+            "author": {"id": "111"}
+        });
+        assert!(ch
+            .parse_revision_event("MESSAGE_UPDATE", &unfurl, "bot")
+            .is_none());
+
+        let stranger = json!({
+            "id": "900",
+            "channel_id": "42",
+            "content": "hi",
+            "edited_timestamp": "2026-01-01T00:00:00Z",
+            "author": {"id": "222"}
+        });
+        assert!(ch
+            .parse_revision_event("MESSAGE_UPDATE", &stranger, "bot")
+            .is_none());
+    }
+
+    #[test]
+    fn message_url_accepts_prefixed_ids() {
+        assert_eq!(
+            DiscordChannel::message_url("42", "discord_900"),
+            "https://discord.com/api/v10/channels/42/messages/900"
+        );
+    }
+
     #[test]
     fn normalize_incoming_content_requires_mention_when_enabled() {
         let cleaned = normalize_incoming_content("hello there", true, "12345");
@@ -1659,6 +1907,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn typed_attachments_skip_inlined_text_files() {
+        let attachments = vec![
+            serde_json::json!({
+                "url": "https://cdn.discordapp.com/attachments/123/456/doc.pdf",
+                "filename": "doc.pdf",
+                "content_type": "application/pdf",
+                "size": 4096
+            }),
+            serde_json::json!({
+                "url": "https://cdn.discordapp.com/attachments/123/456/notes.txt",
+                "filename": "notes.txt",
+                "content_type": "text/plain"
+            }),
+        ];
+
+        let typed = typed_attachments(&attachments);
+
+        assert_eq!(typed.len(), 1);
+        assert_eq!(typed[0].filename.as_deref(), Some("doc.pdf"));
+        assert_eq!(typed[0].mime_type.as_deref(), Some("application/pdf"));
+        assert_eq!(typed[0].size, Some(4096));
+    }
+
     #[test]
     fn is_image_attachment_prefers_non_image_content_type_over_extension() {
         assert!(!is_image_attachment(
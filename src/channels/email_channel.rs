@@ -14,7 +14,7 @@ use async_imap::types::Fetch;
 use async_imap::Session;
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
-use lettre::message::SinglePart;
+use lettre::message::MultiPart;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
@@ -33,7 +33,8 @@ use tokio_rustls::TlsConnector;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::render::{render_markdown, Format};
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 
 /// Email channel configuration
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -460,6 +461,8 @@ impl EmailChannel {
                 channel: "email".to_string(),
                 timestamp: email.timestamp,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             };
 
             if tx.send(msg).await.is_err() {
@@ -528,7 +531,13 @@ impl Channel for EmailChannel {
             .from(self.config.from_address.parse()?)
             .to(message.recipient.parse()?)
             .subject(subject)
-            .singlepart(SinglePart::plain(body.to_string()))?;
+            .multipart(MultiPart::alternative_plain_html(
+                render_markdown(body, Format::PlainText),
+                format!(
+                    "<!DOCTYPE html><html><body>{}</body></html>",
+                    render_markdown(body, Format::EmailHtml)
+                ),
+            ))?;
 
         let transport = self.create_smtp_transport()?;
         transport.send(&email)?;
@@ -1,4 +1,5 @@
-use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::channels::render::{render_markdown, Format};
+use crate::channels::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -304,7 +305,7 @@ impl IrcChannel {
 
 /// Certificate verifier that accepts any certificate (for `verify_tls=false`).
 #[derive(Debug)]
-struct NoVerify;
+pub(crate) struct NoVerify;
 
 impl rustls::client::danger::ServerCertVerifier for NoVerify {
     fn verify_server_cert(
@@ -360,7 +361,7 @@ impl Channel for IrcChannel {
         // 512 - sender prefix (~64 bytes for :nick!user@host) - "PRIVMSG " - target - " :" - "\r\n"
         let overhead = SENDER_PREFIX_RESERVE + 10 + message.recipient.len() + 2;
         let max_payload = 512_usize.saturating_sub(overhead);
-        let chunks = split_message(&message.content, max_payload);
+        let chunks = split_message(&render_markdown(&message.content, Format::Irc), max_payload);
 
         for chunk in chunks {
             Self::send_raw(writer, &format!("PRIVMSG {} :{chunk}", message.recipient)).await?;
@@ -580,6 +581,8 @@ impl Channel for IrcChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: Vec::new(),
+                        event: ChannelEvent::Message,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use base64::Engine;
 use futures_util::{SinkExt, StreamExt};
@@ -966,6 +966,8 @@ impl LarkChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: Vec::new(),
+                        event: ChannelEvent::Message,
                     };
 
                     tracing::debug!("Lark WS: message in {}", lark_msg.chat_id);
@@ -1264,6 +1266,8 @@ impl LarkChannel {
             channel: self.channel_name().to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         });
 
         messages
@@ -1392,6 +1396,8 @@ impl LarkChannel {
             channel: self.channel_name().to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         });
 
         messages
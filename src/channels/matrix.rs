@@ -1,4 +1,5 @@
-use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::channels::render::{render_markdown, Format};
+use crate::channels::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use matrix_sdk::{
     authentication::matrix::MatrixSession,
@@ -692,8 +693,11 @@ impl Channel for MatrixChannel {
             anyhow::bail!("Matrix room '{}' is not in joined state", target_room_id);
         }
 
-        room.send(RoomMessageEventContent::text_markdown(&message.content))
-            .await?;
+        room.send(RoomMessageEventContent::text_html(
+            render_markdown(&message.content, Format::PlainText),
+            render_markdown(&message.content, Format::MatrixHtml),
+        ))
+        .await?;
 
         Ok(())
     }
@@ -831,6 +835,8 @@ impl Channel for MatrixChannel {
                         .unwrap_or_default()
                         .as_secs(),
                     thread_ts: None,
+                    attachments: Vec::new(),
+                    event: ChannelEvent::Message,
                 };
 
                 let _ = tx.send(msg).await;
@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use crate::security::{GuardAction, GuardResult, PromptGuard};
 use anyhow::{bail, Result};
 use async_trait::async_trait;
@@ -992,6 +992,8 @@ impl MattermostChannel {
             #[allow(clippy::cast_sign_loss)]
             timestamp: (create_at / 1000) as u64,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
     }
 }
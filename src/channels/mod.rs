@@ -14,6 +14,7 @@
 //! To add a new channel, implement [`Channel`] in a new submodule and wire it into
 //! [`start_channels`]. See `AGENTS.md` §7.2 for the full change playbook.
 
+pub mod attachments;
 pub mod clawdtalk;
 pub mod cli;
 pub mod dingtalk;
@@ -29,18 +30,26 @@ pub mod matrix;
 pub mod mattermost;
 pub mod nextcloud_talk;
 pub mod nostr;
+pub mod outbound;
 pub mod qq;
+pub mod render;
 pub mod signal;
 pub mod slack;
 pub mod telegram;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod traits;
 pub mod transcription;
+pub mod tts;
 pub mod wati;
+pub mod webhook;
 pub mod whatsapp;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_storage;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_web;
+pub mod xmpp;
+pub mod zulip;
 
 pub use clawdtalk::ClawdTalkChannel;
 pub use cli::CliChannel;
@@ -63,22 +72,30 @@ pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
 pub use traits::{Channel, SendMessage};
 pub use wati::WatiChannel;
+pub use webhook::WebhookChannel;
 pub use whatsapp::WhatsAppChannel;
 #[cfg(feature = "whatsapp-web")]
 pub use whatsapp_web::WhatsAppWebChannel;
+pub use xmpp::XmppChannel;
+pub use zulip::ZulipChannel;
 
 use crate::agent::loop_::{
     build_shell_policy_instructions, build_tool_instructions_from_specs,
-    run_tool_call_loop_with_reply_target, scrub_credentials, SafetyHeartbeatConfig,
+    run_tool_call_loop_with_reply_target, scrub_credentials, with_reply_target,
+    SafetyHeartbeatConfig,
 };
+use crate::agent::plan::{self, PlanDecision};
+use crate::agent::session::{self, SessionCommand, SessionStore};
+use crate::approval::quorum::{self as approval_quorum, QuorumStatus};
 use crate::approval::{ApprovalManager, ApprovalResponse, PendingApprovalError};
 use crate::config::{Config, NonCliNaturalLanguageApprovalMode};
 use crate::identity;
 use crate::memory::{self, Memory};
-use crate::observability::{self, runtime_trace, Observer};
+use crate::observability::{self, runtime_trace, span, transcript, Observer};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
-use crate::security::{LeakDetector, LeakResult, SecurityPolicy};
+use crate::security::dlp::{self, DlpSurface};
+use crate::security::{LeakDetector, LeakResult, SecurityPolicy, UserDirectory};
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
@@ -135,6 +152,210 @@ fn live_channels_registry() -> &'static Mutex<HashMap<String, Arc<dyn Channel>>>
     REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// Named sessions bound to conversation history keys via `/session switch`.
+fn session_bindings() -> &'static Mutex<HashMap<String, String>> {
+    static BINDINGS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    BINDINGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Results of background work (queued delegate tasks) waiting to be added to
+/// a conversation's history, keyed by channel and reply target.
+fn pending_background_results() -> &'static Mutex<HashMap<String, Vec<String>>> {
+    static RESULTS: OnceLock<Mutex<HashMap<String, Vec<String>>>> = OnceLock::new();
+    RESULTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn background_result_key(channel: &str, reply_target: &str) -> String {
+    format!("{}\u{1f}{reply_target}", channel.to_ascii_lowercase())
+}
+
+/// Queue a background result that was already sent to `reply_target` so the
+/// next turn of that conversation sees it in its history.
+pub(crate) fn inject_background_result(channel: &str, reply_target: &str, text: String) {
+    pending_background_results()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(background_result_key(channel, reply_target))
+        .or_default()
+        .push(text);
+}
+
+fn take_background_results(channel: &str, reply_target: &str) -> Vec<String> {
+    pending_background_results()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&background_result_key(channel, reply_target))
+        .unwrap_or_default()
+}
+
+/// Inbound turns remembered so later edits/deletions can find them again.
+const MAX_TRACKED_TURNS: usize = 2048;
+
+/// Where an inbound message ended up, for applying later edits or deletions.
+#[derive(Debug, Clone)]
+struct TrackedTurn {
+    history_key: String,
+    memory_key: String,
+    content: String,
+    channel: String,
+    reply_target: String,
+    /// Platform ids of the messages the reply was sent as.
+    reply_ids: Vec<String>,
+    sequence: u64,
+}
+
+fn tracked_turns() -> &'static Mutex<HashMap<String, TrackedTurn>> {
+    static TURNS: OnceLock<Mutex<HashMap<String, TrackedTurn>>> = OnceLock::new();
+    TURNS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn message_revision_key(msg: &traits::ChannelMessage) -> String {
+    format!("{}\u{1f}{}", msg.channel.to_ascii_lowercase(), msg.id)
+}
+
+fn track_user_turn(msg: &traits::ChannelMessage, history_key: &str, content: &str) {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let mut turns = tracked_turns().lock().unwrap_or_else(|e| e.into_inner());
+    turns.insert(
+        message_revision_key(msg),
+        TrackedTurn {
+            history_key: history_key.to_string(),
+            memory_key: conversation_memory_key(msg),
+            content: content.to_string(),
+            channel: msg.channel.to_ascii_lowercase(),
+            reply_target: msg.reply_target.clone(),
+            reply_ids: Vec::new(),
+            sequence: SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        },
+    );
+    while turns.len() > MAX_TRACKED_TURNS {
+        let Some(oldest) = turns
+            .iter()
+            .min_by_key(|(_, turn)| turn.sequence)
+            .map(|(key, _)| key.clone())
+        else {
+            break;
+        };
+        turns.remove(&oldest);
+    }
+}
+
+/// Remember the platform ids of the reply sent for `msg`: the streamed draft,
+/// or the messages [`traits::record_sent_messages`] saw a plain send create.
+fn track_reply(msg: &traits::ChannelMessage, reply_ids: &[String]) {
+    if reply_ids.is_empty() {
+        return;
+    }
+    if let Some(turn) = tracked_turns()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(&message_revision_key(msg))
+    {
+        turn.reply_ids = reply_ids.to_vec();
+    }
+}
+
+/// Platform ids of the `nth` most recent tracked reply (`1` = latest) in the
+/// `reply_target` conversation on `channel`.
+pub(crate) fn recent_reply_ids(
+    channel: &str,
+    reply_target: &str,
+    nth: usize,
+) -> Option<Vec<String>> {
+    let channel = channel.to_ascii_lowercase();
+    let turns = tracked_turns().lock().unwrap_or_else(|e| e.into_inner());
+    let mut replies: Vec<&TrackedTurn> = turns
+        .values()
+        .filter(|turn| {
+            turn.channel == channel
+                && turn.reply_target == reply_target
+                && !turn.reply_ids.is_empty()
+        })
+        .collect();
+    replies.sort_by_key(|turn| std::cmp::Reverse(turn.sequence));
+    replies
+        .get(nth.checked_sub(1)?)
+        .map(|turn| turn.reply_ids.clone())
+}
+
+/// Record that the reply sent as `old_ids` now consists of `new_ids` (empty
+/// once deleted).
+pub(crate) fn replace_reply_ids(
+    channel: &str,
+    reply_target: &str,
+    old_ids: &[String],
+    new_ids: Vec<String>,
+) {
+    let channel = channel.to_ascii_lowercase();
+    let mut turns = tracked_turns().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(turn) = turns.values_mut().find(|turn| {
+        turn.channel == channel && turn.reply_target == reply_target && turn.reply_ids == old_ids
+    }) {
+        turn.reply_ids = new_ids;
+    }
+}
+
+/// Edit (`Some(text)`) or delete (`None`) the `nth` most recent reply
+/// (`1` = latest) the agent sent to `reply_target` on `channel`. An edited
+/// reply that was split into several messages keeps only the first.
+pub(crate) async fn revise_sent_reply(
+    channel: &dyn Channel,
+    reply_target: &str,
+    nth: usize,
+    text: Option<&str>,
+) -> anyhow::Result<()> {
+    if !channel.supports_message_edits() {
+        anyhow::bail!(
+            "channel `{}` cannot edit or delete its messages",
+            channel.name()
+        );
+    }
+    let Some(ids) = recent_reply_ids(channel.name(), reply_target, nth) else {
+        anyhow::bail!("no tracked reply #{nth} in this conversation");
+    };
+    let (mut keep, remove) = match text {
+        Some(text) => {
+            let (first, rest) = ids.split_first().expect("tracked replies have ids");
+            channel.edit_message(reply_target, first, text).await?;
+            (vec![first.clone()], rest)
+        }
+        None => (Vec::new(), ids.as_slice()),
+    };
+    // Messages that could not be deleted stay tracked so a retry finds them.
+    let mut failure = None;
+    for id in remove {
+        if let Err(e) = channel.delete_message(reply_target, id).await {
+            keep.push(id.clone());
+            failure.get_or_insert(e);
+        }
+    }
+    replace_reply_ids(channel.name(), reply_target, &ids, keep);
+    failure.map_or(Ok(()), Err)
+}
+
+fn tracked_turn(msg: &traits::ChannelMessage) -> Option<TrackedTurn> {
+    tracked_turns()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&message_revision_key(msg))
+        .cloned()
+}
+
+fn forget_tracked_turn(msg: &traits::ChannelMessage) -> Option<TrackedTurn> {
+    tracked_turns()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&message_revision_key(msg))
+}
+
+fn bound_session(sender_key: &str) -> Option<String> {
+    session_bindings()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(sender_key)
+        .cloned()
+}
+
 fn register_live_channels(channels_by_name: &HashMap<String, Arc<dyn Channel>>) {
     let mut guard = live_channels_registry()
         .lock()
@@ -195,6 +416,16 @@ enum ChannelRuntimeCommand {
     ApproveTool(String),
     UnapproveTool(String),
     ListApprovals,
+    ShowIdentity,
+    LinkIdentity(String),
+    ReleaseDlpHold(String),
+    ListQuorumRequests,
+    ApproveQuorumRequest(String),
+    DenyQuorumRequest(String),
+    ApprovePlan(String),
+    EditPlan(String),
+    RejectPlan(String),
+    Session(String),
 }
 
 const APPROVAL_ALL_TOOLS_ONCE_TOKEN: &str = "__all_tools_once__";
@@ -245,6 +476,7 @@ struct RuntimeAutonomyPolicy {
         HashMap<String, NonCliNaturalLanguageApprovalMode>,
     perplexity_filter: crate::config::PerplexityFilterConfig,
     outbound_leak_guard: crate::config::OutboundLeakGuardConfig,
+    dlp: crate::config::DlpConfig,
 }
 
 fn runtime_config_store() -> &'static Mutex<HashMap<PathBuf, RuntimeConfigState>> {
@@ -281,14 +513,18 @@ struct ChannelRuntimeContext {
     workspace_dir: Arc<PathBuf>,
     message_timeout_secs: u64,
     interrupt_on_new_message: bool,
+    plan_mode: crate::config::PlanModeConfig,
     multimodal: crate::config::MultimodalConfig,
     hooks: Option<Arc<crate::hooks::HookRunner>>,
     non_cli_excluded_tools: Arc<Mutex<Vec<String>>>,
     query_classification: crate::config::QueryClassificationConfig,
     model_routes: Vec<crate::config::ModelRouteConfig>,
+    model_cascade: Option<Arc<providers::cascade::CascadePolicy>>,
     approval_manager: Arc<ApprovalManager>,
     safety_heartbeat: Option<SafetyHeartbeatConfig>,
     startup_perplexity_filter: crate::config::PerplexityFilterConfig,
+    voice_replies: Option<Arc<tts::VoiceReplies>>,
+    message_edits: crate::config::MessageEditsConfig,
 }
 
 #[derive(Clone)]
@@ -747,12 +983,26 @@ fn parse_runtime_command(channel_name: &str, content: &str) -> Option<ChannelRun
         "/approve-all-once" => Some(ChannelRuntimeCommand::RequestAllToolsOnce),
         "/approve-request" => Some(ChannelRuntimeCommand::RequestToolApproval(tail)),
         "/approve-confirm" => Some(ChannelRuntimeCommand::ConfirmToolApproval(tail)),
+        "/approve-allow" if tail.starts_with(plan::PLAN_REVIEW_ID_PREFIX) => {
+            Some(ChannelRuntimeCommand::ApprovePlan(tail))
+        }
+        "/approve-deny" if tail.starts_with(plan::PLAN_REVIEW_ID_PREFIX) => {
+            Some(ChannelRuntimeCommand::RejectPlan(tail))
+        }
         "/approve-allow" => Some(ChannelRuntimeCommand::ApprovePendingRequest(tail)),
         "/approve-deny" => Some(ChannelRuntimeCommand::DenyToolApproval(tail)),
         "/approve-pending" => Some(ChannelRuntimeCommand::ListPendingApprovals),
         "/approve" => Some(ChannelRuntimeCommand::ApproveTool(tail)),
         "/unapprove" => Some(ChannelRuntimeCommand::UnapproveTool(tail)),
         "/approvals" => Some(ChannelRuntimeCommand::ListApprovals),
+        "/whoami" => Some(ChannelRuntimeCommand::ShowIdentity),
+        "/link" => Some(ChannelRuntimeCommand::LinkIdentity(tail)),
+        "/dlp-release" => Some(ChannelRuntimeCommand::ReleaseDlpHold(tail)),
+        "/quorum-pending" => Some(ChannelRuntimeCommand::ListQuorumRequests),
+        "/quorum-approve" => Some(ChannelRuntimeCommand::ApproveQuorumRequest(tail)),
+        "/quorum-deny" => Some(ChannelRuntimeCommand::DenyQuorumRequest(tail)),
+        "/plan-edit" => Some(ChannelRuntimeCommand::EditPlan(tail)),
+        "/session" => Some(ChannelRuntimeCommand::Session(tail)),
         // Provider/model switching remains limited to channels with session routing.
         "/models" if supports_runtime_model_switch(channel_name) => {
             if let Some(provider) = args.first() {
@@ -894,6 +1144,7 @@ fn is_approval_management_command(command: &ChannelRuntimeCommand) -> bool {
             | ChannelRuntimeCommand::ApproveTool(_)
             | ChannelRuntimeCommand::UnapproveTool(_)
             | ChannelRuntimeCommand::ListApprovals
+            | ChannelRuntimeCommand::ReleaseDlpHold(_)
     )
 }
 
@@ -966,6 +1217,7 @@ fn runtime_autonomy_policy_from_config(config: &Config) -> RuntimeAutonomyPolicy
             .clone(),
         perplexity_filter: config.security.perplexity_filter.clone(),
         outbound_leak_guard: config.security.outbound_leak_guard.clone(),
+        dlp: config.security.dlp.clone(),
     }
 }
 
@@ -1109,11 +1361,17 @@ async fn config_file_stamp(path: &Path) -> Option<ConfigFileStamp> {
 
 fn decrypt_optional_secret_for_runtime_reload(
     store: &crate::security::SecretStore,
+    resolver: &crate::security::SecretResolver,
     value: &mut Option<String>,
     field_name: &str,
 ) -> Result<()> {
     if let Some(raw) = value.clone() {
-        if crate::security::SecretStore::is_encrypted(&raw) {
+        if let Some(resolved) = resolver
+            .resolve(&raw)
+            .with_context(|| format!("Failed to resolve {field_name}"))?
+        {
+            *value = Some(resolved);
+        } else if crate::security::SecretStore::is_encrypted(&raw) {
             *value = Some(
                 store
                     .decrypt(&raw)
@@ -1136,9 +1394,16 @@ async fn load_runtime_defaults_from_config_file(
 
     if let Some(zeroclaw_dir) = path.parent() {
         let store = crate::security::SecretStore::new(zeroclaw_dir, parsed.secrets.encrypt);
-        decrypt_optional_secret_for_runtime_reload(&store, &mut parsed.api_key, "config.api_key")?;
+        let resolver = crate::security::SecretResolver::from_config(&parsed.secrets, zeroclaw_dir);
+        decrypt_optional_secret_for_runtime_reload(
+            &store,
+            &resolver,
+            &mut parsed.api_key,
+            "config.api_key",
+        )?;
         decrypt_optional_secret_for_runtime_reload(
             &store,
+            &resolver,
             &mut parsed.transcription.api_key,
             "config.transcription.api_key",
         )?;
@@ -1565,6 +1830,9 @@ async fn maybe_apply_runtime_config_update(ctx: &ChannelRuntimeContext) -> Resul
         next_autonomy_policy.non_cli_natural_language_approval_mode,
         &next_autonomy_policy.non_cli_natural_language_approval_mode_by_channel,
     );
+    if let Err(err) = dlp::install_runtime(&next_autonomy_policy.dlp) {
+        tracing::warn!("Keeping previous DLP policy after config reload: {err}");
+    }
     {
         let mut excluded = ctx
             .non_cli_excluded_tools
@@ -1731,6 +1999,66 @@ fn rollback_orphan_user_turn(
     true
 }
 
+/// Rewrite the most recent user turn carrying `original` in a sender's
+/// history, or drop it when `replacement` is `None`. Dropping a standalone
+/// turn also drops the assistant reply that directly follows it.
+fn revise_history_turn(
+    ctx: &ChannelRuntimeContext,
+    sender_key: &str,
+    original: &str,
+    replacement: Option<&str>,
+) -> bool {
+    if original.is_empty() {
+        return false;
+    }
+
+    let mut histories = ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let Some(turns) = histories.get_mut(sender_key) else {
+        return false;
+    };
+    let Some(index) = turns
+        .iter()
+        .rposition(|turn| turn.role == "user" && turn.content.contains(original))
+    else {
+        return false;
+    };
+
+    match replacement {
+        Some(text) => {
+            let content = &mut turns[index].content;
+            if let Some(start) = content.rfind(original) {
+                content.replace_range(start..start + original.len(), text);
+            }
+        }
+        None if turns[index].content == original => {
+            turns.remove(index);
+            if turns
+                .get(index)
+                .is_some_and(|turn| turn.role == "assistant")
+            {
+                turns.remove(index);
+            }
+        }
+        None => {
+            // Compacted histories merge consecutive user turns; only cut the
+            // deleted segment out of the merged text.
+            let content = &mut turns[index].content;
+            if let Some(start) = content.rfind(original) {
+                content.replace_range(start..start + original.len(), "");
+                *content = content.trim_matches('\n').replace("\n\n\n\n", "\n\n");
+            }
+        }
+    }
+
+    if turns.is_empty() {
+        histories.remove(sender_key);
+    }
+    true
+}
+
 fn should_skip_memory_context_entry(key: &str, content: &str) -> bool {
     if memory::is_assistant_autosave_key(key) {
         return true;
@@ -1832,6 +2160,49 @@ async fn get_or_create_provider(
     Ok(Arc::clone(cached))
 }
 
+/// Wrap the default route's provider in the `[model_cascade]` tiers. Sender
+/// `/models` / `/model` overrides keep their explicit selection.
+async fn cascade_default_route(
+    ctx: &ChannelRuntimeContext,
+    route: &ChannelRouteSelection,
+    provider: Arc<dyn Provider>,
+) -> Arc<dyn Provider> {
+    let Some(policy) = ctx.model_cascade.as_ref() else {
+        return provider;
+    };
+    if *route != default_route_selection(ctx) {
+        return provider;
+    }
+    let mut tiers = Vec::new();
+    for hint in policy.tiers() {
+        let Some(tier_route) = ctx.model_routes.iter().find(|r| &r.hint == hint) else {
+            tracing::warn!(
+                hint = hint.as_str(),
+                "Cascade tier has no model route, skipping"
+            );
+            continue;
+        };
+        match get_or_create_provider(ctx, &tier_route.provider).await {
+            Ok(tier_provider) => tiers.push(providers::cascade::CascadeTier::new(
+                hint.clone(),
+                tier_route.provider.clone(),
+                tier_route.model.clone(),
+                tier_provider,
+                tier_route.model.clone(),
+            )),
+            Err(err) => tracing::warn!(
+                hint = hint.as_str(),
+                "Skipping cascade tier whose provider failed to initialize: {err}"
+            ),
+        }
+    }
+    Arc::new(providers::cascade::CascadeProvider::new(
+        Arc::clone(policy),
+        tiers,
+        provider,
+    ))
+}
+
 async fn create_resilient_provider_nonblocking(
     provider_name: &str,
     api_key: Option<String>,
@@ -2103,6 +2474,17 @@ async fn handle_runtime_command_if_needed(
     }
 
     let response = match command {
+        ChannelRuntimeCommand::ShowIdentity => describe_sender_identity(ctx, msg),
+        ChannelRuntimeCommand::LinkIdentity(code) => handle_link_identity_command(ctx, msg, &code),
+        ChannelRuntimeCommand::ReleaseDlpHold(id) => {
+            handle_dlp_release_command(ctx, msg, &id).await
+        }
+        ChannelRuntimeCommand::ListQuorumRequests
+        | ChannelRuntimeCommand::ApproveQuorumRequest(_)
+        | ChannelRuntimeCommand::DenyQuorumRequest(_) => handle_quorum_command(msg, command),
+        ChannelRuntimeCommand::ApprovePlan(_)
+        | ChannelRuntimeCommand::EditPlan(_)
+        | ChannelRuntimeCommand::RejectPlan(_) => handle_plan_review_command(msg, command),
         ChannelRuntimeCommand::ShowProviders => build_providers_help_response(&current),
         ChannelRuntimeCommand::SetProvider(raw_provider) => {
             match resolve_provider_alias(&raw_provider) {
@@ -2112,6 +2494,7 @@ async fn handle_runtime_command_if_needed(
                             current.provider = provider_name.clone();
                             set_route_selection(ctx, &sender_key, current.clone());
                             clear_sender_history(ctx, &sender_key);
+                            persist_bound_session_route(ctx, &sender_key, &current);
                         }
 
                         format!(
@@ -2142,6 +2525,7 @@ async fn handle_runtime_command_if_needed(
                 current.model = model.clone();
                 set_route_selection(ctx, &sender_key, current.clone());
                 clear_sender_history(ctx, &sender_key);
+                persist_bound_session_route(ctx, &sender_key, &current);
 
                 format!(
                     "Model switched to `{model}` for provider `{}` in this sender session.",
@@ -2151,7 +2535,19 @@ async fn handle_runtime_command_if_needed(
         }
         ChannelRuntimeCommand::NewSession => {
             clear_sender_history(ctx, &sender_key);
-            "Conversation history cleared. Starting fresh.".to_string()
+            let left = session_bindings()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&sender_key);
+            match left {
+                Some(name) => format!(
+                    "Conversation history cleared. Left named session `{name}` (its history is kept)."
+                ),
+                None => "Conversation history cleared. Starting fresh.".to_string(),
+            }
+        }
+        ChannelRuntimeCommand::Session(args) => {
+            handle_session_command(ctx, &sender_key, &mut current, &args)
         }
         ChannelRuntimeCommand::RequestAllToolsOnce => {
             let req = ctx.approval_manager.create_non_cli_pending_request(
@@ -2660,77 +3056,482 @@ async fn handle_runtime_command_if_needed(
     true
 }
 
-async fn build_memory_context(
-    mem: &dyn Memory,
-    user_msg: &str,
-    min_relevance_score: f64,
-) -> String {
-    let mut context = String::new();
-
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
-        let mut included = 0usize;
-        let mut used_chars = 0usize;
-
-        for entry in entries.iter().filter(|e| match e.score {
-            Some(score) => score >= min_relevance_score,
-            None => true, // keep entries without a score (e.g. non-vector backends)
-        }) {
-            if included >= MEMORY_CONTEXT_MAX_ENTRIES {
-                break;
-            }
-
-            if should_skip_memory_context_entry(&entry.key, &entry.content) {
-                continue;
-            }
-
-            let content = if entry.content.chars().count() > MEMORY_CONTEXT_ENTRY_MAX_CHARS {
-                truncate_with_ellipsis(&entry.content, MEMORY_CONTEXT_ENTRY_MAX_CHARS)
+fn describe_sender_identity(ctx: &ChannelRuntimeContext, msg: &traits::ChannelMessage) -> String {
+    let Some(directory) = ctx.approval_manager.user_directory() else {
+        return format!(
+            "Sender `{}` on channel `{}`.\nThe user directory is disabled (`[security.users].enabled = false`).",
+            msg.sender, msg.channel
+        );
+    };
+    match directory.resolve(&msg.channel, &msg.sender) {
+        Some(user) => {
+            let display_name = if user.display_name.is_empty() {
+                user.id.clone()
             } else {
-                entry.content.clone()
+                user.display_name.clone()
             };
+            format!(
+                "You are `{}` ({display_name}) with role `{}`.\nLinked identities: {}\nUse `/link` to get a one-time code for linking another channel.",
+                user.id,
+                user.role,
+                user.identities.join(", ")
+            )
+        }
+        None => {
+            let role = directory
+                .role_for(&msg.channel, &msg.sender)
+                .unwrap_or_else(|| "(channel policy)".to_string());
+            format!(
+                "Sender `{}` on channel `{}` is not linked to a user (effective role: `{role}`).\nAsk an operator for a link code, then send `/link <code>`.",
+                msg.sender, msg.channel
+            )
+        }
+    }
+}
 
-            let line = format!("- {}: {}\n", entry.key, content);
-            let line_chars = line.chars().count();
-            if used_chars + line_chars > MEMORY_CONTEXT_MAX_CHARS {
-                break;
-            }
+fn handle_link_identity_command(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+    code: &str,
+) -> String {
+    let Some(directory) = ctx.approval_manager.user_directory() else {
+        return "Identity linking is unavailable: the user directory is disabled (`[security.users].enabled = false`).".to_string();
+    };
+    let code = code.trim();
 
-            if included == 0 {
-                context.push_str("[Memory context]\n");
+    if code.is_empty() {
+        let Some(user) = directory.resolve(&msg.channel, &msg.sender) else {
+            return "This identity is not linked to a user yet.\nUsage: `/link <code>` with a code from `zeroclaw users link-code <user>` or from `/link` on an already linked channel.".to_string();
+        };
+        return match directory.issue_link_code(&user.id) {
+            Ok((issued, expires_at)) => {
+                runtime_trace::record_event(
+                    "user_link_code_issued",
+                    Some(msg.channel.as_str()),
+                    None,
+                    None,
+                    None,
+                    Some(true),
+                    None,
+                    serde_json::json!({
+                        "user_id": user.id,
+                        "sender": msg.sender,
+                        "expires_at": expires_at.to_rfc3339(),
+                    }),
+                );
+                format!(
+                    "One-time link code for `{}`: `{issued}`\nSend `/link {issued}` from the other channel before {}.",
+                    user.id,
+                    expires_at.to_rfc3339()
+                )
             }
+            Err(err) => format!("Failed to issue link code: {err}"),
+        };
+    }
 
-            context.push_str(&line);
-            used_chars += line_chars;
-            included += 1;
+    match directory.redeem_link_code(code, &msg.channel, &msg.sender) {
+        Ok(user) => {
+            runtime_trace::record_event(
+                "user_identity_linked",
+                Some(msg.channel.as_str()),
+                None,
+                None,
+                None,
+                Some(true),
+                None,
+                serde_json::json!({
+                    "user_id": user.id,
+                    "sender": msg.sender,
+                }),
+            );
+            format!(
+                "Linked `{}:{}` to user `{}` (role `{}`).",
+                msg.channel, msg.sender, user.id, user.role
+            )
         }
-
-        if included > 0 {
-            context.push('\n');
+        Err(err) => {
+            runtime_trace::record_event(
+                "user_identity_linked",
+                Some(msg.channel.as_str()),
+                None,
+                None,
+                None,
+                Some(false),
+                Some("link code rejected"),
+                serde_json::json!({
+                    "sender": msg.sender,
+                }),
+            );
+            format!("Identity link failed: {err}")
         }
     }
-
-    context
 }
 
-/// Extract a compact summary of tool interactions from history messages added
-/// during `run_tool_call_loop`. Scans assistant messages for `<tool_call>` tags
-/// or native tool-call JSON to collect tool names used.
-/// Returns an empty string when no tools were invoked.
-fn extract_tool_context_summary(history: &[ChatMessage], start_index: usize) -> String {
-    fn push_unique_tool_name(tool_names: &mut Vec<String>, name: &str) {
-        let candidate = name.trim();
-        if candidate.is_empty() {
-            return;
-        }
-        if !tool_names.iter().any(|existing| existing == candidate) {
-            tool_names.push(candidate.to_string());
-        }
+async fn handle_dlp_release_command(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+    id: &str,
+) -> String {
+    let id = id.trim();
+    if id.is_empty() {
+        return "Usage: `/dlp-release <id>` with the id from a withheld-response notice."
+            .to_string();
     }
+    let Some(held) = dlp::release_held(id) else {
+        return format!("No withheld response `{id}` is pending (it may have expired or already been released).");
+    };
+    let Some(target) = ctx.channels_by_name.get(&held.channel).cloned() else {
+        return format!(
+            "Withheld response `{id}` targets channel `{}`, which is not running.",
+            held.channel
+        );
+    };
 
-    fn collect_tool_names_from_tool_call_tags(content: &str, tool_names: &mut Vec<String>) {
-        const TAG_PAIRS: [(&str, &str); 4] = [
-            ("<tool_call>", "</tool_call>"),
-            ("<toolcall>", "</toolcall>"),
+    let delivered = target
+        .send(
+            &SendMessage::new(&held.content, &held.reply_target).in_thread(held.thread_ts.clone()),
+        )
+        .await;
+    runtime_trace::record_event(
+        "dlp_hold_released",
+        Some(held.channel.as_str()),
+        None,
+        None,
+        None,
+        Some(delivered.is_ok()),
+        delivered.as_ref().err().map(|_| "delivery failed"),
+        serde_json::json!({
+            "id": held.id,
+            "approver": msg.sender,
+            "approver_channel": msg.channel,
+            "detectors": held.detectors,
+        }),
+    );
+    match delivered {
+        Ok(()) => format!(
+            "Released withheld response `{id}` to `{}` ({}).",
+            held.channel, held.reply_target
+        ),
+        Err(err) => format!("Failed to deliver withheld response `{id}`: {err}"),
+    }
+}
+
+/// Remember a `/models` or `/model` switch as the bound session's override.
+fn persist_bound_session_route(
+    ctx: &ChannelRuntimeContext,
+    sender_key: &str,
+    current: &ChannelRouteSelection,
+) {
+    let Some(name) = bound_session(sender_key) else {
+        return;
+    };
+    let result = SessionStore::new(&ctx.workspace_dir).update(&name, |stored| {
+        stored.provider = Some(current.provider.clone());
+        stored.model = Some(current.model.clone());
+    });
+    if let Err(err) = result {
+        tracing::warn!("Failed to persist route for session `{name}`: {err}");
+    }
+}
+
+fn handle_session_command(
+    ctx: &ChannelRuntimeContext,
+    sender_key: &str,
+    current: &mut ChannelRouteSelection,
+    args: &str,
+) -> String {
+    let command = match session::parse_command(args) {
+        Ok(command) => command,
+        Err(usage) => return usage,
+    };
+    let store = SessionStore::new(&ctx.workspace_dir);
+    let bound = bound_session(sender_key);
+    let bind = |next: &session::NamedSession, current: &mut ChannelRouteSelection| {
+        session_bindings()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(sender_key.to_string(), next.name.clone());
+        ctx.conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(sender_key.to_string(), next.chat_turns(MAX_CHANNEL_HISTORY));
+        if next.provider.is_some() || next.model.is_some() {
+            if let Some(provider) = &next.provider {
+                current.provider.clone_from(provider);
+            }
+            if let Some(model) = &next.model {
+                current.model.clone_from(model);
+            }
+            set_route_selection(ctx, sender_key, current.clone());
+        }
+    };
+
+    let result = match command {
+        SessionCommand::Show => match bound.as_deref() {
+            Some(name) => store.open(name).map(|stored| stored.describe()),
+            None => Ok(
+                "No named session is bound to this conversation. Use `/session switch <name>`."
+                    .to_string(),
+            ),
+        },
+        SessionCommand::List => store
+            .list()
+            .map(|sessions| session::format_list(&sessions, bound.as_deref())),
+        SessionCommand::Switch(name) => store.open(&name).map(|next| {
+            bind(&next, current);
+            format!(
+                "Switched to session `{}` ({} messages, {}/{}).",
+                next.name,
+                next.history.len(),
+                current.provider,
+                current.model
+            )
+        }),
+        SessionCommand::Fork(name) => {
+            let source = match bound.as_deref() {
+                Some(bound) => store.open(bound),
+                None => {
+                    let mut unnamed = session::NamedSession::new(&name);
+                    if let Some(turns) = ctx
+                        .conversation_histories
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .get(sender_key)
+                    {
+                        unnamed.append(turns);
+                    }
+                    Ok(unnamed)
+                }
+            };
+            source
+                .and_then(|source| store.fork(&source, &name))
+                .map(|fork| {
+                    bind(&fork, current);
+                    format!("Forked into session `{}`.", fork.name)
+                })
+        }
+        SessionCommand::Delete(name) => {
+            if bound.as_deref() == Some(name.as_str()) {
+                return "Cannot delete the session bound to this conversation; use `/new` or switch away first.".to_string();
+            }
+            store.delete(&name).map(|deleted| {
+                if !deleted {
+                    return format!("No session named `{name}`.");
+                }
+                session_bindings()
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .retain(|_, bound| *bound != name);
+                format!("Deleted session `{name}`.")
+            })
+        }
+        SessionCommand::Tools(allowed) => match bound.as_deref() {
+            Some(name) => store.update(name, |stored| {
+                stored.allowed_tools = allowed;
+                stored.describe()
+            }),
+            None => Ok(
+                "No named session is bound to this conversation. Use `/session switch <name>`."
+                    .to_string(),
+            ),
+        },
+    };
+    result.unwrap_or_else(|err| format!("⚠️ {err}"))
+}
+
+/// Plan reviews are answered by the sender who requested the plan, so they
+/// bypass the approver checks used for tool approvals.
+fn handle_plan_review_command(
+    msg: &traits::ChannelMessage,
+    command: ChannelRuntimeCommand,
+) -> String {
+    let (request_id, decision) = match command {
+        ChannelRuntimeCommand::ApprovePlan(raw) => (raw.trim().to_string(), PlanDecision::Approve),
+        ChannelRuntimeCommand::RejectPlan(raw) => (raw.trim().to_string(), PlanDecision::Reject),
+        ChannelRuntimeCommand::EditPlan(raw) => {
+            let raw = raw.trim();
+            let (request_id, changes) = raw.split_once(char::is_whitespace).unwrap_or((raw, ""));
+            if changes.trim().is_empty() {
+                return "Usage: `/plan-edit <plan-id> <requested changes>`".to_string();
+            }
+            (
+                request_id.to_string(),
+                PlanDecision::Edit(changes.trim().to_string()),
+            )
+        }
+        _ => return String::new(),
+    };
+    if request_id.is_empty() {
+        return "Usage: `/approve-allow <plan-id>` or `/approve-deny <plan-id>`".to_string();
+    }
+
+    let action = match &decision {
+        PlanDecision::Approve => "Approved",
+        PlanDecision::Edit(_) => "Re-planning with your changes for",
+        PlanDecision::Reject => "Rejected",
+    };
+    match plan::resolve_review(&request_id, &msg.channel, &msg.sender, decision) {
+        Ok(()) => format!("{action} plan `{request_id}`."),
+        Err(err) => err,
+    }
+}
+
+/// Quorum commands are authorized per rule by the quorum store rather than by
+/// `autonomy.non_cli_approval_approvers`, so approver groups can differ.
+fn handle_quorum_command(msg: &traits::ChannelMessage, command: ChannelRuntimeCommand) -> String {
+    let Some(store) = approval_quorum::runtime_store() else {
+        return "Approval quorum is disabled (`[security.approval_quorum].enabled = false`)."
+            .to_string();
+    };
+
+    let (action, result) = match command {
+        ChannelRuntimeCommand::ListQuorumRequests => {
+            if !store.is_approver(&msg.channel, &msg.sender) {
+                return "Only members of a quorum approver group can list quorum requests."
+                    .to_string();
+            }
+            let pending = store.pending();
+            if pending.is_empty() {
+                return "No quorum requests are pending.".to_string();
+            }
+            let lines: Vec<String> = pending
+                .iter()
+                .map(|request| format!("- {}", approval_quorum::describe_request(request)))
+                .collect();
+            return format!("Pending quorum requests:\n{}", lines.join("\n"));
+        }
+        ChannelRuntimeCommand::ApproveQuorumRequest(raw) => {
+            let mut parts = raw.split_whitespace();
+            let Some(request_id) = parts.next() else {
+                return "Usage: `/quorum-approve <request-id> [otp]`".to_string();
+            };
+            (
+                "approve",
+                store.approve(request_id, &msg.channel, &msg.sender, parts.next()),
+            )
+        }
+        ChannelRuntimeCommand::DenyQuorumRequest(raw) => {
+            let request_id = raw.trim();
+            if request_id.is_empty() {
+                return "Usage: `/quorum-deny <request-id>`".to_string();
+            }
+            ("deny", store.deny(request_id, &msg.channel, &msg.sender))
+        }
+        _ => return String::new(),
+    };
+
+    runtime_trace::record_event(
+        "approval_quorum_command",
+        Some(msg.channel.as_str()),
+        None,
+        None,
+        None,
+        Some(result.is_ok()),
+        result.as_ref().err().map(|_| "quorum command rejected"),
+        serde_json::json!({
+            "action": action,
+            "sender": msg.sender,
+            "request_id": result.as_ref().ok().map(|request| request.id.clone()),
+        }),
+    );
+    match result {
+        Ok(request) => match request.status {
+            QuorumStatus::Approved => format!(
+                "Quorum reached for `{}` ({}/{}). The `{}` operation may now run once; retry it before {}.",
+                request.id,
+                request.approvals.len(),
+                request.required,
+                request.operation,
+                request.expires_at
+            ),
+            QuorumStatus::Denied => format!(
+                "Denied quorum request `{}` for `{}`.",
+                request.id, request.operation
+            ),
+            _ => format!(
+                "Recorded approval for `{}` ({}/{}); {} more needed.",
+                request.id,
+                request.approvals.len(),
+                request.required,
+                request.remaining()
+            ),
+        },
+        Err(err) => format!("Quorum {action} failed: {err}"),
+    }
+}
+
+async fn build_memory_context(
+    mem: &dyn Memory,
+    user_msg: &str,
+    min_relevance_score: f64,
+    session_id: Option<&str>,
+) -> String {
+    let mut context = String::new();
+
+    if let Ok(entries) = mem.recall(user_msg, 5, session_id).await {
+        let mut included = 0usize;
+        let mut used_chars = 0usize;
+
+        for entry in entries.iter().filter(|e| match e.score {
+            Some(score) => score >= min_relevance_score,
+            None => true, // keep entries without a score (e.g. non-vector backends)
+        }) {
+            if included >= MEMORY_CONTEXT_MAX_ENTRIES {
+                break;
+            }
+
+            if should_skip_memory_context_entry(&entry.key, &entry.content) {
+                continue;
+            }
+
+            let content = if entry.content.chars().count() > MEMORY_CONTEXT_ENTRY_MAX_CHARS {
+                truncate_with_ellipsis(&entry.content, MEMORY_CONTEXT_ENTRY_MAX_CHARS)
+            } else {
+                entry.content.clone()
+            };
+
+            let line = format!("- {}: {}\n", entry.key, content);
+            let line_chars = line.chars().count();
+            if used_chars + line_chars > MEMORY_CONTEXT_MAX_CHARS {
+                break;
+            }
+
+            if included == 0 {
+                context.push_str("[Memory context]\n");
+            }
+
+            context.push_str(&line);
+            used_chars += line_chars;
+            included += 1;
+        }
+
+        if included > 0 {
+            context.push('\n');
+        }
+    }
+
+    context
+}
+
+/// Extract a compact summary of tool interactions from history messages added
+/// during `run_tool_call_loop`. Scans assistant messages for `<tool_call>` tags
+/// or native tool-call JSON to collect tool names used.
+/// Returns an empty string when no tools were invoked.
+fn extract_tool_context_summary(history: &[ChatMessage], start_index: usize) -> String {
+    fn push_unique_tool_name(tool_names: &mut Vec<String>, name: &str) {
+        let candidate = name.trim();
+        if candidate.is_empty() {
+            return;
+        }
+        if !tool_names.iter().any(|existing| existing == candidate) {
+            tool_names.push(candidate.to_string());
+        }
+    }
+
+    fn collect_tool_names_from_tool_call_tags(content: &str, tool_names: &mut Vec<String>) {
+        const TAG_PAIRS: [(&str, &str); 4] = [
+            ("<tool_call>", "</tool_call>"),
+            ("<toolcall>", "</toolcall>"),
             ("<tool-call>", "</tool-call>"),
             ("<invoke>", "</invoke>"),
         ];
@@ -2851,6 +3652,59 @@ pub(crate) fn sanitize_channel_response(
     }
 }
 
+/// Run a sanitized channel reply through `[security.dlp]`. Blocked replies are
+/// replaced with a notice; `require-approval` replies are delivered redacted
+/// and the original is held for `/dlp-release`.
+fn apply_outbound_dlp(
+    msg: &traits::ChannelMessage,
+    provider: &str,
+    model: &str,
+    response: String,
+) -> String {
+    let outcome = dlp::apply(DlpSurface::ChannelMessage, Some(&msg.channel), &response);
+    if outcome.is_clean() {
+        return response;
+    }
+
+    let detectors = outcome.detector_names();
+    runtime_trace::record_event(
+        "channel_message_outbound_dlp",
+        Some(msg.channel.as_str()),
+        Some(provider),
+        Some(model),
+        None,
+        Some(!outcome.is_withheld()),
+        Some("Outbound response matched security.dlp detectors"),
+        serde_json::json!({
+            "sender": msg.sender,
+            "detectors": detectors,
+            "action": outcome.action.map(dlp::action_label),
+        }),
+    );
+
+    match outcome.action {
+        Some(crate::config::DlpAction::Block) => format!(
+            "I withheld my response because it matched data-loss-prevention rules ({}). Please ask for a summary without the sensitive details.",
+            detectors.join(", ")
+        ),
+        Some(crate::config::DlpAction::RequireApproval) => {
+            let id = dlp::hold_for_release(
+                &msg.channel,
+                &msg.reply_target,
+                msg.thread_ts.clone(),
+                response,
+                detectors.clone(),
+            );
+            format!(
+                "{}\n\n(Parts of this response were withheld by data-loss-prevention rules: {}. An approver can release the original with `/dlp-release {id}`.)",
+                outcome.content,
+                detectors.join(", ")
+            )
+        }
+        _ => outcome.content,
+    }
+}
+
 fn is_tool_call_payload(value: &serde_json::Value, known_tool_names: &HashSet<String>) -> bool {
     let Some(object) = value.as_object() else {
         return false;
@@ -3128,13 +3982,15 @@ fn spawn_scoped_typing_task(
 
 async fn process_channel_message(
     ctx: Arc<ChannelRuntimeContext>,
-    msg: traits::ChannelMessage,
+    mut msg: traits::ChannelMessage,
     cancellation_token: CancellationToken,
 ) {
     if cancellation_token.is_cancelled() {
         return;
     }
 
+    attachments::materialize_inbound(&mut msg).await;
+
     println!(
         "  💬 [{}] from {}: {}",
         msg.channel,
@@ -3225,10 +4081,14 @@ or tune thresholds in config.",
 
     let history_key = conversation_history_key(&msg);
     // Try classification first, fall back to sender/default route
-    let route = classify_message_route(ctx.as_ref(), &msg.content)
-        .unwrap_or_else(|| get_route_selection(ctx.as_ref(), &history_key));
+    let classified_route = classify_message_route(ctx.as_ref(), &msg.content);
+    let cascade_route = classified_route.is_none();
+    let route = classified_route.unwrap_or_else(|| get_route_selection(ctx.as_ref(), &history_key));
     let runtime_defaults = runtime_defaults_snapshot(ctx.as_ref());
     let active_provider = match get_or_create_provider(ctx.as_ref(), &route.provider).await {
+        Ok(provider) if cascade_route => {
+            cascade_default_route(ctx.as_ref(), &route, provider).await
+        }
         Ok(provider) => provider,
         Err(err) => {
             let safe_err = providers::sanitize_api_error(&err.to_string());
@@ -3247,6 +4107,10 @@ or tune thresholds in config.",
             return;
         }
     };
+    let user_directory = ctx.approval_manager.user_directory();
+    let memory_session_id = user_directory
+        .as_ref()
+        .and_then(|directory| directory.memory_session_id(&msg.channel, &msg.sender));
     if ctx.auto_save_memory && msg.content.chars().count() >= AUTOSAVE_MIN_MESSAGE_CHARS {
         let autosave_key = conversation_memory_key(&msg);
         let _ = ctx
@@ -3255,7 +4119,7 @@ or tune thresholds in config.",
                 &autosave_key,
                 &msg.content,
                 crate::memory::MemoryCategory::Conversation,
-                None,
+                memory_session_id.as_deref(),
             )
             .await;
     }
@@ -3276,12 +4140,17 @@ or tune thresholds in config.",
     let timestamped_content = format!("[{now}] {}", msg.content);
     let persisted_user_content = msg.content.clone();
 
+    for result in take_background_results(&msg.channel, &msg.reply_target) {
+        append_sender_turn(ctx.as_ref(), &history_key, ChatMessage::assistant(result));
+    }
+
     // Preserve user turn before the LLM call so interrupted requests keep context.
     append_sender_turn(
         ctx.as_ref(),
         &history_key,
         ChatMessage::user(&persisted_user_content),
     );
+    track_user_turn(&msg, &history_key, &persisted_user_content);
 
     // Build history from per-sender conversation cache.
     let prior_turns_raw = ctx
@@ -3311,6 +4180,7 @@ or tune thresholds in config.",
                     ctx.memory.as_ref(),
                     &msg.content,
                     ctx.min_relevance_score,
+                    memory_session_id.as_deref(),
                 )
                 .await;
                 if !memory_context.is_empty() {
@@ -3322,11 +4192,44 @@ or tune thresholds in config.",
 
     let expose_internal_tool_details =
         msg.channel == "cli" || should_expose_internal_tool_details(&msg.content);
-    let excluded_tools_snapshot = if msg.channel == "cli" {
+    let mut excluded_tools_snapshot = if msg.channel == "cli" {
         Vec::new()
     } else {
         snapshot_non_cli_excluded_tools(ctx.as_ref())
     };
+    if msg.channel != "cli" {
+        if let Some(directory) = user_directory.as_ref() {
+            let role_denied = directory.denied_tools(
+                &msg.channel,
+                &msg.sender,
+                ctx.tools_registry.iter().map(|tool| tool.name()),
+            );
+            for tool_name in role_denied {
+                if !excluded_tools_snapshot.contains(&tool_name) {
+                    excluded_tools_snapshot.push(tool_name);
+                }
+            }
+        }
+    }
+    let active_session = bound_session(&history_key).map(|name| session::ActiveSession {
+        store: SessionStore::new(&ctx.workspace_dir),
+        name,
+    });
+    if let Some(active) = active_session.as_ref() {
+        match active.store.load(&active.name) {
+            Ok(Some(stored)) => {
+                for tool_name in
+                    stored.excluded_tools(ctx.tools_registry.iter().map(|tool| tool.name()))
+                {
+                    if !excluded_tools_snapshot.contains(&tool_name) {
+                        excluded_tools_snapshot.push(tool_name);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!("Failed to load session `{}`: {err}", active.name),
+        }
+    }
     let mut system_prompt = build_channel_system_prompt(
         ctx.system_prompt.as_str(),
         &msg.channel,
@@ -3442,13 +4345,24 @@ or tune thresholds in config.",
         Cancelled,
     }
 
-    let timeout_budget_secs =
+    // `/plan <task>` (or `[agent.plan_mode].auto`) runs a reviewed plan step by
+    // step instead of a single tool loop; the review wait extends the budget.
+    let plan_task = target_channel
+        .as_ref()
+        .and_then(|channel| Some((channel, plan::plan_request(&ctx.plan_mode, &msg.content)?)));
+    let mut timeout_budget_secs =
         channel_message_timeout_budget_secs(ctx.message_timeout_secs, ctx.max_tool_iterations);
-    let llm_result = tokio::select! {
-        () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
-        result = tokio::time::timeout(
-            Duration::from_secs(timeout_budget_secs),
-            run_tool_call_loop_with_reply_target(
+    if plan_task.is_some() {
+        let reviews = u64::try_from(ctx.plan_mode.max_replans)
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        timeout_budget_secs = timeout_budget_secs
+            .saturating_mul(reviews)
+            .saturating_add(ctx.plan_mode.approval_timeout_secs.saturating_mul(reviews));
+    }
+    let tool_loop = async {
+        let Some((channel, task)) = plan_task else {
+            return run_tool_call_loop_with_reply_target(
                 active_provider.as_ref(),
                 &mut history,
                 ctx.tools_registry.as_ref(),
@@ -3466,6 +4380,45 @@ or tune thresholds in config.",
                 delta_tx,
                 ctx.hooks.as_deref(),
                 &excluded_tools_snapshot,
+            )
+            .await;
+        };
+        let reviewer = plan::ChannelPlanReviewer {
+            channel: Arc::clone(channel),
+            sender: msg.sender.clone(),
+            reply_target: msg.reply_target.clone(),
+            thread_ts: msg.thread_ts.clone(),
+            timeout: Duration::from_secs(ctx.plan_mode.approval_timeout_secs),
+        };
+        let execution = plan::PlanExecution {
+            provider: active_provider.as_ref(),
+            tools_registry: ctx.tools_registry.as_ref(),
+            observer: ctx.observer.as_ref(),
+            provider_name: route.provider.as_str(),
+            model: route.model.as_str(),
+            temperature: runtime_defaults.temperature,
+            silent: true,
+            approval: Some(ctx.approval_manager.as_ref()),
+            channel_name: msg.channel.as_str(),
+            multimodal_config: &ctx.multimodal,
+            max_tool_iterations: ctx.max_tool_iterations,
+            cancellation_token: Some(cancellation_token.clone()),
+            hooks: ctx.hooks.as_deref(),
+            excluded_tools: &excluded_tools_snapshot,
+        };
+        with_reply_target(
+            Some(msg.reply_target.as_str()),
+            plan::run_plan_mode(&execution, &ctx.plan_mode, &mut history, task, &reviewer),
+        )
+        .await
+    };
+    let llm_result = tokio::select! {
+        () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
+        result = tokio::time::timeout(
+            Duration::from_secs(timeout_budget_secs),
+            transcript::scope(
+                history_key.clone(),
+                session::scope(active_session.clone(), tool_loop),
             ),
         ) => LlmExecutionResult::Completed(result),
     };
@@ -3604,6 +4557,12 @@ or tune thresholds in config.",
                     "I blocked part of my draft response because it appeared to contain credential material. Please ask me to provide a redacted summary.".to_string()
                 }
             };
+            let delivered_response = apply_outbound_dlp(
+                &msg,
+                route.provider.as_str(),
+                route.model.as_str(),
+                delivered_response,
+            );
             runtime_trace::record_event(
                 "channel_message_outbound",
                 Some(msg.channel.as_str()),
@@ -3634,6 +4593,18 @@ or tune thresholds in config.",
                 &history_key,
                 ChatMessage::assistant(&history_response),
             );
+            if let Some(active) = active_session.as_ref() {
+                let turn = [
+                    ChatMessage::user(&persisted_user_content),
+                    ChatMessage::assistant(&history_response),
+                ];
+                if let Err(err) = active
+                    .store
+                    .update(&active.name, |stored| stored.append(&turn))
+                {
+                    tracing::warn!("Failed to persist session `{}`: {err}", active.name);
+                }
+            }
             println!(
                 "  🤖 Reply ({}ms): {}",
                 started_at.elapsed().as_millis(),
@@ -3641,26 +4612,49 @@ or tune thresholds in config.",
             );
             if let Some(channel) = target_channel.as_ref() {
                 if let Some(ref draft_id) = draft_message_id {
-                    if let Err(e) = channel
+                    match channel
                         .finalize_draft(&msg.reply_target, draft_id, &delivered_response)
                         .await
                     {
-                        tracing::warn!("Failed to finalize draft: {e}; sending as new message");
-                        let _ = channel
-                            .send(
-                                &SendMessage::new(&delivered_response, &msg.reply_target)
-                                    .in_thread(msg.thread_ts.clone()),
+                        Ok(()) => track_reply(&msg, std::slice::from_ref(draft_id)),
+                        Err(e) => {
+                            tracing::warn!("Failed to finalize draft: {e}; sending as new message");
+                            let (sent, reply_ids) = traits::record_sent_messages(
+                                channel.send(
+                                    &SendMessage::new(&delivered_response, &msg.reply_target)
+                                        .in_thread(msg.thread_ts.clone()),
+                                ),
                             )
                             .await;
+                            if sent.is_ok() {
+                                track_reply(&msg, &reply_ids);
+                            }
+                        }
+                    }
+                } else {
+                    let spoken = match ctx.voice_replies.as_ref() {
+                        Some(voice) => {
+                            voice
+                                .send_voice_reply(channel.as_ref(), &msg, &delivered_response)
+                                .await
+                        }
+                        None => false,
+                    };
+                    if !spoken {
+                        let (sent, reply_ids) =
+                            traits::record_sent_messages(attachments::deliver_reply(
+                                channel.as_ref(),
+                                SendMessage::new(delivered_response, &msg.reply_target)
+                                    .in_thread(msg.thread_ts.clone()),
+                            ))
+                            .await;
+                        match sent {
+                            Ok(()) => track_reply(&msg, &reply_ids),
+                            Err(e) => {
+                                eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+                            }
+                        }
                     }
-                } else if let Err(e) = channel
-                    .send(
-                        &SendMessage::new(delivered_response, &msg.reply_target)
-                            .in_thread(msg.thread_ts.clone()),
-                    )
-                    .await
-                {
-                    eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
                 }
             }
         }
@@ -3886,6 +4880,134 @@ or tune thresholds in config.",
     }
 }
 
+/// Apply an inbound edit or deletion to the turn it refers to. Returns the
+/// edited message when its in-flight turn was cancelled and should be run
+/// again with the new text.
+async fn handle_message_revision(
+    ctx: &ChannelRuntimeContext,
+    in_flight_by_message: &tokio::sync::Mutex<HashMap<String, InFlightSenderTaskState>>,
+    mut msg: traits::ChannelMessage,
+) -> Option<traits::ChannelMessage> {
+    if !ctx.message_edits.enabled {
+        return None;
+    }
+
+    let deleted = msg.event == traits::ChannelEvent::Deleted;
+    let action = if deleted {
+        crate::config::InFlightEditAction::Cancel
+    } else {
+        ctx.message_edits.in_flight
+    };
+    let in_flight = in_flight_by_message
+        .lock()
+        .await
+        .get(&message_revision_key(&msg))
+        .cloned();
+    let was_in_flight = in_flight.is_some();
+    if let Some(state) = in_flight {
+        if action != crate::config::InFlightEditAction::Ignore {
+            tracing::info!(
+                channel = %msg.channel,
+                message_id = %msg.id,
+                "Cancelling in-flight request for revised message"
+            );
+            state.cancellation.cancel();
+        }
+        state.completion.wait().await;
+    }
+
+    runtime_trace::record_event(
+        if deleted {
+            "channel_message_deleted"
+        } else {
+            "channel_message_edited"
+        },
+        Some(msg.channel.as_str()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        serde_json::json!({
+            "sender": msg.sender,
+            "message_id": msg.id,
+            "reply_target": msg.reply_target,
+            "was_in_flight": was_in_flight,
+            "content_preview": truncate_with_ellipsis(&msg.content, 160),
+        }),
+    );
+
+    if deleted {
+        let Some(turn) = forget_tracked_turn(&msg) else {
+            tracing::debug!(
+                channel = %msg.channel,
+                message_id = %msg.id,
+                "Ignoring deletion of untracked message"
+            );
+            return None;
+        };
+        revise_history_turn(ctx, &turn.history_key, &turn.content, None);
+        let _ = ctx.memory.forget(&turn.memory_key).await;
+        if ctx.message_edits.delete_replies {
+            if let Some(channel) = ctx
+                .channels_by_name
+                .get(&msg.channel)
+                .filter(|channel| channel.supports_message_edits())
+            {
+                for reply_id in &turn.reply_ids {
+                    if let Err(e) = channel.delete_message(&msg.reply_target, reply_id).await {
+                        tracing::warn!("Failed to delete reply on {}: {e}", channel.name());
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    let Some(turn) = tracked_turn(&msg) else {
+        tracing::debug!(
+            channel = %msg.channel,
+            message_id = %msg.id,
+            "Ignoring edit of untracked message"
+        );
+        return None;
+    };
+    if msg.content.trim().is_empty() || msg.content == turn.content {
+        return None;
+    }
+
+    if was_in_flight && action == crate::config::InFlightEditAction::Rerun {
+        revise_history_turn(ctx, &turn.history_key, &turn.content, None);
+        forget_tracked_turn(&msg);
+        msg.event = traits::ChannelEvent::Message;
+        return Some(msg);
+    }
+
+    revise_history_turn(ctx, &turn.history_key, &turn.content, Some(&msg.content));
+    track_user_turn(&msg, &turn.history_key, &msg.content);
+    track_reply(&msg, &turn.reply_ids);
+    if ctx.auto_save_memory {
+        let _ = ctx.memory.forget(&turn.memory_key).await;
+        if msg.content.chars().count() >= AUTOSAVE_MIN_MESSAGE_CHARS {
+            let memory_session_id = ctx
+                .approval_manager
+                .user_directory()
+                .as_ref()
+                .and_then(|directory| directory.memory_session_id(&msg.channel, &msg.sender));
+            let _ = ctx
+                .memory
+                .store(
+                    &turn.memory_key,
+                    &msg.content,
+                    crate::memory::MemoryCategory::Conversation,
+                    memory_session_id.as_deref(),
+                )
+                .await;
+        }
+    }
+    None
+}
+
 async fn run_message_dispatch_loop(
     mut rx: tokio::sync::mpsc::Receiver<traits::ChannelMessage>,
     ctx: Arc<ChannelRuntimeContext>,
@@ -3897,6 +5019,10 @@ async fn run_message_dispatch_loop(
         String,
         InFlightSenderTaskState,
     >::new()));
+    let in_flight_by_message = Arc::new(tokio::sync::Mutex::new(HashMap::<
+        String,
+        InFlightSenderTaskState,
+    >::new()));
     let task_sequence = Arc::new(AtomicU64::new(1));
 
     while let Some(msg) = rx.recv().await {
@@ -3907,15 +5033,33 @@ async fn run_message_dispatch_loop(
 
         let worker_ctx = Arc::clone(&ctx);
         let in_flight = Arc::clone(&in_flight_by_sender);
+        let in_flight_by_message = Arc::clone(&in_flight_by_message);
         let task_sequence = Arc::clone(&task_sequence);
         workers.spawn(async move {
             let _permit = permit;
+            let msg = if msg.event == traits::ChannelEvent::Message {
+                msg
+            } else {
+                match handle_message_revision(&worker_ctx, &in_flight_by_message, msg).await {
+                    Some(rerun) => rerun,
+                    None => return,
+                }
+            };
             let interrupt_enabled =
                 worker_ctx.interrupt_on_new_message && msg.channel == "telegram";
             let sender_scope_key = interruption_scope_key(&msg);
             let cancellation_token = CancellationToken::new();
             let completion = Arc::new(InFlightTaskCompletion::new());
             let task_id = task_sequence.fetch_add(1, Ordering::Relaxed);
+            let revision_key = message_revision_key(&msg);
+            in_flight_by_message.lock().await.insert(
+                revision_key.clone(),
+                InFlightSenderTaskState {
+                    task_id,
+                    cancellation: cancellation_token.clone(),
+                    completion: Arc::clone(&completion),
+                },
+            );
 
             if interrupt_enabled {
                 let previous = {
@@ -3943,6 +5087,16 @@ async fn run_message_dispatch_loop(
 
             process_channel_message(worker_ctx, msg, cancellation_token).await;
 
+            {
+                let mut active = in_flight_by_message.lock().await;
+                if active
+                    .get(&revision_key)
+                    .is_some_and(|state| state.task_id == task_id)
+                {
+                    active.remove(&revision_key);
+                }
+            }
+
             if interrupt_enabled {
                 let mut active = in_flight.lock().await;
                 if active
@@ -4610,7 +5764,12 @@ fn collect_configured_channels(
                 )
                 .with_group_reply_allowed_senders(mm.group_reply_allowed_sender_ids())
                 .with_bot_peer_usernames(
-                    config.team.bots.iter().map(|b| b.username.clone()).collect(),
+                    config
+                        .team
+                        .bots
+                        .iter()
+                        .map(|b| b.username.clone())
+                        .collect(),
                 ),
             ),
         });
@@ -4752,6 +5911,21 @@ fn collect_configured_channels(
         });
     }
 
+    if let Some(callbacks) = config
+        .channels_config
+        .webhook
+        .as_ref()
+        .and_then(|webhook| webhook.callbacks.as_ref())
+    {
+        channels.push(ConfiguredChannel {
+            display_name: "Webhook",
+            channel: Arc::new(WebhookChannel::new(
+                callbacks.clone(),
+                &config.workspace_dir,
+            )),
+        });
+    }
+
     if let Some(ref email_cfg) = config.channels_config.email {
         channels.push(ConfiguredChannel {
             display_name: "Email",
@@ -4777,6 +5951,20 @@ fn collect_configured_channels(
         });
     }
 
+    if let Some(ref xmpp_cfg) = config.channels_config.xmpp {
+        channels.push(ConfiguredChannel {
+            display_name: "XMPP",
+            channel: Arc::new(XmppChannel::new(xmpp_cfg.clone())),
+        });
+    }
+
+    if let Some(ref zulip_cfg) = config.channels_config.zulip {
+        channels.push(ConfiguredChannel {
+            display_name: "Zulip",
+            channel: Arc::new(ZulipChannel::new(zulip_cfg.clone())),
+        });
+    }
+
     #[cfg(feature = "channel-lark")]
     if let Some(ref lk) = config.channels_config.lark {
         if lk.use_feishu {
@@ -4948,6 +6136,11 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
 pub async fn start_channels(config: Config) -> Result<()> {
     // Ensure stale channel handles are never reused across restarts.
     clear_live_channels();
+    crate::security::cancellation::spawn_watcher();
+    attachments::init(
+        &config.workspace_dir,
+        config.channels_config.attachment_max_download_mb,
+    );
 
     let provider_name = resolved_default_provider(&config);
     let provider_runtime_options = providers::ProviderRuntimeOptions {
@@ -4997,6 +6190,8 @@ pub async fn start_channels(config: Config) -> Result<()> {
 
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
+    dlp::set_runtime_observer(observer.clone());
+    span::set_runtime_observer(observer.clone());
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let security = Arc::new(SecurityPolicy::from_config(
@@ -5216,9 +6411,19 @@ pub async fn start_channels(config: Config) -> Result<()> {
         println!();
     }
 
+    let outbound_settings = &config.channels_config.outbound;
+    let outbound_store = (outbound_settings.enabled && outbound_settings.persist)
+        .then(|| outbound::OutboundStore::shared(&config.workspace_dir));
     let channels: Vec<Arc<dyn Channel>> = configured_channels
         .into_iter()
-        .map(|configured| configured.channel)
+        .map(|configured| {
+            outbound::QueuedChannel::wrap(
+                configured.channel,
+                outbound_settings,
+                outbound_store.clone(),
+                Arc::clone(&observer),
+            )
+        })
         .collect();
 
     println!("🦀 ZeroClaw Channel Server");
@@ -5277,6 +6482,17 @@ pub async fn start_channels(config: Config) -> Result<()> {
             .collect::<HashMap<_, _>>(),
     );
     register_live_channels(channels_by_name.as_ref());
+    if let Some(store) = &outbound_store {
+        let replayed = outbound::replay_pending(
+            store,
+            channels_by_name.as_ref(),
+            outbound_settings.max_age_secs,
+        )
+        .await;
+        if replayed > 0 {
+            println!("  📤 Resending {replayed} undelivered message(s)");
+        }
+    }
     let max_in_flight_messages = compute_max_in_flight_messages(channels.len());
 
     println!("  🚦 In-flight message limit: {max_in_flight_messages}");
@@ -5291,6 +6507,23 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .as_ref()
         .is_some_and(|tg| tg.interrupt_on_new_message);
 
+    let user_directory = if config.security.users.enabled {
+        let config_dir = config
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        let directory = UserDirectory::load(&config.security, config_dir)
+            .context("Failed to load [security.users] directory")?;
+        println!(
+            "  👥 User directory: {} ({} users)",
+            directory.state_path().display(),
+            directory.list().len()
+        );
+        Some(Arc::new(directory))
+    } else {
+        None
+    };
+
     let runtime_ctx = Arc::new(ChannelRuntimeContext {
         channels_by_name,
         provider: Arc::clone(&provider),
@@ -5314,6 +6547,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         workspace_dir: Arc::new(config.workspace_dir.clone()),
         message_timeout_secs,
         interrupt_on_new_message,
+        plan_mode: config.agent.plan_mode.clone(),
         multimodal: config.multimodal.clone(),
         hooks: if config.hooks.enabled {
             let mut runner = crate::hooks::HookRunner::new();
@@ -5329,9 +6563,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
         )),
         query_classification: config.query_classification.clone(),
         model_routes: config.model_routes.clone(),
+        model_cascade: providers::cascade::CascadePolicy::from_config(&config),
         // Preserve startup perplexity filter config to ensure policy is not weakened
         // when runtime store lookup misses.
         startup_perplexity_filter: config.security.perplexity_filter.clone(),
+        voice_replies: match tts::VoiceReplies::from_config(&config.tts, &config.workspace_dir) {
+            Ok(voice) => voice.map(Arc::new),
+            Err(e) => {
+                tracing::warn!("Voice replies disabled: {e:#}");
+                None
+            }
+        },
+        message_edits: config.channels_config.message_edits.clone(),
         // WASM skill tools are sandboxed by the WASM engine and cannot access the
         // host filesystem, network, or shell. Pre-approve them so they are not
         // denied on non-CLI channels (which have no interactive stdin to prompt).
@@ -5343,7 +6586,9 @@ pub async fn start_channels(config: Config) -> Result<()> {
                     autonomy.auto_approve.push(name);
                 }
             }
-            Arc::new(ApprovalManager::from_config(&autonomy))
+            let manager = ApprovalManager::from_config(&autonomy);
+            manager.set_user_directory(user_directory);
+            Arc::new(manager)
         },
         safety_heartbeat: if config.agent.safety_heartbeat_interval > 0 {
             Some(SafetyHeartbeatConfig {
@@ -5370,6 +6615,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channels::traits::ChannelEvent;
     use crate::memory::{Memory, MemoryCategory, SqliteMemory};
     use crate::observability::NoopObserver;
     use crate::providers::{ChatMessage, Provider};
@@ -5492,6 +6738,94 @@ mod tests {
         assert_eq!(parse_runtime_command("slack", "/models"), None);
     }
 
+    #[test]
+    fn parse_runtime_command_supports_identity_commands_on_all_channels() {
+        assert_eq!(
+            parse_runtime_command("email", "/whoami"),
+            Some(ChannelRuntimeCommand::ShowIdentity)
+        );
+        assert_eq!(
+            parse_runtime_command("slack", "/link"),
+            Some(ChannelRuntimeCommand::LinkIdentity(String::new()))
+        );
+        assert_eq!(
+            parse_runtime_command("telegram", "/link@zeroclaw_bot 3F9A01BC7E"),
+            Some(ChannelRuntimeCommand::LinkIdentity(
+                "3F9A01BC7E".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_runtime_command_supports_quorum_commands_outside_approver_gate() {
+        let command = parse_runtime_command("slack", "/quorum-approve qrm-1a2b3c4d 123456");
+        assert_eq!(
+            command,
+            Some(ChannelRuntimeCommand::ApproveQuorumRequest(
+                "qrm-1a2b3c4d 123456".to_string()
+            ))
+        );
+        // Quorum approver groups are enforced per rule by the quorum store.
+        assert!(!is_approval_management_command(&command.unwrap()));
+        assert_eq!(
+            parse_runtime_command("slack", "/quorum-deny qrm-1a2b3c4d"),
+            Some(ChannelRuntimeCommand::DenyQuorumRequest(
+                "qrm-1a2b3c4d".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_runtime_command("slack", "/quorum-pending"),
+            Some(ChannelRuntimeCommand::ListQuorumRequests)
+        );
+    }
+
+    #[test]
+    fn parse_runtime_command_supports_plan_review_commands() {
+        let command = parse_runtime_command("slack", "/approve-allow plan-1a2b3c4d");
+        assert_eq!(
+            command,
+            Some(ChannelRuntimeCommand::ApprovePlan(
+                "plan-1a2b3c4d".to_string()
+            ))
+        );
+        // Plan reviews are bound to the requesting sender, not the approver list.
+        assert!(!is_approval_management_command(&command.unwrap()));
+        assert_eq!(
+            parse_runtime_command("slack", "/plan-edit plan-1a2b3c4d skip the deploy step"),
+            Some(ChannelRuntimeCommand::EditPlan(
+                "plan-1a2b3c4d skip the deploy step".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_runtime_command("slack", "/approve-deny plan-1a2b3c4d"),
+            Some(ChannelRuntimeCommand::RejectPlan(
+                "plan-1a2b3c4d".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_runtime_command("slack", "/approve-allow apr-1a2b3c4d"),
+            Some(ChannelRuntimeCommand::ApprovePendingRequest(
+                "apr-1a2b3c4d".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_runtime_command("slack", "/plan deploy the site"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_runtime_command_supports_dlp_release_as_approval_command() {
+        let command = parse_runtime_command("discord", "/dlp-release dlp-1a2b3c4d");
+        assert_eq!(
+            command,
+            Some(ChannelRuntimeCommand::ReleaseDlpHold(
+                "dlp-1a2b3c4d".to_string()
+            ))
+        );
+        assert!(is_approval_management_command(&command.unwrap()));
+    }
+
     #[test]
     fn parse_runtime_command_supports_natural_language_approval_intents() {
         assert_eq!(
@@ -5682,6 +7016,7 @@ mod tests {
             api_url: None,
             reliability: Arc::new(crate::config::ReliabilityConfig::default()),
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             provider_runtime_options: providers::ProviderRuntimeOptions::default(),
@@ -5690,9 +7025,12 @@ mod tests {
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         };
 
         assert!(compact_sender_history(&ctx, &sender));
@@ -5736,6 +7074,7 @@ mod tests {
             api_url: None,
             reliability: Arc::new(crate::config::ReliabilityConfig::default()),
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             provider_runtime_options: providers::ProviderRuntimeOptions::default(),
@@ -5744,9 +7083,12 @@ mod tests {
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         };
 
         append_sender_turn(&ctx, &sender, ChatMessage::user("hello"));
@@ -5793,6 +7135,7 @@ mod tests {
             api_url: None,
             reliability: Arc::new(crate::config::ReliabilityConfig::default()),
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             provider_runtime_options: providers::ProviderRuntimeOptions::default(),
@@ -5801,9 +7144,12 @@ mod tests {
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         };
 
         assert!(rollback_orphan_user_turn(&ctx, &sender, "pending"));
@@ -5820,6 +7166,333 @@ mod tests {
         assert_eq!(turns[1].content, "ok");
     }
 
+    fn revision_test_ctx(histories: HashMap<String, Vec<ChatMessage>>) -> ChannelRuntimeContext {
+        ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(histories)),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
+            query_classification: crate::config::QueryClassificationConfig::default(),
+            model_routes: Vec::new(),
+            model_cascade: None,
+            approval_manager: mock_price_approved_manager(),
+            safety_heartbeat: None,
+            startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
+        }
+    }
+
+    fn revision_message(id: &str, content: &str, event: ChannelEvent) -> traits::ChannelMessage {
+        traits::ChannelMessage {
+            id: id.to_string(),
+            sender: "alice".to_string(),
+            reply_target: "chat-1".to_string(),
+            content: content.to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 1,
+            thread_ts: None,
+            attachments: vec![],
+            event,
+        }
+    }
+
+    #[test]
+    fn revise_history_turn_rewrites_and_drops_turns() {
+        let sender = "test-channel_alice".to_string();
+        let mut histories = HashMap::new();
+        histories.insert(
+            sender.clone(),
+            vec![
+                ChatMessage::user("first"),
+                ChatMessage::assistant("reply one"),
+                ChatMessage::user("second"),
+                ChatMessage::assistant("reply two"),
+            ],
+        );
+        let ctx = revision_test_ctx(histories);
+
+        assert!(revise_history_turn(&ctx, &sender, "second", Some("2nd")));
+        assert!(revise_history_turn(&ctx, &sender, "first", None));
+        assert!(!revise_history_turn(&ctx, &sender, "missing", None));
+
+        let histories = ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let turns = &histories[&sender];
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].content, "2nd");
+        assert_eq!(turns[1].content, "reply two");
+    }
+
+    #[test]
+    fn revise_history_turn_cuts_segment_out_of_merged_turn() {
+        let sender = "test-channel_alice".to_string();
+        let mut histories = HashMap::new();
+        histories.insert(
+            sender.clone(),
+            vec![ChatMessage::user("forwarded\n\noops\n\nsummarize")],
+        );
+        let ctx = revision_test_ctx(histories);
+
+        assert!(revise_history_turn(&ctx, &sender, "oops", None));
+
+        let histories = ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        assert_eq!(histories[&sender][0].content, "forwarded\n\nsummarize");
+    }
+
+    #[tokio::test]
+    async fn handle_message_revision_rewrites_and_forgets_completed_turns() {
+        let original = revision_message("rev-completed-1", "book a table", ChannelEvent::Message);
+        let history_key = conversation_history_key(&original);
+        let mut histories = HashMap::new();
+        histories.insert(
+            history_key.clone(),
+            vec![
+                ChatMessage::user("book a table"),
+                ChatMessage::assistant("done"),
+            ],
+        );
+        let ctx = revision_test_ctx(histories);
+        let in_flight = tokio::sync::Mutex::new(HashMap::new());
+        track_user_turn(&original, &history_key, "book a table");
+
+        let edit = revision_message(
+            "rev-completed-1",
+            "book a table for two",
+            ChannelEvent::Edited,
+        );
+        assert!(handle_message_revision(&ctx, &in_flight, edit)
+            .await
+            .is_none());
+        assert_eq!(
+            ctx.conversation_histories.lock().unwrap()[&history_key][0].content,
+            "book a table for two"
+        );
+
+        let delete = revision_message("rev-completed-1", "", ChannelEvent::Deleted);
+        assert!(handle_message_revision(&ctx, &in_flight, delete.clone())
+            .await
+            .is_none());
+        assert!(!ctx
+            .conversation_histories
+            .lock()
+            .unwrap()
+            .contains_key(&history_key));
+        assert!(tracked_turn(&delete).is_none());
+    }
+
+    /// Channel whose sends report platform ids (`m1`, `m2`, ...) and that
+    /// records edits and deletions of them.
+    #[derive(Default)]
+    struct EditableRecordingChannel {
+        sent: AtomicUsize,
+        edits: parking_lot::Mutex<Vec<(String, String)>>,
+        deletes: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for EditableRecordingChannel {
+        fn name(&self) -> &str {
+            "editable"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            // Every 10 characters go out as one platform message.
+            for _ in message.content.as_bytes().chunks(10) {
+                let n = self.sent.fetch_add(1, Ordering::SeqCst) + 1;
+                traits::note_sent_message(format!("m{n}"));
+            }
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn supports_message_edits(&self) -> bool {
+            true
+        }
+
+        async fn edit_message(
+            &self,
+            _recipient: &str,
+            message_id: &str,
+            text: &str,
+        ) -> anyhow::Result<()> {
+            self.edits
+                .lock()
+                .push((message_id.to_string(), text.to_string()));
+            Ok(())
+        }
+
+        async fn delete_message(&self, _recipient: &str, message_id: &str) -> anyhow::Result<()> {
+            self.deletes.lock().push(message_id.to_string());
+            Ok(())
+        }
+    }
+
+    fn editable_message(
+        id: &str,
+        reply_target: &str,
+        event: ChannelEvent,
+    ) -> traits::ChannelMessage {
+        traits::ChannelMessage {
+            channel: "editable".to_string(),
+            reply_target: reply_target.to_string(),
+            ..revision_message(id, "question", event)
+        }
+    }
+
+    async fn send_tracked_reply(
+        channel: &EditableRecordingChannel,
+        msg: &traits::ChannelMessage,
+        reply: &str,
+    ) {
+        track_user_turn(msg, &conversation_history_key(msg), &msg.content);
+        let (sent, reply_ids) = traits::record_sent_messages(attachments::deliver_reply(
+            channel,
+            SendMessage::new(reply, &msg.reply_target),
+        ))
+        .await;
+        sent.unwrap();
+        track_reply(msg, &reply_ids);
+    }
+
+    #[tokio::test]
+    async fn plain_replies_are_deleted_with_the_user_message() {
+        let channel = Arc::new(EditableRecordingChannel::default());
+        let original = editable_message("editable-del-1", "chat-del", ChannelEvent::Message);
+        send_tracked_reply(&channel, &original, "a reply split in three").await;
+        assert_eq!(
+            recent_reply_ids("editable", "chat-del", 1),
+            Some(vec!["m1".to_string(), "m2".to_string(), "m3".to_string()])
+        );
+
+        let mut ctx = revision_test_ctx(HashMap::new());
+        ctx.channels_by_name = Arc::new(HashMap::from([(
+            "editable".to_string(),
+            channel.clone() as Arc<dyn Channel>,
+        )]));
+        ctx.message_edits.delete_replies = true;
+        let in_flight = tokio::sync::Mutex::new(HashMap::new());
+        let delete = editable_message("editable-del-1", "chat-del", ChannelEvent::Deleted);
+        assert!(handle_message_revision(&ctx, &in_flight, delete)
+            .await
+            .is_none());
+
+        assert_eq!(*channel.deletes.lock(), vec!["m1", "m2", "m3"]);
+        assert!(recent_reply_ids("editable", "chat-del", 1).is_none());
+    }
+
+    #[tokio::test]
+    async fn revise_sent_reply_edits_and_deletes_earlier_replies() {
+        let channel = EditableRecordingChannel::default();
+        let first = editable_message("editable-rev-1", "chat-rev", ChannelEvent::Message);
+        let second = editable_message("editable-rev-2", "chat-rev", ChannelEvent::Message);
+        send_tracked_reply(&channel, &first, "short").await;
+        send_tracked_reply(&channel, &second, "a longer second reply").await;
+
+        // Editing the latest reply rewrites its first message and removes the rest.
+        revise_sent_reply(&channel, "chat-rev", 1, Some("fixed"))
+            .await
+            .unwrap();
+        assert_eq!(
+            *channel.edits.lock(),
+            vec![("m2".to_string(), "fixed".to_string())]
+        );
+        assert_eq!(*channel.deletes.lock(), vec!["m3", "m4"]);
+        assert_eq!(
+            recent_reply_ids("editable", "chat-rev", 1),
+            Some(vec!["m2".to_string()])
+        );
+
+        revise_sent_reply(&channel, "chat-rev", 2, None)
+            .await
+            .unwrap();
+        assert_eq!(*channel.deletes.lock(), vec!["m3", "m4", "m1"]);
+        assert_eq!(
+            recent_reply_ids("editable", "chat-rev", 2),
+            None,
+            "deleted replies are no longer offered"
+        );
+        assert!(revise_sent_reply(&channel, "chat-rev", 2, None)
+            .await
+            .is_err());
+        assert!(revise_sent_reply(&channel, "other-chat", 1, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn handle_message_revision_cancels_and_reruns_in_flight_turn() {
+        let original =
+            revision_message("rev-in-flight-1", "summarize doc A", ChannelEvent::Message);
+        let history_key = conversation_history_key(&original);
+        let mut histories = HashMap::new();
+        histories.insert(
+            history_key.clone(),
+            vec![ChatMessage::user("summarize doc A")],
+        );
+        let ctx = revision_test_ctx(histories);
+        track_user_turn(&original, &history_key, "summarize doc A");
+
+        let cancellation = CancellationToken::new();
+        let completion = Arc::new(InFlightTaskCompletion::new());
+        completion.mark_done();
+        let in_flight = tokio::sync::Mutex::new(HashMap::from([(
+            message_revision_key(&original),
+            InFlightSenderTaskState {
+                task_id: 1,
+                cancellation: cancellation.clone(),
+                completion,
+            },
+        )]));
+
+        let edit = revision_message("rev-in-flight-1", "summarize doc B", ChannelEvent::Edited);
+        let rerun = handle_message_revision(&ctx, &in_flight, edit)
+            .await
+            .expect("edited in-flight message should be re-run");
+
+        assert!(cancellation.is_cancelled());
+        assert_eq!(rerun.event, ChannelEvent::Message);
+        assert_eq!(rerun.content, "summarize doc B");
+        assert!(!ctx
+            .conversation_histories
+            .lock()
+            .unwrap()
+            .contains_key(&history_key));
+    }
+
     struct DummyProvider;
 
     #[async_trait::async_trait]
@@ -6394,14 +8067,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(vec!["mock_price".to_string()])),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6414,6 +8091,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6471,14 +8150,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6491,6 +8174,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6535,14 +8220,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6555,6 +8244,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6613,14 +8304,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             approval_manager: mock_price_approved_manager(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6633,6 +8328,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "draft-streaming-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6690,14 +8387,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             approval_manager: mock_price_approved_manager(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6710,6 +8411,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "draft-streaming-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6759,14 +8462,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6779,6 +8486,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6823,14 +8532,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6843,6 +8556,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -6896,14 +8611,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -6916,6 +8635,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7000,14 +8721,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(vec!["mock_price".to_string()])),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
         assert_eq!(
             runtime_ctx
@@ -7032,6 +8757,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7152,14 +8879,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
         assert_eq!(
             runtime_ctx
@@ -7178,6 +8909,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7264,14 +8997,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager,
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -7284,6 +9021,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7371,14 +9110,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(vec!["shell".to_string()])),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager,
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -7391,6 +9134,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7460,14 +9205,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::clone(&approval_manager),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -7480,6 +9229,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7551,14 +9302,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::clone(&approval_manager),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -7571,6 +9326,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7651,14 +9408,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(vec!["mock_price".to_string()])),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -7671,6 +9432,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7705,6 +9468,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7799,14 +9564,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
         maybe_apply_runtime_config_update(runtime_ctx.as_ref())
             .await
@@ -7824,6 +9593,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7893,14 +9664,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -7913,6 +9688,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -7947,6 +9724,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8040,14 +9819,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8060,6 +9843,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8157,14 +9942,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8177,6 +9966,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8254,14 +10045,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8274,6 +10069,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8303,6 +10100,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8370,14 +10169,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(&autonomy_cfg)),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8390,6 +10193,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8418,6 +10223,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8487,14 +10294,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8507,6 +10318,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8563,14 +10376,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8583,6 +10400,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8656,16 +10475,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8678,6 +10501,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 4,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8812,16 +10637,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         maybe_apply_runtime_config_update(runtime_ctx.as_ref())
@@ -8929,14 +10758,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -8949,6 +10782,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -8994,14 +10829,18 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: mock_price_approved_manager(),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -9014,6 +10853,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -9171,16 +11012,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
@@ -9192,6 +11037,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
         .await
         .unwrap();
@@ -9203,6 +11050,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
         .await
         .unwrap();
@@ -9258,16 +11107,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: true,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -9280,6 +11133,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             })
             .await
             .unwrap();
@@ -9292,6 +11147,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             })
             .await
             .unwrap();
@@ -9357,16 +11214,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: true,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -9379,6 +11240,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             })
             .await
             .unwrap();
@@ -9391,6 +11254,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             })
             .await
             .unwrap();
@@ -9438,16 +11303,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -9460,6 +11329,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -9504,16 +11375,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -9526,6 +11401,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -9902,6 +11779,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         assert_eq!(conversation_memory_key(&msg), "slack_U123_msg_abc123");
@@ -9917,6 +11796,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -9926,6 +11807,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         assert_ne!(
@@ -9944,6 +11827,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "qq".into(),
             timestamp: 1,
             thread_ts: Some("msg-a".into()),
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -9953,6 +11838,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "qq".into(),
             timestamp: 2,
             thread_ts: Some("msg-b".into()),
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         assert_eq!(conversation_history_key(&msg1), "qq_user_open_1");
@@ -9975,6 +11862,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -9984,6 +11873,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         mem.store(
@@ -10017,7 +11908,7 @@ BTC is currently around $65,000 based on latest tool output."#
             .await
             .unwrap();
 
-        let context = build_memory_context(&mem, "age", 0.0).await;
+        let context = build_memory_context(&mem, "age", 0.0, None).await;
         assert!(context.contains("[Memory context]"));
         assert!(context.contains("Age is 45"));
     }
@@ -10055,16 +11946,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -10077,6 +11972,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -10092,6 +11989,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -10147,16 +12046,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -10169,6 +12072,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -10243,16 +12148,20 @@ BTC is currently around $65,000 based on latest tool output."#
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -10265,6 +12174,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -11026,16 +12937,20 @@ BTC is currently around $65,000 based on latest tool output."#;
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         // Simulate a photo attachment message with [IMAGE:] marker.
@@ -11049,6 +12964,8 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -11099,16 +13016,20 @@ BTC is currently around $65,000 based on latest tool output."#;
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
+            plan_mode: crate::config::PlanModeConfig::default(),
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
             query_classification: crate::config::QueryClassificationConfig::default(),
             model_routes: Vec::new(),
+            model_cascade: None,
             approval_manager: Arc::new(ApprovalManager::from_config(
                 &crate::config::AutonomyConfig::default(),
             )),
             safety_heartbeat: None,
             startup_perplexity_filter: crate::config::PerplexityFilterConfig::default(),
+            voice_replies: None,
+            message_edits: crate::config::MessageEditsConfig::default(),
         });
 
         process_channel_message(
@@ -11121,6 +13042,8 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -11136,6 +13059,8 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             },
             CancellationToken::new(),
         )
@@ -0,0 +1,1217 @@
+//! Outbound send queue shared by all channels.
+//!
+//! [`QueuedChannel`] wraps a channel so that every `send` waits its turn in a
+//! per-recipient lane, is throttled by token buckets that follow the
+//! platform's published limits, and is retried when the platform answers with
+//! a rate limit (honoring `Retry-After`) or a transient network error.
+//! With persistence on, every message is appended to an on-disk log
+//! (`<workspace>/state/outbound_queue.jsonl`) until it is delivered; sends
+//! that fail for good stay in the log as dead letters and are resent by
+//! [`replay_pending`] after a restart.
+
+use super::traits::{Attachment, AttachmentLimits, Channel, ChannelMessage, SendMessage};
+use crate::config::{OutboundQueueConfig, OutboundRateLimitConfig};
+use crate::observability::traits::ObserverMetric;
+use crate::observability::Observer;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+pub const OUTBOUND_QUEUE_FILE: &str = "state/outbound_queue.jsonl";
+
+/// Log lines appended before the log is compacted down to pending entries.
+const COMPACT_AFTER_LINES: usize = 512;
+
+/// Recipient lanes kept per channel before idle ones are pruned.
+const MAX_IDLE_LANES: usize = 1024;
+
+/// Messages currently waiting in or being sent from any outbound lane.
+static OUTBOUND_DEPTH: AtomicU64 = AtomicU64::new(0);
+
+/// Error a channel returns when the platform rejected a send with a rate
+/// limit. The queue waits `retry_after` (or backs off) and tries again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimited {
+    pub channel: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimited {
+    pub fn new(channel: impl Into<String>, retry_after: Option<Duration>) -> Self {
+        Self {
+            channel: channel.into(),
+            retry_after,
+        }
+    }
+
+    /// Build from a response's `Retry-After` header.
+    pub fn from_headers(channel: impl Into<String>, headers: &reqwest::header::HeaderMap) -> Self {
+        let retry_after = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        Self::new(channel, retry_after)
+    }
+
+    /// Build from a rate-limited response, falling back to the `retry_after`
+    /// field Discord and Telegram (under `parameters`) put in the JSON body.
+    pub fn from_response(
+        channel: impl Into<String>,
+        headers: &reqwest::header::HeaderMap,
+        body: &str,
+    ) -> Self {
+        let mut limited = Self::from_headers(channel, headers);
+        if limited.retry_after.is_none() {
+            let parsed: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+            limited.retry_after = parsed
+                .get("retry_after")
+                .or_else(|| parsed.pointer("/parameters/retry_after"))
+                .and_then(serde_json::Value::as_f64)
+                .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                .map(Duration::from_secs_f64);
+        }
+        limited
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.retry_after {
+            Some(wait) => write!(
+                f,
+                "{} rate limited the send (retry after {:.1}s)",
+                self.channel,
+                wait.as_secs_f64()
+            ),
+            None => write!(f, "{} rate limited the send", self.channel),
+        }
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Context a chunked send attaches to its error once the first `chunks`
+/// chunks were delivered. The queue retries from
+/// [`SendMessage::resume_from`] so delivered chunks are not sent twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartiallySent {
+    pub chunks: usize,
+}
+
+impl PartiallySent {
+    /// Attach progress to `error`; no-op when nothing was delivered yet.
+    pub fn wrap(error: anyhow::Error, chunks: usize) -> anyhow::Error {
+        if chunks == 0 {
+            error
+        } else {
+            error.context(Self { chunks })
+        }
+    }
+}
+
+impl fmt::Display for PartiallySent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "send stopped after {} delivered chunk(s)", self.chunks)
+    }
+}
+
+/// Parse a `Retry-After` value in (possibly fractional) seconds.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let seconds: f64 = value.trim().parse().ok()?;
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+/// Published limits for platforms that enforce them per recipient.
+fn builtin_limit(channel: &str) -> Option<OutboundRateLimitConfig> {
+    let (per_recipient_per_sec, per_recipient_burst, global_per_sec) = match channel {
+        // ~1 message/s per chat with short bursts, 30 messages/s per bot.
+        "telegram" => (1.0, 3, Some(30.0)),
+        // 5 messages per 5 s per channel, 50 requests/s per bot.
+        "discord" => (1.0, 5, Some(50.0)),
+        // chat.postMessage: 1 message/s per channel with short bursts.
+        "slack" => (1.0, 3, None),
+        // Cloud API pair limit: 1 message per 6 s per user (bursts of 45),
+        // 80 messages/s per business number.
+        "whatsapp" => (1.0 / 6.0, 45, Some(80.0)),
+        _ => return None,
+    };
+    Some(OutboundRateLimitConfig {
+        per_recipient_per_sec,
+        per_recipient_burst,
+        global_per_sec,
+    })
+}
+
+/// Configured override for `channel`, else the built-in platform limit.
+pub fn effective_limit(
+    settings: &OutboundQueueConfig,
+    channel: &str,
+) -> Option<OutboundRateLimitConfig> {
+    let channel = channel.to_ascii_lowercase();
+    settings
+        .limits
+        .get(&channel)
+        .copied()
+        .or_else(|| builtin_limit(&channel))
+        .filter(|limit| limit.per_recipient_per_sec > 0.0)
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    per_sec: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_sec: f64, burst: u32, now: Instant) -> Self {
+        let capacity = f64::from(burst.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            per_sec,
+            updated: now,
+        }
+    }
+
+    /// Take one token, or return how long until one is available.
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec))
+        }
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.per_sec >= self.capacity
+    }
+}
+
+/// One recipient's place in line. Holding the lane keeps sends to that
+/// recipient in order, including across retries.
+#[derive(Debug, Default)]
+struct Lane {
+    bucket: Option<TokenBucket>,
+}
+
+/// Burst that allows one second's worth of sends at `per_sec`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn one_second_burst(per_sec: f64) -> u32 {
+    per_sec.ceil().clamp(1.0, f64::from(u32::MAX)) as u32
+}
+
+struct Limiter {
+    limit: Option<OutboundRateLimitConfig>,
+    global: Option<Mutex<TokenBucket>>,
+    lanes: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Lane>>>>,
+}
+
+impl Limiter {
+    fn new(limit: Option<OutboundRateLimitConfig>) -> Self {
+        let global = limit
+            .and_then(|limit| limit.global_per_sec)
+            .filter(|per_sec| *per_sec > 0.0)
+            .map(|per_sec| {
+                Mutex::new(TokenBucket::new(
+                    per_sec,
+                    one_second_burst(per_sec),
+                    Instant::now(),
+                ))
+            });
+        Self {
+            limit,
+            global,
+            lanes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limiter for `channel`, shared by every wrapper of that channel so cron
+    /// deliveries and replies draw from the same budget.
+    fn shared(channel: &str, limit: Option<OutboundRateLimitConfig>) -> Arc<Self> {
+        static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<Limiter>>>> = OnceLock::new();
+        let mut limiters = LIMITERS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match limiters.get(channel) {
+            Some(existing) if existing.limit == limit => Arc::clone(existing),
+            _ => {
+                let limiter = Arc::new(Self::new(limit));
+                limiters.insert(channel.to_string(), Arc::clone(&limiter));
+                limiter
+            }
+        }
+    }
+
+    fn lane(&self, recipient: &str) -> Arc<tokio::sync::Mutex<Lane>> {
+        let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        if lanes.len() > MAX_IDLE_LANES {
+            let now = Instant::now();
+            lanes.retain(|_, lane| {
+                if Arc::strong_count(lane) > 1 {
+                    return true;
+                }
+                let Ok(lane) = lane.try_lock() else {
+                    return true;
+                };
+                lane.bucket.as_ref().is_some_and(|b| !b.is_full(now))
+            });
+        }
+        Arc::clone(lanes.entry(recipient.to_string()).or_default())
+    }
+
+    /// Wait until both the recipient's and the channel-wide bucket allow a send.
+    async fn wait_for_token(&self, lane: &mut Lane) {
+        if let Some(limit) = self.limit {
+            let bucket = lane.bucket.get_or_insert_with(|| {
+                TokenBucket::new(
+                    limit.per_recipient_per_sec,
+                    limit.per_recipient_burst,
+                    Instant::now(),
+                )
+            });
+            while let Err(wait) = bucket.try_take(Instant::now()) {
+                tokio::time::sleep(wait).await;
+            }
+        }
+        if let Some(global) = &self.global {
+            loop {
+                let taken = global
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .try_take(Instant::now());
+                match taken {
+                    Ok(()) => break,
+                    Err(wait) => tokio::time::sleep(wait).await,
+                }
+            }
+        }
+    }
+}
+
+/// Counts a message in `OUTBOUND_DEPTH` for as long as it is alive.
+struct DepthGuard {
+    observer: Arc<dyn Observer>,
+}
+
+impl DepthGuard {
+    fn enter(observer: &Arc<dyn Observer>) -> Self {
+        let depth = OUTBOUND_DEPTH.fetch_add(1, Ordering::Relaxed) + 1;
+        observer.record_metric(&ObserverMetric::QueueDepth(depth));
+        Self {
+            observer: Arc::clone(observer),
+        }
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        let depth = OUTBOUND_DEPTH.fetch_sub(1, Ordering::Relaxed) - 1;
+        self.observer
+            .record_metric(&ObserverMetric::QueueDepth(depth));
+    }
+}
+
+/// Current number of queued or in-flight outbound messages.
+pub fn queue_depth() -> u64 {
+    OUTBOUND_DEPTH.load(Ordering::Relaxed)
+}
+
+/// A message persisted until it is delivered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PendingSend {
+    id: String,
+    channel: String,
+    queued_at: i64,
+    recipient: String,
+    content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thread_ts: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon_emoji: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reply_broadcast: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blocks: Option<serde_json::Value>,
+    /// Leading chunks already delivered (see [`PartiallySent`]).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    resume_from: usize,
+    /// Last error once every attempt failed; the entry waits for the next
+    /// [`replay_pending`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dead_letter: Option<String>,
+}
+
+impl PendingSend {
+    fn new(channel: &str, message: &SendMessage) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel: channel.to_string(),
+            queued_at: chrono::Utc::now().timestamp(),
+            recipient: message.recipient.clone(),
+            content: message.content.clone(),
+            subject: message.subject.clone(),
+            thread_ts: message.thread_ts.clone(),
+            username: message.username.clone(),
+            icon_emoji: message.icon_emoji.clone(),
+            reply_broadcast: message.reply_broadcast,
+            blocks: message.blocks.clone(),
+            resume_from: message.resume_from,
+            dead_letter: None,
+        }
+    }
+
+    fn to_message(&self) -> SendMessage {
+        let mut message = SendMessage::new(&self.content, &self.recipient);
+        message.subject = self.subject.clone();
+        message.thread_ts = self.thread_ts.clone();
+        message.username = self.username.clone();
+        message.icon_emoji = self.icon_emoji.clone();
+        message.reply_broadcast = self.reply_broadcast;
+        message.blocks = self.blocks.clone();
+        message.resume_from = self.resume_from;
+        message
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// One line of the outbound log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum StoreRecord {
+    Queued(Box<PendingSend>),
+    Progress { id: String, chunks: usize },
+    Delivered { id: String },
+    DeadLettered { id: String, error: String },
+}
+
+/// Fold the log at `path` into the entries that are still undelivered.
+fn read_log(path: &Path) -> Vec<PendingSend> {
+    let Ok(raw) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<PendingSend> = Vec::new();
+    for line in raw.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<StoreRecord>(line) {
+            Ok(StoreRecord::Queued(entry)) => {
+                entries.retain(|existing| existing.id != entry.id);
+                entries.push(*entry);
+            }
+            Ok(StoreRecord::Progress { id, chunks }) => {
+                if let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) {
+                    entry.resume_from = entry.resume_from.max(chunks);
+                }
+            }
+            Ok(StoreRecord::Delivered { id }) => entries.retain(|entry| entry.id != id),
+            Ok(StoreRecord::DeadLettered { id, error }) => {
+                if let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) {
+                    entry.dead_letter = Some(error);
+                }
+            }
+            Err(e) => tracing::warn!("Skipping unreadable line in {}: {e}", path.display()),
+        }
+    }
+    entries
+}
+
+/// Replace the log at `path` with one `queued` line per entry.
+fn write_log(path: &Path, entries: &[PendingSend]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut out = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut out, &StoreRecord::Queued(Box::new(entry.clone())))?;
+        out.push(b'\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, out).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Undelivered messages on disk (`<workspace>/state/outbound_queue.jsonl`).
+///
+/// Sends append one line each instead of rewriting the file; the log is
+/// compacted off the async runtime every [`COMPACT_AFTER_LINES`] lines and
+/// when [`replay_pending`] runs.
+pub struct OutboundStore {
+    path: PathBuf,
+    /// Lines appended since the last compaction.
+    appended: tokio::sync::Mutex<usize>,
+}
+
+impl OutboundStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            path: workspace_dir.join(OUTBOUND_QUEUE_FILE),
+            appended: tokio::sync::Mutex::new(0),
+        }
+    }
+
+    /// Store for `workspace_dir`, shared by every queue in the process so
+    /// appends and compactions never interleave.
+    pub fn shared(workspace_dir: &Path) -> Arc<Self> {
+        static STORES: OnceLock<Mutex<HashMap<PathBuf, Arc<OutboundStore>>>> = OnceLock::new();
+        let mut stores = STORES
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        Arc::clone(
+            stores
+                .entry(workspace_dir.to_path_buf())
+                .or_insert_with(|| Arc::new(Self::new(workspace_dir))),
+        )
+    }
+
+    async fn append(&self, record: &StoreRecord) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let mut appended = self.appended.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+
+        *appended += 1;
+        if *appended >= COMPACT_AFTER_LINES {
+            let path = self.path.clone();
+            tokio::task::spawn_blocking(move || write_log(&path, &read_log(&path))).await??;
+            *appended = 0;
+        }
+        Ok(())
+    }
+
+    async fn push(&self, entry: PendingSend) -> Result<()> {
+        self.append(&StoreRecord::Queued(Box::new(entry))).await
+    }
+
+    async fn mark_progress(&self, id: &str, chunks: usize) -> Result<()> {
+        self.append(&StoreRecord::Progress {
+            id: id.to_string(),
+            chunks,
+        })
+        .await
+    }
+
+    async fn mark_delivered(&self, id: &str) -> Result<()> {
+        self.append(&StoreRecord::Delivered { id: id.to_string() })
+            .await
+    }
+
+    async fn mark_dead_lettered(&self, id: &str, error: &str) -> Result<()> {
+        self.append(&StoreRecord::DeadLettered {
+            id: id.to_string(),
+            error: error.to_string(),
+        })
+        .await
+    }
+
+    /// Entries for `channels` that are still undelivered. Entries older than
+    /// `max_age_secs` are dropped from the log; the rest stay until the
+    /// resend is marked delivered.
+    async fn pending(&self, channels: &[String], max_age_secs: u64) -> Result<Vec<PendingSend>> {
+        let cutoff =
+            chrono::Utc::now().timestamp() - i64::try_from(max_age_secs).unwrap_or(i64::MAX);
+        let mut appended = self.appended.lock().await;
+        let path = self.path.clone();
+        let channels = channels.to_vec();
+        let pending = tokio::task::spawn_blocking(move || -> Result<Vec<PendingSend>> {
+            let mut entries = read_log(&path);
+            entries.retain(|entry| {
+                let expired = channels.contains(&entry.channel) && entry.queued_at < cutoff;
+                if expired {
+                    tracing::warn!(
+                        channel = %entry.channel,
+                        recipient = %entry.recipient,
+                        "Discarding persisted outbound message older than {max_age_secs}s"
+                    );
+                }
+                !expired
+            });
+            write_log(&path, &entries)?;
+            entries.retain(|entry| channels.contains(&entry.channel));
+            Ok(entries)
+        })
+        .await??;
+        *appended = 0;
+        Ok(pending)
+    }
+}
+
+tokio::task_local! {
+    /// Persisted entry [`replay_pending`] is resending; `send` reuses it
+    /// instead of logging a new copy.
+    static REPLAYING: PendingSend;
+}
+
+/// How long to wait before retrying `error`, or `None` when it is permanent.
+///
+/// Only typed errors count: [`RateLimited`], and `reqwest` errors for
+/// timeouts, connection failures or an HTTP 429 status. Error text is never
+/// inspected, so a message that merely mentions "429" is not retried.
+fn retry_delay(
+    error: &anyhow::Error,
+    attempt: u32,
+    settings: &OutboundQueueConfig,
+) -> Option<Duration> {
+    let cap = Duration::from_secs(settings.max_retry_after_secs.max(1));
+    let backoff = Duration::from_secs(1_u64 << attempt.saturating_sub(1).min(16)).min(cap);
+
+    if let Some(limited) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<RateLimited>())
+    {
+        return Some(limited.retry_after.unwrap_or(backoff).min(cap));
+    }
+
+    let transient = error.chain().any(|cause| {
+        cause.downcast_ref::<reqwest::Error>().is_some_and(|e| {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS)
+        })
+    });
+    transient.then_some(backoff)
+}
+
+/// Channel wrapper that routes sends through the outbound queue.
+pub struct QueuedChannel {
+    inner: Arc<dyn Channel>,
+    limiter: Arc<Limiter>,
+    settings: OutboundQueueConfig,
+    store: Option<Arc<OutboundStore>>,
+    observer: Arc<dyn Observer>,
+}
+
+impl QueuedChannel {
+    /// Wrap `inner`, or return it unchanged when the queue is disabled.
+    pub fn wrap(
+        inner: Arc<dyn Channel>,
+        settings: &OutboundQueueConfig,
+        store: Option<Arc<OutboundStore>>,
+        observer: Arc<dyn Observer>,
+    ) -> Arc<dyn Channel> {
+        if !settings.enabled {
+            return inner;
+        }
+        let name = inner.name().to_ascii_lowercase();
+        let limiter = Limiter::shared(&name, effective_limit(settings, &name));
+        Arc::new(Self {
+            inner,
+            limiter,
+            settings: settings.clone(),
+            store: store.filter(|_| settings.persist),
+            observer,
+        })
+    }
+
+    /// Send with throttling and retries; the caller owns persistence. A send
+    /// that stopped partway ([`PartiallySent`]) is retried from the first
+    /// undelivered chunk, and the progress is logged under `persisted`.
+    async fn deliver(
+        &self,
+        message: &SendMessage,
+        persisted: Option<(&OutboundStore, &str)>,
+    ) -> Result<()> {
+        let lane = self.limiter.lane(&message.recipient);
+        let mut lane = lane.lock().await;
+        let max_attempts = self.settings.max_attempts.max(1);
+        let mut message = Cow::Borrowed(message);
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            self.limiter.wait_for_token(&mut lane).await;
+            let error = match self.inner.send(&message).await {
+                Ok(()) => return Ok(()),
+                Err(error) => error,
+            };
+            if let Some(progress) = error.downcast_ref::<PartiallySent>() {
+                if progress.chunks > message.resume_from {
+                    message.to_mut().resume_from = progress.chunks;
+                    if let Some((store, id)) = persisted {
+                        if let Err(e) = store.mark_progress(id, progress.chunks).await {
+                            tracing::warn!("Failed to record outbound send progress: {e:#}");
+                        }
+                    }
+                }
+            }
+            let Some(delay) = retry_delay(&error, attempt, &self.settings) else {
+                return Err(error);
+            };
+            if attempt >= max_attempts {
+                return Err(error.context(format!(
+                    "{} send to {} failed after {attempt} attempts",
+                    self.inner.name(),
+                    message.recipient
+                )));
+            }
+            tracing::warn!(
+                channel = self.inner.name(),
+                recipient = %message.recipient,
+                "Outbound send failed (attempt {attempt}/{max_attempts}); retrying in {:.1}s: {error:#}",
+                delay.as_secs_f64()
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Wait for a send slot without retrying; used for drafts, prompts and uploads.
+    async fn throttled<T>(
+        &self,
+        recipient: &str,
+        send: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let _depth = DepthGuard::enter(&self.observer);
+        let lane = self.limiter.lane(recipient);
+        let mut lane = lane.lock().await;
+        self.limiter.wait_for_token(&mut lane).await;
+        send.await
+    }
+}
+
+#[async_trait]
+impl Channel for QueuedChannel {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        let _depth = DepthGuard::enter(&self.observer);
+        let persisted = match &self.store {
+            Some(store) if message.attachments.is_empty() => match REPLAYING.try_with(Clone::clone)
+            {
+                Ok(entry) => Some((store, entry.id)),
+                Err(_) => {
+                    let entry = PendingSend::new(&self.inner.name().to_ascii_lowercase(), message);
+                    let id = entry.id.clone();
+                    match store.push(entry).await {
+                        Ok(()) => Some((store, id)),
+                        Err(e) => {
+                            tracing::warn!("Failed to persist outbound message: {e:#}");
+                            None
+                        }
+                    }
+                }
+            },
+            _ => None,
+        };
+
+        let result = self
+            .deliver(
+                message,
+                persisted
+                    .as_ref()
+                    .map(|(store, id)| (store.as_ref(), id.as_str())),
+            )
+            .await;
+        if let Some((store, id)) = persisted {
+            let recorded = match &result {
+                Ok(()) => store.mark_delivered(&id).await,
+                Err(error) => {
+                    tracing::warn!(
+                        channel = self.inner.name(),
+                        recipient = %message.recipient,
+                        "Keeping undelivered outbound message for replay: {error:#}"
+                    );
+                    store.mark_dead_lettered(&id, &format!("{error:#}")).await
+                }
+            };
+            if let Err(e) = recorded {
+                tracing::warn!("Failed to update persisted outbound message: {e:#}");
+            }
+        }
+        result
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        self.inner.listen(tx).await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+
+    async fn start_typing(&self, recipient: &str) -> Result<()> {
+        self.inner.start_typing(recipient).await
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> Result<()> {
+        self.inner.stop_typing(recipient).await
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.inner.supports_draft_updates()
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> Result<Option<String>> {
+        self.throttled(&message.recipient, self.inner.send_draft(message))
+            .await
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<Option<String>> {
+        self.inner.update_draft(recipient, message_id, text).await
+    }
+
+    async fn finalize_draft(&self, recipient: &str, message_id: &str, text: &str) -> Result<()> {
+        self.inner.finalize_draft(recipient, message_id, text).await
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> Result<()> {
+        self.inner.cancel_draft(recipient, message_id).await
+    }
+
+    fn supports_message_edits(&self) -> bool {
+        self.inner.supports_message_edits()
+    }
+
+    async fn edit_message(&self, recipient: &str, message_id: &str, text: &str) -> Result<()> {
+        self.inner.edit_message(recipient, message_id, text).await
+    }
+
+    async fn delete_message(&self, recipient: &str, message_id: &str) -> Result<()> {
+        self.inner.delete_message(recipient, message_id).await
+    }
+
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        request_id: &str,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+        thread_ts: Option<String>,
+    ) -> Result<()> {
+        self.throttled(
+            recipient,
+            self.inner
+                .send_approval_prompt(recipient, request_id, tool_name, arguments, thread_ts),
+        )
+        .await
+    }
+
+    async fn add_reaction(&self, channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        self.inner.add_reaction(channel_id, message_id, emoji).await
+    }
+
+    fn attachment_limits(&self) -> Option<AttachmentLimits> {
+        self.inner.attachment_limits()
+    }
+
+    async fn send_attachment(&self, message: &SendMessage, attachment: &Attachment) -> Result<()> {
+        self.throttled(
+            &message.recipient,
+            self.inner.send_attachment(message, attachment),
+        )
+        .await
+    }
+
+    async fn remove_reaction(&self, channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        self.inner
+            .remove_reaction(channel_id, message_id, emoji)
+            .await
+    }
+}
+
+/// Resend messages a previous run persisted but did not deliver, including
+/// dead letters. Sends go through `channels` (normally [`QueuedChannel`]s)
+/// in the background; each entry stays in the log until it is delivered.
+#[allow(clippy::implicit_hasher)]
+pub async fn replay_pending(
+    store: &OutboundStore,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+    max_age_secs: u64,
+) -> usize {
+    let by_name: HashMap<String, Arc<dyn Channel>> = channels
+        .iter()
+        .map(|(name, channel)| (name.to_ascii_lowercase(), Arc::clone(channel)))
+        .collect();
+    let names: Vec<String> = by_name.keys().cloned().collect();
+    let pending = match store.pending(&names, max_age_secs).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::warn!("Failed to read persisted outbound messages: {e:#}");
+            return 0;
+        }
+    };
+
+    let count = pending.len();
+    for entry in pending {
+        let Some(channel) = by_name.get(&entry.channel).cloned() else {
+            continue;
+        };
+        if let Some(error) = &entry.dead_letter {
+            tracing::info!(
+                channel = %entry.channel,
+                recipient = %entry.recipient,
+                "Resending dead-lettered outbound message (last error: {error})"
+            );
+        }
+        tokio::spawn(REPLAYING.scope(entry.clone(), async move {
+            if let Err(e) = channel.send(&entry.to_message()).await {
+                tracing::warn!(
+                    channel = %entry.channel,
+                    recipient = %entry.recipient,
+                    "Persisted outbound message failed again: {e:#}"
+                );
+            }
+        }));
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::NoopObserver;
+    use std::sync::atomic::AtomicUsize;
+
+    struct FlakyChannel {
+        name: String,
+        failures: Mutex<Vec<anyhow::Error>>,
+        sent: Mutex<Vec<String>>,
+        resumed_from: Mutex<Vec<usize>>,
+        calls: AtomicUsize,
+    }
+
+    impl FlakyChannel {
+        fn new(name: &str, failures: Vec<anyhow::Error>) -> Arc<Self> {
+            Arc::new(Self {
+                name: name.to_string(),
+                failures: Mutex::new(failures),
+                sent: Mutex::new(Vec::new()),
+                resumed_from: Mutex::new(Vec::new()),
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Channel for FlakyChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn send(&self, message: &SendMessage) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.resumed_from.lock().unwrap().push(message.resume_from);
+            let failure = {
+                let mut failures = self.failures.lock().unwrap();
+                (!failures.is_empty()).then(|| failures.remove(0))
+            };
+            if let Some(error) = failure {
+                return Err(error);
+            }
+            self.sent.lock().unwrap().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn settings() -> OutboundQueueConfig {
+        OutboundQueueConfig {
+            enabled: true,
+            persist: true,
+            max_attempts: 3,
+            ..OutboundQueueConfig::default()
+        }
+    }
+
+    fn wrap(inner: Arc<FlakyChannel>, store: Option<Arc<OutboundStore>>) -> Arc<dyn Channel> {
+        QueuedChannel::wrap(inner, &settings(), store, Arc::new(NoopObserver))
+    }
+
+    #[test]
+    fn token_bucket_allows_burst_then_waits() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1.0, 2, start);
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        let wait = bucket.try_take(start).unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        assert!(bucket.try_take(start + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn effective_limit_prefers_configured_override() {
+        let mut settings = OutboundQueueConfig::default();
+        assert_eq!(
+            effective_limit(&settings, "Telegram"),
+            builtin_limit("telegram")
+        );
+        assert!(effective_limit(&settings, "irc").is_none());
+
+        let custom = OutboundRateLimitConfig {
+            per_recipient_per_sec: 0.5,
+            per_recipient_burst: 1,
+            global_per_sec: None,
+        };
+        settings.limits.insert("irc".into(), custom);
+        assert_eq!(effective_limit(&settings, "irc"), Some(custom));
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_fractional_seconds() {
+        assert_eq!(parse_retry_after("2"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_retry_after(" 0.5 "), Some(Duration::from_millis(500)));
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("-1"), None);
+    }
+
+    #[test]
+    fn rate_limited_reads_retry_after_from_body() {
+        let headers = reqwest::header::HeaderMap::new();
+        let telegram = RateLimited::from_response(
+            "telegram",
+            &headers,
+            r#"{"ok":false,"error_code":429,"parameters":{"retry_after":3}}"#,
+        );
+        assert_eq!(telegram.retry_after, Some(Duration::from_secs(3)));
+
+        let discord = RateLimited::from_response("discord", &headers, r#"{"retry_after":0.25}"#);
+        assert_eq!(discord.retry_after, Some(Duration::from_millis(250)));
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "9".parse().unwrap());
+        let slack = RateLimited::from_response("slack", &headers, "");
+        assert_eq!(slack.retry_after, Some(Duration::from_secs(9)));
+    }
+
+    #[test]
+    fn retry_delay_honors_retry_after_and_skips_permanent_errors() {
+        let settings = settings();
+        let limited =
+            anyhow::Error::new(RateLimited::new("telegram", Some(Duration::from_secs(7))))
+                .context("sendMessage failed");
+        assert_eq!(
+            retry_delay(&limited, 1, &settings),
+            Some(Duration::from_secs(7))
+        );
+
+        let too_long =
+            anyhow::Error::new(RateLimited::new("discord", Some(Duration::from_secs(3600))));
+        assert_eq!(
+            retry_delay(&too_long, 1, &settings),
+            Some(Duration::from_secs(settings.max_retry_after_secs))
+        );
+
+        let status_429 = reqwest::Response::from(
+            axum::http::Response::builder()
+                .status(429)
+                .body(String::new())
+                .unwrap(),
+        )
+        .error_for_status()
+        .unwrap_err();
+        assert_eq!(
+            retry_delay(&anyhow::Error::new(status_429), 3, &settings),
+            Some(Duration::from_secs(4))
+        );
+
+        let text_429 = anyhow::anyhow!("Mattermost post failed (429 Too Many Requests)");
+        assert_eq!(retry_delay(&text_429, 1, &settings), None);
+
+        let permanent = anyhow::anyhow!("Slack chat.postMessage failed: channel_not_found");
+        assert_eq!(retry_delay(&permanent, 1, &settings), None);
+    }
+
+    #[tokio::test]
+    async fn send_retries_after_rate_limit() {
+        let inner = FlakyChannel::new(
+            "queue-test-retry",
+            vec![RateLimited::new("queue-test-retry", Some(Duration::from_millis(20))).into()],
+        );
+        let channel = wrap(Arc::clone(&inner), None);
+
+        channel
+            .send(&SendMessage::new("hello", "42"))
+            .await
+            .unwrap();
+
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(*inner.sent.lock().unwrap(), vec!["hello".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn partially_sent_messages_resume_after_delivered_chunks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(OutboundStore::new(tmp.path()));
+        let limited = RateLimited::new("queue-test-resume", Some(Duration::from_millis(5)));
+        let inner = FlakyChannel::new(
+            "queue-test-resume",
+            vec![
+                PartiallySent::wrap(limited.clone().into(), 2),
+                PartiallySent::wrap(limited.into(), 3),
+            ],
+        );
+        let channel = wrap(Arc::clone(&inner), Some(Arc::clone(&store)));
+
+        channel
+            .send(&SendMessage::new("long reply", "42"))
+            .await
+            .unwrap();
+
+        assert_eq!(*inner.resumed_from.lock().unwrap(), vec![0, 2, 3]);
+        assert!(read_log(&store.path).is_empty());
+    }
+
+    #[tokio::test]
+    async fn dead_letters_keep_send_progress() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(OutboundStore::new(tmp.path()));
+        let inner = FlakyChannel::new(
+            "queue-test-progress",
+            vec![PartiallySent::wrap(anyhow::anyhow!("chat not found"), 1)],
+        );
+        let channel = wrap(Arc::clone(&inner), Some(Arc::clone(&store)));
+
+        assert!(channel.send(&SendMessage::new("hi", "42")).await.is_err());
+
+        let entries = read_log(&store.path);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].resume_from, 1);
+        assert_eq!(entries[0].to_message().resume_from, 1);
+    }
+
+    #[tokio::test]
+    async fn send_gives_up_after_max_attempts() {
+        let failures = (0..5)
+            .map(|_| {
+                anyhow::Error::new(RateLimited::new(
+                    "queue-test-give-up",
+                    Some(Duration::from_millis(5)),
+                ))
+            })
+            .collect();
+        let inner = FlakyChannel::new("queue-test-give-up", failures);
+        let channel = wrap(Arc::clone(&inner), None);
+
+        let err = channel
+            .send(&SendMessage::new("hello", "42"))
+            .await
+            .unwrap_err();
+
+        assert!(format!("{err:#}").contains("failed after 3 attempts"));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn send_does_not_retry_permanent_errors() {
+        let inner = FlakyChannel::new(
+            "queue-test-permanent",
+            vec![anyhow::anyhow!("chat not found")],
+        );
+        let channel = wrap(Arc::clone(&inner), None);
+
+        assert!(channel.send(&SendMessage::new("hi", "42")).await.is_err());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn delivered_messages_are_removed_from_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(OutboundStore::new(tmp.path()));
+        let inner = FlakyChannel::new("queue-test-store", Vec::new());
+        let channel = wrap(Arc::clone(&inner), Some(Arc::clone(&store)));
+
+        channel.send(&SendMessage::new("hi", "42")).await.unwrap();
+
+        assert!(read_log(&store.path).is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_messages_stay_in_store_as_dead_letters() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(OutboundStore::new(tmp.path()));
+        let inner = FlakyChannel::new(
+            "queue-test-dead-letter",
+            vec![anyhow::anyhow!("chat not found")],
+        );
+        let channel = wrap(Arc::clone(&inner), Some(Arc::clone(&store)));
+
+        assert!(channel.send(&SendMessage::new("hi", "42")).await.is_err());
+
+        let entries = read_log(&store.path);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "hi");
+        assert!(entries[0]
+            .dead_letter
+            .as_deref()
+            .is_some_and(|error| error.contains("chat not found")));
+    }
+
+    #[tokio::test]
+    async fn replay_resends_persisted_messages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(OutboundStore::new(tmp.path()));
+        let mut fresh = PendingSend::new(
+            "queue-test-replay",
+            &SendMessage::new("left over", "42").in_thread(Some("7".into())),
+        );
+        fresh.queued_at -= 60;
+        let mut dead = PendingSend::new("queue-test-replay", &SendMessage::new("dead", "43"));
+        dead.dead_letter = Some("rate limited".into());
+        let mut stale = PendingSend::new("queue-test-replay", &SendMessage::new("stale", "42"));
+        stale.queued_at -= 7200;
+        let other = PendingSend::new("elsewhere", &SendMessage::new("keep", "1"));
+        write_log(&store.path, &[fresh, dead, stale, other.clone()]).unwrap();
+
+        let inner = FlakyChannel::new("queue-test-replay", Vec::new());
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert(
+            "queue-test-replay".into(),
+            wrap(Arc::clone(&inner), Some(Arc::clone(&store))),
+        );
+
+        assert_eq!(replay_pending(&store, &channels, 3600).await, 2);
+        for _ in 0..50 {
+            if inner.sent.lock().unwrap().len() == 2 && read_log(&store.path).len() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let mut sent = inner.sent.lock().unwrap().clone();
+        sent.sort();
+        assert_eq!(sent, vec!["dead".to_string(), "left over".to_string()]);
+        assert_eq!(read_log(&store.path), vec![other]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use crate::config::schema::QQEnvironment;
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
@@ -146,6 +146,8 @@ fn build_channel_message(
         channel: "qq".to_string(),
         timestamp: current_unix_timestamp_secs(),
         thread_ts: (!msg_id.is_empty()).then(|| msg_id.to_string()),
+        attachments: Vec::new(),
+        event: ChannelEvent::Message,
     }
 }
 
@@ -0,0 +1,1292 @@
+//! Markdown rendering for outbound channel messages.
+//!
+//! Agent replies are Markdown. [`Document::parse`] turns a reply into a small
+//! block/inline tree once, and [`Document::render`] emits it in the dialect a
+//! platform understands (see [`Format`]). Tables become monospace blocks on
+//! every platform, since no chat client renders Markdown tables.
+//!
+//! [`split_markdown`] splits long replies for platforms with a length limit
+//! without cutting inside a code fence: a fence that spans a split is closed
+//! at the end of one chunk and reopened (with its language) at the start of
+//! the next, so every chunk renders on its own.
+
+use std::fmt::Write as _;
+
+/// Output dialect for [`Document::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Telegram `parse_mode = "HTML"`.
+    TelegramHtml,
+    /// Telegram `parse_mode = "MarkdownV2"`.
+    TelegramMarkdownV2,
+    /// Slack `mrkdwn` text.
+    SlackMrkdwn,
+    /// Discord-flavoured Markdown.
+    Discord,
+    /// Matrix `org.matrix.custom.html` formatted body.
+    MatrixHtml,
+    /// IRC text with mIRC formatting control codes.
+    Irc,
+    /// HTML part of an email.
+    EmailHtml,
+    /// Plain text without markup (email text part, Matrix fallback body).
+    PlainText,
+}
+
+impl Format {
+    fn is_html(self) -> bool {
+        matches!(
+            self,
+            Self::TelegramHtml | Self::MatrixHtml | Self::EmailHtml
+        )
+    }
+}
+
+/// Inline content of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    Text(String),
+    Bold(Vec<Inline>),
+    Italic(Vec<Inline>),
+    Strike(Vec<Inline>),
+    Code(String),
+    Link { text: Vec<Inline>, url: String },
+    LineBreak,
+}
+
+/// One list item; nesting is expressed by `depth`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    pub depth: usize,
+    /// Item number for ordered lists, `None` for bullets.
+    pub number: Option<u64>,
+    pub content: Vec<Inline>,
+}
+
+/// Block-level element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Paragraph(Vec<Inline>),
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    Quote(Vec<Inline>),
+    List(Vec<ListItem>),
+    Table {
+        header: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+    Rule,
+}
+
+/// Parsed Markdown message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Document {
+    pub blocks: Vec<Block>,
+}
+
+/// Parse `markdown` and render it as `format`.
+pub fn render_markdown(markdown: &str, format: Format) -> String {
+    Document::parse(markdown).render(format)
+}
+
+impl Document {
+    /// Parse the Markdown subset models produce: fenced code, headings,
+    /// quotes, lists, pipe tables, rules and paragraphs with bold, italic,
+    /// strikethrough, inline code and links. Single newlines are kept as line
+    /// breaks, matching how chat clients display messages.
+    pub fn parse(markdown: &str) -> Self {
+        let lines: Vec<&str> = markdown.lines().collect();
+        let mut blocks = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let trimmed = lines[i].trim_start();
+            if trimmed.is_empty() {
+                i += 1;
+                continue;
+            }
+
+            if let Some(fence) = fence_marker(trimmed) {
+                let language = trimmed[fence.len()..]
+                    .split_whitespace()
+                    .next()
+                    .map(str::to_string);
+                let mut code = Vec::new();
+                i += 1;
+                while i < lines.len() && !closes_fence(lines[i], fence) {
+                    code.push(lines[i]);
+                    i += 1;
+                }
+                i += 1;
+                blocks.push(Block::Code {
+                    language,
+                    code: code.join("\n"),
+                });
+                continue;
+            }
+
+            if let Some((level, text)) = heading(trimmed) {
+                blocks.push(Block::Heading {
+                    level,
+                    content: parse_inlines(text),
+                });
+                i += 1;
+                continue;
+            }
+
+            if is_rule(trimmed) {
+                blocks.push(Block::Rule);
+                i += 1;
+                continue;
+            }
+
+            if trimmed.starts_with('>') {
+                let mut quoted = Vec::new();
+                while i < lines.len() {
+                    let Some(rest) = lines[i].trim_start().strip_prefix('>') else {
+                        break;
+                    };
+                    quoted.push(rest.strip_prefix(' ').unwrap_or(rest));
+                    i += 1;
+                }
+                blocks.push(Block::Quote(parse_lines(&quoted)));
+                continue;
+            }
+
+            if list_item(lines[i]).is_some() {
+                let mut items: Vec<ListItem> = Vec::new();
+                while i < lines.len() {
+                    if let Some(item) = list_item(lines[i]) {
+                        items.push(item);
+                    } else if lines[i].starts_with([' ', '\t']) && !lines[i].trim().is_empty() {
+                        // Indented continuation of the previous item.
+                        if let Some(last) = items.last_mut() {
+                            last.content.push(Inline::LineBreak);
+                            last.content.extend(parse_inlines(lines[i].trim()));
+                        }
+                    } else {
+                        break;
+                    }
+                    i += 1;
+                }
+                blocks.push(Block::List(items));
+                continue;
+            }
+
+            if lines[i].contains('|') && lines.get(i + 1).is_some_and(|l| is_table_separator(l)) {
+                let header = table_row(lines[i]);
+                i += 2;
+                let mut rows = Vec::new();
+                while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                    rows.push(table_row(lines[i]));
+                    i += 1;
+                }
+                blocks.push(Block::Table { header, rows });
+                continue;
+            }
+
+            let mut paragraph = vec![trimmed.trim_end()];
+            i += 1;
+            while i < lines.len() && !lines[i].trim().is_empty() && !starts_block(&lines, i) {
+                paragraph.push(lines[i].trim());
+                i += 1;
+            }
+            blocks.push(Block::Paragraph(parse_lines(&paragraph)));
+        }
+
+        Self { blocks }
+    }
+
+    /// Render the document in `format`.
+    pub fn render(&self, format: Format) -> String {
+        let separator = match format {
+            Format::MatrixHtml | Format::EmailHtml => "\n",
+            _ => "\n\n",
+        };
+        self.blocks
+            .iter()
+            .map(|block| render_block(block, format))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Slack Block Kit blocks for documents with headings or rules, which
+    /// `mrkdwn` text cannot express. Returns `None` when plain `mrkdwn` is
+    /// enough or the message would exceed Slack's 50-block limit.
+    pub fn slack_blocks(&self) -> Option<serde_json::Value> {
+        const MAX_BLOCKS: usize = 50;
+        const MAX_SECTION_CHARS: usize = 3000;
+        const MAX_HEADER_CHARS: usize = 150;
+
+        let structured = self
+            .blocks
+            .iter()
+            .any(|block| matches!(block, Block::Heading { .. } | Block::Rule));
+        if !structured {
+            return None;
+        }
+
+        let mut blocks = Vec::new();
+        for block in &self.blocks {
+            match block {
+                Block::Heading { content, .. } => {
+                    let text: String = plain_text(content).chars().take(MAX_HEADER_CHARS).collect();
+                    blocks.push(serde_json::json!({
+                        "type": "header",
+                        "text": {"type": "plain_text", "text": text},
+                    }));
+                }
+                Block::Rule => blocks.push(serde_json::json!({"type": "divider"})),
+                other => {
+                    let text = render_block(other, Format::SlackMrkdwn);
+                    for chunk in split_markdown(&text, MAX_SECTION_CHARS) {
+                        blocks.push(serde_json::json!({
+                            "type": "section",
+                            "text": {"type": "mrkdwn", "text": chunk},
+                        }));
+                    }
+                }
+            }
+        }
+        (blocks.len() <= MAX_BLOCKS).then(|| serde_json::Value::Array(blocks))
+    }
+}
+
+// ── Block parsing ───────────────────────────────────────────────
+
+fn fence_marker(trimmed: &str) -> Option<&str> {
+    let ch = trimmed.chars().next()?;
+    if ch != '`' && ch != '~' {
+        return None;
+    }
+    let len = trimmed.bytes().take_while(|b| *b == ch as u8).count();
+    (len >= 3).then(|| &trimmed[..len])
+}
+
+fn closes_fence(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let Some(ch) = fence.chars().next() else {
+        return false;
+    };
+    trimmed.len() >= fence.len() && trimmed.chars().all(|c| c == ch)
+}
+
+fn heading(trimmed: &str) -> Option<(u8, &str)> {
+    let level = trimmed.bytes().take_while(|b| *b == b'#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    #[allow(clippy::cast_possible_truncation)]
+    Some((level as u8, text))
+}
+
+fn is_rule(trimmed: &str) -> bool {
+    let compact: String = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|c| c == *marker))
+}
+
+fn list_item(line: &str) -> Option<ListItem> {
+    let indent: usize = line
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let trimmed = line.trim_start();
+
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(bullet) {
+            return Some(ListItem {
+                depth: indent / 2,
+                number: None,
+                content: parse_inlines(rest.trim()),
+            });
+        }
+    }
+
+    let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+    if (1..=9).contains(&digits) {
+        let rest = &trimmed[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some(ListItem {
+                depth: indent / 2,
+                number: trimmed[..digits].parse().ok(),
+                content: parse_inlines(rest.trim()),
+            });
+        }
+    }
+    None
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let trimmed = line.trim();
+    // A lone `---` is a rule, not a table separator.
+    if !trimmed.contains('|') {
+        return false;
+    }
+    let inner = trimmed.trim_start_matches('|').trim_end_matches('|');
+    !inner.is_empty()
+        && inner.split('|').all(|cell| {
+            let cell = cell.trim();
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+fn table_row(line: &str) -> Vec<Vec<Inline>> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix('|').unwrap_or(trimmed);
+    trimmed
+        .split('|')
+        .map(|cell| parse_inlines(cell.trim()))
+        .collect()
+}
+
+fn starts_block(lines: &[&str], i: usize) -> bool {
+    let trimmed = lines[i].trim_start();
+    fence_marker(trimmed).is_some()
+        || heading(trimmed).is_some()
+        || trimmed.starts_with('>')
+        || list_item(lines[i]).is_some()
+        || is_rule(trimmed)
+        || (lines[i].contains('|') && lines.get(i + 1).is_some_and(|l| is_table_separator(l)))
+}
+
+// ── Inline parsing ──────────────────────────────────────────────
+
+fn parse_lines(lines: &[&str]) -> Vec<Inline> {
+    let mut out = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            out.push(Inline::LineBreak);
+        }
+        out.extend(parse_inlines(line));
+    }
+    out
+}
+
+fn parse_inlines(text: &str) -> Vec<Inline> {
+    let mut out = Vec::new();
+    let mut buf = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+
+        if c == '\\' {
+            if let Some(next) = rest[1..].chars().next().filter(char::is_ascii_punctuation) {
+                buf.push(next);
+                i += 1 + next.len_utf8();
+                continue;
+            }
+        }
+
+        if c == '`' {
+            let run = rest.bytes().take_while(|b| *b == b'`').count();
+            let delim = &rest[..run];
+            if let Some(end) = rest[run..].find(delim) {
+                let code = &rest[run..run + end];
+                let code = if code.len() > 1 && code.starts_with(' ') && code.ends_with(' ') {
+                    &code[1..code.len() - 1]
+                } else {
+                    code
+                };
+                flush_text(&mut buf, &mut out);
+                out.push(Inline::Code(code.to_string()));
+                i += run + end + run;
+            } else {
+                buf.push_str(delim);
+                i += run;
+            }
+            continue;
+        }
+
+        if let Some(delim) = ["**", "__", "~~"].into_iter().find(|d| rest.starts_with(d)) {
+            if let Some(end) = rest[2..].find(delim).filter(|end| *end > 0) {
+                let inner = parse_inlines(&rest[2..2 + end]);
+                flush_text(&mut buf, &mut out);
+                out.push(if delim == "~~" {
+                    Inline::Strike(inner)
+                } else {
+                    Inline::Bold(inner)
+                });
+                i += 4 + end;
+                continue;
+            }
+        }
+
+        if c == '*' || c == '_' {
+            let opens_word = c == '*'
+                || text[..i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|prev| !prev.is_alphanumeric());
+            let next_is_text = rest[1..].chars().next().is_some_and(|n| !n.is_whitespace());
+            if opens_word && next_is_text {
+                if let Some(end) = italic_close(&rest[1..], c) {
+                    let inner = parse_inlines(&rest[1..=end]);
+                    flush_text(&mut buf, &mut out);
+                    out.push(Inline::Italic(inner));
+                    i += end + 2;
+                    continue;
+                }
+            }
+        }
+
+        if c == '[' {
+            if let Some((label, url, consumed)) = parse_link(rest) {
+                flush_text(&mut buf, &mut out);
+                out.push(Inline::Link {
+                    text: parse_inlines(label),
+                    url: url.to_string(),
+                });
+                i += consumed;
+                continue;
+            }
+        }
+
+        if c == '<' {
+            if let Some(end) = rest.find('>') {
+                let inner = &rest[1..end];
+                if (inner.starts_with("https://") || inner.starts_with("http://"))
+                    && !inner.contains(char::is_whitespace)
+                {
+                    flush_text(&mut buf, &mut out);
+                    out.push(Inline::Link {
+                        text: vec![Inline::Text(inner.to_string())],
+                        url: inner.to_string(),
+                    });
+                    i += end + 1;
+                    continue;
+                }
+            }
+        }
+
+        buf.push(c);
+        i += c.len_utf8();
+    }
+
+    flush_text(&mut buf, &mut out);
+    out
+}
+
+fn flush_text(buf: &mut String, out: &mut Vec<Inline>) {
+    if !buf.is_empty() {
+        out.push(Inline::Text(std::mem::take(buf)));
+    }
+}
+
+/// Byte offset (within `s`) of the delimiter closing an italic span.
+fn italic_close(s: &str, delim: char) -> Option<usize> {
+    let mut prev: Option<char> = None;
+    let mut chars = s.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let next = chars.peek().map(|(_, n)| *n);
+        if ch == delim
+            && prev.is_some_and(|p| !p.is_whitespace())
+            && next != Some(delim)
+            && (delim == '*' || next.is_none_or(|n| !n.is_alphanumeric()))
+        {
+            return Some(index);
+        }
+        prev = Some(ch);
+    }
+    None
+}
+
+/// Parse `[label](url)` at the start of `s`.
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let mut depth = 0usize;
+    let mut label_end = None;
+    for (index, ch) in s.char_indices() {
+        match ch {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    label_end = Some(index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let label_end = label_end?;
+    let after = &s[label_end + 1..];
+    if !after.starts_with('(') {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    for (index, ch) in after.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    let url = after[1..index].trim();
+                    if url.is_empty() || url.contains(char::is_whitespace) {
+                        return None;
+                    }
+                    return Some((&s[1..label_end], url, label_end + 1 + index + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// ── Rendering ───────────────────────────────────────────────────
+
+fn render_block(block: &Block, format: Format) -> String {
+    match block {
+        Block::Paragraph(content) => {
+            let text = render_inlines(content, format);
+            if matches!(format, Format::MatrixHtml | Format::EmailHtml) {
+                format!("<p>{text}</p>")
+            } else {
+                text
+            }
+        }
+        Block::Heading { level, content } => render_heading(*level, content, format),
+        Block::Code { language, code } => render_code_block(language.as_deref(), code, format),
+        Block::Quote(content) => {
+            let text = render_inlines(content, format);
+            if format.is_html() {
+                format!("<blockquote>{text}</blockquote>")
+            } else {
+                let marker = if format == Format::TelegramMarkdownV2 {
+                    ">"
+                } else {
+                    "> "
+                };
+                prefix_lines(&text, marker)
+            }
+        }
+        Block::List(items) => render_list(items, format),
+        Block::Table { header, rows } => {
+            render_code_block(None, &table_to_monospace(header, rows), format)
+        }
+        Block::Rule => {
+            if matches!(format, Format::MatrixHtml | Format::EmailHtml) {
+                "<hr>".to_string()
+            } else {
+                "──────────".to_string()
+            }
+        }
+    }
+}
+
+fn render_heading(level: u8, content: &[Inline], format: Format) -> String {
+    let text = render_inlines(content, format);
+    match format {
+        Format::TelegramHtml => format!("<b>{text}</b>"),
+        Format::TelegramMarkdownV2 | Format::SlackMrkdwn => format!("*{text}*"),
+        Format::Discord if level <= 3 => format!("{} {text}", "#".repeat(usize::from(level))),
+        Format::Discord => format!("**{text}**"),
+        Format::MatrixHtml | Format::EmailHtml => format!("<h{level}>{text}</h{level}>"),
+        Format::Irc => format!("\x02{text}\x02"),
+        Format::PlainText => text,
+    }
+}
+
+fn render_code_block(language: Option<&str>, code: &str, format: Format) -> String {
+    // Only pass through plain language names; anything else could smuggle
+    // attributes or markup into the output.
+    let language = language
+        .filter(|lang| {
+            lang.len() <= 32
+                && lang
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '#' | '.'))
+        })
+        .unwrap_or("");
+    match format {
+        // Telegram rejects class attributes on <code>.
+        Format::TelegramHtml => format!("<pre><code>{}</code></pre>", escape_html(code)),
+        Format::MatrixHtml | Format::EmailHtml if !language.is_empty() => format!(
+            "<pre><code class=\"language-{language}\">{}</code></pre>",
+            escape_html(code)
+        ),
+        Format::MatrixHtml | Format::EmailHtml => {
+            format!("<pre><code>{}</code></pre>", escape_html(code))
+        }
+        Format::TelegramMarkdownV2 => {
+            format!("```{language}\n{}\n```", escape_markdown_v2_code(code))
+        }
+        Format::SlackMrkdwn => format!("```\n{}\n```", escape_slack(code)),
+        Format::Discord => format!("```{language}\n{code}\n```"),
+        Format::Irc | Format::PlainText => code.to_string(),
+    }
+}
+
+fn render_list(items: &[ListItem], format: Format) -> String {
+    if matches!(format, Format::MatrixHtml | Format::EmailHtml) {
+        return render_html_list(items, format);
+    }
+    items
+        .iter()
+        .map(|item| {
+            let marker = match (item.number, format) {
+                (Some(n), Format::TelegramMarkdownV2) => format!("{n}\\."),
+                (Some(n), _) => format!("{n}."),
+                (None, Format::Discord) => "-".to_string(),
+                (None, _) => "•".to_string(),
+            };
+            format!(
+                "{}{marker} {}",
+                "  ".repeat(item.depth),
+                render_inlines(&item.content, format)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_html_list(items: &[ListItem], format: Format) -> String {
+    let mut out = String::new();
+    // Open list tags, innermost last. The last `<li>` of every open list is
+    // still open, so a deeper list nests inside its parent item.
+    let mut open: Vec<&'static str> = Vec::new();
+    for item in items {
+        let tag = if item.number.is_some() { "ol" } else { "ul" };
+        let depth = item.depth.min(open.len());
+        while open.len() > depth + 1 {
+            let _ = write!(out, "</li></{}>", open.pop().unwrap_or("ul"));
+        }
+        if open.len() == depth + 1 && open.last() != Some(&tag) {
+            let _ = write!(out, "</li></{}>", open.pop().unwrap_or("ul"));
+        }
+        if open.len() == depth + 1 {
+            out.push_str("</li>");
+        } else {
+            match item.number.filter(|n| *n != 1) {
+                Some(start) => {
+                    let _ = write!(out, "<ol start=\"{start}\">");
+                }
+                None => {
+                    let _ = write!(out, "<{tag}>");
+                }
+            }
+            open.push(tag);
+        }
+        let _ = write!(out, "<li>{}", render_inlines(&item.content, format));
+    }
+    while let Some(tag) = open.pop() {
+        let _ = write!(out, "</li></{tag}>");
+    }
+    out
+}
+
+fn table_to_monospace(header: &[Vec<Inline>], rows: &[Vec<Vec<Inline>>]) -> String {
+    let to_text = |cells: &[Vec<Inline>]| -> Vec<String> {
+        cells.iter().map(|cell| plain_text(cell)).collect()
+    };
+    let header = to_text(header);
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| to_text(row)).collect();
+
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([header.len()])
+        .max()
+        .unwrap_or(0);
+    let mut widths = vec![0usize; columns];
+    for row in rows.iter().chain([&header]) {
+        for (index, cell) in row.iter().enumerate() {
+            widths[index] = widths[index].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |row: &[String]| -> String {
+        (0..columns)
+            .map(|index| {
+                let cell = row.get(index).map_or("", String::as_str);
+                let pad = widths[index] - cell.chars().count();
+                format!("{cell}{}", " ".repeat(pad))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_row(&header)];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    lines.extend(rows.iter().map(|row| format_row(row)));
+    lines.join("\n")
+}
+
+fn render_inlines(inlines: &[Inline], format: Format) -> String {
+    let mut out = String::new();
+    for inline in inlines {
+        render_inline(inline, format, &mut out);
+    }
+    out
+}
+
+fn render_inline(inline: &Inline, format: Format, out: &mut String) {
+    match inline {
+        Inline::Text(text) => out.push_str(&escape_text(text, format)),
+        Inline::LineBreak => {
+            if matches!(format, Format::MatrixHtml | Format::EmailHtml) {
+                out.push_str("<br>");
+            }
+            out.push('\n');
+        }
+        Inline::Bold(inner) => {
+            let (open, close) = match format {
+                Format::TelegramHtml => ("<b>", "</b>"),
+                Format::MatrixHtml | Format::EmailHtml => ("<strong>", "</strong>"),
+                Format::TelegramMarkdownV2 | Format::SlackMrkdwn => ("*", "*"),
+                Format::Discord => ("**", "**"),
+                Format::Irc => ("\x02", "\x02"),
+                Format::PlainText => ("", ""),
+            };
+            wrap(inner, format, open, close, out);
+        }
+        Inline::Italic(inner) => {
+            let (open, close) = match format {
+                Format::TelegramHtml => ("<i>", "</i>"),
+                Format::MatrixHtml | Format::EmailHtml => ("<em>", "</em>"),
+                Format::TelegramMarkdownV2 | Format::SlackMrkdwn => ("_", "_"),
+                Format::Discord => ("*", "*"),
+                Format::Irc => ("\x1d", "\x1d"),
+                Format::PlainText => ("", ""),
+            };
+            wrap(inner, format, open, close, out);
+        }
+        Inline::Strike(inner) => {
+            let (open, close) = match format {
+                Format::TelegramHtml => ("<s>", "</s>"),
+                Format::MatrixHtml | Format::EmailHtml => ("<del>", "</del>"),
+                Format::TelegramMarkdownV2 | Format::SlackMrkdwn => ("~", "~"),
+                Format::Discord => ("~~", "~~"),
+                Format::Irc => ("\x1e", "\x1e"),
+                Format::PlainText => ("", ""),
+            };
+            wrap(inner, format, open, close, out);
+        }
+        Inline::Code(code) => match format {
+            Format::TelegramHtml | Format::MatrixHtml | Format::EmailHtml => {
+                let _ = write!(out, "<code>{}</code>", escape_html(code));
+            }
+            Format::TelegramMarkdownV2 => {
+                let _ = write!(out, "`{}`", escape_markdown_v2_code(code));
+            }
+            Format::SlackMrkdwn => {
+                let _ = write!(out, "`{}`", escape_slack(code));
+            }
+            Format::Discord if code.contains('`') => {
+                let _ = write!(out, "`` {code} ``");
+            }
+            Format::Discord => {
+                let _ = write!(out, "`{code}`");
+            }
+            Format::Irc => {
+                let _ = write!(out, "\x11{code}\x11");
+            }
+            Format::PlainText => out.push_str(code),
+        },
+        Inline::Link { text, url } => render_link(text, url, format, out),
+    }
+}
+
+fn wrap(inner: &[Inline], format: Format, open: &str, close: &str, out: &mut String) {
+    out.push_str(open);
+    for inline in inner {
+        render_inline(inline, format, out);
+    }
+    out.push_str(close);
+}
+
+fn render_link(text: &[Inline], url: &str, format: Format, out: &mut String) {
+    let label = plain_text(text);
+    let web = url.starts_with("https://") || url.starts_with("http://");
+    match format {
+        Format::TelegramHtml | Format::MatrixHtml | Format::EmailHtml
+            if web || url.starts_with("mailto:") =>
+        {
+            let _ = write!(
+                out,
+                "<a href=\"{}\">{}</a>",
+                escape_html(url),
+                render_inlines(text, format)
+            );
+        }
+        Format::TelegramMarkdownV2 if web => {
+            let _ = write!(
+                out,
+                "[{}]({})",
+                render_inlines(text, format),
+                url.replace('\\', "\\\\").replace(')', "\\)")
+            );
+        }
+        Format::SlackMrkdwn if web => {
+            let _ = write!(out, "<{}|{}>", escape_slack(url), escape_slack(&label));
+        }
+        Format::Discord if web && label != url => {
+            let _ = write!(out, "[{}]({url})", render_inlines(text, format));
+        }
+        _ if label == url => out.push_str(&escape_text(url, format)),
+        _ => {
+            out.push_str(&render_inlines(text, format));
+            out.push_str(&escape_text(&format!(" ({url})"), format));
+        }
+    }
+}
+
+fn plain_text(inlines: &[Inline]) -> String {
+    render_inlines(inlines, Format::PlainText)
+}
+
+fn prefix_lines(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{prefix}{line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape_text(text: &str, format: Format) -> String {
+    match format {
+        Format::TelegramHtml | Format::MatrixHtml | Format::EmailHtml => escape_html(text),
+        Format::TelegramMarkdownV2 => escape_markdown_v2(text),
+        Format::SlackMrkdwn => escape_slack(text),
+        Format::Discord | Format::Irc | Format::PlainText => text.to_string(),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_markdown_v2(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if "_*[]()~`>#+-=|{}.!\\".contains(ch) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+fn escape_markdown_v2_code(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`")
+}
+
+// ── Splitting ───────────────────────────────────────────────────
+
+/// Split Markdown into chunks of at most `max_chars` characters.
+///
+/// Breaks at a newline in the second half of the window, else at a space,
+/// else at the limit. A code fence open at a break is closed in that chunk
+/// and reopened in the next.
+pub fn split_markdown(text: &str, max_chars: usize) -> Vec<String> {
+    split_measured(text, max_chars, max_chars, Measure::Chars)
+}
+
+/// Like [`split_markdown`], but chunks hold at most `max_bytes` bytes of
+/// UTF-8, for platforms whose limit is in bytes. Breaks stay on character
+/// boundaries.
+pub fn split_markdown_bytes(text: &str, max_bytes: usize) -> Vec<String> {
+    split_measured(text, max_bytes, max_bytes, Measure::Bytes)
+}
+
+/// Like [`split_markdown`], but a remainder of up to `tail_chars` is kept
+/// whole. Lets callers reserve room for continuation markers on every chunk
+/// except the last.
+pub fn split_markdown_with_tail(text: &str, chunk_chars: usize, tail_chars: usize) -> Vec<String> {
+    split_measured(text, chunk_chars, tail_chars, Measure::Chars)
+}
+
+/// Unit chunk limits are counted in.
+#[derive(Debug, Clone, Copy)]
+enum Measure {
+    Chars,
+    Bytes,
+}
+
+impl Measure {
+    fn len(self, text: &str) -> usize {
+        match self {
+            Self::Chars => text.chars().count(),
+            Self::Bytes => text.len(),
+        }
+    }
+
+    /// Byte offset of the longest prefix of `text` within `budget` units.
+    fn prefix_end(self, text: &str, budget: usize) -> usize {
+        match self {
+            Self::Chars => text
+                .char_indices()
+                .nth(budget)
+                .map_or(text.len(), |(idx, _)| idx),
+            Self::Bytes if budget >= text.len() => text.len(),
+            Self::Bytes => {
+                let mut end = budget;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                // Always make progress, even when the first char is wider.
+                if end == 0 {
+                    text.chars().next().map_or(0, char::len_utf8)
+                } else {
+                    end
+                }
+            }
+        }
+    }
+}
+
+fn split_measured(
+    text: &str,
+    chunk_chars: usize,
+    tail_chars: usize,
+    measure: Measure,
+) -> Vec<String> {
+    if measure.len(text) <= tail_chars.max(chunk_chars) {
+        return vec![text.to_string()];
+    }
+
+    let longest_fence = text
+        .lines()
+        .filter_map(|line| fence_marker(line.trim_start()))
+        .map(str::len)
+        .max();
+    // Room for "\n" plus the closing fence when a chunk ends inside code.
+    let close_reserve = longest_fence.map_or(0, |len| len + 1);
+
+    let mut chunks = Vec::new();
+    let mut remaining = text;
+    let mut reopen: Option<String> = None;
+
+    while !remaining.is_empty() {
+        let prefix = reopen.as_ref().map(|opener| format!("{opener}\n"));
+        let prefix_chars = prefix.as_deref().map_or(0, |p| measure.len(p));
+
+        if prefix_chars + measure.len(remaining) <= tail_chars {
+            chunks.push(format!("{}{remaining}", prefix.unwrap_or_default()));
+            break;
+        }
+
+        let budget = chunk_chars
+            .saturating_sub(prefix_chars + close_reserve)
+            .max(1);
+        let end = break_point(remaining, budget, measure);
+        let piece = &remaining[..end];
+        remaining = &remaining[end..];
+
+        let open_fence = open_fence_after(reopen.as_deref(), piece);
+        let mut chunk = prefix.unwrap_or_default();
+        chunk.push_str(piece);
+        if let Some(opener) = &open_fence {
+            if !remaining.is_empty() {
+                if !chunk.ends_with('\n') {
+                    chunk.push('\n');
+                }
+                chunk.push_str(fence_marker(opener).unwrap_or("```"));
+            }
+        }
+        chunks.push(chunk);
+        reopen = open_fence.filter(|_| !remaining.is_empty());
+    }
+
+    chunks
+}
+
+/// Byte offset to break `text` at, keeping the first part within `budget`.
+fn break_point(text: &str, budget: usize, measure: Measure) -> usize {
+    let hard_split = measure.prefix_end(text, budget);
+    if hard_split == text.len() {
+        return hard_split;
+    }
+
+    let search_area = &text[..hard_split];
+    if let Some(pos) = search_area.rfind('\n') {
+        // Don't break at a newline too close to the start of the window.
+        if measure.len(&search_area[..pos]) >= budget / 2 {
+            pos + 1
+        } else {
+            search_area.rfind(' ').map_or(hard_split, |space| space + 1)
+        }
+    } else if let Some(pos) = search_area.rfind(' ') {
+        pos + 1
+    } else {
+        hard_split
+    }
+}
+
+/// Opener line of the code fence still open after `piece`, given the fence
+/// (if any) open at its start.
+fn open_fence_after(open_at_start: Option<&str>, piece: &str) -> Option<String> {
+    let mut open = open_at_start.map(str::to_string);
+    for line in piece.lines() {
+        let trimmed = line.trim_start();
+        match &open {
+            Some(opener) => {
+                if closes_fence(line, fence_marker(opener).unwrap_or("```")) {
+                    open = None;
+                }
+            }
+            None => {
+                if fence_marker(trimmed).is_some() {
+                    open = Some(trimmed.trim_end().to_string());
+                }
+            }
+        }
+    }
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "## Results\n\nFound **3** issues in `main.rs`, see [docs](https://example.com/a_b).\n\n- first *item*\n- second ~~item~~\n  1. nested\n\n```rust\nfn main() {}\n```\n\n| Name | Count |\n|---|---:|\n| alpha | 1 |\n| beta | 22 |";
+
+    #[test]
+    fn parses_blocks_and_inlines() {
+        let doc = Document::parse(SAMPLE);
+
+        assert_eq!(doc.blocks.len(), 5);
+        assert!(matches!(doc.blocks[0], Block::Heading { level: 2, .. }));
+        assert_eq!(
+            doc.blocks[1],
+            Block::Paragraph(vec![
+                Inline::Text("Found ".into()),
+                Inline::Bold(vec![Inline::Text("3".into())]),
+                Inline::Text(" issues in ".into()),
+                Inline::Code("main.rs".into()),
+                Inline::Text(", see ".into()),
+                Inline::Link {
+                    text: vec![Inline::Text("docs".into())],
+                    url: "https://example.com/a_b".into(),
+                },
+                Inline::Text(".".into()),
+            ])
+        );
+        let Block::List(items) = &doc.blocks[2] else {
+            panic!("expected list");
+        };
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2].depth, 1);
+        assert_eq!(items[2].number, Some(1));
+        assert_eq!(
+            doc.blocks[3],
+            Block::Code {
+                language: Some("rust".into()),
+                code: "fn main() {}".into(),
+            }
+        );
+        assert!(matches!(&doc.blocks[4], Block::Table { rows, .. } if rows.len() == 2));
+    }
+
+    #[test]
+    fn snake_case_and_arithmetic_are_not_emphasis() {
+        let doc = Document::parse("use my_var_name and 2 * 3 * 4");
+        assert_eq!(
+            doc.blocks,
+            vec![Block::Paragraph(vec![Inline::Text(
+                "use my_var_name and 2 * 3 * 4".into()
+            )])]
+        );
+    }
+
+    #[test]
+    fn renders_telegram_html() {
+        let html = render_markdown(SAMPLE, Format::TelegramHtml);
+
+        assert!(html.starts_with("<b>Results</b>\n\nFound <b>3</b> issues in <code>main.rs</code>, see <a href=\"https://example.com/a_b\">docs</a>."));
+        assert!(html.contains("• first <i>item</i>\n• second <s>item</s>\n  1. nested"));
+        assert!(html.contains("<pre><code>fn main() {}</code></pre>"));
+        assert!(html.contains(
+            "<pre><code>Name  | Count\n------+------\nalpha | 1\nbeta  | 22</code></pre>"
+        ));
+    }
+
+    #[test]
+    fn renders_telegram_markdown_v2_with_escaping() {
+        let rendered = render_markdown("Done. Cost: 1+1 = **2**!", Format::TelegramMarkdownV2);
+        assert_eq!(rendered, "Done\\. Cost: 1\\+1 \\= *2*\\!");
+
+        let code = render_markdown("```\nlet s = `x`;\n```", Format::TelegramMarkdownV2);
+        assert_eq!(code, "```\nlet s = \\`x\\`;\n```");
+    }
+
+    #[test]
+    fn renders_slack_mrkdwn() {
+        let rendered = render_markdown(
+            "**Deploy** <ok> [runbook](https://x.test/r) ~~old~~",
+            Format::SlackMrkdwn,
+        );
+        assert_eq!(
+            rendered,
+            "*Deploy* &lt;ok&gt; <https://x.test/r|runbook> ~old~"
+        );
+    }
+
+    #[test]
+    fn slack_blocks_only_for_structured_documents() {
+        assert!(Document::parse("just text").slack_blocks().is_none());
+
+        let blocks = Document::parse("# Title\n\nbody **bold**\n\n---")
+            .slack_blocks()
+            .unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[0]["text"]["text"], "Title");
+        assert_eq!(blocks[1]["text"]["text"], "body *bold*");
+        assert_eq!(blocks[2]["type"], "divider");
+    }
+
+    #[test]
+    fn renders_discord_markdown() {
+        let rendered = render_markdown("#### Deep\n\n* a\n* b", Format::Discord);
+        assert_eq!(rendered, "**Deep**\n\n- a\n- b");
+    }
+
+    #[test]
+    fn renders_matrix_html_lists_and_code_language() {
+        let html = render_markdown(
+            "1. one\n2. two\n   - sub\n\n```py\nx = 1\n```",
+            Format::MatrixHtml,
+        );
+        assert_eq!(
+            html,
+            "<ol><li>one</li><li>two<ul><li>sub</li></ul></li></ol>\n<pre><code class=\"language-py\">x = 1</code></pre>"
+        );
+
+        let html = render_markdown("1. one\n   - sub\n2. two", Format::MatrixHtml);
+        assert_eq!(
+            html,
+            "<ol><li>one<ul><li>sub</li></ul></li><li>two</li></ol>"
+        );
+    }
+
+    #[test]
+    fn renders_irc_control_codes() {
+        let rendered = render_markdown("**bold** _it_ `code` [site](https://x.test)", Format::Irc);
+        assert_eq!(
+            rendered,
+            "\x02bold\x02 \x1dit\x1d \x11code\x11 site (https://x.test)"
+        );
+    }
+
+    #[test]
+    fn renders_email_html_and_text() {
+        let doc = Document::parse("Hello **team**\nline two\n\n> quoted");
+        assert_eq!(
+            doc.render(Format::EmailHtml),
+            "<p>Hello <strong>team</strong><br>\nline two</p>\n<blockquote>quoted</blockquote>"
+        );
+        assert_eq!(
+            doc.render(Format::PlainText),
+            "Hello team\nline two\n\n> quoted"
+        );
+    }
+
+    #[test]
+    fn code_language_with_markup_is_dropped() {
+        let html = render_markdown("```rust\" onclick=\"x\ncode\n```", Format::MatrixHtml);
+        assert_eq!(html, "<pre><code>code</code></pre>");
+    }
+
+    #[test]
+    fn split_without_fences_keeps_content() {
+        let text = "word ".repeat(1000);
+        let chunks = split_markdown(&text, 2000);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn byte_split_respects_utf8_length() {
+        let text = "日本語のテキスト ".repeat(500);
+        let chunks = split_markdown_bytes(&text, 1000);
+
+        assert!(chunks.len() > 10);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 1000, "chunk has {} bytes", chunk.len());
+        }
+        assert_eq!(chunks.concat(), text);
+
+        let unbroken = "é".repeat(700);
+        let chunks = split_markdown_bytes(&unbroken, 999);
+        assert_eq!(
+            chunks.iter().map(String::len).collect::<Vec<_>>(),
+            [998, 402]
+        );
+    }
+
+    #[test]
+    fn split_never_breaks_inside_code_fence() {
+        let mut code = String::new();
+        for n in 0..300 {
+            let _ = writeln!(code, "let v{n} = {n};");
+        }
+        let text = format!("Intro\n\n```rust\n{code}```\nOutro");
+        let chunks = split_markdown(&text, 1000);
+
+        assert!(chunks.len() > 2);
+        for chunk in &chunks {
+            assert!(
+                chunk.chars().count() <= 1000,
+                "chunk too long: {}",
+                chunk.len()
+            );
+            let fences = chunk.lines().filter(|l| l.starts_with("```")).count();
+            assert_eq!(fences % 2, 0, "unbalanced fence in chunk:\n{chunk}");
+        }
+        assert!(chunks[1].starts_with("```rust\n"));
+        assert!(chunks.last().unwrap().ends_with("Outro"));
+
+        // Every code line survives exactly once.
+        let joined = chunks.join("\n");
+        for n in [0, 150, 299] {
+            assert_eq!(joined.matches(&format!("let v{n} = {n};")).count(), 1);
+        }
+    }
+
+    #[test]
+    fn split_with_tail_keeps_short_remainder_whole() {
+        let text = "a".repeat(105);
+        let chunks = split_markdown_with_tail(&text, 90, 100);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), 90);
+        assert_eq!(chunks[1].len(), 15);
+    }
+}
@@ -1,4 +1,8 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::outbound::RateLimited;
+use super::render::{Document, Format};
+use super::traits::{
+    note_sent_message, Attachment, Channel, ChannelEvent, ChannelMessage, SendMessage,
+};
 use async_trait::async_trait;
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
@@ -100,6 +104,153 @@ impl SlackChannel {
             .map(str::to_string)
     }
 
+    /// Message subtypes other than plain user messages and file uploads.
+    fn is_ignored_subtype(msg: &serde_json::Value) -> bool {
+        msg.get("subtype")
+            .is_some_and(|subtype| subtype.as_str() != Some("file_share"))
+    }
+
+    /// Edits and deletions of earlier messages, delivered in Socket Mode as
+    /// `message_changed` / `message_deleted` subtypes.
+    fn parse_revision_event(
+        &self,
+        event: &serde_json::Value,
+        scoped_channel: Option<&str>,
+        bot_user_id: &str,
+    ) -> Option<ChannelMessage> {
+        let (kind, message) = match event.get("subtype").and_then(|v| v.as_str())? {
+            "message_changed" => (ChannelEvent::Edited, event.get("message")?),
+            "message_deleted" => (ChannelEvent::Deleted, event.get("previous_message")?),
+            _ => return None,
+        };
+
+        let channel_id = event.get("channel").and_then(|v| v.as_str())?;
+        if scoped_channel.is_some_and(|scoped| scoped != channel_id) {
+            return None;
+        }
+        let user = message.get("user").and_then(|v| v.as_str())?;
+        if user == bot_user_id || !self.is_user_allowed(user) {
+            return None;
+        }
+        let ts = message
+            .get("ts")
+            .or_else(|| event.get("deleted_ts"))
+            .and_then(|v| v.as_str())?;
+
+        let content = if kind == ChannelEvent::Edited {
+            let text = message.get("text").and_then(|v| v.as_str())?;
+            // Unfurls and other attachment updates also arrive as
+            // `message_changed`; only text changes count as edits.
+            let previous = event
+                .get("previous_message")
+                .and_then(|m| m.get("text"))
+                .and_then(|v| v.as_str());
+            if previous == Some(text) {
+                return None;
+            }
+            let text = if Self::contains_bot_mention(text, bot_user_id) {
+                Self::strip_bot_mentions(text, bot_user_id)
+            } else {
+                text.trim().to_string()
+            };
+            if text.is_empty() {
+                return None;
+            }
+            text
+        } else {
+            String::new()
+        };
+
+        Some(ChannelMessage {
+            id: format!("slack_{channel_id}_{ts}"),
+            sender: user.to_string(),
+            reply_target: channel_id.to_string(),
+            content,
+            channel: "slack".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: Self::inbound_thread_ts(message, ts),
+            attachments: Vec::new(),
+            event: kind,
+        })
+    }
+
+    /// Accept both raw Slack `ts` values and inbound `slack_<channel>_<ts>` ids.
+    fn message_ts(message_id: &str) -> &str {
+        message_id.rsplit('_').next().unwrap_or(message_id)
+    }
+
+    async fn post_chat_method(&self, method: &str, body: &serde_json::Value) -> anyhow::Result<()> {
+        let resp = self
+            .http_client()
+            .post(format!("https://slack.com/api/{method}"))
+            .bearer_auth(&self.bot_token)
+            .json(body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if !status.is_success() {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Slack {method} failed ({status}): {sanitized}");
+        }
+
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack {method} failed: {err}");
+        }
+
+        Ok(())
+    }
+
+    /// Files shared with a message, fetched lazily with the bot token.
+    fn inbound_files(&self, msg: &serde_json::Value) -> Vec<Attachment> {
+        msg.get("files")
+            .and_then(|files| files.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|file| {
+                let url = file
+                    .get("url_private_download")
+                    .or_else(|| file.get("url_private"))
+                    .and_then(|v| v.as_str())?;
+                let name = file
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+                let mut attachment = Attachment::remote(url, name)
+                    .with_header("Authorization", format!("Bearer {}", self.bot_token));
+                if let Some(mime) = file.get("mimetype").and_then(|v| v.as_str()) {
+                    attachment = attachment.with_mime(mime);
+                }
+                if let Some(size) = file.get("size").and_then(serde_json::Value::as_u64) {
+                    attachment = attachment.with_size(size);
+                }
+                Some(attachment)
+            })
+            .collect()
+    }
+
+    /// Content for an upload without text; the attachment references are
+    /// appended by the channel runtime.
+    fn file_only_content(
+        text: &str,
+        files: &[Attachment],
+        require_mention: bool,
+    ) -> Option<String> {
+        (text.trim().is_empty() && !files.is_empty() && !require_mention).then(String::new)
+    }
+
     fn normalized_channel_id(input: Option<&str>) -> Option<String> {
         input
             .map(str::trim)
@@ -411,9 +562,17 @@ impl SlackChannel {
                 if event.get("type").and_then(|v| v.as_str()) != Some("message") {
                     continue;
                 }
-                // Skip non-user message subtypes (e.g. channel_join/message_changed)
+                if let Some(revision) =
+                    self.parse_revision_event(event, scoped_channel.as_deref(), bot_user_id)
+                {
+                    if tx.send(revision).await.is_err() {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                // Skip non-user message subtypes (e.g. channel_join/bot_message)
                 // to avoid invalid thread replies.
-                if event.get("subtype").is_some() {
+                if Self::is_ignored_subtype(event) {
                     continue;
                 }
 
@@ -447,7 +606,8 @@ impl SlackChannel {
                     .get("text")
                     .and_then(|v| v.as_str())
                     .unwrap_or_default();
-                if text.is_empty() {
+                let files = self.inbound_files(event);
+                if text.is_empty() && files.is_empty() {
                     continue;
                 }
 
@@ -471,6 +631,7 @@ impl SlackChannel {
 
                 let Some(normalized_text) =
                     Self::normalize_incoming_content(text, require_mention, bot_user_id)
+                        .or_else(|| Self::file_only_content(text, &files, require_mention))
                 else {
                     continue;
                 };
@@ -488,6 +649,8 @@ impl SlackChannel {
                         .unwrap_or_default()
                         .as_secs(),
                     thread_ts: Self::inbound_thread_ts(event, ts),
+                    attachments: files,
+                    event: ChannelEvent::Message,
                 };
 
                 if tx.send(channel_msg).await.is_err() {
@@ -652,11 +815,16 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let document = Document::parse(&message.content);
         let mut body = serde_json::json!({
             "channel": message.recipient,
-            "text": message.content
+            "text": document.render(Format::SlackMrkdwn)
         });
 
+        if let Some(blocks) = message.blocks.clone().or_else(|| document.slack_blocks()) {
+            body["blocks"] = blocks;
+        }
+
         if let Some(ref ts) = message.thread_ts {
             body["thread_ts"] = serde_json::json!(ts);
         }
@@ -670,11 +838,15 @@ impl Channel for SlackChannel {
             .await?;
 
         let status = resp.status();
+        let headers = resp.headers().clone();
         let body = resp
             .text()
             .await
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
 
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited::from_response("slack", &headers, &body).into());
+        }
         if !status.is_success() {
             let sanitized = crate::providers::sanitize_api_error(&body);
             anyhow::bail!("Slack chat.postMessage failed ({status}): {sanitized}");
@@ -689,6 +861,9 @@ impl Channel for SlackChannel {
                 .unwrap_or("unknown");
             anyhow::bail!("Slack chat.postMessage failed: {err}");
         }
+        if let Some(ts) = parsed.get("ts").and_then(|ts| ts.as_str()) {
+            note_sent_message(ts);
+        }
 
         Ok(())
     }
@@ -776,7 +951,7 @@ impl Channel for SlackChannel {
                     for msg in messages.iter().rev() {
                         // Skip non-user message subtypes (e.g. channel_join/message_changed)
                         // to avoid invalid thread replies.
-                        if msg.get("subtype").is_some() {
+                        if Self::is_ignored_subtype(msg) {
                             continue;
                         }
                         let ts = msg.get("ts").and_then(|t| t.as_str()).unwrap_or("");
@@ -785,6 +960,7 @@ impl Channel for SlackChannel {
                             .and_then(|u| u.as_str())
                             .unwrap_or("unknown");
                         let text = msg.get("text").and_then(|t| t.as_str()).unwrap_or("");
+                        let files = self.inbound_files(msg);
                         let last_ts = last_ts_by_channel
                             .get(&channel_id)
                             .map(String::as_str)
@@ -804,7 +980,7 @@ impl Channel for SlackChannel {
                         }
 
                         // Skip empty or already-seen
-                        if text.is_empty() || ts <= last_ts {
+                        if (text.is_empty() && files.is_empty()) || ts <= last_ts {
                             continue;
                         }
 
@@ -815,6 +991,7 @@ impl Channel for SlackChannel {
                             self.mention_only && is_group_message && !allow_sender_without_mention;
                         let Some(normalized_text) =
                             Self::normalize_incoming_content(text, require_mention, &bot_user_id)
+                                .or_else(|| Self::file_only_content(text, &files, require_mention))
                         else {
                             continue;
                         };
@@ -832,6 +1009,8 @@ impl Channel for SlackChannel {
                                 .unwrap_or_default()
                                 .as_secs(),
                             thread_ts: Self::inbound_thread_ts(msg, ts),
+                            attachments: files,
+                            event: ChannelEvent::Message,
                         };
 
                         if tx.send(channel_msg).await.is_err() {
@@ -843,6 +1022,39 @@ impl Channel for SlackChannel {
         }
     }
 
+    fn supports_message_edits(&self) -> bool {
+        true
+    }
+
+    async fn edit_message(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let document = Document::parse(text);
+        self.post_chat_method(
+            "chat.update",
+            &serde_json::json!({
+                "channel": recipient,
+                "ts": Self::message_ts(message_id),
+                "text": document.render(Format::SlackMrkdwn),
+            }),
+        )
+        .await
+    }
+
+    async fn delete_message(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.post_chat_method(
+            "chat.delete",
+            &serde_json::json!({
+                "channel": recipient,
+                "ts": Self::message_ts(message_id),
+            }),
+        )
+        .await
+    }
+
     async fn health_check(&self) -> bool {
         self.http_client()
             .get("https://slack.com/api/auth.test")
@@ -952,6 +1164,69 @@ mod tests {
         assert!(ch.is_user_allowed("U12345"));
     }
 
+    #[test]
+    fn parse_revision_event_reads_edits_and_deletions() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, None, vec!["U111".into()]);
+        let edit = serde_json::json!({
+            "type": "message",
+            "subtype": "message_changed",
+            "channel": "C1",
+            "message": {"user": "U111", "text": "<@U_BOT> fixed typo", "ts": "1.2"},
+            "previous_message": {"user": "U111", "text": "<@U_BOT> fixd typo", "ts": "1.2"}
+        });
+        let msg = ch.parse_revision_event(&edit, None, "U_BOT").unwrap();
+        assert_eq!(msg.event, ChannelEvent::Edited);
+        assert_eq!(msg.id, "slack_C1_1.2");
+        assert_eq!(msg.content, "fixed typo");
+
+        let delete = serde_json::json!({
+            "type": "message",
+            "subtype": "message_deleted",
+            "channel": "C1",
+            "deleted_ts": "1.2",
+            "previous_message": {"user": "U111", "text": "fixed typo", "ts": "1.2"}
+        });
+        let msg = ch.parse_revision_event(&delete, None, "U_BOT").unwrap();
+        assert_eq!(msg.event, ChannelEvent::Deleted);
+        assert_eq!(msg.id, "slack_C1_1.2");
+    }
+
+    #[test]
+    fn parse_revision_event_skips_unfurls_and_unauthorized_users() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, None, vec!["U111".into()]);
+        let unfurl = serde_json::json!({
+            "subtype": "message_changed",
+            "channel": "C1",
+            "message": {"user": "U111", "text": "see https://x.io", "ts": "1.2"},
+            "previous_message": {"user": "U111", "text": "see https://x.io", "ts": "1.2"}
+        });
+        assert!(ch.parse_revision_event(&unfurl, None, "U_BOT").is_none());
+
+        let stranger = serde_json::json!({
+            "subtype": "message_changed",
+            "channel": "C1",
+            "message": {"user": "U999", "text": "new", "ts": "1.2"},
+            "previous_message": {"user": "U999", "text": "old", "ts": "1.2"}
+        });
+        assert!(ch.parse_revision_event(&stranger, None, "U_BOT").is_none());
+
+        let other_channel = serde_json::json!({
+            "subtype": "message_changed",
+            "channel": "C1",
+            "message": {"user": "U111", "text": "new", "ts": "1.2"},
+            "previous_message": {"user": "U111", "text": "old", "ts": "1.2"}
+        });
+        assert!(ch
+            .parse_revision_event(&other_channel, Some("C2"), "U_BOT")
+            .is_none());
+    }
+
+    #[test]
+    fn message_ts_strips_inbound_prefix() {
+        assert_eq!(SlackChannel::message_ts("slack_C1_1.2"), "1.2");
+        assert_eq!(SlackChannel::message_ts("1.2"), "1.2");
+    }
+
     #[test]
     fn normalize_incoming_content_requires_mention_when_enabled() {
         assert!(SlackChannel::normalize_incoming_content("hello", true, "U_BOT").is_none());
@@ -1092,6 +1367,45 @@ mod tests {
         assert_eq!(thread_ts, None);
     }
 
+    #[test]
+    fn inbound_files_become_authenticated_remote_attachments() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, None, vec!["*".into()]);
+        let msg = serde_json::json!({
+            "subtype": "file_share",
+            "files": [{
+                "name": "report.pdf",
+                "mimetype": "application/pdf",
+                "size": 2048,
+                "url_private_download": "https://files.slack.com/files-pri/T1-F1/download/report.pdf"
+            }]
+        });
+
+        assert!(!SlackChannel::is_ignored_subtype(&msg));
+        let files = ch.inbound_files(&msg);
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files[0].kind,
+            crate::channels::traits::AttachmentKind::Document
+        );
+        assert_eq!(files[0].filename.as_deref(), Some("report.pdf"));
+        assert_eq!(files[0].size, Some(2048));
+        assert_eq!(
+            files[0].source,
+            crate::channels::traits::AttachmentSource::Remote {
+                url: "https://files.slack.com/files-pri/T1-F1/download/report.pdf".into(),
+                headers: vec![("Authorization".into(), "Bearer xoxb-fake".into())],
+            }
+        );
+        assert_eq!(
+            SlackChannel::file_only_content("", &files, false).as_deref(),
+            Some("")
+        );
+        assert!(SlackChannel::file_only_content("", &files, true).is_none());
+        assert!(SlackChannel::is_ignored_subtype(
+            &serde_json::json!({"subtype": "channel_join"})
+        ));
+    }
+
     #[test]
     fn ensure_poll_cursor_bootstraps_new_channel() {
         let mut cursors = HashMap::new();
@@ -1,4 +1,9 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::outbound::{PartiallySent, RateLimited};
+use super::render::{render_markdown, split_markdown_with_tail, Format};
+use super::traits::{
+    note_sent_message, Attachment, AttachmentKind, AttachmentLimits, AttachmentSource, Channel,
+    ChannelEvent, ChannelMessage, SendMessage,
+};
 use crate::config::{Config, StreamMode};
 use crate::security::pairing::PairingGuard;
 use anyhow::Context;
@@ -6,7 +11,6 @@ use async_trait::async_trait;
 use directories::UserDirs;
 use parking_lot::Mutex;
 use reqwest::multipart::{Form, Part};
-use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -52,56 +56,13 @@ const TELEGRAM_APPROVAL_CALLBACK_DENY_PREFIX: &str = "zcapr:no:";
 /// Tries to split at word boundaries when possible, and handles continuation.
 /// The effective per-chunk limit is reduced to leave room for continuation markers.
 fn split_message_for_telegram(message: &str) -> Vec<String> {
-    if message.chars().count() <= TELEGRAM_MAX_MESSAGE_LENGTH {
-        return vec![message.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = message;
-    let chunk_limit = TELEGRAM_MAX_MESSAGE_LENGTH - TELEGRAM_CONTINUATION_OVERHEAD;
-
-    while !remaining.is_empty() {
-        // If the remainder fits within the full limit, take it all (last chunk
-        // or single chunk — continuation overhead is at most 14 chars).
-        if remaining.chars().count() <= TELEGRAM_MAX_MESSAGE_LENGTH {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Find the byte offset for the Nth character boundary.
-        let hard_split = remaining
-            .char_indices()
-            .nth(chunk_limit)
-            .map_or(remaining.len(), |(idx, _)| idx);
-
-        let chunk_end = if hard_split == remaining.len() {
-            hard_split
-        } else {
-            // Try to find a good break point (newline, then space)
-            let search_area = &remaining[..hard_split];
-
-            // Prefer splitting at newline
-            if let Some(pos) = search_area.rfind('\n') {
-                // Don't split if the newline is too close to the start
-                if search_area[..pos].chars().count() >= chunk_limit / 2 {
-                    pos + 1
-                } else {
-                    // Try space as fallback
-                    search_area.rfind(' ').unwrap_or(hard_split) + 1
-                }
-            } else if let Some(pos) = search_area.rfind(' ') {
-                pos + 1
-            } else {
-                // Hard split at character boundary
-                hard_split
-            }
-        };
-
-        chunks.push(remaining[..chunk_end].to_string());
-        remaining = &remaining[chunk_end..];
-    }
-
-    chunks
+    // A remainder that fits the full limit is sent whole: the last chunk only
+    // carries the short "(continued)" prefix.
+    split_markdown_with_tail(
+        message,
+        TELEGRAM_MAX_MESSAGE_LENGTH - TELEGRAM_CONTINUATION_OVERHEAD,
+        TELEGRAM_MAX_MESSAGE_LENGTH,
+    )
 }
 
 fn pick_uniform_index(len: usize) -> usize {
@@ -165,6 +126,18 @@ impl TelegramAttachmentKind {
     }
 }
 
+impl From<AttachmentKind> for TelegramAttachmentKind {
+    fn from(kind: AttachmentKind) -> Self {
+        match kind {
+            AttachmentKind::Image => Self::Image,
+            AttachmentKind::Document => Self::Document,
+            AttachmentKind::Video => Self::Video,
+            AttachmentKind::Audio => Self::Audio,
+            AttachmentKind::Voice => Self::Voice,
+        }
+    }
+}
+
 /// Check whether a file path has a recognized image extension.
 fn is_image_extension(path: &Path) -> bool {
     path.extension()
@@ -443,6 +416,9 @@ fn parse_attachment_markers(message: &str) -> (String, Vec<TelegramAttachment>)
     (cleaned.trim().to_string(), attachments)
 }
 
+/// Telegram Bot API maximum upload size for bot-sent files (50 MB).
+const TELEGRAM_MAX_FILE_UPLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
 /// Telegram Bot API maximum file download size (20 MB).
 const TELEGRAM_MAX_FILE_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
 
@@ -695,6 +671,8 @@ impl TelegramChannel {
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
     }
 
@@ -1597,6 +1575,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: vec![Attachment::local(local_path)],
+            event: ChannelEvent::Message,
         })
     }
 
@@ -1698,14 +1678,17 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             }
         };
 
-        let text =
-            match super::transcription::transcribe_audio(audio_data, &file_name, config).await {
-                Ok(t) => t,
-                Err(e) => {
-                    tracing::warn!("Voice transcription failed: {e}");
-                    return None;
-                }
-            };
+        let text = match super::transcription::transcribe_audio_for_channel(
+            "telegram", audio_data, &file_name, config,
+        )
+        .await
+        {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Voice transcription failed: {e}");
+                return None;
+            }
+        };
 
         if text.trim().is_empty() {
             tracing::info!("Voice transcription returned empty text, skipping");
@@ -1745,6 +1728,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
     }
 
@@ -1824,6 +1809,22 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Some(format!("> @{reply_sender}:\n{quoted_lines}"))
     }
 
+    /// Text edits arrive as `edited_message` updates carrying the full new
+    /// text. The Bot API does not report deletions.
+    fn parse_edited_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        let edited = update.get("edited_message")?;
+        let mut msg = self.parse_update_message(&serde_json::json!({ "message": edited }))?;
+        msg.event = ChannelEvent::Edited;
+        Some(msg)
+    }
+
+    /// Accept both bare Telegram message ids and inbound `telegram_<chat>_<id>` ids.
+    fn parse_message_id(message_id: &str) -> anyhow::Result<i64> {
+        let raw = message_id.rsplit('_').next().unwrap_or(message_id);
+        raw.parse::<i64>()
+            .map_err(|e| anyhow::anyhow!("invalid Telegram message_id '{message_id}': {e}"))
+    }
+
     fn parse_update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
         let message = update.get("message")?;
 
@@ -1904,6 +1905,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
     }
 
@@ -1952,158 +1955,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
 
     /// Convert Markdown to Telegram HTML format.
     /// Telegram HTML supports: <b>, <i>, <u>, <s>, <code>, <pre>, <a href="...">
-    /// This mirrors OpenClaw's markdownToTelegramHtml approach.
     fn markdown_to_telegram_html(text: &str) -> String {
-        let lines: Vec<&str> = text.split('\n').collect();
-        let mut result_lines: Vec<String> = Vec::new();
-
-        for line in &lines {
-            let trimmed_line = line.trim_start();
-            if trimmed_line.starts_with("```") {
-                // Preserve fence lines so the second-pass block parser can consume them
-                // without interference from inline backtick handling.
-                result_lines.push(trimmed_line.to_string());
-                continue;
-            }
-
-            let mut line_out = String::new();
-
-            // Handle code blocks (``` ... ```) - handled at text level below
-            // Handle headers: ## Title → <b>Title</b>
-            let stripped = line.trim_start_matches('#');
-            let header_level = line.len() - stripped.len();
-            if header_level > 0 && line.starts_with('#') && stripped.starts_with(' ') {
-                let title = Self::escape_html(stripped.trim());
-                result_lines.push(format!("<b>{title}</b>"));
-                continue;
-            }
-
-            // Inline formatting
-            let mut i = 0;
-            let bytes = line.as_bytes();
-            let len = bytes.len();
-            while i < len {
-                // Bold: **text** or __text__
-                if i + 1 < len && bytes[i] == b'*' && bytes[i + 1] == b'*' {
-                    if let Some(end) = line[i + 2..].find("**") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        write!(line_out, "<b>{inner}</b>").unwrap();
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                if i + 1 < len && bytes[i] == b'_' && bytes[i + 1] == b'_' {
-                    if let Some(end) = line[i + 2..].find("__") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        write!(line_out, "<b>{inner}</b>").unwrap();
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                // Italic: *text* or _text_ (single)
-                if bytes[i] == b'*' && (i == 0 || bytes[i - 1] != b'*') {
-                    if let Some(end) = line[i + 1..].find('*') {
-                        if end > 0 {
-                            let inner = Self::escape_html(&line[i + 1..i + 1 + end]);
-                            write!(line_out, "<i>{inner}</i>").unwrap();
-                            i += 2 + end;
-                            continue;
-                        }
-                    }
-                }
-                // Inline code: `code`
-                if bytes[i] == b'`' && (i == 0 || bytes[i - 1] != b'`') {
-                    if let Some(end) = line[i + 1..].find('`') {
-                        let inner = Self::escape_html(&line[i + 1..i + 1 + end]);
-                        write!(line_out, "<code>{inner}</code>").unwrap();
-                        i += 2 + end;
-                        continue;
-                    }
-                }
-                // Markdown link: [text](url)
-                if bytes[i] == b'[' {
-                    if let Some(bracket_end) = line[i + 1..].find(']') {
-                        let text_part = &line[i + 1..i + 1 + bracket_end];
-                        let after_bracket = i + 1 + bracket_end + 1; // position after ']'
-                        if after_bracket < len && bytes[after_bracket] == b'(' {
-                            if let Some(paren_end) = line[after_bracket + 1..].find(')') {
-                                let url = &line[after_bracket + 1..after_bracket + 1 + paren_end];
-                                if url.starts_with("http://") || url.starts_with("https://") {
-                                    let text_html = Self::escape_html(text_part);
-                                    let url_html = Self::escape_html(url);
-                                    write!(line_out, "<a href=\"{url_html}\">{text_html}</a>")
-                                        .unwrap();
-                                    i = after_bracket + 1 + paren_end + 1;
-                                    continue;
-                                }
-                            }
-                        }
-                    }
-                }
-                // Strikethrough: ~~text~~
-                if i + 1 < len && bytes[i] == b'~' && bytes[i + 1] == b'~' {
-                    if let Some(end) = line[i + 2..].find("~~") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        write!(line_out, "<s>{inner}</s>").unwrap();
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                // Default: escape HTML entities
-                let ch = line[i..].chars().next().unwrap();
-                match ch {
-                    '<' => line_out.push_str("&lt;"),
-                    '>' => line_out.push_str("&gt;"),
-                    '&' => line_out.push_str("&amp;"),
-                    '"' => line_out.push_str("&quot;"),
-                    '\'' => line_out.push_str("&#39;"),
-                    _ => line_out.push(ch),
-                }
-                i += ch.len_utf8();
-            }
-            result_lines.push(line_out);
-        }
-
-        // Second pass: handle ``` code blocks across lines
-        let joined = result_lines.join("\n");
-        let mut final_out = String::with_capacity(joined.len());
-        let mut in_code_block = false;
-        let mut code_buf = String::new();
-
-        for line in joined.split('\n') {
-            let trimmed = line.trim();
-            if trimmed.starts_with("```") {
-                if in_code_block {
-                    in_code_block = false;
-                    let escaped = code_buf.trim_end_matches('\n');
-                    // Telegram HTML parse mode supports <pre> and <code>, but not class attributes.
-                    writeln!(final_out, "<pre><code>{escaped}</code></pre>").unwrap();
-                    code_buf.clear();
-                } else {
-                    in_code_block = true;
-                    code_buf.clear();
-                }
-            } else if in_code_block {
-                code_buf.push_str(line);
-                code_buf.push('\n');
-            } else {
-                final_out.push_str(line);
-                final_out.push('\n');
-            }
-        }
-        if in_code_block && !code_buf.is_empty() {
-            writeln!(final_out, "<pre><code>{}</code></pre>", code_buf.trim_end()).unwrap();
-        }
-
-        final_out.trim_end_matches('\n').to_string()
-    }
-
-    fn escape_html(s: &str) -> String {
-        s.replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('"', "&quot;")
-            .replace('\'', "&#39;")
+        render_markdown(text, Format::TelegramHtml)
     }
 
     async fn send_text_chunks(
@@ -2112,9 +1965,23 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         chat_id: &str,
         thread_id: Option<&str>,
     ) -> anyhow::Result<()> {
+        self.send_text_chunks_from(message, chat_id, thread_id, 0)
+            .await
+            .map(|_| ())
+    }
+
+    /// Send `message` in chunks, skipping the first `skip` that an earlier
+    /// attempt delivered. Returns how many chunks the message splits into.
+    async fn send_text_chunks_from(
+        &self,
+        message: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        skip: usize,
+    ) -> anyhow::Result<usize> {
         let chunks = split_message_for_telegram(message);
 
-        for (index, chunk) in chunks.iter().enumerate() {
+        for (index, chunk) in chunks.iter().enumerate().skip(skip) {
             let text = if chunks.len() > 1 {
                 if index == 0 {
                     format!("{chunk}\n\n(continues...)")
@@ -2127,76 +1994,110 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 chunk.to_string()
             };
 
-            let mut markdown_body = serde_json::json!({
-                "chat_id": chat_id,
-                "text": Self::markdown_to_telegram_html(&text),
-                "parse_mode": "HTML"
-            });
+            self.send_text_chunk(&text, chat_id, thread_id)
+                .await
+                .map_err(|e| PartiallySent::wrap(e, index))?;
 
-            // Add message_thread_id for forum topic support
-            if let Some(tid) = thread_id {
-                markdown_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
+            if index < chunks.len() - 1 {
+                tokio::time::sleep(Duration::from_millis(100)).await;
             }
+        }
 
-            let markdown_resp = self
-                .http_client()
-                .post(self.api_url("sendMessage"))
-                .json(&markdown_body)
-                .send()
-                .await?;
+        Ok(chunks.len())
+    }
 
-            if markdown_resp.status().is_success() {
-                if index < chunks.len() - 1 {
-                    tokio::time::sleep(Duration::from_millis(100)).await;
-                }
-                continue;
-            }
+    async fn send_text_chunk(
+        &self,
+        text: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut markdown_body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": Self::markdown_to_telegram_html(text),
+            "parse_mode": "HTML"
+        });
 
-            let markdown_status = markdown_resp.status();
-            let markdown_err = markdown_resp.text().await.unwrap_or_default();
-            tracing::warn!(
-                status = ?markdown_status,
-                "Telegram sendMessage with Markdown failed; retrying without parse_mode"
-            );
+        // Add message_thread_id for forum topic support
+        if let Some(tid) = thread_id {
+            markdown_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
+        }
 
-            let mut plain_body = serde_json::json!({
-                "chat_id": chat_id,
-                "text": text,
-            });
+        let markdown_resp = self
+            .http_client()
+            .post(self.api_url("sendMessage"))
+            .json(&markdown_body)
+            .send()
+            .await?;
 
-            // Add message_thread_id for forum topic support
-            if let Some(tid) = thread_id {
-                plain_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
-            }
-            let plain_resp = self
-                .http_client()
-                .post(self.api_url("sendMessage"))
-                .json(&plain_body)
-                .send()
-                .await?;
+        if markdown_resp.status().is_success() {
+            Self::note_sent_reply(markdown_resp).await;
+            return Ok(());
+        }
 
-            if !plain_resp.status().is_success() {
-                let plain_status = plain_resp.status();
-                let plain_err = plain_resp.text().await.unwrap_or_default();
-                let sanitized_markdown_err = Self::sanitize_telegram_error(&markdown_err);
-                let sanitized_plain_err = Self::sanitize_telegram_error(&plain_err);
-                anyhow::bail!(
-                    "Telegram sendMessage failed (markdown {}: {}; plain {}: {})",
-                    markdown_status,
-                    sanitized_markdown_err,
-                    plain_status,
-                    sanitized_plain_err
-                );
-            }
+        let markdown_status = markdown_resp.status();
+        let markdown_headers = markdown_resp.headers().clone();
+        let markdown_err = markdown_resp.text().await.unwrap_or_default();
+        if markdown_status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(
+                RateLimited::from_response("telegram", &markdown_headers, &markdown_err).into(),
+            );
+        }
+        tracing::warn!(
+            status = ?markdown_status,
+            "Telegram sendMessage with Markdown failed; retrying without parse_mode"
+        );
 
-            if index < chunks.len() - 1 {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-            }
+        let mut plain_body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": text,
+        });
+
+        // Add message_thread_id for forum topic support
+        if let Some(tid) = thread_id {
+            plain_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
         }
+        let plain_resp = self
+            .http_client()
+            .post(self.api_url("sendMessage"))
+            .json(&plain_body)
+            .send()
+            .await?;
 
+        if !plain_resp.status().is_success() {
+            let plain_status = plain_resp.status();
+            let plain_err = plain_resp.text().await.unwrap_or_default();
+            let sanitized_markdown_err = Self::sanitize_telegram_error(&markdown_err);
+            let sanitized_plain_err = Self::sanitize_telegram_error(&plain_err);
+            anyhow::bail!(
+                "Telegram sendMessage failed (markdown {}: {}; plain {}: {})",
+                markdown_status,
+                sanitized_markdown_err,
+                plain_status,
+                sanitized_plain_err
+            );
+        }
+
+        Self::note_sent_reply(plain_resp).await;
         Ok(())
     }
 
+    /// Report the `message_id` of an accepted `sendMessage` so the reply can
+    /// be edited or deleted later.
+    async fn note_sent_reply(resp: reqwest::Response) {
+        let message_id = resp
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| {
+                body.pointer("/result/message_id")
+                    .and_then(|id| id.as_i64())
+            });
+        if let Some(message_id) = message_id {
+            note_sent_message(message_id.to_string());
+        }
+    }
+
     async fn send_media_by_url(
         &self,
         method: &str,
@@ -2236,7 +2137,7 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Ok(())
     }
 
-    async fn send_attachment(
+    async fn send_marker_attachment(
         &self,
         chat_id: &str,
         thread_id: Option<&str>,
@@ -2900,7 +2801,7 @@ impl Channel for TelegramChannel {
 
             // Send attachments
             for attachment in &attachments {
-                self.send_attachment(&chat_id, thread_id.as_deref(), attachment)
+                self.send_marker_attachment(&chat_id, thread_id.as_deref(), attachment)
                     .await?;
             }
 
@@ -3031,6 +2932,79 @@ impl Channel for TelegramChannel {
         }
     }
 
+    fn supports_message_edits(&self) -> bool {
+        true
+    }
+
+    async fn edit_message(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let (chat_id, _) = Self::parse_reply_target(recipient);
+        let message_id = Self::parse_message_id(message_id)?;
+        let text = strip_tool_call_tags(text);
+        let display_text: String = text.chars().take(TELEGRAM_MAX_MESSAGE_LENGTH).collect();
+
+        let html_resp = self
+            .client
+            .post(self.api_url("editMessageText"))
+            .json(&serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "text": Self::markdown_to_telegram_html(&display_text),
+                "parse_mode": "HTML",
+            }))
+            .send()
+            .await?;
+        if html_resp.status().is_success() {
+            return Ok(());
+        }
+
+        let plain_resp = self
+            .client
+            .post(self.api_url("editMessageText"))
+            .json(&serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "text": display_text,
+            }))
+            .send()
+            .await?;
+        if !plain_resp.status().is_success() {
+            let status = plain_resp.status();
+            let body = plain_resp.text().await.unwrap_or_default();
+            let sanitized = Self::sanitize_telegram_error(&body);
+            anyhow::bail!("Telegram editMessageText failed ({status}): {sanitized}");
+        }
+
+        Ok(())
+    }
+
+    async fn delete_message(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        let (chat_id, _) = Self::parse_reply_target(recipient);
+        let message_id = Self::parse_message_id(message_id)?;
+
+        let response = self
+            .client
+            .post(self.api_url("deleteMessage"))
+            .json(&serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+            }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let sanitized = Self::sanitize_telegram_error(&body);
+            anyhow::bail!("Telegram deleteMessage failed ({status}): {sanitized}");
+        }
+
+        Ok(())
+    }
+
     async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
         let (chat_id, _) = Self::parse_reply_target(recipient);
         self.last_draft_edit.lock().remove(&chat_id);
@@ -3063,6 +3037,31 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    fn attachment_limits(&self) -> Option<AttachmentLimits> {
+        Some(AttachmentLimits::all_kinds(TELEGRAM_MAX_FILE_UPLOAD_BYTES))
+    }
+
+    async fn send_attachment(
+        &self,
+        message: &SendMessage,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        let (chat_id, thread_id) = match message.recipient.split_once(':') {
+            Some((chat, thread)) => (chat, Some(thread)),
+            None => (message.recipient.as_str(), None),
+        };
+        let target = match &attachment.source {
+            AttachmentSource::Local(path) => path.display().to_string(),
+            AttachmentSource::Remote { url, .. } => url.clone(),
+        };
+        let attachment = TelegramAttachment {
+            kind: attachment.kind.into(),
+            target,
+        };
+        self.send_marker_attachment(chat_id, thread_id, &attachment)
+            .await
+    }
+
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         // Strip tool_call tags before processing to prevent Markdown parsing failures
         let content = strip_tool_call_tags(&message.content);
@@ -3075,26 +3074,43 @@ impl Channel for TelegramChannel {
 
         let (text_without_markers, attachments) = parse_attachment_markers(&content);
 
+        // Text chunks and attachments are numbered in send order so a retry
+        // after a rate limit resumes at `resume_from` instead of resending.
         if !attachments.is_empty() {
+            let mut sent = 0;
             if !text_without_markers.is_empty() {
-                self.send_text_chunks(&text_without_markers, chat_id, thread_id)
+                sent = self
+                    .send_text_chunks_from(
+                        &text_without_markers,
+                        chat_id,
+                        thread_id,
+                        message.resume_from,
+                    )
                     .await?;
             }
 
-            for attachment in &attachments {
-                self.send_attachment(chat_id, thread_id, attachment).await?;
+            for (offset, attachment) in attachments.iter().enumerate() {
+                let index = sent + offset;
+                if index < message.resume_from {
+                    continue;
+                }
+                self.send_marker_attachment(chat_id, thread_id, attachment)
+                    .await
+                    .map_err(|e| PartiallySent::wrap(e, index))?;
             }
 
             return Ok(());
         }
 
         if let Some(attachment) = parse_path_only_attachment(&content) {
-            self.send_attachment(chat_id, thread_id, &attachment)
+            self.send_marker_attachment(chat_id, thread_id, &attachment)
                 .await?;
             return Ok(());
         }
 
-        self.send_text_chunks(&content, chat_id, thread_id).await
+        self.send_text_chunks_from(&content, chat_id, thread_id, message.resume_from)
+            .await
+            .map(|_| ())
     }
 
     async fn send_approval_prompt(
@@ -3179,7 +3195,7 @@ impl Channel for TelegramChannel {
             let probe = serde_json::json!({
                 "offset": offset,
                 "timeout": 0,
-                "allowed_updates": ["message", "callback_query"]
+                "allowed_updates": ["message", "edited_message", "callback_query"]
             });
             match self.http_client().post(&url).json(&probe).send().await {
                 Err(e) => {
@@ -3254,7 +3270,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message", "callback_query"]
+                "allowed_updates": ["message", "edited_message", "callback_query"]
             });
 
             let resp = match self.http_client().post(&url).json(&body).send().await {
@@ -3321,6 +3337,13 @@ Ensure only one `zeroclaw` process is using this bot token."
                         offset = uid + 1;
                     }
 
+                    if let Some(edit) = self.parse_edited_message(update) {
+                        if tx.send(edit).await.is_err() {
+                            return Ok(());
+                        }
+                        continue;
+                    }
+
                     let msg = if let Some(m) = self.parse_update_message(update) {
                         m
                     } else if let Some(m) = self.try_parse_approval_callback_query(update) {
@@ -3941,6 +3964,42 @@ mod tests {
         assert_eq!(msg.id, "telegram_-100200300_33");
     }
 
+    #[test]
+    fn parse_edited_message_marks_event_as_edit() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], false, true);
+        let update = serde_json::json!({
+            "update_id": 2,
+            "edited_message": {
+                "message_id": 33,
+                "text": "hello again",
+                "from": {
+                    "id": 555,
+                    "username": "alice"
+                },
+                "chat": {
+                    "id": -100_200_300
+                }
+            }
+        });
+
+        let msg = ch.parse_edited_message(&update).expect("edit should parse");
+
+        assert_eq!(msg.event, ChannelEvent::Edited);
+        assert_eq!(msg.id, "telegram_-100200300_33");
+        assert_eq!(msg.content, "hello again");
+        assert!(ch.parse_update_message(&update).is_none());
+    }
+
+    #[test]
+    fn parse_message_id_accepts_bare_and_prefixed_ids() {
+        assert_eq!(TelegramChannel::parse_message_id("42").unwrap(), 42);
+        assert_eq!(
+            TelegramChannel::parse_message_id("telegram_-100200300_33").unwrap(),
+            33
+        );
+        assert!(TelegramChannel::parse_message_id("draft").is_err());
+    }
+
     #[test]
     fn parse_update_message_allows_numeric_id_without_username() {
         let ch = TelegramChannel::new("token".into(), vec!["555".into()], false, true);
@@ -5899,4 +5958,92 @@ mod tests {
         assert_eq!(result, "[Document: report.pdf] /tmp/workspace/report.pdf");
         assert!(!result.starts_with("[IMAGE:"));
     }
+
+    #[tokio::test]
+    async fn chunked_send_reports_progress_and_resumes_after_rate_limit() {
+        use crate::channels::outbound::PartiallySent;
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .and(body_string_contains("(continued)"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+                "ok": false,
+                "error_code": 429,
+                "parameters": {"retry_after": 1}
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true,
+                "result": {"message_id": 1}
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let channel = TelegramChannel::new("token".into(), vec!["*".into()], false, false)
+            .with_api_base(server.uri());
+        let long = "word ".repeat(TELEGRAM_MAX_MESSAGE_LENGTH / 3);
+        let mut message = SendMessage::new(long, "123");
+
+        let err = channel.send(&message).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PartiallySent>(),
+            Some(&PartiallySent { chunks: 1 })
+        );
+        assert!(err.chain().any(|cause| cause.is::<RateLimited>()));
+
+        message.resume_from = 1;
+        channel.send(&message).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sent_replies_report_ids_that_edit_and_delete_accept() {
+        use crate::channels::traits::record_sent_messages;
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let ok = ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ok": true,
+            "result": {"message_id": 77}
+        }));
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/editMessageText"))
+            .and(body_string_contains("\"message_id\":77"))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/deleteMessage"))
+            .and(body_string_contains("\"message_id\":77"))
+            .respond_with(ok)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = TelegramChannel::new("token".into(), vec!["*".into()], false, false)
+            .with_api_base(server.uri());
+        let (sent, ids) = record_sent_messages(channel.send(&SendMessage::new("hi", "123"))).await;
+        sent.unwrap();
+        assert_eq!(ids, vec!["77"]);
+
+        assert!(channel.supports_message_edits());
+        channel.edit_message("123", &ids[0], "fixed").await.unwrap();
+        channel.delete_message("123", &ids[0]).await.unwrap();
+    }
 }
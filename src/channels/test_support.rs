@@ -0,0 +1,14 @@
+//! Fixtures shared by channel unit tests.
+
+use std::path::Path;
+
+/// Write an executable shell script `name` into `dir` standing in for an
+/// external tool (ffmpeg, whisper, piper) and return its path.
+#[cfg(unix)]
+pub(crate) fn fake_binary(dir: &Path, name: &str, script: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.display().to_string()
+}
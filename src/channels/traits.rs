@@ -1,4 +1,227 @@
 use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+/// Kind of file carried by an [`Attachment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttachmentKind {
+    Image,
+    Audio,
+    Voice,
+    Video,
+    Document,
+}
+
+impl AttachmentKind {
+    /// Parse an inline marker name such as `IMAGE` in `[IMAGE:/tmp/a.png]`.
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        match marker.trim().to_ascii_uppercase().as_str() {
+            "IMAGE" | "PHOTO" => Some(Self::Image),
+            "AUDIO" => Some(Self::Audio),
+            "VOICE" => Some(Self::Voice),
+            "VIDEO" => Some(Self::Video),
+            "DOCUMENT" | "FILE" => Some(Self::Document),
+            _ => None,
+        }
+    }
+
+    /// Marker name used when rendering an attachment inline.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::Image => "IMAGE",
+            Self::Audio => "AUDIO",
+            Self::Voice => "VOICE",
+            Self::Video => "VIDEO",
+            Self::Document => "DOCUMENT",
+        }
+    }
+
+    /// Classify by MIME type, defaulting to [`AttachmentKind::Document`].
+    pub fn from_mime(mime: &str) -> Self {
+        let mime = mime.trim().to_ascii_lowercase();
+        if mime.starts_with("image/") {
+            Self::Image
+        } else if mime == "audio/ogg" || mime.contains("opus") {
+            Self::Voice
+        } else if mime.starts_with("audio/") {
+            Self::Audio
+        } else if mime.starts_with("video/") {
+            Self::Video
+        } else {
+            Self::Document
+        }
+    }
+
+    /// Classify by file extension, defaulting to [`AttachmentKind::Document`].
+    pub fn from_path(path: &str) -> Self {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Self::Image,
+            "ogg" | "oga" | "opus" => Self::Voice,
+            "mp3" | "m4a" | "wav" | "flac" | "aac" => Self::Audio,
+            "mp4" | "mov" | "mkv" | "webm" | "avi" => Self::Video,
+            _ => Self::Document,
+        }
+    }
+}
+
+/// Where the bytes of an [`Attachment`] live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentSource {
+    /// File already on local disk.
+    Local(PathBuf),
+    /// Fetched lazily through the shared download cache
+    /// (see [`crate::channels::attachments::AttachmentCache::fetch`]).
+    Remote {
+        url: String,
+        /// Extra request headers, e.g. `Authorization` for Slack file URLs.
+        headers: Vec<(String, String)>,
+    },
+}
+
+/// A typed file attached to an inbound or outbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    pub mime_type: Option<String>,
+    pub filename: Option<String>,
+    /// Size in bytes, when known up front.
+    pub size: Option<u64>,
+    pub source: AttachmentSource,
+}
+
+impl Attachment {
+    /// Attachment backed by a local file; kind is inferred from the extension.
+    pub fn local(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string);
+        let size = std::fs::metadata(&path).ok().map(|meta| meta.len());
+        Self {
+            kind: AttachmentKind::from_path(&path.to_string_lossy()),
+            mime_type: None,
+            filename,
+            size,
+            source: AttachmentSource::Local(path),
+        }
+    }
+
+    /// Attachment fetched on demand from `url`.
+    pub fn remote(url: impl Into<String>, filename: Option<String>) -> Self {
+        let url = url.into();
+        let kind_hint = filename
+            .as_deref()
+            .unwrap_or_else(|| url.split(['?', '#']).next().unwrap_or_default());
+        Self {
+            kind: AttachmentKind::from_path(kind_hint),
+            mime_type: None,
+            filename,
+            size: None,
+            source: AttachmentSource::Remote {
+                url,
+                headers: Vec::new(),
+            },
+        }
+    }
+
+    /// Override the inferred kind.
+    pub fn with_kind(mut self, kind: AttachmentKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the MIME type and reclassify the kind from it.
+    pub fn with_mime(mut self, mime: impl Into<String>) -> Self {
+        let mime = mime.into();
+        self.kind = AttachmentKind::from_mime(&mime);
+        self.mime_type = Some(mime);
+        self
+    }
+
+    /// Set the size in bytes.
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Add a request header used when fetching a remote attachment.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        if let AttachmentSource::Remote { headers, .. } = &mut self.source {
+            headers.push((name.into(), value.into()));
+        }
+        self
+    }
+
+    /// Local path, if the attachment is already on disk.
+    pub fn local_path(&self) -> Option<&Path> {
+        match &self.source {
+            AttachmentSource::Local(path) => Some(path),
+            AttachmentSource::Remote { .. } => None,
+        }
+    }
+
+    /// Best display name: filename, then path or URL.
+    pub fn display_name(&self) -> String {
+        if let Some(name) = &self.filename {
+            return name.clone();
+        }
+        match &self.source {
+            AttachmentSource::Local(path) => path.display().to_string(),
+            AttachmentSource::Remote { url, .. } => url.clone(),
+        }
+    }
+
+    /// Inline marker form, e.g. `[IMAGE:/path/to/file.png]`.
+    pub fn to_marker(&self) -> String {
+        let target = match &self.source {
+            AttachmentSource::Local(path) => path.display().to_string(),
+            AttachmentSource::Remote { url, .. } => url.clone(),
+        };
+        format!("[{}:{target}]", self.kind.marker())
+    }
+}
+
+/// Per-platform limits for outbound attachments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentLimits {
+    /// Maximum upload size in bytes.
+    pub max_bytes: u64,
+    /// Kinds the platform accepts.
+    pub kinds: Vec<AttachmentKind>,
+}
+
+impl AttachmentLimits {
+    /// Limits accepting every kind up to `max_bytes`.
+    pub fn all_kinds(max_bytes: u64) -> Self {
+        Self {
+            max_bytes,
+            kinds: vec![
+                AttachmentKind::Image,
+                AttachmentKind::Audio,
+                AttachmentKind::Voice,
+                AttachmentKind::Video,
+                AttachmentKind::Document,
+            ],
+        }
+    }
+}
+
+/// What an inbound [`ChannelMessage`] reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelEvent {
+    /// A new message.
+    #[default]
+    Message,
+    /// The sender edited the earlier message `id`; `content` is the new text.
+    Edited,
+    /// The sender deleted the earlier message `id`.
+    Deleted,
+}
 
 /// A message received from or sent to a channel
 #[derive(Debug, Clone)]
@@ -12,6 +235,10 @@ pub struct ChannelMessage {
     /// Platform thread identifier (e.g. Slack `ts`, Discord thread ID).
     /// When set, replies should be posted as threaded responses.
     pub thread_ts: Option<String>,
+    /// Files received with the message.
+    pub attachments: Vec<Attachment>,
+    /// New message, or an edit/deletion of an earlier one.
+    pub event: ChannelEvent,
 }
 
 /// Message to send through a channel
@@ -30,6 +257,11 @@ pub struct SendMessage {
     pub reply_broadcast: Option<bool>,
     /// Block Kit JSON payload (Slack blocks / Discord embeds).
     pub blocks: Option<serde_json::Value>,
+    /// Files to send after the text (see [`Channel::send_attachment`]).
+    pub attachments: Vec<Attachment>,
+    /// Leading chunks an earlier attempt already delivered; channels that
+    /// split long messages skip them (see [`crate::channels::outbound::PartiallySent`]).
+    pub resume_from: usize,
 }
 
 impl SendMessage {
@@ -44,6 +276,8 @@ impl SendMessage {
             icon_emoji: None,
             reply_broadcast: None,
             blocks: None,
+            attachments: Vec::new(),
+            resume_from: 0,
         }
     }
 
@@ -62,6 +296,8 @@ impl SendMessage {
             icon_emoji: None,
             reply_broadcast: None,
             blocks: None,
+            attachments: Vec::new(),
+            resume_from: 0,
         }
     }
 
@@ -84,6 +320,12 @@ impl SendMessage {
         self
     }
 
+    /// Attach files to send alongside the text.
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
     /// Broadcast a threaded reply to the parent channel.
     pub fn with_reply_broadcast(mut self, broadcast: bool) -> Self {
         self.reply_broadcast = Some(broadcast);
@@ -91,6 +333,29 @@ impl SendMessage {
     }
 }
 
+tokio::task_local! {
+    /// Platform ids collected by [`record_sent_messages`].
+    static SENT_MESSAGE_IDS: std::cell::RefCell<Vec<String>>;
+}
+
+/// Run `fut` and also return the platform ids of the messages channels sent
+/// while it ran, in send order. These are the ids
+/// [`Channel::edit_message`] and [`Channel::delete_message`] take.
+pub async fn record_sent_messages<F: std::future::Future>(fut: F) -> (F::Output, Vec<String>) {
+    SENT_MESSAGE_IDS
+        .scope(std::cell::RefCell::new(Vec::new()), async move {
+            let output = fut.await;
+            (output, SENT_MESSAGE_IDS.with(std::cell::RefCell::take))
+        })
+        .await
+}
+
+/// Report the platform id of a message a channel just sent from
+/// [`Channel::send`]. No-op outside [`record_sent_messages`].
+pub fn note_sent_message(id: impl Into<String>) {
+    let _ = SENT_MESSAGE_IDS.try_with(|ids| ids.borrow_mut().push(id.into()));
+}
+
 /// Core channel trait — implement for any messaging platform
 #[async_trait]
 pub trait Channel: Send + Sync {
@@ -158,6 +423,35 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Whether [`Channel::edit_message`] and [`Channel::delete_message`] are supported.
+    fn supports_message_edits(&self) -> bool {
+        false
+    }
+
+    /// Replace the text of a message this bot sent earlier.
+    ///
+    /// `message_id` is the platform-scoped ID returned by [`Channel::send_draft`]
+    /// or reported from [`Channel::send`] via [`note_sent_message`].
+    async fn edit_message(
+        &self,
+        _recipient: &str,
+        message_id: &str,
+        _text: &str,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "channel `{}` does not support editing messages ({message_id})",
+            self.name()
+        )
+    }
+
+    /// Delete a message this bot sent earlier.
+    async fn delete_message(&self, _recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "channel `{}` does not support deleting messages ({message_id})",
+            self.name()
+        )
+    }
+
     /// Send an interactive approval prompt, if supported by the channel.
     ///
     /// Default behavior sends a plain-text fallback with slash-command actions.
@@ -197,6 +491,27 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Outbound attachment limits, or `None` when the channel cannot send files.
+    fn attachment_limits(&self) -> Option<AttachmentLimits> {
+        None
+    }
+
+    /// Send a single file to `message.recipient` (threading from `message`).
+    ///
+    /// Callers should go through [`crate::channels::attachments::deliver`],
+    /// which checks [`Channel::attachment_limits`] first.
+    async fn send_attachment(
+        &self,
+        _message: &SendMessage,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "channel `{}` does not support sending attachments ({})",
+            self.name(),
+            attachment.display_name()
+        )
+    }
+
     /// Remove a reaction (emoji) from a message previously added by this bot.
     async fn remove_reaction(
         &self,
@@ -236,6 +551,8 @@ mod tests {
                 channel: "dummy".into(),
                 timestamp: 123,
                 thread_ts: None,
+                attachments: Vec::new(),
+                event: ChannelEvent::Message,
             })
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -252,6 +569,8 @@ mod tests {
             channel: "dummy".into(),
             timestamp: 999,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         let cloned = message.clone();
@@ -347,7 +666,6 @@ mod tests {
         assert_eq!(msg.thread_ts.as_deref(), Some("ts123"));
     }
 
-
     #[tokio::test]
     async fn approval_prompt_truncates_safely_for_multibyte_utf8() {
         let channel = DummyChannel;
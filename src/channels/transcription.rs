@@ -1,11 +1,21 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
 use anyhow::{bail, Context, Result};
 use reqwest::multipart::{Form, Part};
 
-use crate::config::TranscriptionConfig;
+use crate::config::{TranscriptionBackend, TranscriptionConfig};
 
 /// Maximum upload size accepted by the Groq Whisper API (25 MB).
 const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
 
+/// Sanity cap for recordings transcribed locally (200 MB).
+const MAX_LOCAL_AUDIO_BYTES: usize = 200 * 1024 * 1024;
+
+/// Default `whisper.cpp` model, relative to the home directory.
+const DEFAULT_LOCAL_MODEL: &str = ".zeroclaw/models/whisper/ggml-base.bin";
+
 /// Map file extension to MIME type for Whisper-compatible transcription APIs.
 fn mime_for_audio(extension: &str) -> Option<&'static str> {
     match extension.to_ascii_lowercase().as_str() {
@@ -31,20 +41,52 @@ fn normalize_audio_filename(file_name: &str) -> String {
     }
 }
 
-/// Transcribe audio bytes via a Whisper-compatible transcription API.
+/// Transcribe audio bytes with the configured default backend.
 ///
 /// Returns the transcribed text on success.
 ///
-/// Credential resolution order:
-/// 1. `config.transcription.api_key`
-/// 2. `GROQ_API_KEY` environment variable (backward compatibility)
-///
 /// The caller is responsible for enforcing duration limits *before* downloading
 /// the file; this function enforces the byte-size cap.
 pub async fn transcribe_audio(
     audio_data: Vec<u8>,
     file_name: &str,
     config: &TranscriptionConfig,
+) -> Result<String> {
+    transcribe_with(config.backend, audio_data, file_name, config).await
+}
+
+/// Transcribe a voice note received on `channel`, honouring the per-channel
+/// backend overrides in `[transcription.channels]`.
+pub async fn transcribe_audio_for_channel(
+    channel: &str,
+    audio_data: Vec<u8>,
+    file_name: &str,
+    config: &TranscriptionConfig,
+) -> Result<String> {
+    transcribe_with(config.backend_for(channel), audio_data, file_name, config).await
+}
+
+async fn transcribe_with(
+    backend: TranscriptionBackend,
+    audio_data: Vec<u8>,
+    file_name: &str,
+    config: &TranscriptionConfig,
+) -> Result<String> {
+    match backend {
+        TranscriptionBackend::Api => transcribe_via_api(audio_data, file_name, config).await,
+        TranscriptionBackend::Local => transcribe_locally(audio_data, file_name, config).await,
+    }
+}
+
+/// Transcribe audio bytes via a Whisper-compatible transcription API.
+///
+/// Credential resolution order:
+/// 1. `config.transcription.api_key`
+/// 2. `GROQ_API_KEY` environment variable (backward compatibility)
+async fn transcribe_via_api(
+    audio_data: Vec<u8>,
+    file_name: &str,
+    config: &TranscriptionConfig,
 ) -> Result<String> {
     if audio_data.len() > MAX_AUDIO_BYTES {
         bail!(
@@ -122,9 +164,218 @@ pub async fn transcribe_audio(
     Ok(text)
 }
 
+/// Resolve the local `whisper.cpp` model path.
+fn local_model_path(config: &TranscriptionConfig) -> PathBuf {
+    match config.local.model_path.as_deref() {
+        Some(path) => PathBuf::from(shellexpand::tilde(path).as_ref()),
+        None => directories::UserDirs::new().map_or_else(
+            || PathBuf::from("/usr/local/share/whisper/ggml-base.bin"),
+            |dirs| dirs.home_dir().join(DEFAULT_LOCAL_MODEL),
+        ),
+    }
+}
+
+/// Run `program` to completion, failing with its stderr on a non-zero exit.
+async fn run_tool(program: &str, command: &mut tokio::process::Command) -> Result<()> {
+    let output = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run `{program}`"))?;
+    if !output.status.success() {
+        bail!(
+            "`{program}` exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Convert `input` to 16 kHz mono WAV chunks of at most `chunk_secs`
+/// seconds, as `whisper.cpp` expects. Returns the chunk paths in order.
+async fn normalize_to_wav_chunks(
+    ffmpeg: &str,
+    input: &Path,
+    dir: &Path,
+    chunk_secs: u64,
+) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join("chunk_%04d.wav");
+    run_tool(
+        ffmpeg,
+        tokio::process::Command::new(ffmpeg)
+            .args(["-hide_banner", "-loglevel", "error", "-i"])
+            .arg(input)
+            .args([
+                "-vn",
+                "-ac",
+                "1",
+                "-ar",
+                "16000",
+                "-c:a",
+                "pcm_s16le",
+                "-f",
+                "segment",
+                "-segment_time",
+                &chunk_secs.max(1).to_string(),
+                "-reset_timestamps",
+                "1",
+            ])
+            .arg(&pattern),
+    )
+    .await?;
+
+    let mut chunks = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_chunk = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("chunk_") && name.ends_with(".wav"));
+        if is_chunk {
+            chunks.push(path);
+        }
+    }
+    chunks.sort();
+    if chunks.is_empty() {
+        bail!("ffmpeg produced no audio");
+    }
+    Ok(chunks)
+}
+
+/// Text and detected language of one `whisper.cpp` JSON result.
+fn parse_whisper_json(json: &serde_json::Value) -> (String, Option<String>) {
+    let text = json["transcription"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .filter_map(|segment| segment["text"].as_str())
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+    let language = json["result"]["language"]
+        .as_str()
+        .map(str::to_string)
+        .filter(|language| !language.is_empty());
+    (text, language)
+}
+
+/// Transcribe one 16 kHz WAV chunk with `whisper.cpp`.
+async fn whisper_chunk(
+    config: &TranscriptionConfig,
+    model: &Path,
+    chunk: &Path,
+    language: &str,
+) -> Result<(String, Option<String>)> {
+    let binary = &config.local.whisper_binary;
+    let output_prefix = chunk.with_extension("");
+    let mut command = tokio::process::Command::new(binary);
+    command
+        .arg("-m")
+        .arg(model)
+        .arg("-f")
+        .arg(chunk)
+        .args(["-l", language, "-oj", "-np", "-nt", "-of"])
+        .arg(&output_prefix);
+    if let Some(threads) = config.local.threads {
+        command.args(["-t", &threads.to_string()]);
+    }
+    run_tool(binary, &mut command).await?;
+
+    let json_path = output_prefix.with_extension("json");
+    let raw = tokio::fs::read(&json_path)
+        .await
+        .with_context(|| format!("whisper.cpp wrote no output to {}", json_path.display()))?;
+    let json: serde_json::Value =
+        serde_json::from_slice(&raw).context("Failed to parse whisper.cpp output")?;
+    Ok(parse_whisper_json(&json))
+}
+
+/// Transcribe audio bytes offline with a local `whisper.cpp` binary.
+///
+/// The recording is converted to 16 kHz mono WAV with ffmpeg and split into
+/// `chunk_secs` chunks. Without a configured `language`, the language
+/// detected on the first chunk is used for the rest.
+async fn transcribe_locally(
+    audio_data: Vec<u8>,
+    file_name: &str,
+    config: &TranscriptionConfig,
+) -> Result<String> {
+    if audio_data.len() > MAX_LOCAL_AUDIO_BYTES {
+        bail!(
+            "Audio file too large ({} bytes, max {MAX_LOCAL_AUDIO_BYTES})",
+            audio_data.len()
+        );
+    }
+    let model = local_model_path(config);
+    if !model.exists() {
+        bail!(
+            "whisper.cpp model not found: {} (set [transcription.local].model_path)",
+            model.display()
+        );
+    }
+
+    let work = tempfile::tempdir().context("Failed to create transcription work directory")?;
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("audio");
+    let input = work.path().join(format!("input.{extension}"));
+    tokio::fs::write(&input, &audio_data).await?;
+
+    let work_dir = work.path().to_path_buf();
+    let transcribe = async {
+        let chunks = normalize_to_wav_chunks(
+            &config.local.ffmpeg_binary,
+            &input,
+            &work_dir,
+            config.local.chunk_secs,
+        )
+        .await?;
+
+        let mut language = config.language.clone();
+        let mut parts = Vec::with_capacity(chunks.len());
+        for chunk in &chunks {
+            let (text, detected) =
+                whisper_chunk(config, &model, chunk, language.as_deref().unwrap_or("auto")).await?;
+            if language.is_none() {
+                if let Some(detected) = detected {
+                    tracing::debug!("whisper.cpp detected language: {detected}");
+                    language = Some(detected);
+                }
+            }
+            if !text.is_empty() {
+                parts.push(text);
+            }
+        }
+        Ok::<_, anyhow::Error>(parts.join(" "))
+    };
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(config.local.timeout_secs.max(1)),
+        transcribe,
+    )
+    .await
+    .context("Local transcription timed out");
+    // Removing the work directory is blocking I/O; keep it off the runtime.
+    let _ = tokio::task::spawn_blocking(move || drop(work)).await;
+    result?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(unix)]
+    use crate::channels::test_support::fake_binary;
 
     #[tokio::test]
     async fn rejects_oversized_audio() {
@@ -232,6 +483,85 @@ mod tests {
         assert_eq!(normalize_audio_filename("voice"), "voice");
     }
 
+    #[test]
+    fn parse_whisper_json_joins_segments_and_reads_language() {
+        let json = serde_json::json!({
+            "result": {"language": "ru"},
+            "transcription": [
+                {"text": " Привет,"},
+                {"text": "  "},
+                {"text": " мир."}
+            ]
+        });
+        let (text, language) = parse_whisper_json(&json);
+        assert_eq!(text, "Привет, мир.");
+        assert_eq!(language.as_deref(), Some("ru"));
+    }
+
+    #[test]
+    fn backend_for_honours_channel_overrides() {
+        let mut config = TranscriptionConfig::default();
+        config
+            .channels
+            .insert("whatsapp".into(), TranscriptionBackend::Local);
+        assert_eq!(config.backend_for("whatsapp"), TranscriptionBackend::Local);
+        assert_eq!(config.backend_for("telegram"), TranscriptionBackend::Api);
+    }
+
+    #[tokio::test]
+    async fn local_backend_requires_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = TranscriptionConfig::default();
+        config.backend = TranscriptionBackend::Local;
+        config.local.model_path = Some(dir.path().join("missing.bin").display().to_string());
+
+        let err = transcribe_audio(vec![0u8; 100], "voice.ogg", &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("model not found"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn local_backend_transcribes_chunks_with_detected_language() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = dir.path().join("ggml-tiny.bin");
+        std::fs::write(&model, b"model").unwrap();
+
+        // Fake ffmpeg: emit two chunks next to the output pattern (last arg).
+        let ffmpeg = fake_binary(
+            dir.path(),
+            "ffmpeg",
+            r#"for last; do :; done
+d=$(dirname "$last")
+printf one > "$d/chunk_0000.wav"
+printf two > "$d/chunk_0001.wav""#,
+        );
+        // Fake whisper.cpp: echo chunk content and requested language as JSON.
+        let whisper = fake_binary(
+            dir.path(),
+            "whisper-cli",
+            r#"while [ $# -gt 0 ]; do
+  case "$1" in -f) f="$2"; shift;; -of) of="$2"; shift;; -l) l="$2"; shift;; esac
+  shift
+done
+printf '{"result":{"language":"de"},"transcription":[{"text":" %s-%s "}]}' "$(cat "$f")" "$l" > "$of.json""#,
+        );
+
+        let mut config = TranscriptionConfig::default();
+        config
+            .channels
+            .insert("telegram".into(), TranscriptionBackend::Local);
+        config.local.model_path = Some(model.display().to_string());
+        config.local.ffmpeg_binary = ffmpeg;
+        config.local.whisper_binary = whisper;
+
+        let text = transcribe_audio_for_channel("telegram", vec![1u8; 100], "voice.oga", &config)
+            .await
+            .unwrap();
+        assert_eq!(text, "one-auto two-de");
+    }
+
     #[tokio::test]
     async fn rejects_unsupported_audio_format() {
         let data = vec![0u8; 100];
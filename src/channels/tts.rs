@@ -0,0 +1,855 @@
+//! Text-to-speech voice replies.
+//!
+//! [`VoiceReplies`] decides per message whether a reply should be spoken
+//! (see [`TtsReplyMode`]), synthesizes it through a [`TtsProvider`] and sends
+//! the audio as an Opus/OGG voice note. Every provider returns Opus/OGG:
+//! OpenAI-compatible endpoints produce it directly, Piper WAV and ElevenLabs
+//! MP3 output is encoded with ffmpeg. Any failure leaves the caller to send
+//! the reply as text.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+use super::attachments;
+use super::render::{Block, Document, Format};
+use super::traits::{Attachment, AttachmentKind, Channel, ChannelMessage, SendMessage};
+use crate::config::{TtsConfig, TtsProviderKind, TtsReplyMode};
+
+const DEFAULT_OPENAI_URL: &str = "https://api.openai.com/v1/audio/speech";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini-tts";
+const DEFAULT_OPENAI_VOICE: &str = "alloy";
+const DEFAULT_ELEVENLABS_URL: &str = "https://api.elevenlabs.io/v1/text-to-speech";
+const DEFAULT_ELEVENLABS_MODEL: &str = "eleven_multilingual_v2";
+/// ElevenLabs' stock "Rachel" voice.
+const DEFAULT_ELEVENLABS_VOICE: &str = "21m00Tcm4TlvDq8ikWAM";
+const DEFAULT_PIPER_VOICE: &str = "en_US-lessac-medium";
+
+/// Voice notes are staged here, relative to the workspace: channels only
+/// upload local files from inside the workspace.
+pub const VOICE_NOTE_DIR: &str = "state/voice_replies";
+
+/// Content prefix channels put on transcribed voice notes.
+const VOICE_MARKER: &str = "[Voice] ";
+
+/// A speech synthesis backend.
+#[async_trait]
+pub trait TtsProvider: Send + Sync {
+    /// Backend name for logs.
+    fn name(&self) -> &str;
+
+    /// Synthesize `text` into an Opus/OGG voice note.
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>>;
+}
+
+/// Resolve the API key from config, then from `env_var`.
+fn resolve_api_key(config: &TtsConfig, env_var: &str) -> Result<String> {
+    config
+        .api_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .or_else(|| {
+            std::env::var(env_var)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        })
+        .with_context(|| format!("Missing TTS API key: set [tts].api_key or {env_var}"))
+}
+
+/// Pull a readable error message out of a JSON error body.
+fn api_error_message(body: &str) -> String {
+    let parsed: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    parsed
+        .as_ref()
+        .and_then(|value| {
+            value["error"]["message"]
+                .as_str()
+                .or_else(|| value["detail"]["message"].as_str())
+                .or_else(|| value["detail"].as_str())
+        })
+        .map_or_else(
+            || crate::util::truncate_with_ellipsis(body.trim(), 200),
+            ToOwned::to_owned,
+        )
+}
+
+/// Encode any audio ffmpeg understands into mono Opus/OGG.
+async fn encode_ogg_opus(ffmpeg: &str, input: Vec<u8>) -> Result<Vec<u8>> {
+    let mut child = tokio::process::Command::new(ffmpeg)
+        .args([
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-i",
+            "pipe:0",
+            "-vn",
+            "-ac",
+            "1",
+            "-c:a",
+            "libopus",
+            "-b:a",
+            "32k",
+            "-application",
+            "voip",
+            "-f",
+            "ogg",
+            "pipe:1",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start `{ffmpeg}` for Opus encoding"))?;
+
+    let mut stdin = child.stdin.take().context("ffmpeg stdin unavailable")?;
+    let writer = tokio::spawn(async move {
+        let result = stdin.write_all(&input).await;
+        drop(stdin);
+        result
+    });
+    let output = child
+        .wait_with_output()
+        .await
+        .context("Failed to wait for ffmpeg")?;
+    // ffmpeg may exit before reading all input; its status is what matters.
+    let _ = writer.await;
+
+    if !output.status.success() {
+        bail!(
+            "ffmpeg exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    if output.stdout.is_empty() {
+        bail!("ffmpeg produced no audio");
+    }
+    Ok(output.stdout)
+}
+
+/// OpenAI-compatible `POST /audio/speech` backend.
+pub struct OpenAiTts {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+    model: String,
+    voice: String,
+}
+
+impl OpenAiTts {
+    pub fn new(config: &TtsConfig) -> Result<Self> {
+        Ok(Self {
+            client: crate::config::build_runtime_proxy_client_with_timeouts(
+                "tts.openai",
+                config.timeout_secs,
+                10,
+            ),
+            api_url: config
+                .api_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OPENAI_URL.into()),
+            api_key: resolve_api_key(config, "OPENAI_API_KEY")?,
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OPENAI_MODEL.into()),
+            voice: config
+                .voice
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OPENAI_VOICE.into()),
+        })
+    }
+}
+
+#[async_trait]
+impl TtsProvider for OpenAiTts {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "voice": self.voice,
+                "input": text,
+                "response_format": "opus",
+            }))
+            .send()
+            .await
+            .context("Failed to send speech request")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Speech API error ({status}): {}", api_error_message(&body));
+        }
+        let audio = response
+            .bytes()
+            .await
+            .context("Failed to read speech response")?;
+        if audio.is_empty() {
+            bail!("Speech API returned no audio");
+        }
+        Ok(audio.to_vec())
+    }
+}
+
+/// ElevenLabs-style `POST /v1/text-to-speech/{voice}` backend.
+pub struct ElevenLabsTts {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+    model: String,
+    voice: String,
+    ffmpeg: String,
+}
+
+impl ElevenLabsTts {
+    pub fn new(config: &TtsConfig) -> Result<Self> {
+        Ok(Self {
+            client: crate::config::build_runtime_proxy_client_with_timeouts(
+                "tts.elevenlabs",
+                config.timeout_secs,
+                10,
+            ),
+            api_url: config
+                .api_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ELEVENLABS_URL.into()),
+            api_key: resolve_api_key(config, "ELEVENLABS_API_KEY")?,
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ELEVENLABS_MODEL.into()),
+            voice: config
+                .voice
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ELEVENLABS_VOICE.into()),
+            ffmpeg: config.ffmpeg_binary.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl TtsProvider for ElevenLabsTts {
+    fn name(&self) -> &str {
+        "elevenlabs"
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        let url = format!(
+            "{}/{}",
+            self.api_url.trim_end_matches('/'),
+            urlencoding::encode(&self.voice)
+        );
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("output_format", "mp3_44100_128")])
+            .header("xi-api-key", &self.api_key)
+            .header("Accept", "audio/mpeg")
+            .json(&serde_json::json!({
+                "text": text,
+                "model_id": self.model,
+            }))
+            .send()
+            .await
+            .context("Failed to send speech request")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Speech API error ({status}): {}", api_error_message(&body));
+        }
+        let audio = response
+            .bytes()
+            .await
+            .context("Failed to read speech response")?;
+        if audio.is_empty() {
+            bail!("Speech API returned no audio");
+        }
+        encode_ogg_opus(&self.ffmpeg, audio.to_vec()).await
+    }
+}
+
+/// Local Piper backend, as used by `robot-kit`'s speak tool.
+pub struct PiperTts {
+    binary: String,
+    model_path: PathBuf,
+    ffmpeg: String,
+}
+
+impl PiperTts {
+    pub fn new(config: &TtsConfig) -> Self {
+        let voice = config.voice.as_deref().unwrap_or(DEFAULT_PIPER_VOICE);
+        let model_path = if voice.ends_with(".onnx") {
+            PathBuf::from(voice)
+        } else {
+            let dir = config.piper_model_dir.as_deref().map_or_else(
+                || {
+                    directories::UserDirs::new().map_or_else(
+                        || PathBuf::from("/usr/local/share/piper"),
+                        |dirs| dirs.home_dir().join(".zeroclaw/models/piper"),
+                    )
+                },
+                |dir| PathBuf::from(shellexpand::tilde(dir).as_ref()),
+            );
+            dir.join(format!("{voice}.onnx"))
+        };
+        Self {
+            binary: config.piper_binary.clone(),
+            model_path,
+            ffmpeg: config.ffmpeg_binary.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl TtsProvider for PiperTts {
+    fn name(&self) -> &str {
+        "piper"
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        if !self.model_path.exists() {
+            bail!("Piper voice model not found: {}", self.model_path.display());
+        }
+        let wav = tempfile::Builder::new()
+            .prefix("zeroclaw-tts-")
+            .suffix(".wav")
+            .tempfile()
+            .context("Failed to create Piper output file")?;
+
+        let mut piper = tokio::process::Command::new(&self.binary)
+            .arg("--model")
+            .arg(&self.model_path)
+            .arg("--output_file")
+            .arg(wav.path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start `{}`", self.binary))?;
+
+        if let Some(mut stdin) = piper.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let output = piper
+            .wait_with_output()
+            .await
+            .context("Failed to wait for Piper")?;
+        if !output.status.success() {
+            bail!(
+                "Piper exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let audio = tokio::fs::read(wav.path())
+            .await
+            .context("Failed to read Piper output")?;
+        if audio.is_empty() {
+            bail!("Piper produced no audio");
+        }
+        encode_ogg_opus(&self.ffmpeg, audio).await
+    }
+}
+
+/// Build the provider selected by `config.provider`.
+pub fn create_provider(config: &TtsConfig) -> Result<Box<dyn TtsProvider>> {
+    Ok(match config.provider {
+        TtsProviderKind::OpenAi => Box::new(OpenAiTts::new(config)?),
+        TtsProviderKind::ElevenLabs => Box::new(ElevenLabsTts::new(config)?),
+        TtsProviderKind::Piper => Box::new(PiperTts::new(config)),
+    })
+}
+
+/// Whether `msg` was a voice note (transcribed or attached).
+pub fn is_voice_message(msg: &ChannelMessage) -> bool {
+    msg.content.starts_with(VOICE_MARKER)
+        || msg.content.contains(&format!("\n{VOICE_MARKER}"))
+        || msg
+            .attachments
+            .iter()
+            .any(|attachment| attachment.kind == AttachmentKind::Voice)
+}
+
+/// Text to speak for `response`, or `None` when it should stay text.
+///
+/// Replies with code blocks, tables or attachments are not read aloud, and
+/// neither are replies longer than `max_chars`.
+pub fn speech_text(response: &str, max_chars: usize) -> Option<String> {
+    let (text, attachments) = attachments::parse_markers(response);
+    if !attachments.is_empty() {
+        return None;
+    }
+    let document = Document::parse(&text);
+    if document
+        .blocks
+        .iter()
+        .any(|block| matches!(block, Block::Code { .. } | Block::Table { .. }))
+    {
+        return None;
+    }
+    let spoken = document.render(Format::PlainText).trim().to_string();
+    if spoken.is_empty() || spoken.chars().count() > max_chars {
+        return None;
+    }
+    Some(spoken)
+}
+
+/// Voice reply policy and synthesis for channel replies.
+pub struct VoiceReplies {
+    config: TtsConfig,
+    provider: Box<dyn TtsProvider>,
+    /// Where voice notes are written before upload ([`VOICE_NOTE_DIR`]).
+    staging_dir: PathBuf,
+}
+
+impl VoiceReplies {
+    /// Build from `[tts]`; `None` when voice replies are disabled.
+    pub fn from_config(config: &TtsConfig, workspace_dir: &Path) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        Ok(Some(Self::with_provider(
+            config.clone(),
+            create_provider(config)?,
+            workspace_dir,
+        )))
+    }
+
+    pub fn with_provider(
+        config: TtsConfig,
+        provider: Box<dyn TtsProvider>,
+        workspace_dir: &Path,
+    ) -> Self {
+        Self {
+            config,
+            provider,
+            staging_dir: workspace_dir.join(VOICE_NOTE_DIR),
+        }
+    }
+
+    /// Effective policy for `sender` on `channel`: user override, then
+    /// channel override, then `reply_mode`.
+    pub fn reply_mode(&self, channel: &str, sender: &str) -> TtsReplyMode {
+        self.config
+            .users
+            .get(sender)
+            .or_else(|| self.config.channels.get(channel))
+            .copied()
+            .unwrap_or(self.config.reply_mode)
+    }
+
+    /// Whether the reply to `msg` should be spoken.
+    pub fn wants_voice(&self, msg: &ChannelMessage) -> bool {
+        match self.reply_mode(&msg.channel, &msg.sender) {
+            TtsReplyMode::Never => false,
+            TtsReplyMode::WhenVoice => is_voice_message(msg),
+            TtsReplyMode::Always => true,
+        }
+    }
+
+    /// Send `response` to `msg` as a voice note when policy allows it.
+    ///
+    /// Returns `false` when nothing was sent and the caller should reply with
+    /// text instead: policy says text, the channel cannot send voice notes,
+    /// the reply is not speakable, or synthesis or upload failed.
+    pub async fn send_voice_reply(
+        &self,
+        channel: &dyn Channel,
+        msg: &ChannelMessage,
+        response: &str,
+    ) -> bool {
+        if !self.wants_voice(msg) {
+            return false;
+        }
+        let Some(limits) = channel.attachment_limits() else {
+            return false;
+        };
+        if !limits.kinds.contains(&AttachmentKind::Voice) {
+            return false;
+        }
+        let Some(text) = speech_text(response, self.config.max_chars) else {
+            return false;
+        };
+
+        match self.try_send(channel, msg, &text, &limits).await {
+            Ok(()) => {
+                tracing::info!(
+                    channel = channel.name(),
+                    provider = self.provider.name(),
+                    "Sent voice reply"
+                );
+                true
+            }
+            Err(error) => {
+                tracing::warn!(
+                    channel = channel.name(),
+                    provider = self.provider.name(),
+                    "Voice reply failed, falling back to text: {error:#}"
+                );
+                false
+            }
+        }
+    }
+
+    async fn try_send(
+        &self,
+        channel: &dyn Channel,
+        msg: &ChannelMessage,
+        text: &str,
+        limits: &super::traits::AttachmentLimits,
+    ) -> Result<()> {
+        let audio = tokio::time::timeout(
+            Duration::from_secs(self.config.timeout_secs.max(1)),
+            self.provider.synthesize(text),
+        )
+        .await
+        .context("speech synthesis timed out")??;
+
+        tokio::fs::create_dir_all(&self.staging_dir)
+            .await
+            .context("Failed to create voice note directory")?;
+        // Removed again when `file` drops, after the upload.
+        let file = tempfile::Builder::new()
+            .prefix("voice-")
+            .suffix(".ogg")
+            .tempfile_in(&self.staging_dir)
+            .context("Failed to create voice note file")?;
+        tokio::fs::write(file.path(), &audio)
+            .await
+            .context("Failed to write voice note")?;
+
+        let attachment = Attachment::local(file.path())
+            .with_kind(AttachmentKind::Voice)
+            .with_mime("audio/ogg")
+            .with_size(audio.len() as u64);
+        attachments::check_limits(limits, &attachment).map_err(anyhow::Error::msg)?;
+
+        let message = SendMessage::new("", &msg.reply_target).in_thread(msg.thread_ts.clone());
+        channel.send_attachment(&message, &attachment).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use crate::channels::test_support::fake_binary;
+    use crate::channels::traits::{AttachmentLimits, ChannelEvent};
+    use parking_lot::Mutex;
+    use wiremock::matchers::{body_string_contains, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn message(content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            reply_target: "chat-1".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
+        }
+    }
+
+    struct StaticTts(Result<Vec<u8>, String>);
+
+    #[async_trait]
+    impl TtsProvider for StaticTts {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        async fn synthesize(&self, _text: &str) -> Result<Vec<u8>> {
+            self.0.clone().map_err(anyhow::Error::msg)
+        }
+    }
+
+    #[derive(Default)]
+    struct VoiceChannel {
+        voice: Mutex<Vec<(String, Vec<u8>)>>,
+    }
+
+    #[async_trait]
+    impl Channel for VoiceChannel {
+        fn name(&self) -> &str {
+            "telegram"
+        }
+
+        async fn send(&self, _message: &SendMessage) -> Result<()> {
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+
+        fn attachment_limits(&self) -> Option<AttachmentLimits> {
+            Some(AttachmentLimits::all_kinds(1024 * 1024))
+        }
+
+        async fn send_attachment(
+            &self,
+            message: &SendMessage,
+            attachment: &Attachment,
+        ) -> Result<()> {
+            assert_eq!(attachment.kind, AttachmentKind::Voice);
+            let bytes = std::fs::read(attachment.local_path().unwrap())?;
+            self.voice.lock().push((message.recipient.clone(), bytes));
+            Ok(())
+        }
+    }
+
+    fn replies(config: TtsConfig, result: Result<Vec<u8>, String>) -> VoiceReplies {
+        VoiceReplies::with_provider(config, Box::new(StaticTts(result)), &std::env::temp_dir())
+    }
+
+    #[test]
+    fn detects_voice_messages() {
+        assert!(is_voice_message(&message("[Voice] hello")));
+        assert!(is_voice_message(&message("> @bob:\n> hi\n\n[Voice] hello")));
+        assert!(!is_voice_message(&message("hello [Voice] ")));
+
+        let mut attached = message("");
+        attached
+            .attachments
+            .push(Attachment::local("/tmp/a.ogg").with_kind(AttachmentKind::Voice));
+        assert!(is_voice_message(&attached));
+    }
+
+    #[test]
+    fn reply_mode_prefers_user_then_channel() {
+        let mut config = TtsConfig::default();
+        config
+            .channels
+            .insert("telegram".into(), TtsReplyMode::Always);
+        config.users.insert("alice".into(), TtsReplyMode::Never);
+        let voice = replies(config, Ok(Vec::new()));
+
+        assert_eq!(voice.reply_mode("telegram", "alice"), TtsReplyMode::Never);
+        assert_eq!(voice.reply_mode("telegram", "bob"), TtsReplyMode::Always);
+        assert_eq!(voice.reply_mode("whatsapp", "bob"), TtsReplyMode::WhenVoice);
+    }
+
+    #[test]
+    fn speech_text_skips_unspeakable_replies() {
+        assert_eq!(
+            speech_text("**Sure**, it is _sunny_.", 100).as_deref(),
+            Some("Sure, it is sunny.")
+        );
+        assert!(speech_text("Run:\n```sh\nls\n```", 100).is_none());
+        assert!(speech_text("| a | b |\n|---|---|\n| 1 | 2 |", 100).is_none());
+        assert!(speech_text("Here [IMAGE:/tmp/cat.png]", 100).is_none());
+        assert!(speech_text(&"word ".repeat(50), 100).is_none());
+        assert!(speech_text("   ", 100).is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_voice_note_only_when_user_spoke() {
+        let voice = replies(TtsConfig::default(), Ok(b"OggS-audio".to_vec()));
+        let channel = VoiceChannel::default();
+
+        assert!(
+            !voice
+                .send_voice_reply(&channel, &message("hi"), "Hello!")
+                .await
+        );
+        assert!(
+            voice
+                .send_voice_reply(&channel, &message("[Voice] hi"), "Hello!")
+                .await
+        );
+
+        let sent = channel.voice.lock();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "chat-1");
+        assert_eq!(sent[0].1, b"OggS-audio");
+    }
+
+    #[tokio::test]
+    async fn telegram_uploads_voice_note_staged_in_workspace() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendVoice"))
+            .and(body_string_contains("OggS-audio"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true,
+                "result": {"message_id": 7}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let workspace = tempfile::tempdir().unwrap();
+        let channel = crate::channels::telegram::TelegramChannel::new(
+            "token".into(),
+            vec!["*".into()],
+            false,
+            false,
+        )
+        .with_api_base(server.uri())
+        .with_workspace_dir(workspace.path().to_path_buf());
+        let voice = VoiceReplies::with_provider(
+            TtsConfig::default(),
+            Box::new(StaticTts(Ok(b"OggS-audio".to_vec()))),
+            workspace.path(),
+        );
+
+        assert!(
+            voice
+                .send_voice_reply(&channel, &message("[Voice] hi"), "Hello!")
+                .await
+        );
+        let staged = std::fs::read_dir(workspace.path().join(VOICE_NOTE_DIR))
+            .unwrap()
+            .count();
+        assert_eq!(staged, 0, "voice note should be removed after upload");
+    }
+
+    #[tokio::test]
+    async fn synthesis_failure_falls_back_to_text() {
+        let voice = replies(TtsConfig::default(), Err("quota exceeded".into()));
+        let channel = VoiceChannel::default();
+
+        assert!(
+            !voice
+                .send_voice_reply(&channel, &message("[Voice] hi"), "Hello!")
+                .await
+        );
+        assert!(channel.voice.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn openai_requests_opus_speech() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/speech"))
+            .and(header("authorization", "Bearer sk-test"))
+            .and(body_string_contains("\"response_format\":\"opus\""))
+            .and(body_string_contains("\"voice\":\"nova\""))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"OggS".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = TtsConfig {
+            api_key: Some("sk-test".into()),
+            api_url: Some(format!("{}/v1/audio/speech", server.uri())),
+            voice: Some("nova".into()),
+            ..TtsConfig::default()
+        };
+        let audio = OpenAiTts::new(&config)
+            .unwrap()
+            .synthesize("hello")
+            .await
+            .unwrap();
+        assert_eq!(audio, b"OggS");
+    }
+
+    #[tokio::test]
+    async fn openai_surfaces_api_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+                "error": {"message": "Rate limit reached"}
+            })))
+            .mount(&server)
+            .await;
+
+        let config = TtsConfig {
+            api_key: Some("sk-test".into()),
+            api_url: Some(format!("{}/v1/audio/speech", server.uri())),
+            ..TtsConfig::default()
+        };
+        let err = OpenAiTts::new(&config)
+            .unwrap()
+            .synthesize("hello")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Rate limit reached"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn elevenlabs_encodes_mp3_through_ffmpeg() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/text-to-speech/voice-1"))
+            .and(query_param("output_format", "mp3_44100_128"))
+            .and(header("xi-api-key", "el-test"))
+            .and(body_string_contains(
+                "\"model_id\":\"eleven_multilingual_v2\"",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"ID3-mp3".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = TtsConfig {
+            provider: TtsProviderKind::ElevenLabs,
+            api_key: Some("el-test".into()),
+            api_url: Some(format!("{}/v1/text-to-speech", server.uri())),
+            voice: Some("voice-1".into()),
+            ffmpeg_binary: fake_binary(dir.path(), "ffmpeg", "printf 'OggS:'; cat"),
+            ..TtsConfig::default()
+        };
+        let audio = create_provider(&config)
+            .unwrap()
+            .synthesize("hello")
+            .await
+            .unwrap();
+        assert_eq!(audio, b"OggS:ID3-mp3");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn piper_pipes_text_and_encodes_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("test-voice.onnx"), b"model").unwrap();
+        // Fake piper: write stdin to the --output_file argument.
+        let piper = fake_binary(dir.path(), "piper", r#"cat > "$4""#);
+        let config = TtsConfig {
+            provider: TtsProviderKind::Piper,
+            voice: Some("test-voice".into()),
+            piper_binary: piper,
+            piper_model_dir: Some(dir.path().display().to_string()),
+            ffmpeg_binary: fake_binary(dir.path(), "ffmpeg", "printf 'OggS:'; cat"),
+            ..TtsConfig::default()
+        };
+        let audio = create_provider(&config)
+            .unwrap()
+            .synthesize("hello there")
+            .await
+            .unwrap();
+        assert_eq!(audio, b"OggS:hello there");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn ffmpeg_failure_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let ffmpeg = fake_binary(dir.path(), "ffmpeg", "echo 'no libopus' >&2; exit 1");
+        let err = encode_ogg_opus(&ffmpeg, b"wav".to_vec()).await.unwrap_err();
+        assert!(err.to_string().contains("no libopus"), "{err}");
+    }
+}
@@ -0,0 +1,793 @@
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::WebhookCallbackConfig;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Callback delivery log, relative to the workspace.
+pub const DELIVERY_LOG_FILE: &str = "state/webhook_deliveries.jsonl";
+/// Conversation id → reply URL registry, relative to the workspace.
+pub const CONVERSATIONS_FILE: &str = "state/webhook_conversations.jsonl";
+
+/// Oldest conversations are forgotten beyond this many entries.
+const MAX_CONVERSATIONS: usize = 10_000;
+/// Rewrite the conversations log after this many appended lines.
+const COMPACT_AFTER_LINES: usize = 512;
+/// Conversation ids double as session names (`webhook-<id>`, at most 64 chars).
+const MAX_CONVERSATION_ID_LEN: usize = 56;
+const MAX_BACKOFF_MS: u64 = 60_000;
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Outbound half of the webhook channel.
+///
+/// Inbound requests arrive through the gateway's `POST /webhook`. Requests that
+/// carry a `conversation_id` / `reply_url` register the URL here; every reply
+/// for that conversation — including later cron or delegate results addressed
+/// to `webhook` + conversation id — is POSTed to it with an HMAC signature,
+/// retried with exponential backoff and recorded in [`DELIVERY_LOG_FILE`].
+pub struct WebhookChannel {
+    config: WebhookCallbackConfig,
+    conversations: Arc<ConversationRegistry>,
+    delivery_log_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConversationEntry {
+    /// Registered callback URL; `None` replies go to `default_reply_url`.
+    #[serde(default)]
+    reply_url: Option<String>,
+    /// Caller that opened the conversation (see [`WebhookChannel::register_conversation`]).
+    caller: String,
+    updated_at: i64,
+}
+
+/// One line of [`CONVERSATIONS_FILE`].
+#[derive(Debug, Serialize, Deserialize)]
+struct ConversationRecord {
+    id: String,
+    #[serde(flatten)]
+    entry: ConversationEntry,
+}
+
+/// Read the conversations log at `path`; later lines win and only the
+/// newest [`MAX_CONVERSATIONS`] entries are kept.
+fn read_conversations(path: &Path) -> HashMap<String, ConversationEntry> {
+    let Ok(raw) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    let mut conversations = HashMap::new();
+    for line in raw.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<ConversationRecord>(line) {
+            Ok(record) => {
+                conversations.insert(record.id, record.entry);
+            }
+            Err(e) => tracing::warn!("Skipping unreadable line in {}: {e}", path.display()),
+        }
+    }
+    evict_oldest(&mut conversations);
+    conversations
+}
+
+/// Replace the conversations log at `path` with one line per entry.
+fn write_conversations(path: &Path, records: &[ConversationRecord]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut out = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut out, record)?;
+        out.push(b'\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, out).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+fn evict_oldest(conversations: &mut HashMap<String, ConversationEntry>) {
+    if conversations.len() <= MAX_CONVERSATIONS {
+        return;
+    }
+    let mut by_age: Vec<(String, i64)> = conversations
+        .iter()
+        .map(|(id, entry)| (id.clone(), entry.updated_at))
+        .collect();
+    by_age.sort_by_key(|(_, updated_at)| *updated_at);
+    let excess = conversations.len() - MAX_CONVERSATIONS;
+    for (id, _) in by_age.into_iter().take(excess) {
+        conversations.remove(&id);
+    }
+}
+
+/// Registered conversations, kept in memory and shared by every
+/// [`WebhookChannel`] on the same workspace (gateway, channels, cron).
+///
+/// New conversations append one line to [`CONVERSATIONS_FILE`]; the log is
+/// rewritten off the async runtime every [`COMPACT_AFTER_LINES`] lines.
+/// Follow-up requests only touch memory, so eviction after a restart goes by
+/// registration time.
+struct ConversationRegistry {
+    path: PathBuf,
+    entries: parking_lot::Mutex<HashMap<String, ConversationEntry>>,
+    /// Lines appended since the last compaction.
+    appended: tokio::sync::Mutex<usize>,
+}
+
+impl ConversationRegistry {
+    fn shared(workspace_dir: &Path) -> Arc<Self> {
+        static REGISTRIES: OnceLock<Mutex<HashMap<PathBuf, Arc<ConversationRegistry>>>> =
+            OnceLock::new();
+        let mut registries = REGISTRIES
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        Arc::clone(
+            registries
+                .entry(workspace_dir.to_path_buf())
+                .or_insert_with(|| {
+                    let path = workspace_dir.join(CONVERSATIONS_FILE);
+                    Arc::new(Self {
+                        entries: parking_lot::Mutex::new(read_conversations(&path)),
+                        path,
+                        appended: tokio::sync::Mutex::new(0),
+                    })
+                }),
+        )
+    }
+
+    fn reply_url(&self, conversation_id: &str) -> Option<String> {
+        self.entries
+            .lock()
+            .get(conversation_id)
+            .and_then(|entry| entry.reply_url.clone())
+    }
+
+    async fn append(&self, record: &ConversationRecord) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let mut appended = self.appended.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+
+        *appended += 1;
+        if *appended >= COMPACT_AFTER_LINES {
+            let records: Vec<ConversationRecord> = self
+                .entries
+                .lock()
+                .iter()
+                .map(|(id, entry)| ConversationRecord {
+                    id: id.clone(),
+                    entry: entry.clone(),
+                })
+                .collect();
+            let path = self.path.clone();
+            tokio::task::spawn_blocking(move || write_conversations(&path, &records)).await??;
+            *appended = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Why an existing conversation refused a request.
+#[derive(Debug, thiserror::Error)]
+pub enum ConversationConflict {
+    #[error("Conversation {0} belongs to another caller")]
+    OtherCaller(String),
+    #[error("Conversation {0} is bound to a different reply_url")]
+    ReplyUrlChanged(String),
+}
+
+/// Result of one callback delivery, as written to the delivery log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DeliveryRecord {
+    timestamp: String,
+    delivery_id: String,
+    event: String,
+    conversation_id: String,
+    url: String,
+    attempts: u32,
+    delivered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookCallbackConfig, workspace_dir: &Path) -> Self {
+        Self {
+            config,
+            conversations: ConversationRegistry::shared(workspace_dir),
+            delivery_log_path: workspace_dir.join(DELIVERY_LOG_FILE),
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "channel.webhook",
+            self.config.timeout_secs.max(1),
+            CONNECT_TIMEOUT_SECS,
+        )
+    }
+
+    /// Check that a caller-supplied `reply_url` may receive callbacks.
+    ///
+    /// The host must be listed in `allowed_hosts` (or `"*"`), and plain HTTP is
+    /// only accepted for loopback hosts.
+    pub fn check_reply_url(&self, reply_url: &str) -> Result<()> {
+        let url = reqwest::Url::parse(reply_url.trim()).context("reply_url is not a valid URL")?;
+        let host = url
+            .host_str()
+            .context("reply_url has no host")?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        let loopback = host == "localhost"
+            || host
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback());
+        match url.scheme() {
+            "https" => {}
+            "http" if loopback => {}
+            scheme => bail!("reply_url must use https (got {scheme})"),
+        }
+        let allowed = self
+            .config
+            .allowed_hosts
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(&host));
+        if !allowed {
+            bail!(
+                "reply_url host {host} is not in channels_config.webhook.callbacks.allowed_hosts"
+            );
+        }
+        Ok(())
+    }
+
+    /// Bind `conversation_id` to `caller` and its `reply_url` so later replies
+    /// find it.
+    ///
+    /// The first request of a conversation registers it. Later requests must
+    /// come from the same `caller` and may only repeat the registered
+    /// `reply_url`, so nobody else can join the conversation's session or
+    /// redirect its replies ([`ConversationConflict`]). A new conversation
+    /// needs a `reply_url` unless `default_reply_url` is configured.
+    pub async fn register_conversation(
+        &self,
+        conversation_id: &str,
+        caller: &str,
+        reply_url: Option<&str>,
+    ) -> Result<()> {
+        validate_conversation_id(conversation_id)?;
+        let reply_url = reply_url.map(str::trim).filter(|url| !url.is_empty());
+        if let Some(reply_url) = reply_url {
+            self.check_reply_url(reply_url)?;
+        }
+
+        let registered = {
+            let mut conversations = self.conversations.entries.lock();
+            let now = chrono::Utc::now().timestamp_millis();
+            if let Some(entry) = conversations.get_mut(conversation_id) {
+                if entry.caller != caller {
+                    return Err(ConversationConflict::OtherCaller(conversation_id.into()).into());
+                }
+                if reply_url.is_some_and(|url| entry.reply_url.as_deref() != Some(url)) {
+                    return Err(
+                        ConversationConflict::ReplyUrlChanged(conversation_id.into()).into(),
+                    );
+                }
+                entry.updated_at = now;
+                return Ok(());
+            }
+            if reply_url.is_none() && self.default_reply_url().is_none() {
+                bail!(
+                    "Conversation {conversation_id} has no reply_url; include one in the first request"
+                );
+            }
+            let entry = ConversationEntry {
+                reply_url: reply_url.map(str::to_string),
+                caller: caller.to_string(),
+                updated_at: now,
+            };
+            conversations.insert(conversation_id.to_string(), entry.clone());
+            evict_oldest(&mut conversations);
+            ConversationRecord {
+                id: conversation_id.to_string(),
+                entry,
+            }
+        };
+
+        if let Err(e) = self.conversations.append(&registered).await {
+            self.conversations.entries.lock().remove(conversation_id);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn default_reply_url(&self) -> Option<String> {
+        self.config
+            .default_reply_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+    }
+
+    fn resolve_reply_url(&self, conversation_id: &str) -> Option<String> {
+        self.conversations
+            .reply_url(conversation_id)
+            .or_else(|| self.default_reply_url())
+    }
+
+    /// Report a failed request back to the conversation as an `error` event.
+    pub async fn send_error(
+        &self,
+        conversation_id: &str,
+        in_reply_to: Option<&str>,
+        error: &str,
+    ) -> Result<()> {
+        self.deliver("error", conversation_id, error, in_reply_to)
+            .await
+    }
+
+    async fn deliver(
+        &self,
+        event: &str,
+        conversation_id: &str,
+        content: &str,
+        in_reply_to: Option<&str>,
+    ) -> Result<()> {
+        let Some(url) = self.resolve_reply_url(conversation_id) else {
+            bail!(
+                "No reply_url registered for webhook conversation {conversation_id} \
+                 and no channels_config.webhook.callbacks.default_reply_url"
+            );
+        };
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let body = serde_json::to_string(&serde_json::json!({
+            "type": event,
+            "delivery_id": delivery_id,
+            "conversation_id": conversation_id,
+            "in_reply_to": in_reply_to,
+            "content": content,
+            "timestamp": chrono::Utc::now().timestamp(),
+        }))?;
+
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut backoff_ms = self.config.initial_backoff_ms;
+        let mut attempts = 0;
+        let mut status = None;
+        let mut error = None;
+        let mut delivered = false;
+        while attempts < max_attempts {
+            if attempts > 0 {
+                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                backoff_ms = backoff_ms.saturating_mul(2).min(MAX_BACKOFF_MS);
+            }
+            attempts += 1;
+
+            let timestamp = chrono::Utc::now().timestamp().to_string();
+            let signature = sign_payload(&self.config.signing_secret, &timestamp, &body);
+            let result = self
+                .http_client()
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-ZeroClaw-Event", event)
+                .header("X-ZeroClaw-Delivery", &delivery_id)
+                .header("X-ZeroClaw-Timestamp", &timestamp)
+                .header("X-ZeroClaw-Signature", signature)
+                .body(body.clone())
+                .send()
+                .await;
+            match result {
+                Ok(resp) if resp.status().is_success() => {
+                    status = Some(resp.status().as_u16());
+                    error = None;
+                    delivered = true;
+                    break;
+                }
+                Ok(resp) => {
+                    let code = resp.status();
+                    status = Some(code.as_u16());
+                    let text = resp.text().await.unwrap_or_default();
+                    error = Some(crate::providers::sanitize_api_error(&text));
+                    let retryable = code.is_server_error()
+                        || code == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || code == reqwest::StatusCode::REQUEST_TIMEOUT;
+                    if !retryable {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    status = None;
+                    error = Some(e.to_string());
+                }
+            }
+            tracing::debug!(
+                "Webhook callback {delivery_id} attempt {attempts}/{max_attempts} failed: {}",
+                error.as_deref().unwrap_or_default()
+            );
+        }
+
+        self.record(&DeliveryRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            delivery_id: delivery_id.clone(),
+            event: event.to_string(),
+            conversation_id: conversation_id.to_string(),
+            url,
+            attempts,
+            delivered,
+            status,
+            error: error.clone(),
+        });
+
+        if delivered {
+            Ok(())
+        } else {
+            let status = status.map_or_else(|| "no response".to_string(), |s| s.to_string());
+            bail!(
+                "Webhook callback {delivery_id} failed after {attempts} attempt(s) ({status}): {}",
+                error.unwrap_or_default()
+            )
+        }
+    }
+
+    fn record(&self, record: &DeliveryRecord) {
+        let path = &self.delivery_log_path;
+        let append = || -> Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+            Ok(())
+        };
+        if let Err(err) = append() {
+            tracing::warn!("Failed to record webhook delivery: {err:#}");
+        }
+    }
+}
+
+/// Validate a caller-chosen conversation id (letters, digits, `-`, `_`, `.`).
+pub fn validate_conversation_id(conversation_id: &str) -> Result<()> {
+    let valid = !conversation_id.is_empty()
+        && conversation_id.len() <= MAX_CONVERSATION_ID_LEN
+        && !conversation_id.starts_with('.')
+        && conversation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        bail!(
+            "Invalid conversation_id `{conversation_id}` (use up to {MAX_CONVERSATION_ID_LEN} letters, digits, `-`, `_` or `.`)"
+        );
+    }
+    Ok(())
+}
+
+/// Agent session that keeps the history of one webhook conversation.
+pub fn conversation_session_name(conversation_id: &str) -> String {
+    format!("webhook-{conversation_id}")
+}
+
+/// `X-ZeroClaw-Signature` value: `sha256=` + hex HMAC-SHA256 of `"{timestamp}.{body}"`.
+pub fn sign_payload(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[async_trait]
+impl Channel for WebhookChannel {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        self.deliver(
+            "message",
+            &message.recipient,
+            &message.content,
+            message.thread_ts.as_deref(),
+        )
+        .await
+    }
+
+    async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        tracing::info!(
+            "Webhook callback channel active. \
+            Inbound messages are received via the gateway's /webhook endpoint."
+        );
+
+        // Keep task alive; incoming requests are handled by the gateway webhook handler.
+        loop {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn callbacks(allowed_hosts: &[&str]) -> WebhookCallbackConfig {
+        WebhookCallbackConfig {
+            signing_secret: "shh".into(),
+            allowed_hosts: allowed_hosts.iter().map(ToString::to_string).collect(),
+            default_reply_url: None,
+            max_attempts: 3,
+            initial_backoff_ms: 1,
+            timeout_secs: 5,
+        }
+    }
+
+    fn read_log(workspace: &Path) -> Vec<DeliveryRecord> {
+        std::fs::read_to_string(workspace.join(DELIVERY_LOG_FILE))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sign_payload_matches_hmac_of_timestamp_and_body() {
+        let signature = sign_payload("shh", "1700000000", r#"{"a":1}"#);
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"shh").unwrap();
+        mac.update(br#"1700000000.{"a":1}"#);
+        assert_eq!(
+            signature,
+            format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+        );
+    }
+
+    #[test]
+    fn reply_url_must_be_https_on_allowed_host() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ch = WebhookChannel::new(callbacks(&["hooks.example.com"]), tmp.path());
+        assert!(ch.check_reply_url("https://hooks.example.com/zc").is_ok());
+        assert!(ch.check_reply_url("https://HOOKS.example.com/zc").is_ok());
+        assert!(ch.check_reply_url("http://hooks.example.com/zc").is_err());
+        assert!(ch.check_reply_url("https://evil.example.com/zc").is_err());
+        assert!(ch.check_reply_url("not a url").is_err());
+
+        let ch = WebhookChannel::new(callbacks(&["*"]), tmp.path());
+        assert!(ch.check_reply_url("https://anything.example.org/").is_ok());
+        assert!(ch.check_reply_url("http://127.0.0.1:9000/cb").is_ok());
+        assert!(ch.check_reply_url("http://[::1]:9000/cb").is_ok());
+        assert!(ch.check_reply_url("ftp://anything.example.org/").is_err());
+    }
+
+    #[test]
+    fn conversation_ids_are_session_safe() {
+        assert!(validate_conversation_id("ticket-42_a.b").is_ok());
+        assert!(validate_conversation_id("").is_err());
+        assert!(validate_conversation_id("../escape").is_err());
+        assert!(validate_conversation_id(".hidden").is_err());
+        assert!(validate_conversation_id(&"x".repeat(MAX_CONVERSATION_ID_LEN + 1)).is_err());
+        assert_eq!(conversation_session_name("t1"), "webhook-t1");
+    }
+
+    #[tokio::test]
+    async fn conversations_persist_and_fall_back_to_default_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cfg = callbacks(&["hooks.example.com"]);
+        let ch = WebhookChannel::new(cfg.clone(), tmp.path());
+        ch.register_conversation("c1", "alice", Some("https://hooks.example.com/one"))
+            .await
+            .unwrap();
+        assert!(ch
+            .register_conversation("c2", "alice", Some("https://other.example.com/"))
+            .await
+            .is_err());
+        assert!(ch.register_conversation("c3", "alice", None).await.is_err());
+
+        // A fresh instance (e.g. the cron scheduler) sees the registration.
+        cfg.default_reply_url = Some("https://hooks.example.com/default".into());
+        let reopened = WebhookChannel::new(cfg, tmp.path());
+        assert_eq!(
+            reopened.resolve_reply_url("c1").as_deref(),
+            Some("https://hooks.example.com/one")
+        );
+        assert_eq!(
+            reopened.resolve_reply_url("unknown").as_deref(),
+            Some("https://hooks.example.com/default")
+        );
+        assert!(ch.resolve_reply_url("unknown").is_none());
+        reopened
+            .register_conversation("c3", "alice", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            reopened.resolve_reply_url("c3").as_deref(),
+            Some("https://hooks.example.com/default")
+        );
+
+        // Both registrations are on disk for the next process; the follow-up
+        // requests above did not add lines.
+        let path = tmp.path().join(CONVERSATIONS_FILE);
+        let on_disk = read_conversations(&path);
+        assert_eq!(on_disk.len(), 2);
+        assert_eq!(
+            on_disk["c1"].reply_url.as_deref(),
+            Some("https://hooks.example.com/one")
+        );
+        assert_eq!(on_disk["c3"].caller, "alice");
+    }
+
+    #[tokio::test]
+    async fn conversations_stay_bound_to_caller_and_reply_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ch = WebhookChannel::new(callbacks(&["hooks.example.com"]), tmp.path());
+        ch.register_conversation("c1", "alice", Some("https://hooks.example.com/one"))
+            .await
+            .unwrap();
+
+        // Follow-ups by the owner may omit or repeat the reply_url.
+        ch.register_conversation("c1", "alice", None).await.unwrap();
+        ch.register_conversation("c1", "alice", Some("https://hooks.example.com/one"))
+            .await
+            .unwrap();
+
+        let err = ch
+            .register_conversation("c1", "alice", Some("https://hooks.example.com/two"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConversationConflict>(),
+            Some(ConversationConflict::ReplyUrlChanged(_))
+        ));
+        let err = ch
+            .register_conversation("c1", "mallory", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConversationConflict>(),
+            Some(ConversationConflict::OtherCaller(_))
+        ));
+        assert_eq!(
+            ch.resolve_reply_url("c1").as_deref(),
+            Some("https://hooks.example.com/one")
+        );
+    }
+
+    #[tokio::test]
+    async fn send_posts_signed_callback_and_logs_delivery() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/cb"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let ch = WebhookChannel::new(callbacks(&["127.0.0.1"]), tmp.path());
+        ch.register_conversation("c1", "alice", Some(&format!("{}/cb", server.uri())))
+            .await
+            .unwrap();
+        ch.send(&SendMessage::new("hello", "c1").in_thread(Some("webhook_m1".into())))
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let request = &requests[0];
+        let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+        let body = String::from_utf8(request.body.clone()).unwrap();
+        assert_eq!(
+            header("X-ZeroClaw-Signature"),
+            sign_payload("shh", header("X-ZeroClaw-Timestamp"), &body)
+        );
+        assert_eq!(header("X-ZeroClaw-Event"), "message");
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["conversation_id"], "c1");
+        assert_eq!(payload["content"], "hello");
+        assert_eq!(payload["in_reply_to"], "webhook_m1");
+        assert_eq!(payload["delivery_id"], header("X-ZeroClaw-Delivery"));
+
+        let log = read_log(tmp.path());
+        assert_eq!(log.len(), 1);
+        assert!(log[0].delivered);
+        assert_eq!(log[0].attempts, 1);
+        assert_eq!(log[0].status, Some(204));
+    }
+
+    #[tokio::test]
+    async fn send_retries_server_errors_with_backoff() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/cb"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/cb"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cfg = callbacks(&[]);
+        cfg.default_reply_url = Some(format!("{}/cb", server.uri()));
+        let ch = WebhookChannel::new(cfg, tmp.path());
+        ch.send(&SendMessage::new("later result", "cron-job-1"))
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 3);
+        let delivery = |i: usize| {
+            requests[i]
+                .headers
+                .get("X-ZeroClaw-Delivery")
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(delivery(0), delivery(2), "retries reuse the delivery id");
+
+        let log = read_log(tmp.path());
+        assert!(log[0].delivered);
+        assert_eq!(log[0].attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/cb"))
+            .respond_with(ResponseTemplate::new(410).set_body_string("gone"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let ch = WebhookChannel::new(callbacks(&["127.0.0.1"]), tmp.path());
+        ch.register_conversation("c1", "alice", Some(&format!("{}/cb", server.uri())))
+            .await
+            .unwrap();
+        let err = ch
+            .send_error("c1", None, "LLM request failed")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("410"), "{err}");
+
+        let log = read_log(tmp.path());
+        assert!(!log[0].delivered);
+        assert_eq!(log[0].event, "error");
+        assert_eq!(log[0].attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn send_without_reply_url_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ch = WebhookChannel::new(callbacks(&["*"]), tmp.path());
+        assert!(ch.send(&SendMessage::new("x", "nowhere")).await.is_err());
+    }
+}
@@ -26,7 +26,9 @@
 //! This channel is automatically selected when `session_path` is set in the config.
 //! The Cloud API channel is used when `phone_number_id` is set.
 
-use super::traits::{Channel, ChannelMessage, SendMessage};
+#[cfg(feature = "whatsapp-web")]
+use super::traits::{Attachment, AttachmentKind, AttachmentLimits, AttachmentSource};
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 use super::whatsapp_storage::RusqliteStore;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
@@ -44,6 +46,25 @@ enum WaAttachmentKind {
     Document,
     Video,
     Audio,
+    /// Push-to-talk voice note (Opus/OGG).
+    Voice,
+}
+
+/// Maximum media upload size accepted by WhatsApp (16 MB).
+#[cfg(feature = "whatsapp-web")]
+const WA_MAX_MEDIA_BYTES: u64 = 16 * 1024 * 1024;
+
+#[cfg(feature = "whatsapp-web")]
+impl From<AttachmentKind> for WaAttachmentKind {
+    fn from(kind: AttachmentKind) -> Self {
+        match kind {
+            AttachmentKind::Image => Self::Image,
+            AttachmentKind::Document => Self::Document,
+            AttachmentKind::Video => Self::Video,
+            AttachmentKind::Audio => Self::Audio,
+            AttachmentKind::Voice => Self::Voice,
+        }
+    }
 }
 
 #[cfg(feature = "whatsapp-web")]
@@ -55,6 +76,7 @@ impl WaAttachmentKind {
             "DOCUMENT" => Some(Self::Document),
             "VIDEO" => Some(Self::Video),
             "AUDIO" => Some(Self::Audio),
+            "VOICE" => Some(Self::Voice),
             _ => None,
         }
     }
@@ -65,7 +87,7 @@ impl WaAttachmentKind {
             Self::Image => wa_rs_core::download::MediaType::Image,
             Self::Document => wa_rs_core::download::MediaType::Document,
             Self::Video => wa_rs_core::download::MediaType::Video,
-            Self::Audio => wa_rs_core::download::MediaType::Audio,
+            Self::Audio | Self::Voice => wa_rs_core::download::MediaType::Audio,
         }
     }
 }
@@ -415,6 +437,20 @@ impl WhatsAppWebChannel {
                 })),
                 ..Default::default()
             },
+            WaAttachmentKind::Voice => wa_rs_proto::whatsapp::Message {
+                audio_message: Some(Box::new(wa_rs_proto::whatsapp::message::AudioMessage {
+                    url: Some(upload.url),
+                    direct_path: Some(upload.direct_path),
+                    media_key: Some(upload.media_key),
+                    file_enc_sha256: Some(upload.file_enc_sha256),
+                    file_sha256: Some(upload.file_sha256),
+                    file_length: Some(upload.file_length),
+                    mimetype: Some("audio/ogg; codecs=opus".to_string()),
+                    ptt: Some(true),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            },
         };
 
         let msg_id = client.send_message(to.clone(), outgoing).await?;
@@ -434,6 +470,26 @@ impl Channel for WhatsAppWebChannel {
         "whatsapp"
     }
 
+    fn attachment_limits(&self) -> Option<AttachmentLimits> {
+        Some(AttachmentLimits::all_kinds(WA_MAX_MEDIA_BYTES))
+    }
+
+    async fn send_attachment(&self, message: &SendMessage, attachment: &Attachment) -> Result<()> {
+        let client = self.client.lock().clone();
+        let Some(client) = client else {
+            anyhow::bail!("WhatsApp Web client not connected. Initialize the bot first.");
+        };
+        let AttachmentSource::Local(path) = &attachment.source else {
+            anyhow::bail!("WhatsApp Web can only upload local files");
+        };
+        let to = self.recipient_to_jid(&message.recipient)?;
+        let attachment = WaAttachment {
+            kind: attachment.kind.into(),
+            target: path.display().to_string(),
+        };
+        self.send_media_attachment(&client, &to, &attachment).await
+    }
+
     async fn send(&self, message: &SendMessage) -> Result<()> {
         let client = self.client.lock().clone();
         let Some(client) = client else {
@@ -618,7 +674,8 @@ impl Channel for WhatsAppWebChannel {
                                         // &AudioMessage which implements Downloadable.
                                         match _client.download(audio_msg.as_ref()).await {
                                             Ok(audio_bytes) => {
-                                                match super::transcription::transcribe_audio(
+                                                match super::transcription::transcribe_audio_for_channel(
+                                                    "whatsapp",
                                                     audio_bytes,
                                                     file_name,
                                                     tc,
@@ -679,6 +736,8 @@ impl Channel for WhatsAppWebChannel {
                                         content,
                                         timestamp: chrono::Utc::now().timestamp() as u64,
                                         thread_ts: None,
+                                        attachments: Vec::new(),
+                                        event: ChannelEvent::Message,
                                     })
                                     .await
                                 {
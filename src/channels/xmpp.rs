@@ -0,0 +1,1515 @@
+use super::traits::{Attachment, Channel, ChannelEvent, ChannelMessage, SendMessage};
+use crate::config::{StreamMode, XmppConfig, XmppTlsMode};
+use anyhow::Context;
+use async_trait::async_trait;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, Mutex};
+use tokio_rustls::rustls;
+
+const NS_TLS: &str = "urn:ietf:params:xml:ns:xmpp-tls";
+const NS_SASL: &str = "urn:ietf:params:xml:ns:xmpp-sasl";
+const NS_BIND: &str = "urn:ietf:params:xml:ns:xmpp-bind";
+const NS_SESSION: &str = "urn:ietf:params:xml:ns:xmpp-session";
+const NS_STANZAS: &str = "urn:ietf:params:xml:ns:xmpp-stanzas";
+const NS_MUC: &str = "http://jabber.org/protocol/muc";
+const NS_MUC_USER: &str = "http://jabber.org/protocol/muc#user";
+const NS_CHAT_STATES: &str = "http://jabber.org/protocol/chatstates";
+const NS_CORRECT: &str = "urn:xmpp:message-correct:0";
+const NS_DELAY: &str = "urn:xmpp:delay";
+const NS_PING: &str = "urn:xmpp:ping";
+const NS_HINTS: &str = "urn:xmpp:hints";
+const NS_OOB: &str = "jabber:x:oob";
+
+/// Resource bound for the bot session.
+const RESOURCE: &str = "zeroclaw";
+
+/// Stream negotiation (TLS, SASL, bind) must finish within this window.
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Idle period after which the server is pinged (XEP-0199).
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// If nothing arrives for this long (pings included), the connection is dead.
+const READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Upper bound for a single buffered stanza.
+const MAX_STANZA_BYTES: usize = 1024 * 1024;
+
+/// Largest SCRAM iteration count accepted from a server; higher values would
+/// let a malicious server stall the client in PBKDF2.
+const MAX_SCRAM_ITERATIONS: u32 = 100_000;
+
+/// Monotonic counter to ensure unique message IDs under burst traffic.
+static MSG_SEQ: AtomicU64 = AtomicU64::new(0);
+
+trait XmppIo: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> XmppIo for T {}
+
+type BoxedIo = Box<dyn XmppIo>;
+type WriteHalf = tokio::io::WriteHalf<BoxedIo>;
+
+/// XMPP (Jabber) client channel.
+///
+/// Connects with STARTTLS or direct TLS, authenticates with SASL
+/// (SCRAM-SHA-256 or PLAIN), and handles 1:1 chats and MUC rooms. Typing
+/// indicators use XEP-0085 chat states; streamed drafts are updated in place
+/// with XEP-0308 message correction.
+pub struct XmppChannel {
+    jid: String,
+    password: String,
+    server: String,
+    port: u16,
+    tls: XmppTlsMode,
+    verify_tls: bool,
+    rooms: Vec<String>,
+    nickname: String,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    group_reply_allowed_sender_ids: Vec<String>,
+    stream_mode: StreamMode,
+    draft_update_interval_ms: u64,
+    /// Shared write half of the session stream for sending stanzas.
+    writer: Arc<Mutex<Option<WriteHalf>>>,
+    last_draft_edit: parking_lot::Mutex<HashMap<String, Instant>>,
+}
+
+impl XmppChannel {
+    pub fn new(config: XmppConfig) -> Self {
+        let mention_only = config.effective_group_reply_mode().requires_mention();
+        let group_reply_allowed_sender_ids = config.group_reply_allowed_sender_ids();
+        let jid = bare_jid(config.jid.trim()).to_string();
+        let server = config
+            .server
+            .filter(|server| !server.trim().is_empty())
+            .unwrap_or_else(|| domain_of(&jid).to_string());
+        let port = config.port.unwrap_or(match config.tls {
+            XmppTlsMode::Starttls => 5222,
+            XmppTlsMode::Direct => 5223,
+        });
+        let nickname = config
+            .nickname
+            .filter(|nick| !nick.trim().is_empty())
+            .unwrap_or_else(|| local_part(&jid).to_string());
+
+        Self {
+            jid,
+            password: config.password,
+            server,
+            port,
+            tls: config.tls,
+            verify_tls: config.verify_tls.unwrap_or(true),
+            rooms: config
+                .rooms
+                .iter()
+                .map(|room| bare_jid(room.trim()).to_string())
+                .filter(|room| !room.is_empty())
+                .collect(),
+            nickname,
+            allowed_users: config.allowed_users,
+            mention_only,
+            group_reply_allowed_sender_ids,
+            stream_mode: config.stream_mode,
+            draft_update_interval_ms: config.draft_update_interval_ms,
+            writer: Arc::new(Mutex::new(None)),
+            last_draft_edit: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check a sender (bare JID or occupant JID) against the allowlist.
+    /// Empty list means deny everyone; `"*"` allows everyone.
+    fn is_user_allowed(&self, sender: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(sender))
+    }
+
+    fn is_group_sender_trigger_enabled(&self, sender: &str) -> bool {
+        self.group_reply_allowed_sender_ids
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(sender))
+    }
+
+    fn is_room(&self, jid: &str) -> bool {
+        let jid = bare_jid(jid);
+        self.rooms.iter().any(|room| room.eq_ignore_ascii_case(jid))
+    }
+
+    fn message_type_for(&self, recipient: &str) -> &'static str {
+        if self.is_room(recipient) {
+            "groupchat"
+        } else {
+            "chat"
+        }
+    }
+
+    /// Whether `body` addresses the bot by its room nickname.
+    fn mentions_bot(&self, body: &str) -> bool {
+        let nick = self.nickname.to_lowercase();
+        if nick.is_empty() {
+            return false;
+        }
+        let body = body.to_lowercase();
+        body.match_indices(&nick).any(|(start, _)| {
+            let before = body[..start].chars().next_back();
+            let after = body[start + nick.len()..].chars().next();
+            before.is_none_or(|c| !c.is_alphanumeric())
+                && after.is_none_or(|c| !c.is_alphanumeric())
+        })
+    }
+
+    async fn connect(&self) -> anyhow::Result<BoxedIo> {
+        let addr = format!("{}:{}", self.server, self.port);
+        let tcp = tokio::time::timeout(
+            Duration::from_secs(30),
+            tokio::net::TcpStream::connect(&addr),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("XMPP connect to {addr} timed out"))??;
+
+        match self.tls {
+            XmppTlsMode::Starttls => Ok(Box::new(tcp)),
+            XmppTlsMode::Direct => self.tls_wrap(Box::new(tcp)).await,
+        }
+    }
+
+    async fn tls_wrap(&self, io: BoxedIo) -> anyhow::Result<BoxedIo> {
+        let tls_config = if self.verify_tls {
+            let root_store: rustls::RootCertStore =
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+            rustls::ClientConfig::builder()
+                .with_root_certificates(root_store)
+                .with_no_client_auth()
+        } else {
+            rustls::ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(super::irc::NoVerify))
+                .with_no_client_auth()
+        };
+
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+        // XMPP certificates are issued for the JID domain, not the host name.
+        let domain = rustls::pki_types::ServerName::try_from(domain_of(&self.jid).to_string())?;
+        let tls = connector.connect(domain, io).await?;
+        Ok(Box::new(tls))
+    }
+
+    /// Negotiate the stream on `io` and run the session until it ends.
+    /// `secure` tells whether `io` is already encrypted.
+    async fn run(
+        &self,
+        io: BoxedIo,
+        secure: bool,
+        tx: mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        let (stream, bound_jid) =
+            tokio::time::timeout(NEGOTIATION_TIMEOUT, self.negotiate(io, secure))
+                .await
+                .map_err(|_| anyhow::anyhow!("XMPP stream negotiation timed out"))??;
+        tracing::info!("XMPP session established as {bound_jid}");
+
+        let result = self.run_session(stream, tx).await;
+        *self.writer.lock().await = None;
+        result
+    }
+
+    /// STARTTLS (when needed), SASL and resource binding. Returns the
+    /// negotiated stream and the bound full JID.
+    async fn negotiate(
+        &self,
+        io: BoxedIo,
+        mut secure: bool,
+    ) -> anyhow::Result<(XmlStream<BoxedIo>, String)> {
+        let domain = domain_of(&self.jid).to_string();
+        let mut stream = XmlStream::new(io);
+        let mut authenticated = false;
+
+        loop {
+            stream.send(&stream_header(&domain)).await?;
+            let features = stream.read_features().await?;
+
+            if !secure {
+                if features.child_ns("starttls", NS_TLS).is_none() {
+                    anyhow::bail!(
+                        "XMPP server does not offer STARTTLS; refusing to authenticate in plaintext"
+                    );
+                }
+                stream
+                    .send(&format!("<starttls xmlns='{NS_TLS}'/>"))
+                    .await?;
+                let reply = stream.read_stanza().await?;
+                if reply.name != "proceed" {
+                    anyhow::bail!("XMPP server rejected STARTTLS");
+                }
+                stream = XmlStream::new(self.tls_wrap(stream.io).await?);
+                secure = true;
+                continue;
+            }
+
+            if !authenticated {
+                self.authenticate(&mut stream, &features).await?;
+                authenticated = true;
+                continue;
+            }
+
+            if features.child_ns("bind", NS_BIND).is_none() {
+                anyhow::bail!("XMPP server did not offer resource binding");
+            }
+            stream
+                .send(&format!(
+                    "<iq type='set' id='bind_1'><bind xmlns='{NS_BIND}'><resource>{RESOURCE}</resource></bind></iq>"
+                ))
+                .await?;
+            let reply = stream.read_stanza().await?;
+            if reply.attr("type") != Some("result") {
+                anyhow::bail!("XMPP resource binding failed: {}", stanza_error(&reply));
+            }
+            let bound_jid = reply
+                .child_ns("bind", NS_BIND)
+                .and_then(|bind| bind.child("jid"))
+                .map(|jid| jid.text.trim().to_string())
+                .filter(|jid| !jid.is_empty())
+                .unwrap_or_else(|| format!("{}/{RESOURCE}", self.jid));
+
+            // RFC 3921 session establishment, still required by some servers.
+            if features
+                .child_ns("session", NS_SESSION)
+                .is_some_and(|session| session.child("optional").is_none())
+            {
+                stream
+                    .send(&format!(
+                        "<iq type='set' id='sess_1'><session xmlns='{NS_SESSION}'/></iq>"
+                    ))
+                    .await?;
+                let reply = stream.read_stanza().await?;
+                if reply.attr("type") != Some("result") {
+                    anyhow::bail!(
+                        "XMPP session establishment failed: {}",
+                        stanza_error(&reply)
+                    );
+                }
+            }
+
+            return Ok((stream, bound_jid));
+        }
+    }
+
+    async fn authenticate(
+        &self,
+        stream: &mut XmlStream<BoxedIo>,
+        features: &Element,
+    ) -> anyhow::Result<()> {
+        let mechanisms: Vec<&str> = features
+            .child_ns("mechanisms", NS_SASL)
+            .map(|list| {
+                list.children
+                    .iter()
+                    .filter(|child| child.name == "mechanism")
+                    .map(|child| child.text.trim())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let username = local_part(&self.jid);
+        let b64 = base64::engine::general_purpose::STANDARD;
+
+        if mechanisms.contains(&"SCRAM-SHA-256") {
+            let scram = ScramSha256::new(username, &uuid::Uuid::new_v4().simple().to_string());
+            stream
+                .send(&format!(
+                    "<auth xmlns='{NS_SASL}' mechanism='SCRAM-SHA-256'>{}</auth>",
+                    b64.encode(scram.client_first())
+                ))
+                .await?;
+            let challenge = stream.read_stanza().await?;
+            if challenge.name != "challenge" {
+                anyhow::bail!("XMPP authentication failed: {}", sasl_failure(&challenge));
+            }
+            let server_first = String::from_utf8(b64.decode(challenge.text.trim())?)?;
+            let (client_final, server_signature) =
+                scram.client_final(&self.password, &server_first)?;
+            stream
+                .send(&format!(
+                    "<response xmlns='{NS_SASL}'>{}</response>",
+                    b64.encode(client_final)
+                ))
+                .await?;
+            let outcome = stream.read_stanza().await?;
+            if outcome.name != "success" {
+                anyhow::bail!("XMPP authentication failed: {}", sasl_failure(&outcome));
+            }
+            let server_final = String::from_utf8(b64.decode(outcome.text.trim())?)?;
+            let verifier = server_final
+                .strip_prefix("v=")
+                .map(|v| b64.decode(v))
+                .transpose()?;
+            if verifier.as_deref() != Some(server_signature.as_slice()) {
+                anyhow::bail!("XMPP server signature mismatch during SCRAM authentication");
+            }
+            return Ok(());
+        }
+
+        if mechanisms.contains(&"PLAIN") {
+            let credentials = format!("\0{username}\0{}", self.password);
+            stream
+                .send(&format!(
+                    "<auth xmlns='{NS_SASL}' mechanism='PLAIN'>{}</auth>",
+                    b64.encode(credentials)
+                ))
+                .await?;
+            let outcome = stream.read_stanza().await?;
+            if outcome.name != "success" {
+                anyhow::bail!("XMPP authentication failed: {}", sasl_failure(&outcome));
+            }
+            return Ok(());
+        }
+
+        anyhow::bail!(
+            "XMPP server offers no supported SASL mechanism (offered: {})",
+            mechanisms.join(", ")
+        )
+    }
+
+    async fn run_session(
+        &self,
+        stream: XmlStream<BoxedIo>,
+        tx: mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        let XmlStream { io, mut buf } = stream;
+        let (mut reader, writer) = tokio::io::split(io);
+        *self.writer.lock().await = Some(writer);
+
+        self.write_stanza("<presence/>").await?;
+        for room in &self.rooms {
+            self.write_stanza(&format!(
+                "<presence to='{}'><x xmlns='{NS_MUC}'><history maxstanzas='0'/></x></presence>",
+                escape(&format!("{room}/{}", self.nickname))
+            ))
+            .await?;
+        }
+
+        // Real JIDs of room occupants, keyed by occupant JID (room@service/nick).
+        let mut occupants: HashMap<String, String> = HashMap::new();
+        let mut idle = Duration::ZERO;
+
+        loop {
+            let frame =
+                match tokio::time::timeout(KEEPALIVE_INTERVAL, read_frame(&mut reader, &mut buf))
+                    .await
+                {
+                    Ok(frame) => {
+                        idle = Duration::ZERO;
+                        frame?
+                    }
+                    Err(_) => {
+                        idle += KEEPALIVE_INTERVAL;
+                        if idle >= READ_TIMEOUT {
+                            anyhow::bail!("XMPP read timed out (no data for {READ_TIMEOUT:?})");
+                        }
+                        let seq = MSG_SEQ.fetch_add(1, Ordering::Relaxed);
+                        self.write_stanza(&format!(
+                            "<iq type='get' id='ping_{seq}' to='{}'><ping xmlns='{NS_PING}'/></iq>",
+                            escape(domain_of(&self.jid))
+                        ))
+                        .await?;
+                        continue;
+                    }
+                };
+
+            let stanza = match frame {
+                Frame::Stanza(stanza) => stanza,
+                Frame::StreamOpen(_) => continue,
+                Frame::StreamClose => anyhow::bail!("XMPP stream closed by server"),
+            };
+
+            match stanza.name.as_str() {
+                "message" => {
+                    if let Some(msg) = self.parse_message(&stanza, &occupants) {
+                        if tx.send(msg).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                "presence" => track_occupant(&stanza, &mut occupants),
+                "iq" => self.handle_iq(&stanza).await?,
+                "error" => anyhow::bail!("XMPP stream error: {}", stream_error(&stanza)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Convert an inbound `<message/>` into a channel message, applying the
+    /// allowlist and MUC mention gating.
+    fn parse_message(
+        &self,
+        stanza: &Element,
+        occupants: &HashMap<String, String>,
+    ) -> Option<ChannelMessage> {
+        let from = stanza.attr("from")?;
+        let kind = stanza.attr("type").unwrap_or("normal");
+        let body = stanza.child("body").map(|body| body.text.trim())?;
+
+        if kind == "error" {
+            tracing::warn!("XMPP message error from {from}: {}", stanza_error(stanza));
+            return None;
+        }
+
+        let (sender, reply_target) = if kind == "groupchat" {
+            let room = bare_jid(from);
+            let nick = resource(from)?;
+            if !self.is_room(room) || nick == self.nickname {
+                return None;
+            }
+            // Room history replayed on join is not a new request.
+            if stanza.child_ns("delay", NS_DELAY).is_some() {
+                return None;
+            }
+            let sender = occupants
+                .get(from)
+                .cloned()
+                .unwrap_or_else(|| from.to_string());
+            (sender, room.to_string())
+        } else {
+            let sender = bare_jid(from).to_string();
+            (sender.clone(), sender)
+        };
+
+        if sender.eq_ignore_ascii_case(&self.jid) || !self.is_user_allowed(&sender) {
+            return None;
+        }
+
+        if kind == "groupchat"
+            && self.mention_only
+            && !self.is_group_sender_trigger_enabled(&sender)
+            && !self.mentions_bot(body)
+        {
+            return None;
+        }
+
+        // XEP-0066 out-of-band data: clients send HTTP uploads as the bare URL
+        // in the body, so the attachment reference replaces it.
+        let oob_url = stanza
+            .child_ns("x", NS_OOB)
+            .and_then(|x| x.child("url"))
+            .map(|url| url.text.trim())
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"));
+        let attachments: Vec<Attachment> = oob_url
+            .map(|url| {
+                let filename = url
+                    .split(['?', '#'])
+                    .next()
+                    .and_then(|path| path.rsplit('/').next())
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string);
+                vec![Attachment::remote(url, filename)]
+            })
+            .unwrap_or_default();
+        let content = if oob_url == Some(body) { "" } else { body };
+        if content.is_empty() && attachments.is_empty() {
+            return None;
+        }
+
+        let seq = MSG_SEQ.fetch_add(1, Ordering::Relaxed);
+        Some(ChannelMessage {
+            id: format!("xmpp_{}_{seq}", chrono::Utc::now().timestamp_millis()),
+            sender,
+            reply_target,
+            content: content.to_string(),
+            channel: "xmpp".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            attachments,
+            event: ChannelEvent::Message,
+        })
+    }
+
+    async fn handle_iq(&self, stanza: &Element) -> anyhow::Result<()> {
+        let kind = stanza.attr("type").unwrap_or_default();
+        if kind != "get" && kind != "set" {
+            return Ok(());
+        }
+        let id = escape(stanza.attr("id").unwrap_or_default());
+        let to = stanza
+            .attr("from")
+            .map(|from| format!(" to='{}'", escape(from)))
+            .unwrap_or_default();
+
+        if kind == "get" && stanza.child_ns("ping", NS_PING).is_some() {
+            return self
+                .write_stanza(&format!("<iq type='result' id='{id}'{to}/>"))
+                .await;
+        }
+        self.write_stanza(&format!(
+            "<iq type='error' id='{id}'{to}><error type='cancel'><service-unavailable xmlns='{NS_STANZAS}'/></error></iq>"
+        ))
+        .await
+    }
+
+    async fn write_stanza(&self, xml: &str) -> anyhow::Result<()> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("XMPP not connected"))?;
+        writer.write_all(xml.as_bytes()).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Build a `<message/>` stanza. `replaces` turns it into an XEP-0308
+    /// correction of an earlier message.
+    fn message_stanza(&self, to: &str, id: &str, body: &str, replaces: Option<&str>) -> String {
+        let correction = replaces
+            .map(|original| format!("<replace id='{}' xmlns='{NS_CORRECT}'/>", escape(original)))
+            .unwrap_or_default();
+        format!(
+            "<message to='{}' type='{}' id='{}'><body>{}</body><active xmlns='{NS_CHAT_STATES}'/>{correction}</message>",
+            escape(bare_jid(to)),
+            self.message_type_for(to),
+            escape(id),
+            escape(body)
+        )
+    }
+
+    async fn send_chat_state(&self, recipient: &str, state: &str) -> anyhow::Result<()> {
+        self.write_stanza(&format!(
+            "<message to='{}' type='{}'><{state} xmlns='{NS_CHAT_STATES}'/><no-store xmlns='{NS_HINTS}'/></message>",
+            escape(bare_jid(recipient)),
+            self.message_type_for(recipient)
+        ))
+        .await
+    }
+}
+
+#[async_trait]
+impl Channel for XmppChannel {
+    fn name(&self) -> &str {
+        "xmpp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.write_stanza(&self.message_stanza(
+            &message.recipient,
+            &new_message_id(),
+            &message.content,
+            None,
+        ))
+        .await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!(
+            "XMPP channel connecting to {}:{} as {}...",
+            self.server,
+            self.port,
+            self.jid
+        );
+        let io = self.connect().await?;
+        self.run(io, self.tls == XmppTlsMode::Direct, tx).await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.connect().await.is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.send_chat_state(recipient, "composing").await
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.send_chat_state(recipient, "active").await
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        if !self.supports_draft_updates() {
+            return Ok(None);
+        }
+        let id = new_message_id();
+        self.write_stanza(&self.message_stanza(&message.recipient, &id, &message.content, None))
+            .await?;
+        self.last_draft_edit
+            .lock()
+            .insert(bare_jid(&message.recipient).to_string(), Instant::now());
+        Ok(Some(id))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let key = bare_jid(recipient).to_string();
+        if let Some(last_time) = self.last_draft_edit.lock().get(&key) {
+            let elapsed = u64::try_from(last_time.elapsed().as_millis()).unwrap_or(u64::MAX);
+            if elapsed < self.draft_update_interval_ms {
+                return Ok(None);
+            }
+        }
+
+        // Corrections always reference the original message ID (XEP-0308).
+        self.write_stanza(&self.message_stanza(
+            recipient,
+            &new_message_id(),
+            text,
+            Some(message_id),
+        ))
+        .await?;
+        self.last_draft_edit.lock().insert(key, Instant::now());
+        Ok(None)
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.last_draft_edit.lock().remove(bare_jid(recipient));
+        self.write_stanza(&self.message_stanza(
+            recipient,
+            &new_message_id(),
+            text,
+            Some(message_id),
+        ))
+        .await
+    }
+}
+
+fn new_message_id() -> String {
+    format!("zc-{}", uuid::Uuid::new_v4().simple())
+}
+
+fn stream_header(domain: &str) -> String {
+    format!(
+        "<?xml version='1.0'?><stream:stream to='{}' version='1.0' xml:lang='en' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams'>",
+        escape(domain)
+    )
+}
+
+/// Record the real JID of a MUC occupant from a room presence.
+fn track_occupant(stanza: &Element, occupants: &mut HashMap<String, String>) {
+    let Some(from) = stanza.attr("from") else {
+        return;
+    };
+    if stanza.attr("type") == Some("unavailable") {
+        occupants.remove(from);
+        return;
+    }
+    let real_jid = stanza
+        .child_ns("x", NS_MUC_USER)
+        .and_then(|x| x.child("item"))
+        .and_then(|item| item.attr("jid"));
+    if let Some(real_jid) = real_jid {
+        occupants.insert(from.to_string(), bare_jid(real_jid).to_string());
+    } else if stanza.attr("type") == Some("error") {
+        tracing::warn!("XMPP presence error from {from}: {}", stanza_error(stanza));
+    }
+}
+
+fn sasl_failure(stanza: &Element) -> String {
+    stanza
+        .children
+        .iter()
+        .find(|child| child.name != "text")
+        .map_or_else(|| stanza.name.clone(), |child| child.name.clone())
+}
+
+fn stanza_error(stanza: &Element) -> String {
+    stanza
+        .child("error")
+        .and_then(|error| error.children.iter().find(|child| child.name != "text"))
+        .map_or_else(|| "unknown error".to_string(), |child| child.name.clone())
+}
+
+fn stream_error(stanza: &Element) -> String {
+    stanza
+        .children
+        .iter()
+        .find(|child| child.name != "text")
+        .map_or_else(|| "unknown".to_string(), |child| child.name.clone())
+}
+
+// ── JIDs ────────────────────────────────────────────────────────
+
+fn bare_jid(jid: &str) -> &str {
+    jid.split_once('/').map_or(jid, |(bare, _)| bare)
+}
+
+fn resource(jid: &str) -> Option<&str> {
+    jid.split_once('/')
+        .map(|(_, resource)| resource)
+        .filter(|resource| !resource.is_empty())
+}
+
+fn local_part(jid: &str) -> &str {
+    let bare = bare_jid(jid);
+    bare.split_once('@').map_or("", |(local, _)| local)
+}
+
+fn domain_of(jid: &str) -> &str {
+    let bare = bare_jid(jid);
+    bare.split_once('@').map_or(bare, |(_, domain)| domain)
+}
+
+// ── XML stream ──────────────────────────────────────────────────
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Minimal XML element tree for a single stanza.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Element {
+    /// Local name (namespace prefix stripped).
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn parse(xml: &str) -> anyhow::Result<Self> {
+        use quick_xml::events::{BytesStart, Event};
+        use quick_xml::Reader;
+
+        fn open(start: &BytesStart<'_>) -> anyhow::Result<Element> {
+            let mut attrs = Vec::new();
+            for attr in start.attributes() {
+                let attr = attr?;
+                attrs.push((
+                    String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                    attr.unescape_value()?.into_owned(),
+                ));
+            }
+            Ok(Element {
+                name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+                attrs,
+                ..Element::default()
+            })
+        }
+
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<Element> = Vec::new();
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => stack.push(open(&start)?),
+                Event::Empty(start) => {
+                    let element = open(&start)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(current) = stack.last_mut() {
+                        current.text.push_str(&text.unescape()?);
+                    }
+                }
+                Event::End(_) => {
+                    let element = stack.pop().context("unbalanced XML end tag")?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                // An unclosed root (the stream header) ends at EOF.
+                Event::Eof => {
+                    let mut element = stack.pop().context("empty XML fragment")?;
+                    while let Some(mut parent) = stack.pop() {
+                        parent.children.push(element);
+                        element = parent;
+                    }
+                    return Ok(element);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn child_ns(&self, name: &str, ns: &str) -> Option<&Element> {
+        self.children
+            .iter()
+            .find(|child| child.name == name && child.attr("xmlns") == Some(ns))
+    }
+}
+
+#[derive(Debug)]
+enum Frame {
+    /// `<stream:stream ...>` opening tag.
+    StreamOpen(Element),
+    /// A complete top-level element.
+    Stanza(Element),
+    /// `</stream:stream>`.
+    StreamClose,
+}
+
+/// Cut the next complete frame from the front of `buf`, if one is buffered.
+fn take_frame(buf: &mut Vec<u8>) -> anyhow::Result<Option<Frame>> {
+    'frame: loop {
+        let Some(start) = buf.iter().position(|b| !b.is_ascii_whitespace()) else {
+            // Whitespace keepalives.
+            buf.clear();
+            return Ok(None);
+        };
+        if buf[start] != b'<' {
+            anyhow::bail!("unexpected character data at XMPP stream level");
+        }
+
+        let mut depth = 0usize;
+        let mut pos = start;
+        loop {
+            let Some(open) = buf[pos..].iter().position(|b| *b == b'<').map(|i| pos + i) else {
+                return Ok(None);
+            };
+            let Some(close) = tag_end(buf, open) else {
+                return Ok(None);
+            };
+            let tag = &buf[open..=close];
+
+            if tag.starts_with(b"<?") {
+                if depth == 0 {
+                    buf.drain(..=close);
+                    continue 'frame;
+                }
+            } else if tag.starts_with(b"</") {
+                if depth == 0 {
+                    buf.drain(..=close);
+                    return Ok(Some(Frame::StreamClose));
+                }
+                depth -= 1;
+            } else if !tag.ends_with(b"/>") {
+                if depth == 0 && tag.starts_with(b"<stream:stream") {
+                    let header = std::str::from_utf8(tag)?.to_string();
+                    buf.drain(..=close);
+                    return Ok(Some(Frame::StreamOpen(Element::parse(&header)?)));
+                }
+                depth += 1;
+            }
+
+            pos = close + 1;
+            if depth == 0 {
+                let xml = std::str::from_utf8(&buf[start..pos])?.to_string();
+                buf.drain(..pos);
+                return Ok(Some(Frame::Stanza(Element::parse(&xml)?)));
+            }
+        }
+    }
+}
+
+/// Index of the `>` closing the tag that starts at `open`, skipping quoted
+/// attribute values.
+fn tag_end(buf: &[u8], open: usize) -> Option<usize> {
+    let mut quote: Option<u8> = None;
+    for (index, byte) in buf.iter().enumerate().skip(open + 1) {
+        match (quote, *byte) {
+            (Some(q), b) if b == q => quote = None,
+            (None, b'"' | b'\'') => quote = Some(*byte),
+            (None, b'>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+) -> anyhow::Result<Frame> {
+    loop {
+        if let Some(frame) = take_frame(buf)? {
+            return Ok(frame);
+        }
+        if buf.len() > MAX_STANZA_BYTES {
+            anyhow::bail!("XMPP stanza exceeds {MAX_STANZA_BYTES} bytes");
+        }
+        if reader.read_buf(buf).await? == 0 {
+            anyhow::bail!("XMPP connection closed by server");
+        }
+    }
+}
+
+/// XML stream used during negotiation, before the session splits it.
+struct XmlStream<S> {
+    io: S,
+    buf: Vec<u8>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> XmlStream<S> {
+    fn new(io: S) -> Self {
+        Self {
+            io,
+            buf: Vec::new(),
+        }
+    }
+
+    async fn send(&mut self, xml: &str) -> anyhow::Result<()> {
+        self.io.write_all(xml.as_bytes()).await?;
+        self.io.flush().await?;
+        Ok(())
+    }
+
+    /// Next top-level stanza, skipping stream headers.
+    async fn read_stanza(&mut self) -> anyhow::Result<Element> {
+        loop {
+            match read_frame(&mut self.io, &mut self.buf).await? {
+                Frame::StreamOpen(_) => {}
+                Frame::Stanza(stanza) if stanza.name == "error" => {
+                    anyhow::bail!("XMPP stream error: {}", stream_error(&stanza));
+                }
+                Frame::Stanza(stanza) => return Ok(stanza),
+                Frame::StreamClose => anyhow::bail!("XMPP stream closed by server"),
+            }
+        }
+    }
+
+    async fn read_features(&mut self) -> anyhow::Result<Element> {
+        let stanza = self.read_stanza().await?;
+        if stanza.name != "features" {
+            anyhow::bail!("expected XMPP stream features, got <{}>", stanza.name);
+        }
+        Ok(stanza)
+    }
+}
+
+// ── SASL SCRAM-SHA-256 (RFC 5802 / RFC 7677) ────────────────────
+
+type HmacSha256 = Hmac<Sha256>;
+
+struct ScramSha256 {
+    nonce: String,
+    client_first_bare: String,
+}
+
+impl ScramSha256 {
+    fn new(username: &str, nonce: &str) -> Self {
+        let username = username.replace('=', "=3D").replace(',', "=2C");
+        Self {
+            nonce: nonce.to_string(),
+            client_first_bare: format!("n={username},r={nonce}"),
+        }
+    }
+
+    fn client_first(&self) -> String {
+        format!("n,,{}", self.client_first_bare)
+    }
+
+    /// Client-final message and the expected server signature.
+    fn client_final(
+        &self,
+        password: &str,
+        server_first: &str,
+    ) -> anyhow::Result<(String, Vec<u8>)> {
+        let field = |key: &str| {
+            server_first
+                .split(',')
+                .find_map(|part| part.strip_prefix(key).and_then(|v| v.strip_prefix('=')))
+        };
+        let nonce = field("r").context("SCRAM challenge without nonce")?;
+        if !nonce.starts_with(&self.nonce) || nonce.len() == self.nonce.len() {
+            anyhow::bail!("SCRAM server nonce does not extend the client nonce");
+        }
+        let salt = base64::engine::general_purpose::STANDARD
+            .decode(field("s").context("SCRAM challenge without salt")?)?;
+        let iterations: u32 = field("i")
+            .context("SCRAM challenge without iteration count")?
+            .parse()?;
+        if iterations == 0 {
+            anyhow::bail!("SCRAM iteration count must be positive");
+        }
+        if iterations > MAX_SCRAM_ITERATIONS {
+            anyhow::bail!(
+                "SCRAM iteration count {iterations} exceeds the limit of {MAX_SCRAM_ITERATIONS}"
+            );
+        }
+
+        let salted = pbkdf2_sha256(password.as_bytes(), &salt, iterations);
+        let client_key = hmac_sha256(&salted, b"Client Key");
+        let stored_key = Sha256::digest(client_key);
+        let without_proof = format!("c=biws,r={nonce}");
+        let auth_message = format!("{},{server_first},{without_proof}", self.client_first_bare);
+        let client_signature = hmac_sha256(&stored_key, auth_message.as_bytes());
+        let proof: Vec<u8> = client_key
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(key, sig)| key ^ sig)
+            .collect();
+        let server_key = hmac_sha256(&salted, b"Server Key");
+        let server_signature = hmac_sha256(&server_key, auth_message.as_bytes());
+
+        Ok((
+            format!(
+                "{without_proof},p={}",
+                base64::engine::general_purpose::STANDARD.encode(proof)
+            ),
+            server_signature.to_vec(),
+        ))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut block = salt.to_vec();
+    block.extend_from_slice(&1u32.to_be_bytes());
+    let mut u = hmac_sha256(password, &block);
+    let mut out = u;
+    for _ in 1..iterations {
+        u = hmac_sha256(password, &u);
+        for (acc, byte) in out.iter_mut().zip(u.iter()) {
+            *acc ^= byte;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::{TcpListener, TcpStream};
+
+    fn make_config() -> XmppConfig {
+        XmppConfig {
+            jid: "bot@localhost".into(),
+            password: "secret".into(),
+            server: Some("127.0.0.1".into()),
+            port: None,
+            tls: XmppTlsMode::Starttls,
+            verify_tls: None,
+            rooms: vec!["room@conference.localhost".into()],
+            nickname: Some("ZeroBot".into()),
+            allowed_users: vec!["alice@localhost".into()],
+            group_reply: Some(crate::config::GroupReplyConfig {
+                mode: Some(crate::config::GroupReplyMode::MentionOnly),
+                allowed_sender_ids: Vec::new(),
+            }),
+            stream_mode: StreamMode::Partial,
+            draft_update_interval_ms: 0,
+        }
+    }
+
+    #[test]
+    fn new_applies_defaults() {
+        let mut config = make_config();
+        config.server = None;
+        config.nickname = None;
+        config.tls = XmppTlsMode::Direct;
+        let ch = XmppChannel::new(config);
+        assert_eq!(ch.server, "localhost");
+        assert_eq!(ch.port, 5223);
+        assert_eq!(ch.nickname, "bot");
+        assert!(ch.mention_only);
+        assert!(ch.verify_tls);
+    }
+
+    #[test]
+    fn xmpp_config_minimal_toml() {
+        let config: XmppConfig =
+            toml::from_str("jid = \"bot@example.com\"\npassword = \"pw\"").unwrap();
+        assert_eq!(config.tls, XmppTlsMode::Starttls);
+        assert!(config.rooms.is_empty());
+        assert_eq!(config.stream_mode, StreamMode::Off);
+        assert_eq!(
+            config.effective_group_reply_mode(),
+            crate::config::GroupReplyMode::AllMessages
+        );
+    }
+
+    #[test]
+    fn jid_helpers() {
+        assert_eq!(bare_jid("a@b.c/res/x"), "a@b.c");
+        assert_eq!(resource("room@muc.b.c/Nick Name"), Some("Nick Name"));
+        assert_eq!(resource("a@b.c"), None);
+        assert_eq!(local_part("a@b.c/r"), "a");
+        assert_eq!(domain_of("a@b.c/r"), "b.c");
+        assert_eq!(domain_of("b.c"), "b.c");
+    }
+
+    #[test]
+    fn mention_detection_respects_word_boundaries() {
+        let ch = XmppChannel::new(make_config());
+        assert!(ch.mentions_bot("zerobot: status?"));
+        assert!(ch.mentions_bot("hey @ZeroBot"));
+        assert!(!ch.mentions_bot("ZeroBots are cool"));
+        assert!(!ch.mentions_bot("nothing here"));
+    }
+
+    #[test]
+    fn take_frame_handles_partial_data_and_quoted_brackets() {
+        let mut buf = b"<?xml version='1.0'?><stream:stream from='x' xmlns:stream='s'> <message body-attr='a>b'><bo".to_vec();
+        assert!(matches!(
+            take_frame(&mut buf).unwrap(),
+            Some(Frame::StreamOpen(_))
+        ));
+        assert!(take_frame(&mut buf).unwrap().is_none());
+
+        buf.extend_from_slice(b"dy>1 &lt; 2</body><x/></message>  </stream:stream>");
+        let Some(Frame::Stanza(message)) = take_frame(&mut buf).unwrap() else {
+            panic!("expected stanza");
+        };
+        assert_eq!(message.attr("body-attr"), Some("a>b"));
+        assert_eq!(message.child("body").unwrap().text, "1 < 2");
+        assert!(matches!(
+            take_frame(&mut buf).unwrap(),
+            Some(Frame::StreamClose)
+        ));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn scram_sha256_matches_rfc7677_vector() {
+        let scram = ScramSha256::new("user", "rOprNGfwEbeRWgbNEkqO");
+        assert_eq!(scram.client_first(), "n,,n=user,r=rOprNGfwEbeRWgbNEkqO");
+
+        let server_first = "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+        let (client_final, server_signature) = scram.client_final("pencil", server_first).unwrap();
+        assert_eq!(
+            client_final,
+            "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+        );
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD.encode(server_signature),
+            "6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4="
+        );
+    }
+
+    #[test]
+    fn scram_rejects_foreign_nonce() {
+        let scram = ScramSha256::new("user", "abc");
+        assert!(scram.client_final("pw", "r=xyz123,s=AAAA,i=1").is_err());
+    }
+
+    #[test]
+    fn scram_rejects_excessive_iteration_count() {
+        let scram = ScramSha256::new("user", "abc");
+        let err = scram
+            .client_final("pw", "r=abc123,s=AAAA,i=4294967295")
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn message_stanza_escapes_and_marks_corrections() {
+        let ch = XmppChannel::new(make_config());
+        let stanza = ch.message_stanza(
+            "room@conference.localhost",
+            "id2",
+            "a < b & 'c'",
+            Some("id1"),
+        );
+        assert!(stanza
+            .starts_with("<message to='room@conference.localhost' type='groupchat' id='id2'>"));
+        assert!(stanza.contains("<body>a &lt; b &amp; &apos;c&apos;</body>"));
+        assert!(stanza.contains(&format!("<replace id='id1' xmlns='{NS_CORRECT}'/>")));
+
+        let direct = ch.message_stanza("alice@localhost/phone", "id3", "hi", None);
+        assert!(direct.starts_with("<message to='alice@localhost' type='chat' id='id3'>"));
+        assert!(!direct.contains("replace"));
+    }
+
+    #[tokio::test]
+    async fn send_without_connection_fails() {
+        let ch = XmppChannel::new(make_config());
+        let err = ch
+            .send(&SendMessage::new("hi", "alice@localhost"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not connected"));
+    }
+
+    // ── Stand-in server ─────────────────────────────────────
+
+    struct StandIn {
+        socket: TcpStream,
+        buf: Vec<u8>,
+    }
+
+    impl StandIn {
+        async fn send(&mut self, xml: &str) {
+            self.socket.write_all(xml.as_bytes()).await.unwrap();
+        }
+
+        async fn next(&mut self) -> Frame {
+            tokio::time::timeout(
+                Duration::from_secs(5),
+                read_frame(&mut self.socket, &mut self.buf),
+            )
+            .await
+            .expect("stand-in server timed out waiting for client")
+            .unwrap()
+        }
+
+        async fn stanza(&mut self) -> Element {
+            match self.next().await {
+                Frame::Stanza(stanza) => stanza,
+                other => panic!("expected stanza, got {other:?}"),
+            }
+        }
+
+        async fn open_stream(&mut self, features: &str) {
+            assert!(matches!(self.next().await, Frame::StreamOpen(_)));
+            self.send(&format!(
+                "<?xml version='1.0'?><stream:stream from='localhost' id='s1' version='1.0' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams'><stream:features>{features}</stream:features>"
+            ))
+            .await;
+        }
+    }
+
+    async fn start_client(
+        ch: Arc<XmppChannel>,
+    ) -> (
+        StandIn,
+        mpsc::Receiver<ChannelMessage>,
+        tokio::task::JoinHandle<anyhow::Result<()>>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel(8);
+        let client = tokio::spawn(async move {
+            let tcp = TcpStream::connect(addr).await?;
+            // The stand-in speaks plain TCP; treat it as an already-secured link.
+            ch.run(Box::new(tcp), true, tx).await
+        });
+        let (socket, _) = listener.accept().await.unwrap();
+        (
+            StandIn {
+                socket,
+                buf: Vec::new(),
+            },
+            rx,
+            client,
+        )
+    }
+
+    async fn recv(rx: &mut mpsc::Receiver<ChannelMessage>) -> ChannelMessage {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("timed out waiting for channel message")
+            .expect("channel closed")
+    }
+
+    #[tokio::test]
+    async fn session_with_stand_in_server() {
+        let ch = Arc::new(XmppChannel::new(make_config()));
+        let (mut server, mut rx, client) = start_client(Arc::clone(&ch)).await;
+
+        // SASL PLAIN
+        server
+            .open_stream(&format!(
+                "<mechanisms xmlns='{NS_SASL}'><mechanism>PLAIN</mechanism></mechanisms>"
+            ))
+            .await;
+        let auth = server.stanza().await;
+        assert_eq!(auth.attr("mechanism"), Some("PLAIN"));
+        let credentials = base64::engine::general_purpose::STANDARD
+            .decode(auth.text.trim())
+            .unwrap();
+        assert_eq!(credentials, b"\0bot\0secret");
+        server.send(&format!("<success xmlns='{NS_SASL}'/>")).await;
+
+        // Resource binding
+        server
+            .open_stream(&format!("<bind xmlns='{NS_BIND}'/>"))
+            .await;
+        let bind = server.stanza().await;
+        assert_eq!(bind.attr("type"), Some("set"));
+        assert_eq!(
+            bind.child_ns("bind", NS_BIND)
+                .and_then(|b| b.child("resource"))
+                .map(|r| r.text.as_str()),
+            Some(RESOURCE)
+        );
+        server
+            .send(&format!(
+                "<iq type='result' id='bind_1'><bind xmlns='{NS_BIND}'><jid>bot@localhost/zeroclaw</jid></bind></iq>"
+            ))
+            .await;
+
+        // Initial presence and MUC join
+        let presence = server.stanza().await;
+        assert_eq!(presence.name, "presence");
+        assert_eq!(presence.attr("to"), None);
+        let join = server.stanza().await;
+        assert_eq!(join.attr("to"), Some("room@conference.localhost/ZeroBot"));
+        assert!(join.child_ns("x", NS_MUC).is_some());
+
+        // Server ping is answered
+        server
+            .send("<iq type='get' id='p1' from='localhost'><ping xmlns='urn:xmpp:ping'/></iq>")
+            .await;
+        let pong = server.stanza().await;
+        assert_eq!(pong.attr("type"), Some("result"));
+        assert_eq!(pong.attr("id"), Some("p1"));
+
+        // Occupant presence reveals alice's real JID
+        server
+            .send(&format!(
+                "<presence from='room@conference.localhost/Alice'><x xmlns='{NS_MUC_USER}'><item jid='alice@localhost/laptop' role='participant'/></x></presence>"
+            ))
+            .await;
+        // Not addressed to the bot: gated
+        server
+            .send("<message from='room@conference.localhost/Alice' type='groupchat' id='m1'><body>lunch?</body></message>")
+            .await;
+        // Replayed history: ignored
+        server
+            .send(&format!(
+                "<message from='room@conference.localhost/Alice' type='groupchat'><body>ZeroBot: old</body><delay xmlns='{NS_DELAY}' stamp='2020-01-01T00:00:00Z'/></message>"
+            ))
+            .await;
+        // Echo of our own room message: ignored
+        server
+            .send("<message from='room@conference.localhost/ZeroBot' type='groupchat'><body>ZeroBot: echo</body></message>")
+            .await;
+        server
+            .send("<message from='room@conference.localhost/Alice' type='groupchat' id='m2'><body>ZeroBot: deploy status?</body></message>")
+            .await;
+
+        let msg = recv(&mut rx).await;
+        assert_eq!(msg.channel, "xmpp");
+        assert_eq!(msg.sender, "alice@localhost");
+        assert_eq!(msg.reply_target, "room@conference.localhost");
+        assert_eq!(msg.content, "ZeroBot: deploy status?");
+
+        // Direct chat: non-allowlisted sender dropped, alice accepted
+        server
+            .send("<message from='mallory@localhost/x' type='chat'><body>hi</body></message>")
+            .await;
+        server
+            .send("<message from='alice@localhost/phone' type='chat'><body>hello bot</body></message>")
+            .await;
+        let msg = recv(&mut rx).await;
+        assert_eq!(msg.sender, "alice@localhost");
+        assert_eq!(msg.reply_target, "alice@localhost");
+        assert_eq!(msg.content, "hello bot");
+
+        // Outbound: room reply, chat state and draft correction
+        ch.send(&SendMessage::new("on it", "room@conference.localhost"))
+            .await
+            .unwrap();
+        let reply = server.stanza().await;
+        assert_eq!(reply.attr("type"), Some("groupchat"));
+        assert_eq!(reply.child("body").unwrap().text, "on it");
+
+        ch.start_typing("alice@localhost").await.unwrap();
+        let typing = server.stanza().await;
+        assert_eq!(typing.attr("type"), Some("chat"));
+        assert!(typing.child_ns("composing", NS_CHAT_STATES).is_some());
+        assert!(typing.child("body").is_none());
+
+        let draft_id = ch
+            .send_draft(&SendMessage::new("Thinking", "alice@localhost"))
+            .await
+            .unwrap()
+            .expect("draft id");
+        let draft = server.stanza().await;
+        assert_eq!(draft.attr("id"), Some(draft_id.as_str()));
+
+        ch.update_draft("alice@localhost", &draft_id, "Thinking harder")
+            .await
+            .unwrap();
+        let update = server.stanza().await;
+        assert_eq!(
+            update
+                .child_ns("replace", NS_CORRECT)
+                .and_then(|r| r.attr("id")),
+            Some(draft_id.as_str())
+        );
+
+        ch.finalize_draft("alice@localhost", &draft_id, "Done")
+            .await
+            .unwrap();
+        let fin = server.stanza().await;
+        assert_eq!(fin.child("body").unwrap().text, "Done");
+        assert_eq!(
+            fin.child_ns("replace", NS_CORRECT)
+                .and_then(|r| r.attr("id")),
+            Some(draft_id.as_str())
+        );
+        assert_ne!(fin.attr("id"), Some(draft_id.as_str()));
+
+        // Server closes the stream: listen ends with an error and disconnects.
+        server.send("</stream:stream>").await;
+        let result = client.await.unwrap();
+        assert!(result.unwrap_err().to_string().contains("closed"));
+        assert!(ch
+            .send(&SendMessage::new("x", "alice@localhost"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_plaintext_without_starttls() {
+        let ch = XmppChannel::new(make_config());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut server = StandIn {
+                socket,
+                buf: Vec::new(),
+            };
+            server
+                .open_stream(&format!(
+                    "<mechanisms xmlns='{NS_SASL}'><mechanism>PLAIN</mechanism></mechanisms>"
+                ))
+                .await;
+            server
+        });
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let Err(err) = ch.negotiate(Box::new(tcp), false).await else {
+            panic!("negotiation must fail without STARTTLS");
+        };
+        assert!(err.to_string().contains("STARTTLS"));
+        drop(server.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn authentication_failure_is_reported() {
+        let ch = Arc::new(XmppChannel::new(make_config()));
+        let (mut server, _rx, client) = start_client(ch).await;
+        server
+            .open_stream(&format!(
+                "<mechanisms xmlns='{NS_SASL}'><mechanism>PLAIN</mechanism></mechanisms>"
+            ))
+            .await;
+        let _auth = server.stanza().await;
+        server
+            .send(&format!(
+                "<failure xmlns='{NS_SASL}'><not-authorized/></failure>"
+            ))
+            .await;
+        let err = client.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("not-authorized"), "{err}");
+    }
+}
@@ -0,0 +1,974 @@
+use super::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
+use crate::config::ZulipConfig;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Zulip rejects message bodies over 10 000 bytes; chunks stay below it in bytes.
+const ZULIP_MAX_MESSAGE_BYTES: usize = 9_000;
+/// Long-poll budget. Zulip sends a heartbeat event roughly every 50 seconds.
+const ZULIP_POLL_TIMEOUT_SECS: u64 = 120;
+const ZULIP_CONNECT_TIMEOUT_SECS: u64 = 10;
+/// Upper bound for the retry backoff after failed register/poll requests.
+const ZULIP_MAX_BACKOFF_SECS: u64 = 60;
+/// Topic used when a stream message is sent without a thread or subject.
+const ZULIP_DEFAULT_TOPIC: &str = "general chat";
+
+/// Reply-target prefix for stream messages (`stream:<stream name>`).
+const STREAM_TARGET_PREFIX: &str = "stream:";
+/// Reply-target prefix for direct messages (`dm:<email>[,<email>...]`).
+const DM_TARGET_PREFIX: &str = "dm:";
+
+/// Zulip channel — receives through the real-time events queue
+/// (`POST /api/v1/register` + long-polling `GET /api/v1/events`) and replies via
+/// `POST /api/v1/messages`.
+///
+/// Stream topics are surfaced as `thread_ts`, so every topic gets its own
+/// conversation history and replies land in the topic they came from.
+pub struct ZulipChannel {
+    site: String,
+    email: String,
+    api_key: String,
+    streams: Vec<String>,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    group_reply_allowed_sender_ids: Vec<String>,
+}
+
+/// The bot's own account, resolved at startup.
+#[derive(Debug, Clone, Default)]
+struct BotIdentity {
+    user_id: i64,
+    full_name: String,
+}
+
+/// An event queue registered with the server.
+#[derive(Debug, Clone)]
+struct EventQueue {
+    id: String,
+    last_event_id: i64,
+}
+
+impl ZulipChannel {
+    pub fn new(config: ZulipConfig) -> Self {
+        let mention_only = config.effective_group_reply_mode().requires_mention();
+        let group_reply_allowed_sender_ids = config.group_reply_allowed_sender_ids();
+        Self {
+            site: config.site.trim().trim_end_matches('/').to_string(),
+            email: config.email.trim().to_string(),
+            api_key: config.api_key,
+            streams: config
+                .streams
+                .iter()
+                .map(|stream| stream.trim().to_string())
+                .filter(|stream| !stream.is_empty())
+                .collect(),
+            allowed_users: config.allowed_users,
+            mention_only,
+            group_reply_allowed_sender_ids,
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "channel.zulip",
+            ZULIP_POLL_TIMEOUT_SECS,
+            ZULIP_CONNECT_TIMEOUT_SECS,
+        )
+    }
+
+    fn api_url(&self, endpoint: &str) -> String {
+        format!("{}/api/v1/{endpoint}", self.site)
+    }
+
+    /// Check a sender email against the allowlist.
+    /// Empty list means deny everyone; `"*"` allows everyone.
+    fn is_user_allowed(&self, email: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(email))
+    }
+
+    fn is_group_sender_trigger_enabled(&self, email: &str) -> bool {
+        self.group_reply_allowed_sender_ids
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(email))
+    }
+
+    fn is_stream_watched(&self, stream: &str) -> bool {
+        self.streams.is_empty()
+            || self
+                .streams
+                .iter()
+                .any(|entry| entry.eq_ignore_ascii_case(stream))
+    }
+
+    /// Send a request and decode Zulip's `{"result": ...}` envelope.
+    ///
+    /// Error responses surface Zulip's `code` (e.g. `BAD_EVENT_QUEUE_ID`) in the
+    /// message so callers can branch on it.
+    async fn call(
+        &self,
+        request: reqwest::RequestBuilder,
+        what: &str,
+    ) -> Result<serde_json::Value> {
+        let resp = request
+            .basic_auth(&self.email, Some(&self.api_key))
+            .send()
+            .await
+            .with_context(|| format!("Zulip {what} request failed"))?;
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+        let parsed: Option<serde_json::Value> = serde_json::from_str(&body).ok();
+        let succeeded = parsed
+            .as_ref()
+            .and_then(|value| value.get("result"))
+            .and_then(serde_json::Value::as_str)
+            == Some("success");
+        match parsed {
+            Some(value) if status.is_success() && succeeded => Ok(value),
+            Some(value) => {
+                let code = value
+                    .get("code")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("UNKNOWN");
+                let msg = value
+                    .get("msg")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default();
+                let sanitized = crate::providers::sanitize_api_error(msg);
+                bail!("Zulip {what} failed ({status}, {code}): {sanitized}");
+            }
+            None => {
+                let sanitized = crate::providers::sanitize_api_error(&body);
+                bail!("Zulip {what} failed ({status}): {sanitized}");
+            }
+        }
+    }
+
+    async fn fetch_identity(&self) -> Result<BotIdentity> {
+        let me = self
+            .call(self.http_client().get(self.api_url("users/me")), "users/me")
+            .await?;
+        Ok(BotIdentity {
+            user_id: me
+                .get("user_id")
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or_default(),
+            full_name: me
+                .get("full_name")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    async fn register_queue(&self) -> Result<EventQueue> {
+        let resp = self
+            .call(
+                self.http_client().post(self.api_url("register")).form(&[
+                    ("event_types", r#"["message"]"#),
+                    ("apply_markdown", "false"),
+                    ("client_gravatar", "true"),
+                ]),
+                "register",
+            )
+            .await?;
+        let id = resp
+            .get("queue_id")
+            .and_then(serde_json::Value::as_str)
+            .filter(|id| !id.is_empty())
+            .context("Zulip register response is missing queue_id")?
+            .to_string();
+        let last_event_id = resp
+            .get("last_event_id")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(-1);
+        Ok(EventQueue { id, last_event_id })
+    }
+
+    /// Long-poll the queue once and return the raw events.
+    async fn poll_events(&self, queue: &EventQueue) -> Result<Vec<serde_json::Value>> {
+        let resp = self
+            .call(
+                self.http_client().get(self.api_url("events")).query(&[
+                    ("queue_id", queue.id.clone()),
+                    ("last_event_id", queue.last_event_id.to_string()),
+                ]),
+                "events",
+            )
+            .await?;
+        Ok(resp
+            .get("events")
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Convert a `message` event into a [`ChannelMessage`].
+    ///
+    /// Returns `None` for heartbeats, the bot's own messages, unwatched streams,
+    /// unauthorized senders and stream messages that fail mention gating.
+    fn parse_event(&self, event: &serde_json::Value, bot: &BotIdentity) -> Option<ChannelMessage> {
+        if event.get("type").and_then(serde_json::Value::as_str) != Some("message") {
+            return None;
+        }
+        let message = event.get("message")?;
+        let id = message.get("id").and_then(serde_json::Value::as_i64)?;
+        let sender_id = message
+            .get("sender_id")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or_default();
+        let sender_email = message
+            .get("sender_email")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        if (bot.user_id != 0 && sender_id == bot.user_id)
+            || sender_email.eq_ignore_ascii_case(&self.email)
+        {
+            return None;
+        }
+        if !self.is_user_allowed(sender_email) {
+            tracing::warn!(
+                "Zulip: ignoring message from unauthorized user: {sender_email}. \
+                 Add to channels_config.zulip.allowed_users in config.toml, \
+                 or run `zeroclaw onboard --channels-only`."
+            );
+            return None;
+        }
+
+        let raw = message
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        let timestamp = message
+            .get("timestamp")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            });
+
+        let (reply_target, thread_ts, content) =
+            match message.get("type").and_then(serde_json::Value::as_str) {
+                Some("stream") => {
+                    let stream = message
+                        .get("display_recipient")
+                        .and_then(serde_json::Value::as_str)?;
+                    if !self.is_stream_watched(stream) {
+                        return None;
+                    }
+                    let topic = message
+                        .get("subject")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or_default();
+                    let flagged = event
+                        .get("flags")
+                        .and_then(serde_json::Value::as_array)
+                        .is_some_and(|flags| {
+                            flags.iter().any(|flag| flag.as_str() == Some("mentioned"))
+                        });
+                    let (mentioned, stripped) = strip_bot_mentions(raw, bot);
+                    if self.mention_only
+                        && !(flagged || mentioned)
+                        && !self.is_group_sender_trigger_enabled(sender_email)
+                    {
+                        return None;
+                    }
+                    (
+                        format!("{STREAM_TARGET_PREFIX}{stream}"),
+                        Some(if topic.is_empty() {
+                            ZULIP_DEFAULT_TOPIC.to_string()
+                        } else {
+                            topic.to_string()
+                        }),
+                        stripped,
+                    )
+                }
+                Some("private") => {
+                    // Group DMs reply to everyone in the conversation except the bot.
+                    let recipients: Vec<String> = message
+                        .get("display_recipient")
+                        .and_then(serde_json::Value::as_array)
+                        .map(|people| {
+                            people
+                                .iter()
+                                .filter_map(|person| {
+                                    person.get("email").and_then(serde_json::Value::as_str)
+                                })
+                                .filter(|email| !email.eq_ignore_ascii_case(&self.email))
+                                .map(str::to_string)
+                                .collect()
+                        })
+                        .filter(|people: &Vec<String>| !people.is_empty())
+                        .unwrap_or_else(|| vec![sender_email.to_string()]);
+                    (
+                        format!("{DM_TARGET_PREFIX}{}", recipients.join(",")),
+                        None,
+                        strip_bot_mentions(raw, bot).1,
+                    )
+                }
+                _ => return None,
+            };
+
+        if content.is_empty() {
+            return None;
+        }
+
+        Some(ChannelMessage {
+            id: format!("zulip_{id}"),
+            sender: sender_email.to_string(),
+            reply_target,
+            content,
+            channel: "zulip".to_string(),
+            timestamp,
+            thread_ts,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
+        })
+    }
+
+    /// Register a queue and pump its events into `tx` until the queue expires.
+    ///
+    /// Returns `Ok(true)` when the queue must be re-registered and `Ok(false)`
+    /// when the receiver has gone away.
+    async fn run_queue(
+        &self,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+        bot: &BotIdentity,
+    ) -> Result<bool> {
+        let mut queue = self.register_queue().await?;
+        tracing::info!("Zulip: event queue {} registered", queue.id);
+        loop {
+            let events = match self.poll_events(&queue).await {
+                Ok(events) => events,
+                Err(e) if e.to_string().contains("BAD_EVENT_QUEUE_ID") => {
+                    tracing::info!("Zulip: event queue expired, registering a new one");
+                    return Ok(true);
+                }
+                Err(e) => return Err(e),
+            };
+            for event in &events {
+                if let Some(id) = event.get("id").and_then(serde_json::Value::as_i64) {
+                    queue.last_event_id = queue.last_event_id.max(id);
+                }
+                if let Some(msg) = self.parse_event(event, bot) {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send_chunk(&self, target: &ZulipTarget<'_>, content: &str) -> Result<()> {
+        let form: Vec<(&str, String)> = match target {
+            ZulipTarget::Stream { stream, topic } => vec![
+                ("type", "stream".to_string()),
+                ("to", (*stream).to_string()),
+                ("topic", (*topic).to_string()),
+                ("content", content.to_string()),
+            ],
+            ZulipTarget::Direct(emails) => vec![
+                ("type", "private".to_string()),
+                ("to", serde_json::to_string(emails)?),
+                ("content", content.to_string()),
+            ],
+        };
+        self.call(
+            self.http_client()
+                .post(self.api_url("messages"))
+                .form(&form),
+            "send message",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn reaction_request(
+        &self,
+        method: reqwest::Method,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<()> {
+        let Some(emoji_name) = zulip_emoji_name(emoji) else {
+            tracing::debug!("Zulip: no emoji name for {emoji:?}, skipping reaction");
+            return Ok(());
+        };
+        let message_id = message_id.strip_prefix("zulip_").unwrap_or(message_id);
+        let url = self.api_url(&format!("messages/{message_id}/reactions"));
+        self.call(
+            self.http_client()
+                .request(method, url)
+                .query(&[("emoji_name", emoji_name)]),
+            "reaction",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Parsed send destination.
+#[derive(Debug, PartialEq, Eq)]
+enum ZulipTarget<'a> {
+    Stream { stream: &'a str, topic: &'a str },
+    Direct(Vec<&'a str>),
+}
+
+/// Resolve a reply target. `stream:<name>` needs a topic (from `thread_ts`
+/// or the message subject); `dm:<emails>` and bare email lists are direct messages.
+fn parse_target<'a>(recipient: &'a str, topic: Option<&'a str>) -> Result<ZulipTarget<'a>> {
+    if let Some(stream) = recipient.strip_prefix(STREAM_TARGET_PREFIX) {
+        let stream = stream.trim();
+        if stream.is_empty() {
+            bail!("Zulip stream target is missing the stream name");
+        }
+        let topic = topic
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .unwrap_or(ZULIP_DEFAULT_TOPIC);
+        return Ok(ZulipTarget::Stream { stream, topic });
+    }
+    let emails: Vec<&str> = recipient
+        .strip_prefix(DM_TARGET_PREFIX)
+        .unwrap_or(recipient)
+        .split(',')
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+        .collect();
+    if emails.is_empty() {
+        bail!("Zulip direct message target has no recipients");
+    }
+    Ok(ZulipTarget::Direct(emails))
+}
+
+/// Detect and remove `@**Bot Name**` / `@**Bot Name|id**` mentions (and their
+/// silent `@_**...**` forms). Returns whether a loud mention was found.
+fn strip_bot_mentions(content: &str, bot: &BotIdentity) -> (bool, String) {
+    if bot.full_name.is_empty() {
+        return (false, content.trim().to_string());
+    }
+    let mut mentioned = false;
+    let mut text = content.to_string();
+    let loud = [
+        format!("@**{}**", bot.full_name),
+        format!("@**{}|{}**", bot.full_name, bot.user_id),
+    ];
+    for pattern in &loud {
+        if text.contains(pattern.as_str()) {
+            mentioned = true;
+            text = text.replace(pattern.as_str(), "");
+        }
+    }
+    for pattern in [
+        format!("@_**{}**", bot.full_name),
+        format!("@_**{}|{}**", bot.full_name, bot.user_id),
+    ] {
+        text = text.replace(pattern.as_str(), "");
+    }
+    (mentioned, text.trim().to_string())
+}
+
+/// Map a Unicode emoji (as used by the runtime's ack reactions) to Zulip's
+/// emoji name. Plain names such as `"thumbs_up"` pass through unchanged.
+fn zulip_emoji_name(emoji: &str) -> Option<&str> {
+    let name = match emoji.trim_end_matches('\u{FE0F}') {
+        "\u{1F440}" => "eyes",
+        "\u{2705}" => "check",
+        "\u{26A0}" => "warning",
+        "\u{274C}" => "cross_mark",
+        "\u{1F44D}" => "+1",
+        "\u{1F44E}" => "-1",
+        "\u{2764}" => "heart",
+        "\u{1F389}" => "tada",
+        "\u{1F525}" => "fire",
+        "\u{1F914}" => "thinking",
+        "\u{1F680}" => "rocket",
+        other
+            if !other.is_empty()
+                && other
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')) =>
+        {
+            other
+        }
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[async_trait]
+impl Channel for ZulipChannel {
+    fn name(&self) -> &str {
+        "zulip"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        let topic = message.thread_ts.as_deref().or(message.subject.as_deref());
+        let target = parse_target(&message.recipient, topic)?;
+        for chunk in super::render::split_markdown_bytes(&message.content, ZULIP_MAX_MESSAGE_BYTES)
+        {
+            self.send_chunk(&target, &chunk).await?;
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        let bot = match self.fetch_identity().await {
+            Ok(bot) => bot,
+            Err(e) => {
+                tracing::warn!(
+                    "Zulip: bot identity unresolved ({e}); @mention detection will rely on \
+                     message flags only"
+                );
+                BotIdentity::default()
+            }
+        };
+        tracing::info!(
+            "Zulip channel listening on {} as {}...",
+            self.site,
+            self.email
+        );
+
+        let mut backoff = 1;
+        loop {
+            match self.run_queue(&tx, &bot).await {
+                Ok(true) => backoff = 1,
+                Ok(false) => return Ok(()),
+                Err(e) => {
+                    tracing::warn!("Zulip event queue error: {e}; retrying in {backoff}s");
+                    tokio::time::sleep(Duration::from_secs(backoff)).await;
+                    backoff = (backoff * 2).min(ZULIP_MAX_BACKOFF_SECS);
+                }
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        match self.fetch_identity().await {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!("Zulip health_check failed: {e}");
+                false
+            }
+        }
+    }
+
+    async fn add_reaction(&self, _channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        self.reaction_request(reqwest::Method::POST, message_id, emoji)
+            .await
+    }
+
+    async fn remove_reaction(
+        &self,
+        _channel_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<()> {
+        self.reaction_request(reqwest::Method::DELETE, message_id, emoji)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GroupReplyConfig, GroupReplyMode};
+
+    fn config(site: &str) -> ZulipConfig {
+        ZulipConfig {
+            site: site.to_string(),
+            email: "zeroclaw-bot@chat.example.com".into(),
+            api_key: "key".into(),
+            streams: Vec::new(),
+            allowed_users: vec!["*".into()],
+            group_reply: None,
+        }
+    }
+
+    fn bot() -> BotIdentity {
+        BotIdentity {
+            user_id: 42,
+            full_name: "ZeroClaw".into(),
+        }
+    }
+
+    /// Recorded `message` event for a stream post.
+    fn stream_event(id: i64, content: &str, flags: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "type": "message",
+            "id": id,
+            "flags": flags,
+            "message": {
+                "id": 1000 + id,
+                "sender_id": 7,
+                "sender_email": "alice@example.com",
+                "sender_full_name": "Alice",
+                "type": "stream",
+                "stream_id": 3,
+                "display_recipient": "ops",
+                "subject": "deploys",
+                "content": content,
+                "content_type": "text/x-markdown",
+                "timestamp": 1_700_000_000u64
+            }
+        })
+    }
+
+    /// Recorded `message` event for a direct message (bot + Alice + Bob).
+    fn private_event(id: i64, content: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "message",
+            "id": id,
+            "flags": ["read"],
+            "message": {
+                "id": 2000 + id,
+                "sender_id": 7,
+                "sender_email": "alice@example.com",
+                "type": "private",
+                "display_recipient": [
+                    {"id": 7, "email": "alice@example.com", "full_name": "Alice"},
+                    {"id": 8, "email": "bob@example.com", "full_name": "Bob"},
+                    {"id": 42, "email": "zeroclaw-bot@chat.example.com", "full_name": "ZeroClaw"}
+                ],
+                "subject": "",
+                "content": content,
+                "timestamp": 1_700_000_100u64
+            }
+        })
+    }
+
+    #[test]
+    fn stream_message_maps_topic_to_thread() {
+        let ch = ZulipChannel::new(config("https://chat.example.com/"));
+        let msg = ch
+            .parse_event(&stream_event(1, "status?", &[]), &bot())
+            .unwrap();
+        assert_eq!(msg.id, "zulip_1001");
+        assert_eq!(msg.sender, "alice@example.com");
+        assert_eq!(msg.reply_target, "stream:ops");
+        assert_eq!(msg.thread_ts.as_deref(), Some("deploys"));
+        assert_eq!(msg.content, "status?");
+        assert_eq!(msg.channel, "zulip");
+        assert_eq!(msg.timestamp, 1_700_000_000);
+        assert_eq!(ch.site, "https://chat.example.com");
+    }
+
+    #[test]
+    fn private_message_replies_to_other_participants() {
+        let ch = ZulipChannel::new(config("https://chat.example.com"));
+        let msg = ch.parse_event(&private_event(2, "hi"), &bot()).unwrap();
+        assert_eq!(msg.reply_target, "dm:alice@example.com,bob@example.com");
+        assert!(msg.thread_ts.is_none());
+    }
+
+    #[test]
+    fn ignores_own_messages_heartbeats_and_unauthorized_senders() {
+        let mut cfg = config("https://chat.example.com");
+        cfg.allowed_users = vec!["Bob@Example.com".into()];
+        let ch = ZulipChannel::new(cfg);
+        assert!(ch
+            .parse_event(&serde_json::json!({"type": "heartbeat", "id": 3}), &bot())
+            .is_none());
+        assert!(ch
+            .parse_event(&stream_event(4, "hello", &[]), &bot())
+            .is_none());
+
+        let mut own = stream_event(5, "echo", &[]);
+        own["message"]["sender_id"] = 42.into();
+        own["message"]["sender_email"] = "bob@example.com".into();
+        assert!(ch.parse_event(&own, &bot()).is_none());
+
+        let mut from_bob = stream_event(6, "hello", &[]);
+        from_bob["message"]["sender_email"] = "bob@example.com".into();
+        assert!(ch.parse_event(&from_bob, &bot()).is_some());
+    }
+
+    #[test]
+    fn stream_filter_limits_watched_streams() {
+        let mut cfg = config("https://chat.example.com");
+        cfg.streams = vec!["support".into()];
+        let ch = ZulipChannel::new(cfg);
+        assert!(ch
+            .parse_event(&stream_event(1, "hi", &[]), &bot())
+            .is_none());
+        assert!(ch.parse_event(&private_event(2, "hi"), &bot()).is_some());
+    }
+
+    #[test]
+    fn mention_only_gates_stream_messages() {
+        let mut cfg = config("https://chat.example.com");
+        cfg.group_reply = Some(GroupReplyConfig {
+            mode: Some(GroupReplyMode::MentionOnly),
+            allowed_sender_ids: Vec::new(),
+        });
+        let ch = ZulipChannel::new(cfg);
+        assert!(ch
+            .parse_event(&stream_event(1, "status?", &[]), &bot())
+            .is_none());
+
+        let msg = ch
+            .parse_event(
+                &stream_event(2, "@**ZeroClaw|42** status?", &["mentioned"]),
+                &bot(),
+            )
+            .unwrap();
+        assert_eq!(msg.content, "status?");
+
+        // Direct messages are never mention-gated.
+        assert!(ch.parse_event(&private_event(3, "hi"), &bot()).is_some());
+    }
+
+    #[test]
+    fn strip_bot_mentions_handles_loud_and_silent_forms() {
+        assert_eq!(
+            strip_bot_mentions("@**ZeroClaw** deploy", &bot()),
+            (true, "deploy".to_string())
+        );
+        assert_eq!(
+            strip_bot_mentions("thanks @_**ZeroClaw|42**", &bot()),
+            (false, "thanks".to_string())
+        );
+        assert_eq!(
+            strip_bot_mentions("@**Alice** hi", &bot()),
+            (false, "@**Alice** hi".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_target_resolves_streams_and_direct_messages() {
+        assert_eq!(
+            parse_target("stream:ops", Some("deploys")).unwrap(),
+            ZulipTarget::Stream {
+                stream: "ops",
+                topic: "deploys"
+            }
+        );
+        assert_eq!(
+            parse_target("stream:ops", None).unwrap(),
+            ZulipTarget::Stream {
+                stream: "ops",
+                topic: ZULIP_DEFAULT_TOPIC
+            }
+        );
+        assert_eq!(
+            parse_target("dm:a@example.com, b@example.com", None).unwrap(),
+            ZulipTarget::Direct(vec!["a@example.com", "b@example.com"])
+        );
+        assert_eq!(
+            parse_target("a@example.com", None).unwrap(),
+            ZulipTarget::Direct(vec!["a@example.com"])
+        );
+        assert!(parse_target("stream:", None).is_err());
+        assert!(parse_target("dm:", None).is_err());
+    }
+
+    #[test]
+    fn emoji_names_cover_ack_reactions() {
+        assert_eq!(zulip_emoji_name("\u{1F440}"), Some("eyes"));
+        assert_eq!(zulip_emoji_name("\u{2705}"), Some("check"));
+        assert_eq!(zulip_emoji_name("\u{26A0}\u{FE0F}"), Some("warning"));
+        assert_eq!(zulip_emoji_name("thumbs_up"), Some("thumbs_up"));
+        assert_eq!(zulip_emoji_name("\u{1F9A9}"), None);
+    }
+
+    mod http {
+        use super::*;
+        use wiremock::matchers::{body_string_contains, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        fn success(extra: serde_json::Value) -> ResponseTemplate {
+            let mut body = serde_json::json!({"result": "success", "msg": ""});
+            if let (Some(body), Some(extra)) = (body.as_object_mut(), extra.as_object()) {
+                body.extend(extra.clone());
+            }
+            ResponseTemplate::new(200).set_body_json(body)
+        }
+
+        #[tokio::test]
+        async fn send_replies_into_stream_topic() {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/api/v1/messages"))
+                .and(body_string_contains("type=stream"))
+                .and(body_string_contains("to=ops"))
+                .and(body_string_contains("topic=deploys"))
+                .respond_with(success(serde_json::json!({"id": 99})))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let ch = ZulipChannel::new(config(&server.uri()));
+            ch.send(&SendMessage::new("done", "stream:ops").in_thread(Some("deploys".into())))
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn send_direct_message_uses_email_list() {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/api/v1/messages"))
+                .and(body_string_contains("type=private"))
+                .and(body_string_contains("alice%40example.com"))
+                .respond_with(success(serde_json::json!({"id": 100})))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let ch = ZulipChannel::new(config(&server.uri()));
+            ch.send(&SendMessage::new("hi", "dm:alice@example.com"))
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn send_error_surfaces_zulip_code() {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/api/v1/messages"))
+                .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                    "result": "error",
+                    "msg": "Stream 'nope' does not exist",
+                    "code": "STREAM_DOES_NOT_EXIST"
+                })))
+                .mount(&server)
+                .await;
+
+            let ch = ZulipChannel::new(config(&server.uri()));
+            let err = ch
+                .send(&SendMessage::new("hi", "stream:nope"))
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("STREAM_DOES_NOT_EXIST"), "{err}");
+        }
+
+        #[tokio::test]
+        async fn reactions_map_emoji_and_strip_id_prefix() {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/api/v1/messages/1001/reactions"))
+                .and(query_param("emoji_name", "eyes"))
+                .respond_with(success(serde_json::json!({})))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("DELETE"))
+                .and(path("/api/v1/messages/1001/reactions"))
+                .and(query_param("emoji_name", "eyes"))
+                .respond_with(success(serde_json::json!({})))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let ch = ZulipChannel::new(config(&server.uri()));
+            ch.add_reaction("stream:ops", "zulip_1001", "\u{1F440}")
+                .await
+                .unwrap();
+            ch.remove_reaction("stream:ops", "zulip_1001", "\u{1F440}")
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn listen_replays_events_and_reregisters_expired_queue() {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/users/me"))
+                .respond_with(success(serde_json::json!({
+                    "user_id": 42,
+                    "full_name": "ZeroClaw",
+                    "email": "zeroclaw-bot@chat.example.com"
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/api/v1/register"))
+                .respond_with(success(serde_json::json!({
+                    "queue_id": "q1",
+                    "last_event_id": -1
+                })))
+                .up_to_n_times(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/api/v1/register"))
+                .respond_with(success(serde_json::json!({
+                    "queue_id": "q2",
+                    "last_event_id": -1
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/events"))
+                .and(query_param("queue_id", "q1"))
+                .and(query_param("last_event_id", "-1"))
+                .respond_with(success(serde_json::json!({
+                    "events": [
+                        {"type": "heartbeat", "id": 0},
+                        stream_event(1, "@**ZeroClaw** ping", &["mentioned"]),
+                        private_event(2, "hello"),
+                    ]
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/events"))
+                .and(query_param("queue_id", "q1"))
+                .and(query_param("last_event_id", "2"))
+                .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                    "result": "error",
+                    "msg": "Bad event queue ID: q1",
+                    "code": "BAD_EVENT_QUEUE_ID",
+                    "queue_id": "q1"
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/events"))
+                .and(query_param("queue_id", "q2"))
+                .and(query_param("last_event_id", "-1"))
+                .respond_with(success(serde_json::json!({
+                    "events": [stream_event(3, "after re-register", &[])]
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/events"))
+                .and(query_param("queue_id", "q2"))
+                .and(query_param("last_event_id", "3"))
+                .respond_with(
+                    success(serde_json::json!({"events": []})).set_delay(Duration::from_secs(30)),
+                )
+                .mount(&server)
+                .await;
+
+            let ch = ZulipChannel::new(config(&server.uri()));
+            let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+            let handle = tokio::spawn(async move { ch.listen(tx).await });
+
+            let mut received = Vec::new();
+            for _ in 0..3 {
+                let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                    .await
+                    .expect("timed out waiting for replayed event")
+                    .expect("listener closed");
+                received.push(msg);
+            }
+            handle.abort();
+
+            assert_eq!(received[0].content, "ping");
+            assert_eq!(received[0].thread_ts.as_deref(), Some("deploys"));
+            assert_eq!(
+                received[1].reply_target,
+                "dm:alice@example.com,bob@example.com"
+            );
+            assert_eq!(received[2].content, "after re-register");
+        }
+    }
+}
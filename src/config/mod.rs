@@ -5,24 +5,32 @@ pub mod traits;
 pub use schema::{
     apply_runtime_proxy_to_builder, build_runtime_proxy_client,
     build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
-    AgentConfig, AgentsIpcConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig,
-    BrowserConfig, BuiltinHooksConfig, ChannelsConfig, ClassificationRule, ComposioConfig, Config,
-    CoordinationConfig, CostConfig, CronConfig, DelegateAgentConfig, DiscordConfig,
+    AgentConfig, AgentsIpcConfig, ApprovalQuorumConfig, ApprovalQuorumRuleConfig, AuditConfig,
+    AutonomyConfig, BrowserComputerUseConfig,
+    BrowserConfig, BuiltinHooksConfig, CassetteMatchMode, CassetteMode, ChannelsConfig, ClassificationRule, ComposioConfig, Config,
+    CoordinationConfig, CoordinationPeerConfig, CostConfig, CronConfig, DelegateAgentConfig, DelegateQueueConfig, DiscordConfig, DlpAction,
+    DlpConfig, DlpDetectorConfig, DlpDetectorKind, DlpPiiClass,
     DockerRuntimeConfig, EconomicConfig, EconomicTokenPricing, EmbeddingRouteConfig, EstopConfig,
     FeishuConfig, GatewayConfig, GroupReplyConfig, GroupReplyMode, HardwareConfig,
     HardwareTransport, HeartbeatConfig, HooksConfig, HttpRequestConfig,
-    HttpRequestCredentialProfile, IMessageConfig, IdentityConfig, LarkConfig, MatrixConfig,
-    MemoryConfig, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig,
+    HttpRequestCredentialProfile, IMessageConfig, IdentityConfig, InFlightEditAction, LarkConfig, LocalTranscriptionConfig,
+    MatrixConfig, MemoryConfig, MessageEditsConfig, ModelCascadeConfig, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig,
+    NotificationDestination, NotificationRouteConfig, NotificationSeverity, NotificationTargetConfig,
+    NotificationsConfig,
     NonCliNaturalLanguageApprovalMode, ObservabilityConfig, OtpChallengeDelivery, OtpConfig,
-    OtpMethod, OutboundLeakGuardAction, OutboundLeakGuardConfig, PeripheralBoardConfig,
-    PeripheralsConfig, PerplexityFilterConfig, PluginEntryConfig, PluginsConfig, ProviderConfig,
-    ProxyConfig, ProxyScope, QdrantConfig, QueryClassificationConfig, ReliabilityConfig,
+    OtpMethod, OutboundLeakGuardAction, OutboundLeakGuardConfig, OutboundQueueConfig,
+    OutboundRateLimitConfig, PeripheralBoardConfig,
+    PeripheralsConfig, PerplexityFilterConfig, PlanModeConfig, PluginEntryConfig, PluginsConfig, ProviderCassetteConfig,
+    ProviderConfig, ProxyConfig, ProxyScope, QdrantConfig, QueryClassificationConfig, QuietHoursConfig, ReliabilityConfig,
     ResearchPhaseConfig, ResearchTrigger, ResourceLimitsConfig, RuntimeConfig, SandboxBackend,
     SandboxConfig, SchedulerConfig, SecretsConfig, SecurityConfig, SecurityRoleConfig,
     SkillsConfig, SkillsPromptInjectionMode, SlackConfig, StorageConfig, StorageProviderConfig,
-    StorageProviderSection, StreamMode, SyscallAnomalyConfig, TelegramConfig, TranscriptionConfig,
-    TunnelConfig, UrlAccessConfig, WasmCapabilityEscalationMode, WasmConfig, WasmModuleHashPolicy,
-    WasmRuntimeConfig, WasmSecurityConfig, WebFetchConfig, WebSearchConfig, WebhookConfig,
+    StorageProviderSection, StreamMode, SyscallAnomalyConfig, TelegramConfig, TranscriptionBackend, TranscriptionConfig,
+    TtsConfig, TtsProviderKind, TtsReplyMode,
+    TunnelConfig, UrlAccessConfig, UserDirectoryConfig, WasmCapabilityEscalationMode, WasmConfig,
+    WasmModuleHashPolicy,
+    WasmRuntimeConfig, WasmSecurityConfig, WebFetchConfig, WebSearchConfig, WebhookCallbackConfig, WebhookConfig, XmppConfig, XmppTlsMode,
+    ZulipConfig,
     // Fork additions
     TeamBotEntry, TeamConfig, LinearConfig,
 };
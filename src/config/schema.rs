@@ -1,6 +1,6 @@
 use crate::config::traits::ChannelConfig;
 use crate::providers::{is_glm_alias, is_zai_alias};
-use crate::security::{AutonomyLevel, DomainMatcher, GuardAction};
+use crate::security::{AutonomyLevel, CommandRiskLevel, DomainMatcher, GuardAction};
 use anyhow::{Context, Result};
 use directories::UserDirs;
 use schemars::JsonSchema;
@@ -43,6 +43,8 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "memory.embeddings",
     "tunnel.custom",
     "transcription.groq",
+    "tts.openai",
+    "tts.elevenlabs",
 ];
 
 const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] = &[
@@ -52,6 +54,7 @@ const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] = &[
     "memory.*",
     "tunnel.*",
     "transcription.*",
+    "tts.*",
 ];
 
 static RUNTIME_PROXY_CONFIG: OnceLock<RwLock<ProxyConfig>> = OnceLock::new();
@@ -165,6 +168,10 @@ pub struct Config {
     #[serde(default)]
     pub query_classification: QueryClassificationConfig,
 
+    /// Budget-aware model cascading across `[[model_routes]]` hints (`[model_cascade]`).
+    #[serde(default)]
+    pub model_cascade: ModelCascadeConfig,
+
     /// Heartbeat configuration for periodic health pings (`[heartbeat]`).
     #[serde(default)]
     pub heartbeat: HeartbeatConfig,
@@ -254,6 +261,10 @@ pub struct Config {
     #[serde(default)]
     pub coordination: CoordinationConfig,
 
+    /// Persistent delegate task queue (`[delegate_queue]`).
+    #[serde(default)]
+    pub delegate_queue: DelegateQueueConfig,
+
     /// Hooks configuration (lifecycle hooks and built-in hook toggles).
     #[serde(default)]
     pub hooks: HooksConfig,
@@ -270,6 +281,14 @@ pub struct Config {
     #[serde(default)]
     pub transcription: TranscriptionConfig,
 
+    /// Text-to-speech voice replies (`[tts]`).
+    #[serde(default)]
+    pub tts: TtsConfig,
+
+    /// Cross-channel notification targets and routing (`[notifications]`).
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+
     /// Inter-process agent communication (`[agents_ipc]`).
     #[serde(default)]
     pub agents_ipc: AgentsIpcConfig,
@@ -371,6 +390,65 @@ pub struct ProviderConfig {
     /// Existing configs that omit `provider.transport` remain valid and fall back to defaults.
     #[serde(default)]
     pub transport: Option<String>,
+    /// Record provider traffic to a cassette file, or replay it offline
+    /// (`[provider.cassette]`).
+    #[serde(default)]
+    pub cassette: ProviderCassetteConfig,
+}
+
+/// Whether provider calls are recorded to or replayed from a cassette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CassetteMode {
+    /// Talk to the configured provider directly.
+    #[default]
+    Off,
+    /// Forward calls to the configured provider and write every request and
+    /// response to the cassette.
+    Record,
+    /// Serve responses from the cassette without contacting any provider.
+    Replay,
+}
+
+/// How replayed requests are matched against recorded ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CassetteMatchMode {
+    /// Requests must match in order: same call kind, model and messages
+    /// (timestamps masked).
+    #[default]
+    Strict,
+    /// Match the next unused recording with the same last user message and
+    /// tool names; requests without such a recording fail.
+    Fuzzy,
+}
+
+/// Provider record/replay configuration (`[provider.cassette]` section).
+///
+/// Env overrides: `ZEROCLAW_PROVIDER_CASSETTE_MODE`, `ZEROCLAW_PROVIDER_CASSETTE`
+/// (path) and `ZEROCLAW_PROVIDER_CASSETTE_MATCH`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ProviderCassetteConfig {
+    /// `off` (default), `record` or `replay`.
+    #[serde(default)]
+    pub mode: CassetteMode,
+    /// Cassette JSON file. Required unless `mode = "off"`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// `strict` (default) or `fuzzy` request matching during replay.
+    #[serde(default)]
+    pub match_mode: CassetteMatchMode,
+}
+
+impl ProviderCassetteConfig {
+    /// Resolved cassette path (`~` expanded), if configured.
+    pub fn resolved_path(&self) -> Option<PathBuf> {
+        self.path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(shellexpand::tilde(path).as_ref()))
+    }
 }
 
 // ── Delegate Agents ──────────────────────────────────────────────
@@ -403,6 +481,12 @@ pub struct DelegateAgentConfig {
     /// Maximum tool-call iterations in agentic mode.
     #[serde(default = "default_max_tool_iterations")]
     pub max_iterations: usize,
+    /// Maximum queued tasks for this agent that run at the same time.
+    #[serde(default = "default_delegate_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Retries for a failed queued task before it is marked failed.
+    #[serde(default = "default_delegate_max_retries")]
+    pub max_retries: u32,
 }
 
 fn default_max_depth() -> u32 {
@@ -413,6 +497,14 @@ fn default_max_tool_iterations() -> usize {
     10
 }
 
+fn default_delegate_max_concurrent() -> usize {
+    1
+}
+
+fn default_delegate_max_retries() -> u32 {
+    2
+}
+
 impl std::fmt::Debug for DelegateAgentConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DelegateAgentConfig")
@@ -425,6 +517,8 @@ impl std::fmt::Debug for DelegateAgentConfig {
             .field("agentic", &self.agentic)
             .field("allowed_tools", &self.allowed_tools)
             .field("max_iterations", &self.max_iterations)
+            .field("max_concurrent", &self.max_concurrent)
+            .field("max_retries", &self.max_retries)
             .finish()
     }
 }
@@ -449,6 +543,8 @@ impl std::fmt::Debug for Config {
             self.channels_config.nextcloud_talk.is_some(),
             self.channels_config.email.is_some(),
             self.channels_config.irc.is_some(),
+            self.channels_config.xmpp.is_some(),
+            self.channels_config.zulip.is_some(),
             self.channels_config.lark.is_some(),
             self.channels_config.feishu.is_some(),
             self.channels_config.dingtalk.is_some(),
@@ -564,7 +660,71 @@ fn default_transcription_max_duration_secs() -> u64 {
     120
 }
 
-/// Voice transcription configuration (Whisper API via Groq).
+fn default_local_whisper_binary() -> String {
+    "whisper-cli".into()
+}
+
+fn default_local_ffmpeg_binary() -> String {
+    "ffmpeg".into()
+}
+
+fn default_local_chunk_secs() -> u64 {
+    300
+}
+
+fn default_local_timeout_secs() -> u64 {
+    600
+}
+
+/// Where voice notes are transcribed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionBackend {
+    /// Hosted Whisper-compatible API (`api_url`).
+    #[default]
+    Api,
+    /// Local `whisper.cpp` binary (`[transcription.local]`).
+    Local,
+}
+
+/// Offline transcription with a local `whisper.cpp` binary
+/// (`[transcription.local]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LocalTranscriptionConfig {
+    /// `whisper.cpp` CLI executable (`whisper-cli`, or `main` in older builds).
+    #[serde(default = "default_local_whisper_binary")]
+    pub whisper_binary: String,
+    /// GGML model file (default: `~/.zeroclaw/models/whisper/ggml-base.bin`).
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// ffmpeg executable used to convert audio to 16 kHz mono WAV.
+    #[serde(default = "default_local_ffmpeg_binary")]
+    pub ffmpeg_binary: String,
+    /// Recordings are split into chunks of this many seconds.
+    #[serde(default = "default_local_chunk_secs")]
+    pub chunk_secs: u64,
+    /// Worker threads passed to `whisper.cpp` (default: its own choice).
+    #[serde(default)]
+    pub threads: Option<u32>,
+    /// Timeout in seconds for transcribing a whole recording.
+    #[serde(default = "default_local_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for LocalTranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            whisper_binary: default_local_whisper_binary(),
+            model_path: None,
+            ffmpeg_binary: default_local_ffmpeg_binary(),
+            chunk_secs: default_local_chunk_secs(),
+            threads: None,
+            timeout_secs: default_local_timeout_secs(),
+        }
+    }
+}
+
+/// Voice transcription configuration (Whisper API via Groq, or local whisper.cpp).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TranscriptionConfig {
     /// Enable voice transcription for channels that support it.
@@ -587,6 +747,22 @@ pub struct TranscriptionConfig {
     /// Maximum voice duration in seconds (messages longer than this are skipped).
     #[serde(default = "default_transcription_max_duration_secs")]
     pub max_duration_secs: u64,
+    /// Default backend: `api` or `local`.
+    #[serde(default)]
+    pub backend: TranscriptionBackend,
+    /// Per-channel backend overrides, keyed by channel name (e.g. `telegram`).
+    #[serde(default)]
+    pub channels: HashMap<String, TranscriptionBackend>,
+    /// Local `whisper.cpp` settings.
+    #[serde(default)]
+    pub local: LocalTranscriptionConfig,
+}
+
+impl TranscriptionConfig {
+    /// Backend used for voice notes received on `channel`.
+    pub fn backend_for(&self, channel: &str) -> TranscriptionBackend {
+        self.channels.get(channel).copied().unwrap_or(self.backend)
+    }
 }
 
 impl Default for TranscriptionConfig {
@@ -598,6 +774,128 @@ impl Default for TranscriptionConfig {
             model: default_transcription_model(),
             language: None,
             max_duration_secs: default_transcription_max_duration_secs(),
+            backend: TranscriptionBackend::default(),
+            channels: HashMap::new(),
+            local: LocalTranscriptionConfig::default(),
+        }
+    }
+}
+
+// ── Text-to-speech ──────────────────────────────────────────────
+
+/// Speech synthesis backend for voice replies.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsProviderKind {
+    /// OpenAI-compatible `POST /audio/speech` endpoint.
+    #[default]
+    OpenAi,
+    /// Local Piper binary (output is encoded to Opus with ffmpeg).
+    Piper,
+    /// ElevenLabs-style `POST /v1/text-to-speech/{voice}` endpoint.
+    ElevenLabs,
+}
+
+/// When a reply is sent as a voice note instead of text.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TtsReplyMode {
+    /// Always reply with text.
+    Never,
+    /// Reply with voice when the user's message was a voice note.
+    #[default]
+    WhenVoice,
+    /// Reply with voice to every message.
+    Always,
+}
+
+fn default_tts_piper_binary() -> String {
+    "piper".into()
+}
+
+fn default_tts_ffmpeg_binary() -> String {
+    "ffmpeg".into()
+}
+
+fn default_tts_max_chars() -> usize {
+    1500
+}
+
+fn default_tts_timeout_secs() -> u64 {
+    60
+}
+
+/// Text-to-speech configuration for voice replies (`[tts]`).
+///
+/// Channels that can send voice notes (Telegram, WhatsApp) reply with
+/// synthesized Opus/OGG audio according to `reply_mode`; any synthesis
+/// failure falls back to the text reply.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TtsConfig {
+    /// Enable voice replies.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Synthesis backend: `openai`, `piper` or `elevenlabs`.
+    #[serde(default)]
+    pub provider: TtsProviderKind,
+    /// API key for HTTP backends.
+    ///
+    /// If unset, runtime falls back to `OPENAI_API_KEY` or `ELEVENLABS_API_KEY`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Endpoint override. Defaults to the provider's public API.
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Synthesis model (e.g. `gpt-4o-mini-tts`, `eleven_multilingual_v2`).
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Voice name, ElevenLabs voice id, or Piper voice (model file stem).
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Piper executable.
+    #[serde(default = "default_tts_piper_binary")]
+    pub piper_binary: String,
+    /// Directory holding Piper `.onnx` voices (default: `~/.zeroclaw/models/piper`).
+    #[serde(default)]
+    pub piper_model_dir: Option<String>,
+    /// ffmpeg executable used to encode Opus/OGG voice notes.
+    #[serde(default = "default_tts_ffmpeg_binary")]
+    pub ffmpeg_binary: String,
+    /// Replies longer than this many characters are sent as text.
+    #[serde(default = "default_tts_max_chars")]
+    pub max_chars: usize,
+    /// Synthesis timeout in seconds.
+    #[serde(default = "default_tts_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Default policy for when to reply with voice.
+    #[serde(default)]
+    pub reply_mode: TtsReplyMode,
+    /// Per-channel policy overrides, keyed by channel name (e.g. `telegram`).
+    #[serde(default)]
+    pub channels: HashMap<String, TtsReplyMode>,
+    /// Per-user policy overrides, keyed by sender id. Takes precedence over
+    /// `channels`.
+    #[serde(default)]
+    pub users: HashMap<String, TtsReplyMode>,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TtsProviderKind::default(),
+            api_key: None,
+            api_url: None,
+            model: None,
+            voice: None,
+            piper_binary: default_tts_piper_binary(),
+            piper_model_dir: None,
+            ffmpeg_binary: default_tts_ffmpeg_binary(),
+            max_chars: default_tts_max_chars(),
+            timeout_secs: default_tts_timeout_secs(),
+            reply_mode: TtsReplyMode::default(),
+            channels: HashMap::new(),
+            users: HashMap::new(),
         }
     }
 }
@@ -742,6 +1040,69 @@ pub struct CoordinationConfig {
     /// Maximum retained dedupe window size for processed message IDs.
     #[serde(default = "default_coordination_max_seen_message_ids")]
     pub max_seen_message_ids: usize,
+    /// Identity of this node when exchanging envelopes with remote peers.
+    /// Must match the `name` this node is given in each peer's `peers` list.
+    #[serde(default = "default_coordination_node_id")]
+    pub node_id: String,
+    /// Remote gateways whose agents can be delegated to (`[[coordination.peers]]`).
+    #[serde(default)]
+    pub peers: Vec<CoordinationPeerConfig>,
+    /// Delivery attempts per envelope before it is dead-lettered.
+    #[serde(default = "default_coordination_remote_max_attempts")]
+    pub remote_max_attempts: u32,
+    /// Base delay between delivery attempts in milliseconds; doubles per attempt.
+    #[serde(default = "default_coordination_remote_retry_backoff_ms")]
+    pub remote_retry_backoff_ms: u64,
+    /// How long a remote delegation waits for the peer's `TaskResult`.
+    #[serde(default = "default_coordination_remote_task_timeout_secs")]
+    pub remote_task_timeout_secs: u64,
+}
+
+fn default_coordination_node_id() -> String {
+    "local".into()
+}
+
+fn default_coordination_remote_max_attempts() -> u32 {
+    5
+}
+
+fn default_coordination_remote_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_coordination_remote_task_timeout_secs() -> u64 {
+    600
+}
+
+/// Remote coordination peer (`[[coordination.peers]]`).
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CoordinationPeerConfig {
+    /// Peer node name; must match the peer's own `coordination.node_id`.
+    pub name: String,
+    /// Base URL of the peer gateway (e.g. `https://gpu-box:42617`).
+    pub url: String,
+    /// Bearer token obtained from the peer's `POST /pair`.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Secret shared with this peer. Both sides sign every envelope with it
+    /// (HMAC-SHA256) and only accept envelopes carrying the peer's signature.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Agents configured on the peer that this node may delegate to.
+    #[serde(default)]
+    pub agents: Vec<String>,
+}
+
+impl std::fmt::Debug for CoordinationPeerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoordinationPeerConfig")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("token_configured", &self.token.is_some())
+            .field("secret_configured", &self.secret.is_some())
+            .field("agents", &self.agents)
+            .finish()
+    }
 }
 
 impl Default for CoordinationConfig {
@@ -753,6 +1114,56 @@ impl Default for CoordinationConfig {
             max_dead_letters: default_coordination_max_dead_letters(),
             max_context_entries: default_coordination_max_context_entries(),
             max_seen_message_ids: default_coordination_max_seen_message_ids(),
+            node_id: default_coordination_node_id(),
+            peers: Vec::new(),
+            remote_max_attempts: default_coordination_remote_max_attempts(),
+            remote_retry_backoff_ms: default_coordination_remote_retry_backoff_ms(),
+            remote_task_timeout_secs: default_coordination_remote_task_timeout_secs(),
+        }
+    }
+}
+
+fn default_delegate_queue_poll_interval_secs() -> u64 {
+    5
+}
+
+fn default_delegate_queue_task_timeout_secs() -> u64 {
+    900
+}
+
+fn default_delegate_queue_retry_backoff_secs() -> u64 {
+    30
+}
+
+/// Persistent delegate task queue (`[delegate_queue]` section).
+///
+/// When enabled, the `delegate_queue` tool stores delegated work in
+/// `<workspace>/delegate/queue.db` and a background worker runs it with
+/// per-agent concurrency limits (`[agents.<name>].max_concurrent`) and retries
+/// (`[agents.<name>].max_retries`). Tasks survive restarts.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DelegateQueueConfig {
+    /// Enable the `delegate_queue` tool and its background worker.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between queue polls. Default: `5`.
+    #[serde(default = "default_delegate_queue_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Timeout for a single task attempt. Default: `900`.
+    #[serde(default = "default_delegate_queue_task_timeout_secs")]
+    pub task_timeout_secs: u64,
+    /// Base delay before retrying a failed task; doubles per attempt. Default: `30`.
+    #[serde(default = "default_delegate_queue_retry_backoff_secs")]
+    pub retry_backoff_secs: u64,
+}
+
+impl Default for DelegateQueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval_secs: default_delegate_queue_poll_interval_secs(),
+            task_timeout_secs: default_delegate_queue_task_timeout_secs(),
+            retry_backoff_secs: default_delegate_queue_retry_backoff_secs(),
         }
     }
 }
@@ -810,6 +1221,83 @@ pub struct AgentConfig {
     /// Fork addition — proposed for upstream `AgentConfig`; see docs/planning/DECISIONS.md.
     #[serde(default)]
     pub tool_allowlist: Vec<String>,
+    /// Tool outputs longer than this many characters are stored as session
+    /// artifacts and replaced by a head/tail preview that the model can expand
+    /// with the `artifact_read` tool. Set to `0` to keep full outputs in history.
+    #[serde(default = "default_agent_tool_output_max_chars")]
+    pub tool_output_max_chars: usize,
+    /// Characters of an oversized output kept inline, split between head and tail.
+    #[serde(default = "default_agent_tool_output_preview_chars")]
+    pub tool_output_preview_chars: usize,
+    /// Artifacts kept per session before the oldest are pruned.
+    #[serde(default = "default_agent_max_artifacts_per_session")]
+    pub max_artifacts_per_session: usize,
+    /// Planner/executor mode (`[agent.plan_mode]`).
+    #[serde(default)]
+    pub plan_mode: PlanModeConfig,
+}
+
+/// Planner/executor mode configuration (`[agent.plan_mode]` section).
+///
+/// A planning call turns the request into numbered steps (with expected tools
+/// and risk), the user approves, edits or rejects the plan, and the agent then
+/// executes it step by step, re-planning when a step fails. Start a planned
+/// turn with `/plan <task>` (CLI and channels), or set `auto = true` to plan
+/// every message.
+///
+/// ```toml
+/// [agent.plan_mode]
+/// auto = false
+/// require_approval = true
+/// max_steps = 8
+/// max_replans = 2
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PlanModeConfig {
+    /// Plan every user message instead of only `/plan <task>` requests.
+    #[serde(default)]
+    pub auto: bool,
+    /// Ask the user to approve, edit or reject each plan before execution.
+    /// When `false`, plans are shown and executed immediately.
+    #[serde(default = "default_true")]
+    pub require_approval: bool,
+    /// Maximum steps accepted from the planner.
+    #[serde(default = "default_plan_mode_max_steps")]
+    pub max_steps: usize,
+    /// Re-plans allowed after failed steps before the plan is abandoned.
+    #[serde(default = "default_plan_mode_max_replans")]
+    pub max_replans: usize,
+    /// Seconds to wait for a channel user to answer a plan review.
+    #[serde(default = "default_plan_mode_approval_timeout_secs")]
+    pub approval_timeout_secs: u64,
+    /// Tool-loop iterations per step. `0` uses `agent.max_tool_iterations`.
+    #[serde(default)]
+    pub step_max_tool_iterations: usize,
+}
+
+fn default_plan_mode_max_steps() -> usize {
+    8
+}
+
+fn default_plan_mode_max_replans() -> usize {
+    2
+}
+
+fn default_plan_mode_approval_timeout_secs() -> u64 {
+    300
+}
+
+impl Default for PlanModeConfig {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            require_approval: true,
+            max_steps: default_plan_mode_max_steps(),
+            max_replans: default_plan_mode_max_replans(),
+            approval_timeout_secs: default_plan_mode_approval_timeout_secs(),
+            step_max_tool_iterations: 0,
+        }
+    }
 }
 
 fn default_agent_max_tool_iterations() -> usize {
@@ -844,6 +1332,18 @@ fn default_safety_heartbeat_turn_interval() -> usize {
     10
 }
 
+fn default_agent_tool_output_max_chars() -> usize {
+    16_000
+}
+
+fn default_agent_tool_output_preview_chars() -> usize {
+    2_000
+}
+
+fn default_agent_max_artifacts_per_session() -> usize {
+    50
+}
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
@@ -858,6 +1358,10 @@ impl Default for AgentConfig {
             safety_heartbeat_interval: default_safety_heartbeat_interval(),
             safety_heartbeat_turn_interval: default_safety_heartbeat_turn_interval(),
             tool_allowlist: Vec::new(),
+            tool_output_max_chars: default_agent_tool_output_max_chars(),
+            tool_output_preview_chars: default_agent_tool_output_preview_chars(),
+            max_artifacts_per_session: default_agent_max_artifacts_per_session(),
+            plan_mode: PlanModeConfig::default(),
         }
     }
 }
@@ -1469,11 +1973,31 @@ pub struct SecretsConfig {
     /// Enable encryption for API keys and tokens in config.toml
     #[serde(default = "default_true")]
     pub encrypt: bool,
+    /// Allow `exec://<command>` secret references, which run a shell command
+    /// (e.g. `pass show zeroclaw/openrouter`) at config load time. Default: `false`.
+    #[serde(default)]
+    pub allow_exec: bool,
+    /// Timeout in seconds for `exec://` secret commands. Default: `10`.
+    #[serde(default = "default_secret_exec_timeout_secs")]
+    pub exec_timeout_secs: u64,
+    /// References resolved at load time, so `save()` writes them back instead
+    /// of the resolved secret. Computed, never serialized.
+    #[serde(skip)]
+    pub resolved_references: Vec<crate::security::secret_backends::SecretReferenceBinding>,
+}
+
+fn default_secret_exec_timeout_secs() -> u64 {
+    10
 }
 
 impl Default for SecretsConfig {
     fn default() -> Self {
-        Self { encrypt: true }
+        Self {
+            encrypt: true,
+            allow_exec: false,
+            exec_timeout_secs: default_secret_exec_timeout_secs(),
+            resolved_references: Vec::new(),
+        }
     }
 }
 
@@ -2756,6 +3280,20 @@ pub struct ObservabilityConfig {
     /// Maximum entries retained when runtime_trace_mode = "rolling".
     #[serde(default = "default_runtime_trace_max_entries")]
     pub runtime_trace_max_entries: usize,
+
+    /// Record full per-session transcripts (prompts, responses, tool calls
+    /// with arguments and outputs, timing) for `zeroclaw sessions`.
+    #[serde(default)]
+    pub transcripts_enabled: bool,
+
+    /// Transcript directory (one JSONL file per session). Relative paths are
+    /// resolved under workspace_dir.
+    #[serde(default = "default_transcripts_path")]
+    pub transcripts_path: String,
+
+    /// Maximum sessions retained; the least recently written are pruned.
+    #[serde(default = "default_transcripts_max_sessions")]
+    pub transcripts_max_sessions: usize,
 }
 
 impl Default for ObservabilityConfig {
@@ -2767,6 +3305,9 @@ impl Default for ObservabilityConfig {
             runtime_trace_mode: default_runtime_trace_mode(),
             runtime_trace_path: default_runtime_trace_path(),
             runtime_trace_max_entries: default_runtime_trace_max_entries(),
+            transcripts_enabled: false,
+            transcripts_path: default_transcripts_path(),
+            transcripts_max_sessions: default_transcripts_max_sessions(),
         }
     }
 }
@@ -2779,6 +3320,14 @@ fn default_runtime_trace_path() -> String {
     "state/runtime-trace.jsonl".to_string()
 }
 
+fn default_transcripts_path() -> String {
+    "state/transcripts".to_string()
+}
+
+fn default_transcripts_max_sessions() -> usize {
+    200
+}
+
 fn default_runtime_trace_max_entries() -> usize {
     200
 }
@@ -2993,6 +3542,8 @@ pub struct AutonomyConfig {
     /// - `"telegram:alice"`: allow sender `alice` only on `telegram`
     /// - `"telegram:*"`: allow any sender on `telegram`
     /// - `"*:alice"`: allow sender `alice` on any channel
+    /// - `"user:alice"`: allow any identity linked to directory user `alice`
+    /// - `"role:admin"`: allow any sender whose directory role is `admin`
     #[serde(default)]
     pub non_cli_approval_approvers: Vec<String>,
 
@@ -3702,6 +4253,59 @@ pub struct ClassificationRule {
     pub priority: i32,
 }
 
+// ── Model Cascade ────────────────────────────────────────────────
+
+/// Budget-aware model cascading (`[model_cascade]` section).
+///
+/// Requests without an explicit `hint:` are tried on the cheapest tier first
+/// and escalated to the next tier only when the answer fails its check.
+/// Escalation is skipped once it would exceed the `[cost]` budgets.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelCascadeConfig {
+    /// Enable cascading. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// `[[model_routes]]` hints to try, cheapest first. The last tier's answer
+    /// is always accepted.
+    #[serde(default)]
+    pub tiers: Vec<String>,
+    /// Ask the tier model to rate its own answer before accepting it. When
+    /// `false`, only empty, hedging or malformed answers escalate. Default: `true`.
+    #[serde(default = "default_true")]
+    pub self_check: bool,
+    /// Minimum confidence (0.0–1.0) needed to accept a tier's answer. Default: `0.7`.
+    #[serde(default = "default_cascade_min_confidence")]
+    pub min_confidence: f64,
+    /// Output tokens assumed per escalation when checking the budget. Default: `1024`.
+    #[serde(default = "default_cascade_escalation_output_tokens")]
+    pub escalation_output_tokens: u64,
+    /// Append every cascade decision to `state/cascade_decisions.jsonl` for
+    /// tuning. Default: `true`.
+    #[serde(default = "default_true")]
+    pub log_decisions: bool,
+}
+
+fn default_cascade_min_confidence() -> f64 {
+    0.7
+}
+
+fn default_cascade_escalation_output_tokens() -> u64 {
+    1024
+}
+
+impl Default for ModelCascadeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tiers: Vec::new(),
+            self_check: true,
+            min_confidence: default_cascade_min_confidence(),
+            escalation_output_tokens: default_cascade_escalation_output_tokens(),
+            log_decisions: true,
+        }
+    }
+}
+
 // ── Heartbeat ────────────────────────────────────────────────────
 
 /// Heartbeat configuration for periodic health pings (`[heartbeat]` section).
@@ -3794,6 +4398,183 @@ impl Default for CronConfig {
     }
 }
 
+// ── Notifications ───────────────────────────────────────────────
+
+/// Severity of a notification, lowest first.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl NotificationSeverity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// One place a notification is delivered to.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct NotificationDestination {
+    /// Delivery channel (`telegram`, `slack`, `discord`, `email`, ...,
+    /// or `pushover`).
+    pub channel: String,
+    /// Recipient on that channel (chat id, channel id, address). Not used
+    /// by `pushover`.
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+/// A named notification target (`[notifications.targets.<name>]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationTargetConfig {
+    /// Destinations every notification for this target is sent to.
+    #[serde(default)]
+    pub destinations: Vec<NotificationDestination>,
+    /// Person on the receiving end; their `[notifications.quiet_hours]`
+    /// entry applies to this target.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Batch non-critical notifications into one digest message every
+    /// `digest_interval_secs`.
+    #[serde(default)]
+    pub digest: bool,
+}
+
+/// Severity/source routing rule (`[[notifications.routes]]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationRouteConfig {
+    /// Lowest severity this rule matches.
+    #[serde(default)]
+    pub min_severity: NotificationSeverity,
+    /// Sources this rule matches (`agent`, `gateway`, `heartbeat`, `cron`,
+    /// `alert_forwarder`, ...). Empty matches every source.
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Targets notified when the rule matches.
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+fn default_quiet_hours_start() -> String {
+    "22:00".into()
+}
+
+fn default_quiet_hours_end() -> String {
+    "07:00".into()
+}
+
+fn default_quiet_hours_bypass() -> NotificationSeverity {
+    NotificationSeverity::Critical
+}
+
+/// Per-user quiet hours (`[notifications.quiet_hours.<user>]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QuietHoursConfig {
+    /// Start of the quiet window, `HH:MM` local time.
+    #[serde(default = "default_quiet_hours_start")]
+    pub start: String,
+    /// End of the quiet window, `HH:MM` local time. May be earlier than
+    /// `start` for windows spanning midnight.
+    #[serde(default = "default_quiet_hours_end")]
+    pub end: String,
+    /// IANA timezone (e.g. `Europe/Berlin`). Default: UTC.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Notifications at or above this severity are delivered during quiet
+    /// hours; everything else is held and sent as a digest afterwards.
+    #[serde(default = "default_quiet_hours_bypass")]
+    pub bypass_severity: NotificationSeverity,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            start: default_quiet_hours_start(),
+            end: default_quiet_hours_end(),
+            timezone: None,
+            bypass_severity: default_quiet_hours_bypass(),
+        }
+    }
+}
+
+fn default_notification_dedup_window_secs() -> u64 {
+    600
+}
+
+fn default_notification_digest_interval_secs() -> u64 {
+    900
+}
+
+/// Cross-channel notification routing (`[notifications]` section).
+///
+/// Used by the `notify` tool, `POST /api/notify`, and as the `notify`
+/// delivery channel for cron jobs and heartbeat output.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationsConfig {
+    /// Enable notification routing. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Named targets, e.g. `oncall` = Slack #ops + Pushover + email.
+    #[serde(default)]
+    pub targets: HashMap<String, NotificationTargetConfig>,
+    /// Routing rules used when a notification names no target.
+    #[serde(default)]
+    pub routes: Vec<NotificationRouteConfig>,
+    /// Target used when no route matches.
+    #[serde(default)]
+    pub default_target: Option<String>,
+    /// Quiet hours keyed by user name (see `targets.<name>.user`).
+    #[serde(default)]
+    pub quiet_hours: HashMap<String, QuietHoursConfig>,
+    /// Identical notifications within this many seconds are dropped.
+    /// `0` disables deduplication.
+    #[serde(default = "default_notification_dedup_window_secs")]
+    pub dedup_window_secs: u64,
+    /// How often digest targets are flushed.
+    #[serde(default = "default_notification_digest_interval_secs")]
+    pub digest_interval_secs: u64,
+    /// Severity of `/webhooks/{source}` alerts. Unset infers it from the
+    /// alert status (failed → `critical`, cancelled → `warning`).
+    #[serde(default)]
+    pub alert_severity: Option<NotificationSeverity>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            targets: HashMap::new(),
+            routes: Vec::new(),
+            default_target: None,
+            quiet_hours: HashMap::new(),
+            dedup_window_secs: default_notification_dedup_window_secs(),
+            digest_interval_secs: default_notification_digest_interval_secs(),
+            alert_severity: None,
+        }
+    }
+}
+
 // ── Tunnel ──────────────────────────────────────────────────────
 
 /// Tunnel configuration for exposing the gateway publicly (`[tunnel]` section).
@@ -3922,6 +4703,10 @@ pub struct ChannelsConfig {
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     /// IRC channel configuration.
     pub irc: Option<IrcConfig>,
+    /// XMPP (Jabber) channel configuration.
+    pub xmpp: Option<XmppConfig>,
+    /// Zulip channel configuration.
+    pub zulip: Option<ZulipConfig>,
     /// Lark channel configuration.
     pub lark: Option<LarkConfig>,
     /// Feishu channel configuration.
@@ -3940,6 +4725,132 @@ pub struct ChannelsConfig {
     /// Default: 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
     #[serde(default = "default_channel_message_timeout_secs")]
     pub message_timeout_secs: u64,
+    /// Largest inbound attachment (in MB) fetched into the shared download
+    /// cache (`<workspace>/state/attachment_cache`). Default: 25.
+    #[serde(default = "default_attachment_max_download_mb")]
+    pub attachment_max_download_mb: u64,
+    /// Handling of inbound message edits and deletions
+    /// (`[channels_config.message_edits]`).
+    #[serde(default)]
+    pub message_edits: MessageEditsConfig,
+    /// Outbound send queue with per-recipient rate limits and retries
+    /// (`[channels_config.outbound]`).
+    #[serde(default)]
+    pub outbound: OutboundQueueConfig,
+}
+
+/// What happens when a user edits a message whose turn is still running.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InFlightEditAction {
+    /// Cancel the running turn and answer the edited text instead.
+    #[default]
+    Rerun,
+    /// Cancel the running turn without answering.
+    Cancel,
+    /// Let the running turn finish; only history is rewritten.
+    Ignore,
+}
+
+/// Inbound message edit/delete handling (`[channels_config.message_edits]`).
+///
+/// Edits always rewrite the matching user turn in conversation history, and
+/// deletions remove it together with the reply.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MessageEditsConfig {
+    /// Act on edit/delete events at all. When `false` they are dropped.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Edit of a message whose turn is still running: `rerun`, `cancel` or `ignore`.
+    #[serde(default)]
+    pub in_flight: InFlightEditAction,
+    /// Also delete the bot's reply when the user deletes their message
+    /// (needs a channel that streams replies as editable drafts).
+    #[serde(default)]
+    pub delete_replies: bool,
+}
+
+impl Default for MessageEditsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            in_flight: InFlightEditAction::default(),
+            delete_replies: false,
+        }
+    }
+}
+
+/// Outbound rate limit for one channel
+/// (`[channels_config.outbound.limits.<channel>]`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct OutboundRateLimitConfig {
+    /// Sustained sends per second to one recipient (chat, channel or user).
+    pub per_recipient_per_sec: f64,
+    /// Sends allowed back-to-back to one recipient before throttling kicks in.
+    #[serde(default = "default_outbound_burst")]
+    pub per_recipient_burst: u32,
+    /// Sustained sends per second across all recipients. Unset means unlimited.
+    #[serde(default)]
+    pub global_per_sec: Option<f64>,
+}
+
+fn default_outbound_burst() -> u32 {
+    1
+}
+
+fn default_outbound_max_attempts() -> u32 {
+    5
+}
+
+fn default_outbound_max_retry_after_secs() -> u64 {
+    300
+}
+
+fn default_outbound_max_age_secs() -> u64 {
+    86_400
+}
+
+/// Outbound send queue (`[channels_config.outbound]`).
+///
+/// Sends are queued per channel and recipient, throttled with token buckets
+/// (built-in limits for Telegram, Discord, Slack and WhatsApp), retried on
+/// rate limits and transient errors, and optionally persisted until delivered.
+/// Off by default.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OutboundQueueConfig {
+    /// Route channel sends through the queue. When `false` (the default),
+    /// sends go straight to the platform API.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Delivery attempts per message before it is dropped.
+    #[serde(default = "default_outbound_max_attempts")]
+    pub max_attempts: u32,
+    /// Upper bound for a single wait, including platform `Retry-After` values.
+    #[serde(default = "default_outbound_max_retry_after_secs")]
+    pub max_retry_after_secs: u64,
+    /// Log queued messages in `<workspace>/state/outbound_queue.jsonl` and
+    /// resend undelivered ones after a restart.
+    #[serde(default)]
+    pub persist: bool,
+    /// Persisted messages older than this are discarded instead of resent.
+    #[serde(default = "default_outbound_max_age_secs")]
+    pub max_age_secs: u64,
+    /// Per-channel limit overrides, keyed by channel name.
+    #[serde(default)]
+    pub limits: HashMap<String, OutboundRateLimitConfig>,
+}
+
+impl Default for OutboundQueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: default_outbound_max_attempts(),
+            max_retry_after_secs: default_outbound_max_retry_after_secs(),
+            persist: false,
+            max_age_secs: default_outbound_max_age_secs(),
+            limits: HashMap::new(),
+        }
+    }
 }
 
 impl ChannelsConfig {
@@ -3999,6 +4910,14 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(self.irc.as_ref())),
                 self.irc.is_some()
             ),
+            (
+                Box::new(ConfigWrapper::new(self.xmpp.as_ref())),
+                self.xmpp.is_some()
+            ),
+            (
+                Box::new(ConfigWrapper::new(self.zulip.as_ref())),
+                self.zulip.is_some()
+            ),
             (
                 Box::new(ConfigWrapper::new(self.lark.as_ref())),
                 self.lark.is_some(),
@@ -4042,6 +4961,10 @@ fn default_channel_message_timeout_secs() -> u64 {
     300
 }
 
+fn default_attachment_max_download_mb() -> u64 {
+    25
+}
+
 impl Default for ChannelsConfig {
     fn default() -> Self {
         Self {
@@ -4060,6 +4983,8 @@ impl Default for ChannelsConfig {
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -4067,6 +4992,9 @@ impl Default for ChannelsConfig {
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: default_channel_message_timeout_secs(),
+            attachment_max_download_mb: default_attachment_max_download_mb(),
+            message_edits: MessageEditsConfig::default(),
+            outbound: OutboundQueueConfig::default(),
         }
     }
 }
@@ -4366,6 +5294,47 @@ pub struct WebhookConfig {
     pub port: u16,
     /// Optional shared secret for webhook signature verification.
     pub secret: Option<String>,
+    /// Signed outbound callbacks for asynchronous conversations.
+    /// When set, requests carrying `conversation_id` / `reply_url` are answered
+    /// by POSTing to the reply URL instead of in the HTTP response.
+    #[serde(default)]
+    pub callbacks: Option<WebhookCallbackConfig>,
+}
+
+/// Outbound callback delivery for the webhook channel (`[channels_config.webhook.callbacks]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookCallbackConfig {
+    /// HMAC-SHA256 key used to sign every callback (`X-ZeroClaw-Signature`).
+    pub signing_secret: String,
+    /// Hosts a per-request `reply_url` may point to (case-insensitive, `"*"` = any).
+    /// Empty = only `default_reply_url` is used.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Callback URL for conversations without a registered `reply_url`
+    /// (e.g. cron or delegate results addressed to a conversation id).
+    #[serde(default)]
+    pub default_reply_url: Option<String>,
+    /// Delivery attempts per callback, including the first (default: 5).
+    #[serde(default = "default_webhook_callback_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on every further retry (default: 1000).
+    #[serde(default = "default_webhook_callback_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Per-attempt request timeout in seconds (default: 10).
+    #[serde(default = "default_webhook_callback_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_webhook_callback_max_attempts() -> u32 {
+    5
+}
+
+fn default_webhook_callback_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_webhook_callback_timeout_secs() -> u64 {
+    10
 }
 
 impl ChannelConfig for WebhookConfig {
@@ -4654,6 +5623,119 @@ fn default_irc_port() -> u16 {
     6697
 }
 
+/// Transport security for the XMPP client connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum XmppTlsMode {
+    /// Plain TCP upgraded with STARTTLS (RFC 6120). Default port 5222.
+    #[default]
+    Starttls,
+    /// TLS from the first byte (XEP-0368 "direct TLS"). Default port 5223.
+    Direct,
+}
+
+/// XMPP (Jabber) channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct XmppConfig {
+    /// Bot account JID (e.g. `"zeroclaw@chat.example.com"`).
+    pub jid: String,
+    /// Account password (SASL SCRAM-SHA-256 or PLAIN over TLS).
+    pub password: String,
+    /// Server hostname. Defaults to the JID domain.
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Server port. Defaults to 5222 (`starttls`) or 5223 (`direct`).
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// How the connection is secured.
+    #[serde(default)]
+    pub tls: XmppTlsMode,
+    /// Verify the server TLS certificate (default: true).
+    #[serde(default)]
+    pub verify_tls: Option<bool>,
+    /// MUC room JIDs to join (e.g. `"ops@conference.example.com"`).
+    #[serde(default)]
+    pub rooms: Vec<String>,
+    /// Nickname used in MUC rooms. Defaults to the JID local part.
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Allowed sender bare JIDs (case-insensitive) or `"*"`. Empty = deny all.
+    /// In rooms that hide real JIDs, occupant JIDs (`room@service/nick`) are matched.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Group-chat trigger controls for MUC rooms.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+    /// Streaming mode for progressive replies via XEP-0308 message correction.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft corrections.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
+}
+
+impl ChannelConfig for XmppConfig {
+    fn name() -> &'static str {
+        "XMPP"
+    }
+    fn desc() -> &'static str {
+        "Jabber / XMPP over TLS"
+    }
+}
+
+impl XmppConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::AllMessages)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
+/// Zulip channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ZulipConfig {
+    /// Zulip organization URL (e.g. `"https://chat.example.com"`).
+    pub site: String,
+    /// Bot account email address.
+    pub email: String,
+    /// Bot API key.
+    pub api_key: String,
+    /// Stream names to listen in. Empty = every stream the bot is subscribed to.
+    #[serde(default)]
+    pub streams: Vec<String>,
+    /// Allowed sender email addresses (case-insensitive) or `"*"`. Empty = deny all.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Group-chat trigger controls for stream messages.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+}
+
+impl ChannelConfig for ZulipConfig {
+    fn name() -> &'static str {
+        "Zulip"
+    }
+    fn desc() -> &'static str {
+        "Zulip streams and direct messages"
+    }
+}
+
+impl ZulipConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::AllMessages)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
 /// How ZeroClaw receives events from Feishu / Lark.
 ///
 /// - `websocket` (default) — persistent WSS long-connection; no public URL required.
@@ -4825,6 +5907,14 @@ pub struct SecurityConfig {
     #[serde(default)]
     pub estop: EstopConfig,
 
+    /// User directory mapping channel identities to users and roles.
+    #[serde(default)]
+    pub users: UserDirectoryConfig,
+
+    /// Multi-approver quorum rules for destructive operations.
+    #[serde(default)]
+    pub approval_quorum: ApprovalQuorumConfig,
+
     /// Syscall anomaly detection profile for daemon shell/process execution.
     #[serde(default)]
     pub syscall_anomaly: SyscallAnomalyConfig,
@@ -4837,6 +5927,10 @@ pub struct SecurityConfig {
     #[serde(default)]
     pub outbound_leak_guard: OutboundLeakGuardConfig,
 
+    /// Data-loss-prevention pipeline for outbound and persisted content.
+    #[serde(default)]
+    pub dlp: DlpConfig,
+
     /// Shared URL access policy for network-enabled tools.
     #[serde(default)]
     pub url_access: UrlAccessConfig,
@@ -4853,33 +5947,207 @@ pub enum OutboundLeakGuardAction {
     Block,
 }
 
-/// Outbound credential leak guard configuration.
+/// Outbound credential leak guard configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OutboundLeakGuardConfig {
+    /// Enable outbound credential leak scanning for channel responses.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Action to take when potential credentials are detected.
+    #[serde(default)]
+    pub action: OutboundLeakGuardAction,
+
+    /// Detection sensitivity (0.0-1.0, higher = more aggressive).
+    #[serde(default = "default_outbound_leak_guard_sensitivity")]
+    pub sensitivity: f64,
+}
+
+fn default_outbound_leak_guard_sensitivity() -> f64 {
+    0.7
+}
+
+impl Default for OutboundLeakGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: OutboundLeakGuardAction::Redact,
+            sensitivity: default_outbound_leak_guard_sensitivity(),
+        }
+    }
+}
+
+/// Action taken when a DLP detector matches.
+///
+/// Ordered from least to most restrictive; when several detections apply to
+/// one piece of content the most restrictive action wins.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum DlpAction {
+    /// Replace all but the last few characters of the match with `*`.
+    Mask,
+    /// Replace the match with a `[REDACTED_<DETECTOR>]` placeholder.
+    #[default]
+    Redact,
+    /// Withhold the content until an approver releases it. Surfaces without a
+    /// human in the loop treat this like `block`.
+    RequireApproval,
+    /// Drop the content entirely.
+    Block,
+}
+
+/// Built-in PII classes. Classes with a checksum are validated before they
+/// count as a detection.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DlpPiiClass {
+    /// Email addresses.
+    Email,
+    /// International or separated phone numbers (10-15 digits).
+    Phone,
+    /// IBANs, validated with the ISO 7064 mod-97 checksum.
+    Iban,
+    /// Payment card numbers, validated with the Luhn checksum.
+    CreditCard,
+    /// US Social Security numbers in `AAA-GG-SSSS` form with invalid ranges excluded.
+    UsSsn,
+    /// Spanish DNI/NIE numbers, validated with the control letter.
+    EsDni,
+}
+
+/// Kind of a custom DLP detector.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DlpDetectorKind {
+    /// Match `pattern` as a regular expression.
+    Regex,
+    /// Match any entry in `keywords` as a whole word.
+    Keywords,
+    /// Match tokens of at least `min_length` chars whose Shannon entropy is at
+    /// least `min_entropy` bits per char.
+    Entropy,
+}
+
+/// Custom DLP detector (`[[security.dlp.detectors]]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DlpDetectorConfig {
+    /// Detector name, used in placeholders, logs and metrics.
+    pub name: String,
+
+    /// Detector kind.
+    pub kind: DlpDetectorKind,
+
+    /// Regular expression for `kind = "regex"`.
+    #[serde(default)]
+    pub pattern: Option<String>,
+
+    /// Keyword list for `kind = "keywords"`.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    /// Match keywords case-sensitively.
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// Minimum Shannon entropy (bits/char) for `kind = "entropy"`.
+    #[serde(default = "default_dlp_min_entropy")]
+    pub min_entropy: f64,
+
+    /// Minimum token length for `kind = "entropy"`.
+    #[serde(default = "default_dlp_min_length")]
+    pub min_length: usize,
+
+    /// Action override for this detector (defaults to `default_action`).
+    #[serde(default)]
+    pub action: Option<DlpAction>,
+}
+
+fn default_dlp_min_entropy() -> f64 {
+    4.0
+}
+
+fn default_dlp_min_length() -> usize {
+    20
+}
+
+/// Data-loss-prevention configuration (`[security.dlp]`).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
-pub struct OutboundLeakGuardConfig {
-    /// Enable outbound credential leak scanning for channel responses.
-    #[serde(default = "default_true")]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DlpConfig {
+    /// Enable the DLP pipeline.
+    #[serde(default)]
     pub enabled: bool,
 
-    /// Action to take when potential credentials are detected.
+    /// Action for detections without a detector-specific action.
     #[serde(default)]
-    pub action: OutboundLeakGuardAction,
+    pub default_action: DlpAction,
 
-    /// Detection sensitivity (0.0-1.0, higher = more aggressive).
+    /// Include the built-in credential patterns from the leak detector.
+    #[serde(default = "default_true")]
+    pub credentials: bool,
+
+    /// Sensitivity for built-in credential heuristics (0.0-1.0).
     #[serde(default = "default_outbound_leak_guard_sensitivity")]
     pub sensitivity: f64,
+
+    /// Built-in PII classes to detect.
+    #[serde(default)]
+    pub pii: Vec<DlpPiiClass>,
+
+    /// Custom detectors.
+    #[serde(default)]
+    pub detectors: Vec<DlpDetectorConfig>,
+
+    /// Per-channel minimum action, keyed by channel name (`gateway` for
+    /// gateway responses). Channel actions only tighten detector actions.
+    #[serde(default)]
+    pub channel_actions: HashMap<String, DlpAction>,
+
+    /// Number of trailing characters left visible by the `mask` action.
+    #[serde(default = "default_dlp_mask_visible_chars")]
+    pub mask_visible_chars: usize,
+
+    /// Scan outbound channel messages.
+    #[serde(default = "default_true")]
+    pub scan_channel_messages: bool,
+
+    /// Scan tool outputs before they are fed back to the model.
+    #[serde(default = "default_true")]
+    pub scan_tool_outputs: bool,
+
+    /// Scan content written to memory.
+    #[serde(default = "default_true")]
+    pub scan_memory_writes: bool,
+
+    /// Scan gateway (webhook, WebSocket, OpenAI-compatible) responses.
+    #[serde(default = "default_true")]
+    pub scan_gateway_responses: bool,
 }
 
-fn default_outbound_leak_guard_sensitivity() -> f64 {
-    0.7
+fn default_dlp_mask_visible_chars() -> usize {
+    4
 }
 
-impl Default for OutboundLeakGuardConfig {
+impl Default for DlpConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            action: OutboundLeakGuardAction::Redact,
+            enabled: false,
+            default_action: DlpAction::Redact,
+            credentials: true,
             sensitivity: default_outbound_leak_guard_sensitivity(),
+            pii: Vec::new(),
+            detectors: Vec::new(),
+            channel_actions: HashMap::new(),
+            mask_visible_chars: default_dlp_mask_visible_chars(),
+            scan_channel_messages: true,
+            scan_tool_outputs: true,
+            scan_memory_writes: true,
+            scan_gateway_responses: true,
         }
     }
 }
@@ -5184,6 +6452,128 @@ impl Default for EstopConfig {
     }
 }
 
+/// User directory configuration (`[security.users]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserDirectoryConfig {
+    /// Enable the user directory for channel senders.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// File path used to persist users, linked identities, and link codes.
+    #[serde(default = "default_user_directory_state_file")]
+    pub state_file: String,
+
+    /// Role applied to senders not linked to any user. Unset keeps the
+    /// existing channel-level policy for unknown senders.
+    #[serde(default)]
+    pub unknown_sender_role: Option<String>,
+
+    /// Store and recall channel memories per linked user instead of globally.
+    #[serde(default = "default_true")]
+    pub scope_memory: bool,
+
+    /// Lifetime of one-time identity link codes, in seconds.
+    #[serde(default = "default_user_link_code_ttl_secs")]
+    pub link_code_ttl_secs: u64,
+}
+
+fn default_user_directory_state_file() -> String {
+    "~/.zeroclaw/users.json".to_string()
+}
+
+fn default_user_link_code_ttl_secs() -> u64 {
+    600
+}
+
+impl Default for UserDirectoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            state_file: default_user_directory_state_file(),
+            unknown_sender_role: None,
+            scope_memory: true,
+            link_code_ttl_secs: default_user_link_code_ttl_secs(),
+        }
+    }
+}
+
+/// Approval quorum configuration (`[security.approval_quorum]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalQuorumConfig {
+    /// Enforce quorum rules for matching operations.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// File path used to persist quorum requests and their approvals.
+    #[serde(default = "default_approval_quorum_state_file")]
+    pub state_file: String,
+
+    /// Quorum rules; the first rule matching an operation applies.
+    #[serde(default)]
+    pub rules: Vec<ApprovalQuorumRuleConfig>,
+}
+
+fn default_approval_quorum_state_file() -> String {
+    "~/.zeroclaw/approval-quorum.json".to_string()
+}
+
+impl Default for ApprovalQuorumConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            state_file: default_approval_quorum_state_file(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// A single quorum rule (`[[security.approval_quorum.rules]]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalQuorumRuleConfig {
+    /// Rule name shown in approval requests and the audit log.
+    pub name: String,
+
+    /// Tool names this rule covers, or `estop_resume` for `zeroclaw estop resume`.
+    pub operations: Vec<String>,
+
+    /// Only apply to calls whose `command` argument is at least this risky
+    /// (`low`, `medium`, `high`). Unset applies to every call.
+    #[serde(default)]
+    pub min_command_risk: Option<CommandRiskLevel>,
+
+    /// Only apply when this argument is present and non-empty
+    /// (for example `commit_message` on `apply_patch`).
+    #[serde(default)]
+    pub when_argument: Option<String>,
+
+    /// Number of distinct approvers required.
+    #[serde(default = "default_approval_quorum_approvals")]
+    pub approvals: usize,
+
+    /// Approver group. Same syntax as `autonomy.non_cli_approval_approvers`,
+    /// including `user:<id>` and `role:<name>` entries.
+    pub approvers: Vec<String>,
+
+    /// Require every approver to supply a current OTP code.
+    #[serde(default)]
+    pub require_otp: bool,
+
+    /// Seconds a request stays open (and an approval stays usable).
+    #[serde(default = "default_approval_quorum_expiry_secs")]
+    pub expiry_secs: u64,
+}
+
+fn default_approval_quorum_approvals() -> usize {
+    2
+}
+
+fn default_approval_quorum_expiry_secs() -> u64 {
+    900
+}
+
 /// Syscall anomaly detection configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SyscallAnomalyConfig {
@@ -5606,11 +6996,15 @@ impl Default for Config {
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
             coordination: CoordinationConfig::default(),
+            delegate_queue: DelegateQueueConfig::default(),
             hooks: HooksConfig::default(),
             plugins: PluginsConfig::default(),
             hardware: HardwareConfig::default(),
             query_classification: QueryClassificationConfig::default(),
+            model_cascade: ModelCascadeConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
+            notifications: NotificationsConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
             mcp: McpConfig::default(),
             model_support_vision: None,
@@ -5873,100 +7267,130 @@ async fn resolve_runtime_config_dirs(
     ))
 }
 
+/// Reverses config secret protection on load and re-applies it on save.
+///
+/// On load, secret references (`secret://`, `env://`, `file://`, `exec://`)
+/// are resolved through [`crate::security::SecretResolver`] and `enc:`/`enc2:`
+/// values are decrypted. Each resolved reference is recorded so that saving
+/// writes the reference back rather than encrypting the resolved secret.
+struct ConfigSecretCodec {
+    store: crate::security::SecretStore,
+    resolver: crate::security::SecretResolver,
+    bindings: parking_lot::Mutex<Vec<crate::security::SecretReferenceBinding>>,
+}
+
+impl ConfigSecretCodec {
+    fn new(zeroclaw_dir: &Path, secrets: &SecretsConfig) -> Self {
+        Self {
+            store: crate::security::SecretStore::new(zeroclaw_dir, secrets.encrypt),
+            resolver: crate::security::SecretResolver::from_config(secrets, zeroclaw_dir),
+            bindings: parking_lot::Mutex::new(secrets.resolved_references.clone()),
+        }
+    }
+
+    fn decrypt(&self, raw: &str, field_name: &str) -> Result<String> {
+        if let Some(resolved) = self
+            .resolver
+            .resolve(raw)
+            .with_context(|| format!("Failed to resolve {field_name}"))?
+        {
+            self.bindings
+                .lock()
+                .push(crate::security::SecretReferenceBinding {
+                    field: field_name.to_string(),
+                    reference: raw.trim().to_string(),
+                    resolved: resolved.clone(),
+                });
+            return Ok(resolved);
+        }
+        if crate::security::SecretStore::is_encrypted(raw) {
+            return self
+                .store
+                .decrypt(raw)
+                .with_context(|| format!("Failed to decrypt {field_name}"));
+        }
+        Ok(raw.to_string())
+    }
+
+    fn encrypt(&self, raw: &str, field_name: &str) -> Result<String> {
+        if let Some(binding) = self
+            .bindings
+            .lock()
+            .iter()
+            .find(|binding| binding.field == field_name && binding.resolved == raw)
+        {
+            return Ok(binding.reference.clone());
+        }
+        if crate::security::SecretStore::is_encrypted(raw)
+            || crate::security::SecretReference::is_reference(raw)
+        {
+            return Ok(raw.to_string());
+        }
+        self.store
+            .encrypt(raw)
+            .with_context(|| format!("Failed to encrypt {field_name}"))
+    }
+
+    fn into_bindings(self) -> Vec<crate::security::SecretReferenceBinding> {
+        self.bindings.into_inner()
+    }
+}
+
 fn decrypt_optional_secret(
-    store: &crate::security::SecretStore,
+    store: &ConfigSecretCodec,
     value: &mut Option<String>,
     field_name: &str,
 ) -> Result<()> {
     if let Some(raw) = value.clone() {
-        if crate::security::SecretStore::is_encrypted(&raw) {
-            *value = Some(
-                store
-                    .decrypt(&raw)
-                    .with_context(|| format!("Failed to decrypt {field_name}"))?,
-            );
-        }
+        *value = Some(store.decrypt(&raw, field_name)?);
     }
     Ok(())
 }
 
-fn decrypt_secret(
-    store: &crate::security::SecretStore,
-    value: &mut String,
-    field_name: &str,
-) -> Result<()> {
-    if crate::security::SecretStore::is_encrypted(value) {
-        *value = store
-            .decrypt(value)
-            .with_context(|| format!("Failed to decrypt {field_name}"))?;
-    }
+fn decrypt_secret(store: &ConfigSecretCodec, value: &mut String, field_name: &str) -> Result<()> {
+    *value = store.decrypt(value, field_name)?;
     Ok(())
 }
 
 fn decrypt_vec_secrets(
-    store: &crate::security::SecretStore,
+    store: &ConfigSecretCodec,
     values: &mut [String],
     field_name: &str,
 ) -> Result<()> {
     for (idx, value) in values.iter_mut().enumerate() {
-        if crate::security::SecretStore::is_encrypted(value) {
-            *value = store
-                .decrypt(value)
-                .with_context(|| format!("Failed to decrypt {field_name}[{idx}]"))?;
-        }
+        *value = store.decrypt(value, &format!("{field_name}[{idx}]"))?;
     }
     Ok(())
 }
 
 fn encrypt_optional_secret(
-    store: &crate::security::SecretStore,
+    store: &ConfigSecretCodec,
     value: &mut Option<String>,
     field_name: &str,
 ) -> Result<()> {
     if let Some(raw) = value.clone() {
-        if !crate::security::SecretStore::is_encrypted(&raw) {
-            *value = Some(
-                store
-                    .encrypt(&raw)
-                    .with_context(|| format!("Failed to encrypt {field_name}"))?,
-            );
-        }
+        *value = Some(store.encrypt(&raw, field_name)?);
     }
     Ok(())
 }
 
-fn encrypt_secret(
-    store: &crate::security::SecretStore,
-    value: &mut String,
-    field_name: &str,
-) -> Result<()> {
-    if !crate::security::SecretStore::is_encrypted(value) {
-        *value = store
-            .encrypt(value)
-            .with_context(|| format!("Failed to encrypt {field_name}"))?;
-    }
+fn encrypt_secret(store: &ConfigSecretCodec, value: &mut String, field_name: &str) -> Result<()> {
+    *value = store.encrypt(value, field_name)?;
     Ok(())
 }
 
 fn encrypt_vec_secrets(
-    store: &crate::security::SecretStore,
+    store: &ConfigSecretCodec,
     values: &mut [String],
     field_name: &str,
 ) -> Result<()> {
     for (idx, value) in values.iter_mut().enumerate() {
-        if !crate::security::SecretStore::is_encrypted(value) {
-            *value = store
-                .encrypt(value)
-                .with_context(|| format!("Failed to encrypt {field_name}[{idx}]"))?;
-        }
+        *value = store.encrypt(value, &format!("{field_name}[{idx}]"))?;
     }
     Ok(())
 }
 
-fn decrypt_channel_secrets(
-    store: &crate::security::SecretStore,
-    channels: &mut ChannelsConfig,
-) -> Result<()> {
+fn decrypt_channel_secrets(store: &ConfigSecretCodec, channels: &mut ChannelsConfig) -> Result<()> {
     if let Some(ref mut telegram) = channels.telegram {
         decrypt_secret(
             store,
@@ -6006,6 +7430,13 @@ fn decrypt_channel_secrets(
             &mut webhook.secret,
             "config.channels_config.webhook.secret",
         )?;
+        if let Some(ref mut callbacks) = webhook.callbacks {
+            decrypt_secret(
+                store,
+                &mut callbacks.signing_secret,
+                "config.channels_config.webhook.callbacks.signing_secret",
+            )?;
+        }
     }
     if let Some(ref mut matrix) = channels.matrix {
         decrypt_secret(
@@ -6072,6 +7503,20 @@ fn decrypt_channel_secrets(
             "config.channels_config.irc.sasl_password",
         )?;
     }
+    if let Some(ref mut xmpp) = channels.xmpp {
+        decrypt_secret(
+            store,
+            &mut xmpp.password,
+            "config.channels_config.xmpp.password",
+        )?;
+    }
+    if let Some(ref mut zulip) = channels.zulip {
+        decrypt_secret(
+            store,
+            &mut zulip.api_key,
+            "config.channels_config.zulip.api_key",
+        )?;
+    }
     if let Some(ref mut lark) = channels.lark {
         decrypt_secret(
             store,
@@ -6125,10 +7570,7 @@ fn decrypt_channel_secrets(
     Ok(())
 }
 
-fn encrypt_channel_secrets(
-    store: &crate::security::SecretStore,
-    channels: &mut ChannelsConfig,
-) -> Result<()> {
+fn encrypt_channel_secrets(store: &ConfigSecretCodec, channels: &mut ChannelsConfig) -> Result<()> {
     if let Some(ref mut telegram) = channels.telegram {
         encrypt_secret(
             store,
@@ -6168,6 +7610,13 @@ fn encrypt_channel_secrets(
             &mut webhook.secret,
             "config.channels_config.webhook.secret",
         )?;
+        if let Some(ref mut callbacks) = webhook.callbacks {
+            encrypt_secret(
+                store,
+                &mut callbacks.signing_secret,
+                "config.channels_config.webhook.callbacks.signing_secret",
+            )?;
+        }
     }
     if let Some(ref mut matrix) = channels.matrix {
         encrypt_secret(
@@ -6234,6 +7683,20 @@ fn encrypt_channel_secrets(
             "config.channels_config.irc.sasl_password",
         )?;
     }
+    if let Some(ref mut xmpp) = channels.xmpp {
+        encrypt_secret(
+            store,
+            &mut xmpp.password,
+            "config.channels_config.xmpp.password",
+        )?;
+    }
+    if let Some(ref mut zulip) = channels.zulip {
+        encrypt_secret(
+            store,
+            &mut zulip.api_key,
+            "config.channels_config.zulip.api_key",
+        )?;
+    }
     if let Some(ref mut lark) = channels.lark {
         encrypt_secret(
             store,
@@ -6465,13 +7928,14 @@ impl Config {
             // Set computed paths that are skipped during serialization
             config.config_path = config_path.clone();
             config.workspace_dir = workspace_dir;
-            let store = crate::security::SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
+            let store = ConfigSecretCodec::new(&zeroclaw_dir, &config.secrets);
             decrypt_optional_secret(&store, &mut config.api_key, "config.api_key")?;
             decrypt_optional_secret(
                 &store,
                 &mut config.transcription.api_key,
                 "config.transcription.api_key",
             )?;
+            decrypt_optional_secret(&store, &mut config.tts.api_key, "config.tts.api_key")?;
             decrypt_optional_secret(
                 &store,
                 &mut config.composio.api_key,
@@ -6539,8 +8003,21 @@ impl Config {
             for agent in config.agents.values_mut() {
                 decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
             }
+            for peer in &mut config.coordination.peers {
+                decrypt_optional_secret(
+                    &store,
+                    &mut peer.token,
+                    "config.coordination.peers.*.token",
+                )?;
+                decrypt_optional_secret(
+                    &store,
+                    &mut peer.secret,
+                    "config.coordination.peers.*.secret",
+                )?;
+            }
 
             decrypt_channel_secrets(&store, &mut config.channels_config)?;
+            config.secrets.resolved_references = store.into_bindings();
 
             config.apply_env_overrides();
             config.validate()?;
@@ -7030,6 +8507,66 @@ impl Config {
         if self.security.estop.state_file.trim().is_empty() {
             anyhow::bail!("security.estop.state_file must not be empty");
         }
+        if self.security.users.state_file.trim().is_empty() {
+            anyhow::bail!("security.users.state_file must not be empty");
+        }
+        if let Some(role) = self.security.users.unknown_sender_role.as_deref() {
+            let normalized_role = role.trim().to_ascii_lowercase();
+            let built_in_exists = built_in_roles
+                .iter()
+                .any(|built_in| built_in == &normalized_role.as_str());
+            if !built_in_exists && !custom_role_names.contains(&normalized_role) {
+                anyhow::bail!(
+                    "security.users.unknown_sender_role references unknown role: {normalized_role}"
+                );
+            }
+        }
+        if self.security.users.link_code_ttl_secs == 0 {
+            anyhow::bail!("security.users.link_code_ttl_secs must be greater than 0");
+        }
+        if self.security.approval_quorum.enabled {
+            let quorum = &self.security.approval_quorum;
+            if quorum.state_file.trim().is_empty() {
+                anyhow::bail!("security.approval_quorum.state_file must not be empty");
+            }
+            let mut rule_names = std::collections::HashSet::new();
+            for (i, rule) in quorum.rules.iter().enumerate() {
+                let name = rule.name.trim();
+                if name.is_empty() {
+                    anyhow::bail!("security.approval_quorum.rules[{i}].name must not be empty");
+                }
+                if !rule_names.insert(name.to_ascii_lowercase()) {
+                    anyhow::bail!("security.approval_quorum.rules contains duplicate rule: {name}");
+                }
+                if rule.operations.is_empty()
+                    || rule.operations.iter().any(|op| op.trim().is_empty())
+                {
+                    anyhow::bail!(
+                        "security.approval_quorum.rules[{i}].operations must list non-empty operation names"
+                    );
+                }
+                if rule.approvals == 0 {
+                    anyhow::bail!(
+                        "security.approval_quorum.rules[{i}].approvals must be greater than 0"
+                    );
+                }
+                if rule.approvers.iter().all(|entry| entry.trim().is_empty()) {
+                    anyhow::bail!(
+                        "security.approval_quorum.rules[{i}].approvers must not be empty"
+                    );
+                }
+                if rule.require_otp && !self.security.otp.enabled {
+                    anyhow::bail!(
+                        "security.approval_quorum.rules[{i}].require_otp=true requires security.otp.enabled=true"
+                    );
+                }
+                if rule.expiry_secs == 0 {
+                    anyhow::bail!(
+                        "security.approval_quorum.rules[{i}].expiry_secs must be greater than 0"
+                    );
+                }
+            }
+        }
         if self.security.syscall_anomaly.max_denied_events_per_minute == 0 {
             anyhow::bail!(
                 "security.syscall_anomaly.max_denied_events_per_minute must be greater than 0"
@@ -7095,6 +8632,10 @@ impl Config {
         if !(0.0..=1.0).contains(&self.security.outbound_leak_guard.sensitivity) {
             anyhow::bail!("security.outbound_leak_guard.sensitivity must be between 0.0 and 1.0");
         }
+        if self.security.dlp.enabled {
+            crate::security::dlp::DlpEngine::from_config(&self.security.dlp)
+                .context("Invalid security.dlp configuration")?;
+        }
 
         // Browser
         if normalize_browser_open_choice(&self.browser.browser_open).is_none() {
@@ -7203,6 +8744,12 @@ impl Config {
             anyhow::bail!("provider.transport must be one of: auto, websocket, sse");
         }
 
+        if self.provider.cassette.mode != CassetteMode::Off
+            && self.provider.cassette.resolved_path().is_none()
+        {
+            anyhow::bail!("provider.cassette.path is required when provider.cassette.mode is set");
+        }
+
         if self.provider_api.is_some()
             && !self
                 .default_provider
@@ -7543,6 +9090,35 @@ impl Config {
             }
         }
 
+        // Provider cassette: ZEROCLAW_PROVIDER_CASSETTE_MODE, ZEROCLAW_PROVIDER_CASSETTE,
+        // ZEROCLAW_PROVIDER_CASSETTE_MATCH
+        if let Ok(mode) = std::env::var("ZEROCLAW_PROVIDER_CASSETTE_MODE") {
+            match mode.trim().to_ascii_lowercase().as_str() {
+                "off" | "" => self.provider.cassette.mode = CassetteMode::Off,
+                "record" => self.provider.cassette.mode = CassetteMode::Record,
+                "replay" => self.provider.cassette.mode = CassetteMode::Replay,
+                other => tracing::warn!(
+                    mode = other,
+                    "Ignoring invalid ZEROCLAW_PROVIDER_CASSETTE_MODE (valid: off|record|replay)"
+                ),
+            }
+        }
+        if let Ok(path) = std::env::var("ZEROCLAW_PROVIDER_CASSETTE") {
+            if !path.trim().is_empty() {
+                self.provider.cassette.path = Some(path.trim().to_string());
+            }
+        }
+        if let Ok(match_mode) = std::env::var("ZEROCLAW_PROVIDER_CASSETTE_MATCH") {
+            match match_mode.trim().to_ascii_lowercase().as_str() {
+                "strict" => self.provider.cassette.match_mode = CassetteMatchMode::Strict,
+                "fuzzy" => self.provider.cassette.match_mode = CassetteMatchMode::Fuzzy,
+                other => tracing::warn!(
+                    match_mode = other,
+                    "Ignoring invalid ZEROCLAW_PROVIDER_CASSETTE_MATCH (valid: strict|fuzzy)"
+                ),
+            }
+        }
+
         // Vision support override: ZEROCLAW_MODEL_SUPPORT_VISION or MODEL_SUPPORT_VISION
         if let Ok(flag) = std::env::var("ZEROCLAW_MODEL_SUPPORT_VISION")
             .or_else(|_| std::env::var("MODEL_SUPPORT_VISION"))
@@ -7975,7 +9551,7 @@ impl Config {
             .config_path
             .parent()
             .context("Config path must have a parent directory")?;
-        let store = crate::security::SecretStore::new(zeroclaw_dir, self.secrets.encrypt);
+        let store = ConfigSecretCodec::new(zeroclaw_dir, &self.secrets);
 
         encrypt_optional_secret(&store, &mut config_to_save.api_key, "config.api_key")?;
         encrypt_optional_secret(
@@ -7983,6 +9559,11 @@ impl Config {
             &mut config_to_save.transcription.api_key,
             "config.transcription.api_key",
         )?;
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.tts.api_key,
+            "config.tts.api_key",
+        )?;
         encrypt_optional_secret(
             &store,
             &mut config_to_save.composio.api_key,
@@ -8051,6 +9632,15 @@ impl Config {
             encrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
         }
 
+        for peer in &mut config_to_save.coordination.peers {
+            encrypt_optional_secret(&store, &mut peer.token, "config.coordination.peers.*.token")?;
+            encrypt_optional_secret(
+                &store,
+                &mut peer.secret,
+                "config.coordination.peers.*.secret",
+            )?;
+        }
+
         encrypt_channel_secrets(&store, &mut config_to_save.channels_config)?;
 
         let toml_str =
@@ -8299,6 +9889,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
 
@@ -8628,11 +10220,13 @@ default_temperature = 0.7
             reliability: ReliabilityConfig::default(),
             scheduler: SchedulerConfig::default(),
             coordination: CoordinationConfig::default(),
+            delegate_queue: DelegateQueueConfig::default(),
             skills: SkillsConfig::default(),
             plugins: PluginsConfig::default(),
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
+            model_cascade: ModelCascadeConfig::default(),
             heartbeat: HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 15,
@@ -8668,6 +10262,8 @@ default_temperature = 0.7
                 nextcloud_talk: None,
                 email: None,
                 irc: None,
+                xmpp: None,
+                zulip: None,
                 lark: None,
                 feishu: None,
                 dingtalk: None,
@@ -8675,6 +10271,9 @@ default_temperature = 0.7
                 nostr: None,
                 clawdtalk: None,
                 message_timeout_secs: 300,
+                attachment_max_download_mb: 25,
+                message_edits: MessageEditsConfig::default(),
+                outbound: OutboundQueueConfig::default(),
             },
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
@@ -8697,6 +10296,8 @@ default_temperature = 0.7
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
+            notifications: NotificationsConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
             mcp: McpConfig::default(),
             model_support_vision: None,
@@ -9041,11 +10642,13 @@ tool_dispatcher = "xml"
             reliability: ReliabilityConfig::default(),
             scheduler: SchedulerConfig::default(),
             coordination: CoordinationConfig::default(),
+            delegate_queue: DelegateQueueConfig::default(),
             skills: SkillsConfig::default(),
             plugins: PluginsConfig::default(),
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
+            model_cascade: ModelCascadeConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
             goal_loop: GoalLoopConfig::default(),
@@ -9071,6 +10674,8 @@ tool_dispatcher = "xml"
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
+            notifications: NotificationsConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
             mcp: McpConfig::default(),
             model_support_vision: None,
@@ -9146,6 +10751,8 @@ tool_dispatcher = "xml"
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
 
@@ -9600,6 +11207,8 @@ allowed_users = ["@ops:matrix.org"]
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -9607,6 +11216,9 @@ allowed_users = ["@ops:matrix.org"]
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            attachment_max_download_mb: 25,
+            message_edits: MessageEditsConfig::default(),
+            outbound: OutboundQueueConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -9878,6 +11490,8 @@ channel_id = "C123"
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -9885,6 +11499,9 @@ channel_id = "C123"
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            attachment_max_download_mb: 25,
+            message_edits: MessageEditsConfig::default(),
+            outbound: OutboundQueueConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -10112,7 +11729,10 @@ enable = true
 
     #[test]
     async fn secrets_config_serde_roundtrip() {
-        let s = SecretsConfig { encrypt: false };
+        let s = SecretsConfig {
+            encrypt: false,
+            ..SecretsConfig::default()
+        };
         let toml_str = toml::to_string(&s).unwrap();
         let parsed: SecretsConfig = toml::from_str(&toml_str).unwrap();
         assert!(!parsed.encrypt);
@@ -10976,6 +12596,64 @@ provider_api = "not-a-real-mode"
         let _ = fs::remove_dir_all(temp_home).await;
     }
 
+    #[test]
+    async fn load_or_init_resolves_secret_references_and_save_preserves_them() {
+        let _env_guard = env_override_lock().await;
+        let temp_home =
+            std::env::temp_dir().join(format!("zeroclaw_test_home_{}", uuid::Uuid::new_v4()));
+        let workspace_dir = temp_home.join("profile-secrets");
+        fs::create_dir_all(&workspace_dir).await.unwrap();
+
+        let original_home = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &temp_home);
+        std::env::set_var("ZEROCLAW_WORKSPACE", &workspace_dir);
+
+        use crate::security::SecretBackend;
+        crate::security::secret_backends::LocalVaultBackend::new(&workspace_dir)
+            .set("openrouter", "sk-from-vault")
+            .unwrap();
+        let token_file = workspace_dir.join("telegram_token");
+        fs::write(&token_file, "123:from-file\n").await.unwrap();
+
+        let mut seed = Config::default();
+        seed.config_path = workspace_dir.join("config.toml");
+        seed.workspace_dir = workspace_dir.join("workspace");
+        seed.api_key = Some("secret://openrouter".into());
+        seed.channels_config.telegram = Some(TelegramConfig {
+            bot_token: format!("file://{}", token_file.display()),
+            allowed_users: vec!["*".into()],
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            interrupt_on_new_message: false,
+            mention_only: false,
+            group_reply: None,
+            base_url: None,
+            ack_enabled: true,
+        });
+        seed.save().await.unwrap();
+
+        let config = Config::load_or_init().await.unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("sk-from-vault"));
+        assert_eq!(
+            config.channels_config.telegram.as_ref().unwrap().bot_token,
+            "123:from-file"
+        );
+
+        config.save().await.unwrap();
+        let persisted = fs::read_to_string(&config.config_path).await.unwrap();
+        assert!(persisted.contains("secret://openrouter"));
+        assert!(persisted.contains("file://"));
+        assert!(!persisted.contains("sk-from-vault"));
+
+        std::env::remove_var("ZEROCLAW_WORKSPACE");
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(temp_home).await;
+    }
+
     #[test]
     async fn load_or_init_workspace_suffix_uses_legacy_config_layout() {
         let _env_guard = env_override_lock().await;
@@ -11328,6 +13006,34 @@ default_model = "legacy-model"
         std::env::remove_var("PROVIDER_TRANSPORT");
     }
 
+    #[test]
+    async fn env_override_provider_cassette_sets_mode_path_and_match() {
+        let _env_guard = env_override_lock().await;
+        let mut config = Config::default();
+
+        std::env::set_var("ZEROCLAW_PROVIDER_CASSETTE_MODE", "Replay");
+        std::env::set_var("ZEROCLAW_PROVIDER_CASSETTE", "cassettes/hello.json");
+        std::env::set_var("ZEROCLAW_PROVIDER_CASSETTE_MATCH", "fuzzy");
+        config.apply_env_overrides();
+        assert_eq!(config.provider.cassette.mode, CassetteMode::Replay);
+        assert_eq!(
+            config.provider.cassette.resolved_path(),
+            Some(PathBuf::from("cassettes/hello.json"))
+        );
+        assert_eq!(
+            config.provider.cassette.match_mode,
+            CassetteMatchMode::Fuzzy
+        );
+        assert!(config.validate().is_ok());
+
+        config.provider.cassette.path = None;
+        assert!(config.validate().is_err());
+
+        std::env::remove_var("ZEROCLAW_PROVIDER_CASSETTE_MODE");
+        std::env::remove_var("ZEROCLAW_PROVIDER_CASSETTE");
+        std::env::remove_var("ZEROCLAW_PROVIDER_CASSETTE_MATCH");
+    }
+
     #[test]
     async fn env_override_provider_transport_invalid_zeroclaw_does_not_override_existing() {
         let _env_guard = env_override_lock().await;
@@ -12105,6 +13811,39 @@ default_model = "legacy-model"
         assert_eq!(tc.model, "whisper-large-v3-turbo");
         assert!(tc.language.is_none());
         assert_eq!(tc.max_duration_secs, 120);
+        assert_eq!(tc.backend, TranscriptionBackend::Api);
+        assert_eq!(tc.local.whisper_binary, "whisper-cli");
+        assert_eq!(tc.local.chunk_secs, 300);
+    }
+
+    #[test]
+    async fn tts_config_parses_reply_policies() {
+        let tts: TtsConfig = toml::from_str(
+            r#"
+enabled = true
+provider = "elevenlabs"
+reply_mode = "always"
+
+[channels]
+whatsapp = "never"
+
+[users]
+"42" = "when_voice"
+"#,
+        )
+        .unwrap();
+        assert!(tts.enabled);
+        assert_eq!(tts.provider, TtsProviderKind::ElevenLabs);
+        assert_eq!(tts.reply_mode, TtsReplyMode::Always);
+        assert_eq!(tts.channels.get("whatsapp"), Some(&TtsReplyMode::Never));
+        assert_eq!(tts.users.get("42"), Some(&TtsReplyMode::WhenVoice));
+        assert_eq!(tts.ffmpeg_binary, "ffmpeg");
+        assert_eq!(tts.max_chars, 1500);
+
+        let defaults = TtsConfig::default();
+        assert!(!defaults.enabled);
+        assert_eq!(defaults.provider, TtsProviderKind::OpenAi);
+        assert_eq!(defaults.reply_mode, TtsReplyMode::WhenVoice);
     }
 
     #[test]
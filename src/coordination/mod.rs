@@ -1,3 +1,5 @@
+pub mod remote;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -986,7 +988,14 @@ impl InMemoryMessageBus {
         self.lock_state().dead_letters.clone()
     }
 
-    fn push_dead_letter(&self, envelope: CoordinationEnvelope, reason: String) {
+    /// Whether an envelope id is still inside the idempotency window.
+    pub fn has_seen_message(&self, message_id: &str) -> bool {
+        self.lock_state().seen_message_ids.contains(message_id)
+    }
+
+    /// Record an envelope that could not be delivered, e.g. by a remote transport
+    /// that exhausted its retries.
+    pub fn push_dead_letter(&self, envelope: CoordinationEnvelope, reason: String) {
         let mut state = self.lock_state();
         push_dead_letter_locked(&mut state, envelope, reason);
     }
@@ -0,0 +1,837 @@
+//! Networked coordination transport between gateways.
+//!
+//! Envelopes are POSTed to a peer's [`ENVELOPE_PATH`] endpoint with the peer's
+//! pairing bearer token and an HMAC signature keyed with the secret shared with
+//! that peer, so a node can only speak as itself. The receiver answers with an `Ack` envelope once the
+//! message is accepted into its bus. Senders retry on transport errors and
+//! retryable HTTP statuses (at-least-once delivery) and dead-letter the envelope
+//! into the local bus once retries are exhausted or the peer rejects it.
+//! Receivers acknowledge redelivered message ids without processing them again.
+
+use super::{CoordinationEnvelope, CoordinationError, CoordinationPayload, InMemoryMessageBus};
+use crate::config::{CoordinationConfig, CoordinationPeerConfig};
+use crate::tools::Tool;
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, Mac};
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Gateway route that accepts coordination envelopes from peers.
+pub const ENVELOPE_PATH: &str = "/api/coordination/envelope";
+/// Header carrying the sending node's `coordination.node_id`.
+pub const NODE_HEADER: &str = "X-Coordination-Node";
+/// Header carrying the signing time (unix seconds).
+pub const TIMESTAMP_HEADER: &str = "X-Coordination-Timestamp";
+/// Header carrying the envelope signature, see [`sign_envelope`].
+pub const SIGNATURE_HEADER: &str = "X-Coordination-Signature";
+
+/// Largest accepted difference between a signed timestamp and the local clock.
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Upper bound for a single retry delay.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Outcome of a task delegated to an agent on a peer node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTaskResult {
+    pub success: bool,
+    pub output: String,
+}
+
+/// Reasons an inbound envelope is refused.
+#[derive(Debug, Error)]
+pub enum InboundError {
+    #[error("unknown coordination peer `{0}`")]
+    UnknownPeer(String),
+    #[error("coordination peer `{peer}` is not authenticated: {reason}")]
+    Unauthenticated { peer: String, reason: &'static str },
+    #[error("invalid coordination envelope: {0}")]
+    InvalidEnvelope(String),
+    #[error("agent `{0}` is not served by this node")]
+    UnknownAgent(String),
+    #[error(transparent)]
+    Rejected(#[from] CoordinationError),
+}
+
+impl InboundError {
+    /// HTTP status the gateway answers with. All of these are permanent, so
+    /// senders dead-letter instead of retrying.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::UnknownPeer(_) => 403,
+            Self::Unauthenticated { .. } => 401,
+            Self::UnknownAgent(_) => 404,
+            Self::Rejected(CoordinationError::ContextVersionMismatch { .. }) => 409,
+            Self::InvalidEnvelope(_) | Self::Rejected(_) => 400,
+        }
+    }
+}
+
+/// Sends envelopes to and accepts envelopes from peer gateways.
+pub struct RemoteCoordinator {
+    node_id: String,
+    lead_agent: String,
+    peers: Vec<CoordinationPeerConfig>,
+    bus: InMemoryMessageBus,
+    client: reqwest::Client,
+    max_attempts: u32,
+    retry_backoff: Duration,
+    task_timeout: Duration,
+    /// Runs inbound `DelegateTask`s; invoked like the `delegate` tool.
+    executor: Option<Arc<dyn Tool>>,
+    local_agents: HashSet<String>,
+    pending_results: Mutex<HashMap<String, oneshot::Sender<RemoteTaskResult>>>,
+}
+
+impl RemoteCoordinator {
+    pub fn new(
+        config: &CoordinationConfig,
+        bus: InMemoryMessageBus,
+        lead_agent: impl Into<String>,
+    ) -> Self {
+        let node_id = config.node_id.trim();
+        Self {
+            node_id: if node_id.is_empty() {
+                "local".to_string()
+            } else {
+                node_id.to_string()
+            },
+            lead_agent: lead_agent.into(),
+            peers: config.peers.clone(),
+            bus,
+            client: crate::config::build_runtime_proxy_client_with_timeouts(
+                "coordination.remote",
+                REQUEST_TIMEOUT_SECS,
+                CONNECT_TIMEOUT_SECS,
+            ),
+            max_attempts: config.remote_max_attempts.max(1),
+            retry_backoff: Duration::from_millis(config.remote_retry_backoff_ms),
+            task_timeout: Duration::from_secs(config.remote_task_timeout_secs.max(1)),
+            executor: None,
+            local_agents: HashSet::new(),
+            pending_results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serve inbound `DelegateTask`s for `local_agents` through `executor`.
+    pub fn with_executor(
+        mut self,
+        executor: Arc<dyn Tool>,
+        local_agents: impl IntoIterator<Item = String>,
+    ) -> Self {
+        self.executor = Some(executor);
+        self.local_agents = local_agents.into_iter().collect();
+        self
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    pub fn bus(&self) -> &InMemoryMessageBus {
+        &self.bus
+    }
+
+    pub fn peer(&self, name: &str) -> Option<&CoordinationPeerConfig> {
+        self.peers.iter().find(|peer| peer.name == name)
+    }
+
+    /// First peer that advertises `agent`.
+    pub fn peer_for_agent(&self, agent: &str) -> Option<&CoordinationPeerConfig> {
+        self.peers
+            .iter()
+            .find(|peer| peer.agents.iter().any(|candidate| candidate == agent))
+    }
+
+    /// `(agent, peer)` pairs reachable through this node.
+    pub fn remote_agents(&self) -> Vec<(String, String)> {
+        self.peers
+            .iter()
+            .flat_map(|peer| {
+                peer.agents
+                    .iter()
+                    .map(|agent| (agent.clone(), peer.name.clone()))
+            })
+            .collect()
+    }
+
+    /// Delegate `prompt` to `agent` on the peer that serves it and wait for the
+    /// peer's `TaskResult`.
+    pub async fn delegate(
+        &self,
+        agent: &str,
+        prompt: &str,
+        context: &str,
+    ) -> Result<RemoteTaskResult> {
+        let peer = self
+            .peer_for_agent(agent)
+            .with_context(|| format!("no coordination peer serves agent '{agent}'"))?
+            .name
+            .clone();
+        let task_id = Uuid::new_v4().to_string();
+        let mut envelope = CoordinationEnvelope::new_direct(
+            self.lead_agent.clone(),
+            agent,
+            format!("delegate:{task_id}"),
+            "delegate.request",
+            CoordinationPayload::DelegateTask {
+                task_id: task_id.clone(),
+                summary: prompt.to_string(),
+                metadata: json!({
+                    "context": context,
+                    "origin_node": self.node_id,
+                }),
+            },
+        );
+        envelope.correlation_id = Some(task_id.clone());
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_results.lock().insert(task_id.clone(), tx);
+        if let Err(error) = self.send(&peer, envelope).await {
+            self.pending_results.lock().remove(&task_id);
+            return Err(error);
+        }
+
+        match tokio::time::timeout(self.task_timeout, rx).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => bail!("remote delegation to '{agent}@{peer}' was dropped"),
+            Err(_) => {
+                self.pending_results.lock().remove(&task_id);
+                bail!(
+                    "remote delegation to '{agent}@{peer}' timed out after {}s",
+                    self.task_timeout.as_secs()
+                )
+            }
+        }
+    }
+
+    /// Deliver `envelope` to `peer_name`, retrying until the peer acknowledges it.
+    /// Undeliverable envelopes are dead-lettered in the local bus.
+    pub async fn send(&self, peer_name: &str, envelope: CoordinationEnvelope) -> Result<()> {
+        let Some(peer) = self.peer(peer_name) else {
+            let reason = format!("unknown coordination peer '{peer_name}'");
+            self.bus.push_dead_letter(envelope, reason.clone());
+            bail!(reason);
+        };
+        if let Err(error) = envelope.validate() {
+            self.bus.push_dead_letter(envelope, error.to_string());
+            return Err(error.into());
+        }
+
+        let mut last_error = String::new();
+        for attempt in 1..=self.max_attempts {
+            match self.post_envelope(peer, &envelope).await {
+                Ok(()) => return Ok(()),
+                Err(DeliveryError::Permanent(reason)) => {
+                    last_error = reason;
+                    break;
+                }
+                Err(DeliveryError::Retryable(reason)) => {
+                    tracing::debug!(
+                        peer = %peer.name,
+                        message_id = %envelope.id,
+                        attempt,
+                        "coordination delivery failed: {reason}"
+                    );
+                    last_error = reason;
+                    if attempt < self.max_attempts {
+                        tokio::time::sleep(self.backoff_for(attempt)).await;
+                    }
+                }
+            }
+        }
+
+        let reason = format!("delivery to peer '{}' failed: {last_error}", peer.name);
+        tracing::warn!(message_id = %envelope.id, "coordination: {reason}");
+        self.bus.push_dead_letter(envelope, reason.clone());
+        bail!(reason)
+    }
+
+    fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << (attempt - 1).min(16);
+        self.retry_backoff
+            .saturating_mul(factor)
+            .min(MAX_RETRY_BACKOFF)
+    }
+
+    async fn post_envelope(
+        &self,
+        peer: &CoordinationPeerConfig,
+        envelope: &CoordinationEnvelope,
+    ) -> Result<(), DeliveryError> {
+        let Some(secret) = peer.secret.as_deref().filter(|secret| !secret.is_empty()) else {
+            return Err(DeliveryError::Permanent(format!(
+                "no shared secret configured for peer '{}'",
+                peer.name
+            )));
+        };
+        let body = serde_json::to_string(envelope)
+            .map_err(|error| DeliveryError::Permanent(error.to_string()))?;
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = sign_envelope(secret, &self.node_id, &timestamp, &body);
+
+        let url = format!("{}{ENVELOPE_PATH}", peer.url.trim_end_matches('/'));
+        let mut request = self
+            .client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(NODE_HEADER, &self.node_id)
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(SIGNATURE_HEADER, signature)
+            .body(body);
+        if let Some(token) = peer.token.as_deref().filter(|token| !token.is_empty()) {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| DeliveryError::Retryable(error.to_string()))?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            let detail = body
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or("no error detail");
+            let reason = format!("HTTP {status}: {detail}");
+            return if status.is_server_error()
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            {
+                Err(DeliveryError::Retryable(reason))
+            } else {
+                Err(DeliveryError::Permanent(reason))
+            };
+        }
+
+        let acked = body
+            .get("ack")
+            .cloned()
+            .and_then(|ack| serde_json::from_value::<CoordinationEnvelope>(ack).ok())
+            .is_some_and(|ack| {
+                matches!(
+                    ack.payload,
+                    CoordinationPayload::Ack { ref acked_message_id }
+                        if *acked_message_id == envelope.id
+                )
+            });
+        if acked {
+            Ok(())
+        } else {
+            Err(DeliveryError::Retryable(
+                "peer response did not acknowledge the message".into(),
+            ))
+        }
+    }
+
+    /// Check that `body` was signed by `from_node` with the secret shared with
+    /// it, recently enough to rule out replays of old captures.
+    fn authenticate(
+        &self,
+        from_node: &str,
+        timestamp: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> Result<(), InboundError> {
+        let peer = self
+            .peer(from_node)
+            .ok_or_else(|| InboundError::UnknownPeer(from_node.to_string()))?;
+        let unauthenticated = |reason| InboundError::Unauthenticated {
+            peer: from_node.to_string(),
+            reason,
+        };
+        let secret = peer
+            .secret
+            .as_deref()
+            .filter(|secret| !secret.is_empty())
+            .ok_or_else(|| unauthenticated("no shared secret configured"))?;
+        let signed_at: i64 = timestamp
+            .parse()
+            .map_err(|_| unauthenticated("missing or invalid timestamp"))?;
+        if (chrono::Utc::now().timestamp() - signed_at).abs() > MAX_CLOCK_SKEW_SECS {
+            return Err(unauthenticated("timestamp outside the allowed clock skew"));
+        }
+        let signature = signature
+            .strip_prefix("sha256=")
+            .and_then(|hex_signature| hex::decode(hex_signature).ok())
+            .ok_or_else(|| unauthenticated("missing or malformed signature"))?;
+        envelope_mac(secret, from_node, timestamp, body)
+            .verify_slice(&signature)
+            .map_err(|_| unauthenticated("signature mismatch"))
+    }
+
+    /// Accept an authenticated envelope from `from_node` and return the `Ack`
+    /// to answer with.
+    fn receive(
+        self: &Arc<Self>,
+        from_node: &str,
+        envelope: CoordinationEnvelope,
+    ) -> Result<(CoordinationEnvelope, bool), InboundError> {
+        if self.bus.has_seen_message(&envelope.id) {
+            return Ok((self.ack_for(&envelope), true));
+        }
+
+        if let CoordinationPayload::DelegateTask { .. } = &envelope.payload {
+            let target = envelope.to.clone().unwrap_or_default();
+            if self.executor.is_none() || !self.local_agents.contains(&target) {
+                return Err(InboundError::UnknownAgent(target));
+            }
+        }
+
+        let ack = self.ack_for(&envelope);
+        match self.bus.publish(envelope.clone()) {
+            Ok(_) => {}
+            Err(CoordinationError::DuplicateMessageId { .. }) => return Ok((ack, true)),
+            Err(error) => return Err(error.into()),
+        }
+
+        match envelope.payload {
+            CoordinationPayload::DelegateTask { .. } => {
+                let coordinator = Arc::clone(self);
+                let from_node = from_node.to_string();
+                tokio::spawn(async move {
+                    coordinator.run_inbound_task(&from_node, envelope).await;
+                });
+            }
+            CoordinationPayload::TaskResult {
+                task_id,
+                success,
+                output,
+            } => {
+                if let Some(waiter) = self.pending_results.lock().remove(&task_id) {
+                    let _ = waiter.send(RemoteTaskResult { success, output });
+                }
+            }
+            CoordinationPayload::ContextPatch { .. }
+            | CoordinationPayload::Ack { .. }
+            | CoordinationPayload::Control { .. } => {}
+        }
+
+        Ok((ack, false))
+    }
+
+    /// Authenticate and accept a raw envelope body POSTed by `from_node`,
+    /// mapped to an HTTP status code and JSON body.
+    pub fn handle_inbound(
+        self: &Arc<Self>,
+        from_node: &str,
+        timestamp: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> (u16, Value) {
+        let result = self
+            .authenticate(from_node, timestamp, signature, body)
+            .and_then(|()| {
+                serde_json::from_slice::<CoordinationEnvelope>(body)
+                    .map_err(|error| InboundError::InvalidEnvelope(error.to_string()))
+            })
+            .and_then(|envelope| self.receive(from_node, envelope));
+        match result {
+            Ok((ack, duplicate)) => (
+                200,
+                json!({
+                    "ok": true,
+                    "duplicate": duplicate,
+                    "ack": ack,
+                }),
+            ),
+            Err(error) => (error.http_status(), json!({ "error": error.to_string() })),
+        }
+    }
+
+    fn ack_for(&self, envelope: &CoordinationEnvelope) -> CoordinationEnvelope {
+        let mut ack = CoordinationEnvelope::new_direct(
+            self.node_id.clone(),
+            envelope.from.clone(),
+            envelope.conversation_id.clone(),
+            "coordination.ack",
+            CoordinationPayload::Ack {
+                acked_message_id: envelope.id.clone(),
+            },
+        );
+        ack.correlation_id = envelope.correlation_id.clone();
+        ack.causation_id = Some(envelope.id.clone());
+        ack
+    }
+
+    async fn run_inbound_task(&self, from_node: &str, request: CoordinationEnvelope) {
+        let CoordinationPayload::DelegateTask {
+            task_id,
+            summary,
+            metadata,
+        } = &request.payload
+        else {
+            return;
+        };
+        let Some(executor) = &self.executor else {
+            return;
+        };
+        let agent = request.to.clone().unwrap_or_default();
+        let context = metadata
+            .get("context")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        let (success, output) = match executor
+            .execute(json!({
+                "agent": agent,
+                "prompt": summary,
+                "context": context,
+            }))
+            .await
+        {
+            Ok(result) if result.success => (true, result.output),
+            Ok(result) => (
+                false,
+                result
+                    .error
+                    .filter(|error| !error.is_empty())
+                    .unwrap_or(result.output),
+            ),
+            Err(error) => (false, error.to_string()),
+        };
+        let output = if output.trim().is_empty() {
+            "(no output)".to_string()
+        } else {
+            output
+        };
+
+        let mut result = CoordinationEnvelope::new_direct(
+            agent.clone(),
+            request.from.clone(),
+            request.conversation_id.clone(),
+            "delegate.result",
+            CoordinationPayload::TaskResult {
+                task_id: task_id.clone(),
+                success,
+                output,
+            },
+        );
+        result.correlation_id = Some(
+            request
+                .correlation_id
+                .clone()
+                .unwrap_or_else(|| task_id.clone()),
+        );
+        result.causation_id = Some(request.id.clone());
+        if let Err(error) = self.send(from_node, result).await {
+            tracing::warn!(
+                "coordination: result for task '{task_id}' on agent '{agent}' was not delivered: {error}"
+            );
+        }
+    }
+}
+
+enum DeliveryError {
+    Retryable(String),
+    Permanent(String),
+}
+
+/// `X-Coordination-Signature` value: `sha256=` + hex HMAC-SHA256 of
+/// `"{node}.{timestamp}.{body}"` keyed with the secret shared by both peers.
+pub fn sign_envelope(secret: &str, node: &str, timestamp: &str, body: &str) -> String {
+    let mac = envelope_mac(secret, node, timestamp, body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn envelope_mac(secret: &str, node: &str, timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(node.as_bytes());
+    mac.update(b".");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+fn registry() -> &'static RwLock<Option<Arc<RemoteCoordinator>>> {
+    static REGISTRY: OnceLock<RwLock<Option<Arc<RemoteCoordinator>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(None))
+}
+
+/// Make `coordinator` the process-wide endpoint for inbound peer traffic.
+pub fn install(coordinator: Arc<RemoteCoordinator>) {
+    *registry().write() = Some(coordinator);
+}
+
+/// The installed coordinator, if any peers are configured.
+pub fn current() -> Option<Arc<RemoteCoordinator>> {
+    registry().read().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+    use axum::body::Bytes;
+    use axum::extract::Json;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use tokio::net::TcpListener;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "delegate"
+        }
+
+        fn description(&self) -> &str {
+            "echo"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, args: Value) -> Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "{} handled: {}",
+                    args["agent"].as_str().unwrap_or_default(),
+                    args["prompt"].as_str().unwrap_or_default()
+                ),
+                error: None,
+            })
+        }
+    }
+
+    fn peer(name: &str, url: &str, agents: &[&str]) -> CoordinationPeerConfig {
+        CoordinationPeerConfig {
+            name: name.into(),
+            url: url.into(),
+            token: Some("zc_test".into()),
+            secret: Some("shared-test-secret".into()),
+            agents: agents.iter().map(|agent| (*agent).to_string()).collect(),
+        }
+    }
+
+    fn coordinator(
+        node_id: &str,
+        peers: Vec<CoordinationPeerConfig>,
+        agents: &[&str],
+    ) -> RemoteCoordinator {
+        let config = CoordinationConfig {
+            node_id: node_id.into(),
+            peers,
+            remote_max_attempts: 2,
+            remote_retry_backoff_ms: 1,
+            remote_task_timeout_secs: 5,
+            ..CoordinationConfig::default()
+        };
+        let bus = InMemoryMessageBus::new();
+        bus.register_agent("delegate-lead").unwrap();
+        for agent in agents {
+            bus.register_agent(*agent).unwrap();
+        }
+        RemoteCoordinator::new(&config, bus, "delegate-lead")
+    }
+
+    async fn bind() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    fn serve(listener: TcpListener, coordinator: Arc<RemoteCoordinator>) {
+        let app = Router::new().route(
+            ENVELOPE_PATH,
+            post(move |headers: HeaderMap, body: Bytes| {
+                let coordinator = coordinator.clone();
+                async move {
+                    let header = |name| {
+                        headers
+                            .get(name)
+                            .and_then(|value| value.to_str().ok())
+                            .unwrap_or_default()
+                            .to_string()
+                    };
+                    let (status, body) = coordinator.handle_inbound(
+                        &header(NODE_HEADER),
+                        &header(TIMESTAMP_HEADER),
+                        &header(SIGNATURE_HEADER),
+                        &body,
+                    );
+                    (StatusCode::from_u16(status).unwrap(), Json(body))
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+    }
+
+    async fn pair() -> (Arc<RemoteCoordinator>, Arc<RemoteCoordinator>) {
+        let (listener_a, url_a) = bind().await;
+        let (listener_b, url_b) = bind().await;
+        let a = Arc::new(coordinator(
+            "alpha",
+            vec![peer("beta", &url_b, &["researcher"])],
+            &[],
+        ));
+        let b = Arc::new(
+            coordinator("beta", vec![peer("alpha", &url_a, &[])], &["researcher"])
+                .with_executor(Arc::new(EchoTool), ["researcher".to_string()]),
+        );
+        serve(listener_a, a.clone());
+        serve(listener_b, b.clone());
+        (a, b)
+    }
+
+    #[tokio::test]
+    async fn delegates_task_to_peer_and_receives_result() {
+        let (a, b) = pair().await;
+
+        let result = a.delegate("researcher", "map the repo", "").await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.output, "researcher handled: map the repo");
+        assert_eq!(b.bus().pending_for_agent("researcher").unwrap(), 1);
+        assert_eq!(a.bus().pending_for_agent("delegate-lead").unwrap(), 1);
+        assert!(a.bus().dead_letters().is_empty());
+    }
+
+    #[tokio::test]
+    async fn redelivered_envelope_is_acked_once() {
+        let (a, b) = pair().await;
+        let envelope = CoordinationEnvelope::new_direct(
+            "delegate-lead",
+            "researcher",
+            "conv-1",
+            "note",
+            CoordinationPayload::Control {
+                action: "ping".into(),
+                note: None,
+            },
+        );
+
+        a.send("beta", envelope.clone()).await.unwrap();
+        a.send("beta", envelope).await.unwrap();
+
+        assert_eq!(b.bus().pending_for_agent("researcher").unwrap(), 1);
+        assert!(b.bus().dead_letters().is_empty());
+    }
+
+    #[tokio::test]
+    async fn context_patch_version_conflict_is_dead_lettered() {
+        let (a, b) = pair().await;
+        let patch = |version| {
+            CoordinationEnvelope::new_broadcast(
+                "delegate-lead",
+                "conv-1",
+                "context",
+                CoordinationPayload::ContextPatch {
+                    key: "plan/status".into(),
+                    expected_version: version,
+                    value: json!("drafted"),
+                },
+            )
+        };
+
+        a.send("beta", patch(0)).await.unwrap();
+        let error = a.send("beta", patch(0)).await.unwrap_err();
+
+        assert!(error.to_string().contains("409"));
+        assert_eq!(b.bus().context_entry("plan/status").unwrap().version, 1);
+        assert_eq!(a.bus().dead_letter_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn unreachable_peer_is_dead_lettered_after_retries() {
+        let (listener, url) = bind().await;
+        drop(listener);
+        let a = coordinator("alpha", vec![peer("beta", &url, &[])], &[]);
+        let envelope = CoordinationEnvelope::new_broadcast(
+            "delegate-lead",
+            "conv-1",
+            "note",
+            CoordinationPayload::Control {
+                action: "ping".into(),
+                note: None,
+            },
+        );
+
+        assert!(a.send("beta", envelope).await.is_err());
+
+        let dead = a.bus().dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert!(dead[0].reason.contains("delivery to peer 'beta' failed"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_peer_and_agent() {
+        let (_, b) = pair().await;
+        let task = CoordinationEnvelope::new_direct(
+            "delegate-lead",
+            "writer",
+            "conv-1",
+            "delegate.request",
+            CoordinationPayload::DelegateTask {
+                task_id: "t1".into(),
+                summary: "draft".into(),
+                metadata: json!({}),
+            },
+        );
+
+        let body = serde_json::to_string(&task).unwrap();
+        let now = chrono::Utc::now().timestamp().to_string();
+        let signed = |node: &str| sign_envelope("shared-test-secret", node, &now, &body);
+
+        let (status, _) = b.handle_inbound("mallory", &now, &signed("mallory"), body.as_bytes());
+        assert_eq!(status, 403);
+        let (status, _) = b.handle_inbound("alpha", &now, &signed("alpha"), body.as_bytes());
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn rejects_spoofed_or_stale_signatures() {
+        let (_, b) = pair().await;
+        let ping = CoordinationEnvelope::new_broadcast(
+            "delegate-lead",
+            "conv-1",
+            "note",
+            CoordinationPayload::Control {
+                action: "ping".into(),
+                note: None,
+            },
+        );
+        let body = serde_json::to_string(&ping).unwrap();
+        let now = chrono::Utc::now().timestamp().to_string();
+
+        // Claiming to be `alpha` without its secret.
+        let forged = sign_envelope("guessed", "alpha", &now, &body);
+        let (status, _) = b.handle_inbound("alpha", &now, &forged, body.as_bytes());
+        assert_eq!(status, 401);
+        let (status, _) = b.handle_inbound("alpha", &now, "", body.as_bytes());
+        assert_eq!(status, 401);
+
+        let stale = (chrono::Utc::now().timestamp() - 3600).to_string();
+        let signature = sign_envelope("shared-test-secret", "alpha", &stale, &body);
+        let (status, _) = b.handle_inbound("alpha", &stale, &signature, body.as_bytes());
+        assert_eq!(status, 401);
+
+        let signature = sign_envelope("shared-test-secret", "alpha", &now, &body);
+        let tampered = body.replace("ping", "stop");
+        let (status, _) = b.handle_inbound("alpha", &now, &signature, tampered.as_bytes());
+        assert_eq!(status, 401);
+
+        assert_eq!(b.bus().dead_letter_count(), 0);
+        let (status, _) = b.handle_inbound("alpha", &now, &signature, body.as_bytes());
+        assert_eq!(status, 200);
+    }
+}
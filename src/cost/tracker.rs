@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 /// Cost tracker for API usage monitoring and budget enforcement.
 pub struct CostTracker {
@@ -34,6 +34,22 @@ impl CostTracker {
         })
     }
 
+    /// Process-wide tracker for `workspace_dir`, created on first use.
+    ///
+    /// Every component recording spend against one workspace (gateway cost
+    /// observer, model cascade) shares this instance, so budget checks see
+    /// each other's usage and one session summary covers all of it.
+    pub fn shared(config: &CostConfig, workspace_dir: &Path) -> Result<Arc<Self>> {
+        static TRACKERS: OnceLock<Mutex<HashMap<PathBuf, Arc<CostTracker>>>> = OnceLock::new();
+        let mut trackers = TRACKERS.get_or_init(|| Mutex::new(HashMap::new())).lock();
+        if let Some(tracker) = trackers.get(workspace_dir) {
+            return Ok(Arc::clone(tracker));
+        }
+        let tracker = Arc::new(Self::new(config.clone(), workspace_dir)?);
+        trackers.insert(workspace_dir.to_path_buf(), Arc::clone(&tracker));
+        Ok(tracker)
+    }
+
     /// Get the session ID.
     pub fn session_id(&self) -> &str {
         &self.session_id
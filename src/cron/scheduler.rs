@@ -1,14 +1,16 @@
+use crate::channels::outbound::{OutboundStore, QueuedChannel};
 #[cfg(feature = "channel-lark")]
 use crate::channels::LarkChannel;
 use crate::channels::{
     Channel, DiscordChannel, EmailChannel, MattermostChannel, QQChannel, SendMessage, SlackChannel,
-    TelegramChannel, WhatsAppChannel,
+    TelegramChannel, WebhookChannel, WhatsAppChannel,
 };
 use crate::config::Config;
 use crate::cron::{
     due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
     update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
+use crate::security::cancellation::{self, StopScope};
 use crate::security::SecurityPolicy;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -135,7 +137,17 @@ async fn execute_and_persist_job(
     warn_if_high_frequency_agent_job(job);
 
     let started_at = Utc::now();
-    let (success, output) = execute_job_with_retry(config, security, job).await;
+    let estop_guard = cancellation::register(StopScope::cron_job(&job.id));
+    let (success, output) = if estop_guard.is_stopped() {
+        (false, cancellation::stopped_message("Cron job"))
+    } else {
+        tokio::select! {
+            biased;
+            () = estop_guard.stopped() => (false, cancellation::stopped_message("Cron job")),
+            result = execute_job_with_retry(config, security, job) => result,
+        }
+    };
+    drop(estop_guard);
     let finished_at = Utc::now();
     let success = persist_job_result(config, job, success, &output, started_at, finished_at).await;
 
@@ -182,6 +194,7 @@ async fn run_agent_job(
                 config.default_temperature,
                 vec![],
                 false,
+                None,
             )
             .await
         }
@@ -331,7 +344,7 @@ pub(crate) async fn deliver_announcement(
                 tg.ack_enabled,
             )
             .with_workspace_dir(config.workspace_dir.clone());
-            channel.send(&SendMessage::new(output, target)).await?;
+            send_queued(config, Arc::new(channel), target, output).await?;
         }
         "discord" => {
             let dc = config
@@ -347,7 +360,7 @@ pub(crate) async fn deliver_announcement(
                 dc.mention_only,
             )
             .with_workspace_dir(config.workspace_dir.clone());
-            channel.send(&SendMessage::new(output, target)).await?;
+            send_queued(config, Arc::new(channel), target, output).await?;
         }
         "slack" => {
             let sl = config
@@ -361,7 +374,7 @@ pub(crate) async fn deliver_announcement(
                 sl.channel_id.clone(),
                 sl.allowed_users.clone(),
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            send_queued(config, Arc::new(channel), target, output).await?;
         }
         "mattermost" => {
             let mm = config
@@ -382,7 +395,7 @@ pub(crate) async fn deliver_announcement(
                 mm.admin_token.clone(),
                 mm.prompt_guard_action,
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            send_queued(config, Arc::new(channel), target, output).await?;
         }
         "qq" => {
             let qq = config
@@ -396,7 +409,7 @@ pub(crate) async fn deliver_announcement(
                 qq.allowed_users.clone(),
                 qq.environment.clone(),
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            send_queued(config, Arc::new(channel), target, output).await?;
         }
         "whatsapp_web" | "whatsapp" => {
             let wa = config
@@ -416,7 +429,7 @@ pub(crate) async fn deliver_announcement(
                     wa.verify_token.clone().unwrap_or_default(),
                     wa.allowed_numbers.clone(),
                 );
-                channel.send(&SendMessage::new(output, target)).await?;
+                send_queued(config, Arc::new(channel), target, output).await?;
             } else {
                 anyhow::bail!(
                     "whatsapp_web delivery requires an active channels runtime session; start daemon/channels with whatsapp web enabled"
@@ -432,7 +445,7 @@ pub(crate) async fn deliver_announcement(
                     .as_ref()
                     .ok_or_else(|| anyhow::anyhow!("lark channel not configured"))?;
                 let channel = LarkChannel::from_lark_config(lark);
-                channel.send(&SendMessage::new(output, target)).await?;
+                send_queued(config, Arc::new(channel), target, output).await?;
             }
             #[cfg(not(feature = "channel-lark"))]
             {
@@ -448,7 +461,7 @@ pub(crate) async fn deliver_announcement(
                     .as_ref()
                     .ok_or_else(|| anyhow::anyhow!("feishu channel not configured"))?;
                 let channel = LarkChannel::from_feishu_config(feishu);
-                channel.send(&SendMessage::new(output, target)).await?;
+                send_queued(config, Arc::new(channel), target, output).await?;
             }
             #[cfg(not(feature = "channel-lark"))]
             {
@@ -462,7 +475,20 @@ pub(crate) async fn deliver_announcement(
                 .as_ref()
                 .ok_or_else(|| anyhow::anyhow!("email channel not configured"))?;
             let channel = EmailChannel::new(email.clone());
-            channel.send(&SendMessage::new(output, target)).await?;
+            send_queued(config, Arc::new(channel), target, output).await?;
+        }
+        "webhook" => {
+            let callbacks = config
+                .channels_config
+                .webhook
+                .as_ref()
+                .and_then(|webhook| webhook.callbacks.as_ref())
+                .ok_or_else(|| anyhow::anyhow!("webhook callbacks not configured"))?;
+            let channel = WebhookChannel::new(callbacks.clone(), &config.workspace_dir);
+            send_queued(config, Arc::new(channel), target, output).await?;
+        }
+        "notify" => {
+            Box::pin(crate::notify::announce(config, "cron", target, output)).await?;
         }
         other => anyhow::bail!("unsupported delivery channel: {other}"),
     }
@@ -470,6 +496,27 @@ pub(crate) async fn deliver_announcement(
     Ok(())
 }
 
+/// Send through the outbound queue so cron deliveries share rate limits,
+/// retries and persistence with channel replies.
+async fn send_queued(
+    config: &Config,
+    channel: Arc<dyn Channel>,
+    target: &str,
+    output: &str,
+) -> Result<()> {
+    let settings = &config.channels_config.outbound;
+    let store = settings
+        .persist
+        .then(|| OutboundStore::shared(&config.workspace_dir));
+    let channel = QueuedChannel::wrap(
+        channel,
+        settings,
+        store,
+        Arc::new(crate::observability::NoopObserver),
+    );
+    channel.send(&SendMessage::new(output, target)).await
+}
+
 async fn run_job_command(
     config: &Config,
     security: &SecurityPolicy,
@@ -25,6 +25,8 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         );
     }
 
+    crate::security::cancellation::spawn_watcher();
+
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
     let max_backoff = config
         .reliability
@@ -87,6 +89,19 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         ));
     }
 
+    if config.notifications.enabled {
+        let notify_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "notifications",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = notify_cfg.clone();
+                async move { Box::pin(crate::notify::run_flush_worker(cfg)).await }
+            },
+        ));
+    }
+
     if config.cron.enabled {
         let scheduler_cfg = config.clone();
         handles.push(spawn_component_supervisor(
@@ -222,6 +237,7 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
                 temp,
                 vec![],
                 false,
+                None,
             )
             .await
             {
@@ -229,14 +245,19 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
                     crate::health::mark_component_ok("heartbeat");
                     if let Some(announcement) = heartbeat_announcement_text(&output) {
                         if let Some((channel, target)) = &delivery {
-                            if let Err(e) = crate::cron::scheduler::deliver_announcement(
-                                &config,
-                                channel,
-                                target,
-                                &announcement,
-                            )
-                            .await
-                            {
+                            let delivered = if channel == "notify" {
+                                crate::notify::announce(&config, "heartbeat", target, &announcement)
+                                    .await
+                            } else {
+                                crate::cron::scheduler::deliver_announcement(
+                                    &config,
+                                    channel,
+                                    target,
+                                    &announcement,
+                                )
+                                .await
+                            };
+                            if let Err(e) = delivered {
                                 crate::health::mark_component_error(
                                     "heartbeat",
                                     format!("delivery failed: {e}"),
@@ -312,6 +333,10 @@ fn heartbeat_delivery_target(config: &Config) -> Result<Option<(String, String)>
         (Some(_), None) => anyhow::bail!("heartbeat.to is required when heartbeat.target is set"),
         (None, Some(_)) => anyhow::bail!("heartbeat.target is required when heartbeat.to is set"),
         (Some(channel), Some(target)) => {
+            if channel.eq_ignore_ascii_case("notify") {
+                crate::notify::validate_recipient(config, target)?;
+                return Ok(Some(("notify".to_string(), target.to_string())));
+            }
             validate_heartbeat_channel_config(config, channel)?;
             Ok(Some((channel.to_string(), target.to_string())))
         }
@@ -647,6 +672,26 @@ mod tests {
             .contains("unsupported heartbeat.target channel"));
     }
 
+    #[test]
+    fn heartbeat_delivery_target_accepts_notification_target() {
+        let mut config = Config::default();
+        config.heartbeat.target = Some("notify".into());
+        config.heartbeat.to = Some("oncall:warning".into());
+        let err = heartbeat_delivery_target(&config).unwrap_err();
+        assert!(err.to_string().contains("[notifications] enabled = true"));
+
+        config.notifications.enabled = true;
+        config.notifications.targets.insert(
+            "oncall".into(),
+            crate::config::NotificationTargetConfig::default(),
+        );
+        let target = heartbeat_delivery_target(&config).unwrap();
+        assert_eq!(
+            target,
+            Some(("notify".to_string(), "oncall:warning".to_string()))
+        );
+    }
+
     #[test]
     fn heartbeat_delivery_target_requires_channel_configuration() {
         let mut config = Config::default();
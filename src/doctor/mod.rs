@@ -1283,6 +1283,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         config.agents.insert(
@@ -1297,6 +1299,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
 
@@ -0,0 +1,291 @@
+//! Checks on tool-call sequences and final replies.
+
+use super::scenario::{Assertion, ToolOrder};
+use regex::Regex;
+use serde_json::Value;
+
+/// Compare observed tool calls with the expected list; `None` when they match.
+pub fn check_tool_calls(
+    expected: &[String],
+    actual: &[String],
+    order: ToolOrder,
+) -> Option<String> {
+    let matches = match order {
+        ToolOrder::Exact => expected == actual,
+        ToolOrder::Subsequence => {
+            let mut remaining = actual.iter();
+            expected
+                .iter()
+                .all(|name| remaining.any(|call| call == name))
+        }
+        ToolOrder::Unordered => {
+            let mut expected = expected.to_vec();
+            let mut actual = actual.to_vec();
+            expected.sort();
+            actual.sort();
+            expected == actual
+        }
+    };
+    (!matches).then(|| {
+        format!(
+            "tool calls {actual:?} do not match expected {expected:?} ({})",
+            match order {
+                ToolOrder::Exact => "exact",
+                ToolOrder::Subsequence => "subsequence",
+                ToolOrder::Unordered => "unordered",
+            }
+        )
+    })
+}
+
+/// Evaluate a non-judge assertion against `text`; `None` when it holds.
+/// Judge assertions are graded separately by the runner.
+pub fn check_text(assertion: &Assertion, text: &str) -> Option<String> {
+    match assertion {
+        Assertion::Contains(needle) => {
+            (!text.contains(needle.as_str())).then(|| format!("reply does not contain {needle:?}"))
+        }
+        Assertion::NotContains(needle) => text
+            .contains(needle.as_str())
+            .then(|| format!("reply contains {needle:?}")),
+        Assertion::Regex(pattern) => match Regex::new(pattern) {
+            Ok(regex) if regex.is_match(text) => None,
+            Ok(_) => Some(format!("reply does not match /{pattern}/")),
+            Err(error) => Some(format!("invalid regex /{pattern}/: {error}")),
+        },
+        Assertion::JsonSchema(schema) => match extract_json(text) {
+            Some(value) => {
+                let errors = validate_schema(schema, &value, "$");
+                (!errors.is_empty())
+                    .then(|| format!("reply JSON violates schema: {}", errors.join("; ")))
+            }
+            None => Some("reply is not valid JSON".to_string()),
+        },
+        Assertion::Judge(_) => None,
+    }
+}
+
+/// Parse the reply as JSON, tolerating a surrounding Markdown code fence.
+fn extract_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+    let fenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))?
+        .trim_end()
+        .strip_suffix("```")?;
+    serde_json::from_str(fenced.trim()).ok()
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Validate `value` against the common JSON Schema keywords: `type`, `enum`,
+/// `const`, `required`, `properties`, `additionalProperties`, `items`,
+/// `minLength`/`maxLength`, `pattern`, `minimum`/`maximum` and
+/// `minItems`/`maxItems`. Unknown keywords are ignored.
+pub fn validate_schema(schema: &Value, value: &Value, path: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(schema) = schema.as_object() else {
+        return errors;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| type_matches(name, value)) {
+            errors.push(format!("{path}: expected type {}", allowed.join("|")));
+            return errors;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!("{path}: value not in enum"));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{path}: expected const {expected}"));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(key) {
+                        errors.push(format!("{path}: missing required property '{key}'"));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, child) in object {
+                let child_path = format!("{path}.{key}");
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(child_schema) => {
+                        errors.extend(validate_schema(child_schema, child, &child_path));
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{child_path}: additional property not allowed"));
+                        }
+                        Some(extra @ Value::Object(_)) => {
+                            errors.extend(validate_schema(extra, child, &child_path));
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{path}: expected at least {min} items"));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if (items.len() as u64) > max {
+                    errors.push(format!("{path}: expected at most {max} items"));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    errors.extend(validate_schema(
+                        item_schema,
+                        item,
+                        &format!("{path}[{index}]"),
+                    ));
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    errors.push(format!("{path}: shorter than {min} characters"));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    errors.push(format!("{path}: longer than {max} characters"));
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                if !Regex::new(pattern).is_ok_and(|regex| regex.is_match(text)) {
+                    errors.push(format!("{path}: does not match pattern /{pattern}/"));
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    errors.push(format!("{path}: below minimum {min}"));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    errors.push(format!("{path}: above maximum {max}"));
+                }
+            }
+        }
+        _ => {}
+    }
+    errors
+}
+
+/// System prompt for `judge` assertions.
+pub const JUDGE_SYSTEM_PROMPT: &str = "You grade an AI assistant's reply against criteria. \
+Answer with PASS or FAIL on the first line, followed by a one-sentence reason.";
+
+pub fn judge_prompt(criteria: &str, user_messages: &[String], reply: &str) -> String {
+    format!(
+        "Criteria:\n{criteria}\n\nConversation (user messages):\n{}\n\nAssistant reply:\n{reply}",
+        user_messages.join("\n---\n")
+    )
+}
+
+/// Interpret a judge verdict; `None` when it passed.
+pub fn judge_failure(criteria: &str, verdict: &str) -> Option<String> {
+    let verdict = verdict.trim();
+    let first_word = verdict
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| !word.is_empty())
+        .unwrap_or_default();
+    if first_word.eq_ignore_ascii_case("pass") {
+        None
+    } else {
+        let reason: String = verdict.chars().take(200).collect();
+        Some(format!("judge rejected {criteria:?}: {reason}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tool_order_modes() {
+        let expected = vec!["a".to_string(), "b".to_string()];
+        let actual = vec!["a".to_string(), "x".to_string(), "b".to_string()];
+        assert!(check_tool_calls(&expected, &actual, ToolOrder::Exact).is_some());
+        assert!(check_tool_calls(&expected, &actual, ToolOrder::Subsequence).is_none());
+        assert!(check_tool_calls(&expected, &actual, ToolOrder::Unordered).is_some());
+        let swapped = vec!["b".to_string(), "a".to_string()];
+        assert!(check_tool_calls(&expected, &swapped, ToolOrder::Unordered).is_none());
+        assert!(check_tool_calls(&expected, &swapped, ToolOrder::Subsequence).is_some());
+    }
+
+    #[test]
+    fn text_assertions() {
+        let text = "Found 2 files: a.txt, b.txt";
+        assert!(check_text(&Assertion::Contains("a.txt".into()), text).is_none());
+        assert!(check_text(&Assertion::NotContains("error".into()), text).is_none());
+        assert!(check_text(&Assertion::Regex(r"(?i)found \d+ files".into()), text).is_none());
+        assert!(check_text(&Assertion::Contains("c.txt".into()), text).is_some());
+    }
+
+    #[test]
+    fn json_schema_assertion_accepts_fenced_json_and_reports_violations() {
+        let schema = json!({
+            "type": "object",
+            "required": ["files", "count"],
+            "additionalProperties": false,
+            "properties": {
+                "files": {"type": "array", "items": {"type": "string", "pattern": "\\.txt$"}, "minItems": 1},
+                "count": {"type": "integer", "minimum": 1}
+            }
+        });
+        let good = "```json\n{\"files\": [\"a.txt\"], \"count\": 1}\n```";
+        assert!(check_text(&Assertion::JsonSchema(schema.clone()), good).is_none());
+
+        let bad = r#"{"files": ["a.md"], "count": 0, "extra": true}"#;
+        let failure = check_text(&Assertion::JsonSchema(schema), bad).unwrap();
+        assert!(failure.contains("$.files[0]: does not match pattern"));
+        assert!(failure.contains("$.count: below minimum 1"));
+        assert!(failure.contains("$.extra: additional property not allowed"));
+    }
+
+    #[test]
+    fn judge_verdicts() {
+        assert!(judge_failure("lists files", "PASS - both files listed").is_none());
+        assert!(judge_failure("lists files", "**Pass**").is_none());
+        assert!(judge_failure("lists files", "FAIL: missing b.txt").is_some());
+    }
+}
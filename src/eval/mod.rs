@@ -0,0 +1,812 @@
+//! Agent evaluation harness (`zeroclaw eval run <suite>`).
+//!
+//! A suite is a YAML or TOML file of scenarios. Each scenario sends one or
+//! more user messages through the real tool-call loop with a restricted tool
+//! set (built-in tools named in `allowed_tools` plus canned `mock_tools`),
+//! then checks the observed tool-call sequence and the final reply. Runs go
+//! against the configured provider or a replay cassette, so suites can gate
+//! CI deterministically.
+
+pub mod assertions;
+pub mod report;
+pub mod scenario;
+
+use crate::agent::loop_::{
+    build_shell_policy_instructions, build_tool_instructions, run_tool_call_loop,
+};
+use crate::approval::ApprovalManager;
+use crate::config::{CassetteMode, Config, ProviderCassetteConfig};
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{Observer, ObserverEvent};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::security::SecurityPolicy;
+use crate::tools::{Tool, ToolResult};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use report::{EvalReport, ScenarioReport, SuiteReport};
+use scenario::{Assertion, MockToolSpec, Scenario, Suite};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_SCENARIO_TIMEOUT_SECS: u64 = 300;
+
+pub async fn handle_command(command: crate::EvalCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::EvalCommands::Run {
+            suite,
+            provider,
+            model,
+            cassette,
+            record,
+            format,
+            output,
+            filter,
+        } => {
+            let options = RunOptions {
+                provider,
+                model,
+                cassette: cassette.map(PathBuf::from),
+                record,
+                filter,
+            };
+            let report = run_suites(config, Path::new(&suite), &options).await?;
+            let rendered = if format == "junit" {
+                report.to_junit()
+            } else {
+                report.to_json()
+            };
+            let summary = format!(
+                "Eval: {}/{} scenarios passed ({:.1}%), {} input / {} output tokens, {:.1}s",
+                report.passed,
+                report.total,
+                report.pass_rate * 100.0,
+                report.input_tokens,
+                report.output_tokens,
+                Duration::from_millis(report.duration_ms).as_secs_f64()
+            );
+            if let Some(path) = output {
+                std::fs::write(&path, rendered)
+                    .with_context(|| format!("Failed to write eval report to {path}"))?;
+                print_failures(&report);
+                println!("{summary}");
+                println!("Report written to {path}");
+            } else {
+                println!("{rendered}");
+                eprintln!("{summary}");
+            }
+            if report.failed > 0 {
+                anyhow::bail!("{} eval scenario(s) failed", report.failed);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_failures(report: &EvalReport) {
+    for suite in &report.suites {
+        for scenario in suite.scenarios.iter().filter(|scenario| !scenario.passed) {
+            println!("✗ {}/{}", suite.name, scenario.name);
+            for failure in &scenario.failures {
+                println!("    {failure}");
+            }
+        }
+    }
+}
+
+/// CLI overrides applied on top of suite and config settings.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// Cassette to replay, or to record into when `record` is set.
+    pub cassette: Option<PathBuf>,
+    pub record: bool,
+    /// Only run scenarios whose name contains this substring.
+    pub filter: Option<String>,
+}
+
+pub async fn run_suites(config: &Config, path: &Path, options: &RunOptions) -> Result<EvalReport> {
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let suites = scenario::load_suites(path)?;
+    let mut reports = Vec::with_capacity(suites.len());
+    for suite in &suites {
+        reports.push(run_suite(config, suite, options).await?);
+    }
+    Ok(EvalReport::new(started_at, reports))
+}
+
+fn resolve_cassette(
+    config: &Config,
+    suite: &Suite,
+    options: &RunOptions,
+) -> ProviderCassetteConfig {
+    let mut cassette = config.provider.cassette.clone();
+    if let Some(path) = &options.cassette {
+        cassette.mode = if options.record {
+            CassetteMode::Record
+        } else {
+            CassetteMode::Replay
+        };
+        cassette.path = Some(path.display().to_string());
+    } else if let Some(path) = &suite.cassette {
+        cassette.mode = CassetteMode::Replay;
+        cassette.path = Some(path.display().to_string());
+    }
+    cassette
+}
+
+fn create_provider(
+    config: &Config,
+    provider_name: &str,
+    model: &str,
+    cassette: &ProviderCassetteConfig,
+) -> Result<Box<dyn Provider>> {
+    let provider_runtime_options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        provider_transport: config.effective_provider_transport(),
+        zeroclaw_dir: config.config_path.parent().map(PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning_level: config.effective_provider_reasoning_level(),
+        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
+        max_tokens_override: None,
+        model_support_vision: config.model_support_vision,
+    };
+    let provider = providers::create_routed_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model,
+        &provider_runtime_options,
+    )?;
+    providers::cassette::wrap_with_cassette(provider, cassette)
+}
+
+async fn run_suite(config: &Config, suite: &Suite, options: &RunOptions) -> Result<SuiteReport> {
+    let provider_name = options
+        .provider
+        .as_deref()
+        .or(suite.provider.as_deref())
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openrouter")
+        .to_string();
+    let model = options
+        .model
+        .as_deref()
+        .or(suite.model.as_deref())
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4")
+        .to_string();
+    let cassette = resolve_cassette(config, suite, options);
+    let provider = create_provider(config, &provider_name, &model, &cassette)
+        .with_context(|| format!("Failed to create provider for suite '{}'", suite.name))?;
+
+    let mut scenarios = Vec::new();
+    for scenario in &suite.scenarios {
+        if let Some(filter) = &options.filter {
+            if !scenario.name.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        tracing::info!(suite = %suite.name, scenario = %scenario.name, "Running eval scenario");
+        let report = run_scenario(
+            config,
+            suite,
+            scenario,
+            provider.as_ref(),
+            &provider_name,
+            &model,
+        )
+        .await;
+        scenarios.push(report);
+    }
+
+    Ok(SuiteReport::new(
+        suite.name.clone(),
+        suite.source.display().to_string(),
+        provider_name,
+        model,
+        scenarios,
+    ))
+}
+
+fn build_tools(config: &Config, scenario: &Scenario) -> Result<Vec<Box<dyn Tool>>> {
+    let mut tools: Vec<Box<dyn Tool>> = scenario
+        .mock_tools
+        .iter()
+        .map(|spec| Box::new(MockTool::new(spec)) as Box<dyn Tool>)
+        .collect();
+    if scenario.allowed_tools.is_empty() {
+        return Ok(tools);
+    }
+
+    let runtime: Arc<dyn crate::runtime::RuntimeAdapter> =
+        Arc::from(crate::runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let mut available = crate::tools::default_tools_with_runtime(security, runtime);
+    for name in &scenario.allowed_tools {
+        if tools.iter().any(|tool| tool.name() == name) {
+            continue;
+        }
+        let index = available
+            .iter()
+            .position(|tool| tool.name() == name)
+            .with_context(|| format!("Unknown allowed tool '{name}'"))?;
+        tools.push(available.swap_remove(index));
+    }
+    Ok(tools)
+}
+
+fn build_system_prompt(
+    config: &Config,
+    scenario: &Scenario,
+    tools: &[Box<dyn Tool>],
+    model: &str,
+    native_tools: bool,
+) -> String {
+    let mut system_prompt = if let Some(prompt) = &scenario.system_prompt {
+        prompt.clone()
+    } else {
+        let tool_descs: Vec<(&str, &str)> = tools
+            .iter()
+            .map(|tool| (tool.name(), tool.description()))
+            .collect();
+        let skills = crate::skills::load_skills_with_config(&config.workspace_dir, config);
+        let mut prompt = crate::channels::build_system_prompt_with_mode(
+            &config.workspace_dir,
+            model,
+            &tool_descs,
+            &skills,
+            Some(&config.identity),
+            config.agent.compact_context.then_some(6000),
+            native_tools,
+            config.skills.prompt_injection_mode,
+        );
+        prompt.push_str(&build_shell_policy_instructions(&config.autonomy));
+        prompt
+    };
+    if !native_tools && !tools.is_empty() {
+        system_prompt.push_str(&build_tool_instructions(tools));
+    }
+    system_prompt
+}
+
+async fn run_scenario(
+    config: &Config,
+    suite: &Suite,
+    scenario: &Scenario,
+    provider: &dyn Provider,
+    provider_name: &str,
+    model: &str,
+) -> ScenarioReport {
+    let observer = EvalObserver::default();
+    let started = Instant::now();
+    let mut failures = Vec::new();
+    let mut final_text = String::new();
+
+    match execute_scenario(
+        config,
+        suite,
+        scenario,
+        provider,
+        provider_name,
+        model,
+        &observer,
+    )
+    .await
+    {
+        Ok(text) => final_text = text,
+        Err(error) => failures.push(format!("agent run failed: {error:#}")),
+    }
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let stats = observer.snapshot();
+
+    if failures.is_empty() {
+        if let Some(expected) = &scenario.expected_tool_calls {
+            failures.extend(assertions::check_tool_calls(
+                expected,
+                &stats.tool_calls,
+                scenario.tool_order,
+            ));
+        }
+        for assertion in &scenario.assertions {
+            let failure = match assertion {
+                Assertion::Judge(criteria) => {
+                    let judge_model = suite.judge_model.as_deref().unwrap_or(model);
+                    let prompt =
+                        assertions::judge_prompt(criteria, &scenario.messages, &final_text);
+                    match provider
+                        .chat_with_system(
+                            Some(assertions::JUDGE_SYSTEM_PROMPT),
+                            &prompt,
+                            judge_model,
+                            0.0,
+                        )
+                        .await
+                    {
+                        Ok(verdict) => assertions::judge_failure(criteria, &verdict),
+                        Err(error) => Some(format!("judge call failed: {error:#}")),
+                    }
+                }
+                other => assertions::check_text(other, &final_text),
+            };
+            failures.extend(failure);
+        }
+    }
+
+    ScenarioReport {
+        name: scenario.name.clone(),
+        passed: failures.is_empty(),
+        failures,
+        duration_ms,
+        input_tokens: stats.input_tokens,
+        output_tokens: stats.output_tokens,
+        llm_calls: stats.llm_calls,
+        tool_calls: stats.tool_calls,
+        final_text,
+    }
+}
+
+async fn execute_scenario(
+    config: &Config,
+    suite: &Suite,
+    scenario: &Scenario,
+    provider: &dyn Provider,
+    provider_name: &str,
+    model: &str,
+    observer: &EvalObserver,
+) -> Result<String> {
+    let tools = build_tools(config, scenario)?;
+    let system_prompt = build_system_prompt(
+        config,
+        scenario,
+        &tools,
+        model,
+        provider.supports_native_tools(),
+    );
+    let temperature = suite.temperature.unwrap_or(config.default_temperature);
+    let max_tool_iterations = scenario
+        .max_tool_iterations
+        .unwrap_or(config.agent.max_tool_iterations);
+    let timeout = Duration::from_secs(
+        scenario
+            .timeout_secs
+            .unwrap_or(DEFAULT_SCENARIO_TIMEOUT_SECS),
+    );
+
+    // Real tools go through the configured approval policy; with nobody to
+    // answer a prompt, calls that need approval are denied. Mock tools never
+    // touch the host, so they are always approved.
+    let mut autonomy = config.autonomy.clone();
+    autonomy
+        .auto_approve
+        .extend(scenario.mock_tools.iter().map(|mock| mock.name.clone()));
+    let approval = ApprovalManager::from_config(&autonomy);
+
+    let conversation = async {
+        let mut history = vec![ChatMessage::system(&system_prompt)];
+        let mut reply = String::new();
+        for message in &scenario.messages {
+            history.push(ChatMessage::user(message));
+            reply = run_tool_call_loop(
+                provider,
+                &mut history,
+                &tools,
+                observer,
+                provider_name,
+                model,
+                temperature,
+                true,
+                Some(&approval),
+                "eval",
+                &config.multimodal,
+                max_tool_iterations,
+                None,
+                None,
+                None,
+                &[],
+            )
+            .await?;
+        }
+        Ok(reply)
+    };
+    tokio::time::timeout(timeout, conversation)
+        .await
+        .with_context(|| format!("timed out after {}s", timeout.as_secs()))?
+}
+
+/// Tool that replays scripted outputs from the scenario file.
+struct MockTool {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+    outputs: Vec<String>,
+    error: Option<String>,
+    calls: AtomicUsize,
+}
+
+impl MockTool {
+    fn new(spec: &MockToolSpec) -> Self {
+        Self {
+            name: spec.name.clone(),
+            description: spec
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Mock tool {}", spec.name)),
+            parameters: spec.parameters.clone().unwrap_or_else(
+                || serde_json::json!({ "type": "object", "additionalProperties": true }),
+            ),
+            outputs: spec.outputs(),
+            error: spec.error.clone(),
+            calls: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MockTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.parameters.clone()
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> Result<ToolResult> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(error) = &self.error {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error.clone()),
+            });
+        }
+        let output = self
+            .outputs
+            .get(call)
+            .or_else(|| self.outputs.last())
+            .cloned()
+            .unwrap_or_default();
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct EvalStats {
+    tool_calls: Vec<String>,
+    input_tokens: u64,
+    output_tokens: u64,
+    llm_calls: u64,
+}
+
+/// Collects tool-call order, token usage and LLM call counts for one scenario.
+#[derive(Default)]
+struct EvalObserver {
+    stats: Mutex<EvalStats>,
+}
+
+impl EvalObserver {
+    fn snapshot(&self) -> EvalStats {
+        self.stats.lock().clone()
+    }
+}
+
+impl Observer for EvalObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let mut stats = self.stats.lock();
+        match event {
+            ObserverEvent::ToolCallStart { tool } => stats.tool_calls.push(tool.clone()),
+            ObserverEvent::LlmResponse {
+                input_tokens,
+                output_tokens,
+                ..
+            } => {
+                stats.llm_calls += 1;
+                stats.input_tokens += input_tokens.unwrap_or(0);
+                stats.output_tokens += output_tokens.unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "eval"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::cassette::{Cassette, ReplayProvider};
+    use crate::providers::traits::{ProviderCapabilities, TokenUsage};
+    use crate::providers::{ChatRequest, ChatResponse, ToolCall};
+    use std::collections::VecDeque;
+
+    /// Scripted provider returning queued responses in order.
+    struct ScriptedProvider {
+        responses: Mutex<VecDeque<ChatResponse>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(responses: Vec<ChatResponse>) -> Self {
+            Self {
+                responses: Mutex::new(responses.into()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("PASS: criteria met".to_string())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            self.responses
+                .lock()
+                .pop_front()
+                .context("scripted provider exhausted")
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                native_tool_calling: true,
+                vision: false,
+            }
+        }
+    }
+
+    fn text(reply: &str) -> ChatResponse {
+        ChatResponse {
+            text: Some(reply.to_string()),
+            tool_calls: Vec::new(),
+            usage: Some(TokenUsage {
+                input_tokens: Some(10),
+                output_tokens: Some(5),
+            }),
+            reasoning_content: None,
+            quota_metadata: None,
+        }
+    }
+
+    fn tool_call(name: &str) -> ChatResponse {
+        ChatResponse {
+            text: Some(String::new()),
+            tool_calls: vec![ToolCall {
+                id: format!("call_{name}"),
+                name: name.to_string(),
+                arguments: "{}".to_string(),
+            }],
+            usage: None,
+            reasoning_content: None,
+            quota_metadata: None,
+        }
+    }
+
+    fn suite_from_yaml(yaml: &str) -> Suite {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("suite.yaml");
+        std::fs::write(&path, yaml).unwrap();
+        scenario::load_suite_file(&path).unwrap()
+    }
+
+    fn test_config(workspace: &Path) -> Config {
+        Config {
+            workspace_dir: workspace.to_path_buf(),
+            ..Config::default()
+        }
+    }
+
+    const SUITE: &str = r#"
+scenarios:
+  - name: weather lookup
+    messages: ["What's the weather in Oslo?"]
+    system_prompt: You are a weather assistant.
+    mock_tools:
+      - name: get_weather
+        outputs: ['{"temp_c": 4, "sky": "overcast"}']
+    expected_tool_calls: [get_weather]
+    assertions:
+      - contains: "4"
+      - regex: "(?i)overcast"
+      - not_contains: error
+      - judge: mentions the temperature
+"#;
+
+    #[tokio::test]
+    async fn scenario_passes_with_expected_tool_calls_and_assertions() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = test_config(workspace.path());
+        let suite = suite_from_yaml(SUITE);
+        let provider = ScriptedProvider::new(vec![
+            tool_call("get_weather"),
+            text("It is 4°C and overcast in Oslo."),
+        ]);
+
+        let report =
+            run_scenario(&config, &suite, &suite.scenarios[0], &provider, "mock", "m").await;
+
+        assert!(report.passed, "failures: {:?}", report.failures);
+        assert_eq!(report.tool_calls, vec!["get_weather".to_string()]);
+        assert_eq!(report.llm_calls, 2);
+    }
+
+    #[tokio::test]
+    async fn scenario_reports_tool_sequence_and_text_failures() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = test_config(workspace.path());
+        let suite = suite_from_yaml(SUITE);
+        let provider = ScriptedProvider::new(vec![text("I cannot check the weather, error.")]);
+
+        let report =
+            run_scenario(&config, &suite, &suite.scenarios[0], &provider, "mock", "m").await;
+
+        assert!(!report.passed);
+        assert!(report.failures[0].contains("tool calls [] do not match"));
+        assert!(report
+            .failures
+            .iter()
+            .any(|failure| failure.contains("does not match /(?i)overcast/")));
+        assert!(report
+            .failures
+            .iter()
+            .any(|failure| failure.contains("reply contains \"error\"")));
+    }
+
+    #[tokio::test]
+    async fn unknown_allowed_tool_fails_the_scenario() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = test_config(workspace.path());
+        let suite = suite_from_yaml(
+            "scenarios:\n  - name: bad\n    messages: [hi]\n    allowed_tools: [teleport]\n",
+        );
+        let provider = ScriptedProvider::new(vec![text("hi")]);
+
+        let report =
+            run_scenario(&config, &suite, &suite.scenarios[0], &provider, "mock", "m").await;
+
+        assert!(!report.passed);
+        assert!(report.failures[0].contains("Unknown allowed tool 'teleport'"));
+    }
+
+    #[tokio::test]
+    async fn suite_runs_against_replay_cassette_and_renders_reports() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = test_config(workspace.path());
+        let suite_dir = tempfile::tempdir().unwrap();
+        let cassette_path = suite_dir.path().join("weather.cassette.json");
+
+        // Record a cassette by running the scripted provider through a
+        // recording wrapper, then replay the suite from it.
+        let suite_path = suite_dir.path().join("weather.yaml");
+        std::fs::write(
+            &suite_path,
+            SUITE.replace("      - judge: mentions the temperature\n", ""),
+        )
+        .unwrap();
+        let suite = scenario::load_suite_file(&suite_path).unwrap();
+        let recorder = providers::cassette::RecordingProvider::new(
+            Box::new(ScriptedProvider::new(vec![
+                tool_call("get_weather"),
+                text("It is 4°C and overcast in Oslo."),
+            ])),
+            cassette_path.clone(),
+        )
+        .unwrap();
+        let recorded =
+            run_scenario(&config, &suite, &suite.scenarios[0], &recorder, "mock", "m").await;
+        assert!(recorded.passed, "failures: {:?}", recorded.failures);
+        // The recorder writes the cassette when it is dropped.
+        drop(recorder);
+        assert_eq!(
+            Cassette::load(&cassette_path).unwrap().interactions.len(),
+            2
+        );
+
+        let replay =
+            ReplayProvider::load(&cassette_path, crate::config::CassetteMatchMode::Strict).unwrap();
+        let replayed =
+            run_scenario(&config, &suite, &suite.scenarios[0], &replay, "mock", "m").await;
+        assert!(replayed.passed, "failures: {:?}", replayed.failures);
+        assert_eq!(replay.remaining(), 0);
+
+        let report = EvalReport::new(
+            "now".into(),
+            vec![SuiteReport::new(
+                suite.name.clone(),
+                suite_path.display().to_string(),
+                "mock".into(),
+                "m".into(),
+                vec![replayed],
+            )],
+        );
+        assert_eq!(report.pass_rate, 1.0);
+        let junit = report.to_junit();
+        assert!(junit.contains("<testsuite name=\"weather\" tests=\"1\" failures=\"0\""));
+        assert!(junit.contains("<testcase name=\"weather lookup\""));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(
+            json["suites"][0]["scenarios"][0]["tool_calls"][0],
+            "get_weather"
+        );
+    }
+
+    #[test]
+    fn cli_cassette_overrides_suite_cassette() {
+        let config = Config::default();
+        let mut suite = suite_from_yaml(SUITE);
+        suite.cassette = Some(PathBuf::from("/suites/weather.json"));
+
+        let from_suite = resolve_cassette(&config, &suite, &RunOptions::default());
+        assert_eq!(from_suite.mode, CassetteMode::Replay);
+        assert_eq!(from_suite.path.as_deref(), Some("/suites/weather.json"));
+
+        let options = RunOptions {
+            cassette: Some(PathBuf::from("/tmp/new.json")),
+            record: true,
+            ..RunOptions::default()
+        };
+        let from_cli = resolve_cassette(&config, &suite, &options);
+        assert_eq!(from_cli.mode, CassetteMode::Record);
+        assert_eq!(from_cli.path.as_deref(), Some("/tmp/new.json"));
+    }
+
+    #[test]
+    fn toml_suites_load_from_directory_in_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("b.toml"),
+            "[[scenarios]]\nname = \"greet\"\nmessages = [\"hi\"]\nassertions = [{ contains = \"hello\" }]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("a.yml"),
+            "name: first\nscenarios:\n  - name: s\n    messages: [x]\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.md"), "ignored").unwrap();
+
+        let suites = scenario::load_suites(dir.path()).unwrap();
+        let names: Vec<&str> = suites.iter().map(|suite| suite.name.as_str()).collect();
+        assert_eq!(names, vec!["first", "b"]);
+        assert!(matches!(
+            suites[1].scenarios[0].assertions[0],
+            Assertion::Contains(ref needle) if needle == "hello"
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+//! Evaluation reports in JSON and JUnit XML.
+
+use quick_xml::escape::escape;
+use serde::Serialize;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub passed: bool,
+    pub failures: Vec<String>,
+    pub duration_ms: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub llm_calls: u64,
+    pub tool_calls: Vec<String>,
+    pub final_text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    pub name: String,
+    pub source: String,
+    pub provider: String,
+    pub model: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub pass_rate: f64,
+    pub duration_ms: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub scenarios: Vec<ScenarioReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub started_at: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub pass_rate: f64,
+    pub duration_ms: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub suites: Vec<SuiteReport>,
+}
+
+fn pass_rate(passed: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        passed as f64 / total as f64
+    }
+}
+
+impl SuiteReport {
+    pub fn new(
+        name: String,
+        source: String,
+        provider: String,
+        model: String,
+        scenarios: Vec<ScenarioReport>,
+    ) -> Self {
+        let total = scenarios.len();
+        let passed = scenarios.iter().filter(|scenario| scenario.passed).count();
+        Self {
+            name,
+            source,
+            provider,
+            model,
+            total,
+            passed,
+            failed: total - passed,
+            pass_rate: pass_rate(passed, total),
+            duration_ms: scenarios.iter().map(|scenario| scenario.duration_ms).sum(),
+            input_tokens: scenarios.iter().map(|scenario| scenario.input_tokens).sum(),
+            output_tokens: scenarios
+                .iter()
+                .map(|scenario| scenario.output_tokens)
+                .sum(),
+            scenarios,
+        }
+    }
+}
+
+impl EvalReport {
+    pub fn new(started_at: String, suites: Vec<SuiteReport>) -> Self {
+        let total = suites.iter().map(|suite| suite.total).sum();
+        let passed = suites.iter().map(|suite| suite.passed).sum();
+        Self {
+            started_at,
+            total,
+            passed,
+            failed: total - passed,
+            pass_rate: pass_rate(passed, total),
+            duration_ms: suites.iter().map(|suite| suite.duration_ms).sum(),
+            input_tokens: suites.iter().map(|suite| suite.input_tokens).sum(),
+            output_tokens: suites.iter().map(|suite| suite.output_tokens).sum(),
+            suites,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    pub fn to_junit(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"zeroclaw-eval\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+            self.total,
+            self.failed,
+            seconds(self.duration_ms)
+        );
+        for suite in &self.suites {
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+                escape(&suite.name),
+                suite.total,
+                suite.failed,
+                seconds(suite.duration_ms)
+            );
+            let _ = writeln!(xml, "    <properties>");
+            for (name, value) in [
+                ("provider", suite.provider.as_str()),
+                ("model", suite.model.as_str()),
+                ("source", suite.source.as_str()),
+            ] {
+                let _ = writeln!(
+                    xml,
+                    "      <property name=\"{name}\" value=\"{}\"/>",
+                    escape(value)
+                );
+            }
+            let _ = writeln!(xml, "    </properties>");
+            for scenario in &suite.scenarios {
+                let _ = writeln!(
+                    xml,
+                    "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">",
+                    escape(&scenario.name),
+                    escape(&suite.name),
+                    seconds(scenario.duration_ms)
+                );
+                if !scenario.passed {
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{}\">{}</failure>",
+                        escape(scenario.failures.first().map_or("", String::as_str)),
+                        escape(scenario.failures.join("\n"))
+                    );
+                }
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>tokens_in={} tokens_out={} llm_calls={} tool_calls={}\n{}</system-out>",
+                    scenario.input_tokens,
+                    scenario.output_tokens,
+                    scenario.llm_calls,
+                    escape(scenario.tool_calls.join(",")),
+                    escape(&scenario.final_text)
+                );
+                let _ = writeln!(xml, "    </testcase>");
+            }
+            let _ = writeln!(xml, "  </testsuite>");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+fn seconds(duration_ms: u64) -> String {
+    format!("{}.{:03}", duration_ms / 1000, duration_ms % 1000)
+}
@@ -0,0 +1,201 @@
+//! Evaluation suite and scenario definitions (YAML or TOML).
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A named set of scenarios loaded from one suite file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suite {
+    /// Suite name; defaults to the file stem.
+    #[serde(default)]
+    pub name: String,
+    /// Provider override for this suite (defaults to `default_provider`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model override for this suite (defaults to `default_model`).
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Model used for `judge` assertions (defaults to the suite model).
+    #[serde(default)]
+    pub judge_model: Option<String>,
+    /// Replay cassette, relative to the suite file.
+    #[serde(default)]
+    pub cassette: Option<PathBuf>,
+    pub scenarios: Vec<Scenario>,
+    /// File the suite was loaded from.
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+/// One agent conversation with expectations.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// User messages, sent as consecutive turns of one conversation.
+    pub messages: Vec<String>,
+    /// Replaces the workspace system prompt for this scenario.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Built-in tools (shell, file_read, ...) offered besides the mocks.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default)]
+    pub mock_tools: Vec<MockToolSpec>,
+    /// Expected tool-call names, checked according to `tool_order`.
+    #[serde(default)]
+    pub expected_tool_calls: Option<Vec<String>>,
+    #[serde(default)]
+    pub tool_order: ToolOrder,
+    /// Assertions on the final assistant reply.
+    #[serde(
+        default,
+        deserialize_with = "serde_norway::with::singleton_map_recursive::deserialize"
+    )]
+    pub assertions: Vec<Assertion>,
+    #[serde(default)]
+    pub max_tool_iterations: Option<usize>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// A tool that returns canned output instead of touching the host.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockToolSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON schema for the tool arguments (defaults to any object).
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+    /// Output for every call; shorthand for a single-entry `outputs`.
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Outputs for successive calls; the last one repeats.
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// Make the tool fail with this error.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOrder {
+    /// Calls must equal the expected list exactly.
+    #[default]
+    Exact,
+    /// Expected calls must appear in order; other calls may be interleaved.
+    Subsequence,
+    /// Same calls with the same counts, in any order.
+    Unordered,
+}
+
+/// Check applied to the final reply, written as a single-key map
+/// (`contains: "..."`, `regex: "..."`, `json_schema: {...}`, `judge: "..."`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Assertion {
+    Contains(String),
+    NotContains(String),
+    Regex(String),
+    JsonSchema(serde_json::Value),
+    /// Criteria graded by an LLM judge that must answer PASS.
+    Judge(String),
+}
+
+impl MockToolSpec {
+    pub fn outputs(&self) -> Vec<String> {
+        let mut outputs = self.outputs.clone();
+        if let Some(output) = &self.output {
+            outputs.insert(0, output.clone());
+        }
+        outputs
+    }
+}
+
+/// Load a suite file, or every `.yaml`/`.yml`/`.toml` file in a directory
+/// (sorted by name).
+pub fn load_suites(path: &Path) -> Result<Vec<Suite>> {
+    if !path.is_dir() {
+        return Ok(vec![load_suite_file(path)?]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .with_context(|| format!("Failed to read suite directory {}", path.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| {
+            file.is_file()
+                && file
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| matches!(ext, "yaml" | "yml" | "toml"))
+        })
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        anyhow::bail!("No .yaml, .yml or .toml suites in {}", path.display());
+    }
+    files.iter().map(|file| load_suite_file(file)).collect()
+}
+
+pub fn load_suite_file(path: &Path) -> Result<Suite> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read suite {}", path.display()))?;
+    let mut suite: Suite = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&raw)
+            .with_context(|| format!("Failed to parse suite {}", path.display()))?,
+        _ => serde_norway::from_str(&raw)
+            .with_context(|| format!("Failed to parse suite {}", path.display()))?,
+    };
+    if suite.name.trim().is_empty() {
+        suite.name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("suite")
+            .to_string();
+    }
+    if let Some(cassette) = suite.cassette.take() {
+        let base = path.parent().unwrap_or(Path::new("."));
+        suite.cassette = Some(base.join(cassette));
+    }
+    suite.source = path.to_path_buf();
+    suite.validate()?;
+    Ok(suite)
+}
+
+impl Suite {
+    fn validate(&self) -> Result<()> {
+        if self.scenarios.is_empty() {
+            anyhow::bail!("Suite '{}' has no scenarios", self.name);
+        }
+        let mut names = std::collections::HashSet::new();
+        for scenario in &self.scenarios {
+            if !names.insert(scenario.name.as_str()) {
+                anyhow::bail!(
+                    "Suite '{}' has duplicate scenario '{}'",
+                    self.name,
+                    scenario.name
+                );
+            }
+            if scenario.messages.is_empty() {
+                anyhow::bail!("Scenario '{}' needs at least one message", scenario.name);
+            }
+            for assertion in &scenario.assertions {
+                if let Assertion::Regex(pattern) = assertion {
+                    regex::Regex::new(pattern).with_context(|| {
+                        format!("Scenario '{}' has an invalid regex", scenario.name)
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
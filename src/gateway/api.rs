@@ -66,6 +66,16 @@ pub struct CronAddBody {
     pub command: String,
 }
 
+#[derive(Deserialize)]
+pub struct NotifyBody {
+    pub message: String,
+    pub title: Option<String>,
+    pub severity: Option<String>,
+    pub target: Option<String>,
+    pub dedup_key: Option<String>,
+    pub source: Option<String>,
+}
+
 // ── Handlers ────────────────────────────────────────────────────
 
 /// GET /api/status — system status overview
@@ -469,6 +479,58 @@ pub async fn handle_api_memory_delete(
     }
 }
 
+/// POST /api/notify — route a notification through `[notifications]`
+pub async fn handle_api_notify(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<NotifyBody>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let severity = match body.severity.as_deref() {
+        Some(raw) => match crate::notify::parse_severity(raw) {
+            Some(severity) => severity,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": format!("Invalid severity: {raw}")})),
+                )
+                    .into_response();
+            }
+        },
+        None => crate::config::NotificationSeverity::Info,
+    };
+
+    let source = body
+        .source
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("gateway");
+    let mut notification = crate::notify::Notification::new(source, severity, &body.message);
+    if let Some(title) = body.title.as_deref().filter(|t| !t.trim().is_empty()) {
+        notification = notification.with_title(title);
+    }
+    if let Some(target) = body.target.as_deref().filter(|t| !t.trim().is_empty()) {
+        notification = notification.for_target(target.trim());
+    }
+    if let Some(key) = body.dedup_key.as_deref().filter(|k| !k.trim().is_empty()) {
+        notification = notification.with_dedup_key(key);
+    }
+
+    let config = state.config.lock().clone();
+    match crate::notify::notify(&config, notification).await {
+        Ok(report) => Json(serde_json::json!({"status": "ok", "report": report})).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Notification failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 /// GET /api/cost — cost summary
 pub async fn handle_api_cost(
     State(state): State<AppState>,
@@ -608,6 +670,7 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     mask_optional_secret(&mut masked.proxy.https_proxy);
     mask_optional_secret(&mut masked.proxy.all_proxy);
     mask_optional_secret(&mut masked.transcription.api_key);
+    mask_optional_secret(&mut masked.tts.api_key);
     mask_optional_secret(&mut masked.browser.computer_use.api_key);
     mask_optional_secret(&mut masked.web_fetch.api_key);
     mask_optional_secret(&mut masked.web_search.api_key);
@@ -642,6 +705,9 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     }
     if let Some(webhook) = masked.channels_config.webhook.as_mut() {
         mask_optional_secret(&mut webhook.secret);
+        if let Some(callbacks) = webhook.callbacks.as_mut() {
+            mask_required_secret(&mut callbacks.signing_secret);
+        }
     }
     if let Some(matrix) = masked.channels_config.matrix.as_mut() {
         mask_required_secret(&mut matrix.access_token);
@@ -670,6 +736,12 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
         mask_optional_secret(&mut irc.nickserv_password);
         mask_optional_secret(&mut irc.sasl_password);
     }
+    if let Some(xmpp) = masked.channels_config.xmpp.as_mut() {
+        mask_required_secret(&mut xmpp.password);
+    }
+    if let Some(zulip) = masked.channels_config.zulip.as_mut() {
+        mask_required_secret(&mut zulip.api_key);
+    }
     if let Some(lark) = masked.channels_config.lark.as_mut() {
         mask_required_secret(&mut lark.app_secret);
         mask_optional_secret(&mut lark.encrypt_key);
@@ -713,6 +785,7 @@ fn restore_masked_sensitive_fields(
         &mut incoming.transcription.api_key,
         &current.transcription.api_key,
     );
+    restore_optional_secret(&mut incoming.tts.api_key, &current.tts.api_key);
     restore_optional_secret(
         &mut incoming.browser.computer_use.api_key,
         &current.browser.computer_use.api_key,
@@ -791,6 +864,12 @@ fn restore_masked_sensitive_fields(
         current.channels_config.webhook.as_ref(),
     ) {
         restore_optional_secret(&mut incoming_ch.secret, &current_ch.secret);
+        if let (Some(incoming_cb), Some(current_cb)) = (
+            incoming_ch.callbacks.as_mut(),
+            current_ch.callbacks.as_ref(),
+        ) {
+            restore_required_secret(&mut incoming_cb.signing_secret, &current_cb.signing_secret);
+        }
     }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.matrix.as_mut(),
@@ -846,6 +925,18 @@ fn restore_masked_sensitive_fields(
         );
         restore_optional_secret(&mut incoming_ch.sasl_password, &current_ch.sasl_password);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.xmpp.as_mut(),
+        current.channels_config.xmpp.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.password, &current_ch.password);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.zulip.as_mut(),
+        current.channels_config.zulip.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.api_key, &current_ch.api_key);
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.lark.as_mut(),
         current.channels_config.lark.as_ref(),
@@ -16,7 +16,7 @@ pub mod ws;
 
 use crate::channels::{
     Channel, LinqChannel, NextcloudTalkChannel, QQChannel, SendMessage, WatiChannel,
-    WhatsAppChannel,
+    WebhookChannel, WhatsAppChannel,
 };
 use crate::config::Config;
 use crate::cost::CostTracker;
@@ -31,7 +31,7 @@ use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
 use axum::{
     body::{Body, Bytes},
-    extract::{ConnectInfo, Query, State},
+    extract::{ConnectInfo, Path, Query, State},
     http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json, Response},
     routing::{delete, get, post, put},
@@ -309,6 +309,8 @@ pub struct AppState {
     pub nextcloud_talk: Option<Arc<NextcloudTalkChannel>>,
     /// Nextcloud Talk webhook secret for signature verification
     pub nextcloud_talk_webhook_secret: Option<Arc<str>>,
+    /// Outbound callbacks for asynchronous `/webhook` conversations
+    pub webhook_callbacks: Option<Arc<WebhookChannel>>,
     pub wati: Option<Arc<WatiChannel>>,
     pub qq: Option<Arc<QQChannel>>,
     pub qq_webhook_enabled: bool,
@@ -341,6 +343,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         );
     }
     let config_state = Arc::new(Mutex::new(config.clone()));
+    crate::security::cancellation::spawn_watcher();
 
     // ── Hooks ──────────────────────────────────────────────────────
     let hooks: Option<std::sync::Arc<crate::hooks::HookRunner>> = if config.hooks.enabled {
@@ -421,8 +424,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
 
     // Cost tracker (optional)
     let cost_tracker = if config.cost.enabled {
-        match CostTracker::new(config.cost.clone(), &config.workspace_dir) {
-            Ok(ct) => Some(Arc::new(ct)),
+        match CostTracker::shared(&config.cost, &config.workspace_dir) {
+            Ok(ct) => Some(ct),
             Err(e) => {
                 tracing::warn!("Failed to initialize cost tracker: {e}");
                 None
@@ -444,6 +447,19 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             })
         });
 
+    // Signed callbacks for asynchronous webhook conversations (if configured)
+    let webhook_callbacks: Option<Arc<WebhookChannel>> = config
+        .channels_config
+        .webhook
+        .as_ref()
+        .and_then(|webhook| webhook.callbacks.as_ref())
+        .map(|callbacks| {
+            Arc::new(WebhookChannel::new(
+                callbacks.clone(),
+                &config.workspace_dir,
+            ))
+        });
+
     // WhatsApp channel (if configured)
     let whatsapp_channel: Option<Arc<WhatsAppChannel>> = config
         .channels_config
@@ -614,6 +630,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     println!("  🌐 Web Dashboard: http://{display_addr}/");
     println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
     println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
+    if webhook_callbacks.is_some() {
+        println!(
+            "                    + \"conversation_id\" / \"reply_url\" for signed async callbacks"
+        );
+    }
     println!("  POST /api/chat  — {{\"message\": \"...\", \"context\": [...]}} (tools-enabled, OpenClaw compat)");
     if whatsapp_channel.is_some() {
         println!("  GET  /whatsapp  — Meta webhook verification");
@@ -671,6 +692,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     );
     let broadcast_observer: Arc<dyn crate::observability::Observer> =
         Arc::new(sse::BroadcastObserver::new(base_observer, event_tx.clone()));
+    crate::security::dlp::set_runtime_observer(broadcast_observer.clone());
+    crate::observability::span::set_runtime_observer(broadcast_observer.clone());
 
     let state = AppState {
         config: config_state,
@@ -690,6 +713,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         linq_signing_secret,
         nextcloud_talk: nextcloud_talk_channel,
         nextcloud_talk_webhook_secret,
+        webhook_callbacks,
         wati: wati_channel,
         qq: qq_channel,
         qq_webhook_enabled,
@@ -731,6 +755,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/metrics", get(handle_metrics))
         .route("/pair", post(handle_pair))
         .route("/webhook", get(handle_webhook_usage).post(handle_webhook))
+        .route("/webhooks/{source}", post(handle_alert_webhook))
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message))
         .route("/linq", post(handle_linq_webhook))
@@ -758,10 +783,15 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/memory", get(api::handle_api_memory_list))
         .route("/api/memory", post(api::handle_api_memory_store))
         .route("/api/memory/{key}", delete(api::handle_api_memory_delete))
+        .route("/api/notify", post(api::handle_api_notify))
         .route("/api/cost", get(api::handle_api_cost))
         .route("/api/cli-tools", get(api::handle_api_cli_tools))
         .route("/api/health", get(api::handle_api_health))
         .route("/api/node-control", post(handle_node_control))
+        .route(
+            crate::coordination::remote::ENVELOPE_PATH,
+            post(handle_coordination_envelope),
+        )
         // ── SSE event stream ──
         .route("/api/events", get(sse::handle_sse_events))
         // ── WebSocket agent chat ──
@@ -981,9 +1011,21 @@ async fn run_gateway_chat_simple(state: &AppState, message: &str) -> anyhow::Res
 pub(super) async fn run_gateway_chat_with_tools(
     state: &AppState,
     message: &str,
+) -> anyhow::Result<String> {
+    run_gateway_chat_in_session(state, message, None).await
+}
+
+/// Run the agent loop inside the named session `session`, when given.
+pub(super) async fn run_gateway_chat_in_session(
+    state: &AppState,
+    message: &str,
+    session: Option<&str>,
 ) -> anyhow::Result<String> {
     let config = state.config.lock().clone();
-    crate::agent::process_message(config, message).await
+    Box::pin(crate::agent::process_message_in_session(
+        config, message, session,
+    ))
+    .await
 }
 
 fn gateway_outbound_leak_guard_snapshot(
@@ -1003,7 +1045,7 @@ fn sanitize_gateway_response(
                 "I encountered malformed tool-call output and could not produce a safe reply. Please try again."
                     .to_string()
             } else {
-                sanitized
+                apply_gateway_dlp(sanitized)
             }
         }
         crate::channels::ChannelSanitizationResult::Blocked { .. } => {
@@ -1013,12 +1055,37 @@ fn sanitize_gateway_response(
     }
 }
 
+/// Apply `[security.dlp]` to a sanitized gateway response.
+fn apply_gateway_dlp(response: String) -> String {
+    let outcome = crate::security::dlp::apply(
+        crate::security::dlp::DlpSurface::GatewayResponse,
+        Some("gateway"),
+        &response,
+    );
+    if outcome.is_clean() {
+        return response;
+    }
+    if outcome.is_withheld() {
+        return format!(
+            "I withheld this response because it matched data-loss-prevention rules ({}). Please ask for a summary without the sensitive details.",
+            outcome.detector_names().join(", ")
+        );
+    }
+    outcome.content
+}
+
 /// Webhook request body
 #[derive(serde::Deserialize)]
 pub struct WebhookBody {
     pub message: String,
     #[serde(default)]
     pub stream: Option<bool>,
+    /// Conversation to continue asynchronously; replies go to its callback URL.
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    /// Callback URL for this conversation's replies (registered on first use).
+    #[serde(default)]
+    pub reply_url: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -1204,6 +1271,63 @@ async fn handle_node_control(
     }
 }
 
+/// POST /api/coordination/envelope — coordination envelopes from peer gateways.
+///
+/// Only served with pairing enabled. Peers authenticate with a paired bearer
+/// token, name themselves in `X-Coordination-Node` (which must match a
+/// `[[coordination.peers]]` entry) and sign the body with that peer's shared
+/// secret, so a paired client cannot speak for another node.
+async fn handle_coordination_envelope(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    if !state.pairing.require_pairing() {
+        let err = serde_json::json!({
+            "error": "Remote coordination requires gateway pairing (gateway.require_pairing = true)"
+        });
+        return (StatusCode::FORBIDDEN, Json(err));
+    }
+
+    // ── Bearer auth (pairing) ──
+    let auth = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let token = auth.strip_prefix("Bearer ").unwrap_or("");
+    if !state.pairing.is_authenticated(token) {
+        let err = serde_json::json!({
+            "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
+        });
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+
+    let Some(coordinator) = crate::coordination::remote::current() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Remote coordination is not configured"})),
+        );
+    };
+
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .unwrap_or("")
+    };
+    let (status, body) = coordinator.handle_inbound(
+        header_value(crate::coordination::remote::NODE_HEADER),
+        header_value(crate::coordination::remote::TIMESTAMP_HEADER),
+        header_value(crate::coordination::remote::SIGNATURE_HEADER),
+        &body,
+    );
+    (
+        StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_REQUEST),
+        Json(body),
+    )
+}
+
 /// POST /webhook — main webhook endpoint
 async fn handle_webhook_usage() -> impl IntoResponse {
     (
@@ -1439,24 +1563,114 @@ fn handle_webhook_streaming(
         .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
 }
 
-/// POST /webhook — main webhook endpoint
-async fn handle_webhook(
-    State(state): State<AppState>,
-    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
-    body: Result<Json<WebhookBody>, axum::extract::rejection::JsonRejection>,
+/// Identity an asynchronous `/webhook` conversation is bound to: the paired
+/// bearer token when pairing is on, else the webhook secret holder, else the
+/// (loopback) client address.
+fn webhook_caller(state: &AppState, headers: &HeaderMap, peer_addr: SocketAddr) -> String {
+    if state.pairing.require_pairing() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .unwrap_or("");
+        return format!("token:{}", hash_webhook_secret(token));
+    }
+    if state.webhook_secret_hash.is_some() {
+        return "webhook-secret".to_string();
+    }
+    format!("peer:{}", peer_addr.ip())
+}
+
+/// Accept an asynchronous `/webhook` turn. The agent runs in the background in
+/// the conversation's session and the reply is POSTed to its callback URL.
+async fn accept_webhook_conversation(
+    state: AppState,
+    caller: &str,
+    body: &WebhookBody,
+    message: &str,
 ) -> Response {
-    let rate_key =
-        client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
-    if !state.rate_limiter.allow_webhook(&rate_key) {
-        tracing::warn!("/webhook rate limit exceeded");
-        let err = serde_json::json!({
-            "error": "Too many webhook requests. Please retry later.",
-            "retry_after": RATE_LIMIT_WINDOW_SECS,
-        });
-        return (StatusCode::TOO_MANY_REQUESTS, Json(err)).into_response();
+    let bad_request = |error: String| {
+        let err = serde_json::json!({ "error": error });
+        (StatusCode::BAD_REQUEST, Json(err)).into_response()
+    };
+    let Some(callbacks) = state.webhook_callbacks.clone() else {
+        return bad_request(
+            "conversation_id / reply_url require [channels_config.webhook.callbacks]".into(),
+        );
+    };
+    if body.stream.unwrap_or(false) {
+        return bad_request("`stream` cannot be combined with conversation_id / reply_url".into());
+    }
+
+    let conversation_id = body
+        .conversation_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map_or_else(|| Uuid::new_v4().to_string(), ToString::to_string);
+    if let Err(e) = crate::channels::webhook::validate_conversation_id(&conversation_id) {
+        return bad_request(e.to_string());
+    }
+    if let Err(e) = callbacks
+        .register_conversation(&conversation_id, caller, body.reply_url.as_deref())
+        .await
+    {
+        tracing::warn!("Webhook: rejected conversation {conversation_id}: {e}");
+        if e.downcast_ref::<crate::channels::webhook::ConversationConflict>()
+            .is_some()
+        {
+            let err = serde_json::json!({ "error": e.to_string() });
+            return (StatusCode::FORBIDDEN, Json(err)).into_response();
+        }
+        return bad_request(e.to_string());
     }
 
+    let message_id = format!("webhook_{}", Uuid::new_v4());
+    let body = serde_json::json!({
+        "status": "accepted",
+        "conversation_id": conversation_id,
+        "message_id": message_id,
+    });
+    let message = message.to_string();
+    tokio::spawn(async move {
+        let session = crate::channels::webhook::conversation_session_name(&conversation_id);
+        let delivery = match run_gateway_chat_in_session(&state, &message, Some(&session)).await {
+            Ok(response) => {
+                let leak_guard_cfg = gateway_outbound_leak_guard_snapshot(&state);
+                let safe_response = sanitize_gateway_response(
+                    &response,
+                    state.tools_registry_exec.as_ref(),
+                    &leak_guard_cfg,
+                );
+                callbacks
+                    .send(
+                        &SendMessage::new(safe_response, &conversation_id)
+                            .in_thread(Some(message_id)),
+                    )
+                    .await
+            }
+            Err(e) => {
+                tracing::error!("LLM error for webhook conversation {conversation_id}: {e:#}");
+                callbacks
+                    .send_error(&conversation_id, Some(&message_id), "LLM request failed")
+                    .await
+            }
+        };
+        if let Err(e) = delivery {
+            tracing::error!("Webhook callback for {conversation_id} failed: {e:#}");
+        }
+    });
+    (StatusCode::ACCEPTED, Json(body)).into_response()
+}
+
+/// Auth shared by `/webhook` and `/webhooks/{source}`: pairing bearer token
+/// and/or `X-Webhook-Secret`, and at least one of them for non-loopback peers.
+/// Returns the `401` response for rejected requests.
+fn reject_unauthorized_webhook(
+    state: &AppState,
+    peer_addr: SocketAddr,
+    headers: &HeaderMap,
+) -> Option<Response> {
     // Require at least one auth layer for non-loopback traffic.
     if !state.pairing.require_pairing()
         && state.webhook_secret_hash.is_none()
@@ -1468,7 +1682,7 @@ async fn handle_webhook(
         let err = serde_json::json!({
             "error": "Unauthorized — configure pairing or X-Webhook-Secret for non-local webhook access"
         });
-        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+        return Some((StatusCode::UNAUTHORIZED, Json(err)).into_response());
     }
 
     // ── Bearer token auth (pairing) ──
@@ -1483,7 +1697,7 @@ async fn handle_webhook(
             let err = serde_json::json!({
                 "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
             });
-            return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+            return Some((StatusCode::UNAUTHORIZED, Json(err)).into_response());
         }
     }
 
@@ -1500,10 +1714,83 @@ async fn handle_webhook(
             _ => {
                 tracing::warn!("Webhook: rejected request — invalid or missing X-Webhook-Secret");
                 let err = serde_json::json!({"error": "Unauthorized — invalid or missing X-Webhook-Secret header"});
-                return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+                return Some((StatusCode::UNAUTHORIZED, Json(err)).into_response());
             }
         }
     }
+    None
+}
+
+/// POST /webhooks/{source} — external service alerts (Vercel, Supabase,
+/// Upstash, custom) routed through `[notifications]`
+async fn handle_alert_webhook(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    Path(source): Path<String>,
+    headers: HeaderMap,
+    body: Result<Json<serde_json::Value>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    let rate_key =
+        client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
+    if !state.rate_limiter.allow_webhook(&rate_key) {
+        tracing::warn!("/webhooks/{source} rate limit exceeded");
+        let err = serde_json::json!({
+            "error": "Too many webhook requests. Please retry later.",
+            "retry_after": RATE_LIMIT_WINDOW_SECS,
+        });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err)).into_response();
+    }
+    if let Some(response) = reject_unauthorized_webhook(&state, peer_addr, &headers) {
+        return response;
+    }
+
+    let Json(payload) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!("Alert webhook JSON parse error: {e}");
+            let err = serde_json::json!({ "error": "Invalid JSON body" });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
+    };
+    let Some(text) = crate::alert_forwarder::transform(&source, &payload) else {
+        let err = serde_json::json!({
+            "error": format!("Unknown alert source `{source}` (expected vercel, supabase, upstash or custom)")
+        });
+        return (StatusCode::NOT_FOUND, Json(err)).into_response();
+    };
+
+    let config = state.config.lock().clone();
+    match crate::alert_forwarder::forward_to_notifications(&config, &source, &text).await {
+        Ok(report) => Json(serde_json::json!({"status": "ok", "report": report})).into_response(),
+        Err(e) => {
+            tracing::warn!("Alert webhook {source}: {e}");
+            let err = serde_json::json!({ "error": format!("Alert not delivered: {e}") });
+            (StatusCode::BAD_GATEWAY, Json(err)).into_response()
+        }
+    }
+}
+
+/// POST /webhook — main webhook endpoint
+async fn handle_webhook(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Result<Json<WebhookBody>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    let rate_key =
+        client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
+    if !state.rate_limiter.allow_webhook(&rate_key) {
+        tracing::warn!("/webhook rate limit exceeded");
+        let err = serde_json::json!({
+            "error": "Too many webhook requests. Please retry later.",
+            "retry_after": RATE_LIMIT_WINDOW_SECS,
+        });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err)).into_response();
+    }
+
+    if let Some(response) = reject_unauthorized_webhook(&state, peer_addr, &headers) {
+        return response;
+    }
 
     // ── Parse body ──
     let Json(webhook_body) = match body {
@@ -1551,6 +1838,11 @@ async fn handle_webhook(
             .await;
     }
 
+    if webhook_body.conversation_id.is_some() || webhook_body.reply_url.is_some() {
+        let caller = webhook_caller(&state, &headers, peer_addr);
+        return accept_webhook_conversation(state, &caller, &webhook_body, message).await;
+    }
+
     let provider_label = state
         .config
         .lock()
@@ -2300,7 +2592,7 @@ async fn handle_qq_webhook(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::channels::traits::ChannelMessage;
+    use crate::channels::traits::{ChannelEvent, ChannelMessage};
     use crate::memory::{Memory, MemoryCategory, MemoryEntry};
     use crate::providers::Provider;
     use async_trait::async_trait;
@@ -2405,6 +2697,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2461,6 +2754,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2503,6 +2797,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2546,6 +2841,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2766,6 +3062,8 @@ mod tests {
             channel: "whatsapp".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         let key = whatsapp_memory_key(&msg);
@@ -2782,6 +3080,8 @@ mod tests {
             channel: "qq".into(),
             timestamp: 1,
             thread_ts: Some("msg-123".into()),
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         };
 
         let key = qq_memory_key(&msg);
@@ -3031,6 +3331,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3049,6 +3350,8 @@ Reminder set successfully."#;
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
             stream: None,
+            conversation_id: None,
+            reply_url: None,
         }));
         let first = handle_webhook(
             State(state.clone()),
@@ -3063,6 +3366,8 @@ Reminder set successfully."#;
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
             stream: None,
+            conversation_id: None,
+            reply_url: None,
         }));
         let second = handle_webhook(State(state), test_connect_info(), headers, body)
             .await
@@ -3100,6 +3405,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3119,6 +3425,8 @@ Reminder set successfully."#;
             Ok(Json(WebhookBody {
                 message: "hello".into(),
                 stream: None,
+                conversation_id: None,
+                reply_url: None,
             })),
         )
         .await
@@ -3127,6 +3435,106 @@ Reminder set successfully."#;
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
 
+    #[tokio::test]
+    async fn alert_webhook_routes_through_notifications_with_configured_severity() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let mut config = Config::default();
+        config.notifications.enabled = true;
+        config.notifications.targets.insert(
+            "alerts-oncall".into(),
+            crate::config::NotificationTargetConfig::default(),
+        );
+        config
+            .notifications
+            .routes
+            .push(crate::config::NotificationRouteConfig {
+                min_severity: crate::config::NotificationSeverity::Critical,
+                sources: vec!["alert_forwarder".into()],
+                targets: vec!["alerts-oncall".into()],
+            });
+        config.notifications.alert_severity = Some(crate::config::NotificationSeverity::Critical);
+        let config = Arc::new(Mutex::new(config));
+
+        let state = AppState {
+            config: config.clone(),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
+            wati: None,
+            qq: None,
+            qq_webhook_enabled: false,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            tools_registry_exec: Arc::new(Vec::new()),
+            multimodal: crate::config::MultimodalConfig::default(),
+            max_tool_iterations: 10,
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+        let payload = || {
+            Ok(Json(serde_json::json!({
+                "type": "deployment.succeeded",
+                "payload": { "name": "alerts-route-test" }
+            })))
+        };
+
+        let response = handle_alert_webhook(
+            State(state.clone()),
+            test_connect_info(),
+            Path("nagios".into()),
+            HeaderMap::new(),
+            payload(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // The configured severity lifts a succeeded deployment into the
+        // critical-only route.
+        let response = handle_alert_webhook(
+            State(state.clone()),
+            test_connect_info(),
+            Path("vercel".into()),
+            HeaderMap::new(),
+            payload(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body["report"]["targets"],
+            serde_json::json!(["alerts-oncall"])
+        );
+
+        // Inferred from the status icon it stays `info` and matches no route.
+        config.lock().notifications.alert_severity = None;
+        let response = handle_alert_webhook(
+            State(state),
+            test_connect_info(),
+            Path("vercel".into()),
+            HeaderMap::new(),
+            payload(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
     #[tokio::test]
     async fn webhook_rejects_empty_message() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -3151,6 +3559,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3170,6 +3579,8 @@ Reminder set successfully."#;
             Ok(Json(WebhookBody {
                 message: "   ".into(),
                 stream: None,
+                conversation_id: None,
+                reply_url: None,
             })),
         )
         .await
@@ -3179,6 +3590,91 @@ Reminder set successfully."#;
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn webhook_async_conversation_validates_callback_target() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+        let tmp = tempfile::tempdir().unwrap();
+        let callbacks = crate::config::WebhookCallbackConfig {
+            signing_secret: "shh".into(),
+            allowed_hosts: vec!["hooks.example.com".into()],
+            default_reply_url: None,
+            max_attempts: 1,
+            initial_backoff_ms: 1,
+            timeout_secs: 1,
+        };
+
+        let mut state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
+            wati: None,
+            qq: None,
+            qq_webhook_enabled: false,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            tools_registry_exec: Arc::new(Vec::new()),
+            multimodal: crate::config::MultimodalConfig::default(),
+            max_tool_iterations: 10,
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let request = |conversation_id: Option<&str>, reply_url: Option<&str>| {
+            Ok(Json(WebhookBody {
+                message: "hello".into(),
+                stream: None,
+                conversation_id: conversation_id.map(str::to_string),
+                reply_url: reply_url.map(str::to_string),
+            }))
+        };
+
+        // Callbacks not configured.
+        let response = handle_webhook(
+            State(state.clone()),
+            test_connect_info(),
+            HeaderMap::new(),
+            request(Some("c1"), Some("https://hooks.example.com/cb")),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        state.webhook_callbacks = Some(Arc::new(WebhookChannel::new(callbacks, tmp.path())));
+        for (conversation_id, reply_url) in [
+            (Some("c1"), Some("https://evil.example.com/cb")),
+            (Some("c1"), None),
+            (Some("../c1"), Some("https://hooks.example.com/cb")),
+        ] {
+            let response = handle_webhook(
+                State(state.clone()),
+                test_connect_info(),
+                HeaderMap::new(),
+                request(conversation_id, reply_url),
+            )
+            .await
+            .into_response();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
     #[tokio::test]
     async fn webhook_stream_response_uses_sse_content_type() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -3203,6 +3699,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3222,6 +3719,8 @@ Reminder set successfully."#;
             Ok(Json(WebhookBody {
                 message: "stream me".into(),
                 stream: Some(true),
+                conversation_id: None,
+                reply_url: None,
             })),
         )
         .await
@@ -3264,6 +3763,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3318,6 +3818,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3377,6 +3878,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3394,6 +3896,8 @@ Reminder set successfully."#;
         let body1 = Ok(Json(WebhookBody {
             message: "hello one".into(),
             stream: None,
+            conversation_id: None,
+            reply_url: None,
         }));
         let first = handle_webhook(
             State(state.clone()),
@@ -3408,6 +3912,8 @@ Reminder set successfully."#;
         let body2 = Ok(Json(WebhookBody {
             message: "hello two".into(),
             stream: None,
+            conversation_id: None,
+            reply_url: None,
         }));
         let second = handle_webhook(State(state), test_connect_info(), headers, body2)
             .await
@@ -3460,6 +3966,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3479,6 +3986,8 @@ Reminder set successfully."#;
             Ok(Json(WebhookBody {
                 message: "hello".into(),
                 stream: None,
+                conversation_id: None,
+                reply_url: None,
             })),
         )
         .await
@@ -3514,6 +4023,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3539,6 +4049,8 @@ Reminder set successfully."#;
             Ok(Json(WebhookBody {
                 message: "hello".into(),
                 stream: None,
+                conversation_id: None,
+                reply_url: None,
             })),
         )
         .await
@@ -3573,6 +4085,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3595,6 +4108,8 @@ Reminder set successfully."#;
             Ok(Json(WebhookBody {
                 message: "hello".into(),
                 stream: None,
+                conversation_id: None,
+                reply_url: None,
             })),
         )
         .await
@@ -3637,6 +4152,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3696,6 +4212,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: Some(channel),
             nextcloud_talk_webhook_secret: Some(Arc::from(secret)),
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3748,6 +4265,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3799,6 +4317,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            webhook_callbacks: None,
             wati: None,
             qq: Some(qq),
             qq_webhook_enabled: true,
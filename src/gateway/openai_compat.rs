@@ -346,9 +346,11 @@ fn handle_streaming(
     let created = unix_timestamp();
     let leak_guard_cfg = state.config.lock().security.outbound_leak_guard.clone();
 
-    // Security-first behavior: when outbound leak guard is enabled, do not emit live
-    // unvetted deltas. Buffer full provider output, sanitize once, then send SSE.
-    if leak_guard_cfg.enabled {
+    // Security-first behavior: when outbound leak guard or DLP is enabled, do not emit
+    // live unvetted deltas. Buffer full provider output, sanitize once, then send SSE.
+    if leak_guard_cfg.enabled
+        || crate::security::dlp::runtime_scans(crate::security::dlp::DlpSurface::GatewayResponse)
+    {
         let model_clone = model.clone();
         let id = request_id.clone();
         let tools_registry = state.tools_registry_exec.clone();
@@ -661,7 +663,7 @@ fn sanitize_openai_compat_response(
                 "I encountered malformed tool-call output and could not produce a safe reply. Please try again."
                     .to_string()
             } else {
-                sanitized
+                super::apply_gateway_dlp(sanitized)
             }
         }
         crate::channels::ChannelSanitizationResult::Blocked { .. } => {
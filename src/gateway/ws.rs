@@ -8,6 +8,9 @@
 //! Server -> Client: {"type":"tool_result","name":"shell","output":"..."}
 //! Server -> Client: {"type":"done","full_response":"..."}
 //! ```
+//!
+//! Connecting with `?session=<name>` continues the named session `<name>`
+//! (shared with `zeroclaw agent --session` and channel `/session switch`).
 
 use super::AppState;
 use crate::agent::loop_::{build_shell_policy_instructions, build_tool_instructions_from_specs};
@@ -35,7 +38,7 @@ fn sanitize_ws_response(
                 "I encountered malformed tool-call output and could not produce a safe reply. Please try again."
                     .to_string()
             } else {
-                sanitized
+                super::apply_gateway_dlp(sanitized)
             }
         }
         crate::channels::ChannelSanitizationResult::Blocked { .. } => {
@@ -181,11 +184,18 @@ pub async fn handle_ws_chat(
         }
     }
 
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    let session = extract_query_param(query.as_deref(), "session");
+    if let Some(name) = session.as_deref() {
+        if let Err(err) = crate::agent::session::validate_name(name) {
+            return (axum::http::StatusCode::BAD_REQUEST, err.to_string()).into_response();
+        }
+    }
+
+    ws.on_upgrade(move |socket| handle_socket(socket, state, session))
         .into_response()
 }
 
-async fn handle_socket(mut socket: WebSocket, state: AppState) {
+async fn handle_socket(mut socket: WebSocket, state: AppState, session: Option<String>) {
     // Maintain conversation history for this WebSocket session
     let mut history: Vec<ChatMessage> = Vec::new();
 
@@ -267,7 +277,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
         }));
 
         // Full agentic loop with tools (includes WASM skills, shell, memory, etc.)
-        match super::run_gateway_chat_with_tools(&state, &content).await {
+        match super::run_gateway_chat_in_session(&state, &content, session.as_deref()).await {
             Ok(response) => {
                 let leak_guard_cfg = { state.config.lock().security.outbound_leak_guard.clone() };
                 let safe_response = finalize_ws_response(
@@ -345,15 +355,19 @@ fn extract_ws_bearer_token(headers: &HeaderMap, query_token: Option<&str>) -> Op
 }
 
 fn extract_query_token(raw_query: Option<&str>) -> Option<String> {
+    extract_query_param(raw_query, "token")
+}
+
+fn extract_query_param(raw_query: Option<&str>, key: &str) -> Option<String> {
     let query = raw_query?;
     for kv in query.split('&') {
         let mut parts = kv.splitn(2, '=');
-        if parts.next() != Some("token") {
+        if parts.next() != Some(key) {
             continue;
         }
-        let token = parts.next().unwrap_or("").trim();
-        if !token.is_empty() {
-            return Some(token.to_string());
+        let value = parts.next().unwrap_or("").trim();
+        if !value.is_empty() {
+            return Some(value.to_string());
         }
     }
     None
@@ -443,6 +457,10 @@ mod tests {
             Some("query-token")
         );
         assert!(extract_query_token(Some("foo=1")).is_none());
+        assert_eq!(
+            extract_query_param(Some("token=t&session=refactor-auth"), "session").as_deref(),
+            Some("refactor-auth")
+        );
     }
 
     struct MockScheduleTool;
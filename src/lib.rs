@@ -40,6 +40,7 @@ use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 
 pub mod agent;
+pub(crate) mod alert_forwarder;
 pub(crate) mod approval;
 pub(crate) mod auth;
 pub mod channels;
@@ -50,6 +51,7 @@ pub(crate) mod cron;
 pub(crate) mod daemon;
 pub(crate) mod doctor;
 pub mod economic;
+pub(crate) mod eval;
 pub mod gateway;
 pub mod goals;
 pub(crate) mod hardware;
@@ -62,6 +64,7 @@ pub(crate) mod integrations;
 pub mod memory;
 pub(crate) mod migration;
 pub(crate) mod multimodal;
+pub(crate) mod notify;
 pub mod observability;
 pub(crate) mod onboard;
 pub mod peripherals;
@@ -189,6 +192,168 @@ pub enum SkillCommands {
     Templates,
 }
 
+/// Secret vault subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SecretCommands {
+    /// Store a secret in the local encrypted vault (referenced as `secret://<name>`)
+    Set {
+        /// Secret name (e.g. openrouter or slack/bot_token)
+        name: String,
+        /// Secret value (prompted without echo if omitted)
+        #[arg(long)]
+        value: Option<String>,
+    },
+    /// Show a stored secret (redacted unless --reveal)
+    Get {
+        /// Secret name
+        name: String,
+        /// Print the full plaintext value
+        #[arg(long)]
+        reveal: bool,
+    },
+    /// List stored secret names with timestamps
+    List,
+    /// Replace the value of an existing secret
+    Rotate {
+        /// Secret name
+        name: String,
+        /// New secret value (prompted without echo if omitted)
+        #[arg(long)]
+        value: Option<String>,
+        /// Generate a random 256-bit hex value instead of prompting
+        #[arg(long, conflicts_with = "value")]
+        generate: bool,
+    },
+}
+
+/// User directory subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UserCommands {
+    /// List users with their roles and linked identities
+    List,
+    /// Show a single user, or the user linked to a `<channel>:<sender>` identity
+    Show {
+        /// User id or `<channel>:<sender>` identity
+        target: String,
+    },
+    /// Create a user
+    Add {
+        /// Stable user id (letters, digits, `_`, `-`, `.`)
+        id: String,
+        /// Human-readable display name
+        #[arg(long)]
+        name: Option<String>,
+        /// Role name (built-in or from [[security.roles]])
+        #[arg(long, default_value = "guest")]
+        role: String,
+        /// Channel identity to link, e.g. telegram:123456 (repeatable)
+        #[arg(long = "identity")]
+        identities: Vec<String>,
+    },
+    /// Delete a user and its linked identities
+    Remove {
+        /// User id
+        id: String,
+    },
+    /// Change a user's role
+    SetRole {
+        /// User id
+        id: String,
+        /// Role name
+        role: String,
+    },
+    /// Link a channel identity (`<channel>:<sender>`) to a user
+    Link {
+        /// User id
+        id: String,
+        /// Identity such as slack:U01ABCDEF or email:alice@example.com
+        identity: String,
+    },
+    /// Unlink a channel identity from whichever user owns it
+    Unlink {
+        /// Identity such as slack:U01ABCDEF
+        identity: String,
+    },
+    /// Issue a one-time code the user can redeem with `/link <code>` from any channel
+    LinkCode {
+        /// User id
+        id: String,
+    },
+}
+
+/// Session transcript subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SessionCommands {
+    /// List recorded session transcripts, most recent first
+    List {
+        /// Maximum sessions to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show a session's prompts, responses and tool calls
+    Show {
+        /// Session id (or unique prefix)
+        session: String,
+        /// Only this turn (1-based index or turn-id prefix)
+        #[arg(long)]
+        turn: Option<String>,
+        /// Print full content instead of previews
+        #[arg(long)]
+        full: bool,
+        /// Print raw transcript entries as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Re-send a recorded turn's LLM requests and compare outputs side by side
+    Replay {
+        /// Session id (or unique prefix)
+        session: String,
+        /// Turn to replay (1-based index or turn-id prefix; defaults to the last)
+        #[arg(long)]
+        turn: Option<String>,
+        /// Provider to replay against (defaults to `default_provider`)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Model to replay against (defaults to the recorded model)
+        #[arg(long)]
+        model: Option<String>,
+        /// Temperature override (defaults to the recorded temperature)
+        #[arg(long)]
+        temperature: Option<f64>,
+    },
+}
+
+/// Evaluation harness subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EvalCommands {
+    /// Run an evaluation suite file (YAML/TOML) or a directory of suites
+    Run {
+        /// Suite file or directory
+        suite: String,
+        /// Provider override (defaults to the suite's, then `default_provider`)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Model override (defaults to the suite's, then `default_model`)
+        #[arg(long)]
+        model: Option<String>,
+        /// Replay provider responses from this cassette instead of the network
+        #[arg(long)]
+        cassette: Option<String>,
+        /// Record provider responses into --cassette instead of replaying
+        #[arg(long, requires = "cassette")]
+        record: bool,
+        /// Report format
+        #[arg(long, default_value = "json", value_parser = ["json", "junit"])]
+        format: String,
+        /// Write the report to this file (a summary is printed to stdout)
+        #[arg(long)]
+        output: Option<String>,
+        /// Only run scenarios whose name contains this text
+        #[arg(long)]
+        filter: Option<String>,
+    },
+}
+
 /// Migration subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum MigrateCommands {
@@ -69,6 +69,7 @@ mod cost;
 mod cron;
 mod daemon;
 mod doctor;
+mod eval;
 mod gateway;
 mod goals;
 mod hardware;
@@ -80,6 +81,7 @@ mod integrations;
 mod memory;
 mod migration;
 mod multimodal;
+mod notify;
 mod observability;
 mod onboard;
 mod peripherals;
@@ -99,8 +101,9 @@ use config::Config;
 
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
 pub use zeroclaw::{
-    ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands, MigrateCommands,
-    PeripheralCommands, ServiceCommands, SkillCommands,
+    ChannelCommands, CronCommands, EvalCommands, HardwareCommands, IntegrationCommands,
+    MigrateCommands, PeripheralCommands, SecretCommands, ServiceCommands, SessionCommands,
+    SkillCommands, UserCommands,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -191,7 +194,8 @@ Examples:
   zeroclaw agent -p anthropic --model claude-sonnet-4-20250514
   zeroclaw agent --peripheral nucleo-f401re:/dev/ttyACM0
   zeroclaw agent --autonomy-level full --max-actions-per-hour 100
-  zeroclaw agent -m \"quick task\" --memory-backend none --compact-context")]
+  zeroclaw agent -m \"quick task\" --memory-backend none --compact-context
+  zeroclaw agent --session refactor-auth      # resume (or create) a named session")]
     Agent {
         /// Single message mode (don't enter interactive mode)
         #[arg(short, long)]
@@ -236,6 +240,10 @@ Examples:
         /// Memory backend (sqlite, markdown, none)
         #[arg(long)]
         memory_backend: Option<String>,
+
+        /// Named session to resume or create (shared with gateway and channels)
+        #[arg(long)]
+        session: Option<String>,
     },
 
     /// Start the gateway server (webhooks, websockets)
@@ -361,6 +369,55 @@ Examples:
         tools: Vec<String>,
     },
 
+    /// Manage secrets in the local encrypted vault
+    #[command(long_about = "\
+Manage secrets in the local encrypted vault.
+
+Config fields that hold credentials accept references instead of \
+plaintext values, resolved when the config is loaded:
+
+  secret://<name>          entry in the local encrypted vault
+  env://<VAR>              environment variable
+  file:///run/secrets/x    file contents (e.g. Docker/Kubernetes secrets)
+  exec://<command>         first line of a command's stdout \
+(requires [secrets].allow_exec = true)
+
+Examples:
+  zeroclaw secrets set openrouter
+  zeroclaw secrets list
+  zeroclaw secrets get openrouter --reveal
+  zeroclaw secrets rotate openrouter
+  # then in config.toml: api_key = \"secret://openrouter\"")]
+    Secrets {
+        #[command(subcommand)]
+        secret_command: SecretCommands,
+    },
+
+    /// Manage the user directory (people, roles, linked channel identities)
+    #[command(long_about = "\
+Manage the user directory.
+
+A user has a role (built-in or from [[security.roles]]) and any number \
+of linked channel identities written as <channel>:<sender>. Channels \
+resolve senders through the directory to apply role-based tool access, \
+`user:<id>` / `role:<name>` approver entries, and per-user memory scoping \
+when [security.users].enabled = true.
+
+Users can link additional channels themselves: `zeroclaw users link-code \
+<id>` (or `/link` on an already linked channel) issues a one-time code, \
+which is redeemed by sending `/link <code>` from the new channel.
+
+Examples:
+  zeroclaw users add alice --name \"Alice\" --role operator --identity telegram:123456
+  zeroclaw users link alice email:alice@example.com
+  zeroclaw users link-code alice
+  zeroclaw users set-role alice admin
+  zeroclaw users show slack:U01ABCDEF")]
+    Users {
+        #[command(subcommand)]
+        user_command: UserCommands,
+    },
+
     /// Configure and manage scheduled tasks
     #[command(long_about = "\
 Configure and manage scheduled tasks.
@@ -386,6 +443,53 @@ Examples:
         cron_command: CronCommands,
     },
 
+    /// Inspect and replay recorded session transcripts
+    #[command(long_about = "\
+Inspect and replay recorded session transcripts.
+
+With [observability] transcripts_enabled = true, every agent turn records \
+its full LLM requests and responses, tool calls with arguments and outputs, \
+token usage and timing (credentials scrubbed) under state/transcripts/, one \
+file per session. Channel conversations use the channel/sender as session \
+id; each `zeroclaw agent` run starts a new session.
+
+`replay` re-sends a recorded turn's LLM requests to another provider or \
+model and prints recorded and new outputs side by side. Tool results come \
+from the transcript, so no tools run again.
+
+Examples:
+  zeroclaw sessions list
+  zeroclaw sessions show telegram_123456 --turn 3 --full
+  zeroclaw sessions replay cli-20260101T120000 --model gpt-4.1-mini")]
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
+    /// Run agent evaluation suites
+    #[command(long_about = "\
+Run agent evaluation suites.
+
+A suite is a YAML or TOML file of scenarios. Each scenario sends user \
+messages through the real agent loop with a restricted tool set \
+(`allowed_tools` plus scripted `mock_tools`), then checks the tool-call \
+sequence and the final reply (contains, not_contains, regex, json_schema, \
+judge). Pass a directory to run every suite in it.
+
+Use --cassette (or `cassette:` in the suite) to replay recorded provider \
+responses for deterministic CI runs; add --record to capture a new \
+cassette from the live provider. Exits non-zero when any scenario fails.
+
+Examples:
+  zeroclaw eval run evals/weather.yaml
+  zeroclaw eval run evals/ --format junit --output eval-report.xml
+  zeroclaw eval run evals/weather.yaml --cassette evals/weather.json --record
+  zeroclaw eval run evals/ --filter refund --model gpt-4.1-mini")]
+    Eval {
+        #[command(subcommand)]
+        eval_command: EvalCommands,
+    },
+
     /// Manage provider model catalogs
     Models {
         #[command(subcommand)]
@@ -911,10 +1015,22 @@ async fn main() -> Result<()> {
         };
     }
 
+    // Secrets are managed before config load so that a config referencing a
+    // not-yet-stored `secret://` entry does not block `zeroclaw secrets set`.
+    if let Commands::Secrets { secret_command } = cli.command {
+        let (config_dir, _) = config::schema::resolve_runtime_dirs_for_onboarding().await?;
+        return handle_secrets_command(&config_dir, secret_command);
+    }
+
     // All other commands need config loaded first
     let mut config = Config::load_or_init().await?;
     config.apply_env_overrides();
     observability::runtime_trace::init_from_config(&config.observability, &config.workspace_dir);
+    observability::transcript::init_from_config(&config.observability, &config.workspace_dir);
+    agent::artifacts::init_from_config(&config.agent, &config.workspace_dir);
+    security::dlp::install_runtime(&config.security.dlp)?;
+    approval::quorum::install_runtime(&config)?;
+    security::cancellation::install_runtime(&config)?;
     if config.security.otp.enabled {
         let config_dir = config
             .config_path
@@ -930,7 +1046,10 @@ async fn main() -> Result<()> {
     }
 
     match cli.command {
-        Commands::Onboard { .. } | Commands::Completions { .. } | Commands::Setup { .. } => {
+        Commands::Onboard { .. }
+        | Commands::Completions { .. }
+        | Commands::Setup { .. }
+        | Commands::Secrets { .. } => {
             unreachable!()
         }
 
@@ -946,6 +1065,7 @@ async fn main() -> Result<()> {
             max_history_messages,
             compact_context,
             memory_backend,
+            session,
         } => {
             if let Some(level) = autonomy_level {
                 config.autonomy.level = level;
@@ -977,6 +1097,7 @@ async fn main() -> Result<()> {
                 temperature,
                 peripheral,
                 interactive,
+                session,
             )
             .await
             .map(|_| ())
@@ -1120,10 +1241,18 @@ async fn main() -> Result<()> {
             level,
             domains,
             tools,
-        } => handle_estop_command(&config, estop_command, level, domains, tools),
+        } => handle_estop_command(&config, estop_command, level, domains, tools).await,
+
+        Commands::Users { user_command } => handle_users_command(&config, user_command),
 
         Commands::Cron { cron_command } => cron::handle_command(cron_command, &config),
 
+        Commands::Eval { eval_command } => eval::handle_command(eval_command, &config).await,
+
+        Commands::Sessions { session_command } => {
+            observability::transcript::handle_command(session_command, &config).await
+        }
+
         Commands::Models { model_command } => match model_command {
             ModelCommands::Refresh {
                 provider,
@@ -1258,7 +1387,7 @@ async fn main() -> Result<()> {
     }
 }
 
-fn handle_estop_command(
+async fn handle_estop_command(
     config: &Config,
     estop_command: Option<EstopSubcommands>,
     level: Option<EstopLevelArg>,
@@ -1286,7 +1415,38 @@ fn handle_estop_command(
             tools,
             otp,
         }) => {
+            let quorum_args = serde_json::json!({
+                "network": network,
+                "domains": domains,
+                "tools": tools,
+            });
             let selector = build_resume_selector(network, domains, tools)?;
+            if let Some(store) = approval::quorum::runtime_store() {
+                match store.authorize(
+                    approval::quorum::ESTOP_RESUME_OPERATION,
+                    &quorum_args,
+                    "cli",
+                )? {
+                    approval::quorum::QuorumDecision::NotRequired => {}
+                    approval::quorum::QuorumDecision::Authorized(request) => {
+                        println!(
+                            "Using approved quorum request {} ({}/{} approvals).",
+                            request.id,
+                            request.approvals.len(),
+                            request.required
+                        );
+                    }
+                    approval::quorum::QuorumDecision::Pending(request) => {
+                        println!("{}", approval::quorum::describe_request(&request));
+                        bail!(
+                            "Estop resume requires {} approvals under quorum rule '{}'. Approvers can run `/quorum-approve {}` in chat; re-run this command once it is approved.",
+                            request.required,
+                            request.rule,
+                            request.id
+                        );
+                    }
+                }
+            }
             let mut otp_code = otp;
             let otp_validator = if config.security.estop.require_otp_to_resume {
                 if !config.security.otp.enabled {
@@ -1323,7 +1483,189 @@ fn handle_estop_command(
             let engage_level = build_engage_level(level, domains, tools)?;
             manager.engage(engage_level)?;
             println!("Estop engaged.");
-            print_estop_status(&manager.status());
+            let status = manager.status();
+            print_estop_status(&status);
+            print_estop_stop_report(manager.state_path(), status.updated_at.as_deref()).await;
+            Ok(())
+        }
+    }
+}
+
+fn handle_users_command(config: &Config, command: UserCommands) -> Result<()> {
+    let config_dir = config
+        .config_path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let directory = security::UserDirectory::load(&config.security, config_dir)?;
+    if !config.security.users.enabled {
+        println!(
+            "Note: the user directory is disabled; set [security.users].enabled = true in config.toml for channels to use it."
+        );
+    }
+
+    match command {
+        UserCommands::List => {
+            let users = directory.list();
+            if users.is_empty() {
+                println!("No users in {}", directory.state_path().display());
+                return Ok(());
+            }
+            println!("Users in {}:", directory.state_path().display());
+            for user in users {
+                print_user(&user);
+            }
+            Ok(())
+        }
+        UserCommands::Show { target } => {
+            let user = if target.contains(':') {
+                let (channel, sender) = security::users::parse_identity(&target)?;
+                directory.resolve(&channel, &sender)
+            } else {
+                directory.get(&target)
+            };
+            let user = user.with_context(|| format!("No user matches '{target}'"))?;
+            print_user(&user);
+            Ok(())
+        }
+        UserCommands::Add {
+            id,
+            name,
+            role,
+            identities,
+        } => {
+            let parsed = identities
+                .iter()
+                .map(|raw| security::users::parse_identity(raw))
+                .collect::<Result<Vec<_>>>()?;
+            let mut user = directory.add_user(&id, name.as_deref().unwrap_or_default(), &role)?;
+            for (channel, sender) in parsed {
+                user = directory.link_identity(&user.id, &channel, &sender)?;
+            }
+            println!("Added user:");
+            print_user(&user);
+            Ok(())
+        }
+        UserCommands::Remove { id } => {
+            if directory.remove_user(&id)? {
+                println!("Removed user '{id}'.");
+            } else {
+                println!("No user '{id}' found.");
+            }
+            Ok(())
+        }
+        UserCommands::SetRole { id, role } => {
+            let user = directory.set_role(&id, &role)?;
+            println!("User '{}' now has role '{}'.", user.id, user.role);
+            Ok(())
+        }
+        UserCommands::Link { id, identity } => {
+            let (channel, sender) = security::users::parse_identity(&identity)?;
+            let user = directory.link_identity(&id, &channel, &sender)?;
+            println!("Linked {channel}:{sender} to user '{}'.", user.id);
+            Ok(())
+        }
+        UserCommands::Unlink { identity } => {
+            let (channel, sender) = security::users::parse_identity(&identity)?;
+            match directory.unlink_identity(&channel, &sender)? {
+                Some(user_id) => println!("Unlinked {channel}:{sender} from user '{user_id}'."),
+                None => println!("{channel}:{sender} is not linked to any user."),
+            }
+            Ok(())
+        }
+        UserCommands::LinkCode { id } => {
+            let (code, expires_at) = directory.issue_link_code(&id)?;
+            println!("One-time link code for '{id}': {code}");
+            println!(
+                "Send `/link {code}` from the channel to link before {}.",
+                expires_at.to_rfc3339()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn print_user(user: &security::UserRecord) {
+    let display_name = if user.display_name.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", user.display_name)
+    };
+    println!("  {}{display_name}  role={}", user.id, user.role);
+    if user.identities.is_empty() {
+        println!("    identities: (none)");
+    } else {
+        println!("    identities: {}", user.identities.join(", "));
+    }
+}
+
+fn handle_secrets_command(config_dir: &std::path::Path, command: SecretCommands) -> Result<()> {
+    use security::secret_backends::{LocalVaultBackend, SecretBackend};
+
+    let vault = LocalVaultBackend::new(config_dir);
+    match command {
+        SecretCommands::Set { name, value } => {
+            let value = match value {
+                Some(value) => value,
+                None => Password::new()
+                    .with_prompt(format!("Value for secret '{name}'"))
+                    .allow_empty_password(false)
+                    .interact()?,
+            };
+            vault.set(&name, &value)?;
+            println!("Stored secret '{name}'. Reference it in config.toml as \"secret://{name}\".");
+            Ok(())
+        }
+        SecretCommands::Get { name, reveal } => {
+            let value = vault.get(&name)?;
+            if reveal {
+                println!("{value}");
+            } else {
+                println!("{name}: {}", security::redact(&value));
+            }
+            Ok(())
+        }
+        SecretCommands::List => {
+            let names = vault.list()?;
+            if names.is_empty() {
+                println!("No secrets stored in {}", vault.path().display());
+                return Ok(());
+            }
+            println!("Secrets in {}:", vault.path().display());
+            for name in names {
+                if let Some(entry) = vault.entry(&name)? {
+                    println!(
+                        "  {name:<32} updated {}  rotations {}",
+                        entry.updated_at, entry.rotations
+                    );
+                }
+            }
+            Ok(())
+        }
+        SecretCommands::Rotate {
+            name,
+            value,
+            generate,
+        } => {
+            let value = if generate {
+                hex::encode(rand::random::<[u8; 32]>())
+            } else if let Some(value) = value {
+                value
+            } else {
+                Password::new()
+                    .with_prompt(format!("New value for secret '{name}'"))
+                    .with_confirmation("Confirm new value", "Values do not match")
+                    .allow_empty_password(false)
+                    .interact()?
+            };
+            let entry = vault.rotate(&name, &value)?;
+            println!(
+                "Rotated secret '{name}' (rotation #{}, updated {}).",
+                entry.rotations, entry.updated_at
+            );
+            if generate {
+                println!("New value: {value}");
+            }
+            println!("Restart running daemons/channels to pick up the new value.");
             Ok(())
         }
     }
@@ -1424,6 +1766,37 @@ fn print_estop_status(state: &security::EstopState) {
     }
 }
 
+async fn print_estop_stop_report(state_path: &std::path::Path, engaged_at: Option<&str>) {
+    let report = security::cancellation::wait_for_report(
+        state_path,
+        engaged_at,
+        std::time::Duration::from_secs(3),
+    )
+    .await;
+    let Some(report) = report else {
+        println!("No running ZeroClaw process reported stopped work (none may be running).");
+        return;
+    };
+    if report.stopped.is_empty() {
+        println!(
+            "Process {} reported no in-flight work to stop.",
+            report.reporter_pid
+        );
+        return;
+    }
+    println!(
+        "Process {} stopped {} in-flight task(s):",
+        report.reporter_pid,
+        report.stopped.len()
+    );
+    for task in &report.stopped {
+        match task.pid {
+            Some(pid) => println!("  - {:?}: {} (pid {pid})", task.kind, task.label),
+            None => println!("  - {:?}: {}", task.kind, task.label),
+        }
+    }
+}
+
 fn write_shell_completion<W: Write>(shell: CompletionShell, writer: &mut W) -> Result<()> {
     use clap_complete::generate;
     use clap_complete::shells;
@@ -2244,4 +2617,113 @@ mod tests {
             other => panic!("expected estop resume command, got {other:?}"),
         }
     }
+
+    #[test]
+    fn cli_parses_users_add_with_identities() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "users",
+            "add",
+            "alice",
+            "--role",
+            "operator",
+            "--identity",
+            "telegram:123",
+            "--identity",
+            "email:alice@example.com",
+        ])
+        .expect("users add command should parse");
+
+        match cli.command {
+            Commands::Users {
+                user_command:
+                    UserCommands::Add {
+                        id,
+                        name,
+                        role,
+                        identities,
+                    },
+            } => {
+                assert_eq!(id, "alice");
+                assert!(name.is_none());
+                assert_eq!(role, "operator");
+                assert_eq!(identities.len(), 2);
+            }
+            other => panic!("expected users add command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_eval_run_with_cassette_and_junit_format() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "eval",
+            "run",
+            "evals/",
+            "--cassette",
+            "evals/replay.json",
+            "--format",
+            "junit",
+        ])
+        .expect("eval run command should parse");
+
+        match cli.command {
+            Commands::Eval {
+                eval_command:
+                    EvalCommands::Run {
+                        suite,
+                        cassette,
+                        record,
+                        format,
+                        ..
+                    },
+            } => {
+                assert_eq!(suite, "evals/");
+                assert_eq!(cassette.as_deref(), Some("evals/replay.json"));
+                assert!(!record);
+                assert_eq!(format, "junit");
+            }
+            other => panic!("expected eval run command, got {other:?}"),
+        }
+
+        assert!(Cli::try_parse_from(["zeroclaw", "eval", "run", "s.yaml", "--record"]).is_err());
+        assert!(
+            Cli::try_parse_from(["zeroclaw", "eval", "run", "s.yaml", "--format", "xml"]).is_err()
+        );
+    }
+
+    #[test]
+    fn cli_parses_sessions_replay_with_turn_and_model() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "sessions",
+            "replay",
+            "telegram_42",
+            "--turn",
+            "2",
+            "--model",
+            "gpt-4.1-mini",
+        ])
+        .expect("sessions replay command should parse");
+
+        match cli.command {
+            Commands::Sessions {
+                session_command:
+                    SessionCommands::Replay {
+                        session,
+                        turn,
+                        provider,
+                        model,
+                        temperature,
+                    },
+            } => {
+                assert_eq!(session, "telegram_42");
+                assert_eq!(turn.as_deref(), Some("2"));
+                assert!(provider.is_none());
+                assert_eq!(model.as_deref(), Some("gpt-4.1-mini"));
+                assert!(temperature.is_none());
+            }
+            other => panic!("expected sessions replay command, got {other:?}"),
+        }
+    }
 }
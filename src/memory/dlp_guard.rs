@@ -0,0 +1,77 @@
+use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use crate::security::dlp::{self, DlpSurface};
+use async_trait::async_trait;
+
+/// Memory decorator that runs `[security.dlp]` on every write.
+///
+/// Matches are redacted or masked before they reach the backend; content that
+/// resolves to `block` or `require-approval` is rejected, since there is no
+/// approver in the loop for memory writes.
+pub struct DlpGuardedMemory {
+    inner: Box<dyn Memory>,
+}
+
+impl DlpGuardedMemory {
+    pub fn new(inner: Box<dyn Memory>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Memory for DlpGuardedMemory {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let outcome = dlp::apply(DlpSurface::MemoryWrite, None, content);
+        if outcome.is_withheld() {
+            anyhow::bail!(
+                "memory write for '{key}' rejected by data-loss-prevention rules: {}",
+                outcome.detector_names().join(", ")
+            );
+        }
+        self.inner
+            .store(key, &outcome.content, category, session_id)
+            .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.inner.recall(query, limit, session_id).await
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        self.inner.get(key).await
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.inner.list(category, session_id).await
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        self.inner.forget(key).await
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        self.inner.count().await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+}
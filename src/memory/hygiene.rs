@@ -444,6 +444,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn named_sessions_survive_hygiene() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        let store = crate::agent::session::SessionStore::new(workspace);
+        store.open("ops").unwrap();
+
+        let path = workspace
+            .join(crate::agent::session::SESSIONS_DIR)
+            .join("ops.json");
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - StdDuration::from_secs(60 * 24 * 60 * 60))
+            .unwrap();
+
+        let cfg = MemoryConfig {
+            archive_after_days: 1,
+            purge_after_days: 1,
+            ..default_cfg()
+        };
+        run_if_due(&cfg, workspace).unwrap();
+
+        assert!(path.exists(), "named session should not be archived");
+        assert!(store.load("ops").unwrap().is_some());
+    }
+
     #[test]
     fn skips_second_run_within_cadence_window() {
         let tmp = TempDir::new().unwrap();
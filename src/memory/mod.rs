@@ -1,6 +1,7 @@
 pub mod backend;
 pub mod chunker;
 pub mod cli;
+pub mod dlp_guard;
 pub mod embeddings;
 pub mod hybrid;
 pub mod hygiene;
@@ -21,6 +22,7 @@ pub use backend::{
     classify_memory_backend, default_memory_backend_key, memory_backend_profile,
     selectable_memory_backends, MemoryBackendKind, MemoryBackendProfile,
 };
+pub use dlp_guard::DlpGuardedMemory;
 pub use hybrid::SqliteQdrantHybridMemory;
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
@@ -193,12 +195,34 @@ pub fn create_memory_with_storage(
 }
 
 /// Factory: create memory with optional storage-provider override and embedding routes.
+///
+/// Writes go through the DLP pipeline when `[security.dlp]` scans memory writes.
 pub fn create_memory_with_storage_and_routes(
     config: &MemoryConfig,
     embedding_routes: &[EmbeddingRouteConfig],
     storage_provider: Option<&StorageProviderConfig>,
     workspace_dir: &Path,
     api_key: Option<&str>,
+) -> anyhow::Result<Box<dyn Memory>> {
+    let memory = create_backend_memory(
+        config,
+        embedding_routes,
+        storage_provider,
+        workspace_dir,
+        api_key,
+    )?;
+    if crate::security::dlp::runtime_scans(crate::security::dlp::DlpSurface::MemoryWrite) {
+        return Ok(Box::new(DlpGuardedMemory::new(memory)));
+    }
+    Ok(memory)
+}
+
+fn create_backend_memory(
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
+    storage_provider: Option<&StorageProviderConfig>,
+    workspace_dir: &Path,
+    api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
     let backend_name = effective_memory_backend_name(&config.backend, storage_provider);
     let backend_kind = classify_memory_backend(&backend_name);
@@ -0,0 +1,852 @@
+//! Cross-channel notification routing.
+//!
+//! A [`Notification`] goes to named targets (`[notifications.targets]`),
+//! either explicitly or through severity/source routing rules. Repeats within
+//! the dedup window are dropped, notifications arriving during the
+//! recipient's quiet hours are held, and digest targets batch non-critical
+//! notifications into one message. Each target fans out to channel
+//! destinations through the cron announcement path, plus Pushover.
+//!
+//! Held queues and dedup history live in process memory and do not survive a
+//! restart.
+
+use crate::config::{Config, NotificationSeverity, NotificationsConfig, QuietHoursConfig};
+use anyhow::Result;
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Upper bound on notifications held per target; the oldest are dropped.
+const MAX_HELD_PER_TARGET: usize = 200;
+/// Digest messages list at most this many entries.
+const DIGEST_MAX_LISTED: usize = 25;
+/// Longest single-entry preview inside a digest.
+const DIGEST_ENTRY_MAX_CHARS: usize = 200;
+/// How often the daemon flushes due digests and quiet-hours backlogs.
+pub const FLUSH_INTERVAL_SECS: u64 = 60;
+
+/// A notification to route.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub title: Option<String>,
+    pub message: String,
+    pub severity: NotificationSeverity,
+    /// Producer used by routing rules (`agent`, `gateway`, `cron`,
+    /// `heartbeat`, `alert_forwarder`, ...).
+    pub source: String,
+    /// Explicit target; skips routing rules.
+    pub target: Option<String>,
+    /// Deduplication key; defaults to source, severity, title and message.
+    pub dedup_key: Option<String>,
+}
+
+impl Notification {
+    pub fn new(
+        source: impl Into<String>,
+        severity: NotificationSeverity,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            title: None,
+            message: message.into(),
+            severity,
+            source: source.into(),
+            target: None,
+            dedup_key: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn for_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn with_dedup_key(mut self, key: impl Into<String>) -> Self {
+        self.dedup_key = Some(key.into());
+        self
+    }
+
+    fn dedup_key(&self) -> String {
+        self.dedup_key.clone().unwrap_or_else(|| {
+            format!(
+                "{}\u{1f}{}\u{1f}{}\u{1f}{}",
+                self.source,
+                self.severity.as_str(),
+                self.title.as_deref().unwrap_or_default(),
+                self.message.trim()
+            )
+        })
+    }
+
+    fn render(&self) -> String {
+        let icon = severity_icon(self.severity);
+        match self.title.as_deref() {
+            Some(title) => format!("{icon} {title}\n{}", self.message.trim()),
+            None => format!("{icon} {}", self.message.trim()),
+        }
+    }
+}
+
+/// Parse a severity name (`info`, `warning`/`warn`, `critical`).
+pub fn parse_severity(raw: &str) -> Option<NotificationSeverity> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "info" => Some(NotificationSeverity::Info),
+        "warning" | "warn" => Some(NotificationSeverity::Warning),
+        "critical" | "crit" => Some(NotificationSeverity::Critical),
+        _ => None,
+    }
+}
+
+fn severity_icon(severity: NotificationSeverity) -> &'static str {
+    match severity {
+        NotificationSeverity::Info => "ℹ️",
+        NotificationSeverity::Warning => "⚠️",
+        NotificationSeverity::Critical => "🚨",
+    }
+}
+
+fn pushover_priority(severity: NotificationSeverity) -> i64 {
+    match severity {
+        NotificationSeverity::Info => -1,
+        NotificationSeverity::Warning => 0,
+        NotificationSeverity::Critical => 1,
+    }
+}
+
+/// Outcome of routing one notification.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NotifyReport {
+    /// Targets the notification was routed to.
+    pub targets: Vec<String>,
+    /// Destinations that accepted it (`channel:recipient`).
+    pub delivered: Vec<String>,
+    /// Targets holding it for a digest or until quiet hours end.
+    pub queued: Vec<String>,
+    /// Destinations that failed, with the error.
+    pub failed: Vec<String>,
+    /// Dropped as a repeat within the dedup window.
+    pub duplicate: bool,
+}
+
+impl NotifyReport {
+    pub fn summary(&self) -> String {
+        if self.duplicate {
+            return "Duplicate notification suppressed.".to_string();
+        }
+        let mut summary = format!("Routed to {}.", self.targets.join(", "));
+        if !self.delivered.is_empty() {
+            let _ = write!(summary, " Delivered: {}.", self.delivered.join(", "));
+        }
+        if !self.queued.is_empty() {
+            let _ = write!(summary, " Held for later: {}.", self.queued.join(", "));
+        }
+        if !self.failed.is_empty() {
+            let _ = write!(summary, " Failed: {}.", self.failed.join("; "));
+        }
+        summary
+    }
+}
+
+/// A rendered message ready for every destination of `target`.
+#[derive(Debug, Clone)]
+struct Outgoing {
+    target: String,
+    title: Option<String>,
+    severity: NotificationSeverity,
+    text: String,
+}
+
+#[derive(Debug)]
+struct HeldQueue {
+    opened_at: Instant,
+    items: Vec<Notification>,
+}
+
+#[derive(Debug, Default)]
+struct RouterState {
+    recent: HashMap<String, Instant>,
+    held: HashMap<String, HeldQueue>,
+}
+
+fn router_state() -> &'static Mutex<RouterState> {
+    static STATE: OnceLock<Mutex<RouterState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(RouterState::default()))
+}
+
+fn resolve_targets(
+    settings: &NotificationsConfig,
+    notification: &Notification,
+) -> Result<Vec<String>> {
+    let known = |name: &str| -> Result<String> {
+        if settings.targets.contains_key(name) {
+            Ok(name.to_string())
+        } else {
+            anyhow::bail!("unknown notification target `{name}`")
+        }
+    };
+
+    if let Some(target) = notification.target.as_deref().map(str::trim) {
+        return Ok(vec![known(target)?]);
+    }
+
+    let mut targets: Vec<String> = Vec::new();
+    for route in &settings.routes {
+        let source_matches = route.sources.is_empty()
+            || route
+                .sources
+                .iter()
+                .any(|source| source.eq_ignore_ascii_case(&notification.source));
+        if notification.severity < route.min_severity || !source_matches {
+            continue;
+        }
+        for target in &route.targets {
+            let target = known(target)?;
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    if !targets.is_empty() {
+        return Ok(targets);
+    }
+
+    match settings.default_target.as_deref() {
+        Some(target) => Ok(vec![known(target)?]),
+        None => anyhow::bail!(
+            "no notification route matches {} notifications from `{}` and no default_target is set",
+            notification.severity.as_str(),
+            notification.source
+        ),
+    }
+}
+
+fn parse_clock(raw: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(raw.trim(), "%H:%M").ok()
+}
+
+fn in_quiet_hours(quiet: &QuietHoursConfig, now: DateTime<Utc>) -> bool {
+    let (Some(start), Some(end)) = (parse_clock(&quiet.start), parse_clock(&quiet.end)) else {
+        tracing::warn!(
+            "Ignoring quiet hours with invalid start/end ({} - {}); expected HH:MM",
+            quiet.start,
+            quiet.end
+        );
+        return false;
+    };
+    let local = match quiet.timezone.as_deref() {
+        Some(name) => match name.parse::<chrono_tz::Tz>() {
+            Ok(tz) => now.with_timezone(&tz).time(),
+            Err(_) => {
+                tracing::warn!("Ignoring unknown quiet hours timezone `{name}`; using UTC");
+                now.time()
+            }
+        },
+        None => now.time(),
+    };
+
+    if start <= end {
+        start <= local && local < end
+    } else {
+        local >= start || local < end
+    }
+}
+
+fn is_quiet_for(
+    settings: &NotificationsConfig,
+    target: &str,
+    now: DateTime<Utc>,
+) -> Option<NotificationSeverity> {
+    let user = settings.targets.get(target)?.user.as_deref()?;
+    let quiet = settings.quiet_hours.get(user)?;
+    in_quiet_hours(quiet, now).then_some(quiet.bypass_severity)
+}
+
+fn render_held(target: &str, items: &[Notification]) -> Outgoing {
+    let severity = items
+        .iter()
+        .map(|item| item.severity)
+        .max()
+        .unwrap_or_default();
+    if let [only] = items {
+        return Outgoing {
+            target: target.to_string(),
+            title: only.title.clone(),
+            severity,
+            text: only.render(),
+        };
+    }
+
+    let mut text = format!("📬 {} notifications for `{target}`", items.len());
+    for item in items.iter().take(DIGEST_MAX_LISTED) {
+        let body = item.message.lines().next().unwrap_or_default().trim();
+        let entry = match item.title.as_deref() {
+            Some(title) => format!("{title}: {body}"),
+            None => body.to_string(),
+        };
+        let _ = write!(
+            text,
+            "\n• {} {}",
+            severity_icon(item.severity),
+            crate::util::truncate_with_ellipsis(&entry, DIGEST_ENTRY_MAX_CHARS)
+        );
+    }
+    if items.len() > DIGEST_MAX_LISTED {
+        let _ = write!(text, "\n…and {} more", items.len() - DIGEST_MAX_LISTED);
+    }
+
+    Outgoing {
+        target: target.to_string(),
+        title: Some(format!("{} notifications", items.len())),
+        severity,
+        text,
+    }
+}
+
+impl RouterState {
+    /// Route one notification. Returns the messages to send right away and
+    /// records targets, held queues and duplicates in `report`.
+    ///
+    /// Reserves the dedup key while the caller still holds the lock, so a
+    /// concurrent identical notification is reported as a duplicate; call
+    /// [`RouterState::release`] if delivery then fails.
+    fn admit(
+        &mut self,
+        settings: &NotificationsConfig,
+        notification: &Notification,
+        now: Instant,
+        wall: DateTime<Utc>,
+        report: &mut NotifyReport,
+    ) -> Result<Vec<Outgoing>> {
+        let targets = resolve_targets(settings, notification)?;
+
+        if settings.dedup_window_secs > 0 {
+            let window = Duration::from_secs(settings.dedup_window_secs);
+            self.recent
+                .retain(|_, seen| now.saturating_duration_since(*seen) < window);
+            if self.recent.contains_key(&notification.dedup_key()) {
+                report.duplicate = true;
+                return Ok(Vec::new());
+            }
+            self.recent.insert(notification.dedup_key(), now);
+        }
+
+        let mut outgoing = Vec::new();
+        for target in targets {
+            let digest = settings
+                .targets
+                .get(&target)
+                .is_some_and(|config| config.digest);
+            let quiet = is_quiet_for(settings, &target, wall)
+                .is_some_and(|bypass| notification.severity < bypass);
+            let batched = digest && notification.severity < NotificationSeverity::Critical;
+
+            if quiet || batched {
+                let queue = self
+                    .held
+                    .entry(target.clone())
+                    .or_insert_with(|| HeldQueue {
+                        opened_at: now,
+                        items: Vec::new(),
+                    });
+                queue.items.push(notification.clone());
+                if queue.items.len() > MAX_HELD_PER_TARGET {
+                    queue.items.remove(0);
+                }
+                report.queued.push(target.clone());
+            } else {
+                outgoing.push(Outgoing {
+                    target: target.clone(),
+                    title: notification.title.clone(),
+                    severity: notification.severity,
+                    text: notification.render(),
+                });
+            }
+            report.targets.push(target);
+        }
+        Ok(outgoing)
+    }
+
+    /// Drop the dedup key [`RouterState::admit`] reserved. Called when no
+    /// target delivered or held `notification`, so failed sends are not
+    /// suppressed as repeats.
+    fn release(&mut self, notification: &Notification) {
+        self.recent.remove(&notification.dedup_key());
+    }
+
+    /// Take held queues that are due: digests whose interval elapsed and
+    /// backlogs whose quiet hours ended.
+    fn take_due(
+        &mut self,
+        settings: &NotificationsConfig,
+        now: Instant,
+        wall: DateTime<Utc>,
+    ) -> Vec<Outgoing> {
+        let interval = Duration::from_secs(settings.digest_interval_secs);
+        let due: Vec<String> = self
+            .held
+            .iter()
+            .filter(|(target, queue)| {
+                let Some(config) = settings.targets.get(target.as_str()) else {
+                    return true;
+                };
+                if is_quiet_for(settings, target, wall).is_some() {
+                    return false;
+                }
+                !config.digest || now.saturating_duration_since(queue.opened_at) >= interval
+            })
+            .map(|(target, _)| target.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|target| {
+                let queue = self.held.remove(&target)?;
+                if !settings.targets.contains_key(&target) || queue.items.is_empty() {
+                    return None;
+                }
+                Some(render_held(&target, &queue.items))
+            })
+            .collect()
+    }
+}
+
+async fn deliver(config: &Config, outgoing: &Outgoing, report: &mut NotifyReport) {
+    let Some(target) = config.notifications.targets.get(&outgoing.target) else {
+        return;
+    };
+    if target.destinations.is_empty() {
+        tracing::warn!(
+            "Notification target `{}` has no destinations",
+            outgoing.target
+        );
+    }
+
+    for destination in &target.destinations {
+        let channel = destination.channel.trim().to_ascii_lowercase();
+        let recipient = destination
+            .to
+            .as_deref()
+            .map(str::trim)
+            .filter(|to| !to.is_empty());
+        let label = match recipient {
+            Some(to) => format!("{channel}:{to}"),
+            None => channel.clone(),
+        };
+
+        let result = match (channel.as_str(), recipient) {
+            ("pushover", _) => {
+                crate::tools::pushover::send_pushover_message(
+                    &config.workspace_dir,
+                    outgoing.title.as_deref(),
+                    &outgoing.text,
+                    pushover_priority(outgoing.severity),
+                )
+                .await
+            }
+            ("notify", _) => Err(anyhow::anyhow!(
+                "notification destinations cannot route back into `notify`"
+            )),
+            (_, None) => Err(anyhow::anyhow!("destination has no `to` recipient")),
+            (_, Some(to)) => {
+                crate::cron::scheduler::deliver_announcement(config, &channel, to, &outgoing.text)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(()) => report.delivered.push(label),
+            Err(e) => {
+                tracing::warn!(
+                    "Notification delivery to {label} (target `{}`) failed: {e}",
+                    outgoing.target
+                );
+                report.failed.push(format!("{label}: {e}"));
+            }
+        }
+    }
+}
+
+/// Route and deliver a notification.
+///
+/// Fails only when the notification cannot be routed (disabled, empty,
+/// unknown target, no matching route); per-destination failures are listed
+/// in the report. Due digests are flushed first.
+pub async fn notify(config: &Config, notification: Notification) -> Result<NotifyReport> {
+    let settings = &config.notifications;
+    if !settings.enabled {
+        anyhow::bail!("notifications are disabled; set [notifications] enabled = true");
+    }
+    if notification.message.trim().is_empty() {
+        anyhow::bail!("notification message is empty");
+    }
+
+    let now = Instant::now();
+    let wall = Utc::now();
+    let mut report = NotifyReport::default();
+    let (due, outgoing) = {
+        let mut state = router_state().lock().unwrap_or_else(|e| e.into_inner());
+        let due = state.take_due(settings, now, wall);
+        let outgoing = state.admit(settings, &notification, now, wall, &mut report)?;
+        (due, outgoing)
+    };
+
+    for held in &due {
+        deliver(config, held, &mut NotifyReport::default()).await;
+    }
+    for message in &outgoing {
+        deliver(config, message, &mut report).await;
+    }
+    if !report.duplicate && report.delivered.is_empty() && report.queued.is_empty() {
+        router_state()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .release(&notification);
+    }
+    Ok(report)
+}
+
+/// Deliver cron/heartbeat output through a notification target. `recipient`
+/// is the target name, optionally with a severity (`oncall:warning`).
+/// Fails when nothing could be delivered or held.
+pub async fn announce(config: &Config, source: &str, recipient: &str, text: &str) -> Result<()> {
+    let (target, severity) = parse_recipient(recipient)?;
+    let report = notify(
+        config,
+        Notification::new(source, severity, text).for_target(target),
+    )
+    .await?;
+    if report.delivered.is_empty() && report.queued.is_empty() && !report.failed.is_empty() {
+        anyhow::bail!(
+            "notification target `{target}` failed: {}",
+            report.failed.join("; ")
+        );
+    }
+    Ok(())
+}
+
+/// Check that a `target[:severity]` recipient names a configured target, so
+/// heartbeat and cron delivery can fail at startup instead of per tick.
+pub fn validate_recipient(config: &Config, recipient: &str) -> Result<()> {
+    if !config.notifications.enabled {
+        anyhow::bail!("delivery channel `notify` requires [notifications] enabled = true");
+    }
+    let (target, _) = parse_recipient(recipient)?;
+    if !config.notifications.targets.contains_key(target) {
+        anyhow::bail!("unknown notification target `{target}`");
+    }
+    Ok(())
+}
+
+fn parse_recipient(recipient: &str) -> Result<(&str, NotificationSeverity)> {
+    let recipient = recipient.trim();
+    match recipient.split_once(':') {
+        Some((target, severity)) => {
+            let severity = parse_severity(severity).ok_or_else(|| {
+                anyhow::anyhow!("unknown notification severity `{severity}` in `{recipient}`")
+            })?;
+            Ok((target.trim(), severity))
+        }
+        None => Ok((recipient, NotificationSeverity::Info)),
+    }
+}
+
+/// Deliver digests and quiet-hours backlogs that are due. Returns how many
+/// held messages were sent.
+pub async fn flush_due(config: &Config) -> usize {
+    if !config.notifications.enabled {
+        return 0;
+    }
+    let due = router_state()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take_due(&config.notifications, Instant::now(), Utc::now());
+    for held in &due {
+        deliver(config, held, &mut NotifyReport::default()).await;
+    }
+    due.len()
+}
+
+/// Daemon worker that periodically flushes held notifications.
+pub async fn run_flush_worker(config: Config) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(FLUSH_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        let sent = flush_due(&config).await;
+        if sent > 0 {
+            tracing::info!("Flushed {sent} held notification digest(s)");
+        }
+        crate::health::mark_component_ok("notifications");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        NotificationDestination, NotificationRouteConfig, NotificationTargetConfig,
+    };
+    use chrono::TimeZone;
+
+    fn target(user: Option<&str>, digest: bool) -> NotificationTargetConfig {
+        NotificationTargetConfig {
+            destinations: vec![NotificationDestination {
+                channel: "slack".into(),
+                to: Some("C_OPS".into()),
+            }],
+            user: user.map(str::to_string),
+            digest,
+        }
+    }
+
+    fn settings() -> NotificationsConfig {
+        let mut settings = NotificationsConfig {
+            enabled: true,
+            ..NotificationsConfig::default()
+        };
+        settings
+            .targets
+            .insert("oncall".into(), target(Some("alice"), false));
+        settings.targets.insert("ops".into(), target(None, true));
+        settings.routes = vec![
+            NotificationRouteConfig {
+                min_severity: NotificationSeverity::Critical,
+                sources: vec![],
+                targets: vec!["oncall".into(), "ops".into()],
+            },
+            NotificationRouteConfig {
+                min_severity: NotificationSeverity::Info,
+                sources: vec!["heartbeat".into()],
+                targets: vec!["ops".into()],
+            },
+        ];
+        settings
+    }
+
+    fn noon() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn resolve_targets_uses_severity_and_source_rules() {
+        let settings = settings();
+        let critical = Notification::new("agent", NotificationSeverity::Critical, "db down");
+        assert_eq!(
+            resolve_targets(&settings, &critical).unwrap(),
+            vec!["oncall".to_string(), "ops".to_string()]
+        );
+
+        let heartbeat = Notification::new("heartbeat", NotificationSeverity::Info, "ok");
+        assert_eq!(resolve_targets(&settings, &heartbeat).unwrap(), vec!["ops"]);
+
+        let unrouted = Notification::new("agent", NotificationSeverity::Info, "fyi");
+        assert!(resolve_targets(&settings, &unrouted).is_err());
+
+        let explicit = unrouted.clone().for_target("oncall");
+        assert_eq!(
+            resolve_targets(&settings, &explicit).unwrap(),
+            vec!["oncall"]
+        );
+        assert!(resolve_targets(&settings, &unrouted.for_target("nobody")).is_err());
+    }
+
+    #[test]
+    fn admit_drops_repeats_within_dedup_window() {
+        let settings = settings();
+        let mut state = RouterState::default();
+        let now = Instant::now();
+        let alert = Notification::new("agent", NotificationSeverity::Critical, "db down")
+            .for_target("oncall");
+
+        let mut first = NotifyReport::default();
+        let sent = state
+            .admit(&settings, &alert, now, noon(), &mut first)
+            .unwrap();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].text.contains("db down"));
+
+        // A concurrent identical notification sees the reserved key.
+        let mut racing = NotifyReport::default();
+        let sent = state
+            .admit(&settings, &alert, now, noon(), &mut racing)
+            .unwrap();
+        assert!(sent.is_empty());
+        assert!(racing.duplicate);
+
+        // Failed deliveries release the key so a retry goes out.
+        state.release(&alert);
+        let mut retry = NotifyReport::default();
+        let sent = state
+            .admit(&settings, &alert, now, noon(), &mut retry)
+            .unwrap();
+        assert_eq!(sent.len(), 1, "undelivered notifications are not deduped");
+        assert!(!retry.duplicate);
+
+        let mut second = NotifyReport::default();
+        let sent = state
+            .admit(
+                &settings,
+                &alert,
+                now + Duration::from_secs(5),
+                noon(),
+                &mut second,
+            )
+            .unwrap();
+        assert!(sent.is_empty());
+        assert!(second.duplicate);
+
+        let mut later = NotifyReport::default();
+        let sent = state
+            .admit(
+                &settings,
+                &alert,
+                now + Duration::from_secs(601),
+                noon(),
+                &mut later,
+            )
+            .unwrap();
+        assert_eq!(sent.len(), 1);
+    }
+
+    #[test]
+    fn digest_targets_batch_until_interval_elapses() {
+        let settings = settings();
+        let mut state = RouterState::default();
+        let now = Instant::now();
+        for text in ["disk 80%", "disk 85%", "disk 90%"] {
+            let mut report = NotifyReport::default();
+            let sent = state
+                .admit(
+                    &settings,
+                    &Notification::new("heartbeat", NotificationSeverity::Warning, text),
+                    now,
+                    noon(),
+                    &mut report,
+                )
+                .unwrap();
+            assert!(sent.is_empty());
+            assert_eq!(report.queued, vec!["ops"]);
+        }
+
+        assert!(state.take_due(&settings, now, noon()).is_empty());
+        let due = state.take_due(&settings, now + Duration::from_secs(900), noon());
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].severity, NotificationSeverity::Warning);
+        assert!(due[0].text.starts_with("📬 3 notifications for `ops`"));
+        assert!(due[0].text.contains("disk 90%"));
+        assert!(state.held.is_empty());
+    }
+
+    #[test]
+    fn quiet_hours_hold_until_window_ends_unless_severity_bypasses() {
+        let mut settings = settings();
+        settings.quiet_hours.insert(
+            "alice".into(),
+            QuietHoursConfig {
+                start: "22:00".into(),
+                end: "07:00".into(),
+                timezone: Some("Europe/Berlin".into()),
+                bypass_severity: NotificationSeverity::Critical,
+            },
+        );
+        let mut state = RouterState::default();
+        let now = Instant::now();
+        // 23:30 UTC is 00:30 in Berlin (winter time).
+        let night = Utc.with_ymd_and_hms(2026, 3, 2, 23, 30, 0).unwrap();
+
+        let mut report = NotifyReport::default();
+        let sent = state
+            .admit(
+                &settings,
+                &Notification::new("agent", NotificationSeverity::Warning, "backup slow")
+                    .for_target("oncall"),
+                now,
+                night,
+                &mut report,
+            )
+            .unwrap();
+        assert!(sent.is_empty());
+        assert_eq!(report.queued, vec!["oncall"]);
+
+        let mut report = NotifyReport::default();
+        let sent = state
+            .admit(
+                &settings,
+                &Notification::new("agent", NotificationSeverity::Critical, "backup failed")
+                    .for_target("oncall"),
+                now,
+                night,
+                &mut report,
+            )
+            .unwrap();
+        assert_eq!(sent.len(), 1);
+
+        assert!(state.take_due(&settings, now, night).is_empty());
+        let morning = Utc.with_ymd_and_hms(2026, 3, 3, 7, 0, 0).unwrap();
+        let due = state.take_due(&settings, now, morning);
+        assert_eq!(due.len(), 1);
+        assert!(due[0].text.contains("backup slow"));
+    }
+
+    #[test]
+    fn in_quiet_hours_handles_same_day_windows() {
+        let quiet = QuietHoursConfig {
+            start: "09:00".into(),
+            end: "17:00".into(),
+            timezone: None,
+            bypass_severity: NotificationSeverity::Critical,
+        };
+        assert!(in_quiet_hours(&quiet, noon()));
+        let evening = Utc.with_ymd_and_hms(2026, 3, 2, 17, 0, 0).unwrap();
+        assert!(!in_quiet_hours(&quiet, evening));
+    }
+
+    #[test]
+    fn parse_recipient_reads_optional_severity() {
+        assert_eq!(
+            parse_recipient("oncall").unwrap(),
+            ("oncall", NotificationSeverity::Info)
+        );
+        assert_eq!(
+            parse_recipient("oncall:critical").unwrap(),
+            ("oncall", NotificationSeverity::Critical)
+        );
+        assert!(parse_recipient("oncall:loud").is_err());
+    }
+
+    #[test]
+    fn validate_recipient_requires_known_target() {
+        let mut config = Config::default();
+        assert!(validate_recipient(&config, "oncall")
+            .unwrap_err()
+            .to_string()
+            .contains("enabled = true"));
+
+        config.notifications.enabled = true;
+        config
+            .notifications
+            .targets
+            .insert("oncall".into(), NotificationTargetConfig::default());
+        assert!(validate_recipient(&config, "oncall:critical").is_ok());
+        assert!(validate_recipient(&config, "family")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown notification target"));
+    }
+
+    #[test]
+    fn parse_severity_accepts_aliases() {
+        assert_eq!(parse_severity("WARN"), Some(NotificationSeverity::Warning));
+        assert_eq!(
+            parse_severity(" critical "),
+            Some(NotificationSeverity::Critical)
+        );
+        assert_eq!(parse_severity("loud"), None);
+    }
+}
@@ -47,9 +47,35 @@ impl Observer for LogObserver {
             ObserverEvent::HeartbeatTick => {
                 info!("heartbeat.tick");
             }
+            ObserverEvent::DlpDetection {
+                surface,
+                detector,
+                action,
+            } => {
+                info!(surface = %surface, detector = %detector, action = %action, "dlp.detection");
+            }
             ObserverEvent::Error { component, message } => {
                 info!(component = %component, error = %message, "error");
             }
+            ObserverEvent::SpanEnd {
+                span,
+                name,
+                duration,
+                error_message,
+                ..
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(
+                    trace_id = %span.trace_id_hex(),
+                    span_id = %span.span_id_hex(),
+                    parent_span_id = ?span.parent_span_id.map(|id| format!("{id:016x}")),
+                    kind = span.kind.as_str(),
+                    name = %name,
+                    duration_ms = ms,
+                    error = ?error_message,
+                    "span.end"
+                );
+            }
             ObserverEvent::LlmRequest {
                 provider,
                 model,
@@ -6,7 +6,9 @@ pub mod noop;
 pub mod otel;
 pub mod prometheus;
 pub mod runtime_trace;
+pub mod span;
 pub mod traits;
+pub mod transcript;
 pub mod verbose;
 
 #[allow(unused_imports)]
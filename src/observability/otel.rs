@@ -1,7 +1,11 @@
+use super::span::{self as agent_span, SpanValue};
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
 use opentelemetry::metrics::{Counter, Gauge, Histogram};
-use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
-use opentelemetry::{global, KeyValue};
+use opentelemetry::trace::{
+    Span, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId, TraceState,
+    Tracer,
+};
+use opentelemetry::{global, Context, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::trace::SdkTracerProvider;
@@ -23,6 +27,7 @@ pub struct OtelObserver {
     channel_messages: Counter<u64>,
     heartbeat_ticks: Counter<u64>,
     errors: Counter<u64>,
+    dlp_detections: Counter<u64>,
     request_latency: Histogram<f64>,
     tokens_used: Counter<u64>,
     active_sessions: Gauge<u64>,
@@ -131,6 +136,11 @@ impl OtelObserver {
             .with_description("Total errors by component")
             .build();
 
+        let dlp_detections = meter
+            .u64_counter("zeroclaw.dlp.detections")
+            .with_description("Total DLP detections by surface, detector and action")
+            .build();
+
         let request_latency = meter
             .f64_histogram("zeroclaw.request.latency")
             .with_description("Request latency in seconds")
@@ -164,6 +174,7 @@ impl OtelObserver {
             channel_messages,
             heartbeat_ticks,
             errors,
+            dlp_detections,
             request_latency,
             tokens_used,
             active_sessions,
@@ -207,6 +218,12 @@ impl Observer for OtelObserver {
                 self.llm_calls.add(1, &attrs);
                 self.llm_duration.record(secs, &attrs);
 
+                // Inside a traced agent turn the call is exported as a
+                // hierarchical `SpanEnd` instead.
+                if agent_span::current().is_some() {
+                    return;
+                }
+
                 // Create a completed span for visibility in trace backends.
                 let start_time = SystemTime::now()
                     .checked_sub(*duration)
@@ -276,28 +293,30 @@ impl Observer for OtelObserver {
                 success,
             } => {
                 let secs = duration.as_secs_f64();
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-
-                let status = if *success {
-                    Status::Ok
-                } else {
-                    Status::error("")
-                };
-
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("tool.call")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("tool.name", tool.clone()),
-                            KeyValue::new("tool.success", *success),
-                            KeyValue::new("duration_s", secs),
-                        ]),
-                );
-                span.set_status(status);
-                span.end();
+                if agent_span::current().is_none() {
+                    let start_time = SystemTime::now()
+                        .checked_sub(*duration)
+                        .unwrap_or(SystemTime::now());
+
+                    let status = if *success {
+                        Status::Ok
+                    } else {
+                        Status::error("")
+                    };
+
+                    let mut span = tracer.build(
+                        opentelemetry::trace::SpanBuilder::from_name("tool.call")
+                            .with_kind(SpanKind::Internal)
+                            .with_start_time(start_time)
+                            .with_attributes(vec![
+                                KeyValue::new("tool.name", tool.clone()),
+                                KeyValue::new("tool.success", *success),
+                                KeyValue::new("duration_s", secs),
+                            ]),
+                    );
+                    span.set_status(status);
+                    span.end();
+                }
 
                 let attrs = [
                     KeyValue::new("tool", tool.clone()),
@@ -307,6 +326,58 @@ impl Observer for OtelObserver {
                 self.tool_duration
                     .record(secs, &[KeyValue::new("tool", tool.clone())]);
             }
+            ObserverEvent::SpanEnd {
+                span,
+                name,
+                start_time,
+                duration,
+                error_message,
+                attributes,
+            } => {
+                let trace_id = TraceId::from_bytes(span.trace_id.to_be_bytes());
+                let parent_cx = match span.parent_span_id {
+                    Some(parent_id) => Context::new().with_remote_span_context(SpanContext::new(
+                        trace_id,
+                        SpanId::from_bytes(parent_id.to_be_bytes()),
+                        TraceFlags::SAMPLED,
+                        true,
+                        TraceState::default(),
+                    )),
+                    None => Context::new(),
+                };
+                let kind = match span.kind {
+                    agent_span::SpanKind::LlmCall => SpanKind::Client,
+                    _ => SpanKind::Internal,
+                };
+                let mut otel_attributes: Vec<KeyValue> = attributes
+                    .iter()
+                    .map(|(key, value)| match value {
+                        SpanValue::String(value) => KeyValue::new(*key, value.clone()),
+                        SpanValue::I64(value) => KeyValue::new(*key, *value),
+                        SpanValue::F64(value) => KeyValue::new(*key, *value),
+                        SpanValue::Bool(value) => KeyValue::new(*key, *value),
+                    })
+                    .collect();
+                otel_attributes.push(KeyValue::new("zeroclaw.span.kind", span.kind.as_str()));
+
+                let mut otel_span = tracer.build_with_context(
+                    opentelemetry::trace::SpanBuilder::from_name(name.clone())
+                        .with_kind(kind)
+                        .with_trace_id(trace_id)
+                        .with_span_id(SpanId::from_bytes(span.span_id.to_be_bytes()))
+                        .with_start_time(*start_time)
+                        .with_attributes(otel_attributes),
+                    &parent_cx,
+                );
+                match error_message {
+                    Some(message) => {
+                        otel_span.set_attribute(KeyValue::new("error.type", "error"));
+                        otel_span.set_status(Status::error(message.clone()));
+                    }
+                    None => otel_span.set_status(Status::Ok),
+                }
+                otel_span.end_with_timestamp(*start_time + *duration);
+            }
             ObserverEvent::ChannelMessage { channel, direction } => {
                 self.channel_messages.add(
                     1,
@@ -319,6 +390,20 @@ impl Observer for OtelObserver {
             ObserverEvent::HeartbeatTick => {
                 self.heartbeat_ticks.add(1, &[]);
             }
+            ObserverEvent::DlpDetection {
+                surface,
+                detector,
+                action,
+            } => {
+                self.dlp_detections.add(
+                    1,
+                    &[
+                        KeyValue::new("surface", surface.clone()),
+                        KeyValue::new("detector", detector.clone()),
+                        KeyValue::new("action", action.clone()),
+                    ],
+                );
+            }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
                 let mut span = tracer.build(
@@ -446,6 +531,26 @@ mod tests {
             success: false,
         });
         obs.record_event(&ObserverEvent::TurnComplete);
+        let turn = agent_span::SpanContext::root(agent_span::SpanKind::Turn);
+        for (span, error_message) in [
+            (turn, None),
+            (
+                turn.child(agent_span::SpanKind::LlmCall),
+                Some("timeout".to_string()),
+            ),
+        ] {
+            obs.record_event(&ObserverEvent::SpanEnd {
+                span,
+                name: "chat claude-sonnet".into(),
+                start_time: SystemTime::now(),
+                duration: Duration::from_millis(20),
+                error_message,
+                attributes: vec![
+                    ("gen_ai.request.model", SpanValue::from("claude-sonnet")),
+                    ("gen_ai.usage.input_tokens", SpanValue::from(100_u64)),
+                ],
+            });
+        }
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "inbound".into(),
@@ -16,6 +16,7 @@ pub struct PrometheusObserver {
     channel_messages: IntCounterVec,
     heartbeat_ticks: prometheus::IntCounter,
     errors: IntCounterVec,
+    dlp_detections: IntCounterVec,
 
     // Histograms
     agent_duration: HistogramVec,
@@ -81,6 +82,15 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let dlp_detections = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_dlp_detections_total",
+                "Total DLP detections by surface, detector and action",
+            ),
+            &["surface", "detector", "action"],
+        )
+        .expect("valid metric");
+
         let agent_duration = HistogramVec::new(
             HistogramOpts::new(
                 "zeroclaw_agent_duration_seconds",
@@ -139,6 +149,7 @@ impl PrometheusObserver {
         registry.register(Box::new(channel_messages.clone())).ok();
         registry.register(Box::new(heartbeat_ticks.clone())).ok();
         registry.register(Box::new(errors.clone())).ok();
+        registry.register(Box::new(dlp_detections.clone())).ok();
         registry.register(Box::new(agent_duration.clone())).ok();
         registry.register(Box::new(tool_duration.clone())).ok();
         registry.register(Box::new(request_latency.clone())).ok();
@@ -156,6 +167,7 @@ impl PrometheusObserver {
             channel_messages,
             heartbeat_ticks,
             errors,
+            dlp_detections,
             agent_duration,
             tool_duration,
             request_latency,
@@ -223,7 +235,8 @@ impl Observer for PrometheusObserver {
             }
             ObserverEvent::ToolCallStart { tool: _ }
             | ObserverEvent::TurnComplete
-            | ObserverEvent::LlmRequest { .. } => {}
+            | ObserverEvent::LlmRequest { .. }
+            | ObserverEvent::SpanEnd { .. } => {}
             ObserverEvent::ToolCall {
                 tool,
                 duration,
@@ -251,6 +264,15 @@ impl Observer for PrometheusObserver {
             } => {
                 self.errors.with_label_values(&[component]).inc();
             }
+            ObserverEvent::DlpDetection {
+                surface,
+                detector,
+                action,
+            } => {
+                self.dlp_detections
+                    .with_label_values(&[surface, detector, action])
+                    .inc();
+            }
         }
     }
 
@@ -405,6 +427,23 @@ mod tests {
         assert!(output.contains(r#"zeroclaw_tool_calls_total{success="false",tool="shell"} 1"#));
     }
 
+    #[test]
+    fn dlp_detections_track_surface_detector_and_action() {
+        let obs = PrometheusObserver::new();
+        for _ in 0..2 {
+            obs.record_event(&ObserverEvent::DlpDetection {
+                surface: "channel_message".into(),
+                detector: "credit_card".into(),
+                action: "redact".into(),
+            });
+        }
+
+        let output = obs.encode();
+        assert!(output.contains(
+            r#"zeroclaw_dlp_detections_total{action="redact",detector="credit_card",surface="channel_message"} 2"#
+        ));
+    }
+
     #[test]
     fn errors_track_by_component() {
         let obs = PrometheusObserver::new();
@@ -0,0 +1,279 @@
+//! Hierarchical span context for agent execution.
+//!
+//! Spans nest as session → turn → iteration → llm_call / tool_call, and a
+//! delegated agent (`delegate`, `subagent_spawn`) nests under the tool call
+//! that started it. The current span travels as a task-local so nested tool
+//! execution and sub-agent loops pick up their parent without extra
+//! parameters. Finished spans are reported as [`ObserverEvent::SpanEnd`] to
+//! the runtime observer registered with [`set_runtime_observer`]; attribute
+//! names follow the OpenTelemetry GenAI semantic conventions.
+
+use super::traits::{Observer, ObserverEvent};
+use crate::agent::loop_::scrub_credentials;
+use parking_lot::RwLock;
+use std::future::Future;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant, SystemTime};
+use uuid::Uuid;
+
+tokio::task_local! {
+    static CURRENT_SPAN: SpanContext;
+}
+
+static RUNTIME_OBSERVER: LazyLock<RwLock<Option<Arc<dyn Observer>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// One `zeroclaw agent` run (single message or interactive session).
+    Session,
+    /// One user message handled by the tool-call loop.
+    Turn,
+    /// One LLM round trip plus the tool calls it requested.
+    Iteration,
+    LlmCall,
+    ToolCall,
+    /// A delegated sub-agent invocation.
+    Agent,
+}
+
+impl SpanKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Session => "session",
+            Self::Turn => "turn",
+            Self::Iteration => "iteration",
+            Self::LlmCall => "llm_call",
+            Self::ToolCall => "tool_call",
+            Self::Agent => "agent",
+        }
+    }
+}
+
+/// Identity of one span within a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanContext {
+    pub trace_id: u128,
+    pub span_id: u64,
+    pub parent_span_id: Option<u64>,
+    pub kind: SpanKind,
+}
+
+impl SpanContext {
+    /// Start a new trace.
+    pub fn root(kind: SpanKind) -> Self {
+        Self {
+            trace_id: Uuid::new_v4().as_u128(),
+            span_id: new_span_id(),
+            parent_span_id: None,
+            kind,
+        }
+    }
+
+    pub fn child(&self, kind: SpanKind) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: new_span_id(),
+            parent_span_id: Some(self.span_id),
+            kind,
+        }
+    }
+
+    pub fn trace_id_hex(&self) -> String {
+        format!("{:032x}", self.trace_id)
+    }
+
+    pub fn span_id_hex(&self) -> String {
+        format!("{:016x}", self.span_id)
+    }
+}
+
+fn new_span_id() -> u64 {
+    // Zero is the invalid span id in W3C trace context.
+    Uuid::new_v4().as_u64_pair().0.max(1)
+}
+
+/// Typed span attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpanValue {
+    String(String),
+    I64(i64),
+    F64(f64),
+    Bool(bool),
+}
+
+impl From<&str> for SpanValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for SpanValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<u64> for SpanValue {
+    fn from(value: u64) -> Self {
+        Self::I64(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<usize> for SpanValue {
+    fn from(value: usize) -> Self {
+        Self::I64(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<f64> for SpanValue {
+    fn from(value: f64) -> Self {
+        Self::F64(value)
+    }
+}
+
+impl From<bool> for SpanValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// Register the observer that receives [`ObserverEvent::SpanEnd`].
+pub fn set_runtime_observer(observer: Arc<dyn Observer>) {
+    *RUNTIME_OBSERVER.write() = Some(observer);
+}
+
+/// The span the current task is running under, if any.
+pub fn current() -> Option<SpanContext> {
+    CURRENT_SPAN.try_with(|span| *span).ok()
+}
+
+/// Run `future` with `span` as the current span.
+pub async fn scope<F: Future>(span: SpanContext, future: F) -> F::Output {
+    CURRENT_SPAN.scope(span, future).await
+}
+
+/// An open span; reported when dropped.
+#[derive(Debug)]
+pub struct SpanGuard {
+    context: SpanContext,
+    name: String,
+    start_time: SystemTime,
+    started: Instant,
+    attributes: Vec<(&'static str, SpanValue)>,
+    error_message: Option<String>,
+}
+
+impl SpanGuard {
+    /// Open a span under the current span, or a new trace when there is none.
+    pub fn start(kind: SpanKind, name: impl Into<String>) -> Self {
+        let context =
+            current().map_or_else(|| SpanContext::root(kind), |parent| parent.child(kind));
+        Self::with_context(context, name)
+    }
+
+    /// Open a span under `self`.
+    pub fn child(&self, kind: SpanKind, name: impl Into<String>) -> Self {
+        Self::with_context(self.context.child(kind), name)
+    }
+
+    fn with_context(context: SpanContext, name: impl Into<String>) -> Self {
+        Self {
+            context,
+            name: name.into(),
+            start_time: SystemTime::now(),
+            started: Instant::now(),
+            attributes: Vec::new(),
+            error_message: None,
+        }
+    }
+
+    pub fn context(&self) -> SpanContext {
+        self.context
+    }
+
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<SpanValue>) {
+        let value = value.into();
+        match self
+            .attributes
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, slot)) => *slot = value,
+            None => self.attributes.push((key, value)),
+        }
+    }
+
+    /// Mark the span as failed; credentials in `message` are scrubbed.
+    pub fn fail(&mut self, message: impl Into<String>) {
+        self.error_message = Some(scrub_credentials(&message.into()));
+    }
+
+    fn event(&self, duration: Duration) -> ObserverEvent {
+        ObserverEvent::SpanEnd {
+            span: self.context,
+            name: self.name.clone(),
+            start_time: self.start_time,
+            duration,
+            error_message: self.error_message.clone(),
+            attributes: self.attributes.clone(),
+        }
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let Some(observer) = RUNTIME_OBSERVER.read().clone() else {
+            return;
+        };
+        observer.record_event(&self.event(self.started.elapsed()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spans_nest_through_task_local_scopes() {
+        assert!(current().is_none());
+        let mut turn = SpanGuard::start(SpanKind::Turn, "turn");
+        turn.set_attribute("gen_ai.operation.name", "invoke_agent");
+        turn.set_attribute("gen_ai.operation.name", "chat");
+        assert_eq!(turn.attributes.len(), 1);
+        assert!(turn.context().parent_span_id.is_none());
+
+        let iteration = turn.child(SpanKind::Iteration, "iteration 1");
+        let (tool, agent) = scope(iteration.context(), async {
+            let tool = SpanGuard::start(SpanKind::ToolCall, "execute_tool delegate");
+            let agent = scope(tool.context(), async {
+                SpanGuard::start(SpanKind::Agent, "invoke_agent researcher").context()
+            })
+            .await;
+            (tool.context(), agent)
+        })
+        .await;
+
+        assert_eq!(
+            iteration.context().parent_span_id,
+            Some(turn.context().span_id)
+        );
+        assert_eq!(tool.parent_span_id, Some(iteration.context().span_id));
+        assert_eq!(agent.parent_span_id, Some(tool.span_id));
+        assert_eq!(agent.trace_id, turn.context().trace_id);
+        assert_eq!(turn.context().trace_id_hex().len(), 32);
+        assert!(current().is_none());
+
+        let ObserverEvent::SpanEnd {
+            attributes, name, ..
+        } = turn.event(Duration::ZERO)
+        else {
+            panic!("expected span end event");
+        };
+        assert_eq!(name, "turn");
+        assert_eq!(
+            attributes,
+            vec![("gen_ai.operation.name", SpanValue::from("chat"))]
+        );
+    }
+}
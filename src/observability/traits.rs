@@ -1,4 +1,5 @@
-use std::time::Duration;
+use super::span::{SpanContext, SpanValue};
+use std::time::{Duration, SystemTime};
 
 /// Discrete events emitted by the agent runtime for observability.
 ///
@@ -56,8 +57,31 @@ pub enum ObserverEvent {
         /// `"inbound"` or `"outbound"`.
         direction: String,
     },
+    /// A traced operation finished.
+    ///
+    /// Spans form a tree through `span.parent_span_id` (session → turn →
+    /// iteration → LLM/tool call → delegated agent); attribute names follow
+    /// the OpenTelemetry GenAI semantic conventions.
+    SpanEnd {
+        span: SpanContext,
+        name: String,
+        start_time: SystemTime,
+        duration: Duration,
+        /// Set when the operation failed.
+        error_message: Option<String>,
+        attributes: Vec<(&'static str, SpanValue)>,
+    },
     /// Periodic heartbeat tick from the runtime keep-alive loop.
     HeartbeatTick,
+    /// The DLP pipeline matched sensitive content.
+    DlpDetection {
+        /// Application point (e.g., `"channel_message"`, `"tool_output"`).
+        surface: String,
+        /// Detector name (e.g., `"credit_card"`, `"credentials"`).
+        detector: String,
+        /// Action applied (`"mask"`, `"redact"`, `"require-approval"`, `"block"`).
+        action: String,
+    },
     /// An error occurred in a named component.
     Error {
         /// Subsystem where the error originated (e.g., `"provider"`, `"gateway"`).
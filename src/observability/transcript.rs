@@ -0,0 +1,1001 @@
+//! Opt-in per-session transcripts of agent turns.
+//!
+//! Every LLM call (full request messages, tool specs, response, usage,
+//! timing) and every tool execution (arguments, output, timing) made by the
+//! tool-call loop is appended to `<transcripts_path>/<session>.jsonl`, with
+//! content passed through `scrub_credentials` first. Transcripts back the
+//! `zeroclaw sessions list|show|replay` commands.
+
+use crate::agent::loop_::scrub_credentials;
+use crate::config::{Config, ObservabilityConfig};
+use crate::providers::{self, ChatMessage, ChatRequest, Provider, ToolCall};
+use crate::tools::ToolSpec;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+use uuid::Uuid;
+
+const DEFAULT_TRANSCRIPTS_REL_PATH: &str = "state/transcripts";
+const SIDE_BY_SIDE_COLUMN_WIDTH: usize = 58;
+const SHOW_PREVIEW_CHARS: usize = 400;
+
+tokio::task_local! {
+    static TRANSCRIPT_SESSION: String;
+}
+
+/// Session used when no caller scoped one (e.g. gateway webhooks).
+static PROCESS_SESSION_ID: LazyLock<String> = LazyLock::new(|| new_session_id("process"));
+
+/// One recorded step of an agent turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub id: String,
+    pub timestamp: String,
+    pub session_id: String,
+    pub turn_id: String,
+    pub channel: String,
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub record: TranscriptRecord,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptRecord {
+    /// A provider call with the exact request sent and what came back.
+    LlmCall {
+        iteration: usize,
+        temperature: f64,
+        messages: Vec<ChatMessage>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tools: Vec<ToolSpec>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tool_calls: Vec<ToolCall>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input_tokens: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_tokens: Option<u64>,
+        duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A tool execution requested by the model.
+    ToolCall {
+        iteration: usize,
+        name: String,
+        arguments: String,
+        output: String,
+        success: bool,
+        duration_ms: u64,
+    },
+}
+
+/// Identifies the turn a transcript entry belongs to.
+#[derive(Debug, Clone, Copy)]
+pub struct TurnContext<'a> {
+    pub channel: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub turn_id: &'a str,
+}
+
+/// Outcome of one provider call, as recorded.
+#[derive(Debug, Clone, Default)]
+pub struct LlmCallRecord {
+    pub response: Option<String>,
+    pub tool_calls: Vec<ToolCall>,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+struct TranscriptStore {
+    dir: PathBuf,
+    max_sessions: usize,
+    write_lock: std::sync::Mutex<()>,
+}
+
+impl TranscriptStore {
+    fn append(&self, entry: &TranscriptEntry) -> Result<()> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        fs::create_dir_all(&self.dir)?;
+
+        let path = session_file(&self.dir, &entry.session_id);
+        let is_new_session = !path.exists();
+        let line = serde_json::to_string(entry)?;
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&path)?;
+        writeln!(file, "{line}")?;
+
+        if is_new_session {
+            self.prune()?;
+        }
+        Ok(())
+    }
+
+    /// Delete the oldest session files beyond `max_sessions`.
+    fn prune(&self) -> Result<()> {
+        let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+                Some((modified, path))
+            })
+            .collect();
+        if files.len() <= self.max_sessions {
+            return Ok(());
+        }
+        files.sort();
+        let excess = files.len() - self.max_sessions;
+        for (_, path) in files.into_iter().take(excess) {
+            let _ = fs::remove_file(path);
+        }
+        Ok(())
+    }
+}
+
+static TRANSCRIPT_STORE: LazyLock<RwLock<Option<Arc<TranscriptStore>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// Resolve the transcript directory from config.
+pub fn resolve_transcripts_dir(config: &ObservabilityConfig, workspace_dir: &Path) -> PathBuf {
+    let raw = config.transcripts_path.trim();
+    if raw.is_empty() {
+        return workspace_dir.join(DEFAULT_TRANSCRIPTS_REL_PATH);
+    }
+    let configured = PathBuf::from(raw);
+    if configured.is_absolute() {
+        configured
+    } else {
+        workspace_dir.join(configured)
+    }
+}
+
+/// Initialize (or disable) transcript recording.
+pub fn init_from_config(config: &ObservabilityConfig, workspace_dir: &Path) {
+    let store = config.transcripts_enabled.then(|| {
+        Arc::new(TranscriptStore {
+            dir: resolve_transcripts_dir(config, workspace_dir),
+            max_sessions: config.transcripts_max_sessions.max(1),
+            write_lock: std::sync::Mutex::new(()),
+        })
+    });
+    let mut guard = TRANSCRIPT_STORE.write().unwrap_or_else(|e| e.into_inner());
+    *guard = store;
+}
+
+fn store() -> Option<Arc<TranscriptStore>> {
+    TRANSCRIPT_STORE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Whether transcripts are being recorded; lets callers skip cloning requests.
+pub fn is_enabled() -> bool {
+    store().is_some()
+}
+
+/// Generate a fresh session id such as `cli-20260101T120000-1a2b3c4d`.
+pub fn new_session_id(prefix: &str) -> String {
+    let short = Uuid::new_v4().simple().to_string();
+    format!(
+        "{prefix}-{}-{}",
+        Utc::now().format("%Y%m%dT%H%M%S"),
+        &short[..8]
+    )
+}
+
+/// Run `future` with `session_id` as the transcript session for every turn
+/// it executes.
+pub async fn scope<F: Future>(session_id: String, future: F) -> F::Output {
+    TRANSCRIPT_SESSION.scope(session_id, future).await
+}
+
+/// Session id for the current task, falling back to a per-process session.
+pub fn current_session_id() -> String {
+    TRANSCRIPT_SESSION
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| PROCESS_SESSION_ID.clone())
+}
+
+fn append(ctx: TurnContext<'_>, record: TranscriptRecord) {
+    let Some(store) = store() else {
+        return;
+    };
+    let entry = TranscriptEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        session_id: current_session_id(),
+        turn_id: ctx.turn_id.to_string(),
+        channel: ctx.channel.to_string(),
+        provider: ctx.provider.to_string(),
+        model: ctx.model.to_string(),
+        record,
+    };
+    if let Err(err) = store.append(&entry) {
+        tracing::warn!("Failed to write session transcript entry: {err}");
+    }
+}
+
+/// Record one provider call of a turn.
+pub fn record_llm_call(
+    ctx: TurnContext<'_>,
+    iteration: usize,
+    temperature: f64,
+    messages: &[ChatMessage],
+    tools: Option<&[ToolSpec]>,
+    call: LlmCallRecord,
+) {
+    if !is_enabled() {
+        return;
+    }
+    let messages = messages
+        .iter()
+        .map(|message| ChatMessage {
+            role: message.role.clone(),
+            content: scrub_credentials(&message.content),
+        })
+        .collect();
+    let tool_calls = call
+        .tool_calls
+        .into_iter()
+        .map(|tool_call| ToolCall {
+            arguments: scrub_credentials(&tool_call.arguments),
+            ..tool_call
+        })
+        .collect();
+    append(
+        ctx,
+        TranscriptRecord::LlmCall {
+            iteration,
+            temperature,
+            messages,
+            tools: tools.map(<[ToolSpec]>::to_vec).unwrap_or_default(),
+            response: call.response.as_deref().map(scrub_credentials),
+            tool_calls,
+            input_tokens: call.input_tokens,
+            output_tokens: call.output_tokens,
+            duration_ms: call.duration_ms,
+            error: call.error,
+        },
+    );
+}
+
+/// Record one tool execution of a turn.
+pub fn record_tool_call(
+    ctx: TurnContext<'_>,
+    iteration: usize,
+    name: &str,
+    arguments: &serde_json::Value,
+    output: &str,
+    success: bool,
+    duration_ms: u64,
+) {
+    if !is_enabled() {
+        return;
+    }
+    append(
+        ctx,
+        TranscriptRecord::ToolCall {
+            iteration,
+            name: name.to_string(),
+            arguments: scrub_credentials(&arguments.to_string()),
+            output: scrub_credentials(output),
+            success,
+            duration_ms,
+        },
+    );
+}
+
+// ── Reading transcripts ──────────────────────────────────────────
+
+fn session_file(dir: &Path, session_id: &str) -> PathBuf {
+    let stem: String = session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{stem}.jsonl"))
+}
+
+fn load_file(path: &Path) -> Result<Vec<TranscriptEntry>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transcript {}", path.display()))?;
+    let mut entries = Vec::new();
+    for line in raw.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        match serde_json::from_str::<TranscriptEntry>(line) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => tracing::warn!("Skipping malformed transcript line: {err}"),
+        }
+    }
+    Ok(entries)
+}
+
+/// Aggregate view of one recorded session.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub channel: String,
+    pub started_at: String,
+    pub updated_at: String,
+    pub turns: usize,
+    pub llm_calls: usize,
+    pub tool_calls: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+fn summarize(entries: &[TranscriptEntry]) -> Option<SessionSummary> {
+    let first = entries.first()?;
+    let last = entries.last()?;
+    let mut summary = SessionSummary {
+        session_id: first.session_id.clone(),
+        channel: first.channel.clone(),
+        started_at: first.timestamp.clone(),
+        updated_at: last.timestamp.clone(),
+        turns: group_turns(entries.to_vec()).len(),
+        llm_calls: 0,
+        tool_calls: 0,
+        input_tokens: 0,
+        output_tokens: 0,
+    };
+    for entry in entries {
+        match &entry.record {
+            TranscriptRecord::LlmCall {
+                input_tokens,
+                output_tokens,
+                ..
+            } => {
+                summary.llm_calls += 1;
+                summary.input_tokens += input_tokens.unwrap_or(0);
+                summary.output_tokens += output_tokens.unwrap_or(0);
+            }
+            TranscriptRecord::ToolCall { .. } => summary.tool_calls += 1,
+        }
+    }
+    Some(summary)
+}
+
+/// List recorded sessions, most recently updated first.
+pub fn list_sessions(dir: &Path) -> Result<Vec<SessionSummary>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            if let Some(summary) = summarize(&load_file(&path)?) {
+                sessions.push(summary);
+            }
+        }
+    }
+    sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(sessions)
+}
+
+/// Load a session by exact id or unique id prefix.
+pub fn load_session(dir: &Path, session_id: &str) -> Result<Vec<TranscriptEntry>> {
+    let exact = session_file(dir, session_id);
+    if exact.exists() {
+        return load_file(&exact);
+    }
+    let matches: Vec<SessionSummary> = list_sessions(dir)?
+        .into_iter()
+        .filter(|summary| summary.session_id.starts_with(session_id))
+        .collect();
+    match matches.as_slice() {
+        [only] => load_file(&session_file(dir, &only.session_id)),
+        [] => anyhow::bail!(
+            "No transcript for session '{session_id}' in {}",
+            dir.display()
+        ),
+        _ => anyhow::bail!(
+            "Session prefix '{session_id}' is ambiguous ({} matches)",
+            matches.len()
+        ),
+    }
+}
+
+/// Entries of one turn in recording order.
+#[derive(Debug, Clone)]
+pub struct TranscriptTurn {
+    pub turn_id: String,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptTurn {
+    /// The last user message sent in the turn's first LLM request.
+    pub fn user_message(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match &entry.record {
+            TranscriptRecord::LlmCall { messages, .. } => messages
+                .iter()
+                .rev()
+                .find(|message| message.role == "user")
+                .map(|message| message.content.as_str()),
+            TranscriptRecord::ToolCall { .. } => None,
+        })
+    }
+}
+
+/// Group entries by turn, preserving first-seen order.
+pub fn group_turns(entries: Vec<TranscriptEntry>) -> Vec<TranscriptTurn> {
+    let mut turns: Vec<TranscriptTurn> = Vec::new();
+    for entry in entries {
+        match turns.iter_mut().find(|turn| turn.turn_id == entry.turn_id) {
+            Some(turn) => turn.entries.push(entry),
+            None => turns.push(TranscriptTurn {
+                turn_id: entry.turn_id.clone(),
+                entries: vec![entry],
+            }),
+        }
+    }
+    turns
+}
+
+/// Select a turn by 1-based index or turn-id prefix; `None` picks the last.
+pub fn select_turn(turns: Vec<TranscriptTurn>, selector: Option<&str>) -> Result<TranscriptTurn> {
+    let count = turns.len();
+    let Some(selector) = selector.map(str::trim).filter(|s| !s.is_empty()) else {
+        return turns.into_iter().last().context("Session has no turns");
+    };
+    if let Ok(index) = selector.parse::<usize>() {
+        return turns
+            .into_iter()
+            .nth(index.saturating_sub(1))
+            .with_context(|| format!("Turn {index} out of range (session has {count} turns)"));
+    }
+    turns
+        .into_iter()
+        .find(|turn| turn.turn_id.starts_with(selector))
+        .with_context(|| format!("No turn matching '{selector}'"))
+}
+
+/// Render a response (text plus requested tool calls) for display.
+fn render_response(text: Option<&str>, tool_calls: &[ToolCall]) -> String {
+    let mut rendered = text.unwrap_or_default().trim().to_string();
+    for call in tool_calls {
+        if !rendered.is_empty() {
+            rendered.push('\n');
+        }
+        let _ = write!(rendered, "→ {}({})", call.name, call.arguments);
+    }
+    rendered
+}
+
+fn wrap_column(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let chars: Vec<char> = raw.chars().collect();
+        if chars.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        for chunk in chars.chunks(width) {
+            lines.push(chunk.iter().collect());
+        }
+    }
+    lines
+}
+
+/// Lay out two texts in adjacent columns.
+pub fn side_by_side(left_title: &str, left: &str, right_title: &str, right: &str) -> String {
+    let width = SIDE_BY_SIDE_COLUMN_WIDTH;
+    let left_lines = wrap_column(left, width);
+    let right_lines = wrap_column(right, width);
+    let mut out = format!("{left_title:<width$} │ {right_title}\n");
+    let _ = writeln!(out, "{:─<width$}─┼─{:─<width$}", "", "");
+    for index in 0..left_lines.len().max(right_lines.len()) {
+        let left = left_lines.get(index).map_or("", String::as_str);
+        let right = right_lines.get(index).map_or("", String::as_str);
+        let pad = width.saturating_sub(left.chars().count());
+        let _ = writeln!(out, "{left}{} │ {right}", " ".repeat(pad));
+    }
+    out
+}
+
+// ── CLI ──────────────────────────────────────────────────────────
+
+pub async fn handle_command(command: crate::SessionCommands, config: &Config) -> Result<()> {
+    let dir = resolve_transcripts_dir(&config.observability, &config.workspace_dir);
+    match command {
+        crate::SessionCommands::List { limit } => {
+            let sessions = list_sessions(&dir)?;
+            if sessions.is_empty() {
+                println!("No session transcripts in {}.", dir.display());
+                if !config.observability.transcripts_enabled {
+                    println!("Enable [observability] transcripts_enabled = true to record them.");
+                }
+                return Ok(());
+            }
+            println!(
+                "Session transcripts (most recent first) — {}",
+                dir.display()
+            );
+            for summary in sessions.iter().take(limit.max(1)) {
+                println!(
+                    "- {}  [{}] turns={} llm_calls={} tool_calls={} tokens={}/{} updated={}",
+                    summary.session_id,
+                    summary.channel,
+                    summary.turns,
+                    summary.llm_calls,
+                    summary.tool_calls,
+                    summary.input_tokens,
+                    summary.output_tokens,
+                    summary.updated_at
+                );
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Show {
+            session,
+            turn,
+            full,
+            json,
+        } => {
+            let entries = load_session(&dir, &session)?;
+            let turns = match turn.as_deref() {
+                Some(selector) => vec![select_turn(group_turns(entries), Some(selector))?],
+                None => group_turns(entries),
+            };
+            if json {
+                let entries: Vec<&TranscriptEntry> =
+                    turns.iter().flat_map(|turn| &turn.entries).collect();
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+                return Ok(());
+            }
+            print_turns(&turns, full);
+            Ok(())
+        }
+        crate::SessionCommands::Replay {
+            session,
+            turn,
+            provider,
+            model,
+            temperature,
+        } => {
+            let entries = load_session(&dir, &session)?;
+            let turn = select_turn(group_turns(entries), turn.as_deref())?;
+            let recorded = turn
+                .entries
+                .first()
+                .context("Selected turn has no entries")?;
+            let provider_name = provider
+                .or_else(|| config.default_provider.clone())
+                .unwrap_or_else(|| recorded.provider.clone());
+            let model = model.unwrap_or_else(|| recorded.model.clone());
+            let replay_provider = create_replay_provider(config, &provider_name, &model)?;
+            println!(
+                "Replaying turn {} of {} ({} → {}/{})\n",
+                turn.turn_id, recorded.session_id, recorded.model, provider_name, model
+            );
+            let comparisons =
+                replay_turn(replay_provider.as_ref(), &model, temperature, &turn).await;
+            if comparisons.is_empty() {
+                println!("Turn has no recorded LLM calls to replay.");
+            }
+            for comparison in comparisons {
+                println!("Iteration {}", comparison.iteration);
+                print!(
+                    "{}",
+                    side_by_side(
+                        &format!("recorded ({})", recorded.model),
+                        &comparison.recorded,
+                        &format!("replayed ({model})"),
+                        &comparison.replayed,
+                    )
+                );
+                println!();
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_turns(turns: &[TranscriptTurn], full: bool) {
+    let clip = |text: &str| {
+        if full {
+            text.to_string()
+        } else {
+            truncate_with_ellipsis(text, SHOW_PREVIEW_CHARS)
+        }
+    };
+    for (index, turn) in turns.iter().enumerate() {
+        let Some(first) = turn.entries.first() else {
+            continue;
+        };
+        println!(
+            "── Turn {} ({}) {} [{}] {}/{}",
+            index + 1,
+            turn.turn_id,
+            first.timestamp,
+            first.channel,
+            first.provider,
+            first.model
+        );
+        if let Some(message) = turn.user_message() {
+            println!("user: {}", clip(message));
+        }
+        for entry in &turn.entries {
+            match &entry.record {
+                TranscriptRecord::LlmCall {
+                    iteration,
+                    messages,
+                    response,
+                    tool_calls,
+                    input_tokens,
+                    output_tokens,
+                    duration_ms,
+                    error,
+                    ..
+                } => {
+                    println!(
+                        "  [llm #{iteration}] {} messages, {duration_ms} ms, tokens {}/{}",
+                        messages.len(),
+                        input_tokens.unwrap_or(0),
+                        output_tokens.unwrap_or(0)
+                    );
+                    if let Some(error) = error {
+                        println!("    error: {error}");
+                    }
+                    let rendered = render_response(response.as_deref(), tool_calls);
+                    if !rendered.is_empty() {
+                        for line in clip(&rendered).lines() {
+                            println!("    {line}");
+                        }
+                    }
+                }
+                TranscriptRecord::ToolCall {
+                    name,
+                    arguments,
+                    output,
+                    success,
+                    duration_ms,
+                    ..
+                } => {
+                    let status = if *success { "ok" } else { "failed" };
+                    println!("  [tool {name}] {status}, {duration_ms} ms");
+                    println!("    args: {}", clip(arguments));
+                    for line in clip(output).lines() {
+                        println!("    {line}");
+                    }
+                }
+            }
+        }
+        println!();
+    }
+}
+
+fn create_replay_provider(
+    config: &Config,
+    provider_name: &str,
+    model: &str,
+) -> Result<Box<dyn Provider>> {
+    let provider_runtime_options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        provider_transport: config.effective_provider_transport(),
+        zeroclaw_dir: config.config_path.parent().map(PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning_level: config.effective_provider_reasoning_level(),
+        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
+        max_tokens_override: None,
+        model_support_vision: config.model_support_vision,
+    };
+    providers::create_routed_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model,
+        &provider_runtime_options,
+    )
+}
+
+/// Recorded and replayed output of one LLM call.
+#[derive(Debug, Clone)]
+pub struct ReplayComparison {
+    pub iteration: usize,
+    pub recorded: String,
+    pub replayed: String,
+}
+
+/// Re-send every recorded LLM request of `turn` to `provider`. Tool results
+/// come from the transcript, so no tools are executed again.
+pub async fn replay_turn(
+    provider: &dyn Provider,
+    model: &str,
+    temperature: Option<f64>,
+    turn: &TranscriptTurn,
+) -> Vec<ReplayComparison> {
+    let mut comparisons = Vec::new();
+    for entry in &turn.entries {
+        let TranscriptRecord::LlmCall {
+            iteration,
+            temperature: recorded_temperature,
+            messages,
+            tools,
+            response,
+            tool_calls,
+            error,
+            ..
+        } = &entry.record
+        else {
+            continue;
+        };
+        let recorded = match error {
+            Some(error) => format!("error: {error}"),
+            None => render_response(response.as_deref(), tool_calls),
+        };
+        let request_tools =
+            (provider.supports_native_tools() && !tools.is_empty()).then_some(tools.as_slice());
+        let replayed = match provider
+            .chat(
+                ChatRequest {
+                    messages,
+                    tools: request_tools,
+                },
+                model,
+                temperature.unwrap_or(*recorded_temperature),
+            )
+            .await
+        {
+            Ok(response) => render_response(response.text.as_deref(), &response.tool_calls),
+            Err(error) => format!(
+                "error: {}",
+                providers::sanitize_api_error(&error.to_string())
+            ),
+        };
+        comparisons.push(ReplayComparison {
+            iteration: *iteration,
+            recorded,
+            replayed,
+        });
+    }
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatResponse;
+    use async_trait::async_trait;
+
+    fn test_store(dir: &Path, max_sessions: usize) -> TranscriptStore {
+        TranscriptStore {
+            dir: dir.to_path_buf(),
+            max_sessions,
+            write_lock: std::sync::Mutex::new(()),
+        }
+    }
+
+    fn entry(session_id: &str, turn_id: &str, record: TranscriptRecord) -> TranscriptEntry {
+        TranscriptEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            session_id: session_id.into(),
+            turn_id: turn_id.into(),
+            channel: "telegram".into(),
+            provider: "openrouter".into(),
+            model: "model-a".into(),
+            record,
+        }
+    }
+
+    fn llm_call(user: &str, response: &str) -> TranscriptRecord {
+        TranscriptRecord::LlmCall {
+            iteration: 1,
+            temperature: 0.7,
+            messages: vec![ChatMessage::system("sys"), ChatMessage::user(user)],
+            tools: Vec::new(),
+            response: Some(response.into()),
+            tool_calls: Vec::new(),
+            input_tokens: Some(12),
+            output_tokens: Some(3),
+            duration_ms: 40,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn sessions_are_listed_grouped_and_loaded_by_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path(), 10);
+        store
+            .append(&entry("telegram_42", "turn-1", llm_call("hi", "hello")))
+            .unwrap();
+        store
+            .append(&entry(
+                "telegram_42",
+                "turn-1",
+                TranscriptRecord::ToolCall {
+                    iteration: 1,
+                    name: "shell".into(),
+                    arguments: "{\"command\":\"ls\"}".into(),
+                    output: "a.txt".into(),
+                    success: true,
+                    duration_ms: 5,
+                },
+            ))
+            .unwrap();
+        store
+            .append(&entry("telegram_42", "turn-2", llm_call("bye", "ciao")))
+            .unwrap();
+
+        let sessions = list_sessions(tmp.path()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].turns, 2);
+        assert_eq!(sessions[0].llm_calls, 2);
+        assert_eq!(sessions[0].tool_calls, 1);
+        assert_eq!(sessions[0].input_tokens, 24);
+
+        let turns = group_turns(load_session(tmp.path(), "telegram_4").unwrap());
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].entries.len(), 2);
+        assert_eq!(turns[1].user_message(), Some("bye"));
+        assert_eq!(
+            select_turn(turns.clone(), Some("1")).unwrap().turn_id,
+            "turn-1"
+        );
+        assert_eq!(select_turn(turns, None).unwrap().turn_id, "turn-2");
+    }
+
+    #[test]
+    fn oldest_sessions_are_pruned_beyond_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path(), 2);
+        for session in ["s1", "s2", "s3"] {
+            store
+                .append(&entry(session, "t", llm_call("x", "y")))
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let ids: Vec<String> = list_sessions(tmp.path())
+            .unwrap()
+            .into_iter()
+            .map(|summary| summary.session_id)
+            .collect();
+        assert_eq!(ids, vec!["s3".to_string(), "s2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn recorded_content_is_scrubbed_and_scoped_to_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = ObservabilityConfig {
+            transcripts_enabled: true,
+            transcripts_path: tmp.path().display().to_string(),
+            ..ObservabilityConfig::default()
+        };
+        init_from_config(&config, tmp.path());
+
+        let ctx = TurnContext {
+            channel: "cli",
+            provider: "mock",
+            model: "m",
+            turn_id: "turn-x",
+        };
+        scope("cli-scrub-test".to_string(), async {
+            record_llm_call(
+                ctx,
+                1,
+                0.0,
+                &[ChatMessage::user("use api_key=sk-abcdef1234567890")],
+                None,
+                LlmCallRecord {
+                    response: Some("token: ghp_secretvalue123456".into()),
+                    ..LlmCallRecord::default()
+                },
+            );
+            record_tool_call(
+                ctx,
+                1,
+                "shell",
+                &serde_json::json!({"command": "echo", "password": "hunter2hunter2"}),
+                "done",
+                true,
+                3,
+            );
+        })
+        .await;
+        init_from_config(&ObservabilityConfig::default(), tmp.path());
+
+        let raw = fs::read_to_string(tmp.path().join("cli-scrub-test.jsonl")).unwrap();
+        assert!(!raw.contains("sk-abcdef1234567890"));
+        assert!(!raw.contains("ghp_secretvalue123456"));
+        assert!(!raw.contains("hunter2hunter2"));
+        assert!(raw.contains("[REDACTED]"));
+        let entries = load_session(tmp.path(), "cli-scrub-test").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.turn_id == "turn-x"));
+    }
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok(message.to_string())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            let last = request.messages.last().map_or("", |m| m.content.as_str());
+            Ok(ChatResponse {
+                text: Some(format!("{model} says {last}")),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+                quota_metadata: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_turn_compares_recorded_and_new_responses() {
+        let turn = TranscriptTurn {
+            turn_id: "t".into(),
+            entries: vec![
+                entry("s", "t", llm_call("ping", "pong")),
+                entry(
+                    "s",
+                    "t",
+                    TranscriptRecord::ToolCall {
+                        iteration: 1,
+                        name: "shell".into(),
+                        arguments: "{}".into(),
+                        output: String::new(),
+                        success: true,
+                        duration_ms: 1,
+                    },
+                ),
+            ],
+        };
+        let comparisons = replay_turn(&EchoProvider, "model-b", None, &turn).await;
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].recorded, "pong");
+        assert_eq!(comparisons[0].replayed, "model-b says ping");
+
+        let rendered = side_by_side("recorded", "pong", "replayed", "model-b says ping");
+        assert!(rendered.lines().nth(2).unwrap().starts_with("pong "));
+        assert!(rendered.contains("│ model-b says ping"));
+    }
+}
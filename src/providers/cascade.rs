@@ -0,0 +1,1054 @@
+//! Budget-aware model cascading.
+//!
+//! [`CascadeProvider`] answers a request with the cheapest `[model_cascade]`
+//! tier first and checks the answer: tool calls must name offered tools with
+//! JSON arguments, text must be non-empty and non-hedging, and (with
+//! `self_check`) the tier model rates its own answer. Failing answers escalate
+//! to the next tier unless the projected spend would exceed the `[cost]`
+//! budgets. Requests that already carry an explicit `hint:` bypass the cascade.
+//! Every tier call and self-check is charged to the workspace's shared
+//! [`CostTracker`]. Streaming requests are not cascaded: a cascading provider
+//! reports no streaming support, and explicit stream calls go straight to the
+//! passthrough provider. Each decision is appended to
+//! `state/cascade_decisions.jsonl` for tuning.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, StreamChunk, StreamOptions,
+    StreamResult, ToolsPayload,
+};
+use super::Provider;
+use crate::config::schema::ModelPricing;
+use crate::config::{Config, ModelCascadeConfig};
+use crate::cost::{BudgetCheck, CostTracker, TokenUsage};
+use crate::tools::ToolSpec;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Decision log location, relative to the workspace.
+pub const DECISION_LOG_FILE: &str = "state/cascade_decisions.jsonl";
+
+/// Fallback pricing for models missing from `[cost.prices]` (USD per 1M
+/// tokens), matching the cost observer's conservative defaults.
+const DEFAULT_INPUT_PRICE: f64 = 3.0;
+const DEFAULT_OUTPUT_PRICE: f64 = 15.0;
+
+const SELF_CHECK_PROMPT: &str = "You grade answers. Given a request and a proposed answer, \
+rate how likely the answer is complete and correct on a scale from 0 (wrong or evasive) \
+to 10 (certainly right). Reply with the number only.";
+
+/// Output tokens assumed for a self-check reply when checking the budget.
+const SELF_CHECK_OUTPUT_TOKENS: u64 = 8;
+
+const HEDGING_PHRASES: &[&str] = &[
+    "i'm not sure",
+    "i am not sure",
+    "i don't know",
+    "i do not know",
+    "i'm not certain",
+    "i am not certain",
+    "i cannot answer",
+    "i can't answer",
+    "i'm unable to answer",
+    "i am unable to answer",
+];
+
+/// Cascade settings shared by every cascading provider of one runtime:
+/// tier order, acceptance threshold, budget tracker and decision log.
+pub struct CascadePolicy {
+    config: ModelCascadeConfig,
+    tracker: Option<Arc<CostTracker>>,
+    prices: HashMap<String, ModelPricing>,
+    decision_log: Option<PathBuf>,
+}
+
+impl CascadePolicy {
+    /// Build the policy for `config`, or `None` when cascading is disabled
+    /// or has no tiers.
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        let cascade = &config.model_cascade;
+        if !cascade.enabled || cascade.tiers.is_empty() {
+            return None;
+        }
+        let tracker = if config.cost.enabled {
+            match CostTracker::shared(&config.cost, &config.workspace_dir) {
+                Ok(tracker) => Some(tracker),
+                Err(err) => {
+                    tracing::warn!("Model cascade runs without budget checks: {err:#}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        Some(Arc::new(Self {
+            config: cascade.clone(),
+            tracker,
+            prices: config.cost.prices.clone(),
+            decision_log: cascade
+                .log_decisions
+                .then(|| config.workspace_dir.join(DECISION_LOG_FILE)),
+        }))
+    }
+
+    /// Configured tier hints, cheapest first.
+    pub fn tiers(&self) -> &[String] {
+        &self.config.tiers
+    }
+
+    fn pricing(&self, provider: &str, model: &str) -> (f64, f64) {
+        self.prices
+            .get(&format!("{provider}/{model}"))
+            .or_else(|| self.prices.get(model))
+            .map_or((DEFAULT_INPUT_PRICE, DEFAULT_OUTPUT_PRICE), |pricing| {
+                (pricing.input, pricing.output)
+            })
+    }
+
+    fn usage(&self, tier: &CascadeTier, input_tokens: u64, output_tokens: u64) -> TokenUsage {
+        let (input_price, output_price) = self.pricing(&tier.provider_name, &tier.model);
+        TokenUsage::new(
+            format!("{}/{}", tier.provider_name, tier.model),
+            input_tokens,
+            output_tokens,
+            input_price,
+            output_price,
+        )
+    }
+
+    /// Whether a call to `tier` of the given size fits the budget.
+    fn within_budget(&self, tier: &CascadeTier, input_tokens: u64, output_tokens: u64) -> bool {
+        let Some(tracker) = &self.tracker else {
+            return true;
+        };
+        let estimate = self.usage(tier, input_tokens, output_tokens);
+        match tracker.check_budget(estimate.cost()) {
+            Ok(BudgetCheck::Exceeded { .. }) => false,
+            Ok(_) => true,
+            Err(err) => {
+                tracing::warn!("Cascade budget check failed, calling anyway: {err:#}");
+                true
+            }
+        }
+    }
+
+    /// Whether escalating to `tier` with `messages` fits the budget.
+    fn escalation_allowed(&self, tier: &CascadeTier, messages: &[ChatMessage]) -> bool {
+        self.within_budget(
+            tier,
+            messages_tokens(messages),
+            self.config.escalation_output_tokens,
+        )
+    }
+
+    /// Charge one call to `tier` to the shared cost tracker.
+    fn charge(&self, tier: &CascadeTier, input_tokens: u64, output_tokens: u64) {
+        let Some(tracker) = &self.tracker else {
+            return;
+        };
+        if let Err(err) = tracker.record_usage(self.usage(tier, input_tokens, output_tokens)) {
+            tracing::warn!("Failed to record cascade usage: {err:#}");
+        }
+    }
+
+    fn record(&self, decision: &CascadeDecision) {
+        tracing::info!(
+            target: "model_cascade",
+            selected = decision.selected.as_str(),
+            outcome = ?decision.outcome,
+            attempts = decision.attempts.len(),
+            "Cascade decision"
+        );
+        let Some(path) = &self.decision_log else {
+            return;
+        };
+        let append = || -> Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(decision)?)?;
+            Ok(())
+        };
+        if let Err(err) = append() {
+            tracing::warn!("Failed to record cascade decision: {err:#}");
+        }
+    }
+}
+
+/// One cascade tier: a `[[model_routes]]` hint and the provider serving it.
+pub struct CascadeTier {
+    pub hint: String,
+    /// Provider and model the hint resolves to, used for pricing and logs.
+    pub provider_name: String,
+    pub model: String,
+    provider: Arc<dyn Provider>,
+    /// Model string passed to `provider` (`hint:<name>` for routed providers).
+    request_model: String,
+}
+
+impl CascadeTier {
+    pub fn new(
+        hint: impl Into<String>,
+        provider_name: impl Into<String>,
+        model: impl Into<String>,
+        provider: Arc<dyn Provider>,
+        request_model: impl Into<String>,
+    ) -> Self {
+        Self {
+            hint: hint.into(),
+            provider_name: provider_name.into(),
+            model: model.into(),
+            provider,
+            request_model: request_model.into(),
+        }
+    }
+}
+
+/// How a cascaded request was settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CascadeOutcome {
+    /// The first tier's answer passed its check.
+    Accepted,
+    /// A later tier answered.
+    Escalated,
+    /// Escalation would exceed the budget; the last checked answer was kept.
+    BudgetLimited,
+    /// No tier produced an answer.
+    Failed,
+}
+
+/// One tier tried for a request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CascadeAttempt {
+    pub hint: String,
+    pub provider: String,
+    pub model: String,
+    /// Confidence in the answer (0.0–1.0); `None` when the call failed or the
+    /// answer was taken unchecked from the last tier.
+    pub confidence: Option<f64>,
+    pub reason: String,
+}
+
+/// One per-request cascade decision, as logged for tuning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CascadeDecision {
+    pub timestamp: String,
+    pub attempts: Vec<CascadeAttempt>,
+    /// Hint whose answer was returned (empty on failure).
+    pub selected: String,
+    pub outcome: CascadeOutcome,
+}
+
+struct Assessment {
+    confidence: f64,
+    reason: &'static str,
+}
+
+/// Provider that answers through cheap-to-strong tiers.
+pub struct CascadeProvider {
+    policy: Arc<CascadePolicy>,
+    tiers: Vec<CascadeTier>,
+    /// Serves explicit `hint:` requests and streaming.
+    passthrough: Arc<dyn Provider>,
+}
+
+impl CascadeProvider {
+    pub fn new(
+        policy: Arc<CascadePolicy>,
+        tiers: Vec<CascadeTier>,
+        passthrough: Arc<dyn Provider>,
+    ) -> Self {
+        Self {
+            policy,
+            tiers,
+            passthrough,
+        }
+    }
+
+    fn bypass(&self, model: &str) -> bool {
+        self.tiers.is_empty() || model.starts_with("hint:")
+    }
+
+    async fn cascade<'a, F>(
+        &'a self,
+        messages: &[ChatMessage],
+        tool_names: Option<&[String]>,
+        call: F,
+    ) -> Result<ChatResponse>
+    where
+        F: Fn(&'a CascadeTier) -> BoxFuture<'a, Result<ChatResponse>>,
+    {
+        let mut attempts = Vec::new();
+        let mut kept: Option<(ChatResponse, String)> = None;
+        let mut last_error = None;
+        let mut outcome = CascadeOutcome::Failed;
+
+        for (index, tier) in self.tiers.iter().enumerate() {
+            if index > 0 && !self.policy.escalation_allowed(tier, messages) {
+                if kept.is_some() {
+                    outcome = CascadeOutcome::BudgetLimited;
+                }
+                attempts.push(attempt(tier, None, "budget_exceeded"));
+                break;
+            }
+
+            let response = match call(tier).await {
+                Ok(mut response) => {
+                    // Spend is charged here at tier pricing; the answer keeps
+                    // no usage so the cost observer cannot count it twice.
+                    let usage = response.usage.take().unwrap_or_default();
+                    self.policy.charge(
+                        tier,
+                        usage
+                            .input_tokens
+                            .unwrap_or_else(|| messages_tokens(messages)),
+                        usage
+                            .output_tokens
+                            .unwrap_or_else(|| response_tokens(&response)),
+                    );
+                    response
+                }
+                Err(err) => {
+                    attempts.push(attempt(tier, None, "error"));
+                    last_error = Some(err);
+                    continue;
+                }
+            };
+
+            if index + 1 == self.tiers.len() {
+                attempts.push(attempt(tier, None, "final_tier"));
+                kept = Some((response, tier.hint.clone()));
+                outcome = if index == 0 {
+                    CascadeOutcome::Accepted
+                } else {
+                    CascadeOutcome::Escalated
+                };
+                break;
+            }
+
+            let assessment = self.assess(tier, messages, tool_names, &response).await;
+            attempts.push(attempt(
+                tier,
+                Some(assessment.confidence),
+                assessment.reason,
+            ));
+            kept = Some((response, tier.hint.clone()));
+            if assessment.confidence >= self.policy.config.min_confidence {
+                outcome = if index == 0 {
+                    CascadeOutcome::Accepted
+                } else {
+                    CascadeOutcome::Escalated
+                };
+                break;
+            }
+        }
+
+        let selected = kept.as_ref().map(|(_, hint)| hint.clone());
+        self.policy.record(&CascadeDecision {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            attempts,
+            selected: selected.unwrap_or_default(),
+            outcome,
+        });
+
+        match kept {
+            Some((response, _)) => Ok(response),
+            None => Err(last_error.unwrap_or_else(|| {
+                anyhow::anyhow!("Model cascade exceeded budget before any tier answered")
+            })),
+        }
+    }
+
+    async fn assess(
+        &self,
+        tier: &CascadeTier,
+        messages: &[ChatMessage],
+        tool_names: Option<&[String]>,
+        response: &ChatResponse,
+    ) -> Assessment {
+        if !response.tool_calls.is_empty() {
+            let valid = response
+                .tool_calls
+                .iter()
+                .all(|call| tool_call_is_valid(&call.name, &call.arguments, tool_names));
+            return tool_assessment(valid);
+        }
+
+        let text = response.text.as_deref().unwrap_or("").trim();
+        if text.is_empty() {
+            return Assessment {
+                confidence: 0.0,
+                reason: "empty",
+            };
+        }
+        if let Some(valid) = prompt_guided_tool_calls_valid(text, tool_names) {
+            return tool_assessment(valid);
+        }
+        let lower = text.to_lowercase();
+        if HEDGING_PHRASES.iter().any(|phrase| lower.contains(phrase)) {
+            return Assessment {
+                confidence: 0.2,
+                reason: "hedging",
+            };
+        }
+        if !self.policy.config.self_check {
+            return Assessment {
+                confidence: 1.0,
+                reason: "heuristic",
+            };
+        }
+
+        let request = messages
+            .iter()
+            .rfind(|message| message.role == "user")
+            .map_or("", |message| message.content.as_str());
+        let prompt = format!("Request:\n{request}\n\nAnswer:\n{text}");
+        let prompt_tokens = estimated_tokens(SELF_CHECK_PROMPT.len() + prompt.len());
+        if !self
+            .policy
+            .within_budget(tier, prompt_tokens, SELF_CHECK_OUTPUT_TOKENS)
+        {
+            // Unchecked; escalation gets its own budget check next.
+            return Assessment {
+                confidence: 0.0,
+                reason: "self_check_budget_exceeded",
+            };
+        }
+        let reply = tier
+            .provider
+            .chat_with_system(Some(SELF_CHECK_PROMPT), &prompt, &tier.request_model, 0.0)
+            .await
+            .ok();
+        let reply_tokens = reply
+            .as_deref()
+            .map_or(0, |reply| estimated_tokens(reply.len()));
+        self.policy.charge(tier, prompt_tokens, reply_tokens);
+        match reply.and_then(|reply| parse_self_check_score(&reply)) {
+            Some(confidence) => Assessment {
+                confidence,
+                reason: "self_check",
+            },
+            None => Assessment {
+                confidence: 1.0,
+                reason: "self_check_unavailable",
+            },
+        }
+    }
+}
+
+fn attempt(tier: &CascadeTier, confidence: Option<f64>, reason: &str) -> CascadeAttempt {
+    CascadeAttempt {
+        hint: tier.hint.clone(),
+        provider: tier.provider_name.clone(),
+        model: tier.model.clone(),
+        confidence,
+        reason: reason.to_string(),
+    }
+}
+
+fn tool_assessment(valid: bool) -> Assessment {
+    if valid {
+        Assessment {
+            confidence: 1.0,
+            reason: "tool_calls",
+        }
+    } else {
+        Assessment {
+            confidence: 0.0,
+            reason: "invalid_tool_call",
+        }
+    }
+}
+
+/// A tool call is valid when it names an offered tool (if the offer is
+/// known) and its arguments are a JSON object.
+fn tool_call_is_valid(name: &str, arguments: &str, tool_names: Option<&[String]>) -> bool {
+    let known = tool_names.map_or(true, |names| names.iter().any(|tool| tool == name));
+    let arguments = arguments.trim();
+    let parsed = arguments.is_empty()
+        || serde_json::from_str::<serde_json::Value>(arguments)
+            .is_ok_and(|value| value.is_object());
+    known && parsed
+}
+
+/// Validate `<tool_call>` blocks of prompt-guided providers; `None` when the
+/// text has none.
+fn prompt_guided_tool_calls_valid(text: &str, tool_names: Option<&[String]>) -> Option<bool> {
+    if !text.contains("<tool_call>") {
+        return None;
+    }
+    let valid = text.split("<tool_call>").skip(1).all(|block| {
+        let body = block.split("</tool_call>").next().unwrap_or("").trim();
+        serde_json::from_str::<serde_json::Value>(body).is_ok_and(|call| {
+            let name = call.get("name").and_then(serde_json::Value::as_str);
+            let arguments =
+                call.get("arguments")
+                    .map_or_else(String::new, |arguments| match arguments {
+                        serde_json::Value::String(raw) => raw.clone(),
+                        other => other.to_string(),
+                    });
+            name.is_some_and(|name| tool_call_is_valid(name, &arguments, tool_names))
+        })
+    });
+    Some(valid)
+}
+
+/// First number in a self-check reply, scaled from 0–10 to 0.0–1.0.
+fn parse_self_check_score(reply: &str) -> Option<f64> {
+    let start = reply.find(|c: char| c.is_ascii_digit())?;
+    let number: String = reply[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let score: f64 = number.trim_end_matches('.').parse().ok()?;
+    Some((score / 10.0).clamp(0.0, 1.0))
+}
+
+/// Rough token count for `chars` bytes of text: ~4 characters per token.
+fn estimated_tokens(chars: usize) -> u64 {
+    (chars / 4) as u64
+}
+
+fn messages_tokens(messages: &[ChatMessage]) -> u64 {
+    estimated_tokens(messages.iter().map(|message| message.content.len()).sum())
+}
+
+/// Estimated output tokens of an answer whose provider reported no usage.
+fn response_tokens(response: &ChatResponse) -> u64 {
+    let text = response.text.as_deref().map_or(0, str::len);
+    let calls: usize = response
+        .tool_calls
+        .iter()
+        .map(|call| call.name.len() + call.arguments.len())
+        .sum();
+    estimated_tokens(text + calls)
+}
+
+fn one_shot_messages(system_prompt: Option<&str>, message: &str) -> Vec<ChatMessage> {
+    system_prompt
+        .map(ChatMessage::system)
+        .into_iter()
+        .chain(std::iter::once(ChatMessage::user(message)))
+        .collect()
+}
+
+fn text_response(text: String) -> ChatResponse {
+    ChatResponse {
+        text: Some(text),
+        tool_calls: Vec::new(),
+        usage: None,
+        reasoning_content: None,
+        quota_metadata: None,
+    }
+}
+
+fn json_tool_names(tools: &[serde_json::Value]) -> Vec<String> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            tool.pointer("/function/name")
+                .or_else(|| tool.get("name"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Provider for CascadeProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.passthrough.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.passthrough.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        if self.bypass(model) {
+            return self
+                .passthrough
+                .chat_with_system(system_prompt, message, model, temperature)
+                .await;
+        }
+        let messages = one_shot_messages(system_prompt, message);
+        let response = self
+            .cascade(&messages, None, |tier| {
+                Box::pin(async move {
+                    tier.provider
+                        .chat_with_system(system_prompt, message, &tier.request_model, temperature)
+                        .await
+                        .map(text_response)
+                })
+            })
+            .await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        if self.bypass(model) {
+            return self
+                .passthrough
+                .chat_with_history(messages, model, temperature)
+                .await;
+        }
+        let response = self
+            .cascade(messages, None, |tier| {
+                Box::pin(async move {
+                    tier.provider
+                        .chat_with_history(messages, &tier.request_model, temperature)
+                        .await
+                        .map(text_response)
+                })
+            })
+            .await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        if self.bypass(model) {
+            return self.passthrough.chat(request, model, temperature).await;
+        }
+        let tool_names: Option<Vec<String>> = request
+            .tools
+            .map(|tools| tools.iter().map(|tool| tool.name.clone()).collect());
+        self.cascade(request.messages, tool_names.as_deref(), |tier| {
+            Box::pin(
+                tier.provider
+                    .chat(request, &tier.request_model, temperature),
+            )
+        })
+        .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        if self.bypass(model) {
+            return self
+                .passthrough
+                .chat_with_tools(messages, tools, model, temperature)
+                .await;
+        }
+        let tool_names = json_tool_names(tools);
+        self.cascade(messages, Some(&tool_names), |tier| {
+            Box::pin(tier.provider.chat_with_tools(
+                messages,
+                tools,
+                &tier.request_model,
+                temperature,
+            ))
+        })
+        .await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.passthrough.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.passthrough.supports_vision()
+    }
+
+    async fn warmup(&self) -> Result<()> {
+        self.passthrough.warmup().await
+    }
+
+    /// Streaming would show a cheap answer that may still be replaced, so
+    /// cascading providers answer in one piece.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.passthrough.stream_chat_with_system(
+            system_prompt,
+            message,
+            model,
+            temperature,
+            options,
+        )
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.passthrough
+            .stream_chat_with_history(messages, model, temperature, options)
+    }
+}
+
+/// Apply `[model_cascade]` to a routed provider: each tier calls `provider`
+/// with `hint:<tier>`. Tiers without a matching `[[model_routes]]` entry are
+/// skipped.
+pub fn wrap_with_cascade(provider: Box<dyn Provider>, config: &Config) -> Box<dyn Provider> {
+    let Some(policy) = CascadePolicy::from_config(config) else {
+        return provider;
+    };
+    let routes: Vec<_> = policy
+        .tiers()
+        .iter()
+        .filter_map(|hint| {
+            let route = config.model_routes.iter().find(|route| &route.hint == hint);
+            if route.is_none() {
+                tracing::warn!(
+                    hint = hint.as_str(),
+                    "Cascade tier has no model route, skipping"
+                );
+            }
+            route
+        })
+        .collect();
+    if routes.is_empty() {
+        return provider;
+    }
+    tracing::info!("Model cascade enabled; replies are not streamed while cascading");
+    let provider: Arc<dyn Provider> = Arc::from(provider);
+    let tiers = routes
+        .into_iter()
+        .map(|route| {
+            CascadeTier::new(
+                route.hint.clone(),
+                route.provider.clone(),
+                route.model.clone(),
+                Arc::clone(&provider),
+                format!("hint:{}", route.hint),
+            )
+        })
+        .collect();
+    Box::new(CascadeProvider::new(policy, tiers, provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ToolCall;
+    use parking_lot::Mutex;
+
+    /// Answers `chat` per model and self-check prompts with a per-model score.
+    struct ScriptedProvider {
+        answers: HashMap<String, ChatResponse>,
+        scores: HashMap<String, String>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(answers: &[(&str, ChatResponse)], scores: &[(&str, &str)]) -> Arc<Self> {
+            Arc::new(Self {
+                answers: answers
+                    .iter()
+                    .map(|(model, response)| ((*model).to_string(), response.clone()))
+                    .collect(),
+                scores: scores
+                    .iter()
+                    .map(|(model, score)| ((*model).to_string(), (*score).to_string()))
+                    .collect(),
+                calls: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().clone()
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            system_prompt: Option<&str>,
+            _message: &str,
+            model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            assert_eq!(system_prompt, Some(SELF_CHECK_PROMPT));
+            self.calls.lock().push(format!("check:{model}"));
+            Ok(self.scores.get(model).cloned().unwrap_or_default())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            self.calls.lock().push(model.to_string());
+            self.answers
+                .get(model)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("no answer for {model}"))
+        }
+    }
+
+    fn config(workspace: &std::path::Path) -> Config {
+        let mut config = Config {
+            workspace_dir: workspace.to_path_buf(),
+            ..Config::default()
+        };
+        config.model_cascade.enabled = true;
+        config.model_cascade.tiers = vec!["cheap".into(), "strong".into()];
+        config
+    }
+
+    fn cascade(config: &Config, scripted: &Arc<ScriptedProvider>) -> CascadeProvider {
+        let provider: Arc<dyn Provider> = scripted.clone();
+        let tiers = ["cheap", "strong"]
+            .into_iter()
+            .map(|hint| {
+                CascadeTier::new(
+                    hint,
+                    "mock",
+                    format!("{hint}-model"),
+                    Arc::clone(&provider),
+                    hint,
+                )
+            })
+            .collect();
+        CascadeProvider::new(CascadePolicy::from_config(config).unwrap(), tiers, provider)
+    }
+
+    fn decisions(workspace: &std::path::Path) -> Vec<CascadeDecision> {
+        std::fs::read_to_string(workspace.join(DECISION_LOG_FILE))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn request(messages: &[ChatMessage]) -> ChatRequest<'_> {
+        ChatRequest {
+            messages,
+            tools: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn confident_cheap_answer_is_kept() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scripted = ScriptedProvider::new(
+            &[
+                ("cheap", text_response("Paris".into())),
+                ("strong", text_response("Paris, France".into())),
+            ],
+            &[("cheap", "9")],
+        );
+        let provider = cascade(&config(tmp.path()), &scripted);
+        let messages = [ChatMessage::user("Capital of France?")];
+
+        let response = provider
+            .chat(request(&messages), "default", 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(response.text.as_deref(), Some("Paris"));
+        assert_eq!(scripted.calls(), vec!["cheap", "check:cheap"]);
+        let logged = decisions(tmp.path());
+        assert_eq!(logged[0].outcome, CascadeOutcome::Accepted);
+        assert_eq!(logged[0].attempts[0].confidence, Some(0.9));
+    }
+
+    #[tokio::test]
+    async fn hedging_or_invalid_tool_calls_escalate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let invalid_call = ChatResponse {
+            tool_calls: vec![ToolCall {
+                id: "1".into(),
+                name: "no_such_tool".into(),
+                arguments: "{}".into(),
+            }],
+            ..text_response(String::new())
+        };
+        let scripted = ScriptedProvider::new(
+            &[
+                ("cheap", invalid_call),
+                ("strong", text_response("Done".into())),
+            ],
+            &[],
+        );
+        let provider = cascade(&config(tmp.path()), &scripted);
+        let messages = [ChatMessage::user("List files")];
+        let tools = [ToolSpec {
+            name: "shell".into(),
+            description: String::new(),
+            parameters: serde_json::json!({}),
+        }];
+
+        let response = provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: Some(&tools),
+                },
+                "default",
+                0.0,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.text.as_deref(), Some("Done"));
+        let logged = decisions(tmp.path());
+        assert_eq!(logged[0].outcome, CascadeOutcome::Escalated);
+        assert_eq!(logged[0].attempts[0].reason, "invalid_tool_call");
+        assert_eq!(logged[0].selected, "strong");
+
+        let hedging = ScriptedProvider::new(
+            &[
+                ("cheap", text_response("I'm not sure, maybe 42?".into())),
+                ("strong", text_response("42".into())),
+            ],
+            &[],
+        );
+        let provider = cascade(&config(tmp.path()), &hedging);
+        let response = provider
+            .chat(request(&messages), "default", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("42"));
+        assert_eq!(hedging.calls(), vec!["cheap", "strong"]);
+    }
+
+    #[tokio::test]
+    async fn exhausted_budget_keeps_cheap_answer() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config(tmp.path());
+        config.cost.enabled = true;
+        config.cost.daily_limit_usd = 0.0;
+        let scripted = ScriptedProvider::new(
+            &[
+                ("cheap", text_response("Probably Paris".into())),
+                ("strong", text_response("Paris".into())),
+            ],
+            &[("cheap", "3/10")],
+        );
+        let provider = cascade(&config, &scripted);
+        let messages = [ChatMessage::user("Capital of France?")];
+
+        let response = provider
+            .chat(request(&messages), "default", 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(response.text.as_deref(), Some("Probably Paris"));
+        assert_eq!(scripted.calls(), vec!["cheap"]);
+        let logged = decisions(tmp.path());
+        assert_eq!(logged[0].outcome, CascadeOutcome::BudgetLimited);
+        assert_eq!(logged[0].attempts[0].reason, "self_check_budget_exceeded");
+        assert_eq!(logged[0].attempts[1].reason, "budget_exceeded");
+    }
+
+    #[tokio::test]
+    async fn tier_and_self_check_spend_is_recorded_in_shared_tracker() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config(tmp.path());
+        config.cost.enabled = true;
+        let cheap = ChatResponse {
+            usage: Some(crate::providers::traits::TokenUsage {
+                input_tokens: Some(100),
+                output_tokens: Some(20),
+            }),
+            ..text_response("Paris".into())
+        };
+        let scripted = ScriptedProvider::new(
+            &[("cheap", cheap), ("strong", text_response("Paris".into()))],
+            &[("cheap", "2")],
+        );
+        let provider = cascade(&config, &scripted);
+        let messages = [ChatMessage::user("Capital of France?")];
+
+        let response = provider
+            .chat(request(&messages), "default", 0.0)
+            .await
+            .unwrap();
+
+        assert!(response.usage.is_none());
+        let summary = CostTracker::shared(&config.cost, tmp.path())
+            .unwrap()
+            .get_summary()
+            .unwrap();
+        // Cheap answer, its self-check and the strong answer.
+        assert_eq!(summary.request_count, 3);
+        assert_eq!(summary.by_model["mock/cheap-model"].request_count, 2);
+        assert!(summary.by_model.contains_key("mock/strong-model"));
+    }
+
+    #[tokio::test]
+    async fn explicit_hint_bypasses_cascade() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scripted =
+            ScriptedProvider::new(&[("hint:reasoning", text_response("ok".into()))], &[]);
+        let provider = cascade(&config(tmp.path()), &scripted);
+        let messages = [ChatMessage::user("Prove it")];
+
+        provider
+            .chat(request(&messages), "hint:reasoning", 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(scripted.calls(), vec!["hint:reasoning"]);
+        assert!(!tmp.path().join(DECISION_LOG_FILE).exists());
+    }
+
+    #[test]
+    fn self_check_scores_and_tool_calls_are_parsed() {
+        assert_eq!(parse_self_check_score("8"), Some(0.8));
+        assert_eq!(parse_self_check_score("Score: 7.5/10"), Some(0.75));
+        assert_eq!(parse_self_check_score("none"), None);
+
+        let tools = vec!["shell".to_string()];
+        assert_eq!(
+            prompt_guided_tool_calls_valid(
+                "<tool_call>{\"name\":\"shell\",\"arguments\":{\"command\":\"ls\"}}</tool_call>",
+                Some(&tools)
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            prompt_guided_tool_calls_valid("<tool_call>{oops</tool_call>", Some(&tools)),
+            Some(false)
+        );
+        assert_eq!(
+            prompt_guided_tool_calls_valid("plain text", Some(&tools)),
+            None
+        );
+    }
+}
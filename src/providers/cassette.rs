@@ -0,0 +1,1156 @@
+//! Record-and-replay provider wrappers for deterministic agent tests.
+//!
+//! [`RecordingProvider`] forwards every call to a real provider and records the
+//! request/response pair (text, tool calls, usage, reasoning content, stream
+//! chunks and errors) into a JSON cassette written when the provider is
+//! dropped. [`ReplayProvider`] serves a cassette
+//! back without network access, matching requests strictly (in order, exact
+//! messages with timestamps masked) or fuzzily (by last user message and tool
+//! names). [`wrap_with_cassette`] applies `[provider.cassette]` so any
+//! `zeroclaw agent -m` session can be recorded once and replayed in CI.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, StreamChunk, StreamError,
+    StreamOptions, StreamResult, TokenUsage, ToolCall, ToolsPayload,
+};
+use super::Provider;
+use crate::config::{CassetteMatchMode, CassetteMode, ProviderCassetteConfig};
+use crate::tools::ToolSpec;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// On-disk cassette: provider capabilities plus recorded interactions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    #[serde(default)]
+    pub capabilities: RecordedCapabilities,
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+        let cassette: Self = serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse cassette {}", path.display()))?;
+        if cassette.version != CASSETTE_VERSION {
+            anyhow::bail!(
+                "Unsupported cassette version {} in {} (expected {CASSETTE_VERSION})",
+                cassette.version,
+                path.display()
+            );
+        }
+        Ok(cassette)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let body = serde_json::to_string_pretty(self).context("Failed to serialize cassette")?;
+        let temp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        fs::write(&temp_path, body)
+            .with_context(|| format!("Failed to write cassette {}", temp_path.display()))?;
+        fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace cassette {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedCapabilities {
+    #[serde(default)]
+    pub native_tool_calling: bool,
+    #[serde(default)]
+    pub vision: bool,
+    #[serde(default)]
+    pub streaming: bool,
+}
+
+/// Which provider entry point produced an interaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionKind {
+    ChatWithSystem,
+    ChatWithHistory,
+    Chat,
+    ChatWithTools,
+    Stream,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub model: String,
+    pub temperature: f64,
+    pub messages: Vec<ChatMessage>,
+    /// Names of the tools offered with the request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default)]
+    pub usage: Option<RecordedUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<RecordedChunk>,
+    /// Error message when the provider call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedUsage {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedChunk {
+    pub delta: String,
+    #[serde(default)]
+    pub is_final: bool,
+    #[serde(default)]
+    pub token_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub kind: InteractionKind,
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+impl RecordedResponse {
+    fn from_chat(response: &ChatResponse) -> Self {
+        Self {
+            text: response.text.clone(),
+            tool_calls: response.tool_calls.clone(),
+            usage: response.usage.as_ref().map(|usage| RecordedUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+            }),
+            reasoning_content: response.reasoning_content.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn from_error(error: &anyhow::Error) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Self::default()
+        }
+    }
+
+    fn into_chat(self) -> Result<ChatResponse> {
+        if let Some(error) = self.error {
+            anyhow::bail!(error);
+        }
+        Ok(ChatResponse {
+            text: self.text,
+            tool_calls: self.tool_calls,
+            usage: self.usage.map(|usage| TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+            }),
+            reasoning_content: self.reasoning_content,
+            quota_metadata: None,
+        })
+    }
+
+    fn into_text(self) -> Result<String> {
+        Ok(self.into_chat()?.text.unwrap_or_default())
+    }
+}
+
+fn one_shot_messages(system_prompt: Option<&str>, message: &str) -> Vec<ChatMessage> {
+    let mut messages = Vec::with_capacity(2);
+    if let Some(system) = system_prompt {
+        messages.push(ChatMessage::system(system));
+    }
+    messages.push(ChatMessage::user(message));
+    messages
+}
+
+fn spec_names(tools: Option<&[ToolSpec]>) -> Vec<String> {
+    tools
+        .unwrap_or_default()
+        .iter()
+        .map(|tool| tool.name.clone())
+        .collect()
+}
+
+/// Tool names from provider-native JSON tool definitions.
+fn json_tool_names(tools: &[serde_json::Value]) -> Vec<String> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            tool.pointer("/function/name")
+                .or_else(|| tool.get("name"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+// ── Recording ────────────────────────────────────────────────────────────
+
+/// Buffers interactions in memory; the cassette is written once, when the
+/// last handle (the provider and any open recorded streams) is dropped.
+struct Recorder {
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl Recorder {
+    fn record(&self, kind: InteractionKind, request: RecordedRequest, response: RecordedResponse) {
+        self.cassette.lock().interactions.push(Interaction {
+            kind,
+            request,
+            response,
+        });
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Err(error) = self.cassette.get_mut().save(&self.path) {
+            tracing::warn!("Failed to persist provider cassette: {error:#}");
+        }
+    }
+}
+
+/// Wraps a provider and records every request/response pair to a cassette.
+/// Interactions are kept in memory and the cassette file is written when the
+/// provider is dropped, so recording adds no file I/O to provider calls. A
+/// session that crashes before that leaves only the empty cassette.
+pub struct RecordingProvider {
+    inner: Box<dyn Provider>,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingProvider {
+    /// Start a fresh cassette at `path`, replacing any existing one.
+    pub fn new(inner: Box<dyn Provider>, path: impl Into<PathBuf>) -> Result<Self> {
+        let capabilities = inner.capabilities();
+        let cassette = Cassette {
+            version: CASSETTE_VERSION,
+            capabilities: RecordedCapabilities {
+                native_tool_calling: capabilities.native_tool_calling,
+                vision: capabilities.vision,
+                streaming: inner.supports_streaming(),
+            },
+            interactions: Vec::new(),
+        };
+        let path = path.into();
+        cassette.save(&path)?;
+        Ok(Self {
+            inner,
+            recorder: Arc::new(Recorder {
+                path,
+                cassette: Mutex::new(cassette),
+            }),
+        })
+    }
+
+    fn record_text(
+        &self,
+        kind: InteractionKind,
+        request: RecordedRequest,
+        result: &Result<String>,
+    ) {
+        let response = match result {
+            Ok(text) => RecordedResponse {
+                text: Some(text.clone()),
+                ..RecordedResponse::default()
+            },
+            Err(error) => RecordedResponse::from_error(error),
+        };
+        self.recorder.record(kind, request, response);
+    }
+
+    fn record_chat(
+        &self,
+        kind: InteractionKind,
+        request: RecordedRequest,
+        result: &Result<ChatResponse>,
+    ) {
+        let response = match result {
+            Ok(response) => RecordedResponse::from_chat(response),
+            Err(error) => RecordedResponse::from_error(error),
+        };
+        self.recorder.record(kind, request, response);
+    }
+
+    /// Tee a stream into the cassette, recording once it ends.
+    fn record_stream(
+        &self,
+        request: RecordedRequest,
+        inner: stream::BoxStream<'static, StreamResult<StreamChunk>>,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        struct Tee {
+            recorder: Arc<Recorder>,
+            request: Option<RecordedRequest>,
+            response: RecordedResponse,
+        }
+
+        impl Tee {
+            fn finish(&mut self) {
+                if let Some(request) = self.request.take() {
+                    self.recorder.record(
+                        InteractionKind::Stream,
+                        request,
+                        std::mem::take(&mut self.response),
+                    );
+                }
+            }
+        }
+
+        impl Drop for Tee {
+            fn drop(&mut self) {
+                self.finish();
+            }
+        }
+
+        let tee = Tee {
+            recorder: Arc::clone(&self.recorder),
+            request: Some(request),
+            response: RecordedResponse::default(),
+        };
+        stream::unfold((inner, tee), |(mut inner, mut tee)| async move {
+            match inner.next().await {
+                Some(Ok(chunk)) => {
+                    tee.response.chunks.push(RecordedChunk {
+                        delta: chunk.delta.clone(),
+                        is_final: chunk.is_final,
+                        token_count: chunk.token_count,
+                    });
+                    Some((Ok(chunk), (inner, tee)))
+                }
+                Some(Err(error)) => {
+                    tee.response.error = Some(error.to_string());
+                    tee.finish();
+                    Some((Err(error), (inner, tee)))
+                }
+                None => {
+                    tee.finish();
+                    None
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let result = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await;
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: one_shot_messages(system_prompt, message),
+            tools: Vec::new(),
+        };
+        self.record_text(InteractionKind::ChatWithSystem, request, &result);
+        result
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let result = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await;
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools: Vec::new(),
+        };
+        self.record_text(InteractionKind::ChatWithHistory, request, &result);
+        result
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let result = self.inner.chat(request, model, temperature).await;
+        let recorded = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: request.messages.to_vec(),
+            tools: spec_names(request.tools),
+        };
+        self.record_chat(InteractionKind::Chat, recorded, &result);
+        result
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn warmup(&self) -> Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let result = self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await;
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools: json_tool_names(tools),
+        };
+        self.record_chat(InteractionKind::ChatWithTools, request, &result);
+        result
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let inner =
+            self.inner
+                .stream_chat_with_system(system_prompt, message, model, temperature, options);
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: one_shot_messages(system_prompt, message),
+            tools: Vec::new(),
+        };
+        self.record_stream(request, inner)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let inner = self
+            .inner
+            .stream_chat_with_history(messages, model, temperature, options);
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools: Vec::new(),
+        };
+        self.record_stream(request, inner)
+    }
+}
+
+// ── Replay ───────────────────────────────────────────────────────────────
+
+/// Mask wall-clock timestamps the agent stamps into prompts so strict matching
+/// survives re-runs on another day.
+fn mask_volatile(text: &str) -> String {
+    static TIMESTAMP: OnceLock<Regex> = OnceLock::new();
+    let pattern = TIMESTAMP.get_or_init(|| {
+        Regex::new(
+            r"\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2}| [A-Z]{2,5})?)?|\b\d{2}:\d{2}:\d{2}\b",
+        )
+        .expect("timestamp pattern is valid")
+    });
+    pattern.replace_all(text, "<ts>").into_owned()
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn last_user_message(messages: &[ChatMessage]) -> Option<String> {
+    messages
+        .iter()
+        .rfind(|message| message.role == "user")
+        .map(|message| normalize_whitespace(&mask_volatile(&message.content)))
+}
+
+fn strict_matches(recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+    recorded.model == request.model
+        && recorded.tools == request.tools
+        && recorded.messages.len() == request.messages.len()
+        && recorded
+            .messages
+            .iter()
+            .zip(&request.messages)
+            .all(|(left, right)| {
+                left.role == right.role
+                    && mask_volatile(&left.content) == mask_volatile(&right.content)
+            })
+}
+
+fn fuzzy_matches(recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+    recorded.tools == request.tools
+        && last_user_message(&recorded.messages) == last_user_message(&request.messages)
+}
+
+fn describe_request(kind: InteractionKind, request: &RecordedRequest) -> String {
+    let last_user = last_user_message(&request.messages).unwrap_or_default();
+    let preview: String = last_user.chars().take(80).collect();
+    format!(
+        "{kind:?} model={} messages={} tools=[{}] last_user={preview:?}",
+        request.model,
+        request.messages.len(),
+        request.tools.join(",")
+    )
+}
+
+/// Serves recorded responses from a cassette without contacting a provider.
+pub struct ReplayProvider {
+    cassette: Cassette,
+    match_mode: CassetteMatchMode,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayProvider {
+    pub fn new(cassette: Cassette, match_mode: CassetteMatchMode) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            cassette,
+            match_mode,
+            used: Mutex::new(used),
+        }
+    }
+
+    pub fn load(path: &Path, match_mode: CassetteMatchMode) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?, match_mode))
+    }
+
+    /// Number of recorded interactions not yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.used.lock().iter().filter(|used| !**used).count()
+    }
+
+    fn next_response(
+        &self,
+        kind: InteractionKind,
+        request: &RecordedRequest,
+    ) -> Result<RecordedResponse> {
+        let mut used = self.used.lock();
+        let mut unused = self
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !used[*index]);
+
+        let index = match self.match_mode {
+            CassetteMatchMode::Strict => {
+                let Some((index, recorded)) = unused.next() else {
+                    anyhow::bail!(
+                        "Cassette exhausted: no recording left for {}",
+                        describe_request(kind, request)
+                    );
+                };
+                if recorded.kind != kind || !strict_matches(&recorded.request, request) {
+                    anyhow::bail!(
+                        "Cassette mismatch at interaction #{index}: recorded {}, got {}",
+                        describe_request(recorded.kind, &recorded.request),
+                        describe_request(kind, request)
+                    );
+                }
+                index
+            }
+            CassetteMatchMode::Fuzzy => {
+                let candidates: Vec<_> = unused
+                    .filter(|(_, recorded)| recorded.kind == kind)
+                    .collect();
+                if candidates.is_empty() {
+                    anyhow::bail!(
+                        "Cassette exhausted: no recording left for {}",
+                        describe_request(kind, request)
+                    );
+                }
+                candidates
+                    .iter()
+                    .find(|(_, recorded)| fuzzy_matches(&recorded.request, request))
+                    .map(|(index, _)| *index)
+                    .with_context(|| {
+                        format!(
+                            "Cassette has no matching interaction for {}",
+                            describe_request(kind, request)
+                        )
+                    })?
+            }
+        };
+
+        used[index] = true;
+        Ok(self.cassette.interactions[index].response.clone())
+    }
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: self.cassette.capabilities.native_tool_calling,
+            vision: self.cassette.capabilities.vision,
+        }
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: one_shot_messages(system_prompt, message),
+            tools: Vec::new(),
+        };
+        self.next_response(InteractionKind::ChatWithSystem, &request)?
+            .into_text()
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools: Vec::new(),
+        };
+        self.next_response(InteractionKind::ChatWithHistory, &request)?
+            .into_text()
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: request.messages.to_vec(),
+            tools: spec_names(request.tools),
+        };
+        self.next_response(InteractionKind::Chat, &request)?
+            .into_chat()
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools: json_tool_names(tools),
+        };
+        self.next_response(InteractionKind::ChatWithTools, &request)?
+            .into_chat()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.cassette.capabilities.streaming
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        _options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: one_shot_messages(system_prompt, message),
+            tools: Vec::new(),
+        };
+        self.replay_stream(&request)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        _options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request = RecordedRequest {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools: Vec::new(),
+        };
+        self.replay_stream(&request)
+    }
+}
+
+impl ReplayProvider {
+    fn replay_stream(
+        &self,
+        request: &RecordedRequest,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let response = match self.next_response(InteractionKind::Stream, request) {
+            Ok(response) => response,
+            Err(error) => {
+                return stream::once(async move { Err(StreamError::Provider(error.to_string())) })
+                    .boxed()
+            }
+        };
+        let mut items: Vec<StreamResult<StreamChunk>> = response
+            .chunks
+            .into_iter()
+            .map(|chunk| {
+                Ok(StreamChunk {
+                    delta: chunk.delta,
+                    is_final: chunk.is_final,
+                    token_count: chunk.token_count,
+                })
+            })
+            .collect();
+        if let Some(error) = response.error {
+            items.push(Err(StreamError::Provider(error)));
+        }
+        stream::iter(items).boxed()
+    }
+}
+
+/// Apply `[provider.cassette]`: record through `provider`, replay instead of
+/// it, or return it unchanged.
+pub fn wrap_with_cassette(
+    provider: Box<dyn Provider>,
+    config: &ProviderCassetteConfig,
+) -> Result<Box<dyn Provider>> {
+    let path = || {
+        config
+            .resolved_path()
+            .context("provider.cassette.path is required when provider.cassette.mode is set")
+    };
+    match config.mode {
+        CassetteMode::Off => Ok(provider),
+        CassetteMode::Record => {
+            let path = path()?;
+            tracing::info!(path = %path.display(), "Recording provider calls to cassette");
+            Ok(Box::new(RecordingProvider::new(provider, path)?))
+        }
+        CassetteMode::Replay => {
+            let path = path()?;
+            tracing::info!(
+                path = %path.display(),
+                match_mode = ?config.match_mode,
+                "Replaying provider calls from cassette"
+            );
+            Ok(Box::new(ReplayProvider::load(&path, config.match_mode)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    struct ScriptedProvider {
+        responses: Mutex<VecDeque<ChatResponse>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(responses: Vec<ChatResponse>) -> Self {
+            Self {
+                responses: Mutex::new(responses.into()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                native_tool_calling: true,
+                vision: false,
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            if message == "fail" {
+                anyhow::bail!("upstream 503");
+            }
+            Ok(format!("echo:{message}"))
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            self.responses
+                .lock()
+                .pop_front()
+                .context("scripted provider exhausted")
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn stream_chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+            _options: StreamOptions,
+        ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+            stream::iter(vec![
+                Ok(StreamChunk::delta("Hel")),
+                Ok(StreamChunk::delta("lo")),
+                Ok(StreamChunk::final_chunk()),
+            ])
+            .boxed()
+        }
+    }
+
+    fn tool_call_response() -> ChatResponse {
+        ChatResponse {
+            text: Some(String::new()),
+            tool_calls: vec![ToolCall {
+                id: "call_1".into(),
+                name: "shell".into(),
+                arguments: r#"{"command":"ls"}"#.into(),
+            }],
+            usage: Some(TokenUsage {
+                input_tokens: Some(120),
+                output_tokens: Some(12),
+            }),
+            reasoning_content: Some("need a listing".into()),
+            quota_metadata: None,
+        }
+    }
+
+    fn final_response() -> ChatResponse {
+        ChatResponse {
+            text: Some("done".into()),
+            tool_calls: Vec::new(),
+            usage: None,
+            reasoning_content: None,
+            quota_metadata: None,
+        }
+    }
+
+    fn tools() -> Vec<ToolSpec> {
+        vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }]
+    }
+
+    fn turn(system: &str, user: &str) -> Vec<ChatMessage> {
+        vec![ChatMessage::system(system), ChatMessage::user(user)]
+    }
+
+    async fn record_session(path: &Path) {
+        let provider = RecordingProvider::new(
+            Box::new(ScriptedProvider::new(vec![
+                tool_call_response(),
+                final_response(),
+            ])),
+            path,
+        )
+        .unwrap();
+        let tools = tools();
+        let first = turn(
+            "sys at 2026-01-02 03:04:05 UTC",
+            "[2026-01-02 03:04:05 UTC] list files",
+        );
+        provider
+            .chat(
+                ChatRequest {
+                    messages: &first,
+                    tools: Some(&tools),
+                },
+                "model-a",
+                0.2,
+            )
+            .await
+            .unwrap();
+        let mut second = first.clone();
+        second.push(ChatMessage::user("[Tool results] a.txt"));
+        provider
+            .chat(
+                ChatRequest {
+                    messages: &second,
+                    tools: Some(&tools),
+                },
+                "model-a",
+                0.2,
+            )
+            .await
+            .unwrap();
+        assert!(provider
+            .chat_with_system(None, "fail", "model-a", 0.2)
+            .await
+            .is_err());
+        let chunks: Vec<_> = provider
+            .stream_chat_with_system(None, "stream", "model-a", 0.2, StreamOptions::new(true))
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn recorded_session_replays_strictly_with_masked_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        record_session(&path).await;
+
+        let replay = ReplayProvider::load(&path, CassetteMatchMode::Strict).unwrap();
+        assert!(replay.supports_native_tools());
+        assert!(replay.supports_streaming());
+
+        let tools = tools();
+        let first = turn(
+            "sys at 2027-05-06 07:08:09 PDT",
+            "[2027-05-06 07:08:09 PDT] list files",
+        );
+        let response = replay
+            .chat(
+                ChatRequest {
+                    messages: &first,
+                    tools: Some(&tools),
+                },
+                "model-a",
+                0.2,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(response.usage.unwrap().input_tokens, Some(120));
+        assert_eq!(
+            response.reasoning_content.as_deref(),
+            Some("need a listing")
+        );
+
+        let mut second = first.clone();
+        second.push(ChatMessage::user("[Tool results] a.txt"));
+        let response = replay
+            .chat(
+                ChatRequest {
+                    messages: &second,
+                    tools: Some(&tools),
+                },
+                "model-a",
+                0.2,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("done"));
+
+        let error = replay
+            .chat_with_system(None, "fail", "model-a", 0.2)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("upstream 503"));
+
+        let deltas: Vec<String> = replay
+            .stream_chat_with_system(None, "stream", "model-a", 0.2, StreamOptions::new(true))
+            .map(|chunk| chunk.unwrap().delta)
+            .collect()
+            .await;
+        assert_eq!(deltas, vec!["Hel", "lo", ""]);
+        assert_eq!(replay.remaining(), 0);
+
+        let exhausted = replay
+            .chat_with_system(None, "again", "model-a", 0.2)
+            .await
+            .unwrap_err();
+        assert!(exhausted.to_string().contains("Cassette exhausted"));
+    }
+
+    #[tokio::test]
+    async fn strict_replay_rejects_changed_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        record_session(&path).await;
+
+        let replay = ReplayProvider::load(&path, CassetteMatchMode::Strict).unwrap();
+        let tools = tools();
+        let changed = turn("a different system prompt", "list files");
+        let error = replay
+            .chat(
+                ChatRequest {
+                    messages: &changed,
+                    tools: Some(&tools),
+                },
+                "model-a",
+                0.2,
+            )
+            .await
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Cassette mismatch at interaction #0"));
+    }
+
+    #[tokio::test]
+    async fn fuzzy_replay_matches_by_last_user_message_out_of_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        record_session(&path).await;
+
+        let replay = ReplayProvider::load(&path, CassetteMatchMode::Fuzzy).unwrap();
+        let tools = tools();
+        let second = vec![
+            ChatMessage::system("rewritten prompt"),
+            ChatMessage::user("[Tool results]   a.txt"),
+        ];
+        let response = replay
+            .chat(
+                ChatRequest {
+                    messages: &second,
+                    tools: Some(&tools),
+                },
+                "model-b",
+                0.7,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("done"));
+
+        let unmatched = turn("rewritten prompt", "something else");
+        let error = replay
+            .chat(
+                ChatRequest {
+                    messages: &unmatched,
+                    tools: Some(&tools),
+                },
+                "model-b",
+                0.7,
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no matching interaction"));
+        assert_eq!(replay.remaining(), 3, "unmatched requests consume nothing");
+    }
+
+    #[tokio::test]
+    async fn recording_writes_cassette_once_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let provider = RecordingProvider::new(
+            Box::new(ScriptedProvider::new(vec![final_response()])),
+            &path,
+        )
+        .unwrap();
+        let messages = turn("sys", "hello");
+        provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                },
+                "model-a",
+                0.2,
+            )
+            .await
+            .unwrap();
+        assert!(Cassette::load(&path).unwrap().interactions.is_empty());
+
+        drop(provider);
+        assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 1);
+    }
+
+    #[test]
+    fn wrap_with_cassette_requires_path_and_honours_off() {
+        let off = ProviderCassetteConfig::default();
+        let provider = wrap_with_cassette(Box::new(ScriptedProvider::new(Vec::new())), &off);
+        assert!(provider.is_ok());
+
+        let missing = ProviderCassetteConfig {
+            mode: CassetteMode::Replay,
+            ..ProviderCassetteConfig::default()
+        };
+        assert!(wrap_with_cassette(Box::new(ScriptedProvider::new(Vec::new())), &missing).is_err());
+    }
+}
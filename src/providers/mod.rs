@@ -9,7 +9,9 @@
 //! The subsystem supports resilient multi-provider configurations through the
 //! [`ReliableProvider`](reliable::ReliableProvider) wrapper, which handles fallback
 //! chains and automatic retry. Model routing across providers is available via
-//! [`create_routed_provider`].
+//! [`create_routed_provider`]. Provider traffic can be recorded to a cassette and
+//! replayed offline via [`cassette::wrap_with_cassette`]. Budget-aware cascading
+//! from cheap to strong routes is applied by [`cascade::wrap_with_cascade`].
 //!
 //! # Extension
 //!
@@ -19,6 +21,8 @@
 pub mod anthropic;
 pub mod backoff;
 pub mod bedrock;
+pub mod cascade;
+pub mod cassette;
 pub mod compatible;
 pub mod copilot;
 pub mod gemini;
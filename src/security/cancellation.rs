@@ -0,0 +1,665 @@
+//! Process-wide cancellation registry driven by the emergency stop.
+//!
+//! In-flight work — tool calls, background processes, sub-agents, cron jobs
+//! and HTTP tool requests — registers a [`StopScope`] and receives a
+//! [`TaskGuard`] carrying a [`CancellationToken`]. When estop is engaged
+//! (usually by `zeroclaw estop` in another process, picked up by
+//! [`spawn_watcher`]), every entry matching the engaged level is cancelled,
+//! tracked process groups are killed, and a [`StopReport`] listing exactly what
+//! was stopped is written next to the estop state file for the CLI to print.
+//!
+//! Registering while a matching level is already engaged returns a guard that
+//! is stopped from the start, so callers can refuse new work the same way.
+
+use super::estop::{EstopManager, EstopState};
+use super::DomainMatcher;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
+
+/// How often the watcher re-reads the estop state file.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tools that reach the network and stop under `network-kill`.
+const NETWORK_TOOLS: &[&str] = &[
+    "http_request",
+    "web_fetch",
+    "web_search_tool",
+    "browser",
+    "browser_open",
+    "composio",
+    "pushover",
+    "linear",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    ToolCall,
+    Process,
+    SubAgent,
+    CronJob,
+    HttpRequest,
+}
+
+/// Describes a unit of in-flight work for estop matching.
+#[derive(Debug, Clone)]
+pub struct StopScope {
+    kind: TaskKind,
+    label: String,
+    tool: Option<String>,
+    host: Option<String>,
+    pid: Option<u32>,
+}
+
+impl StopScope {
+    pub fn tool_call(tool: &str) -> Self {
+        Self {
+            kind: TaskKind::ToolCall,
+            label: tool.to_string(),
+            tool: Some(tool.to_ascii_lowercase()),
+            host: None,
+            pid: None,
+        }
+    }
+
+    /// A background child process; its process group is killed on stop.
+    pub fn process(tool: &str, command: &str, pid: u32) -> Self {
+        Self {
+            kind: TaskKind::Process,
+            label: command.to_string(),
+            tool: Some(tool.to_ascii_lowercase()),
+            host: None,
+            pid: (pid != 0).then_some(pid),
+        }
+    }
+
+    pub fn sub_agent(session_id: &str, agent: &str) -> Self {
+        Self {
+            kind: TaskKind::SubAgent,
+            label: format!("{agent} ({session_id})"),
+            tool: Some("subagent_spawn".to_string()),
+            host: None,
+            pid: None,
+        }
+    }
+
+    pub fn cron_job(job_id: &str) -> Self {
+        Self {
+            kind: TaskKind::CronJob,
+            label: job_id.to_string(),
+            tool: None,
+            host: None,
+            pid: None,
+        }
+    }
+
+    pub fn http_request(tool: &str, host: &str) -> Self {
+        Self {
+            kind: TaskKind::HttpRequest,
+            label: format!("{tool} {host}"),
+            tool: Some(tool.to_ascii_lowercase()),
+            host: Some(host.to_ascii_lowercase()),
+            pid: None,
+        }
+    }
+
+    fn is_network(&self) -> bool {
+        self.kind == TaskKind::HttpRequest
+            || self
+                .tool
+                .as_deref()
+                .is_some_and(|tool| NETWORK_TOOLS.contains(&tool))
+    }
+
+    /// Whether `state` requires this work to stop.
+    fn stopped_by(&self, state: &EstopState) -> bool {
+        if state.kill_all {
+            return true;
+        }
+        if state.network_kill && self.is_network() {
+            return true;
+        }
+        if let Some(tool) = self.tool.as_deref() {
+            if state.frozen_tools.iter().any(|frozen| frozen == tool) {
+                return true;
+            }
+        }
+        match (self.host.as_deref(), state.blocked_domains.is_empty()) {
+            (Some(host), false) => DomainMatcher::new(&state.blocked_domains, &[])
+                .is_ok_and(|matcher| matcher.is_gated(host)),
+            _ => false,
+        }
+    }
+}
+
+/// Handle for registered work. Dropping it unregisters the work.
+#[derive(Debug)]
+pub struct TaskGuard {
+    id: Option<u64>,
+    token: CancellationToken,
+}
+
+impl TaskGuard {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once estop stops this work.
+    pub async fn stopped(&self) {
+        self.token.cancelled().await;
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            runtime().entries.lock().remove(&id);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoppedTask {
+    pub kind: TaskKind,
+    pub label: String,
+    #[serde(default)]
+    pub pid: Option<u32>,
+}
+
+/// What a process stopped in response to an estop engagement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StopReport {
+    /// `updated_at` of the estop state this report responds to.
+    pub engaged_at: Option<String>,
+    pub level: String,
+    pub reporter_pid: u32,
+    pub stopped: Vec<StoppedTask>,
+}
+
+struct TaskEntry {
+    scope: StopScope,
+    token: CancellationToken,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+struct WatchedState {
+    state_path: PathBuf,
+    config: crate::config::EstopConfig,
+    config_dir: PathBuf,
+    state: EstopState,
+    stamp: Option<FileStamp>,
+}
+
+struct Runtime {
+    watched: RwLock<Option<Mutex<WatchedState>>>,
+    entries: Mutex<HashMap<u64, TaskEntry>>,
+    next_id: AtomicU64,
+    watcher_started: AtomicBool,
+}
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime {
+        watched: RwLock::new(None),
+        entries: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+        watcher_started: AtomicBool::new(false),
+    })
+}
+
+/// Install the estop state this process enforces against registered work.
+/// A no-op when `[security.estop]` is disabled.
+pub fn install_runtime(config: &Config) -> Result<()> {
+    if !config.security.estop.enabled {
+        return Ok(());
+    }
+    let config_dir = config
+        .config_path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let manager = EstopManager::load(&config.security.estop, config_dir)?;
+    *runtime().watched.write() = Some(Mutex::new(WatchedState {
+        state_path: manager.state_path().to_path_buf(),
+        config: config.security.estop.clone(),
+        config_dir: config_dir.to_path_buf(),
+        state: manager.status(),
+        stamp: file_stamp(manager.state_path()),
+    }));
+    Ok(())
+}
+
+/// Current estop state, re-read when the state file changed on disk.
+/// `None` when estop is disabled.
+pub fn current_state() -> Option<EstopState> {
+    let watched = runtime().watched.read();
+    let mut watched = watched.as_ref()?.lock();
+    refresh(&mut watched);
+    Some(watched.state.clone())
+}
+
+/// Register in-flight work. The guard is already stopped when the current
+/// estop state blocks it.
+pub fn register(scope: StopScope) -> TaskGuard {
+    let token = CancellationToken::new();
+    let Some(state) = current_state() else {
+        return TaskGuard { id: None, token };
+    };
+    if scope.stopped_by(&state) {
+        token.cancel();
+        return TaskGuard { id: None, token };
+    }
+
+    let id = runtime().next_id.fetch_add(1, Ordering::Relaxed);
+    runtime().entries.lock().insert(
+        id,
+        TaskEntry {
+            scope,
+            token: token.clone(),
+        },
+    );
+    TaskGuard {
+        id: Some(id),
+        token,
+    }
+}
+
+/// Cancel every registered entry that `state` stops, killing tracked process
+/// groups, and report what was stopped.
+pub fn propagate(state: &EstopState) -> StopReport {
+    let mut stopped = Vec::new();
+    {
+        let entries = runtime().entries.lock();
+        let mut matching: Vec<(&u64, &TaskEntry)> = entries
+            .iter()
+            .filter(|(_, entry)| !entry.token.is_cancelled() && entry.scope.stopped_by(state))
+            .collect();
+        matching.sort_by_key(|(id, _)| **id);
+        for (_, entry) in matching {
+            entry.token.cancel();
+            if let Some(pid) = entry.scope.pid {
+                kill_process_group(pid);
+            }
+            tracing::warn!(
+                kind = ?entry.scope.kind,
+                label = %entry.scope.label,
+                pid = ?entry.scope.pid,
+                "Emergency stop interrupted in-flight work"
+            );
+            stopped.push(StoppedTask {
+                kind: entry.scope.kind,
+                label: entry.scope.label.clone(),
+                pid: entry.scope.pid,
+            });
+        }
+    }
+
+    StopReport {
+        engaged_at: state.updated_at.clone(),
+        level: describe_state(state),
+        reporter_pid: std::process::id(),
+        stopped,
+    }
+}
+
+/// Re-read the estop state and propagate a new engagement. Returns the report
+/// when something was engaged since the last poll.
+pub fn poll_once() -> Option<StopReport> {
+    let (state, state_path) = {
+        let watched = runtime().watched.read();
+        let mut watched = watched.as_ref()?.lock();
+        let previous = watched.state.clone();
+        refresh(&mut watched);
+        if watched.state == previous || !watched.state.is_engaged() {
+            return None;
+        }
+        (watched.state.clone(), watched.state_path.clone())
+    };
+
+    let report = propagate(&state);
+    if let Err(error) = write_report(&state_path, &report) {
+        tracing::warn!("Failed to write estop stop report: {error}");
+    }
+    Some(report)
+}
+
+/// Start the background task that watches the estop state file. Safe to call
+/// from every long-running entry point; only the first call spawns.
+pub fn spawn_watcher() {
+    if runtime().watched.read().is_none() || runtime().watcher_started.swap(true, Ordering::SeqCst)
+    {
+        return;
+    }
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            poll_once();
+        }
+    });
+}
+
+/// Message for work refused or interrupted by the current estop level.
+pub fn stopped_message(what: &str) -> String {
+    let level = current_state()
+        .filter(EstopState::is_engaged)
+        .map_or_else(|| "engaged".to_string(), |state| describe_state(&state));
+    format!("{what} stopped by emergency stop ({level})")
+}
+
+pub fn describe_state(state: &EstopState) -> String {
+    let mut parts = Vec::new();
+    if state.kill_all {
+        parts.push("kill-all".to_string());
+    }
+    if state.network_kill {
+        parts.push("network-kill".to_string());
+    }
+    if !state.blocked_domains.is_empty() {
+        parts.push(format!(
+            "domain-block: {}",
+            state.blocked_domains.join(", ")
+        ));
+    }
+    if !state.frozen_tools.is_empty() {
+        parts.push(format!("tool-freeze: {}", state.frozen_tools.join(", ")));
+    }
+    if parts.is_empty() {
+        "disengaged".to_string()
+    } else {
+        parts.join("; ")
+    }
+}
+
+pub fn report_path(state_path: &Path) -> PathBuf {
+    state_path.with_extension("report.json")
+}
+
+/// Wait up to `timeout` for a running process to report on the engagement
+/// stamped `engaged_at`.
+pub async fn wait_for_report(
+    state_path: &Path,
+    engaged_at: Option<&str>,
+    timeout: Duration,
+) -> Option<StopReport> {
+    let path = report_path(state_path);
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(report) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<StopReport>(&raw).ok())
+            .filter(|report| report.engaged_at.as_deref() == engaged_at)
+        {
+            return Some(report);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+fn refresh(watched: &mut WatchedState) {
+    let stamp = file_stamp(&watched.state_path);
+    if stamp == watched.stamp {
+        return;
+    }
+    match EstopManager::load(&watched.config, &watched.config_dir) {
+        Ok(manager) => {
+            watched.state = manager.status();
+            watched.stamp = file_stamp(&watched.state_path);
+        }
+        Err(error) => {
+            tracing::warn!(
+                path = %watched.state_path.display(),
+                "Failed to reload estop state; keeping previous state: {error}"
+            );
+        }
+    }
+}
+
+fn write_report(state_path: &Path, report: &StopReport) -> Result<()> {
+    let path = report_path(state_path);
+    let body = serde_json::to_string_pretty(report).context("Failed to serialize stop report")?;
+    let temp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    fs::write(&temp_path, body)
+        .with_context(|| format!("Failed to write stop report {}", temp_path.display()))?;
+    fs::rename(&temp_path, &path)
+        .with_context(|| format!("Failed to replace stop report {}", path.display()))?;
+    Ok(())
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+/// Kill the process group led by `pid` (background processes are spawned as
+/// group leaders), falling back to the single process.
+fn kill_process_group(pid: u32) {
+    #[cfg(unix)]
+    {
+        let group = std::process::Command::new("kill")
+            .args(["-KILL", "--", &format!("-{pid}")])
+            .output();
+        if !group.is_ok_and(|output| output.status.success()) {
+            let _ = std::process::Command::new("kill")
+                .args(["-KILL", &pid.to_string()])
+                .output();
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .output();
+    }
+}
+
+/// Test helper: install a shared estop state file once and serialize the tests
+/// that engage it. Tests must only freeze tool names of their own.
+#[cfg(test)]
+pub(crate) async fn lock_for_test() -> (tokio::sync::MutexGuard<'static, ()>, PathBuf) {
+    static LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+    static STATE_PATH: OnceLock<PathBuf> = OnceLock::new();
+    let guard = LOCK.lock().await;
+    let state_path = STATE_PATH
+        .get_or_init(|| {
+            let dir = tempfile::tempdir().expect("tempdir").keep();
+            let state_path = dir.join("estop-state.json");
+            let config = crate::config::EstopConfig {
+                enabled: true,
+                state_file: state_path.display().to_string(),
+                require_otp_to_resume: false,
+            };
+            let manager = EstopManager::load(&config, &dir).expect("load estop state");
+            *runtime().watched.write() = Some(Mutex::new(WatchedState {
+                stamp: file_stamp(manager.state_path()),
+                state_path: manager.state_path().to_path_buf(),
+                config,
+                config_dir: dir,
+                state: manager.status(),
+            }));
+            state_path
+        })
+        .clone();
+    (guard, state_path)
+}
+
+/// Test helper: engage `level` on the shared state file from [`lock_for_test`].
+#[cfg(test)]
+pub(crate) fn engage_for_test(state_path: &Path, level: super::EstopLevel) -> EstopState {
+    let config = crate::config::EstopConfig {
+        enabled: true,
+        state_file: state_path.display().to_string(),
+        require_otp_to_resume: false,
+    };
+    let config_dir = state_path.parent().expect("state dir");
+    let mut manager = EstopManager::load(&config, config_dir).expect("load estop state");
+    manager.engage(level).expect("engage estop");
+    manager.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::EstopLevel;
+
+    fn freeze(tools: &[&str]) -> EstopState {
+        EstopState {
+            frozen_tools: tools.iter().map(|tool| (*tool).to_string()).collect(),
+            ..EstopState::default()
+        }
+    }
+
+    #[test]
+    fn scopes_match_engaged_levels() {
+        let http = StopScope::http_request("http_request", "api.example.com");
+        let cron = StopScope::cron_job("job-1");
+
+        let network = EstopState {
+            network_kill: true,
+            ..EstopState::default()
+        };
+        assert!(http.stopped_by(&network));
+        assert!(StopScope::tool_call("web_fetch").stopped_by(&network));
+        assert!(!cron.stopped_by(&network));
+
+        let domains = EstopState {
+            blocked_domains: vec!["*.example.com".into()],
+            ..EstopState::default()
+        };
+        assert!(http.stopped_by(&domains));
+        assert!(!StopScope::http_request("http_request", "other.org").stopped_by(&domains));
+
+        assert!(StopScope::sub_agent("s1", "researcher").stopped_by(&freeze(&["subagent_spawn"])));
+        assert!(!StopScope::tool_call("file_read").stopped_by(&freeze(&["shell"])));
+
+        let kill_all = EstopState {
+            kill_all: true,
+            ..EstopState::default()
+        };
+        assert!(cron.stopped_by(&kill_all));
+    }
+
+    #[tokio::test]
+    async fn engaging_tool_freeze_cancels_matching_work_and_writes_report() {
+        let (_lock, state_path) = lock_for_test().await;
+        let frozen = register(StopScope::tool_call("cxl_report_frozen"));
+        let other = register(StopScope::tool_call("cxl_report_other"));
+        let dropped = register(StopScope::tool_call("cxl_report_frozen"));
+        drop(dropped);
+
+        let state = engage_for_test(
+            &state_path,
+            EstopLevel::ToolFreeze(vec!["cxl_report_frozen".into()]),
+        );
+        let report = poll_once().expect("engagement should be propagated");
+
+        assert!(frozen.is_stopped());
+        assert!(!other.is_stopped());
+        assert_eq!(report.engaged_at, state.updated_at);
+        assert_eq!(
+            report.stopped,
+            vec![StoppedTask {
+                kind: TaskKind::ToolCall,
+                label: "cxl_report_frozen".into(),
+                pid: None,
+            }]
+        );
+
+        let written: StopReport =
+            serde_json::from_str(&fs::read_to_string(report_path(&state_path)).unwrap()).unwrap();
+        assert_eq!(written, report);
+        assert!(
+            poll_once().is_none(),
+            "unchanged state is not re-propagated"
+        );
+    }
+
+    #[tokio::test]
+    async fn register_refuses_work_while_level_is_engaged() {
+        let (_lock, state_path) = lock_for_test().await;
+        engage_for_test(
+            &state_path,
+            EstopLevel::ToolFreeze(vec!["cxl_refused".into()]),
+        );
+        poll_once();
+
+        assert!(register(StopScope::tool_call("cxl_refused")).is_stopped());
+        assert!(!register(StopScope::tool_call("cxl_allowed")).is_stopped());
+        assert!(stopped_message("Tool call").contains("tool-freeze"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn engaging_tool_freeze_kills_registered_process_group() {
+        let (_lock, state_path) = lock_for_test().await;
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", "sleep 30 & sleep 30"])
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .expect("spawn sleeper");
+        let pid = child.id().expect("child pid");
+        let guard = register(StopScope::process("cxl_process", "sleep 30", pid));
+
+        engage_for_test(
+            &state_path,
+            EstopLevel::ToolFreeze(vec!["cxl_process".into()]),
+        );
+        let report = poll_once().expect("engagement should be propagated");
+
+        let status = tokio::time::timeout(Duration::from_secs(5), child.wait())
+            .await
+            .expect("process group should be killed")
+            .expect("wait for child");
+        assert!(!status.success());
+        assert!(guard.is_stopped());
+        assert!(report
+            .stopped
+            .iter()
+            .any(|task| task.kind == TaskKind::Process && task.pid == Some(pid)));
+    }
+
+    #[tokio::test]
+    async fn wait_for_report_matches_engagement_stamp() {
+        let (_lock, state_path) = lock_for_test().await;
+        let state = engage_for_test(&state_path, EstopLevel::ToolFreeze(vec!["cxl_wait".into()]));
+        poll_once();
+
+        let report = wait_for_report(
+            &state_path,
+            state.updated_at.as_deref(),
+            Duration::from_millis(200),
+        )
+        .await;
+        assert!(report.is_some());
+        assert!(
+            wait_for_report(&state_path, Some("stale"), Duration::from_millis(150))
+                .await
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,868 @@
+//! Data-loss-prevention pipeline for outbound and persisted content.
+//!
+//! [`DlpEngine`] combines the built-in credential patterns from
+//! [`LeakDetector`], checksum-validated PII classes and custom regex, keyword
+//! and entropy detectors. Every detection resolves to a [`DlpAction`]; the
+//! most restrictive action wins for the content as a whole.
+//!
+//! The engine is installed process-wide from `[security.dlp]` at startup and
+//! again when the channel runtime reloads its config. [`apply`] is the single application point used for outbound
+//! channel messages, tool outputs fed back to the model, memory writes and
+//! gateway responses. Detections are reported to the registered observer as
+//! [`ObserverEvent::DlpDetection`] so they are exported as metrics.
+
+use crate::config::{DlpAction, DlpConfig, DlpDetectorKind, DlpPiiClass};
+use crate::observability::{Observer, ObserverEvent};
+use crate::security::leak_detector::{LeakDetector, LeakResult};
+use anyhow::{bail, Context, Result};
+use parking_lot::{Mutex, RwLock};
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long a message withheld by `require-approval` stays releasable.
+const HELD_MESSAGE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Maximum number of withheld messages kept for release.
+const HELD_MESSAGE_CAPACITY: usize = 64;
+
+/// Where content is leaving the trust boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DlpSurface {
+    ChannelMessage,
+    ToolOutput,
+    MemoryWrite,
+    GatewayResponse,
+}
+
+impl DlpSurface {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ChannelMessage => "channel_message",
+            Self::ToolOutput => "tool_output",
+            Self::MemoryWrite => "memory_write",
+            Self::GatewayResponse => "gateway_response",
+        }
+    }
+
+    /// Whether a human can release withheld content on this surface.
+    pub fn supports_approval(self) -> bool {
+        matches!(self, Self::ChannelMessage)
+    }
+}
+
+pub fn action_label(action: DlpAction) -> &'static str {
+    match action {
+        DlpAction::Mask => "mask",
+        DlpAction::Redact => "redact",
+        DlpAction::RequireApproval => "require-approval",
+        DlpAction::Block => "block",
+    }
+}
+
+/// A single detector match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlpDetection {
+    pub detector: String,
+    pub action: DlpAction,
+}
+
+/// Result of running content through the DLP pipeline.
+#[derive(Debug, Clone)]
+pub struct DlpOutcome {
+    /// Content with every match redacted or masked.
+    pub content: String,
+    /// Most restrictive action across all detections; `None` when clean.
+    pub action: Option<DlpAction>,
+    pub detections: Vec<DlpDetection>,
+}
+
+impl DlpOutcome {
+    fn clean(content: &str) -> Self {
+        Self {
+            content: content.to_string(),
+            action: None,
+            detections: Vec::new(),
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.detections.is_empty()
+    }
+
+    /// Whether the content must not be delivered as-is (block or
+    /// require-approval).
+    pub fn is_withheld(&self) -> bool {
+        matches!(
+            self.action,
+            Some(DlpAction::Block | DlpAction::RequireApproval)
+        )
+    }
+
+    /// Distinct detector names, in first-seen order.
+    pub fn detector_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for detection in &self.detections {
+            if !names.contains(&detection.detector) {
+                names.push(detection.detector.clone());
+            }
+        }
+        names
+    }
+}
+
+enum Matcher {
+    Regex(Regex),
+    Entropy { min_entropy: f64, min_length: usize },
+    Pii(DlpPiiClass, Regex),
+}
+
+struct Detector {
+    name: String,
+    placeholder: String,
+    matcher: Matcher,
+    action: DlpAction,
+}
+
+impl Detector {
+    fn find_spans(&self, content: &str) -> Vec<(usize, usize)> {
+        match &self.matcher {
+            Matcher::Regex(regex) => regex
+                .find_iter(content)
+                .filter(|m| !m.as_str().is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+            Matcher::Entropy {
+                min_entropy,
+                min_length,
+            } => token_spans(content)
+                .into_iter()
+                .filter(|&(start, end)| {
+                    let token = &content[start..end];
+                    token.len() >= *min_length && shannon_entropy(token.as_bytes()) >= *min_entropy
+                })
+                .collect(),
+            Matcher::Pii(class, regex) => regex
+                .find_iter(content)
+                .filter(|m| validate_pii(*class, content, m.start(), m.end()))
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        }
+    }
+}
+
+/// Compiled DLP detectors and action policy.
+#[allow(clippy::struct_excessive_bools)]
+pub struct DlpEngine {
+    detectors: Vec<Detector>,
+    credentials: Option<LeakDetector>,
+    default_action: DlpAction,
+    channel_actions: HashMap<String, DlpAction>,
+    mask_visible_chars: usize,
+    scan_channel_messages: bool,
+    scan_tool_outputs: bool,
+    scan_memory_writes: bool,
+    scan_gateway_responses: bool,
+}
+
+impl DlpEngine {
+    pub fn from_config(config: &DlpConfig) -> Result<Self> {
+        let mut detectors = Vec::new();
+
+        for class in &config.pii {
+            let name = pii_class_name(*class);
+            if detectors.iter().any(|d: &Detector| d.name == name) {
+                continue;
+            }
+            detectors.push(Detector {
+                name: name.to_string(),
+                placeholder: placeholder_for(name),
+                matcher: Matcher::Pii(*class, pii_regex(*class)),
+                action: config.default_action,
+            });
+        }
+        // Phone numbers are the loosest PII pattern; evaluate them last so
+        // card numbers and IBANs keep their more specific classification.
+        detectors.sort_by_key(|d| d.name == pii_class_name(DlpPiiClass::Phone));
+
+        for (idx, detector) in config.detectors.iter().enumerate() {
+            let name = detector.name.trim();
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                bail!("detectors[{idx}].name must be non-empty and use [A-Za-z0-9_-]");
+            }
+            if detectors.iter().any(|d| d.name == name) {
+                bail!("detectors[{idx}].name duplicates detector '{name}'");
+            }
+            let matcher = match detector.kind {
+                DlpDetectorKind::Regex => {
+                    let pattern = detector
+                        .pattern
+                        .as_deref()
+                        .filter(|p| !p.trim().is_empty())
+                        .with_context(|| format!("detectors[{idx}] ({name}) requires `pattern`"))?;
+                    Matcher::Regex(Regex::new(pattern).with_context(|| {
+                        format!("detectors[{idx}] ({name}) has an invalid pattern")
+                    })?)
+                }
+                DlpDetectorKind::Keywords => {
+                    let regex = keyword_regex(&detector.keywords, detector.case_sensitive)
+                        .with_context(|| {
+                            format!("detectors[{idx}] ({name}) requires non-empty `keywords`")
+                        })?;
+                    Matcher::Regex(regex)
+                }
+                DlpDetectorKind::Entropy => {
+                    if !(0.0..=8.0).contains(&detector.min_entropy) {
+                        bail!("detectors[{idx}] ({name}).min_entropy must be between 0.0 and 8.0");
+                    }
+                    if detector.min_length == 0 {
+                        bail!("detectors[{idx}] ({name}).min_length must be greater than 0");
+                    }
+                    Matcher::Entropy {
+                        min_entropy: detector.min_entropy,
+                        min_length: detector.min_length,
+                    }
+                }
+            };
+            detectors.push(Detector {
+                name: name.to_string(),
+                placeholder: placeholder_for(name),
+                matcher,
+                action: detector.action.unwrap_or(config.default_action),
+            });
+        }
+
+        if !(0.0..=1.0).contains(&config.sensitivity) {
+            bail!("sensitivity must be between 0.0 and 1.0");
+        }
+
+        Ok(Self {
+            detectors,
+            credentials: config
+                .credentials
+                .then(|| LeakDetector::with_sensitivity(config.sensitivity)),
+            default_action: config.default_action,
+            channel_actions: config
+                .channel_actions
+                .iter()
+                .map(|(channel, action)| (channel.trim().to_ascii_lowercase(), *action))
+                .collect(),
+            mask_visible_chars: config.mask_visible_chars,
+            scan_channel_messages: config.scan_channel_messages,
+            scan_tool_outputs: config.scan_tool_outputs,
+            scan_memory_writes: config.scan_memory_writes,
+            scan_gateway_responses: config.scan_gateway_responses,
+        })
+    }
+
+    pub fn scans(&self, surface: DlpSurface) -> bool {
+        match surface {
+            DlpSurface::ChannelMessage => self.scan_channel_messages,
+            DlpSurface::ToolOutput => self.scan_tool_outputs,
+            DlpSurface::MemoryWrite => self.scan_memory_writes,
+            DlpSurface::GatewayResponse => self.scan_gateway_responses,
+        }
+    }
+
+    /// Run `content` through every detector without reporting detections.
+    pub fn inspect(&self, surface: DlpSurface, channel: Option<&str>, content: &str) -> DlpOutcome {
+        if !self.scans(surface) || content.is_empty() {
+            return DlpOutcome::clean(content);
+        }
+
+        let channel_floor = channel.and_then(|name| {
+            self.channel_actions
+                .get(&name.trim().to_ascii_lowercase())
+                .copied()
+        });
+        let effective = |action: DlpAction| channel_floor.map_or(action, |floor| floor.max(action));
+
+        // Earlier detectors win overlapping spans.
+        let mut spans: Vec<(usize, usize, usize)> = Vec::new();
+        for (idx, detector) in self.detectors.iter().enumerate() {
+            for (start, end) in detector.find_spans(content) {
+                if spans.iter().all(|&(s, e, _)| end <= s || start >= e) {
+                    spans.push((start, end, idx));
+                }
+            }
+        }
+        spans.sort_by_key(|&(start, _, _)| start);
+
+        let mut detections = Vec::new();
+        let mut output = String::with_capacity(content.len());
+        let mut cursor = 0;
+        for (start, end, idx) in spans {
+            let detector = &self.detectors[idx];
+            let action = effective(detector.action);
+            output.push_str(&content[cursor..start]);
+            if action == DlpAction::Mask {
+                output.push_str(&mask_value(&content[start..end], self.mask_visible_chars));
+            } else {
+                output.push_str(&detector.placeholder);
+            }
+            cursor = end;
+            detections.push(DlpDetection {
+                detector: detector.name.clone(),
+                action,
+            });
+        }
+        output.push_str(&content[cursor..]);
+
+        if let Some(leak_detector) = &self.credentials {
+            if let LeakResult::Detected { patterns, redacted } = leak_detector.scan(&output) {
+                output = redacted;
+                let action = effective(self.default_action);
+                detections.extend(patterns.into_iter().map(|_| DlpDetection {
+                    detector: "credentials".to_string(),
+                    action,
+                }));
+            }
+        }
+
+        let action = detections.iter().map(|d| d.action).max();
+        DlpOutcome {
+            content: output,
+            action,
+            detections,
+        }
+    }
+}
+
+/// A channel message withheld by `require-approval`, awaiting release.
+#[derive(Debug, Clone)]
+pub struct HeldMessage {
+    pub id: String,
+    pub channel: String,
+    pub reply_target: String,
+    pub thread_ts: Option<String>,
+    pub content: String,
+    pub detectors: Vec<String>,
+    held_at: Instant,
+}
+
+struct DlpRuntime {
+    engine: RwLock<Option<Arc<DlpEngine>>>,
+    observer: RwLock<Option<Arc<dyn Observer>>>,
+    held: Mutex<VecDeque<HeldMessage>>,
+}
+
+fn runtime() -> &'static DlpRuntime {
+    static RUNTIME: OnceLock<DlpRuntime> = OnceLock::new();
+    RUNTIME.get_or_init(|| DlpRuntime {
+        engine: RwLock::new(None),
+        observer: RwLock::new(None),
+        held: Mutex::new(VecDeque::new()),
+    })
+}
+
+/// Install (or clear, when disabled) the process-wide DLP engine.
+pub fn install_runtime(config: &DlpConfig) -> Result<()> {
+    let engine = if config.enabled {
+        Some(Arc::new(
+            DlpEngine::from_config(config).context("Invalid security.dlp configuration")?,
+        ))
+    } else {
+        None
+    };
+    *runtime().engine.write() = engine;
+    Ok(())
+}
+
+/// Register the observer that receives [`ObserverEvent::DlpDetection`].
+pub fn set_runtime_observer(observer: Arc<dyn Observer>) {
+    *runtime().observer.write() = Some(observer);
+}
+
+/// Whether the installed engine scans `surface`.
+pub fn runtime_scans(surface: DlpSurface) -> bool {
+    runtime()
+        .engine
+        .read()
+        .as_ref()
+        .is_some_and(|engine| engine.scans(surface))
+}
+
+/// Apply the installed DLP policy to `content` and report any detections.
+pub fn apply(surface: DlpSurface, channel: Option<&str>, content: &str) -> DlpOutcome {
+    let Some(engine) = runtime().engine.read().clone() else {
+        return DlpOutcome::clean(content);
+    };
+    let outcome = engine.inspect(surface, channel, content);
+    if outcome.is_clean() {
+        return outcome;
+    }
+
+    tracing::warn!(
+        surface = surface.as_str(),
+        channel = channel.unwrap_or(""),
+        detectors = ?outcome.detector_names(),
+        action = outcome.action.map_or("none", action_label),
+        "dlp: sensitive content detected"
+    );
+    if let Some(observer) = runtime().observer.read().clone() {
+        for detection in &outcome.detections {
+            observer.record_event(&ObserverEvent::DlpDetection {
+                surface: surface.as_str().to_string(),
+                detector: detection.detector.clone(),
+                action: action_label(detection.action).to_string(),
+            });
+        }
+    }
+    outcome
+}
+
+/// Withhold a channel message until an approver releases it. Returns the
+/// release id.
+pub fn hold_for_release(
+    channel: &str,
+    reply_target: &str,
+    thread_ts: Option<String>,
+    content: String,
+    detectors: Vec<String>,
+) -> String {
+    let id = format!("dlp-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+    let mut held = runtime().held.lock();
+    held.retain(|message| message.held_at.elapsed() < HELD_MESSAGE_TTL);
+    while held.len() >= HELD_MESSAGE_CAPACITY {
+        held.pop_front();
+    }
+    held.push_back(HeldMessage {
+        id: id.clone(),
+        channel: channel.to_string(),
+        reply_target: reply_target.to_string(),
+        thread_ts,
+        content,
+        detectors,
+        held_at: Instant::now(),
+    });
+    id
+}
+
+/// Take a withheld message for delivery. Each message can be released once.
+pub fn release_held(id: &str) -> Option<HeldMessage> {
+    let mut held = runtime().held.lock();
+    held.retain(|message| message.held_at.elapsed() < HELD_MESSAGE_TTL);
+    let index = held.iter().position(|message| message.id == id.trim())?;
+    held.remove(index)
+}
+
+fn placeholder_for(name: &str) -> String {
+    format!("[REDACTED_{}]", name.to_ascii_uppercase().replace('-', "_"))
+}
+
+fn pii_class_name(class: DlpPiiClass) -> &'static str {
+    match class {
+        DlpPiiClass::Email => "email",
+        DlpPiiClass::Phone => "phone",
+        DlpPiiClass::Iban => "iban",
+        DlpPiiClass::CreditCard => "credit_card",
+        DlpPiiClass::UsSsn => "us_ssn",
+        DlpPiiClass::EsDni => "es_dni",
+    }
+}
+
+fn pii_regex(class: DlpPiiClass) -> Regex {
+    let pattern = match class {
+        DlpPiiClass::Email => r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b",
+        DlpPiiClass::Phone => {
+            r"\+\d{10,15}\b|(?:\+\d{1,3}[\s.-]?)?(?:\(\d{1,4}\)|\d{1,4})(?:[\s.-]\d{2,5}){2,4}\b"
+        }
+        DlpPiiClass::Iban => r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b",
+        DlpPiiClass::CreditCard => r"\b\d(?:[ -]?\d){12,18}\b",
+        DlpPiiClass::UsSsn => r"\b\d{3}-\d{2}-\d{4}\b",
+        DlpPiiClass::EsDni => r"\b[XYZ]?\d{7,8}-?[A-Z]\b",
+    };
+    Regex::new(pattern).expect("built-in DLP pattern must compile")
+}
+
+fn keyword_regex(keywords: &[String], case_sensitive: bool) -> Option<Regex> {
+    let alternatives: Vec<String> = keywords
+        .iter()
+        .map(|keyword| keyword.trim())
+        .filter(|keyword| !keyword.is_empty())
+        .map(|keyword| {
+            let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+            let prefix = if is_word(keyword.chars().next()) {
+                r"\b"
+            } else {
+                ""
+            };
+            let suffix = if is_word(keyword.chars().last()) {
+                r"\b"
+            } else {
+                ""
+            };
+            format!("{prefix}{}{suffix}", regex::escape(keyword))
+        })
+        .collect();
+    if alternatives.is_empty() {
+        return None;
+    }
+    let flags = if case_sensitive { "" } else { "(?i)" };
+    Regex::new(&format!("{flags}(?:{})", alternatives.join("|"))).ok()
+}
+
+fn digits(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn validate_pii(class: DlpPiiClass, content: &str, start: usize, end: usize) -> bool {
+    let value = &content[start..end];
+    match class {
+        DlpPiiClass::Email => true,
+        DlpPiiClass::Phone => {
+            // Reject matches glued to a longer digit run.
+            let glued = content[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_ascii_digit());
+            let count = digits(value).len();
+            !glued && (10..=15).contains(&count)
+        }
+        DlpPiiClass::Iban => iban_is_valid(value),
+        DlpPiiClass::CreditCard => {
+            let digits = digits(value);
+            (13..=19).contains(&digits.len())
+                && digits.iter().any(|d| *d != digits[0])
+                && luhn_is_valid(&digits)
+        }
+        DlpPiiClass::UsSsn => us_ssn_is_valid(value),
+        DlpPiiClass::EsDni => es_dni_is_valid(value),
+    }
+}
+
+fn luhn_is_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn iban_is_valid(value: &str) -> bool {
+    let compact: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let rearranged = compact[4..].chars().chain(compact[..4].chars());
+    let mut remainder: u32 = 0;
+    for c in rearranged {
+        let value = match c {
+            '0'..='9' => c.to_digit(10).unwrap_or(0),
+            'A'..='Z' => u32::from(c) - u32::from('A') + 10,
+            _ => return false,
+        };
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+fn us_ssn_is_valid(value: &str) -> bool {
+    let mut parts = value.split('-');
+    let (Some(area), Some(group), Some(serial)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let (Ok(area), Ok(group), Ok(serial)) = (
+        area.parse::<u32>(),
+        group.parse::<u32>(),
+        serial.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    area != 0 && area != 666 && area < 900 && group != 0 && serial != 0
+}
+
+fn es_dni_is_valid(value: &str) -> bool {
+    const CONTROL_LETTERS: &[u8; 23] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+    let compact: String = value.chars().filter(|c| *c != '-').collect();
+    let Some(letter) = compact.chars().last() else {
+        return false;
+    };
+    let body = &compact[..compact.len() - 1];
+    let numeric = match body.chars().next() {
+        Some('X') => format!("0{}", &body[1..]),
+        Some('Y') => format!("1{}", &body[1..]),
+        Some('Z') => format!("2{}", &body[1..]),
+        _ => body.to_string(),
+    };
+    if numeric.len() != 8 {
+        return false;
+    }
+    let Ok(number) = numeric.parse::<usize>() else {
+        return false;
+    };
+    char::from(CONTROL_LETTERS[number % 23]) == letter
+}
+
+fn mask_value(value: &str, visible: usize) -> String {
+    let total = value.chars().filter(|c| c.is_alphanumeric()).count();
+    let mut remaining_hidden = total.saturating_sub(visible);
+    value
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() && remaining_hidden > 0 {
+                remaining_hidden -= 1;
+                '*'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn token_spans(content: &str) -> Vec<(usize, usize)> {
+    let is_token_char =
+        |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '/' | '=');
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (idx, c) in content.char_indices() {
+        match (is_token_char(c), start) {
+            (true, None) => start = Some(idx),
+            (false, Some(s)) => {
+                spans.push((s, idx));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, content.len()));
+    }
+    spans
+}
+
+fn shannon_entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0_u32; 256];
+    for &b in bytes {
+        counts[b as usize] += 1;
+    }
+    let len = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = f64::from(count) / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DlpDetectorConfig;
+
+    fn config_with_pii(pii: Vec<DlpPiiClass>) -> DlpConfig {
+        DlpConfig {
+            enabled: true,
+            credentials: false,
+            pii,
+            ..DlpConfig::default()
+        }
+    }
+
+    fn detector(name: &str, kind: DlpDetectorKind) -> DlpDetectorConfig {
+        DlpDetectorConfig {
+            name: name.to_string(),
+            kind,
+            pattern: None,
+            keywords: Vec::new(),
+            case_sensitive: false,
+            min_entropy: 4.0,
+            min_length: 20,
+            action: None,
+        }
+    }
+
+    #[test]
+    fn checksum_validated_pii_is_redacted() {
+        let engine = DlpEngine::from_config(&config_with_pii(vec![
+            DlpPiiClass::Iban,
+            DlpPiiClass::CreditCard,
+            DlpPiiClass::UsSsn,
+            DlpPiiClass::EsDni,
+        ]))
+        .unwrap();
+
+        let outcome = engine.inspect(
+            DlpSurface::ChannelMessage,
+            None,
+            "IBAN DE89 3704 0044 0532 0130 00, card 4111 1111 1111 1111, ssn 123-45-6789, dni 12345678Z",
+        );
+        assert_eq!(
+            outcome.content,
+            "IBAN [REDACTED_IBAN], card [REDACTED_CREDIT_CARD], ssn [REDACTED_US_SSN], dni [REDACTED_ES_DNI]"
+        );
+        assert_eq!(outcome.action, Some(DlpAction::Redact));
+        assert_eq!(outcome.detections.len(), 4);
+    }
+
+    #[test]
+    fn invalid_checksums_are_not_detected() {
+        let engine = DlpEngine::from_config(&config_with_pii(vec![
+            DlpPiiClass::Iban,
+            DlpPiiClass::CreditCard,
+            DlpPiiClass::UsSsn,
+            DlpPiiClass::EsDni,
+        ]))
+        .unwrap();
+
+        let content =
+            "IBAN DE89 3704 0044 0532 0130 02, card 4111 1111 1111 1112, ssn 666-45-6789, dni 12345678A";
+        let outcome = engine.inspect(DlpSurface::ChannelMessage, None, content);
+        assert!(outcome.is_clean());
+        assert_eq!(outcome.content, content);
+    }
+
+    #[test]
+    fn email_and_phone_detection() {
+        let engine = DlpEngine::from_config(&config_with_pii(vec![
+            DlpPiiClass::Phone,
+            DlpPiiClass::Email,
+        ]))
+        .unwrap();
+        let outcome = engine.inspect(
+            DlpSurface::ToolOutput,
+            None,
+            "Reach alice@example.com or +1 555-123-4567; build 2024.10.18 passed",
+        );
+        assert_eq!(
+            outcome.content,
+            "Reach [REDACTED_EMAIL] or [REDACTED_PHONE]; build 2024.10.18 passed"
+        );
+    }
+
+    #[test]
+    fn mask_keeps_trailing_characters() {
+        let mut config = config_with_pii(vec![DlpPiiClass::CreditCard]);
+        config.default_action = DlpAction::Mask;
+        let engine = DlpEngine::from_config(&config).unwrap();
+        let outcome = engine.inspect(DlpSurface::ChannelMessage, None, "card 4111-1111-1111-1111");
+        assert_eq!(outcome.content, "card ****-****-****-1111");
+        assert_eq!(outcome.action, Some(DlpAction::Mask));
+    }
+
+    #[test]
+    fn custom_regex_keyword_and_entropy_detectors() {
+        let mut config = config_with_pii(Vec::new());
+        let mut ticket = detector("ticket", DlpDetectorKind::Regex);
+        ticket.pattern = Some(r"INC-\d{6}".to_string());
+        let mut codename = detector("codename", DlpDetectorKind::Keywords);
+        codename.keywords = vec!["Project Nightjar".to_string()];
+        codename.action = Some(DlpAction::Block);
+        let mut entropy = detector("blob", DlpDetectorKind::Entropy);
+        entropy.min_length = 24;
+        config.detectors = vec![ticket, codename, entropy];
+        let engine = DlpEngine::from_config(&config).unwrap();
+
+        let outcome = engine.inspect(
+            DlpSurface::MemoryWrite,
+            None,
+            "see INC-123456 about project nightjar token q8Zr2LmX0vT4nB7yK1wP9sD3",
+        );
+        assert_eq!(
+            outcome.content,
+            "see [REDACTED_TICKET] about [REDACTED_CODENAME] token [REDACTED_BLOB]"
+        );
+        assert_eq!(outcome.action, Some(DlpAction::Block));
+        assert!(outcome.is_withheld());
+        assert_eq!(outcome.detector_names(), vec!["ticket", "codename", "blob"]);
+    }
+
+    #[test]
+    fn channel_actions_only_tighten() {
+        let mut config = config_with_pii(vec![DlpPiiClass::Email]);
+        config
+            .channel_actions
+            .insert("Discord".to_string(), DlpAction::RequireApproval);
+        config
+            .channel_actions
+            .insert("telegram".to_string(), DlpAction::Mask);
+        let engine = DlpEngine::from_config(&config).unwrap();
+
+        let discord = engine.inspect(DlpSurface::ChannelMessage, Some("discord"), "a@b.io");
+        assert_eq!(discord.action, Some(DlpAction::RequireApproval));
+        let telegram = engine.inspect(DlpSurface::ChannelMessage, Some("telegram"), "a@b.io");
+        assert_eq!(telegram.action, Some(DlpAction::Redact));
+    }
+
+    #[test]
+    fn builtin_credentials_are_included() {
+        let engine = DlpEngine::from_config(&DlpConfig {
+            enabled: true,
+            ..DlpConfig::default()
+        })
+        .unwrap();
+        let outcome = engine.inspect(
+            DlpSurface::GatewayResponse,
+            Some("gateway"),
+            "key sk-ant-REDACTED",
+        );
+        assert!(!outcome.content.contains("sk-ant-abcdefghijklmnop"));
+        assert_eq!(outcome.detector_names(), vec!["credentials"]);
+    }
+
+    #[test]
+    fn disabled_surfaces_pass_through() {
+        let mut config = config_with_pii(vec![DlpPiiClass::Email]);
+        config.scan_memory_writes = false;
+        let engine = DlpEngine::from_config(&config).unwrap();
+        assert!(engine
+            .inspect(DlpSurface::MemoryWrite, None, "a@b.io")
+            .is_clean());
+    }
+
+    #[test]
+    fn invalid_detectors_are_rejected() {
+        let mut config = config_with_pii(Vec::new());
+        config.detectors = vec![detector("broken", DlpDetectorKind::Regex)];
+        assert!(DlpEngine::from_config(&config).is_err());
+
+        let mut bad_regex = detector("broken", DlpDetectorKind::Regex);
+        bad_regex.pattern = Some("(".to_string());
+        config.detectors = vec![bad_regex];
+        assert!(DlpEngine::from_config(&config).is_err());
+
+        config.detectors = vec![detector("empty", DlpDetectorKind::Keywords)];
+        assert!(DlpEngine::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn held_messages_release_once() {
+        let id = hold_for_release(
+            "slack",
+            "C01",
+            None,
+            "secret plan".to_string(),
+            vec!["codename".to_string()],
+        );
+        let released = release_held(&id).unwrap();
+        assert_eq!(released.content, "secret plan");
+        assert_eq!(released.reply_target, "C01");
+        assert!(release_held(&id).is_none());
+    }
+}
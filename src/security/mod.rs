@@ -3,8 +3,14 @@
 //! This module provides the security infrastructure for ZeroClaw. The core type
 //! [`SecurityPolicy`] defines autonomy levels, workspace boundaries, and
 //! access-control rules that are enforced across the tool and runtime subsystems.
-//! [`PairingGuard`] implements device pairing for channel authentication, and
-//! [`SecretStore`] handles encrypted credential storage.
+//! [`PairingGuard`] implements device pairing for channel authentication,
+//! [`SecretStore`] handles encrypted credential storage, and
+//! [`SecretResolver`] resolves `secret://`/`env://`/`file://`/`exec://`
+//! references in config values through pluggable [`SecretBackend`]s.
+//! [`UserDirectory`] links channel identities to users and their roles.
+//! [`DlpEngine`] scans outbound and persisted content for credentials, PII and
+//! custom patterns before it leaves the process. The [`cancellation`] registry
+//! propagates an engaged emergency stop to in-flight tools and sub-agents.
 //!
 //! OS-level isolation is provided through the [`Sandbox`] trait defined in
 //! [`traits`], with pluggable backends including Docker, Firejail, Bubblewrap,
@@ -21,7 +27,9 @@
 pub mod audit;
 #[cfg(feature = "sandbox-bubblewrap")]
 pub mod bubblewrap;
+pub mod cancellation;
 pub mod detect;
+pub mod dlp;
 pub mod docker;
 pub mod file_link_guard;
 
@@ -39,15 +47,19 @@ pub mod perplexity;
 pub mod policy;
 pub mod prompt_guard;
 pub mod roles;
+pub mod secret_backends;
 pub mod secrets;
 pub mod sensitive_paths;
 pub mod syscall_anomaly;
 pub mod traits;
+pub mod users;
 
 #[allow(unused_imports)]
 pub use audit::{AuditEvent, AuditEventType, AuditLogger};
 #[allow(unused_imports)]
 pub use detect::create_sandbox;
+#[allow(unused_imports)]
+pub use dlp::{DlpEngine, DlpOutcome, DlpSurface};
 pub use domain_matcher::DomainMatcher;
 #[allow(unused_imports)]
 pub use estop::{EstopLevel, EstopManager, EstopState, ResumeSelector};
@@ -57,15 +69,19 @@ pub use otp::OtpValidator;
 pub use pairing::PairingGuard;
 #[allow(unused_imports)]
 pub use perplexity::{detect_adversarial_suffix, PerplexityAssessment};
-pub use policy::{AutonomyLevel, SecurityPolicy};
+pub use policy::{AutonomyLevel, CommandRiskLevel, SecurityPolicy};
 #[allow(unused_imports)]
 pub use roles::{RoleRegistry, ToolAccess};
 #[allow(unused_imports)]
+pub use secret_backends::{SecretBackend, SecretReference, SecretReferenceBinding, SecretResolver};
+#[allow(unused_imports)]
 pub use secrets::SecretStore;
 #[allow(unused_imports)]
 pub use syscall_anomaly::{SyscallAnomalyAlert, SyscallAnomalyDetector, SyscallAnomalyKind};
 #[allow(unused_imports)]
 pub use traits::{NoopSandbox, Sandbox};
+#[allow(unused_imports)]
+pub use users::{UserDirectory, UserRecord};
 // Prompt injection defense exports
 #[allow(unused_imports)]
 pub use leak_detector::{LeakDetector, LeakResult};
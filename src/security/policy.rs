@@ -33,7 +33,10 @@ impl std::str::FromStr for AutonomyLevel {
 }
 
 /// Risk score for shell command execution.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum CommandRiskLevel {
     Low,
     Medium,
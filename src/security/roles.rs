@@ -125,6 +125,12 @@ impl RoleRegistry {
         Ok(registry)
     }
 
+    #[must_use]
+    pub fn has_role(&self, role_name: &str) -> bool {
+        self.roles
+            .contains_key(&role_name.trim().to_ascii_lowercase())
+    }
+
     #[must_use]
     pub fn resolve_tool_access(
         &self,
@@ -0,0 +1,594 @@
+//! Secret references and pluggable secret backends for config values.
+//!
+//! Config fields that hold credentials may contain a reference instead of the
+//! value itself. References are resolved once at config load time:
+//!
+//! - `secret://name` — entry in the local encrypted vault (`vault.json`)
+//! - `env://VAR` — process environment variable
+//! - `file:///run/secrets/x` — file contents (trailing newline stripped)
+//! - `exec://pass show zeroclaw/openrouter` — stdout of an external command
+//!   (disabled unless `secrets.allow_exec = true`)
+//!
+//! Resolved plaintext never leaves process memory: when the config is saved,
+//! fields still holding a resolved value are written back as the original
+//! reference (see [`SecretReferenceBinding`]).
+
+use crate::config::SecretsConfig;
+use crate::security::secrets::SecretStore;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const VAULT_FILE: &str = "vault.json";
+const EXEC_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Maximum size of a secret read from a file or command output.
+const MAX_SECRET_BYTES: usize = 64 * 1024;
+
+/// A parsed secret reference (`<scheme>://<key>`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReference {
+    pub scheme: String,
+    pub key: String,
+}
+
+impl SecretReference {
+    /// Parse a config value as a secret reference.
+    ///
+    /// Returns `None` for plain values, encrypted `enc2:` values and URLs with
+    /// unrelated schemes (e.g. `https://`), so ordinary config strings are
+    /// never mistaken for references.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (scheme, key) = value.trim().split_once("://")?;
+        if !SUPPORTED_SCHEMES.contains(&scheme) {
+            return None;
+        }
+        let key = key.trim();
+        if key.is_empty() {
+            return None;
+        }
+        Some(Self {
+            scheme: scheme.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    /// Check whether a config value is a secret reference.
+    pub fn is_reference(value: &str) -> bool {
+        Self::parse(value).is_some()
+    }
+}
+
+impl std::fmt::Display for SecretReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.key)
+    }
+}
+
+const SUPPORTED_SCHEMES: &[&str] = &["secret", "env", "file", "exec"];
+
+/// Records which config field was populated from which reference so that
+/// saving the config writes the reference back instead of the secret.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretReferenceBinding {
+    pub field: String,
+    pub reference: String,
+    pub resolved: String,
+}
+
+impl std::fmt::Debug for SecretReferenceBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretReferenceBinding")
+            .field("field", &self.field)
+            .field("reference", &self.reference)
+            .field("resolved", &"***")
+            .finish()
+    }
+}
+
+/// A source of secret values addressed by a reference scheme.
+pub trait SecretBackend: Send + Sync {
+    /// Reference scheme served by this backend (e.g. `"secret"` for `secret://`).
+    fn scheme(&self) -> &'static str;
+
+    /// Fetch the secret addressed by `key`.
+    fn get(&self, key: &str) -> Result<String>;
+
+    /// Store a secret. Read-only backends return an error.
+    fn set(&self, key: &str, _value: &str) -> Result<()> {
+        bail!(
+            "{}:// backend is read-only; cannot store '{key}'",
+            self.scheme()
+        )
+    }
+
+    /// List the names of stored secrets. Backends that cannot enumerate
+    /// their contents return an empty list.
+    fn list(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+// ── Local encrypted vault ───────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultEntry {
+    /// `enc2:` ciphertext produced by [`SecretStore`].
+    pub value: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub rotations: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultFile {
+    #[serde(default)]
+    secrets: BTreeMap<String, VaultEntry>,
+}
+
+/// Named secrets stored in `<config_dir>/vault.json`, always encrypted with
+/// the ChaCha20-Poly1305 key in `<config_dir>/.secret_key`.
+#[derive(Debug, Clone)]
+pub struct LocalVaultBackend {
+    path: PathBuf,
+    store: SecretStore,
+}
+
+impl LocalVaultBackend {
+    pub fn new(zeroclaw_dir: &Path) -> Self {
+        Self {
+            path: zeroclaw_dir.join(VAULT_FILE),
+            // Vault entries are encrypted even when `secrets.encrypt = false`;
+            // the whole point of the vault is to keep plaintext off disk.
+            store: SecretStore::new(zeroclaw_dir, true),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Metadata for a stored secret, without decrypting it.
+    pub fn entry(&self, name: &str) -> Result<Option<VaultEntry>> {
+        Ok(self.load()?.secrets.get(name).cloned())
+    }
+
+    /// Replace the value of an existing secret. Fails if the secret does not exist.
+    pub fn rotate(&self, name: &str, value: &str) -> Result<VaultEntry> {
+        validate_vault_name(name)?;
+        let mut vault = self.load()?;
+        let Some(entry) = vault.secrets.get_mut(name) else {
+            bail!("Secret '{name}' does not exist; use `zeroclaw secrets set` to create it");
+        };
+        entry.value = self.store.encrypt(value)?;
+        entry.updated_at = chrono::Utc::now().to_rfc3339();
+        entry.rotations = entry.rotations.saturating_add(1);
+        let rotated = entry.clone();
+        self.save(&vault)?;
+        Ok(rotated)
+    }
+
+    fn load(&self) -> Result<VaultFile> {
+        if !self.path.exists() {
+            return Ok(VaultFile::default());
+        }
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read secret vault {}", self.path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse secret vault {}", self.path.display()))
+    }
+
+    fn save(&self, vault: &VaultFile) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let body = serde_json::to_vec_pretty(vault)?;
+        let tmp = self
+            .path
+            .with_file_name(format!(".{VAULT_FILE}.tmp-{}", uuid::Uuid::new_v4()));
+        {
+            let mut file = fs::OpenOptions::new()
+                .create_new(true)
+                .write(true)
+                .open(&tmp)
+                .with_context(|| format!("Failed to create {}", tmp.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(fs::Permissions::from_mode(0o600))
+                    .context("Failed to set secret vault permissions")?;
+            }
+            file.write_all(&body)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace secret vault {}", self.path.display()))
+    }
+}
+
+impl SecretBackend for LocalVaultBackend {
+    fn scheme(&self) -> &'static str {
+        "secret"
+    }
+
+    fn get(&self, key: &str) -> Result<String> {
+        let vault = self.load()?;
+        let entry = vault
+            .secrets
+            .get(key)
+            .with_context(|| format!("Secret '{key}' not found in {}", self.path.display()))?;
+        self.store
+            .decrypt(&entry.value)
+            .with_context(|| format!("Failed to decrypt secret '{key}'"))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        validate_vault_name(key)?;
+        if value.is_empty() {
+            bail!("Refusing to store an empty value for secret '{key}'");
+        }
+        let mut vault = self.load()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let encrypted = self.store.encrypt(value)?;
+        vault
+            .secrets
+            .entry(key.to_string())
+            .and_modify(|entry| {
+                entry.value = encrypted.clone();
+                entry.updated_at = now.clone();
+            })
+            .or_insert_with(|| VaultEntry {
+                value: encrypted.clone(),
+                created_at: now.clone(),
+                updated_at: now.clone(),
+                rotations: 0,
+            });
+        self.save(&vault)
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        Ok(self.load()?.secrets.into_keys().collect())
+    }
+}
+
+fn validate_vault_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+    if !valid {
+        bail!("Invalid secret name '{name}': use 1-128 chars of [A-Za-z0-9._/-]");
+    }
+    Ok(())
+}
+
+// ── Environment variables ───────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+pub struct EnvBackend;
+
+impl SecretBackend for EnvBackend {
+    fn scheme(&self) -> &'static str {
+        "env"
+    }
+
+    fn get(&self, key: &str) -> Result<String> {
+        let value =
+            std::env::var(key).with_context(|| format!("Environment variable {key} is not set"))?;
+        if value.is_empty() {
+            bail!("Environment variable {key} is empty");
+        }
+        Ok(value)
+    }
+}
+
+// ── Mounted secret files ────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+pub struct FileBackend;
+
+impl SecretBackend for FileBackend {
+    fn scheme(&self) -> &'static str {
+        "file"
+    }
+
+    fn get(&self, key: &str) -> Result<String> {
+        let path = shellexpand::tilde(key).into_owned();
+        let file =
+            fs::File::open(&path).with_context(|| format!("Failed to open secret file {path}"))?;
+        let mut raw = String::new();
+        file.take(MAX_SECRET_BYTES as u64 + 1)
+            .read_to_string(&mut raw)
+            .with_context(|| format!("Failed to read secret file {path}"))?;
+        if raw.len() > MAX_SECRET_BYTES {
+            bail!("Secret file {path} exceeds {MAX_SECRET_BYTES} bytes");
+        }
+        let value = raw.trim_end_matches(['\n', '\r']);
+        if value.is_empty() {
+            bail!("Secret file {path} is empty");
+        }
+        Ok(value.to_string())
+    }
+}
+
+// ── External command (pass, op, vault, ...) ─────────────────────
+
+#[derive(Debug, Clone)]
+pub struct ExecBackend {
+    timeout: Duration,
+}
+
+impl ExecBackend {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl SecretBackend for ExecBackend {
+    fn scheme(&self) -> &'static str {
+        "exec"
+    }
+
+    fn get(&self, key: &str) -> Result<String> {
+        let mut child = shell_command(key)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run secret command `{key}`"))?;
+
+        // Drain stdout while waiting so a chatty command cannot fill the pipe
+        // and block until the timeout.
+        let reader = child.stdout.take().map(|stdout| {
+            std::thread::spawn(move || -> std::io::Result<Vec<u8>> {
+                let mut stdout = stdout;
+                let mut raw = Vec::new();
+                (&mut stdout)
+                    .take(MAX_SECRET_BYTES as u64 + 1)
+                    .read_to_end(&mut raw)?;
+                std::io::copy(&mut stdout, &mut std::io::sink())?;
+                Ok(raw)
+            })
+        });
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "Secret command `{key}` timed out after {}s",
+                    self.timeout.as_secs()
+                );
+            }
+            std::thread::sleep(EXEC_POLL_INTERVAL);
+        };
+        if !status.success() {
+            bail!("Secret command `{key}` exited with {status}");
+        }
+
+        let raw = match reader {
+            Some(reader) => reader
+                .join()
+                .map_err(|_| anyhow::anyhow!("Secret command `{key}` output reader panicked"))??,
+            None => Vec::new(),
+        };
+
+        if raw.len() > MAX_SECRET_BYTES {
+            bail!("Secret command `{key}` produced more than {MAX_SECRET_BYTES} bytes");
+        }
+        let output = String::from_utf8(raw)
+            .with_context(|| format!("Secret command `{key}` produced non-UTF-8 output"))?;
+        // `pass show` and friends print the secret on the first line.
+        let value = output.lines().next().unwrap_or_default().trim_end();
+        if value.is_empty() {
+            bail!("Secret command `{key}` produced no output");
+        }
+        Ok(value.to_string())
+    }
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+// ── Resolver ────────────────────────────────────────────────────
+
+/// Dispatches secret references to the backend registered for their scheme.
+pub struct SecretResolver {
+    backends: HashMap<&'static str, Box<dyn SecretBackend>>,
+}
+
+impl SecretResolver {
+    /// Empty resolver; register backends with [`SecretResolver::register`].
+    pub fn empty() -> Self {
+        Self {
+            backends: HashMap::new(),
+        }
+    }
+
+    /// Resolver with the built-in backends enabled by `[secrets]`.
+    pub fn from_config(config: &SecretsConfig, zeroclaw_dir: &Path) -> Self {
+        let mut resolver = Self::empty();
+        resolver.register(Box::new(LocalVaultBackend::new(zeroclaw_dir)));
+        resolver.register(Box::new(EnvBackend));
+        resolver.register(Box::new(FileBackend));
+        if config.allow_exec {
+            resolver.register(Box::new(ExecBackend::new(Duration::from_secs(
+                config.exec_timeout_secs.max(1),
+            ))));
+        }
+        resolver
+    }
+
+    /// Register (or replace) the backend for its scheme.
+    pub fn register(&mut self, backend: Box<dyn SecretBackend>) {
+        self.backends.insert(backend.scheme(), backend);
+    }
+
+    /// Resolve `value` if it is a secret reference.
+    ///
+    /// Returns `Ok(None)` for plain values so callers can fall through to
+    /// their usual handling.
+    pub fn resolve(&self, value: &str) -> Result<Option<String>> {
+        let Some(reference) = SecretReference::parse(value) else {
+            return Ok(None);
+        };
+        let Some(backend) = self.backends.get(reference.scheme.as_str()) else {
+            if reference.scheme == "exec" {
+                bail!(
+                    "exec:// secret references are disabled; set [secrets].allow_exec = true to enable them"
+                );
+            }
+            bail!("No secret backend registered for {}://", reference.scheme);
+        };
+        backend
+            .get(&reference.key)
+            .with_context(|| format!("Failed to resolve secret reference {reference}"))
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(allow_exec: bool) -> SecretsConfig {
+        SecretsConfig {
+            allow_exec,
+            ..SecretsConfig::default()
+        }
+    }
+
+    #[test]
+    fn parse_recognizes_supported_schemes_only() {
+        let parsed = SecretReference::parse("secret://openrouter").unwrap();
+        assert_eq!(parsed.scheme, "secret");
+        assert_eq!(parsed.key, "openrouter");
+
+        let file = SecretReference::parse("file:///run/secrets/api_key").unwrap();
+        assert_eq!(file.key, "/run/secrets/api_key");
+
+        assert!(SecretReference::is_reference("env://OPENAI_API_KEY"));
+        assert!(SecretReference::is_reference("exec://pass show zc/key"));
+        assert!(!SecretReference::is_reference("https://proxy.example.com"));
+        assert!(!SecretReference::is_reference("sk-plain-value"));
+        assert!(!SecretReference::is_reference("enc2:abcdef"));
+        assert!(!SecretReference::is_reference("secret://"));
+    }
+
+    #[test]
+    fn vault_set_get_list_rotate_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let vault = LocalVaultBackend::new(tmp.path());
+
+        vault.set("openrouter", "sk-or-123").unwrap();
+        vault.set("slack/bot", "xoxb-456").unwrap();
+        assert_eq!(vault.get("openrouter").unwrap(), "sk-or-123");
+        assert_eq!(vault.list().unwrap(), vec!["openrouter", "slack/bot"]);
+
+        let rotated = vault.rotate("openrouter", "sk-or-789").unwrap();
+        assert_eq!(rotated.rotations, 1);
+        assert_eq!(vault.get("openrouter").unwrap(), "sk-or-789");
+
+        let raw = fs::read_to_string(vault.path()).unwrap();
+        assert!(!raw.contains("sk-or-789"), "vault must not store plaintext");
+        assert!(vault.rotate("missing", "x").is_err());
+    }
+
+    #[test]
+    fn vault_rejects_invalid_names() {
+        let tmp = TempDir::new().unwrap();
+        let vault = LocalVaultBackend::new(tmp.path());
+        assert!(vault.set("", "x").is_err());
+        assert!(vault.set("has space", "x").is_err());
+    }
+
+    #[test]
+    fn file_backend_strips_trailing_newline() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("api_key");
+        fs::write(&path, "sk-from-file\n").unwrap();
+
+        let resolver = SecretResolver::from_config(&config(false), tmp.path());
+        let resolved = resolver
+            .resolve(&format!("file://{}", path.display()))
+            .unwrap();
+        assert_eq!(resolved.as_deref(), Some("sk-from-file"));
+    }
+
+    #[test]
+    fn plain_values_are_not_resolved() {
+        let tmp = TempDir::new().unwrap();
+        let resolver = SecretResolver::from_config(&config(false), tmp.path());
+        assert_eq!(resolver.resolve("sk-plain").unwrap(), None);
+    }
+
+    #[test]
+    fn missing_env_var_is_an_error() {
+        let tmp = TempDir::new().unwrap();
+        let resolver = SecretResolver::from_config(&config(false), tmp.path());
+        assert!(resolver
+            .resolve("env://ZEROCLAW_TEST_SECRET_THAT_DOES_NOT_EXIST")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_references_require_opt_in() {
+        let tmp = TempDir::new().unwrap();
+        let resolver = SecretResolver::from_config(&config(false), tmp.path());
+        let err = resolver.resolve("exec://echo hi").unwrap_err();
+        assert!(err.to_string().contains("allow_exec"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_backend_returns_first_line_of_stdout() {
+        let tmp = TempDir::new().unwrap();
+        let resolver = SecretResolver::from_config(&config(true), tmp.path());
+        let resolved = resolver
+            .resolve("exec://printf 'sk-exec\\nmetadata: ignored\\n'")
+            .unwrap();
+        assert_eq!(resolved.as_deref(), Some("sk-exec"));
+        assert!(resolver.resolve("exec://exit 3").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_backend_times_out() {
+        let backend = ExecBackend::new(Duration::from_millis(100));
+        let err = backend.get("sleep 5").unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_backend_rejects_large_output_without_waiting_for_timeout() {
+        let backend = ExecBackend::new(Duration::from_secs(5));
+        let started = Instant::now();
+        let err = backend.get("head -c 200000 /dev/zero").unwrap_err();
+        assert!(err.to_string().contains("more than"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,649 @@
+//! User directory that maps channel identities to people and roles.
+//!
+//! A user owns one or more linked identities of the form `channel:sender`
+//! (for example `telegram:123456789`, `slack:U01ABCDEF`,
+//! `email:alice@example.com`, `matrix:@alice:example.org`) and exactly one
+//! role from [`RoleRegistry`]. The directory is persisted as JSON under the
+//! config directory and re-read whenever the file changes on disk, so edits
+//! made with `zeroclaw users` apply to a running daemon without a restart.
+
+use crate::config::SecurityConfig;
+use crate::security::roles::{RoleRegistry, ToolAccess};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Prefix for memory session ids scoped to a directory user.
+const USER_MEMORY_SESSION_PREFIX: &str = "user:";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserRecord {
+    pub id: String,
+    #[serde(default)]
+    pub display_name: String,
+    pub role: String,
+    #[serde(default)]
+    pub identities: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LinkCodeRecord {
+    code_sha256: String,
+    user_id: String,
+    expires_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UserDirectoryState {
+    #[serde(default)]
+    users: Vec<UserRecord>,
+    #[serde(default)]
+    link_codes: Vec<LinkCodeRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+#[derive(Debug)]
+struct DirectoryInner {
+    state: UserDirectoryState,
+    stamp: Option<FileStamp>,
+}
+
+#[derive(Debug)]
+pub struct UserDirectory {
+    state_path: PathBuf,
+    roles: RoleRegistry,
+    gated_actions: Vec<String>,
+    unknown_sender_role: Option<String>,
+    scope_memory: bool,
+    link_code_ttl: Duration,
+    inner: Mutex<DirectoryInner>,
+}
+
+impl UserDirectory {
+    pub fn load(security: &SecurityConfig, config_dir: &Path) -> Result<Self> {
+        let config = &security.users;
+        let state_path =
+            crate::security::estop::resolve_state_file_path(config_dir, &config.state_file);
+        let roles = RoleRegistry::from_config(&security.roles)?;
+
+        let unknown_sender_role = config
+            .unknown_sender_role
+            .as_deref()
+            .map(str::trim)
+            .filter(|role| !role.is_empty())
+            .map(str::to_ascii_lowercase);
+        if let Some(role) = unknown_sender_role.as_deref() {
+            if !roles.has_role(role) {
+                bail!("security.users.unknown_sender_role references unknown role: {role}");
+            }
+        }
+
+        let (state, stamp) = read_state(&state_path)?;
+        let ttl_secs = i64::try_from(config.link_code_ttl_secs.max(1)).unwrap_or(i64::MAX);
+
+        Ok(Self {
+            state_path,
+            roles,
+            gated_actions: security.otp.gated_actions.clone(),
+            unknown_sender_role,
+            scope_memory: config.scope_memory,
+            link_code_ttl: Duration::seconds(ttl_secs),
+            inner: Mutex::new(DirectoryInner { state, stamp }),
+        })
+    }
+
+    pub fn state_path(&self) -> &Path {
+        &self.state_path
+    }
+
+    /// All users, sorted by id.
+    pub fn list(&self) -> Vec<UserRecord> {
+        let mut users = self.with_state(|state| state.users.clone());
+        users.sort_by(|a, b| a.id.cmp(&b.id));
+        users
+    }
+
+    pub fn get(&self, user_id: &str) -> Option<UserRecord> {
+        let user_id = user_id.trim().to_ascii_lowercase();
+        self.with_state(|state| state.users.iter().find(|u| u.id == user_id).cloned())
+    }
+
+    /// Resolve the directory user linked to `sender` on `channel`.
+    pub fn resolve(&self, channel: &str, sender: &str) -> Option<UserRecord> {
+        let identity = normalize_identity(channel, sender)?;
+        self.with_state(|state| {
+            state
+                .users
+                .iter()
+                .find(|user| user.identities.contains(&identity))
+                .cloned()
+        })
+    }
+
+    /// Effective role for a channel sender: the linked user's role, or
+    /// `unknown_sender_role` when the sender is not in the directory.
+    pub fn role_for(&self, channel: &str, sender: &str) -> Option<String> {
+        self.resolve(channel, sender)
+            .map(|user| user.role)
+            .or_else(|| self.unknown_sender_role.clone())
+    }
+
+    /// Role-based tool access for a channel sender. `None` means no role
+    /// applies and the caller should fall back to channel-level policy.
+    pub fn tool_access(&self, channel: &str, sender: &str, tool_name: &str) -> Option<ToolAccess> {
+        let role = self.role_for(channel, sender)?;
+        Some(
+            self.roles
+                .resolve_tool_access(&role, tool_name, &self.gated_actions),
+        )
+    }
+
+    /// Subset of `tool_names` that the sender's role does not allow.
+    pub fn denied_tools<'a>(
+        &self,
+        channel: &str,
+        sender: &str,
+        tool_names: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<String> {
+        let Some(role) = self.role_for(channel, sender) else {
+            return Vec::new();
+        };
+        tool_names
+            .into_iter()
+            .filter(|tool| {
+                !self
+                    .roles
+                    .resolve_tool_access(&role, tool, &self.gated_actions)
+                    .allowed
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Memory session id used to keep a user's memories separate from other
+    /// senders. Returns `None` when memory scoping is disabled or the sender
+    /// is not linked to a user.
+    pub fn memory_session_id(&self, channel: &str, sender: &str) -> Option<String> {
+        if !self.scope_memory {
+            return None;
+        }
+        self.resolve(channel, sender)
+            .map(|user| format!("{USER_MEMORY_SESSION_PREFIX}{}", user.id))
+    }
+
+    pub fn add_user(&self, user_id: &str, display_name: &str, role: &str) -> Result<UserRecord> {
+        let user_id = normalize_user_id(user_id)?;
+        let role = self.normalize_role(role)?;
+        self.mutate(|state| {
+            if state.users.iter().any(|user| user.id == user_id) {
+                bail!("User '{user_id}' already exists");
+            }
+            let record = UserRecord {
+                id: user_id.clone(),
+                display_name: display_name.trim().to_string(),
+                role: role.clone(),
+                identities: Vec::new(),
+                created_at: Utc::now().to_rfc3339(),
+            };
+            state.users.push(record.clone());
+            Ok(record)
+        })
+    }
+
+    /// Remove a user and any outstanding link codes. Returns `false` when
+    /// the user did not exist.
+    pub fn remove_user(&self, user_id: &str) -> Result<bool> {
+        let user_id = user_id.trim().to_ascii_lowercase();
+        self.mutate(|state| {
+            let before = state.users.len();
+            state.users.retain(|user| user.id != user_id);
+            state.link_codes.retain(|code| code.user_id != user_id);
+            Ok(state.users.len() != before)
+        })
+    }
+
+    pub fn set_role(&self, user_id: &str, role: &str) -> Result<UserRecord> {
+        let user_id = user_id.trim().to_ascii_lowercase();
+        let role = self.normalize_role(role)?;
+        self.mutate(|state| {
+            let user = find_user_mut(state, &user_id)?;
+            user.role = role.clone();
+            Ok(user.clone())
+        })
+    }
+
+    pub fn link_identity(&self, user_id: &str, channel: &str, sender: &str) -> Result<UserRecord> {
+        let user_id = user_id.trim().to_ascii_lowercase();
+        let identity = normalize_identity(channel, sender)
+            .context("Identity must be in the form <channel>:<sender-id>")?;
+        self.mutate(|state| link_identity_in_state(state, &user_id, &identity))
+    }
+
+    /// Unlink an identity from whichever user owns it. Returns the previous
+    /// owner's id, if any.
+    pub fn unlink_identity(&self, channel: &str, sender: &str) -> Result<Option<String>> {
+        let identity = normalize_identity(channel, sender)
+            .context("Identity must be in the form <channel>:<sender-id>")?;
+        self.mutate(|state| {
+            for user in &mut state.users {
+                let before = user.identities.len();
+                user.identities.retain(|linked| *linked != identity);
+                if user.identities.len() != before {
+                    return Ok(Some(user.id.clone()));
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    /// Issue a one-time code that links the identity redeeming it to
+    /// `user_id`. Only a hash of the code is persisted.
+    pub fn issue_link_code(&self, user_id: &str) -> Result<(String, DateTime<Utc>)> {
+        let user_id = user_id.trim().to_ascii_lowercase();
+        let code = hex::encode_upper(rand::random::<[u8; 5]>());
+        let expires_at = Utc::now() + self.link_code_ttl;
+        let code_sha256 = hash_link_code(&code);
+        self.mutate(|state| {
+            find_user_mut(state, &user_id)?;
+            state.link_codes.push(LinkCodeRecord {
+                code_sha256: code_sha256.clone(),
+                user_id: user_id.clone(),
+                expires_at: expires_at.to_rfc3339(),
+            });
+            Ok(())
+        })?;
+        Ok((code, expires_at))
+    }
+
+    /// Redeem a one-time link code, attaching `channel:sender` to the user
+    /// that issued it. The code is consumed only when linking succeeds; if
+    /// linking fails, it stays valid until it expires.
+    pub fn redeem_link_code(&self, code: &str, channel: &str, sender: &str) -> Result<UserRecord> {
+        let identity = normalize_identity(channel, sender)
+            .context("Cannot link an empty channel or sender identity")?;
+        let code_sha256 = hash_link_code(code);
+        self.mutate(|state| {
+            let Some(index) = state
+                .link_codes
+                .iter()
+                .position(|record| record.code_sha256 == code_sha256)
+            else {
+                bail!("Unknown or already used link code");
+            };
+            let record = state.link_codes.remove(index);
+            if is_expired(&record.expires_at) {
+                bail!("Link code has expired; ask for a new one");
+            }
+            link_identity_in_state(state, &record.user_id, &identity)
+        })
+    }
+
+    /// Whether `role` names a known role (built-in or from `security.roles`).
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.has_role(role)
+    }
+
+    fn normalize_role(&self, role: &str) -> Result<String> {
+        let role = role.trim().to_ascii_lowercase();
+        if !self.roles.has_role(&role) {
+            bail!(
+                "Unknown role '{role}'; define it under [[security.roles]] or use a built-in role"
+            );
+        }
+        Ok(role)
+    }
+
+    fn with_state<T>(&self, read: impl FnOnce(&UserDirectoryState) -> T) -> T {
+        let mut inner = self.inner.lock();
+        self.refresh(&mut inner);
+        read(&inner.state)
+    }
+
+    fn mutate<T>(&self, update: impl FnOnce(&mut UserDirectoryState) -> Result<T>) -> Result<T> {
+        let mut inner = self.inner.lock();
+        self.refresh(&mut inner);
+        let mut next = inner.state.clone();
+        next.link_codes
+            .retain(|record| !is_expired(&record.expires_at));
+        let result = update(&mut next)?;
+        persist_state(&self.state_path, &next)?;
+        inner.state = next;
+        inner.stamp = file_stamp(&self.state_path);
+        Ok(result)
+    }
+
+    fn refresh(&self, inner: &mut DirectoryInner) {
+        let stamp = file_stamp(&self.state_path);
+        if stamp == inner.stamp {
+            return;
+        }
+        match read_state(&self.state_path) {
+            Ok((state, stamp)) => {
+                inner.state = state;
+                inner.stamp = stamp;
+            }
+            Err(error) => {
+                tracing::warn!(
+                    path = %self.state_path.display(),
+                    "Failed to reload user directory; keeping previous state: {error}"
+                );
+            }
+        }
+    }
+}
+
+/// Split a `channel:sender` identity string as accepted by the CLI.
+pub fn parse_identity(raw: &str) -> Result<(String, String)> {
+    let Some((channel, sender)) = raw.trim().split_once(':') else {
+        bail!("Identity '{raw}' must be in the form <channel>:<sender-id>");
+    };
+    let Some(identity) = normalize_identity(channel, sender) else {
+        bail!("Identity '{raw}' must be in the form <channel>:<sender-id>");
+    };
+    let (channel, sender) = identity
+        .split_once(':')
+        .expect("normalized identity always contains a separator");
+    Ok((channel.to_string(), sender.to_string()))
+}
+
+fn normalize_identity(channel: &str, sender: &str) -> Option<String> {
+    let channel = channel.trim().to_ascii_lowercase();
+    let sender = sender.trim();
+    if channel.is_empty() || sender.is_empty() || channel.contains(':') {
+        return None;
+    }
+    // Email addresses are case-insensitive in practice; every other channel
+    // compares sender ids exactly, matching the per-channel allowlists.
+    let sender = if channel == "email" {
+        sender.to_ascii_lowercase()
+    } else {
+        sender.to_string()
+    };
+    Some(format!("{channel}:{sender}"))
+}
+
+fn normalize_user_id(raw: &str) -> Result<String> {
+    let value = raw.trim().to_ascii_lowercase();
+    if value.is_empty() {
+        bail!("User id must not be empty");
+    }
+    if !value
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        bail!("User id '{raw}' contains invalid characters");
+    }
+    Ok(value)
+}
+
+fn find_user_mut<'a>(
+    state: &'a mut UserDirectoryState,
+    user_id: &str,
+) -> Result<&'a mut UserRecord> {
+    state
+        .users
+        .iter_mut()
+        .find(|user| user.id == user_id)
+        .with_context(|| format!("Unknown user '{user_id}'"))
+}
+
+fn link_identity_in_state(
+    state: &mut UserDirectoryState,
+    user_id: &str,
+    identity: &str,
+) -> Result<UserRecord> {
+    if let Some(owner) = state
+        .users
+        .iter()
+        .find(|user| user.id != user_id && user.identities.iter().any(|id| id == identity))
+    {
+        bail!(
+            "Identity '{identity}' is already linked to user '{}'",
+            owner.id
+        );
+    }
+    let user = find_user_mut(state, user_id)?;
+    if !user.identities.iter().any(|linked| linked == identity) {
+        user.identities.push(identity.to_string());
+        user.identities.sort();
+    }
+    Ok(user.clone())
+}
+
+fn hash_link_code(code: &str) -> String {
+    let normalized = code.trim().to_ascii_uppercase();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+fn is_expired(expires_at: &str) -> bool {
+    DateTime::parse_from_rfc3339(expires_at)
+        .map(|at| at.with_timezone(&Utc) <= Utc::now())
+        .unwrap_or(true)
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+fn read_state(path: &Path) -> Result<(UserDirectoryState, Option<FileStamp>)> {
+    if !path.exists() {
+        return Ok((UserDirectoryState::default(), None));
+    }
+    let stamp = file_stamp(path);
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read user directory {}", path.display()))?;
+    let state = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse user directory {}", path.display()))?;
+    Ok((state, stamp))
+}
+
+fn persist_state(path: &Path, state: &UserDirectoryState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create user directory dir {}", parent.display()))?;
+    }
+
+    let body = serde_json::to_string_pretty(state).context("Failed to serialize user directory")?;
+    let temp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    fs::write(&temp_path, body).with_context(|| {
+        format!(
+            "Failed to write temporary user directory file {}",
+            temp_path.display()
+        )
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600));
+    }
+
+    fs::rename(&temp_path, path).with_context(|| {
+        format!(
+            "Failed to atomically replace user directory file {}",
+            path.display()
+        )
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{SecurityRoleConfig, UserDirectoryConfig};
+    use tempfile::tempdir;
+
+    fn security_config(dir: &Path) -> SecurityConfig {
+        SecurityConfig {
+            users: UserDirectoryConfig {
+                enabled: true,
+                state_file: dir.join("users.json").display().to_string(),
+                ..UserDirectoryConfig::default()
+            },
+            ..SecurityConfig::default()
+        }
+    }
+
+    #[test]
+    fn add_link_and_resolve_user_across_channels() {
+        let dir = tempdir().unwrap();
+        let directory = UserDirectory::load(&security_config(dir.path()), dir.path()).unwrap();
+
+        directory.add_user("Alice", "Alice A.", "operator").unwrap();
+        directory.link_identity("alice", "telegram", "123").unwrap();
+        directory
+            .link_identity("alice", "email", "Alice@Example.com")
+            .unwrap();
+
+        let via_telegram = directory.resolve("telegram", "123").unwrap();
+        assert_eq!(via_telegram.id, "alice");
+        assert_eq!(via_telegram.role, "operator");
+        let via_email = directory.resolve("email", "alice@example.com").unwrap();
+        assert_eq!(via_email.id, "alice");
+        assert!(directory.resolve("slack", "123").is_none());
+        assert_eq!(
+            directory.memory_session_id("telegram", "123").as_deref(),
+            Some("user:alice")
+        );
+    }
+
+    #[test]
+    fn identity_cannot_be_linked_to_two_users() {
+        let dir = tempdir().unwrap();
+        let directory = UserDirectory::load(&security_config(dir.path()), dir.path()).unwrap();
+        directory.add_user("alice", "", "viewer").unwrap();
+        directory.add_user("bob", "", "viewer").unwrap();
+        directory.link_identity("alice", "slack", "U01").unwrap();
+
+        let err = directory.link_identity("bob", "slack", "U01").unwrap_err();
+        assert!(err.to_string().contains("already linked"));
+        assert_eq!(
+            directory
+                .unlink_identity("slack", "U01")
+                .unwrap()
+                .as_deref(),
+            Some("alice")
+        );
+        directory.link_identity("bob", "slack", "U01").unwrap();
+    }
+
+    #[test]
+    fn unknown_roles_are_rejected() {
+        let dir = tempdir().unwrap();
+        let directory = UserDirectory::load(&security_config(dir.path()), dir.path()).unwrap();
+        assert!(directory.add_user("alice", "", "superuser").is_err());
+    }
+
+    #[test]
+    fn role_denies_tools_for_linked_sender() {
+        let dir = tempdir().unwrap();
+        let mut security = security_config(dir.path());
+        security.roles.push(SecurityRoleConfig {
+            name: "readonly".to_string(),
+            allowed_tools: vec!["file_read".to_string()],
+            ..SecurityRoleConfig::default()
+        });
+        let directory = UserDirectory::load(&security, dir.path()).unwrap();
+        directory.add_user("carol", "", "readonly").unwrap();
+        directory
+            .link_identity("carol", "matrix", "@carol:example.org")
+            .unwrap();
+
+        let denied = directory.denied_tools("matrix", "@carol:example.org", ["file_read", "shell"]);
+        assert_eq!(denied, vec!["shell".to_string()]);
+        assert!(directory
+            .tool_access("matrix", "@someone:else", "shell")
+            .is_none());
+    }
+
+    #[test]
+    fn unknown_sender_role_applies_to_unlinked_senders() {
+        let dir = tempdir().unwrap();
+        let mut security = security_config(dir.path());
+        security.users.unknown_sender_role = Some("guest".to_string());
+        let directory = UserDirectory::load(&security, dir.path()).unwrap();
+
+        assert_eq!(
+            directory.role_for("telegram", "999").as_deref(),
+            Some("guest")
+        );
+        assert_eq!(
+            directory.denied_tools("telegram", "999", ["shell"]),
+            vec!["shell".to_string()]
+        );
+    }
+
+    #[test]
+    fn link_code_is_single_use() {
+        let dir = tempdir().unwrap();
+        let directory = UserDirectory::load(&security_config(dir.path()), dir.path()).unwrap();
+        directory.add_user("alice", "", "operator").unwrap();
+
+        let (code, _) = directory.issue_link_code("alice").unwrap();
+        let raw = fs::read_to_string(directory.state_path()).unwrap();
+        assert!(
+            !raw.contains(&code),
+            "link codes must not be stored in plaintext"
+        );
+
+        let linked = directory
+            .redeem_link_code(&code.to_ascii_lowercase(), "discord", "42")
+            .unwrap();
+        assert_eq!(linked.identities, vec!["discord:42".to_string()]);
+        assert!(directory.redeem_link_code(&code, "slack", "U02").is_err());
+    }
+
+    #[test]
+    fn failed_link_keeps_code_valid() {
+        let dir = tempdir().unwrap();
+        let directory = UserDirectory::load(&security_config(dir.path()), dir.path()).unwrap();
+        directory.add_user("alice", "", "operator").unwrap();
+        directory.add_user("bob", "", "viewer").unwrap();
+        directory.link_identity("bob", "discord", "42").unwrap();
+
+        let (code, _) = directory.issue_link_code("alice").unwrap();
+        assert!(directory.redeem_link_code(&code, "discord", "42").is_err());
+
+        let linked = directory.redeem_link_code(&code, "slack", "U01").unwrap();
+        assert_eq!(linked.id, "alice");
+    }
+
+    #[test]
+    fn directory_reloads_changes_made_by_another_handle() {
+        let dir = tempdir().unwrap();
+        let security = security_config(dir.path());
+        let daemon = UserDirectory::load(&security, dir.path()).unwrap();
+        assert!(daemon.resolve("telegram", "7").is_none());
+
+        let cli = UserDirectory::load(&security, dir.path()).unwrap();
+        cli.add_user("dave", "Dave", "viewer").unwrap();
+        cli.link_identity("dave", "telegram", "7").unwrap();
+
+        assert_eq!(daemon.resolve("telegram", "7").unwrap().id, "dave");
+    }
+
+    #[test]
+    fn parse_identity_splits_on_first_separator() {
+        let (channel, sender) = parse_identity("Matrix:@alice:example.org").unwrap();
+        assert_eq!(channel, "matrix");
+        assert_eq!(sender, "@alice:example.org");
+        assert!(parse_identity("telegram").is_err());
+        assert!(parse_identity(":123").is_err());
+    }
+}
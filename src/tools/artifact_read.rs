@@ -0,0 +1,339 @@
+use super::traits::{Tool, ToolResult};
+use crate::agent::artifacts::{self, ArtifactStore};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write as _;
+
+/// Default characters returned per page.
+const DEFAULT_PAGE_CHARS: usize = 4_000;
+/// Default lines returned by `slice` when `end_line` is omitted.
+const DEFAULT_SLICE_LINES: usize = 200;
+const DEFAULT_MAX_MATCHES: usize = 50;
+const MAX_GREP_CONTEXT: usize = 5;
+
+/// Reads tool outputs that the tool loop stored as session artifacts because
+/// they exceeded `agent.tool_output_max_chars`.
+pub struct ArtifactReadTool {
+    store: ArtifactStore,
+    max_output_chars: usize,
+}
+
+impl ArtifactReadTool {
+    pub fn new(store: ArtifactStore, max_output_chars: usize) -> Self {
+        Self {
+            store,
+            max_output_chars: max_output_chars.max(1),
+        }
+    }
+
+    fn page(&self, id: &str, content: &str, args: &serde_json::Value) -> String {
+        let page_chars = usize_arg(args, "page_chars")
+            .unwrap_or(DEFAULT_PAGE_CHARS)
+            .clamp(1, self.max_output_chars);
+        let total_chars = content.chars().count();
+        let pages = total_chars.div_ceil(page_chars).max(1);
+        let page = usize_arg(args, "page").unwrap_or(1).clamp(1, pages);
+        let start = (page - 1) * page_chars;
+        let text: String = content.chars().skip(start).take(page_chars).collect();
+        let end = start + text.chars().count();
+        format!("Artifact {id}: page {page}/{pages} (chars {start}-{end} of {total_chars})\n{text}")
+    }
+
+    fn slice(&self, id: &str, content: &str, args: &serde_json::Value) -> String {
+        let lines: Vec<&str> = content.lines().collect();
+        let start_line = usize_arg(args, "start_line").unwrap_or(1).max(1);
+        let end_line = usize_arg(args, "end_line")
+            .unwrap_or(start_line.saturating_add(DEFAULT_SLICE_LINES - 1))
+            .min(lines.len());
+
+        let mut output = format!(
+            "Artifact {id}: lines {start_line}-{end_line} of {}\n",
+            lines.len()
+        );
+        for (index, line) in lines.iter().enumerate().take(end_line).skip(start_line - 1) {
+            if !self.push_bounded(&mut output, &format!("{}: {line}\n", index + 1)) {
+                break;
+            }
+        }
+        output
+    }
+
+    fn grep(&self, id: &str, content: &str, args: &serde_json::Value) -> Result<String, String> {
+        let pattern = args
+            .get("pattern")
+            .and_then(serde_json::Value::as_str)
+            .filter(|value| !value.is_empty())
+            .ok_or("Missing 'pattern' parameter for mode 'grep'")?;
+        let ignore_case = args
+            .get("ignore_case")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .size_limit(1 << 20)
+            .build()
+            .map_err(|error| format!("Invalid pattern: {error}"))?;
+        let context = usize_arg(args, "context")
+            .unwrap_or(0)
+            .min(MAX_GREP_CONTEXT);
+        let max_matches = usize_arg(args, "max_matches")
+            .unwrap_or(DEFAULT_MAX_MATCHES)
+            .max(1);
+
+        let lines: Vec<&str> = content.lines().collect();
+        let matches: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| regex.is_match(line))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut output = format!(
+            "Artifact {id}: {} matching line(s) for /{pattern}/\n",
+            matches.len()
+        );
+        let mut last_printed: Option<usize> = None;
+        for &index in matches.iter().take(max_matches) {
+            let from = index.saturating_sub(context);
+            let to = (index + context).min(lines.len().saturating_sub(1));
+            let from = last_printed.map_or(from, |last| from.max(last + 1));
+            if last_printed.is_some_and(|last| from > last + 1) {
+                output.push_str("--\n");
+            }
+            for (offset, line) in lines[from..=to].iter().enumerate() {
+                let number = from + offset;
+                let marker = if number == index { ':' } else { '-' };
+                if !self.push_bounded(&mut output, &format!("{}{marker} {line}\n", number + 1)) {
+                    return Ok(output);
+                }
+            }
+            last_printed = Some(to);
+        }
+        if matches.len() > max_matches {
+            let _ = writeln!(
+                output,
+                "[{} more match(es) not shown]",
+                matches.len() - max_matches
+            );
+        }
+        Ok(output)
+    }
+
+    /// Append `text` unless that would exceed the output limit.
+    fn push_bounded(&self, output: &mut String, text: &str) -> bool {
+        if output.chars().count() + text.chars().count() > self.max_output_chars {
+            output.push_str("[output limit reached; narrow the range]\n");
+            return false;
+        }
+        output.push_str(text);
+        true
+    }
+}
+
+fn usize_arg(args: &serde_json::Value, key: &str) -> Option<usize> {
+    args.get(key)
+        .and_then(serde_json::Value::as_u64)
+        .map(|value| usize::try_from(value).unwrap_or(usize::MAX))
+}
+
+#[async_trait]
+impl Tool for ArtifactReadTool {
+    fn name(&self) -> &str {
+        "artifact_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a large tool output that was stored as an artifact. Use mode 'page' to read it in \
+         chunks, 'grep' to find matching lines, or 'slice' to read a line range."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "artifact_id": {
+                    "type": "string",
+                    "description": "Artifact id from the truncated tool output (e.g. art-1a2b3c4d5e6f)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["page", "grep", "slice"],
+                    "description": "How to read the artifact (default: page)"
+                },
+                "page": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Page number for mode 'page' (default: 1)"
+                },
+                "page_chars": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Characters per page for mode 'page' (default: 4000)"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression for mode 'grep'"
+                },
+                "ignore_case": {
+                    "type": "boolean",
+                    "description": "Case-insensitive matching for mode 'grep'"
+                },
+                "context": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Lines of context around each grep match (max 5)"
+                },
+                "max_matches": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum grep matches to show (default: 50)"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "First line (1-based) for mode 'slice'"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Last line (inclusive) for mode 'slice' (default: start_line + 199)"
+                }
+            },
+            "required": ["artifact_id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = args
+            .get("artifact_id")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'artifact_id' parameter"))?;
+
+        let content = match self.store.load(&artifacts::current_session(), id) {
+            Ok(content) => content,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error.to_string()),
+                });
+            }
+        };
+
+        let mode = args.get("mode").and_then(|v| v.as_str()).unwrap_or("page");
+        let output = match mode {
+            "page" => Ok(self.page(id, &content, &args)),
+            "slice" => Ok(self.slice(id, &content, &args)),
+            "grep" => self.grep(id, &content, &args),
+            other => Err(format!(
+                "Invalid mode '{other}'. Allowed values: page, grep, slice."
+            )),
+        };
+
+        Ok(match output {
+            Ok(output) => ToolResult {
+                success: true,
+                output,
+                error: None,
+            },
+            Err(error) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::transcript;
+    use tempfile::TempDir;
+
+    fn tool_with_artifact(content: &str) -> (TempDir, ArtifactReadTool, String) {
+        let tmp = TempDir::new().unwrap();
+        let store = ArtifactStore::new(tmp.path(), 10);
+        let id = store.save("artifact-test", content).unwrap();
+        let tool = ArtifactReadTool::new(ArtifactStore::new(tmp.path(), 10), 500);
+        (tmp, tool, id)
+    }
+
+    async fn run(tool: &ArtifactReadTool, args: serde_json::Value) -> ToolResult {
+        transcript::scope("artifact-test".into(), tool.execute(args))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pages_through_artifact() {
+        let (_tmp, tool, id) = tool_with_artifact(&"abcdefghij".repeat(10));
+
+        let result = run(
+            &tool,
+            json!({"artifact_id": id, "page": 2, "page_chars": 30}),
+        )
+        .await;
+
+        assert!(result.success);
+        assert!(result.output.contains("page 2/4 (chars 30-60 of 100)"));
+        assert!(result.output.ends_with("abcdefghijabcdefghijabcdefghij"));
+    }
+
+    #[tokio::test]
+    async fn greps_with_context() {
+        let content = (1..=20)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (_tmp, tool, id) = tool_with_artifact(&content);
+
+        let result = run(
+            &tool,
+            json!({"artifact_id": id, "mode": "grep", "pattern": "^line 1[05]$", "context": 1}),
+        )
+        .await;
+
+        assert!(result.success);
+        assert!(result.output.contains("2 matching line(s)"));
+        assert!(result
+            .output
+            .contains("9- line 9\n10: line 10\n11- line 11\n--\n14- line 14"));
+    }
+
+    #[tokio::test]
+    async fn slices_line_range() {
+        let content = (1..=20)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (_tmp, tool, id) = tool_with_artifact(&content);
+
+        let result = run(
+            &tool,
+            json!({"artifact_id": id, "mode": "slice", "start_line": 3, "end_line": 4}),
+        )
+        .await;
+
+        assert_eq!(
+            result.output,
+            "Artifact ".to_string() + &id + ": lines 3-4 of 20\n3: line 3\n4: line 4\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn artifacts_from_other_sessions_are_not_readable() {
+        let (_tmp, tool, id) = tool_with_artifact("hidden");
+
+        let result = transcript::scope(
+            "other-session".into(),
+            tool.execute(json!({"artifact_id": id})),
+        )
+        .await
+        .unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not found"));
+    }
+}
@@ -1,7 +1,9 @@
 use super::traits::{Tool, ToolResult};
 use crate::agent::loop_::run_tool_call_loop;
 use crate::config::DelegateAgentConfig;
+use crate::coordination::remote::RemoteCoordinator;
 use crate::coordination::{CoordinationEnvelope, CoordinationPayload, InMemoryMessageBus};
+use crate::observability::span::{self, SpanGuard, SpanKind};
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::security::policy::ToolOperation;
@@ -43,6 +45,8 @@ pub struct DelegateTool {
     coordination_bus: Option<InMemoryMessageBus>,
     /// Logical lead agent identity used in coordination trace events.
     coordination_lead_agent: String,
+    /// Transport for agents served by peer gateways (`[[coordination.peers]]`).
+    remote: Option<Arc<RemoteCoordinator>>,
 }
 
 impl DelegateTool {
@@ -76,6 +80,7 @@ impl DelegateTool {
             multimodal_config: crate::config::MultimodalConfig::default(),
             coordination_bus,
             coordination_lead_agent: DEFAULT_COORDINATION_LEAD_AGENT.to_string(),
+            remote: None,
         }
     }
 
@@ -115,6 +120,7 @@ impl DelegateTool {
             multimodal_config: crate::config::MultimodalConfig::default(),
             coordination_bus,
             coordination_lead_agent: DEFAULT_COORDINATION_LEAD_AGENT.to_string(),
+            remote: None,
         }
     }
 
@@ -162,6 +168,66 @@ impl DelegateTool {
         self
     }
 
+    /// Route agents that are not configured locally to the peers serving them.
+    pub fn with_remote_coordinator(mut self, remote: Arc<RemoteCoordinator>) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    fn remote_agent_names(&self) -> Vec<String> {
+        self.remote
+            .as_ref()
+            .map(|remote| {
+                remote
+                    .remote_agents()
+                    .into_iter()
+                    .filter(|(agent, _)| !self.agents.contains_key(agent))
+                    .map(|(agent, peer)| format!("{agent}@{peer}"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    async fn execute_remote(
+        &self,
+        remote: &RemoteCoordinator,
+        agent_name: &str,
+        prompt: &str,
+        context: &str,
+    ) -> ToolResult {
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, "delegate")
+        {
+            return ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            };
+        }
+
+        match remote.delegate(agent_name, prompt, context).await {
+            Ok(result) if result.success => ToolResult {
+                success: true,
+                output: result.output,
+                error: None,
+            },
+            Ok(result) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Remote agent '{agent_name}' failed: {}",
+                    result.output
+                )),
+            },
+            Err(error) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Remote delegation failed: {error}")),
+            },
+        }
+    }
+
     #[cfg(test)]
     fn coordination_bus_snapshot(&self) -> Option<InMemoryMessageBus> {
         self.coordination_bus.clone()
@@ -181,7 +247,8 @@ impl Tool for DelegateTool {
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
-        let agent_names: Vec<&str> = self.agents.keys().map(|s: &String| s.as_str()).collect();
+        let mut agent_names: Vec<String> = self.agents.keys().cloned().collect();
+        agent_names.extend(self.remote_agent_names());
         json!({
             "type": "object",
             "additionalProperties": false,
@@ -251,8 +318,17 @@ impl Tool for DelegateTool {
         let agent_config = match self.agents.get(agent_name) {
             Some(cfg) => cfg,
             None => {
-                let available: Vec<&str> =
-                    self.agents.keys().map(|s: &String| s.as_str()).collect();
+                if let Some(remote) = self
+                    .remote
+                    .as_ref()
+                    .filter(|remote| remote.peer_for_agent(agent_name).is_some())
+                {
+                    return Ok(self
+                        .execute_remote(remote, agent_name, prompt, context)
+                        .await);
+                }
+                let mut available: Vec<String> = self.agents.keys().cloned().collect();
+                available.extend(self.remote_agent_names());
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
@@ -295,6 +371,8 @@ impl Tool for DelegateTool {
 
         let coordination_trace =
             self.start_coordination_trace(agent_name, prompt, context, agent_config);
+        let mut agent_span = agent_span(agent_name, agent_config, "delegate");
+        agent_span.set_attribute("zeroclaw.delegate.depth", u64::from(self.depth));
 
         // Create provider for this agent
         let provider_credential_owned = agent_config
@@ -315,6 +393,7 @@ impl Tool for DelegateTool {
                     "Failed to create provider '{}' for agent '{agent_name}': {e}",
                     agent_config.provider
                 );
+                agent_span.fail(error_message.clone());
                 self.finish_coordination_trace(
                     agent_name,
                     &coordination_trace,
@@ -340,15 +419,17 @@ impl Tool for DelegateTool {
 
         // Agentic mode: run full tool-call loop with allowlisted tools.
         if agent_config.agentic {
-            let result = self
-                .execute_agentic(
+            let result = span::scope(
+                agent_span.context(),
+                self.execute_agentic(
                     agent_name,
                     agent_config,
                     &*provider,
                     &full_prompt,
                     temperature,
-                )
-                .await?;
+                ),
+            )
+            .await?;
 
             let summary = if result.success {
                 result.output.as_str()
@@ -358,6 +439,9 @@ impl Tool for DelegateTool {
                     .as_deref()
                     .unwrap_or("delegate agentic execution failed")
             };
+            if !result.success {
+                agent_span.fail(summary);
+            }
             self.finish_coordination_trace(
                 agent_name,
                 &coordination_trace,
@@ -369,6 +453,7 @@ impl Tool for DelegateTool {
         }
 
         // Wrap the provider call in a timeout to prevent indefinite blocking
+        let mut chat_span = chat_span(&agent_span, agent_config, temperature);
         let result = tokio::time::timeout(
             Duration::from_secs(DELEGATE_TIMEOUT_SECS),
             provider.chat_with_system(
@@ -385,6 +470,8 @@ impl Tool for DelegateTool {
             Err(_elapsed) => {
                 let timeout_message =
                     format!("Agent '{agent_name}' timed out after {DELEGATE_TIMEOUT_SECS}s");
+                chat_span.fail(timeout_message.clone());
+                agent_span.fail(timeout_message.clone());
                 self.finish_coordination_trace(
                     agent_name,
                     &coordination_trace,
@@ -420,6 +507,8 @@ impl Tool for DelegateTool {
             }
             Err(e) => {
                 let failure_message = format!("Agent '{agent_name}' failed: {e}");
+                chat_span.fail(failure_message.clone());
+                agent_span.fail(failure_message.clone());
                 self.finish_coordination_trace(
                     agent_name,
                     &coordination_trace,
@@ -436,6 +525,36 @@ impl Tool for DelegateTool {
     }
 }
 
+/// Open the span for a delegated agent invocation under the current span.
+pub(super) fn agent_span(
+    agent_name: &str,
+    agent_config: &DelegateAgentConfig,
+    mode: &str,
+) -> SpanGuard {
+    let mut agent_span = SpanGuard::start(SpanKind::Agent, format!("invoke_agent {agent_name}"));
+    agent_span.set_attribute("gen_ai.operation.name", "invoke_agent");
+    agent_span.set_attribute("gen_ai.agent.name", agent_name);
+    agent_span.set_attribute("gen_ai.provider.name", agent_config.provider.as_str());
+    agent_span.set_attribute("gen_ai.request.model", agent_config.model.as_str());
+    agent_span.set_attribute("zeroclaw.delegate.mode", mode);
+    agent_span.set_attribute("zeroclaw.delegate.agentic", agent_config.agentic);
+    agent_span
+}
+
+/// Span for the single provider call of a non-agentic delegate.
+pub(super) fn chat_span(
+    agent_span: &SpanGuard,
+    agent_config: &DelegateAgentConfig,
+    temperature: f64,
+) -> SpanGuard {
+    let mut chat_span = agent_span.child(SpanKind::LlmCall, format!("chat {}", agent_config.model));
+    chat_span.set_attribute("gen_ai.operation.name", "chat");
+    chat_span.set_attribute("gen_ai.provider.name", agent_config.provider.as_str());
+    chat_span.set_attribute("gen_ai.request.model", agent_config.model.as_str());
+    chat_span.set_attribute("gen_ai.request.temperature", temperature);
+    chat_span
+}
+
 impl DelegateTool {
     async fn execute_agentic(
         &self,
@@ -797,6 +916,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         agents.insert(
@@ -811,6 +932,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         agents
@@ -967,6 +1090,8 @@ mod tests {
             agentic: true,
             allowed_tools,
             max_iterations,
+            max_concurrent: 1,
+            max_retries: 2,
         }
     }
 
@@ -1075,6 +1200,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -1181,6 +1308,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -1216,6 +1345,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -1396,6 +1527,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
 
@@ -1465,6 +1598,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -0,0 +1,805 @@
+//! Persistent delegate task queue tool and background worker.
+//!
+//! Implements the `delegate_queue` tool, which enqueues delegated work into
+//! [`DelegateQueueStore`] instead of spawning it directly. A per-workspace
+//! [`DelegateQueueWorker`] drains the queue by priority while honouring each
+//! agent's `max_concurrent` and `max_retries`, and reports finished tasks back
+//! to the originating channel conversation.
+//!
+//! Channel conversations are the only callback target. Triggering an SOP on
+//! completion is tracked as its own request: `src/sop` is not compiled into
+//! the runtime yet, so there is no engine to dispatch into.
+
+use super::delegate;
+use super::delegate_queue_store::{
+    DelegateCallback, DelegateQueueStore, DelegateTask, DelegateTaskStatus, NewDelegateTask,
+};
+use super::subagent_spawn::{run_agentic_background, run_simple_background};
+use super::traits::{Tool, ToolResult};
+use crate::channels::traits::SendMessage;
+use crate::config::{Config, DelegateAgentConfig};
+use crate::observability::span;
+use crate::providers::{self, Provider};
+use crate::security::cancellation::{self, StopScope};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::AbortHandle;
+
+/// Extra lease time on top of the task timeout before a running task is
+/// considered abandoned.
+const LEASE_GRACE_SECS: u64 = 60;
+const DEFAULT_LIST_LIMIT: usize = 20;
+const CALLBACK_OUTPUT_MAX_CHARS: usize = 3_500;
+
+/// Everything a worker needs to run queued tasks.
+pub struct DelegateQueueRuntime {
+    pub config: Arc<Config>,
+    pub agents: Arc<HashMap<String, DelegateAgentConfig>>,
+    pub fallback_credential: Option<String>,
+    pub provider_runtime_options: providers::ProviderRuntimeOptions,
+    pub parent_tools: Arc<Vec<Arc<dyn Tool>>>,
+}
+
+/// Background worker that drains one workspace's delegate queue.
+pub struct DelegateQueueWorker {
+    runtime: DelegateQueueRuntime,
+    store: DelegateQueueStore,
+    wake: Notify,
+    started: Mutex<bool>,
+    running: Mutex<HashMap<String, AbortHandle>>,
+}
+
+fn workers() -> &'static Mutex<HashMap<PathBuf, Arc<DelegateQueueWorker>>> {
+    static WORKERS: OnceLock<Mutex<HashMap<PathBuf, Arc<DelegateQueueWorker>>>> = OnceLock::new();
+    WORKERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+impl DelegateQueueWorker {
+    /// Return the process-wide worker for the runtime's workspace, creating it
+    /// on first use so several tool registries never drain the queue twice.
+    pub fn shared(runtime: DelegateQueueRuntime) -> Arc<Self> {
+        let workspace_dir = runtime.config.workspace_dir.clone();
+        workers()
+            .lock()
+            .entry(workspace_dir.clone())
+            .or_insert_with(|| {
+                Arc::new(Self {
+                    store: DelegateQueueStore::new(&workspace_dir),
+                    runtime,
+                    wake: Notify::new(),
+                    started: Mutex::new(false),
+                    running: Mutex::new(HashMap::new()),
+                })
+            })
+            .clone()
+    }
+
+    pub fn store(&self) -> &DelegateQueueStore {
+        &self.store
+    }
+
+    /// Start the polling loop if it is not running yet. Requires a Tokio runtime.
+    pub fn start(self: &Arc<Self>) {
+        {
+            let mut started = self.started.lock();
+            if *started {
+                return;
+            }
+            *started = true;
+        }
+        let worker = Arc::clone(self);
+        tokio::spawn(async move { worker.run().await });
+    }
+
+    /// Resume unfinished work left over from a previous process.
+    pub fn resume_pending(self: &Arc<Self>) {
+        if tokio::runtime::Handle::try_current().is_err() || !self.store.exists() {
+            return;
+        }
+        match self.store.has_pending() {
+            Ok(true) => self.start(),
+            Ok(false) => {}
+            Err(e) => tracing::warn!("delegate queue: failed to inspect pending tasks: {e}"),
+        }
+    }
+
+    /// Start the worker if needed and make it poll immediately.
+    pub fn wake(self: &Arc<Self>) {
+        self.start();
+        self.wake.notify_one();
+    }
+
+    /// Abort a task running in this process. Returns whether one was found.
+    pub fn abort(&self, task_id: &str) -> bool {
+        self.running
+            .lock()
+            .remove(task_id)
+            .map(|handle| handle.abort())
+            .is_some()
+    }
+
+    async fn run(self: Arc<Self>) {
+        let poll_interval =
+            Duration::from_secs(self.runtime.config.delegate_queue.poll_interval_secs.max(1));
+        loop {
+            self.tick().await;
+            tokio::select! {
+                () = tokio::time::sleep(poll_interval) => {}
+                () = self.wake.notified() => {}
+            }
+        }
+    }
+
+    async fn tick(self: &Arc<Self>) {
+        match self.store.recover_expired() {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("delegate queue: recovered {count} interrupted task(s)"),
+            Err(e) => tracing::warn!("delegate queue: lease recovery failed: {e}"),
+        }
+
+        let lease = Duration::from_secs(
+            self.runtime
+                .config
+                .delegate_queue
+                .task_timeout_secs
+                .saturating_add(LEASE_GRACE_SECS),
+        );
+        loop {
+            let agents = Arc::clone(&self.runtime.agents);
+            let claimed = self.store.claim_next(
+                |agent| agents.get(agent).map_or(1, |cfg| cfg.max_concurrent.max(1)),
+                lease,
+            );
+            match claimed {
+                Ok(Some(task)) => self.spawn_task(task),
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::warn!("delegate queue: failed to claim task: {e}");
+                    break;
+                }
+            }
+        }
+
+        self.deliver_callbacks().await;
+    }
+
+    fn spawn_task(self: &Arc<Self>, task: DelegateTask) {
+        let worker = Arc::clone(self);
+        let task_id = task.id.clone();
+        // Hold the lock across spawn so a fast task cannot finish before its
+        // handle is registered.
+        let mut running = self.running.lock();
+        let handle = tokio::spawn(async move {
+            let id = task.id.clone();
+            worker.execute_task(task).await;
+            worker.running.lock().remove(&id);
+            worker.wake.notify_one();
+        });
+        running.insert(task_id, handle.abort_handle());
+    }
+
+    async fn execute_task(&self, task: DelegateTask) {
+        let retry_backoff =
+            Duration::from_secs(self.runtime.config.delegate_queue.retry_backoff_secs);
+        let Some(agent_config) = self.runtime.agents.get(&task.agent).cloned() else {
+            self.record_failure(
+                &task,
+                &format!("Unknown agent '{}'", task.agent),
+                false,
+                retry_backoff,
+            );
+            return;
+        };
+
+        let credential = agent_config
+            .api_key
+            .clone()
+            .or_else(|| self.runtime.fallback_credential.clone());
+        let provider: Box<dyn Provider> = match providers::create_provider_with_options(
+            &agent_config.provider,
+            credential.as_deref(),
+            &self.runtime.provider_runtime_options,
+        ) {
+            Ok(provider) => provider,
+            Err(e) => {
+                let error = format!(
+                    "Failed to create provider '{}' for agent '{}': {e}",
+                    agent_config.provider, task.agent
+                );
+                self.record_failure(&task, &error, false, retry_backoff);
+                return;
+            }
+        };
+
+        let full_prompt = match task.context.as_deref().map(str::trim) {
+            Some(context) if !context.is_empty() => {
+                format!("[Context]\n{context}\n\n[Task]\n{}", task.task)
+            }
+            _ => task.task.clone(),
+        };
+
+        let estop_guard = cancellation::register(StopScope::sub_agent(&task.id, &task.agent));
+        let mut agent_span = delegate::agent_span(&task.agent, &agent_config, "delegate_queue");
+        agent_span.set_attribute("zeroclaw.delegate_queue.task_id", task.id.as_str());
+        agent_span.set_attribute("zeroclaw.delegate_queue.attempt", u64::from(task.attempts));
+
+        let timeout_secs = self.runtime.config.delegate_queue.task_timeout_secs;
+        let run = async {
+            if agent_config.agentic {
+                span::scope(
+                    agent_span.context(),
+                    run_agentic_background(
+                        &task.agent,
+                        &agent_config,
+                        &*provider,
+                        &full_prompt,
+                        &self.runtime.parent_tools,
+                        &self.runtime.config.multimodal,
+                        timeout_secs,
+                    ),
+                )
+                .await
+            } else {
+                run_simple_background(
+                    &task.agent,
+                    &agent_config,
+                    &*provider,
+                    &full_prompt,
+                    &agent_span,
+                    timeout_secs,
+                )
+                .await
+            }
+        };
+        let result = tokio::select! {
+            biased;
+            () = estop_guard.stopped() => {
+                let message = cancellation::stopped_message("Queued delegate task");
+                agent_span.fail(message.clone());
+                self.record_failure(&task, &message, false, retry_backoff);
+                return;
+            }
+            result = run => result,
+        };
+
+        match result {
+            Ok(tool_result) if tool_result.success => {
+                if let Err(e) = self.store.complete(&task.id, &tool_result.output) {
+                    tracing::warn!("delegate queue: failed to record task {}: {e}", task.id);
+                }
+            }
+            Ok(tool_result) => {
+                let error = tool_result
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                agent_span.fail(error.clone());
+                self.record_failure(&task, &error, true, retry_backoff);
+            }
+            Err(e) => {
+                let error = format!("Agent '{}' error: {e}", task.agent);
+                agent_span.fail(error.clone());
+                self.record_failure(&task, &error, true, retry_backoff);
+            }
+        }
+    }
+
+    fn record_failure(&self, task: &DelegateTask, error: &str, retryable: bool, backoff: Duration) {
+        match self.store.fail(&task.id, error, retryable, backoff) {
+            Ok(Some(DelegateTaskStatus::Queued)) => tracing::info!(
+                "delegate queue: task {} attempt {} failed, retrying: {error}",
+                task.id,
+                task.attempts
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("delegate queue: failed to record task {}: {e}", task.id),
+        }
+    }
+
+    async fn deliver_callbacks(&self) {
+        let pending = match self.store.pending_callbacks() {
+            Ok(pending) => pending,
+            Err(e) => {
+                tracing::warn!("delegate queue: failed to load pending callbacks: {e}");
+                return;
+            }
+        };
+        for task in pending {
+            let Some(callback) = task.callback.as_ref() else {
+                continue;
+            };
+            let message = callback_message(&task);
+            match deliver_callback(&self.runtime.config, callback, &message).await {
+                Ok(()) => crate::channels::inject_background_result(
+                    &callback.channel,
+                    &callback.target,
+                    message,
+                ),
+                // The result stays queryable through `delegate_queue status`.
+                Err(e) => tracing::warn!(
+                    "delegate queue: result delivery for task {} to {} failed: {e}",
+                    task.id,
+                    callback.channel
+                ),
+            }
+            if let Err(e) = self.store.mark_notified(&task.id) {
+                tracing::warn!(
+                    "delegate queue: failed to mark task {} notified: {e}",
+                    task.id
+                );
+            }
+        }
+    }
+}
+
+async fn deliver_callback(
+    config: &Config,
+    callback: &DelegateCallback,
+    message: &str,
+) -> anyhow::Result<()> {
+    if let Some(channel) = crate::channels::get_live_channel(&callback.channel) {
+        return channel
+            .send(&SendMessage::new(message, &callback.target))
+            .await;
+    }
+    crate::cron::scheduler::deliver_announcement(
+        config,
+        &callback.channel,
+        &callback.target,
+        message,
+    )
+    .await
+}
+
+fn callback_message(task: &DelegateTask) -> String {
+    let short_id: String = task.id.chars().take(8).collect();
+    match task.status {
+        DelegateTaskStatus::Completed => {
+            let output = task.output.as_deref().unwrap_or("").trim();
+            let output = if output.chars().count() > CALLBACK_OUTPUT_MAX_CHARS {
+                let truncated: String = output.chars().take(CALLBACK_OUTPUT_MAX_CHARS).collect();
+                format!("{truncated}\n…(full result: delegate_queue status {short_id})")
+            } else {
+                output.to_string()
+            };
+            format!(
+                "✅ Background task {short_id} ({}) finished:\n{output}",
+                task.agent
+            )
+        }
+        _ => format!(
+            "❌ Background task {short_id} ({}) failed after {} attempt(s): {}",
+            task.agent,
+            task.attempts,
+            task.error.as_deref().unwrap_or("unknown error")
+        ),
+    }
+}
+
+/// Tool that enqueues delegate work into the persistent queue and inspects it.
+pub struct DelegateQueueTool {
+    worker: Arc<DelegateQueueWorker>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl DelegateQueueTool {
+    pub fn new(worker: Arc<DelegateQueueWorker>, security: Arc<SecurityPolicy>) -> Self {
+        Self { worker, security }
+    }
+
+    fn agents(&self) -> &HashMap<String, DelegateAgentConfig> {
+        &self.worker.runtime.agents
+    }
+
+    fn enqueue(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        let agent = required_str(args, "agent")?;
+        let task = required_str(args, "task")?;
+        let Some(agent_config) = self.agents().get(agent) else {
+            let mut available: Vec<&str> = self.agents().keys().map(String::as_str).collect();
+            available.sort_unstable();
+            return Ok(failure(format!(
+                "Unknown agent '{agent}'. Available agents: {}",
+                if available.is_empty() {
+                    "(none configured)".to_string()
+                } else {
+                    available.join(", ")
+                }
+            )));
+        };
+
+        let callback = match args.get("notify") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(value) => match serde_json::from_value::<DelegateCallback>(value.clone()) {
+                Ok(callback)
+                    if !callback.channel.trim().is_empty()
+                        && !callback.target.trim().is_empty() =>
+                {
+                    Some(callback)
+                }
+                _ => {
+                    return Ok(failure(
+                        "'notify' must be an object with non-empty 'channel' and 'target'",
+                    ))
+                }
+            },
+        };
+
+        let queued = self.worker.store().enqueue(NewDelegateTask {
+            agent: agent.to_string(),
+            task: task.to_string(),
+            context: args
+                .get("context")
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|context| !context.is_empty())
+                .map(str::to_string),
+            priority: args
+                .get("priority")
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or(0),
+            max_retries: agent_config.max_retries,
+            callback,
+        })?;
+        self.worker.wake();
+
+        Ok(ToolResult {
+            success: true,
+            output: json!({
+                "task_id": queued.id,
+                "agent": queued.agent,
+                "priority": queued.priority,
+                "status": queued.status,
+                "notify": queued.callback,
+                "message": "Task queued. The result is reported back when it finishes; use delegate_queue status to check progress."
+            })
+            .to_string(),
+            error: None,
+        })
+    }
+
+    fn status(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        let task_id = required_str(args, "task_id")?;
+        Ok(match self.worker.store().get(task_id)? {
+            Some(task) => ToolResult {
+                success: true,
+                output: serde_json::to_string_pretty(&task)?,
+                error: None,
+            },
+            None => failure(format!("Unknown task '{task_id}'")),
+        })
+    }
+
+    fn list(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        let status = match args.get("status").and_then(serde_json::Value::as_str) {
+            Some(raw) => match DelegateTaskStatus::parse(raw) {
+                Some(status) => Some(status),
+                None => return Ok(failure(format!("Unknown status filter '{raw}'"))),
+            },
+            None => None,
+        };
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|limit| usize::try_from(limit).ok())
+            .unwrap_or(DEFAULT_LIST_LIMIT);
+        let tasks = self.worker.store().list(status, limit)?;
+        let summaries: Vec<serde_json::Value> = tasks
+            .iter()
+            .map(|task| {
+                json!({
+                    "task_id": task.id,
+                    "agent": task.agent,
+                    "task": task.task,
+                    "priority": task.priority,
+                    "status": task.status,
+                    "attempts": task.attempts,
+                    "created_at": task.created_at.to_rfc3339(),
+                    "error": task.error,
+                })
+            })
+            .collect();
+        Ok(ToolResult {
+            success: true,
+            output: json!({ "tasks": summaries, "count": summaries.len() }).to_string(),
+            error: None,
+        })
+    }
+
+    fn cancel(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        let task_id = required_str(args, "task_id")?;
+        if !self.worker.store().cancel(task_id)? {
+            return Ok(failure(format!(
+                "Task '{task_id}' is unknown or already finished"
+            )));
+        }
+        let aborted = self.worker.abort(task_id);
+        Ok(ToolResult {
+            success: true,
+            output: json!({
+                "task_id": task_id,
+                "status": DelegateTaskStatus::Cancelled,
+                "aborted_running": aborted,
+            })
+            .to_string(),
+            error: None,
+        })
+    }
+}
+
+fn required_str<'a>(args: &'a serde_json::Value, key: &str) -> anyhow::Result<&'a str> {
+    args.get(key)
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Missing '{key}' parameter"))
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+#[async_trait]
+impl Tool for DelegateQueueTool {
+    fn name(&self) -> &str {
+        "delegate_queue"
+    }
+
+    fn description(&self) -> &str {
+        "Queue long-running work for a delegate agent. Queued tasks are persisted, run in \
+         priority order with per-agent concurrency limits and retries, survive restarts, and \
+         report their result back to this conversation when done. \
+         Actions: enqueue, status, list, cancel."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut agent_names: Vec<&str> = self.agents().keys().map(String::as_str).collect();
+        agent_names.sort_unstable();
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["enqueue", "status", "list", "cancel"],
+                    "description": "Queue operation to perform"
+                },
+                "agent": {
+                    "type": "string",
+                    "description": format!(
+                        "enqueue: agent to run the task. Available: {}",
+                        if agent_names.is_empty() {
+                            "(none configured)".to_string()
+                        } else {
+                            agent_names.join(", ")
+                        }
+                    )
+                },
+                "task": {
+                    "type": "string",
+                    "description": "enqueue: the task/prompt for the agent"
+                },
+                "context": {
+                    "type": "string",
+                    "description": "enqueue: optional context to prepend"
+                },
+                "priority": {
+                    "type": "integer",
+                    "description": "enqueue: higher runs first (default 0)"
+                },
+                "notify": {
+                    "type": "object",
+                    "description": "enqueue: channel conversation that receives the result. Filled in automatically for channel conversations.",
+                    "properties": {
+                        "channel": { "type": "string" },
+                        "target": { "type": "string" }
+                    },
+                    "required": ["channel", "target"]
+                },
+                "task_id": {
+                    "type": "string",
+                    "description": "status/cancel: task ID returned by enqueue"
+                },
+                "status": {
+                    "type": "string",
+                    "enum": ["queued", "running", "completed", "failed", "cancelled"],
+                    "description": "list: only show tasks in this state"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "list: maximum tasks to return (default 20)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = required_str(&args, "action")?;
+        if matches!(action, "enqueue" | "cancel") {
+            if let Err(error) = self
+                .security
+                .enforce_tool_operation(ToolOperation::Act, "delegate_queue")
+            {
+                return Ok(failure(error));
+            }
+        }
+        match action {
+            "enqueue" => self.enqueue(&args),
+            "status" => self.status(&args),
+            "list" => self.list(&args),
+            "cancel" => self.cancel(&args),
+            other => Ok(failure(format!(
+                "Unknown action '{other}'. Use enqueue, status, list or cancel."
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn agent_config() -> DelegateAgentConfig {
+        DelegateAgentConfig {
+            provider: "ollama".to_string(),
+            model: "llama3".to_string(),
+            system_prompt: None,
+            api_key: None,
+            temperature: None,
+            max_depth: 3,
+            agentic: false,
+            allowed_tools: Vec::new(),
+            max_iterations: 10,
+            max_concurrent: 1,
+            max_retries: 2,
+        }
+    }
+
+    fn make_tool(tmp: &TempDir, security: SecurityPolicy) -> DelegateQueueTool {
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut agents = HashMap::new();
+        agents.insert("researcher".to_string(), agent_config());
+        let worker = DelegateQueueWorker::shared(DelegateQueueRuntime {
+            config: Arc::new(config),
+            agents: Arc::new(agents),
+            fallback_credential: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            parent_tools: Arc::new(Vec::new()),
+        });
+        // Keep tests deterministic: mark the worker as running without a loop.
+        *worker.started.lock() = true;
+        DelegateQueueTool::new(worker, Arc::new(security))
+    }
+
+    #[tokio::test]
+    async fn enqueue_persists_task_with_agent_retries() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(&tmp, SecurityPolicy::default());
+        let result = tool
+            .execute(json!({
+                "action": "enqueue",
+                "agent": "researcher",
+                "task": "compare async runtimes",
+                "priority": 3,
+                "notify": {"channel": "telegram", "target": "-100123"}
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let task_id = output["task_id"].as_str().unwrap();
+
+        let task = DelegateQueueStore::new(tmp.path())
+            .get(task_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.priority, 3);
+        assert_eq!(task.max_retries, 2);
+        assert_eq!(task.status, DelegateTaskStatus::Queued);
+        assert_eq!(task.callback.unwrap().channel, "telegram");
+    }
+
+    #[tokio::test]
+    async fn enqueue_rejects_unknown_agent() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(&tmp, SecurityPolicy::default());
+        let result = tool
+            .execute(json!({"action": "enqueue", "agent": "ghost", "task": "boo"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("researcher"));
+    }
+
+    #[tokio::test]
+    async fn enqueue_blocked_in_read_only_mode() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(
+            &tmp,
+            SecurityPolicy {
+                autonomy: AutonomyLevel::ReadOnly,
+                ..SecurityPolicy::default()
+            },
+        );
+        let result = tool
+            .execute(json!({"action": "enqueue", "agent": "researcher", "task": "x"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        let listed = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(listed.success);
+    }
+
+    #[tokio::test]
+    async fn cancel_marks_queued_task_cancelled() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(&tmp, SecurityPolicy::default());
+        let queued = tool
+            .execute(json!({"action": "enqueue", "agent": "researcher", "task": "x"}))
+            .await
+            .unwrap();
+        let output: serde_json::Value = serde_json::from_str(&queued.output).unwrap();
+        let task_id = output["task_id"].as_str().unwrap();
+
+        let cancelled = tool
+            .execute(json!({"action": "cancel", "task_id": task_id}))
+            .await
+            .unwrap();
+        assert!(cancelled.success);
+        let again = tool
+            .execute(json!({"action": "cancel", "task_id": task_id}))
+            .await
+            .unwrap();
+        assert!(!again.success);
+
+        let listed = tool
+            .execute(json!({"action": "list", "status": "cancelled"}))
+            .await
+            .unwrap();
+        let output: serde_json::Value = serde_json::from_str(&listed.output).unwrap();
+        assert_eq!(output["count"], 1);
+    }
+
+    #[test]
+    fn callback_message_reports_failure_reason() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let task = store
+            .enqueue(NewDelegateTask {
+                agent: "researcher".into(),
+                task: "x".into(),
+                context: None,
+                priority: 0,
+                max_retries: 0,
+                callback: None,
+            })
+            .unwrap();
+        store.claim_next(|_| 1, Duration::from_secs(60)).unwrap();
+        store
+            .fail(&task.id, "quota exceeded", true, Duration::ZERO)
+            .unwrap();
+
+        let failed = store.get(&task.id).unwrap().unwrap();
+        let message = callback_message(&failed);
+        assert!(message.contains("failed after 1 attempt(s): quota exceeded"));
+        assert!(message.contains("researcher"));
+    }
+}
@@ -0,0 +1,663 @@
+//! SQLite-backed store for the persistent delegate task queue.
+//!
+//! Tasks live in `<workspace>/delegate/queue.db` so queued and in-flight
+//! delegated work survives restarts. Running tasks hold a lease; a task whose
+//! lease expires (the process died mid-run) is re-queued or failed depending
+//! on its remaining retries.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+const MAX_TASK_OUTPUT_BYTES: usize = 32 * 1024;
+const TRUNCATED_OUTPUT_MARKER: &str = "\n...[truncated]";
+
+/// Lifecycle state of a queued delegate task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegateTaskStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DelegateTaskStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "queued" => Some(Self::Queued),
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DelegateTaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Channel conversation that receives the task result once it finishes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegateCallback {
+    pub channel: String,
+    pub target: String,
+}
+
+/// Parameters for a new queued task.
+#[derive(Debug, Clone)]
+pub struct NewDelegateTask {
+    pub agent: String,
+    pub task: String,
+    pub context: Option<String>,
+    pub priority: i64,
+    pub max_retries: u32,
+    pub callback: Option<DelegateCallback>,
+}
+
+/// A persisted delegate task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegateTask {
+    pub id: String,
+    pub agent: String,
+    pub task: String,
+    pub context: Option<String>,
+    pub priority: i64,
+    pub status: DelegateTaskStatus,
+    pub attempts: u32,
+    pub max_retries: u32,
+    pub callback: Option<DelegateCallback>,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Handle to the queue database of one workspace.
+#[derive(Debug, Clone)]
+pub struct DelegateQueueStore {
+    db_path: PathBuf,
+}
+
+const TASK_COLUMNS: &str = "id, agent, task, context, priority, status, attempts, max_retries, \
+     callback, output, error, created_at, updated_at, finished_at";
+
+impl DelegateQueueStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            db_path: workspace_dir.join("delegate").join("queue.db"),
+        }
+    }
+
+    /// Whether the queue database exists yet.
+    pub fn exists(&self) -> bool {
+        self.db_path.exists()
+    }
+
+    pub fn enqueue(&self, new_task: NewDelegateTask) -> Result<DelegateTask> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let callback = new_task
+            .callback
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO delegate_tasks (
+                    id, agent, task, context, priority, status, attempts, max_retries,
+                    callback, created_at, updated_at, next_attempt_at
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, 'queued', 0, ?6, ?7, ?8, ?8, ?9)",
+                params![
+                    id,
+                    new_task.agent,
+                    new_task.task,
+                    new_task.context,
+                    new_task.priority,
+                    new_task.max_retries,
+                    callback,
+                    now.to_rfc3339(),
+                    now.timestamp(),
+                ],
+            )
+            .context("Failed to insert delegate task")?;
+            Ok(())
+        })?;
+        self.get(&id)?
+            .ok_or_else(|| anyhow::anyhow!("Delegate task {id} missing after insert"))
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<DelegateTask>> {
+        self.with_connection(|conn| {
+            conn.query_row(
+                &format!("SELECT {TASK_COLUMNS} FROM delegate_tasks WHERE id = ?1"),
+                params![id],
+                map_task_row,
+            )
+            .optional()
+            .context("Failed to load delegate task")
+        })
+    }
+
+    /// Most recent tasks first, optionally filtered by status.
+    pub fn list(
+        &self,
+        status: Option<DelegateTaskStatus>,
+        limit: usize,
+    ) -> Result<Vec<DelegateTask>> {
+        let limit = i64::try_from(limit.max(1)).unwrap_or(i64::MAX);
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {TASK_COLUMNS} FROM delegate_tasks
+                 WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY rowid DESC LIMIT ?2"
+            ))?;
+            let rows = stmt.query_map(
+                params![status.map(DelegateTaskStatus::as_str), limit],
+                map_task_row,
+            )?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to list delegate tasks")
+        })
+    }
+
+    /// Cancel a task that has not finished yet. Returns `false` when the task
+    /// is unknown or already terminal.
+    pub fn cancel(&self, id: &str) -> Result<bool> {
+        let now = Utc::now().to_rfc3339();
+        self.with_connection(|conn| {
+            let changed = conn.execute(
+                "UPDATE delegate_tasks
+                 SET status = 'cancelled', updated_at = ?2, finished_at = ?2, notified = 1
+                 WHERE id = ?1 AND status IN ('queued', 'running')",
+                params![id, now],
+            )?;
+            Ok(changed > 0)
+        })
+    }
+
+    /// Whether any task still needs work or a result callback.
+    pub fn has_pending(&self) -> Result<bool> {
+        self.with_connection(|conn| {
+            let pending: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM delegate_tasks
+                 WHERE status IN ('queued', 'running')
+                    OR (status IN ('completed', 'failed') AND callback IS NOT NULL AND notified = 0)",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok(pending > 0)
+        })
+    }
+
+    /// Atomically claim the highest-priority runnable task whose agent is
+    /// below its concurrency limit, marking it running under a lease.
+    pub fn claim_next(
+        &self,
+        limit_for_agent: impl Fn(&str) -> usize,
+        lease: Duration,
+    ) -> Result<Option<DelegateTask>> {
+        let now = Utc::now();
+        let lease_secs = i64::try_from(lease.as_secs()).unwrap_or(i64::MAX / 2);
+        self.with_connection(|conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let claimed = {
+                let mut running = std::collections::HashMap::<String, usize>::new();
+                let mut stmt = tx.prepare(
+                    "SELECT agent, COUNT(*) FROM delegate_tasks
+                     WHERE status = 'running' GROUP BY agent",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })?;
+                for row in rows {
+                    let (agent, count) = row?;
+                    running.insert(agent, usize::try_from(count).unwrap_or(usize::MAX));
+                }
+
+                let mut stmt = tx.prepare(
+                    "SELECT id, agent FROM delegate_tasks
+                     WHERE status = 'queued' AND next_attempt_at <= ?1
+                     ORDER BY priority DESC, rowid ASC",
+                )?;
+                let candidates = stmt
+                    .query_map(params![now.timestamp()], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+
+                candidates.into_iter().find_map(|(id, agent)| {
+                    let active = running.get(&agent).copied().unwrap_or(0);
+                    (active < limit_for_agent(&agent)).then_some(id)
+                })
+            };
+
+            let Some(id) = claimed else {
+                return Ok(None);
+            };
+            tx.execute(
+                "UPDATE delegate_tasks
+                 SET status = 'running', attempts = attempts + 1, updated_at = ?2,
+                     lease_expires_at = ?3
+                 WHERE id = ?1",
+                params![
+                    id,
+                    now.to_rfc3339(),
+                    now.timestamp().saturating_add(lease_secs)
+                ],
+            )?;
+            let task = tx.query_row(
+                &format!("SELECT {TASK_COLUMNS} FROM delegate_tasks WHERE id = ?1"),
+                params![id],
+                map_task_row,
+            )?;
+            tx.commit()?;
+            Ok(Some(task))
+        })
+    }
+
+    /// Record a successful run. Ignored when the task was cancelled meanwhile.
+    pub fn complete(&self, id: &str, output: &str) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE delegate_tasks
+                 SET status = 'completed', output = ?2, error = NULL, updated_at = ?3,
+                     finished_at = ?3, lease_expires_at = NULL
+                 WHERE id = ?1 AND status = 'running'",
+                params![id, truncate_task_output(output), now],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record a failed attempt. The task is re-queued with exponential backoff
+    /// while retries remain, otherwise marked failed. Returns the new status,
+    /// or `None` when the task was no longer running.
+    pub fn fail(
+        &self,
+        id: &str,
+        error: &str,
+        retryable: bool,
+        retry_backoff: Duration,
+    ) -> Result<Option<DelegateTaskStatus>> {
+        let now = Utc::now();
+        self.with_connection(|conn| {
+            let attempts: Option<(u32, u32)> = conn
+                .query_row(
+                    "SELECT attempts, max_retries FROM delegate_tasks
+                     WHERE id = ?1 AND status = 'running'",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            let Some((attempts, max_retries)) = attempts else {
+                return Ok(None);
+            };
+
+            if retryable && attempts <= max_retries {
+                let delay = retry_delay_secs(retry_backoff, attempts);
+                conn.execute(
+                    "UPDATE delegate_tasks
+                     SET status = 'queued', error = ?2, updated_at = ?3,
+                         next_attempt_at = ?4, lease_expires_at = NULL
+                     WHERE id = ?1",
+                    params![
+                        id,
+                        error,
+                        now.to_rfc3339(),
+                        now.timestamp().saturating_add(delay)
+                    ],
+                )?;
+                Ok(Some(DelegateTaskStatus::Queued))
+            } else {
+                conn.execute(
+                    "UPDATE delegate_tasks
+                     SET status = 'failed', error = ?2, updated_at = ?3, finished_at = ?3,
+                         lease_expires_at = NULL
+                     WHERE id = ?1",
+                    params![id, error, now.to_rfc3339()],
+                )?;
+                Ok(Some(DelegateTaskStatus::Failed))
+            }
+        })
+    }
+
+    /// Re-queue running tasks whose lease expired, failing those without
+    /// retries left. Returns the number of recovered tasks.
+    pub fn recover_expired(&self) -> Result<usize> {
+        let now = Utc::now();
+        self.with_connection(|conn| {
+            let requeued = conn.execute(
+                "UPDATE delegate_tasks
+                 SET status = 'queued', error = 'interrupted before completion',
+                     updated_at = ?1, next_attempt_at = ?2, lease_expires_at = NULL
+                 WHERE status = 'running' AND lease_expires_at < ?2 AND attempts <= max_retries",
+                params![now.to_rfc3339(), now.timestamp()],
+            )?;
+            let failed = conn.execute(
+                "UPDATE delegate_tasks
+                 SET status = 'failed', error = 'interrupted before completion',
+                     updated_at = ?1, finished_at = ?1, lease_expires_at = NULL
+                 WHERE status = 'running' AND lease_expires_at < ?2",
+                params![now.to_rfc3339(), now.timestamp()],
+            )?;
+            Ok(requeued + failed)
+        })
+    }
+
+    /// Finished tasks whose result callback has not been delivered yet.
+    pub fn pending_callbacks(&self) -> Result<Vec<DelegateTask>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {TASK_COLUMNS} FROM delegate_tasks
+                 WHERE status IN ('completed', 'failed') AND callback IS NOT NULL AND notified = 0
+                 ORDER BY rowid ASC"
+            ))?;
+            let rows = stmt.query_map([], map_task_row)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to load pending delegate callbacks")
+        })
+    }
+
+    pub fn mark_notified(&self, id: &str) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE delegate_tasks SET notified = 1 WHERE id = ?1",
+                params![id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "Failed to create delegate queue directory: {}",
+                    parent.display()
+                )
+            })?;
+        }
+
+        let mut conn = Connection::open(&self.db_path).with_context(|| {
+            format!(
+                "Failed to open delegate queue DB: {}",
+                self.db_path.display()
+            )
+        })?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS delegate_tasks (
+                id               TEXT PRIMARY KEY,
+                agent            TEXT NOT NULL,
+                task             TEXT NOT NULL,
+                context          TEXT,
+                priority         INTEGER NOT NULL DEFAULT 0,
+                status           TEXT NOT NULL,
+                attempts         INTEGER NOT NULL DEFAULT 0,
+                max_retries      INTEGER NOT NULL DEFAULT 0,
+                callback         TEXT,
+                notified         INTEGER NOT NULL DEFAULT 0,
+                output           TEXT,
+                error            TEXT,
+                created_at       TEXT NOT NULL,
+                updated_at       TEXT NOT NULL,
+                finished_at      TEXT,
+                next_attempt_at  INTEGER NOT NULL,
+                lease_expires_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_delegate_tasks_status
+                ON delegate_tasks(status, priority);",
+        )
+        .context("Failed to initialize delegate queue schema")?;
+
+        f(&mut conn)
+    }
+}
+
+fn map_task_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DelegateTask> {
+    let status: String = row.get(5)?;
+    let callback: Option<String> = row.get(8)?;
+    Ok(DelegateTask {
+        id: row.get(0)?,
+        agent: row.get(1)?,
+        task: row.get(2)?,
+        context: row.get(3)?,
+        priority: row.get(4)?,
+        status: DelegateTaskStatus::parse(&status).unwrap_or(DelegateTaskStatus::Failed),
+        attempts: row.get(6)?,
+        max_retries: row.get(7)?,
+        callback: callback.and_then(|raw| serde_json::from_str(&raw).ok()),
+        output: row.get(9)?,
+        error: row.get(10)?,
+        created_at: parse_timestamp(&row.get::<_, String>(11)?),
+        updated_at: parse_timestamp(&row.get::<_, String>(12)?),
+        finished_at: row
+            .get::<_, Option<String>>(13)?
+            .map(|raw| parse_timestamp(&raw)),
+    })
+}
+
+fn parse_timestamp(raw: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
+fn retry_delay_secs(base: Duration, attempts: u32) -> i64 {
+    let base = i64::try_from(base.as_secs()).unwrap_or(i64::MAX);
+    let exponent = attempts.saturating_sub(1).min(16);
+    base.saturating_mul(1_i64 << exponent)
+}
+
+fn truncate_task_output(output: &str) -> String {
+    if output.len() <= MAX_TASK_OUTPUT_BYTES {
+        return output.to_string();
+    }
+    let mut end = MAX_TASK_OUTPUT_BYTES - TRUNCATED_OUTPUT_MARKER.len();
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{TRUNCATED_OUTPUT_MARKER}", &output[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn new_task(agent: &str, task: &str, priority: i64, max_retries: u32) -> NewDelegateTask {
+        NewDelegateTask {
+            agent: agent.into(),
+            task: task.into(),
+            context: None,
+            priority,
+            max_retries,
+            callback: None,
+        }
+    }
+
+    #[test]
+    fn enqueue_and_get_round_trip_callback() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let mut request = new_task("researcher", "survey crates", 0, 1);
+        request.callback = Some(DelegateCallback {
+            channel: "telegram".into(),
+            target: "-100123".into(),
+        });
+        let task = store.enqueue(request).unwrap();
+
+        let loaded = store.get(&task.id).unwrap().unwrap();
+        assert_eq!(loaded.status, DelegateTaskStatus::Queued);
+        assert_eq!(loaded.attempts, 0);
+        assert_eq!(loaded.callback.unwrap().target, "-100123");
+        assert!(store.has_pending().unwrap());
+    }
+
+    #[test]
+    fn claim_orders_by_priority_then_insertion() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let low = store.enqueue(new_task("a", "low", 0, 0)).unwrap();
+        let high = store.enqueue(new_task("b", "high", 5, 0)).unwrap();
+
+        let lease = Duration::from_secs(60);
+        let first = store.claim_next(|_| 4, lease).unwrap().unwrap();
+        assert_eq!(first.id, high.id);
+        assert_eq!(first.status, DelegateTaskStatus::Running);
+        assert_eq!(first.attempts, 1);
+        let second = store.claim_next(|_| 4, lease).unwrap().unwrap();
+        assert_eq!(second.id, low.id);
+        assert!(store.claim_next(|_| 4, lease).unwrap().is_none());
+    }
+
+    #[test]
+    fn claim_respects_per_agent_concurrency() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        store.enqueue(new_task("a", "one", 0, 0)).unwrap();
+        store.enqueue(new_task("a", "two", 0, 0)).unwrap();
+        let other = store.enqueue(new_task("b", "three", 0, 0)).unwrap();
+
+        let limit = |agent: &str| if agent == "a" { 1 } else { 2 };
+        let lease = Duration::from_secs(60);
+        let first = store.claim_next(limit, lease).unwrap().unwrap();
+        assert_eq!(first.agent, "a");
+        let second = store.claim_next(limit, lease).unwrap().unwrap();
+        assert_eq!(second.id, other.id);
+        assert!(store.claim_next(limit, lease).unwrap().is_none());
+
+        store.complete(&first.id, "done").unwrap();
+        let third = store.claim_next(limit, lease).unwrap().unwrap();
+        assert_eq!(third.task, "two");
+    }
+
+    #[test]
+    fn fail_requeues_until_retries_exhausted() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let task = store.enqueue(new_task("a", "flaky", 0, 1)).unwrap();
+        let lease = Duration::from_secs(60);
+
+        store.claim_next(|_| 1, lease).unwrap().unwrap();
+        let status = store.fail(&task.id, "boom", true, Duration::ZERO).unwrap();
+        assert_eq!(status, Some(DelegateTaskStatus::Queued));
+
+        let retry = store.claim_next(|_| 1, lease).unwrap().unwrap();
+        assert_eq!(retry.attempts, 2);
+        let status = store.fail(&task.id, "boom", true, Duration::ZERO).unwrap();
+        assert_eq!(status, Some(DelegateTaskStatus::Failed));
+
+        let failed = store.get(&task.id).unwrap().unwrap();
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+        assert!(failed.finished_at.is_some());
+    }
+
+    #[test]
+    fn retry_backoff_delays_next_claim() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let task = store.enqueue(new_task("a", "slow", 0, 3)).unwrap();
+        let lease = Duration::from_secs(60);
+
+        store.claim_next(|_| 1, lease).unwrap().unwrap();
+        store
+            .fail(&task.id, "boom", true, Duration::from_secs(600))
+            .unwrap();
+        assert!(store.claim_next(|_| 1, lease).unwrap().is_none());
+    }
+
+    #[test]
+    fn expired_leases_are_recovered() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let retryable = store.enqueue(new_task("a", "retry", 1, 1)).unwrap();
+        let exhausted = store.enqueue(new_task("b", "last", 0, 0)).unwrap();
+
+        store.claim_next(|_| 1, Duration::ZERO).unwrap().unwrap();
+        store.claim_next(|_| 1, Duration::ZERO).unwrap().unwrap();
+        std::thread::sleep(Duration::from_millis(1100));
+
+        assert_eq!(store.recover_expired().unwrap(), 2);
+        let requeued = store.get(&retryable.id).unwrap().unwrap();
+        assert_eq!(requeued.status, DelegateTaskStatus::Queued);
+        let failed = store.get(&exhausted.id).unwrap().unwrap();
+        assert_eq!(failed.status, DelegateTaskStatus::Failed);
+    }
+
+    #[test]
+    fn cancelled_task_ignores_late_completion() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let task = store.enqueue(new_task("a", "cancel me", 0, 0)).unwrap();
+        store.claim_next(|_| 1, Duration::from_secs(60)).unwrap();
+
+        assert!(store.cancel(&task.id).unwrap());
+        assert!(!store.cancel(&task.id).unwrap());
+        store.complete(&task.id, "too late").unwrap();
+
+        let loaded = store.get(&task.id).unwrap().unwrap();
+        assert_eq!(loaded.status, DelegateTaskStatus::Cancelled);
+        assert!(loaded.output.is_none());
+    }
+
+    #[test]
+    fn pending_callbacks_until_notified() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let mut request = new_task("a", "notify", 0, 0);
+        request.callback = Some(DelegateCallback {
+            channel: "slack".into(),
+            target: "C123".into(),
+        });
+        let task = store.enqueue(request).unwrap();
+        store.claim_next(|_| 1, Duration::from_secs(60)).unwrap();
+        store.complete(&task.id, "result").unwrap();
+
+        let pending = store.pending_callbacks().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].output.as_deref(), Some("result"));
+
+        store.mark_notified(&task.id).unwrap();
+        assert!(store.pending_callbacks().unwrap().is_empty());
+        assert!(!store.has_pending().unwrap());
+    }
+
+    #[test]
+    fn list_filters_by_status() {
+        let tmp = TempDir::new().unwrap();
+        let store = DelegateQueueStore::new(tmp.path());
+        let done = store.enqueue(new_task("a", "done", 0, 0)).unwrap();
+        store.enqueue(new_task("a", "waiting", 0, 0)).unwrap();
+        store.claim_next(|_| 1, Duration::from_secs(60)).unwrap();
+        store.complete(&done.id, "ok").unwrap();
+
+        let queued = store.list(Some(DelegateTaskStatus::Queued), 10).unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].task, "waiting");
+        assert_eq!(store.list(None, 10).unwrap().len(), 2);
+    }
+}
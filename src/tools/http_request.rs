@@ -1,8 +1,9 @@
 use super::traits::{Tool, ToolResult};
 use super::url_validation::{
-    normalize_allowed_domains, validate_url, DomainPolicy, UrlSchemePolicy,
+    normalize_allowed_domains, normalize_domain, validate_url, DomainPolicy, UrlSchemePolicy,
 };
 use crate::config::{HttpRequestCredentialProfile, UrlAccessConfig};
+use crate::security::cancellation::{self, StopScope};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -378,10 +379,29 @@ impl Tool for HttpRequestTool {
             }
         }
 
-        match self
-            .execute_request(&url, method, request_headers, body)
-            .await
-        {
+        let host = normalize_domain(&url).unwrap_or_default();
+        let estop_guard = cancellation::register(StopScope::http_request(self.name(), &host));
+        if estop_guard.is_stopped() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(cancellation::stopped_message("HTTP request")),
+            });
+        }
+
+        let response = tokio::select! {
+            biased;
+            () = estop_guard.stopped() => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(cancellation::stopped_message("HTTP request")),
+                });
+            }
+            response = self.execute_request(&url, method, request_headers, body) => response,
+        };
+
+        match response {
             Ok(response) => {
                 let status = response.status();
                 let status_code = status.as_u16();
@@ -408,7 +428,18 @@ impl Tool for HttpRequestTool {
                 let headers_text = Self::redact_sensitive_values(&headers_text, &sensitive_values);
 
                 // Get response body with size limit
-                let response_text = match response.text().await {
+                let body = tokio::select! {
+                    biased;
+                    () = estop_guard.stopped() => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(cancellation::stopped_message("HTTP request")),
+                        });
+                    }
+                    body = response.text() => body,
+                };
+                let response_text = match body {
                     Ok(text) => self.truncate_response(&text),
                     Err(e) => format!("[Failed to read response body: {e}]"),
                 };
@@ -446,7 +477,7 @@ impl Tool for HttpRequestTool {
 mod tests {
     use super::*;
     use crate::security::{AutonomyLevel, SecurityPolicy};
-    use crate::tools::url_validation::{is_private_or_local_host, normalize_domain};
+    use crate::tools::url_validation::is_private_or_local_host;
 
     fn test_tool(allowed_domains: Vec<&str>) -> HttpRequestTool {
         let security = Arc::new(SecurityPolicy {
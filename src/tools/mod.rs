@@ -17,6 +17,7 @@
 
 pub mod agents_ipc;
 pub mod apply_patch;
+pub mod artifact_read;
 pub mod ask_user;
 pub mod auth_profile;
 pub mod linear;
@@ -33,6 +34,8 @@ pub mod cron_runs;
 pub mod cron_update;
 pub mod delegate;
 pub mod delegate_coordination_status;
+pub mod delegate_queue;
+pub mod delegate_queue_store;
 pub mod docx_read;
 #[cfg(feature = "channel-lark")]
 pub mod feishu_doc;
@@ -57,11 +60,13 @@ pub mod memory_forget;
 pub mod memory_recall;
 pub mod memory_store;
 pub mod model_routing_config;
+pub mod notify;
 pub mod pdf_read;
 pub mod process;
 pub mod proxy_config;
 pub mod pushover;
 pub mod quota_tools;
+pub mod reply_edit;
 pub mod schedule;
 pub mod schema;
 pub mod screenshot;
@@ -81,6 +86,7 @@ pub mod web_search_config;
 pub mod web_search_tool;
 
 pub use apply_patch::ApplyPatchTool;
+pub use artifact_read::ArtifactReadTool;
 pub use browser::{BrowserTool, ComputerUseConfig};
 pub use browser_open::BrowserOpenTool;
 pub use composio::ComposioTool;
@@ -93,6 +99,7 @@ pub use cron_runs::CronRunsTool;
 pub use cron_update::CronUpdateTool;
 pub use delegate::DelegateTool;
 pub use delegate_coordination_status::DelegateCoordinationStatusTool;
+pub use delegate_queue::DelegateQueueTool;
 pub use docx_read::DocxReadTool;
 #[cfg(feature = "channel-lark")]
 pub use feishu_doc::FeishuDocTool;
@@ -118,7 +125,9 @@ pub use model_routing_config::ModelRoutingConfigTool;
 pub use pdf_read::PdfReadTool;
 pub use process::ProcessTool;
 pub use proxy_config::ProxyConfigTool;
+pub use notify::NotifyTool;
 pub use pushover::PushoverTool;
+pub use reply_edit::ReplyEditTool;
 pub use schedule::ScheduleTool;
 #[allow(unused_imports)]
 pub use schema::{CleaningStrategy, SchemaCleanr};
@@ -308,8 +317,13 @@ pub fn all_tools_with_runtime(
             security.clone(),
             workspace_dir.to_path_buf(),
         )),
+        Arc::new(ReplyEditTool::new(security.clone())),
     ];
 
+    if root_config.notifications.enabled {
+        tool_arcs.push(Arc::new(NotifyTool::new(config.clone(), security.clone())));
+    }
+
     if has_shell_access {
         tool_arcs.push(Arc::new(ShellTool::new_with_syscall_detector(
             security.clone(),
@@ -456,7 +470,14 @@ pub fn all_tools_with_runtime(
     }
 
     // Add delegation and sub-agent orchestration tools when agents are configured
-    if !agents.is_empty() {
+    // locally or reachable through coordination peers.
+    let remote_agents_configured = root_config.coordination.enabled
+        && root_config
+            .coordination
+            .peers
+            .iter()
+            .any(|peer| !peer.agents.is_empty());
+    if !agents.is_empty() || remote_agents_configured {
         let delegate_agents: HashMap<String, DelegateAgentConfig> = agents
             .iter()
             .map(|(name, cfg)| (name.clone(), cfg.clone()))
@@ -524,6 +545,30 @@ pub fn all_tools_with_runtime(
                 }
             }
 
+            if !root_config.coordination.peers.is_empty() {
+                // Inbound tasks from peers run on a local-only delegate so they
+                // are never forwarded to another node.
+                let executor = DelegateTool::new_with_options(
+                    delegate_agents.clone(),
+                    delegate_fallback_credential.clone(),
+                    security.clone(),
+                    provider_runtime_options.clone(),
+                )
+                .with_parent_tools(parent_tools.clone())
+                .with_multimodal_config(root_config.multimodal.clone())
+                .with_coordination_bus(coordination_bus.clone(), coordination_lead_agent.clone());
+                let remote = Arc::new(
+                    crate::coordination::remote::RemoteCoordinator::new(
+                        &root_config.coordination,
+                        coordination_bus.clone(),
+                        coordination_lead_agent.clone(),
+                    )
+                    .with_executor(Arc::new(executor), agents.keys().cloned()),
+                );
+                crate::coordination::remote::install(remote.clone());
+                delegate_tool = delegate_tool.with_remote_coordinator(remote);
+            }
+
             delegate_tool = delegate_tool
                 .with_coordination_bus(coordination_bus.clone(), coordination_lead_agent);
             tool_arcs.push(Arc::new(delegate_tool));
@@ -536,21 +581,38 @@ pub fn all_tools_with_runtime(
             tool_arcs.push(Arc::new(delegate_tool));
         }
 
-        let subagent_registry = Arc::new(SubAgentRegistry::new());
-        tool_arcs.push(Arc::new(SubAgentSpawnTool::new(
-            delegate_agents,
-            delegate_fallback_credential,
-            security.clone(),
-            provider_runtime_options,
-            subagent_registry.clone(),
-            parent_tools,
-            root_config.multimodal.clone(),
-        )));
-        tool_arcs.push(Arc::new(SubAgentListTool::new(subagent_registry.clone())));
-        tool_arcs.push(Arc::new(SubAgentManageTool::new(
-            subagent_registry,
-            security.clone(),
-        )));
+        // Queueing and sub-agent spawning need local agent definitions.
+        if !agents.is_empty() {
+            if root_config.delegate_queue.enabled {
+                let worker = delegate_queue::DelegateQueueWorker::shared(
+                    delegate_queue::DelegateQueueRuntime {
+                        config: Arc::new(root_config.clone()),
+                        agents: Arc::new(delegate_agents.clone()),
+                        fallback_credential: delegate_fallback_credential.clone(),
+                        provider_runtime_options: provider_runtime_options.clone(),
+                        parent_tools: parent_tools.clone(),
+                    },
+                );
+                worker.resume_pending();
+                tool_arcs.push(Arc::new(DelegateQueueTool::new(worker, security.clone())));
+            }
+
+            let subagent_registry = Arc::new(SubAgentRegistry::new());
+            tool_arcs.push(Arc::new(SubAgentSpawnTool::new(
+                delegate_agents,
+                delegate_fallback_credential,
+                security.clone(),
+                provider_runtime_options,
+                subagent_registry.clone(),
+                parent_tools,
+                root_config.multimodal.clone(),
+            )));
+            tool_arcs.push(Arc::new(SubAgentListTool::new(subagent_registry.clone())));
+            tool_arcs.push(Arc::new(SubAgentManageTool::new(
+                subagent_registry,
+                security.clone(),
+            )));
+        }
     }
 
     // Feishu document tools (enabled when channel-lark feature is active)
@@ -590,6 +652,17 @@ pub fn all_tools_with_runtime(
     // Fork: always-available interactive tool
     tool_arcs.push(Arc::new(AskUserTool));
 
+    // Paged access to oversized tool outputs spilled by the tool loop
+    if root_config.agent.tool_output_max_chars > 0 {
+        tool_arcs.push(Arc::new(ArtifactReadTool::new(
+            crate::agent::artifacts::ArtifactStore::new(
+                workspace_dir,
+                root_config.agent.max_artifacts_per_session,
+            ),
+            root_config.agent.tool_output_max_chars,
+        )));
+    }
+
     // Linear integration tool (enabled by [linear] config section)
     if root_config.linear.enabled {
         let api_key = root_config.linear.api_key.as_deref().unwrap_or("").trim();
@@ -697,6 +770,7 @@ mod tests {
         assert!(names.contains(&"schedule"));
         assert!(names.contains(&"model_routing_config"));
         assert!(names.contains(&"pushover"));
+        assert!(!names.contains(&"notify"));
         assert!(names.contains(&"proxy_config"));
         assert!(names.contains(&"web_access_config"));
         assert!(names.contains(&"web_search_config"));
@@ -950,6 +1024,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
 
@@ -1035,6 +1111,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
 
@@ -682,6 +682,8 @@ impl ModelRoutingConfigTool {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: DEFAULT_AGENT_MAX_ITERATIONS,
+                max_concurrent: 1,
+                max_retries: 2,
             });
 
         next_agent.provider = provider;
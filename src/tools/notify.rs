@@ -0,0 +1,213 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::notify::{self, Notification};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Route a notification through `[notifications]` targets and rules.
+pub struct NotifyTool {
+    config: Arc<Config>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl NotifyTool {
+    pub fn new(config: Arc<Config>, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+
+    fn target_names(&self) -> String {
+        let mut names: Vec<&str> = self
+            .config
+            .notifications
+            .targets
+            .keys()
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        names.join(", ")
+    }
+}
+
+#[async_trait]
+impl Tool for NotifyTool {
+    fn name(&self) -> &str {
+        "notify"
+    }
+
+    fn description(&self) -> &str {
+        "Send a notification to people through configured notification targets \
+         (e.g. oncall = Slack + Pushover + email). Omit 'target' to route by severity; \
+         repeats are deduplicated and quiet hours/digests are applied automatically."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "Notification text"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Optional short title"
+                },
+                "severity": {
+                    "type": "string",
+                    "enum": ["info", "warning", "critical"],
+                    "description": "Severity used for routing, quiet hours and digests (default: info)"
+                },
+                "target": {
+                    "type": "string",
+                    "description": format!(
+                        "Notification target name; omit to use routing rules. Configured: {}",
+                        self.target_names()
+                    )
+                },
+                "dedup_key": {
+                    "type": "string",
+                    "description": "Optional key; notifications sharing it within the dedup window are sent once"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: rate limit exceeded".into()),
+            });
+        }
+
+        let message = args
+            .get("message")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'message' parameter"))?;
+
+        let severity = match args.get("severity").and_then(|v| v.as_str()) {
+            Some(raw) => match notify::parse_severity(raw) {
+                Some(severity) => severity,
+                None => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "Invalid 'severity': {raw}. Expected info, warning or critical"
+                        )),
+                    })
+                }
+            },
+            None => crate::config::NotificationSeverity::Info,
+        };
+
+        let mut notification = Notification::new("agent", severity, message);
+        if let Some(title) = args
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            notification = notification.with_title(title);
+        }
+        if let Some(target) = args
+            .get("target")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            notification = notification.for_target(target);
+        }
+        if let Some(key) = args
+            .get("dedup_key")
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.trim().is_empty())
+        {
+            notification = notification.with_dedup_key(key);
+        }
+
+        match notify::notify(&self.config, notification).await {
+            Ok(report) => {
+                let success = report.duplicate
+                    || !report.delivered.is_empty()
+                    || !report.queued.is_empty()
+                    || report.failed.is_empty();
+                Ok(ToolResult {
+                    success,
+                    output: report.summary(),
+                    error: (!success).then(|| "All notification destinations failed".to_string()),
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{NotificationTargetConfig, NotificationsConfig};
+
+    fn tool(notifications: NotificationsConfig) -> NotifyTool {
+        let config = Config {
+            notifications,
+            ..Config::default()
+        };
+        NotifyTool::new(Arc::new(config), Arc::new(SecurityPolicy::default()))
+    }
+
+    #[test]
+    fn schema_lists_configured_targets() {
+        let mut notifications = NotificationsConfig::default();
+        notifications
+            .targets
+            .insert("oncall".into(), NotificationTargetConfig::default());
+        notifications
+            .targets
+            .insert("family".into(), NotificationTargetConfig::default());
+
+        let schema = tool(notifications).parameters_schema();
+        let description = schema["properties"]["target"]["description"]
+            .as_str()
+            .unwrap();
+        assert!(description.ends_with("family, oncall"));
+    }
+
+    #[tokio::test]
+    async fn execute_rejects_unknown_severity() {
+        let result = tool(NotificationsConfig::default())
+            .execute(json!({"message": "disk full", "severity": "loud"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Invalid 'severity'"));
+    }
+
+    #[tokio::test]
+    async fn execute_reports_disabled_notifications() {
+        let result = tool(NotificationsConfig::default())
+            .execute(json!({"message": "disk full"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("notifications are disabled"));
+    }
+}
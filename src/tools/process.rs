@@ -1,6 +1,7 @@
 use super::shell::collect_allowed_shell_env_vars;
 use super::traits::{Tool, ToolResult};
 use crate::runtime::RuntimeAdapter;
+use crate::security::cancellation::{self, StopScope, TaskGuard};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use crate::security::SyscallAnomalyDetector;
@@ -33,6 +34,8 @@ struct ProcessEntry {
     stdout_buf: Arc<Mutex<OutputBuffer>>,
     stderr_buf: Arc<Mutex<OutputBuffer>>,
     analyzed_offsets: Mutex<(u64, u64)>,
+    /// Keeps the process registered so an emergency stop kills its group.
+    _estop_guard: TaskGuard,
 }
 
 /// Background process management tool.
@@ -161,6 +164,9 @@ impl ProcessTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
         cmd.env_clear();
+        // Lead a new process group so an emergency stop can kill descendants too.
+        #[cfg(unix)]
+        cmd.process_group(0);
 
         for var in collect_allowed_shell_env_vars(&self.security) {
             if let Ok(val) = std::env::var(&var) {
@@ -180,6 +186,15 @@ impl ProcessTool {
         };
 
         let pid = child.id().unwrap_or(0);
+        let estop_guard = cancellation::register(StopScope::process("process", command, pid));
+        if estop_guard.is_stopped() {
+            let _ = child.start_kill();
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(cancellation::stopped_message("Background process")),
+            });
+        }
 
         // Set up background output readers.
         let stdout_buf = Arc::new(Mutex::new(OutputBuffer::default()));
@@ -208,6 +223,7 @@ impl ProcessTool {
             stdout_buf,
             stderr_buf,
             analyzed_offsets: Mutex::new((0, 0)),
+            _estop_guard: estop_guard,
         };
 
         self.processes.write().unwrap().insert(id, entry);
@@ -2,7 +2,7 @@ use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 const PUSHOVER_API_URL: &str = "https://api.pushover.net/1/messages.json";
@@ -68,11 +68,15 @@ impl PushoverTool {
     }
 
     async fn get_credentials(&self) -> anyhow::Result<(String, String)> {
+        Self::credentials_for_workspace(&self.workspace_dir).await
+    }
+
+    async fn credentials_for_workspace(workspace_dir: &Path) -> anyhow::Result<(String, String)> {
         if let Some(credentials) = Self::parse_process_env_credentials()? {
             return Ok(credentials);
         }
 
-        let env_path = self.workspace_dir.join(".env");
+        let env_path = workspace_dir.join(".env");
         let content = tokio::fs::read_to_string(&env_path)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", env_path.display(), e))?;
@@ -116,6 +120,46 @@ for example by launching ZeroClaw with enject injection.",
     }
 }
 
+/// Send a Pushover message outside the tool loop (notification routing),
+/// using the same credential lookup as the tool.
+pub(crate) async fn send_pushover_message(
+    workspace_dir: &Path,
+    title: Option<&str>,
+    message: &str,
+    priority: i64,
+) -> anyhow::Result<()> {
+    let (token, user_key) = PushoverTool::credentials_for_workspace(workspace_dir).await?;
+
+    let mut form = reqwest::multipart::Form::new()
+        .text("token", token)
+        .text("user", user_key)
+        .text("message", message.to_string())
+        .text("priority", priority.clamp(-2, 1).to_string());
+    if let Some(title) = title {
+        form = form.text("title", title.to_string());
+    }
+
+    let client = crate::config::build_runtime_proxy_client_with_timeouts(
+        "tool.pushover",
+        PUSHOVER_REQUEST_TIMEOUT_SECS,
+        10,
+    );
+    let response = client.post(PUSHOVER_API_URL).multipart(form).send().await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        anyhow::bail!("Pushover API returned status {status}: {body}");
+    }
+
+    let api_status = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json.get("status").and_then(|value| value.as_i64()));
+    if api_status != Some(1) {
+        anyhow::bail!("Pushover API returned an application-level error: {body}");
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl Tool for PushoverTool {
     fn name(&self) -> &str {
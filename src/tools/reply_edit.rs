@@ -0,0 +1,168 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Edit or delete a reply the agent already sent in the current channel
+/// conversation. `channel` and `target` are filled in by the tool loop, so
+/// only the conversation being answered can be changed.
+pub struct ReplyEditTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ReplyEditTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+#[async_trait]
+impl Tool for ReplyEditTool {
+    fn name(&self) -> &str {
+        "reply_edit"
+    }
+
+    fn description(&self) -> &str {
+        "Edit or delete a reply you already sent in this chat (Telegram, Slack, Discord), \
+         e.g. to correct a mistake. 'reply' counts back from your latest sent reply (1)."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["edit", "delete"],
+                    "description": "edit: replace the reply's text; delete: remove it"
+                },
+                "reply": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Which earlier reply: 1 = the latest one sent (default), 2 = the one before, ..."
+                },
+                "text": {
+                    "type": "string",
+                    "description": "edit: the new reply text"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Filled in automatically for channel conversations."
+                },
+                "target": {
+                    "type": "string",
+                    "description": "Filled in automatically for channel conversations."
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.security.can_act() {
+            return Ok(failure("Action blocked: autonomy is read-only"));
+        }
+
+        let text = match args.get("action").and_then(|v| v.as_str()) {
+            Some("edit") => match args
+                .get("text")
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.trim().is_empty())
+            {
+                Some(text) => Some(text),
+                None => return Ok(failure("'text' is required for action 'edit'")),
+            },
+            Some("delete") => None,
+            other => {
+                return Ok(failure(format!(
+                    "Invalid 'action': {}. Expected edit or delete",
+                    other.unwrap_or("<missing>")
+                )))
+            }
+        };
+        let nth = match args.get("reply") {
+            None => 1,
+            Some(value) => match value.as_u64().and_then(|n| usize::try_from(n).ok()) {
+                Some(n) if n >= 1 => n,
+                _ => return Ok(failure("'reply' must be a positive integer")),
+            },
+        };
+
+        let conversation = args
+            .get("channel")
+            .and_then(|v| v.as_str())
+            .zip(args.get("target").and_then(|v| v.as_str()))
+            .filter(|(channel, target)| !channel.trim().is_empty() && !target.trim().is_empty());
+        let Some((channel_name, target)) = conversation else {
+            return Ok(failure(
+                "reply_edit only works inside a channel conversation",
+            ));
+        };
+        let Some(channel) = crate::channels::get_live_channel(channel_name) else {
+            return Ok(failure(format!("Channel `{channel_name}` is not running")));
+        };
+
+        if !self.security.record_action() {
+            return Ok(failure("Action blocked: rate limit exceeded"));
+        }
+
+        match crate::channels::revise_sent_reply(channel.as_ref(), target, nth, text).await {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: if text.is_some() {
+                    format!("Edited reply #{nth}.")
+                } else {
+                    format!("Deleted reply #{nth}.")
+                },
+                error: None,
+            }),
+            Err(e) => Ok(failure(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool() -> ReplyEditTool {
+        ReplyEditTool::new(Arc::new(SecurityPolicy::default()))
+    }
+
+    #[tokio::test]
+    async fn execute_validates_arguments() {
+        let result = tool()
+            .execute(json!({"action": "rewrite", "channel": "telegram", "target": "1"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Invalid 'action'"));
+
+        let result = tool()
+            .execute(json!({"action": "edit", "channel": "telegram", "target": "1"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("'text' is required"));
+
+        let result = tool()
+            .execute(json!({"action": "delete", "reply": 0, "channel": "telegram", "target": "1"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("positive integer"));
+    }
+
+    #[tokio::test]
+    async fn execute_requires_channel_conversation() {
+        let result = tool().execute(json!({"action": "delete"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("channel conversation"));
+    }
+}
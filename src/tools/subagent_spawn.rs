@@ -4,11 +4,14 @@
 //! asynchronously via `tokio::spawn`, returning a session ID immediately.
 //! See `AGENTS.md` §7.3 for the tool change playbook.
 
+use super::delegate;
 use super::subagent_registry::{SubAgentRegistry, SubAgentSession, SubAgentStatus};
 use super::traits::{Tool, ToolResult};
 use crate::config::DelegateAgentConfig;
+use crate::observability::span::{self, SpanGuard};
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
 use crate::providers::{self, ChatMessage, Provider};
+use crate::security::cancellation::{self, StopScope};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -212,6 +215,15 @@ impl Tool for SubAgentSpawnTool {
         let parent_tools = self.parent_tools.clone();
         let multimodal_config = self.multimodal_config.clone();
 
+        let estop_guard = cancellation::register(StopScope::sub_agent(&session_id, agent_name));
+        if estop_guard.is_stopped() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(cancellation::stopped_message("Sub-agent spawn")),
+            });
+        }
+
         // Atomically check concurrent limit and register session to prevent race conditions.
         let session = SubAgentSession {
             id: session_id.clone(),
@@ -237,22 +249,55 @@ impl Tool for SubAgentSpawnTool {
         // Clone what we need for the spawned task
         let registry = self.registry.clone();
         let sid = session_id.clone();
+        // Opened here so the background agent is parented to this tool call;
+        // task-locals do not cross `tokio::spawn`.
+        let mut agent_span = delegate::agent_span(agent_name, &agent_config, "subagent_spawn");
+        agent_span.set_attribute("zeroclaw.subagent.session_id", session_id.as_str());
 
         let handle = tokio::spawn(async move {
-            let result = if is_agentic {
-                run_agentic_background(
-                    &agent_name_owned,
-                    &agent_config,
-                    &*provider,
-                    &full_prompt,
-                    &parent_tools,
-                    &multimodal_config,
-                )
-                .await
-            } else {
-                run_simple_background(&agent_name_owned, &agent_config, &*provider, &full_prompt)
+            let run = async {
+                if is_agentic {
+                    span::scope(
+                        agent_span.context(),
+                        run_agentic_background(
+                            &agent_name_owned,
+                            &agent_config,
+                            &*provider,
+                            &full_prompt,
+                            &parent_tools,
+                            &multimodal_config,
+                            SPAWN_TIMEOUT_SECS,
+                        ),
+                    )
+                    .await
+                } else {
+                    run_simple_background(
+                        &agent_name_owned,
+                        &agent_config,
+                        &*provider,
+                        &full_prompt,
+                        &agent_span,
+                        SPAWN_TIMEOUT_SECS,
+                    )
                     .await
+                }
+            };
+            let result = tokio::select! {
+                biased;
+                () = estop_guard.stopped() => {
+                    agent_span.fail(cancellation::stopped_message("Sub-agent"));
+                    registry.fail(&sid, cancellation::stopped_message("Sub-agent"));
+                    return;
+                }
+                result = run => result,
             };
+            match &result {
+                Ok(tool_result) if !tool_result.success => {
+                    agent_span.fail(tool_result.error.as_deref().unwrap_or("Unknown error"));
+                }
+                Err(e) => agent_span.fail(e.to_string()),
+                Ok(_) => {}
+            }
 
             match result {
                 Ok(tool_result) => {
@@ -290,16 +335,19 @@ impl Tool for SubAgentSpawnTool {
     }
 }
 
-async fn run_simple_background(
+pub(super) async fn run_simple_background(
     agent_name: &str,
     agent_config: &DelegateAgentConfig,
     provider: &dyn Provider,
     full_prompt: &str,
+    agent_span: &SpanGuard,
+    timeout_secs: u64,
 ) -> anyhow::Result<ToolResult> {
     let temperature = agent_config.temperature.unwrap_or(0.7);
 
+    let mut chat_span = delegate::chat_span(agent_span, agent_config, temperature);
     let result = tokio::time::timeout(
-        Duration::from_secs(SPAWN_TIMEOUT_SECS),
+        Duration::from_secs(timeout_secs),
         provider.chat_with_system(
             agent_config.system_prompt.as_deref(),
             full_prompt,
@@ -312,11 +360,12 @@ async fn run_simple_background(
     let result = match result {
         Ok(inner) => inner,
         Err(_elapsed) => {
+            chat_span.fail("timeout");
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
-                    "Agent '{agent_name}' timed out after {SPAWN_TIMEOUT_SECS}s"
+                    "Agent '{agent_name}' timed out after {timeout_secs}s"
                 )),
             });
         }
@@ -340,11 +389,14 @@ async fn run_simple_background(
                 error: None,
             })
         }
-        Err(e) => Ok(ToolResult {
-            success: false,
-            output: String::new(),
-            error: Some(format!("Agent '{agent_name}' failed: {e}")),
-        }),
+        Err(e) => {
+            chat_span.fail(e.to_string());
+            Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Agent '{agent_name}' failed: {e}")),
+            })
+        }
     }
 }
 
@@ -390,13 +442,14 @@ impl Observer for NoopObserver {
     }
 }
 
-async fn run_agentic_background(
+pub(super) async fn run_agentic_background(
     agent_name: &str,
     agent_config: &DelegateAgentConfig,
     provider: &dyn Provider,
     full_prompt: &str,
     parent_tools: &[Arc<dyn Tool>],
     multimodal_config: &crate::config::MultimodalConfig,
+    timeout_secs: u64,
 ) -> anyhow::Result<ToolResult> {
     if agent_config.allowed_tools.is_empty() {
         return Ok(ToolResult {
@@ -422,6 +475,7 @@ async fn run_agentic_background(
             tool.name() != "delegate"
                 && tool.name() != "subagent_spawn"
                 && tool.name() != "subagent_manage"
+                && tool.name() != "delegate_queue"
         })
         .map(|tool| Box::new(ToolArcRef::new(tool.clone())) as Box<dyn Tool>)
         .collect();
@@ -447,7 +501,7 @@ async fn run_agentic_background(
     let noop_observer = NoopObserver;
 
     let result = tokio::time::timeout(
-        Duration::from_secs(SPAWN_TIMEOUT_SECS),
+        Duration::from_secs(timeout_secs),
         crate::agent::loop_::run_tool_call_loop(
             provider,
             &mut history,
@@ -496,7 +550,7 @@ async fn run_agentic_background(
             success: false,
             output: String::new(),
             error: Some(format!(
-                "Agent '{agent_name}' timed out after {SPAWN_TIMEOUT_SECS}s"
+                "Agent '{agent_name}' timed out after {timeout_secs}s"
             )),
         }),
     }
@@ -525,6 +579,8 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                max_concurrent: 1,
+                max_retries: 2,
             },
         );
         agents
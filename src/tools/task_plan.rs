@@ -3,8 +3,11 @@
 //! Provides a `task_plan` tool that lets the agent break complex work into
 //! steps and track progress within a single session. The task list lives in
 //! memory (`Arc<RwLock<Vec<TaskItem>>>`) and is discarded when the session
-//! ends — it is intentionally not persisted via the Memory trait.
+//! ends — it is intentionally not persisted via the Memory trait. Inside a
+//! named session (`agent::session`) the checklist is stored with the session
+//! instead, so it survives restarts and follows the session across surfaces.
 
+use crate::agent::session::{self, ActiveSession, SessionTask};
 use crate::security::{policy::ToolOperation, SecurityPolicy};
 use crate::tools::traits::{Tool, ToolResult};
 use async_trait::async_trait;
@@ -49,6 +52,26 @@ struct TaskItem {
     status: TaskStatus,
 }
 
+impl From<SessionTask> for TaskItem {
+    fn from(task: SessionTask) -> Self {
+        Self {
+            id: task.id,
+            title: task.title,
+            status: TaskStatus::from_str(&task.status).unwrap_or(TaskStatus::Pending),
+        }
+    }
+}
+
+impl From<TaskItem> for SessionTask {
+    fn from(task: TaskItem) -> Self {
+        Self {
+            id: task.id,
+            title: task.title,
+            status: task.status.to_string(),
+        }
+    }
+}
+
 // ── Tool ─────────────────────────────────────────────────────────────────
 
 pub struct TaskPlanTool {
@@ -77,7 +100,11 @@ impl TaskPlanTool {
             })
     }
 
-    fn handle_create(&self, tasks_val: &serde_json::Value) -> ToolResult {
+    fn handle_create(
+        tasks: &mut Vec<TaskItem>,
+        next_id: &mut usize,
+        tasks_val: &serde_json::Value,
+    ) -> ToolResult {
         let arr = match tasks_val.as_array() {
             Some(a) if !a.is_empty() => a,
             _ => {
@@ -114,8 +141,8 @@ impl TaskPlanTool {
         }
 
         let count = items.len();
-        *self.tasks.write().unwrap() = items;
-        *self.next_id.write().unwrap() = id;
+        *tasks = items;
+        *next_id = id;
 
         ToolResult {
             success: true,
@@ -124,7 +151,7 @@ impl TaskPlanTool {
         }
     }
 
-    fn handle_add(&self, title: &str) -> ToolResult {
+    fn handle_add(tasks: &mut Vec<TaskItem>, next_id: &mut usize, title: &str) -> ToolResult {
         if title.is_empty() {
             return ToolResult {
                 success: false,
@@ -133,11 +160,10 @@ impl TaskPlanTool {
             };
         }
 
-        let mut next_id = self.next_id.write().unwrap();
         let id = *next_id;
         *next_id += 1;
 
-        self.tasks.write().unwrap().push(TaskItem {
+        tasks.push(TaskItem {
             id,
             title: title.to_string(),
             status: TaskStatus::Pending,
@@ -150,7 +176,7 @@ impl TaskPlanTool {
         }
     }
 
-    fn handle_update(&self, id: usize, status_str: &str) -> ToolResult {
+    fn handle_update(tasks: &mut [TaskItem], id: usize, status_str: &str) -> ToolResult {
         let status = match TaskStatus::from_str(status_str) {
             Some(s) => s,
             None => {
@@ -164,7 +190,6 @@ impl TaskPlanTool {
             }
         };
 
-        let mut tasks = self.tasks.write().unwrap();
         match tasks.iter_mut().find(|t| t.id == id) {
             Some(task) => {
                 task.status = status;
@@ -182,8 +207,7 @@ impl TaskPlanTool {
         }
     }
 
-    fn handle_list(&self) -> ToolResult {
-        let tasks = self.tasks.read().unwrap();
+    fn handle_list(tasks: &[TaskItem]) -> ToolResult {
         if tasks.is_empty() {
             return ToolResult {
                 success: true,
@@ -199,7 +223,7 @@ impl TaskPlanTool {
         let total = tasks.len();
 
         let mut lines = vec![format!("Tasks ({completed}/{total} completed):")];
-        for t in tasks.iter() {
+        for t in tasks {
             lines.push(format!("- [{}] [{}] {}", t.id, t.status, t.title));
         }
 
@@ -210,9 +234,9 @@ impl TaskPlanTool {
         }
     }
 
-    fn handle_delete(&self) -> ToolResult {
-        self.tasks.write().unwrap().clear();
-        *self.next_id.write().unwrap() = 1;
+    fn handle_delete(tasks: &mut Vec<TaskItem>, next_id: &mut usize) -> ToolResult {
+        tasks.clear();
+        *next_id = 1;
 
         ToolResult {
             success: true,
@@ -220,6 +244,118 @@ impl TaskPlanTool {
             error: None,
         }
     }
+
+    /// Run `args` against a caller-owned checklist.
+    fn execute_action(
+        &self,
+        tasks: &mut Vec<TaskItem>,
+        next_id: &mut usize,
+        args: &serde_json::Value,
+    ) -> ToolResult {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        match action {
+            "create" => {
+                if let Err(r) = self.enforce_mutation() {
+                    return r;
+                }
+                let tasks_val = args.get("tasks").cloned().unwrap_or(json!([]));
+                Self::handle_create(tasks, next_id, &tasks_val)
+            }
+            "add" => {
+                if let Err(r) = self.enforce_mutation() {
+                    return r;
+                }
+                let title = args
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                Self::handle_add(tasks, next_id, title)
+            }
+            "update" => {
+                if let Err(r) = self.enforce_mutation() {
+                    return r;
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                let id = args.get("id").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                let status = args
+                    .get("status")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                if id == 0 {
+                    return ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Parameter 'id' is required for update".into()),
+                    };
+                }
+                if status.is_empty() {
+                    return ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Parameter 'status' is required for update".into()),
+                    };
+                }
+                Self::handle_update(tasks, id, status)
+            }
+            "list" => Self::handle_list(tasks),
+            "delete" => {
+                if let Err(r) = self.enforce_mutation() {
+                    return r;
+                }
+                Self::handle_delete(tasks, next_id)
+            }
+            other => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Unknown action '{other}'. Valid: create, add, update, list, delete"
+                )),
+            },
+        }
+    }
+
+    /// Run `args` against the checklist stored with a named session. The
+    /// session store serializes the load-modify-write, so concurrent calls
+    /// (from this or another session, or the in-memory list) never see or
+    /// overwrite each other's tasks.
+    fn execute_in_session(
+        &self,
+        active: &ActiveSession,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<ToolResult> {
+        let read_only = args.get("action").and_then(|v| v.as_str()) == Some("list");
+        if read_only {
+            let mut tasks = session_tasks(
+                active
+                    .store
+                    .load(&active.name)?
+                    .map(|session| session.tasks)
+                    .unwrap_or_default(),
+            );
+            let mut next_id = next_task_id(&tasks);
+            return Ok(self.execute_action(&mut tasks, &mut next_id, args));
+        }
+
+        active.store.update(&active.name, |session| {
+            let mut tasks = session_tasks(std::mem::take(&mut session.tasks));
+            let mut next_id = next_task_id(&tasks);
+            let result = self.execute_action(&mut tasks, &mut next_id, args);
+            session.tasks = tasks.into_iter().map(SessionTask::from).collect();
+            result
+        })
+    }
+}
+
+fn session_tasks(stored: Vec<SessionTask>) -> Vec<TaskItem> {
+    stored.into_iter().map(TaskItem::from).collect()
+}
+
+fn next_task_id(tasks: &[TaskItem]) -> usize {
+    tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1
 }
 
 #[async_trait]
@@ -276,70 +412,12 @@ impl Tool for TaskPlanTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let action = args
-            .get("action")
-            .and_then(|v| v.as_str())
-            .unwrap_or_default();
-
-        match action {
-            "create" => {
-                if let Err(r) = self.enforce_mutation() {
-                    return Ok(r);
-                }
-                let tasks_val = args.get("tasks").cloned().unwrap_or(json!([]));
-                Ok(self.handle_create(&tasks_val))
-            }
-            "add" => {
-                if let Err(r) = self.enforce_mutation() {
-                    return Ok(r);
-                }
-                let title = args
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default();
-                Ok(self.handle_add(title))
-            }
-            "update" => {
-                if let Err(r) = self.enforce_mutation() {
-                    return Ok(r);
-                }
-                #[allow(clippy::cast_possible_truncation)]
-                let id = args.get("id").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
-                let status = args
-                    .get("status")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default();
-                if id == 0 {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("Parameter 'id' is required for update".into()),
-                    });
-                }
-                if status.is_empty() {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("Parameter 'status' is required for update".into()),
-                    });
-                }
-                Ok(self.handle_update(id, status))
-            }
-            "list" => Ok(self.handle_list()),
-            "delete" => {
-                if let Err(r) = self.enforce_mutation() {
-                    return Ok(r);
-                }
-                Ok(self.handle_delete())
-            }
-            other => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!(
-                    "Unknown action '{other}'. Valid: create, add, update, list, delete"
-                )),
-            }),
+        if let Some(active) = session::active() {
+            return self.execute_in_session(&active, &args);
         }
+        let mut tasks = self.tasks.write().unwrap();
+        let mut next_id = self.next_id.write().unwrap();
+        Ok(self.execute_action(&mut tasks, &mut next_id, &args))
     }
 }
 
@@ -605,4 +683,75 @@ mod tests {
         assert!(r.success);
         assert!(r.output.contains("No tasks"));
     }
+
+    #[tokio::test]
+    async fn named_session_checklist_is_persisted_separately() {
+        let tmp = tempfile::tempdir().unwrap();
+        let active = ActiveSession {
+            store: session::SessionStore::new(tmp.path()),
+            name: "refactor-auth".into(),
+        };
+        let tool = default_tool();
+        tool.execute(json!({ "action": "add", "title": "local" }))
+            .await
+            .unwrap();
+
+        session::scope(Some(active.clone()), async {
+            let r = tool
+                .execute(json!({ "action": "add", "title": "Audit login" }))
+                .await
+                .unwrap();
+            assert!(r.output.contains("[1]"));
+        })
+        .await;
+
+        let stored = active.store.load("refactor-auth").unwrap().unwrap();
+        assert_eq!(stored.tasks.len(), 1);
+        assert_eq!(stored.tasks[0].title, "Audit login");
+        let r = tool.execute(json!({ "action": "list" })).await.unwrap();
+        assert!(r.output.contains("local"));
+        assert!(!r.output.contains("Audit login"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_session_and_local_calls_keep_separate_lists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let active = ActiveSession {
+            store: session::SessionStore::new(tmp.path()),
+            name: "parallel".into(),
+        };
+        let tool = Arc::new(default_tool());
+
+        let mut handles = Vec::new();
+        for i in 0..8 {
+            let session_tool = Arc::clone(&tool);
+            let active = active.clone();
+            handles.push(tokio::spawn(async move {
+                session::scope(Some(active), async {
+                    session_tool
+                        .execute(json!({ "action": "add", "title": format!("session {i}") }))
+                        .await
+                        .unwrap();
+                })
+                .await;
+            }));
+            let local_tool = Arc::clone(&tool);
+            handles.push(tokio::spawn(async move {
+                local_tool
+                    .execute(json!({ "action": "add", "title": format!("local {i}") }))
+                    .await
+                    .unwrap();
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let stored = active.store.load("parallel").unwrap().unwrap();
+        assert_eq!(stored.tasks.len(), 8);
+        assert!(stored.tasks.iter().all(|t| t.title.starts_with("session")));
+        let local = tool.execute(json!({ "action": "list" })).await.unwrap();
+        assert!(local.output.contains("0/8 completed"));
+        assert!(!local.output.contains("session"));
+    }
 }
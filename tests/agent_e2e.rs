@@ -664,6 +664,49 @@ async fn e2e_empty_memory_context_passthrough() {
     );
 }
 
+// ═════════════════════════════════════════════════════════════════════════════
+// Cassette record/replay — deterministic offline sessions
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Records a tool-call session through `RecordingProvider`, then replays it
+/// with `ReplayProvider` (no scripted backend) and expects the same outcome.
+#[tokio::test]
+async fn e2e_cassette_record_then_replay_tool_session() {
+    use zeroclaw::config::CassetteMatchMode;
+    use zeroclaw::providers::cassette;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("echo-session.json");
+
+    let scripted = MockProvider::new(vec![
+        tool_response(vec![ToolCall {
+            id: "tc1".into(),
+            name: "echo".into(),
+            arguments: r#"{"message": "from cassette"}"#.into(),
+        }]),
+        text_response("echo said: from cassette"),
+    ]);
+    let recorder = cassette::RecordingProvider::new(Box::new(scripted), &path).unwrap();
+    let mut agent = build_agent(Box::new(recorder), vec![Box::new(EchoTool)]);
+    let recorded = agent.turn("run echo").await.unwrap();
+    assert_eq!(recorded, "echo said: from cassette");
+
+    let replay = cassette::ReplayProvider::load(&path, CassetteMatchMode::Strict).unwrap();
+    let mut agent = build_agent(Box::new(replay), vec![Box::new(EchoTool)]);
+    let replayed = agent.turn("run echo").await.unwrap();
+    assert_eq!(replayed, recorded);
+
+    let history = agent.history();
+    assert!(
+        history.iter().any(|msg| matches!(
+            msg,
+            ConversationMessage::ToolResults(results)
+                if results.iter().any(|result| result.content.contains("from cassette"))
+        )),
+        "replayed tool call should still execute the real tool"
+    );
+}
+
 // ═════════════════════════════════════════════════════════════════════════════
 // Live integration test — real OpenAI Codex API (requires credentials)
 // ═════════════════════════════════════════════════════════════════════════════
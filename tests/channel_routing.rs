@@ -8,7 +8,7 @@
 //! Verifies sender/reply_target field contracts to prevent field swaps.
 
 use async_trait::async_trait;
-use zeroclaw::channels::traits::{Channel, ChannelMessage, SendMessage};
+use zeroclaw::channels::traits::{Channel, ChannelEvent, ChannelMessage, SendMessage};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // ChannelMessage construction and field semantics
@@ -25,6 +25,8 @@ fn channel_message_sender_field_holds_platform_user_id() {
         channel: "telegram".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        attachments: Vec::new(),
+        event: ChannelEvent::Message,
     };
 
     assert_eq!(msg.sender, "123456789");
@@ -47,6 +49,8 @@ fn channel_message_reply_target_distinct_from_sender() {
         channel: "discord".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        attachments: Vec::new(),
+        event: ChannelEvent::Message,
     };
 
     assert_ne!(
@@ -67,6 +71,8 @@ fn channel_message_fields_not_swapped() {
         channel: "test".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        attachments: Vec::new(),
+        event: ChannelEvent::Message,
     };
 
     assert_eq!(
@@ -93,6 +99,8 @@ fn channel_message_preserves_all_fields_on_clone() {
         channel: "test_channel".into(),
         timestamp: 1700000001,
         thread_ts: None,
+        attachments: Vec::new(),
+        event: ChannelEvent::Message,
     };
 
     let cloned = original.clone();
@@ -186,6 +194,8 @@ impl Channel for CapturingChannel {
             channel: "capturing".into(),
             timestamp: 1700000000,
             thread_ts: None,
+            attachments: Vec::new(),
+            event: ChannelEvent::Message,
         })
         .await
         .map_err(|e| anyhow::anyhow!(e.to_string()))